@@ -0,0 +1,48 @@
+// Copyright (c) 2024 Via Technology Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! `opencl-sys` links the `OpenCL` library whether or not its functions are
+//! called. With the "dynamic" and "mock" features no `opencl-sys` functions
+//! are called, so link an empty `OpenCL` library instead, to build without
+//! the `OpenCL` ICD loader library.
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+/// An empty static library archive.
+const EMPTY_ARCHIVE: &[u8] = b"!<arch>\n";
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+
+    let dynamic = env::var_os("CARGO_FEATURE_DYNAMIC").is_some();
+    let mock = env::var_os("CARGO_FEATURE_MOCK").is_some();
+    let target_os = env::var("CARGO_CFG_TARGET_OS").unwrap_or_default();
+
+    // macOS links the OpenCL framework, which is always present
+    if !(dynamic || mock) || "macos" == target_os {
+        return;
+    }
+
+    let library_name = if "msvc" == env::var("CARGO_CFG_TARGET_ENV").unwrap_or_default() {
+        "OpenCL.lib"
+    } else {
+        "libOpenCL.a"
+    };
+    let out_dir = PathBuf::from(env::var_os("OUT_DIR").expect("OUT_DIR is set by cargo"));
+    fs::write(out_dir.join(library_name), EMPTY_ARCHIVE)
+        .expect("failed to write the empty OpenCL library");
+    println!("cargo:rustc-link-search=native={}", out_dir.display());
+}
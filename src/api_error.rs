@@ -0,0 +1,417 @@
+// Copyright (c) 2024 Via Technology Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! `OpenCL` API errors with the context in which they occurred.
+//!
+//! The adapter functions return the `OpenCL` error code (`cl_int`) on failure.
+//! They also record the name of the `OpenCL` C API function that failed and
+//! its key arguments for the current thread, like `errno` in C.
+//! The recorded context is cleared when the next adapter function is called,
+//! so it always belongs to the last adapter function called on the thread.
+//! The build, compile and link functions also record the build log of each
+//! device when the build fails.
+//!
+//! An [`ApiError`] is created from the error code, on the same thread and
+//! before calling another adapter function, e.g. by the `?` operator:
+//!
+//! ```no_run
+//! use cl3::api_error::ApiError;
+//! use cl3::memory::{create_buffer, CL_MEM_READ_WRITE};
+//! use cl3::types::{cl_context, cl_mem};
+//! use std::ptr;
+//!
+//! fn example(context: cl_context) -> Result<cl_mem, ApiError> {
+//!     let buffer = unsafe { create_buffer(context, CL_MEM_READ_WRITE, 0, ptr::null_mut())? };
+//!     Ok(buffer)
+//! }
+//!
+//! # let context = ptr::null_mut();
+//! if let Err(error) = example(context) {
+//!     // e.g. "clCreateBuffer(flags: 1, size: 0) failed: CL_INVALID_BUFFER_SIZE"
+//!     println!("{}", error);
+//! }
+//! ```
+//!
+//! An `ApiError` converts back into the `OpenCL` error code.
+
+#![allow(non_camel_case_types)]
+
+use super::error_codes::{error_text, ClError};
+use super::program::{get_program_build_info, get_program_info};
+use opencl_sys::{cl_device_id, cl_int, cl_program, CL_PROGRAM_BUILD_LOG, CL_PROGRAM_DEVICES};
+use std::cell::RefCell;
+use std::error::Error;
+use std::fmt;
+
+/// The build log of a device.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BuildLog {
+    /// The device that the program was built for.
+    pub device: cl_device_id,
+    /// The build log of the device, see `CL_PROGRAM_BUILD_LOG`.
+    pub log: String,
+}
+
+// The device id is only used as an identifier, see `owned`.
+unsafe impl Send for BuildLog {}
+unsafe impl Sync for BuildLog {}
+
+/// An `OpenCL` error code with the `OpenCL` C API function and arguments
+/// that caused it, if they were recorded.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ApiError {
+    code: cl_int,
+    function: Option<&'static str>,
+    args: Vec<(&'static str, String)>,
+    build_logs: Vec<BuildLog>,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<ApiError>> = const { RefCell::new(None) };
+}
+
+impl ApiError {
+    /// The `OpenCL` error code.
+    #[must_use]
+    pub const fn code(&self) -> cl_int {
+        self.code
+    }
+
+    /// The name of the `OpenCL` C API function that returned the error, or of
+    /// the function that rejected its arguments, if known.
+    #[must_use]
+    pub const fn function(&self) -> Option<&'static str> {
+        self.function
+    }
+
+    /// The names and values of the key arguments of the function.
+    #[must_use]
+    pub fn args(&self) -> &[(&'static str, String)] {
+        &self.args
+    }
+
+    /// The build logs of the devices, if a program build failed.
+    #[must_use]
+    pub fn build_logs(&self) -> &[BuildLog] {
+        &self.build_logs
+    }
+}
+
+impl From<cl_int> for ApiError {
+    /// Create an `ApiError` from an error code returned by an adapter function.
+    /// Takes the context recorded by the last failed function on this thread,
+    /// if it returned the same error code.
+    fn from(code: cl_int) -> Self {
+        LAST_ERROR
+            .with(|last| last.borrow_mut().take())
+            .filter(|error| code == error.code)
+            .unwrap_or(Self {
+                code,
+                function: None,
+                args: Vec::new(),
+                build_logs: Vec::new(),
+            })
+    }
+}
+
+impl From<ApiError> for cl_int {
+    fn from(error: ApiError) -> Self {
+        error.code
+    }
+}
+
+impl From<ApiError> for ClError {
+    fn from(error: ApiError) -> Self {
+        Self(error.code)
+    }
+}
+
+impl PartialEq<cl_int> for ApiError {
+    fn eq(&self, other: &cl_int) -> bool {
+        self.code == *other
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(function) = self.function {
+            write!(f, "{function}(")?;
+            for (i, (name, value)) in self.args.iter().enumerate() {
+                if 0 < i {
+                    write!(f, ", ")?;
+                }
+                write!(f, "{name}: {value}")?;
+            }
+            write!(f, ") failed: ")?;
+        }
+        write!(f, "{}", error_text(self.code))?;
+
+        for build_log in &self.build_logs {
+            write!(
+                f,
+                "\nbuild log for device {:?}:\n{}",
+                build_log.device, build_log.log
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for ApiError {}
+
+/// Clear the context recorded for the current thread, so that it is not
+/// taken by an error from a later function.
+#[doc(hidden)]
+pub fn clear_error() {
+    // The context cannot be taken after the thread local is destroyed
+    let _ = LAST_ERROR.try_with(|last| last.borrow_mut().take());
+}
+
+/// Call a function without changing the context recorded for the current
+/// thread, e.g. to release an object while an error is being returned.
+pub(crate) fn preserve_error<R>(f: impl FnOnce() -> R) -> R {
+    let error = LAST_ERROR
+        .try_with(|last| last.borrow_mut().take())
+        .ok()
+        .flatten();
+    let result = f();
+    let _ = LAST_ERROR.try_with(|last| *last.borrow_mut() = error);
+    result
+}
+
+/// Discard the error of a function whose failure the caller handles,
+/// e.g. an optional query, clearing its recorded context.
+///
+/// returns the value or None if the function failed.
+pub(crate) fn discard_error<T, E>(result: Result<T, E>) -> Option<T> {
+    if result.is_err() {
+        clear_error();
+    }
+    result.ok()
+}
+
+/// Record the context of a failed `OpenCL` C API function for the current
+/// thread, see `record_error!`.
+///
+/// returns the error code.
+#[doc(hidden)]
+pub fn record(
+    code: cl_int,
+    function: &'static str,
+    args: &[(&'static str, &dyn fmt::Debug)],
+) -> cl_int {
+    record_error_context(code, function, args, Vec::new())
+}
+
+/// Record the context of a failed program build, compile or link with the
+/// build logs of the devices.
+/// If `devices` is empty, the logs of all of the program's devices are recorded.
+///
+/// returns the error code.
+pub(crate) fn record_build(
+    code: cl_int,
+    function: &'static str,
+    args: &[(&'static str, &dyn fmt::Debug)],
+    program: cl_program,
+    devices: &[cl_device_id],
+) -> cl_int {
+    let build_logs = if program.is_null() {
+        Vec::new()
+    } else {
+        build_logs(program, devices)
+    };
+    record_error_context(code, function, args, build_logs)
+}
+
+/// The build logs of a program for the devices that they can be queried for.
+/// If `devices` is empty, the logs of all of the program's devices are returned.
+pub(crate) fn build_logs(program: cl_program, devices: &[cl_device_id]) -> Vec<BuildLog> {
+    preserve_error(|| {
+        let program_devices: Vec<cl_device_id> = if devices.is_empty() {
+            get_program_info(program, CL_PROGRAM_DEVICES).map_or_else(
+                |_| Vec::new(),
+                |value| {
+                    Vec::<isize>::from(value)
+                        .into_iter()
+                        .map(|device| device as cl_device_id)
+                        .collect()
+                },
+            )
+        } else {
+            devices.to_vec()
+        };
+        program_devices
+            .into_iter()
+            .filter_map(|device| {
+                get_program_build_info(program, device, CL_PROGRAM_BUILD_LOG)
+                    .ok()
+                    .map(|log| BuildLog {
+                        device,
+                        log: log.into(),
+                    })
+            })
+            .collect()
+    })
+}
+
+fn record_error_context(
+    code: cl_int,
+    function: &'static str,
+    args: &[(&'static str, &dyn fmt::Debug)],
+    build_logs: Vec<BuildLog>,
+) -> cl_int {
+    let error = ApiError {
+        code,
+        function: Some(function),
+        args: args
+            .iter()
+            .map(|(name, value)| (*name, format!("{value:?}")))
+            .collect(),
+        build_logs,
+    };
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(error));
+    code
+}
+
+/// Record the context of a failed `OpenCL` C API function for the current thread,
+/// e.g. `record_error!(status, clCreateBuffer, flags, size)`, or of a function
+/// that rejected its arguments, e.g. `record_error!(code, Buffer::from_mem)`.
+///
+/// returns the error code.
+macro_rules! record_error {
+    ($status:expr, $type:ident::$function:ident $(, $arg:ident)*) => {
+        $crate::api_error::record(
+            $status,
+            concat!(stringify!($type), "::", stringify!($function)),
+            &[$((stringify!($arg), &$arg as &dyn std::fmt::Debug)),*],
+        )
+    };
+    ($status:expr, $function:ident $(, $arg:ident)*) => {
+        $crate::api_error::record(
+            $status,
+            stringify!($function),
+            &[$((stringify!($arg), &$arg as &dyn std::fmt::Debug)),*],
+        )
+    };
+}
+
+pub(crate) use record_error;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::{create_context, release_context};
+    use crate::device::{get_device_ids, CL_DEVICE_TYPE_GPU};
+    use crate::platform::get_platform_ids;
+    use crate::program::{build_program, create_program_with_source, release_program};
+    use crate::rect_transfer::Region3D;
+    use opencl_sys::{CL_BUILD_PROGRAM_FAILURE, CL_INVALID_BUFFER_SIZE, CL_INVALID_VALUE};
+    use std::ffi::CString;
+    use std::ptr;
+
+    #[test]
+    fn test_api_error() {
+        let flags: u64 = 1;
+        let size: usize = 0;
+        let code = record_error!(CL_INVALID_BUFFER_SIZE, clCreateBuffer, flags, size);
+
+        let error = ApiError::from(code);
+        println!("ApiError: {}", error);
+        assert_eq!(CL_INVALID_BUFFER_SIZE, error.code());
+        assert_eq!(Some("clCreateBuffer"), error.function());
+        assert_eq!(
+            &[("flags", String::from("1")), ("size", String::from("0"))],
+            error.args()
+        );
+        assert_eq!(
+            "clCreateBuffer(flags: 1, size: 0) failed: CL_INVALID_BUFFER_SIZE",
+            error.to_string()
+        );
+        assert_eq!(CL_INVALID_BUFFER_SIZE, cl_int::from(error));
+
+        // The recorded context is only taken once
+        let error = ApiError::from(code);
+        assert_eq!(None, error.function());
+        assert_eq!("CL_INVALID_BUFFER_SIZE", error.to_string());
+
+        // The recorded context is ignored for a different error code
+        record_error!(CL_INVALID_BUFFER_SIZE, clCreateBuffer, flags, size);
+        let error = ApiError::from(CL_INVALID_VALUE);
+        assert_eq!(None, error.function());
+        assert!(error == CL_INVALID_VALUE);
+
+        // The recorded context is cleared by the next adapter function
+        record_error!(CL_INVALID_BUFFER_SIZE, clCreateBuffer, flags, size);
+        clear_error();
+        assert_eq!(None, ApiError::from(CL_INVALID_BUFFER_SIZE).function());
+        #[cfg(feature = "mock")]
+        {
+            record_error!(CL_INVALID_BUFFER_SIZE, clCreateBuffer, flags, size);
+            get_platform_ids().unwrap();
+            assert_eq!(None, ApiError::from(CL_INVALID_BUFFER_SIZE).function());
+        }
+
+        // but not by a function called while an error is being returned
+        record_error!(CL_INVALID_BUFFER_SIZE, clCreateBuffer, flags, size);
+        preserve_error(clear_error);
+        assert_eq!(
+            Some("clCreateBuffer"),
+            ApiError::from(CL_INVALID_BUFFER_SIZE).function()
+        );
+
+        // An error that is handled is discarded
+        record_error!(CL_INVALID_BUFFER_SIZE, clCreateBuffer, flags, size);
+        assert_eq!(None, discard_error(Err::<(), _>(CL_INVALID_BUFFER_SIZE)));
+        assert_eq!(None, ApiError::from(CL_INVALID_BUFFER_SIZE).function());
+
+        // Errors from arguments rejected before calling OpenCL are recorded
+        let code = Region3D::packed().footprint([4, 0, 1]).unwrap_err();
+        let error = ApiError::from(code);
+        assert_eq!(
+            "Region3D::footprint(extent: [4, 0, 1]) failed: CL_INVALID_VALUE",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn test_build_program_error() {
+        let platform_ids = get_platform_ids().unwrap();
+        assert!(0 < platform_ids.len());
+
+        let device_ids = get_device_ids(platform_ids[0], CL_DEVICE_TYPE_GPU).unwrap();
+        assert!(0 < device_ids.len());
+
+        let context = create_context(&device_ids, ptr::null(), None, ptr::null_mut()).unwrap();
+
+        // The source has an extra closing brace
+        let source = "kernel void add(global int* a)\n{\n    a[0] += 1;\n}\n}\n";
+        let program = create_program_with_source(context, &[source]).unwrap();
+        let options = CString::new("-DVALUE=1").unwrap();
+        let result = build_program(program, &device_ids, &options, None, ptr::null_mut());
+        let error = ApiError::from(result.unwrap_err());
+        println!("ApiError: {}", error);
+
+        assert_eq!(CL_BUILD_PROGRAM_FAILURE, error.code());
+        assert_eq!(Some("clBuildProgram"), error.function());
+        assert_eq!(&[("options", String::from("\"-DVALUE=1\""))], error.args());
+        assert_eq!(device_ids.len(), error.build_logs().len());
+        assert_eq!(device_ids[0], error.build_logs()[0].device);
+        assert!(!error.build_logs()[0].log.is_empty());
+
+        unsafe {
+            release_program(program).unwrap();
+            release_context(context).unwrap();
+        }
+    }
+}
@@ -0,0 +1,881 @@
+// Copyright (c) 2024 Via Technology Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A work-group size autotuner with a persistent table of results.
+//!
+//! An [`Autotuner`] times a kernel with each candidate local size, and
+//! optionally each combination of `-D` tunables by rebuilding the program,
+//! using the profiling timestamps of its events. The fastest setting is
+//! recorded in a [`TuningTable`] keyed by the device name, driver version,
+//! kernel name and global size. The table is saved to a file, and a
+//! [`KernelLaunch`] given the table uses the tuned local size, e.g.:
+//!
+//! ```no_run
+//! use cl3::autotune::{Autotuner, TuningKey, TuningTable};
+//! use cl3::kernel_launch::KernelLaunch;
+//! use cl3::types::{cl_command_queue, cl_device_id, cl_int, cl_kernel};
+//! use std::sync::Arc;
+//!
+//! fn example(device: cl_device_id, queue: cl_command_queue, kernel: cl_kernel) -> Result<(), cl_int> {
+//!     // The queue has profiling enabled and the kernel arguments have been set
+//!     let path = "/var/cache/my_app/tuning.txt";
+//!     let mut table = TuningTable::load(path).unwrap_or_default();
+//!     let key = TuningKey::for_kernel(device, kernel, &[1024, 1024])?;
+//!     if table.get(&key).is_none() {
+//!         let tuning = unsafe { Autotuner::new().tune_kernel(queue, kernel, &[1024, 1024])? };
+//!         table.insert(key, tuning.best);
+//!         table.save(path).unwrap();
+//!     }
+//!
+//!     let launch = KernelLaunch::new(kernel, &[1024, 1024]).tuning_table(Arc::new(table));
+//!     let _event = unsafe { launch.enqueue(queue)? };
+//!     Ok(())
+//! }
+//! ```
+//!
+//! The command-queue must be created with `CL_QUEUE_PROFILING_ENABLE`.
+//! Candidate local sizes that fail the [`KernelLaunch`] checks or fail to
+//! execute, and tunable combinations that fail to build, are skipped.
+
+use super::api_error::{discard_error, record_error};
+use super::command_queue::{get_command_queue_info, CL_QUEUE_CONTEXT, CL_QUEUE_DEVICE};
+use super::device_info::{get_device_param, DriverVersion, MaxWorkItemSizes, Name};
+use super::event::{
+    get_event_profiling_info, wait_for_events, CL_PROFILING_COMMAND_END, CL_PROFILING_COMMAND_START,
+};
+use super::kernel::{
+    create_kernel, get_kernel_info, get_kernel_work_group_info, CL_KERNEL_FUNCTION_NAME,
+    CL_KERNEL_WORK_GROUP_SIZE,
+};
+use super::kernel_launch::{KernelLaunch, LaunchError};
+use super::owned::{self, AsCommandQueue, AsDevice, AsKernel};
+use super::program::{build_program, create_program_with_source};
+use super::program_cache::write_atomically;
+use crate::build_options::BuildOptions;
+use libc::size_t;
+use opencl_sys::{
+    cl_command_queue, cl_context, cl_device_id, cl_int, cl_kernel, cl_ulong,
+    CL_INVALID_KERNEL_NAME, CL_INVALID_WORK_GROUP_SIZE,
+};
+use std::collections::btree_map::{self, BTreeMap};
+use std::ffi::CString;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::ptr;
+
+/// The first line of a tuning table file.
+const TABLE_HEADER: &str = "# cl3 tuning table v1";
+
+/// The device, kernel and problem size of a tuned setting.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TuningKey {
+    /// The `CL_DEVICE_NAME`.
+    pub device_name: String,
+    /// The `CL_DRIVER_VERSION`.
+    pub driver_version: String,
+    /// The kernel function name.
+    pub kernel_name: String,
+    /// The global size of the launch.
+    pub global_size: Vec<size_t>,
+}
+
+impl TuningKey {
+    /// Create the key of a kernel name and global size on a device.
+    pub fn new(
+        device: impl AsDevice,
+        kernel_name: &str,
+        global_size: &[size_t],
+    ) -> Result<Self, cl_int> {
+        let device = device.as_raw();
+        Ok(Self {
+            device_name: get_device_param(device, Name)?,
+            driver_version: get_device_param(device, DriverVersion)?,
+            kernel_name: kernel_name.to_owned(),
+            global_size: global_size.to_vec(),
+        })
+    }
+
+    /// Create the key of a kernel and global size on a device.
+    pub fn for_kernel(
+        device: impl AsDevice,
+        kernel: impl AsKernel,
+        global_size: &[size_t],
+    ) -> Result<Self, cl_int> {
+        let kernel_name = String::from(get_kernel_info(kernel, CL_KERNEL_FUNCTION_NAME)?);
+        Self::new(device, &kernel_name, global_size)
+    }
+}
+
+/// The local size and `-D` tunables of a kernel, with its execution time.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TunedSetting {
+    /// The local size.
+    pub local_size: Vec<size_t>,
+    /// The `-D` tunables as name and value pairs, empty unless the program
+    /// was tuned.
+    pub defines: Vec<(String, String)>,
+    /// The shortest execution time in nanoseconds.
+    pub time_ns: cl_ulong,
+}
+
+impl TunedSetting {
+    /// Add the `-D` tunables of the setting to build options.
+    #[must_use]
+    pub fn build_options(&self, options: BuildOptions) -> BuildOptions {
+        self.defines.iter().fold(options, |options, (name, value)| {
+            options.define_value(name, value)
+        })
+    }
+}
+
+/// A table of the fastest settings of kernels, which can be saved to and
+/// loaded from a file.
+///
+/// The file is a text file of tab separated fields: the device name, driver
+/// version, kernel name, comma separated global and local sizes, the time in
+/// nanoseconds and a `NAME=VALUE` field for each define.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TuningTable {
+    entries: BTreeMap<TuningKey, TunedSetting>,
+}
+
+impl TuningTable {
+    /// Create an empty table.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load a table from a file, an empty table if the file does not exist.
+    ///
+    /// returns a Result containing the table, or an `InvalidData` error if
+    /// the file is not a tuning table.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(text) => text.parse(),
+            Err(error) if io::ErrorKind::NotFound == error.kind() => Ok(Self::new()),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Save the table to a file atomically, creating its directory if required.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        write_atomically(path.as_ref(), self.to_string().as_bytes())
+    }
+
+    #[must_use]
+    pub fn get(&self, key: &TuningKey) -> Option<&TunedSetting> {
+        self.entries.get(key)
+    }
+
+    /// The tuned setting of a kernel and global size on a device, if any.
+    pub fn get_for_kernel(
+        &self,
+        device: impl AsDevice,
+        kernel: impl AsKernel,
+        global_size: &[size_t],
+    ) -> Result<Option<&TunedSetting>, cl_int> {
+        Ok(self.get(&TuningKey::for_kernel(device, kernel, global_size)?))
+    }
+
+    /// Insert a setting, returning the setting that it replaced.
+    pub fn insert(&mut self, key: TuningKey, setting: TunedSetting) -> Option<TunedSetting> {
+        self.entries.insert(key, setting)
+    }
+
+    pub fn remove(&mut self, key: &TuningKey) -> Option<TunedSetting> {
+        self.entries.remove(key)
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn iter(&self) -> btree_map::Iter<'_, TuningKey, TunedSetting> {
+        self.entries.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a TuningTable {
+    type Item = (&'a TuningKey, &'a TunedSetting);
+    type IntoIter = btree_map::Iter<'a, TuningKey, TunedSetting>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl fmt::Display for TuningTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{TABLE_HEADER}")?;
+        for (key, setting) in &self.entries {
+            write!(
+                f,
+                "{}\t{}\t{}\t{}\t{}\t{}",
+                escape(&key.device_name),
+                escape(&key.driver_version),
+                escape(&key.kernel_name),
+                join_sizes(&key.global_size),
+                join_sizes(&setting.local_size),
+                setting.time_ns
+            )?;
+            for (name, value) in &setting.defines {
+                write!(f, "\t{}={}", escape(name), escape(value))?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for TuningTable {
+    type Err = io::Error;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let invalid = |line: usize, message: &str| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("tuning table line {line}: {message}"),
+            )
+        };
+
+        let mut lines = text.lines().enumerate();
+        if lines.next().map(|(_, line)| line.trim_end()) != Some(TABLE_HEADER) {
+            return Err(invalid(1, "missing header"));
+        }
+        let mut table = Self::new();
+        for (index, line) in lines {
+            let line_number = index + 1;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let fields: Vec<&str> = line.split('\t').collect();
+            if fields.len() < 6 {
+                return Err(invalid(line_number, "too few fields"));
+            }
+            let global_size = parse_sizes(fields[3])
+                .ok_or_else(|| invalid(line_number, "invalid global size"))?;
+            let local_size =
+                parse_sizes(fields[4]).ok_or_else(|| invalid(line_number, "invalid local size"))?;
+            let time_ns = fields[5]
+                .parse()
+                .map_err(|_| invalid(line_number, "invalid time"))?;
+            let defines = fields[6..]
+                .iter()
+                .map(|define| {
+                    define
+                        .split_once('=')
+                        .map(|(name, value)| (unescape(name), unescape(value)))
+                        .ok_or_else(|| invalid(line_number, "invalid define"))
+                })
+                .collect::<Result<_, _>>()?;
+            table.insert(
+                TuningKey {
+                    device_name: unescape(fields[0]),
+                    driver_version: unescape(fields[1]),
+                    kernel_name: unescape(fields[2]),
+                    global_size,
+                },
+                TunedSetting {
+                    local_size,
+                    defines,
+                    time_ns,
+                },
+            );
+        }
+        Ok(table)
+    }
+}
+
+/// Escape the backslashes, tabs, newlines and equals signs of a table field.
+fn escape(field: &str) -> String {
+    let mut escaped = String::with_capacity(field.len());
+    for c in field.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '=' => escaped.push_str("\\e"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn unescape(field: &str) -> String {
+    let mut unescaped = String::with_capacity(field.len());
+    let mut chars = field.chars();
+    while let Some(c) = chars.next() {
+        if '\\' == c {
+            match chars.next() {
+                Some('t') => unescaped.push('\t'),
+                Some('n') => unescaped.push('\n'),
+                Some('r') => unescaped.push('\r'),
+                Some('e') => unescaped.push('='),
+                Some(c) => unescaped.push(c),
+                None => unescaped.push('\\'),
+            }
+        } else {
+            unescaped.push(c);
+        }
+    }
+    unescaped
+}
+
+fn join_sizes(sizes: &[size_t]) -> String {
+    sizes
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn parse_sizes(field: &str) -> Option<Vec<size_t>> {
+    field.split(',').map(|size| size.parse().ok()).collect()
+}
+
+/// The results of tuning a kernel.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Tuning {
+    /// The fastest setting.
+    pub best: TunedSetting,
+    /// Each setting that was timed, in the order that they were timed.
+    pub trials: Vec<TunedSetting>,
+}
+
+/// A builder for tuning the local size and `-D` tunables of a kernel.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Autotuner {
+    local_sizes: Vec<Vec<size_t>>,
+    tunables: Vec<(String, Vec<String>)>,
+    options: BuildOptions,
+    iterations: usize,
+    pad_global_size: bool,
+}
+
+impl Default for Autotuner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Autotuner {
+    /// Create an autotuner that times each power of two local size within
+    /// the kernel and device limits three times.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            local_sizes: Vec::new(),
+            tunables: Vec::new(),
+            options: BuildOptions::new(),
+            iterations: 3,
+            pad_global_size: false,
+        }
+    }
+
+    /// Add a candidate local size, replacing the default candidates.
+    #[must_use]
+    pub fn local_size(mut self, local_size: &[size_t]) -> Self {
+        self.local_sizes.push(local_size.to_vec());
+        self
+    }
+
+    /// Add a `-D name=value` tunable with its candidate values, used by
+    /// [`Autotuner::tune_program`].
+    #[must_use]
+    pub fn tunable(mut self, name: &str, values: &[impl fmt::Display]) -> Self {
+        self.tunables.push((
+            name.to_owned(),
+            values.iter().map(ToString::to_string).collect(),
+        ));
+        self
+    }
+
+    /// The other build options of [`Autotuner::tune_program`].
+    #[must_use]
+    pub fn options(mut self, options: BuildOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// The number of timed launches of each setting, after a warm-up launch.
+    #[must_use]
+    pub fn iterations(mut self, iterations: usize) -> Self {
+        self.iterations = iterations.max(1);
+        self
+    }
+
+    /// Pad the global size of candidates that do not divide it, see
+    /// [`KernelLaunch::pad_global_size`].
+    #[must_use]
+    pub const fn pad_global_size(mut self) -> Self {
+        self.pad_global_size = true;
+        self
+    }
+
+    /// Time a kernel with each candidate local size.
+    ///
+    /// * `command_queue` - a command-queue with profiling enabled.
+    /// * `kernel` - the kernel, with its arguments set.
+    /// * `global_size` - the global size of the launches.
+    ///
+    /// returns a Result containing the tuning results or the error code from
+    /// the `OpenCL` C API function, `CL_INVALID_WORK_GROUP_SIZE` if no
+    /// candidate local size is valid.
+    ///
+    /// # Safety
+    ///
+    /// This function is unsafe because the kernel arguments must be set and
+    /// valid while the kernel is executed.
+    pub unsafe fn tune_kernel(
+        &self,
+        command_queue: impl AsCommandQueue,
+        kernel: impl AsKernel,
+        global_size: &[size_t],
+    ) -> Result<Tuning, cl_int> {
+        let command_queue = command_queue.as_raw();
+        let kernel = kernel.as_raw();
+        let mut trials = Vec::new();
+        self.time_local_sizes(command_queue, kernel, global_size, &[], &mut trials)?;
+        best_of(trials)
+    }
+
+    /// Build a program from source with each combination of the tunables,
+    /// and time its kernel with each candidate local size.
+    ///
+    /// * `command_queue` - a command-queue with profiling enabled.
+    /// * `sources` - the program source strings.
+    /// * `kernel_name` - the name of the kernel.
+    /// * `global_size` - the global size of the launches.
+    /// * `set_args` - a function that sets the arguments of each new kernel.
+    ///
+    /// returns a Result containing the tuning results or the error code from
+    /// the `OpenCL` C API function, `CL_INVALID_WORK_GROUP_SIZE` if no
+    /// combination builds with a valid candidate local size.
+    ///
+    /// # Safety
+    ///
+    /// This function is unsafe because the kernel arguments must be set and
+    /// valid while the kernel is executed.
+    pub unsafe fn tune_program<F>(
+        &self,
+        command_queue: impl AsCommandQueue,
+        sources: &[&str],
+        kernel_name: &str,
+        global_size: &[size_t],
+        mut set_args: F,
+    ) -> Result<Tuning, cl_int>
+    where
+        F: FnMut(cl_kernel) -> Result<(), cl_int>,
+    {
+        let command_queue = command_queue.as_raw();
+        let context =
+            get_command_queue_info(command_queue, CL_QUEUE_CONTEXT)?.to_ptr() as cl_context;
+        let device =
+            get_command_queue_info(command_queue, CL_QUEUE_DEVICE)?.to_ptr() as cl_device_id;
+        let kernel_name = CString::new(kernel_name).map_err(|_| CL_INVALID_KERNEL_NAME)?;
+
+        let mut trials = Vec::new();
+        for defines in self.define_combinations() {
+            let options = defines
+                .iter()
+                .fold(self.options.clone(), |options, (name, value)| {
+                    options.define_value(name, value)
+                })
+                .to_cstring()?;
+            let program = owned::Program::from_raw(create_program_with_source(context, sources)?);
+            // Skip the combinations that the program does not support
+            if discard_error(build_program(
+                &program,
+                &[device],
+                &options,
+                None,
+                ptr::null_mut(),
+            ))
+            .is_none()
+            {
+                continue;
+            }
+            let kernel = owned::Kernel::from_raw(create_kernel(&program, &kernel_name)?);
+            set_args(kernel.as_raw())?;
+            self.time_local_sizes(
+                command_queue,
+                kernel.as_raw(),
+                global_size,
+                &defines,
+                &mut trials,
+            )?;
+        }
+        best_of(trials)
+    }
+
+    /// Each combination of the tunable values, one empty combination if
+    /// there are no tunables.
+    fn define_combinations(&self) -> Vec<Vec<(String, String)>> {
+        self.tunables
+            .iter()
+            .fold(vec![Vec::new()], |combinations, (name, values)| {
+                combinations
+                    .iter()
+                    .flat_map(|defines| {
+                        values.iter().map(move |value| {
+                            let mut defines = defines.clone();
+                            defines.push((name.clone(), value.clone()));
+                            defines
+                        })
+                    })
+                    .collect()
+            })
+    }
+
+    unsafe fn time_local_sizes(
+        &self,
+        command_queue: cl_command_queue,
+        kernel: cl_kernel,
+        global_size: &[size_t],
+        defines: &[(String, String)],
+        trials: &mut Vec<TunedSetting>,
+    ) -> Result<(), cl_int> {
+        let local_sizes = if self.local_sizes.is_empty() {
+            let device =
+                get_command_queue_info(command_queue, CL_QUEUE_DEVICE)?.to_ptr() as cl_device_id;
+            let work_group_size =
+                get_kernel_work_group_info(kernel, device, CL_KERNEL_WORK_GROUP_SIZE)?.to_size();
+            default_local_sizes(
+                global_size,
+                &get_device_param(device, MaxWorkItemSizes)?,
+                work_group_size,
+                self.pad_global_size,
+            )
+        } else {
+            self.local_sizes.clone()
+        };
+
+        for local_size in local_sizes {
+            let mut launch = KernelLaunch::new(kernel, global_size).local_size(&local_size);
+            if self.pad_global_size {
+                launch = launch.pad_global_size();
+            }
+            if let Some(time_ns) = self.time_launch(command_queue, &launch)? {
+                trials.push(TunedSetting {
+                    local_size,
+                    defines: defines.to_vec(),
+                    time_ns,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// The shortest execution time of a launch, None if the launch is invalid
+    /// or fails to execute.
+    unsafe fn time_launch(
+        &self,
+        command_queue: cl_command_queue,
+        launch: &KernelLaunch,
+    ) -> Result<Option<cl_ulong>, cl_int> {
+        let sizes = launch.work_sizes(command_queue);
+        if let Err(LaunchError::Api(code)) = sizes {
+            return Err(code);
+        }
+        if discard_error(sizes).is_none() {
+            return Ok(None);
+        }
+
+        let mut shortest = cl_ulong::MAX;
+        // The first launch is a warm-up
+        for iteration in 0..=self.iterations {
+            let Some(event) = discard_error(launch.enqueue(command_queue)) else {
+                return Ok(None);
+            };
+            let event = owned::Event::from_raw(event);
+            if discard_error(wait_for_events(&[event.as_raw()])).is_none() {
+                return Ok(None);
+            }
+            let start = get_event_profiling_info(&event, CL_PROFILING_COMMAND_START)?.to_ulong();
+            let end = get_event_profiling_info(&event, CL_PROFILING_COMMAND_END)?.to_ulong();
+            if 0 < iteration {
+                shortest = shortest.min(end.saturating_sub(start));
+            }
+        }
+        Ok(Some(shortest))
+    }
+}
+
+/// The fastest of the trials, the first if several are equally fast.
+fn best_of(trials: Vec<TunedSetting>) -> Result<Tuning, cl_int> {
+    let best = trials
+        .iter()
+        .reduce(|best, trial| {
+            if trial.time_ns < best.time_ns {
+                trial
+            } else {
+                best
+            }
+        })
+        .cloned()
+        .ok_or_else(|| record_error!(CL_INVALID_WORK_GROUP_SIZE, best_of))?;
+    Ok(Tuning { best, trials })
+}
+
+/// The local sizes with a power of two in each dimension, within the device
+/// and kernel limits and at most the (padded) global size.
+fn default_local_sizes(
+    global_size: &[size_t],
+    max_work_item_sizes: &[size_t],
+    work_group_size: size_t,
+    pad_global_size: bool,
+) -> Vec<Vec<size_t>> {
+    global_size
+        .iter()
+        .enumerate()
+        .fold(vec![Vec::new()], |local_sizes, (dimension, global)| {
+            let global = if pad_global_size {
+                global.next_power_of_two()
+            } else {
+                *global
+            };
+            let max = max_work_item_sizes
+                .get(dimension)
+                .copied()
+                .unwrap_or(1)
+                .min(global);
+            local_sizes
+                .iter()
+                .flat_map(|local_size: &Vec<size_t>| {
+                    let product: size_t = local_size.iter().product();
+                    (0..size_t::BITS)
+                        .map(|shift| 1 << shift)
+                        .take_while(move |size| *size <= max && product * size <= work_group_size)
+                        .map(move |size| {
+                            let mut local_size = local_size.clone();
+                            local_size.push(size);
+                            local_size
+                        })
+                })
+                .collect()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[allow(deprecated)]
+    use crate::command_queue::create_command_queue;
+    use crate::command_queue::{release_command_queue, CL_QUEUE_PROFILING_ENABLE};
+    use crate::context::{create_context, release_context};
+    use crate::device::{get_device_ids, CL_DEVICE_TYPE_GPU};
+    use crate::kernel_arg::set_arg;
+    use crate::memory::{create_buffer, release_mem_object, CL_MEM_READ_WRITE};
+    use crate::platform::get_platform_ids;
+    use opencl_sys::{cl_float, cl_uint};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_tuning_table() {
+        assert_eq!(
+            vec![vec![1], vec![2], vec![4], vec![8]],
+            default_local_sizes(&[10], &[256], 256, false)
+        );
+        assert_eq!(
+            vec![vec![1, 1], vec![1, 2], vec![2, 1]],
+            default_local_sizes(&[2, 2], &[256, 256], 2, false)
+        );
+        assert_eq!(5, default_local_sizes(&[10], &[256], 256, true).len());
+
+        let mut table = TuningTable::new();
+        let key = TuningKey {
+            device_name: "GPU\t1".to_owned(),
+            driver_version: "1.0\\2".to_owned(),
+            kernel_name: "add".to_owned(),
+            global_size: vec![1024, 16],
+        };
+        let setting = TunedSetting {
+            local_size: vec![64, 4],
+            defines: vec![("TILE".to_owned(), "a=b".to_owned())],
+            time_ns: 1234,
+        };
+        assert!(table.insert(key.clone(), setting.clone()).is_none());
+        let text = table.to_string();
+        println!("{text}");
+        let parsed: TuningTable = text.parse().unwrap();
+        assert_eq!(table, parsed);
+        assert_eq!(Some(&setting), parsed.get(&key));
+        assert!("cl3\n".parse::<TuningTable>().is_err());
+        assert!(format!("{TABLE_HEADER}\na\tb\n")
+            .parse::<TuningTable>()
+            .is_err());
+    }
+
+    #[test]
+    fn test_autotuner() {
+        let platform_ids = get_platform_ids().unwrap();
+        let device_ids = get_device_ids(platform_ids[0], CL_DEVICE_TYPE_GPU).unwrap();
+        let device_id = device_ids[0];
+        let context = create_context(&device_ids, ptr::null(), None, ptr::null_mut()).unwrap();
+        #[allow(deprecated)]
+        let queue =
+            unsafe { create_command_queue(context, device_id, CL_QUEUE_PROFILING_ENABLE).unwrap() };
+
+        let n: cl_uint = 256;
+        let buffer = unsafe {
+            create_buffer(
+                context,
+                CL_MEM_READ_WRITE,
+                n as usize * std::mem::size_of::<cl_float>(),
+                ptr::null_mut(),
+            )
+            .unwrap()
+        };
+        let source = r#"
+            #if SCALE == 3
+            #error "unsupported SCALE"
+            #endif
+            kernel void fill(global float* a, float v) {
+                a[get_global_id(0)] = v * SCALE;
+            }
+        "#;
+        let tuner = Autotuner::new()
+            .tunable("SCALE", &[1, 2, 3])
+            .local_size(&[16])
+            .local_size(&[64])
+            .local_size(&[100])
+            .iterations(2);
+        let tuning = unsafe {
+            tuner
+                .tune_program(queue, &[source], "fill", &[n as usize], |kernel| {
+                    set_arg(kernel, 0, &buffer)?;
+                    set_arg(kernel, 1, &(1.0 as cl_float))
+                })
+                .unwrap()
+        };
+        println!("{:?}", tuning);
+        // The local size 100 does not divide the global size and SCALE 3
+        // does not build
+        assert_eq!(4, tuning.trials.len());
+        assert!(tuning
+            .trials
+            .iter()
+            .all(|trial| tuning.best.time_ns <= trial.time_ns));
+        assert_eq!(1, tuning.best.defines.len());
+
+        // Tune and record the kernel built with the best defines
+        let options = tuning.best.build_options(BuildOptions::new());
+        let program = create_program_with_source(context, &[source]).unwrap();
+        build_program(
+            program,
+            &device_ids,
+            &options.to_cstring().unwrap(),
+            None,
+            ptr::null_mut(),
+        )
+        .unwrap();
+        let kernel = unsafe {
+            owned::Kernel::from_raw(create_kernel(program, &CString::new("fill").unwrap()).unwrap())
+        };
+        set_arg(&kernel, 0, &buffer).unwrap();
+        set_arg(&kernel, 1, &(2.0 as cl_float)).unwrap();
+        let tuning = unsafe {
+            Autotuner::new()
+                .iterations(1)
+                .tune_kernel(queue, &kernel, &[n as usize])
+                .unwrap()
+        };
+        let work_group_size =
+            get_kernel_work_group_info(&kernel, device_id, CL_KERNEL_WORK_GROUP_SIZE)
+                .unwrap()
+                .to_size();
+        let local_sizes = default_local_sizes(
+            &[n as usize],
+            &get_device_param(device_id, MaxWorkItemSizes).unwrap(),
+            work_group_size,
+            false,
+        );
+        assert_eq!(local_sizes.len(), tuning.trials.len());
+        #[cfg(feature = "mock")]
+        assert_eq!(9, tuning.trials.len());
+
+        let mut table = TuningTable::new();
+        let mut best = tuning.best;
+        best.local_size = vec![32];
+        let key = TuningKey::for_kernel(device_id, &kernel, &[n as usize]).unwrap();
+        assert_eq!("fill", key.kernel_name);
+        table.insert(key, best.clone());
+
+        let path = std::env::temp_dir()
+            .join(format!("cl3_autotune_{}", std::process::id()))
+            .join("tuning.txt");
+        table.save(&path).unwrap();
+        let loaded = TuningTable::load(&path).unwrap();
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+        assert_eq!(table, loaded);
+        assert_eq!(
+            Some(&best),
+            loaded
+                .get_for_kernel(device_id, &kernel, &[n as usize])
+                .unwrap()
+        );
+        assert!(TuningTable::load(&path).unwrap().is_empty());
+
+        // A launch uses the tuned local size
+        let table = Arc::new(loaded);
+        let sizes = KernelLaunch::new(&kernel, &[n as usize])
+            .tuning_table(Arc::clone(&table))
+            .work_sizes(queue)
+            .unwrap();
+        assert_eq!(Some(vec![32]), sizes.local_size);
+        let sizes = KernelLaunch::new(&kernel, &[128])
+            .tuning_table(table)
+            .work_sizes(queue)
+            .unwrap();
+        assert_eq!(Some(vec![128]), sizes.local_size);
+
+        // A local size tuned with a padded global size needs a padded launch
+        let mut table = TuningTable::new();
+        best.local_size = vec![64];
+        let key = TuningKey::for_kernel(device_id, &kernel, &[100]).unwrap();
+        table.insert(key, best);
+        let table = Arc::new(table);
+        let sizes = KernelLaunch::new(&kernel, &[100])
+            .tuning_table(Arc::clone(&table))
+            .pad_global_size()
+            .work_sizes(queue)
+            .unwrap();
+        assert_eq!(Some(vec![64]), sizes.local_size);
+        let sizes = KernelLaunch::new(&kernel, &[100])
+            .tuning_table(table)
+            .work_sizes(queue)
+            .unwrap();
+        assert_eq!(0, 100 % sizes.local_size.unwrap()[0]);
+
+        drop(kernel);
+        unsafe {
+            crate::program::release_program(program).unwrap();
+            release_mem_object(buffer).unwrap();
+            release_command_queue(queue).unwrap();
+            release_context(context).unwrap();
+        }
+    }
+}
@@ -0,0 +1,576 @@
+// Copyright (c) 2024 Via Technology Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Typed buffers.
+//!
+//! A [`Buffer<T>`] is an owned buffer memory object of `len` elements of a
+//! [`Pod`] type `T`. Its offsets and lengths are in elements rather than bytes,
+//! and each range is checked against the buffer's `CL_MEM_SIZE` before the
+//! command is enqueued, so a read or write can never overrun the buffer or
+//! the host slice, e.g.:
+//!
+//! ```no_run
+//! use cl3::buffer::Buffer;
+//! use cl3::memory::CL_MEM_READ_WRITE;
+//! use cl3::types::{cl_command_queue, cl_context, cl_float, cl_int};
+//!
+//! fn example(context: cl_context, queue: cl_command_queue) -> Result<Vec<cl_float>, cl_int> {
+//!     let buffer = Buffer::<cl_float>::create_with_data(context, CL_MEM_READ_WRITE, &[1.0; 1024])?;
+//!     buffer.write(queue, 512, &[2.0; 512])?;
+//!     let mut half = vec![0.0; 512];
+//!     buffer.read(queue, 0, &mut half)?;
+//!     buffer.read_to_vec(queue)
+//! }
+//! ```
+//!
+//! The `read_rect`, `write_rect` and `copy_rect_to` methods transfer
+//! sub-volumes described by a [`RectTransfer`].
+//!
+//! The reads and writes are blocking, so the host slices are not borrowed
+//! beyond the call. The fills and copies only access `OpenCL` memory and
+//! return their events.
+
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
+
+use super::api_error::record_error;
+#[cfg(feature = "CL_VERSION_1_2")]
+use super::command_queue::enqueue_fill_buffer;
+use super::command_queue::{
+    enqueue_copy_buffer, enqueue_copy_buffer_rect, enqueue_read_buffer, enqueue_read_buffer_rect,
+    enqueue_write_buffer, enqueue_write_buffer_rect,
+};
+use super::mapped_memory::{MapRead, MappedBuffer, WriteAccess};
+use super::memory::{
+    create_buffer, get_mem_object_info, CL_MEM_COPY_HOST_PTR, CL_MEM_OBJECT_BUFFER, CL_MEM_SIZE,
+    CL_MEM_TYPE,
+};
+use super::owned::{AsCommandQueue, AsContext, AsMem, Event, Mem};
+use super::rect_transfer::RectTransfer;
+use super::types::Pod;
+use libc::size_t;
+use opencl_sys::{
+    cl_int, cl_mem, cl_mem_flags, CL_BLOCKING, CL_INVALID_BUFFER_SIZE, CL_INVALID_MEM_OBJECT,
+    CL_INVALID_VALUE,
+};
+use std::fmt;
+use std::marker::PhantomData;
+use std::mem;
+use std::ptr;
+
+/// An owned buffer of `len` elements of type `T`.
+pub struct Buffer<T: Pod> {
+    mem: Mem,
+    len: usize,
+    element: PhantomData<T>,
+}
+
+impl<T: Pod> Buffer<T> {
+    /// Create an uninitialized buffer of `len` elements.
+    /// Calls `create_buffer` without a host pointer.
+    ///
+    /// * `context` - a valid `OpenCL` context.
+    /// * `flags` - a bit-field used to specify allocation and usage information
+    ///   about the buffer, it must not contain `CL_MEM_USE_HOST_PTR` or
+    ///   `CL_MEM_COPY_HOST_PTR`.
+    /// * `len` - the number of elements.
+    ///
+    /// returns a Result containing the new buffer or the error code from the
+    /// `OpenCL` C API function.
+    pub fn create(
+        context: impl AsContext,
+        flags: cl_mem_flags,
+        len: usize,
+    ) -> Result<Self, cl_int> {
+        let size = byte_size::<T>(len).ok_or(CL_INVALID_BUFFER_SIZE)?;
+        let mem = unsafe { Mem::from_raw(create_buffer(context, flags, size, ptr::null_mut())?) };
+        Self::from_mem(mem)
+    }
+
+    /// Create a buffer initialized with a copy of `data`.
+    /// Calls `create_buffer` with `CL_MEM_COPY_HOST_PTR`.
+    ///
+    /// * `context` - a valid `OpenCL` context.
+    /// * `flags` - a bit-field used to specify allocation and usage information
+    ///   about the buffer, it must not contain `CL_MEM_USE_HOST_PTR`.
+    /// * `data` - the initial elements of the buffer.
+    ///
+    /// returns a Result containing the new buffer or the error code from the
+    /// `OpenCL` C API function.
+    pub fn create_with_data(
+        context: impl AsContext,
+        flags: cl_mem_flags,
+        data: &[T],
+    ) -> Result<Self, cl_int> {
+        let size = byte_size::<T>(data.len()).ok_or(CL_INVALID_BUFFER_SIZE)?;
+        // CL_MEM_COPY_HOST_PTR copies the data before create_buffer returns
+        let mem = unsafe {
+            Mem::from_raw(create_buffer(
+                context,
+                flags | CL_MEM_COPY_HOST_PTR,
+                size,
+                data.as_ptr().cast_mut().cast(),
+            )?)
+        };
+        Self::from_mem(mem)
+    }
+
+    /// Take a buffer memory object as a typed buffer.
+    ///
+    /// returns a Result containing the buffer, or `CL_INVALID_MEM_OBJECT` if
+    /// `mem` is not a buffer or `CL_INVALID_BUFFER_SIZE` if its `CL_MEM_SIZE`
+    /// is not a multiple of the size of `T`.
+    pub fn from_mem(mem: Mem) -> Result<Self, cl_int> {
+        let mem_type = get_mem_object_info(&mem, CL_MEM_TYPE)?.to_uint();
+        if CL_MEM_OBJECT_BUFFER != mem_type {
+            return Err(record_error!(
+                CL_INVALID_MEM_OBJECT,
+                Buffer::from_mem,
+                mem_type
+            ));
+        }
+        let size = get_mem_object_info(&mem, CL_MEM_SIZE)?.to_size();
+        let element_size = mem::size_of::<T>();
+        if 0 == element_size || 0 != size % element_size {
+            return Err(record_error!(
+                CL_INVALID_BUFFER_SIZE,
+                Buffer::from_mem,
+                size,
+                element_size
+            ));
+        }
+        Ok(Self {
+            mem,
+            len: size / element_size,
+            element: PhantomData,
+        })
+    }
+
+    /// The number of elements.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        0 == self.len
+    }
+
+    /// The size of the buffer in bytes.
+    #[must_use]
+    pub const fn size(&self) -> size_t {
+        self.len * mem::size_of::<T>()
+    }
+
+    #[must_use]
+    pub const fn as_mem(&self) -> &Mem {
+        &self.mem
+    }
+
+    #[must_use]
+    pub fn into_mem(self) -> Mem {
+        self.mem
+    }
+
+    /// Read elements from the buffer, starting at element `offset`, into `data`.
+    /// Calls `enqueue_read_buffer` and blocks until the read is complete.
+    ///
+    /// returns an empty Result, or `CL_INVALID_VALUE` if the elements are
+    /// outside of the buffer or the error code from the `OpenCL` C API function.
+    pub fn read(
+        &self,
+        command_queue: impl AsCommandQueue,
+        offset: usize,
+        data: &mut [T],
+    ) -> Result<(), cl_int> {
+        let (offset, size) = self.byte_range(offset, data.len())?;
+        let event = unsafe {
+            enqueue_read_buffer(
+                command_queue,
+                &self.mem,
+                CL_BLOCKING,
+                offset,
+                size,
+                data.as_mut_ptr().cast(),
+                0,
+                ptr::null(),
+            )?
+        };
+        drop(unsafe { Event::from_raw(event) });
+        Ok(())
+    }
+
+    /// Read all of the elements of the buffer.
+    ///
+    /// returns a Result containing the elements or the error code from the
+    /// `OpenCL` C API function.
+    pub fn read_to_vec(&self, command_queue: impl AsCommandQueue) -> Result<Vec<T>, cl_int> {
+        // Safe because every bit pattern is a valid Pod value
+        let mut data = vec![unsafe { mem::zeroed::<T>() }; self.len];
+        self.read(command_queue, 0, &mut data)?;
+        Ok(data)
+    }
+
+    /// Write `data` to the buffer, starting at element `offset`.
+    /// Calls `enqueue_write_buffer` and blocks until the write is complete.
+    ///
+    /// returns an empty Result, or `CL_INVALID_VALUE` if the elements are
+    /// outside of the buffer or the error code from the `OpenCL` C API function.
+    pub fn write(
+        &self,
+        command_queue: impl AsCommandQueue,
+        offset: usize,
+        data: &[T],
+    ) -> Result<(), cl_int> {
+        let (offset, size) = self.byte_range(offset, data.len())?;
+        let event = unsafe {
+            enqueue_write_buffer(
+                command_queue,
+                &self.mem,
+                CL_BLOCKING,
+                offset,
+                size,
+                data.as_ptr().cast(),
+                0,
+                ptr::null(),
+            )?
+        };
+        drop(unsafe { Event::from_raw(event) });
+        Ok(())
+    }
+
+    /// Fill `len` elements of the buffer, starting at element `offset`, with `value`.
+    /// Calls `enqueue_fill_buffer`.
+    ///
+    /// returns a Result containing the event of the fill, or `CL_INVALID_VALUE`
+    /// if the elements are outside of the buffer or the error code from the
+    /// `OpenCL` C API function.
+    #[cfg(feature = "CL_VERSION_1_2")]
+    pub fn fill(
+        &self,
+        command_queue: impl AsCommandQueue,
+        value: T,
+        offset: usize,
+        len: usize,
+    ) -> Result<Event, cl_int> {
+        let (offset, size) = self.byte_range(offset, len)?;
+        // The pattern is copied before enqueue_fill_buffer returns
+        unsafe {
+            Ok(Event::from_raw(enqueue_fill_buffer(
+                command_queue,
+                &self.mem,
+                ptr::from_ref(&value).cast(),
+                mem::size_of::<T>(),
+                offset,
+                size,
+                0,
+                ptr::null(),
+            )?))
+        }
+    }
+
+    /// Copy `len` elements from the buffer, starting at element `src_offset`,
+    /// to `dst_buffer`, starting at element `dst_offset`.
+    /// Calls `enqueue_copy_buffer`.
+    ///
+    /// returns a Result containing the event of the copy, or `CL_INVALID_VALUE`
+    /// if the elements are outside of either buffer or the error code from the
+    /// `OpenCL` C API function.
+    pub fn copy_to(
+        &self,
+        command_queue: impl AsCommandQueue,
+        dst_buffer: &Self,
+        src_offset: usize,
+        dst_offset: usize,
+        len: usize,
+    ) -> Result<Event, cl_int> {
+        let (src_offset, size) = self.byte_range(src_offset, len)?;
+        let (dst_offset, _) = dst_buffer.byte_range(dst_offset, len)?;
+        unsafe {
+            Ok(Event::from_raw(enqueue_copy_buffer(
+                command_queue,
+                &self.mem,
+                &dst_buffer.mem,
+                src_offset,
+                dst_offset,
+                size,
+                0,
+                ptr::null(),
+            )?))
+        }
+    }
+
+    /// Read a sub-volume of the buffer into a sub-volume of `data`: the
+    /// `src` of the transfer is in the buffer and the `dst` is in `data`.
+    /// Calls `enqueue_read_buffer_rect` and blocks until the read is complete.
+    ///
+    /// returns an empty Result, or `CL_INVALID_VALUE` if a sub-volume is
+    /// invalid or exceeds its array or the error code from the `OpenCL` C API
+    /// function.
+    pub fn read_rect(
+        &self,
+        command_queue: impl AsCommandQueue,
+        transfer: &RectTransfer,
+        data: &mut [T],
+    ) -> Result<(), cl_int> {
+        let rect = transfer.byte_rect::<T>(self.len, data.len())?;
+        let event = unsafe {
+            enqueue_read_buffer_rect(
+                command_queue,
+                &self.mem,
+                CL_BLOCKING,
+                rect.src_origin.as_ptr(),
+                rect.dst_origin.as_ptr(),
+                rect.region.as_ptr(),
+                rect.src_row_pitch,
+                rect.src_slice_pitch,
+                rect.dst_row_pitch,
+                rect.dst_slice_pitch,
+                data.as_mut_ptr().cast(),
+                0,
+                ptr::null(),
+            )?
+        };
+        drop(unsafe { Event::from_raw(event) });
+        Ok(())
+    }
+
+    /// Write a sub-volume of `data` to a sub-volume of the buffer: the `src`
+    /// of the transfer is in `data` and the `dst` is in the buffer.
+    /// Calls `enqueue_write_buffer_rect` and blocks until the write is complete.
+    ///
+    /// returns an empty Result, or `CL_INVALID_VALUE` if a sub-volume is
+    /// invalid or exceeds its array or the error code from the `OpenCL` C API
+    /// function.
+    pub fn write_rect(
+        &self,
+        command_queue: impl AsCommandQueue,
+        transfer: &RectTransfer,
+        data: &[T],
+    ) -> Result<(), cl_int> {
+        let rect = transfer.byte_rect::<T>(data.len(), self.len)?;
+        let event = unsafe {
+            enqueue_write_buffer_rect(
+                command_queue,
+                &self.mem,
+                CL_BLOCKING,
+                rect.dst_origin.as_ptr(),
+                rect.src_origin.as_ptr(),
+                rect.region.as_ptr(),
+                rect.dst_row_pitch,
+                rect.dst_slice_pitch,
+                rect.src_row_pitch,
+                rect.src_slice_pitch,
+                data.as_ptr().cast(),
+                0,
+                ptr::null(),
+            )?
+        };
+        drop(unsafe { Event::from_raw(event) });
+        Ok(())
+    }
+
+    /// Copy a sub-volume of the buffer to a sub-volume of `dst_buffer`.
+    /// Calls `enqueue_copy_buffer_rect`.
+    ///
+    /// returns a Result containing the event of the copy, or `CL_INVALID_VALUE`
+    /// if a sub-volume is invalid or exceeds its buffer or the error code from
+    /// the `OpenCL` C API function.
+    pub fn copy_rect_to(
+        &self,
+        command_queue: impl AsCommandQueue,
+        dst_buffer: &Self,
+        transfer: &RectTransfer,
+    ) -> Result<Event, cl_int> {
+        let rect = transfer.byte_rect::<T>(self.len, dst_buffer.len)?;
+        unsafe {
+            Ok(Event::from_raw(enqueue_copy_buffer_rect(
+                command_queue,
+                &self.mem,
+                &dst_buffer.mem,
+                rect.src_origin.as_ptr(),
+                rect.dst_origin.as_ptr(),
+                rect.region.as_ptr(),
+                rect.src_row_pitch,
+                rect.src_slice_pitch,
+                rect.dst_row_pitch,
+                rect.dst_slice_pitch,
+                0,
+                ptr::null(),
+            )?))
+        }
+    }
+
+    /// Map `len` elements of the buffer, starting at element `offset`, into
+    /// host memory for reading, see [`MappedBuffer`].
+    ///
+    /// returns a Result containing the mapped region, which is unmapped when
+    /// it is dropped, or the error code from the `OpenCL` C API function.
+    pub fn map(
+        &self,
+        command_queue: impl AsCommandQueue,
+        offset: usize,
+        len: usize,
+    ) -> Result<MappedBuffer<'_, T, MapRead>, cl_int> {
+        MappedBuffer::new(command_queue, self, offset, len)
+    }
+
+    /// Map `len` elements of the buffer, starting at element `offset`, into
+    /// host memory with the writing access `A`, see [`MappedBuffer`].
+    /// The buffer is mutably borrowed until the mapped region is dropped.
+    ///
+    /// returns a Result containing the mapped region, which is unmapped when
+    /// it is dropped, or the error code from the `OpenCL` C API function.
+    pub fn map_mut<A: WriteAccess>(
+        &mut self,
+        command_queue: impl AsCommandQueue,
+        offset: usize,
+        len: usize,
+    ) -> Result<MappedBuffer<'_, T, A>, cl_int> {
+        MappedBuffer::new_mut(command_queue, self, offset, len)
+    }
+
+    /// The byte offset and size of `len` elements starting at element `offset`.
+    fn byte_range(&self, offset: usize, len: usize) -> Result<(size_t, size_t), cl_int> {
+        match offset.checked_add(len) {
+            Some(end) if end <= self.len => {
+                let element_size = mem::size_of::<T>();
+                Ok((offset * element_size, len * element_size))
+            }
+            _ => Err(record_error!(
+                CL_INVALID_VALUE,
+                Buffer::byte_range,
+                offset,
+                len
+            )),
+        }
+    }
+}
+
+/// The size in bytes of `len` elements of type `T`, None if it overflows.
+const fn byte_size<T>(len: usize) -> Option<size_t> {
+    len.checked_mul(mem::size_of::<T>())
+}
+
+impl<T: Pod> Clone for Buffer<T> {
+    fn clone(&self) -> Self {
+        Self {
+            mem: self.mem.clone(),
+            len: self.len,
+            element: PhantomData,
+        }
+    }
+}
+
+impl<T: Pod> fmt::Debug for Buffer<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Buffer")
+            .field("mem", &self.mem)
+            .field("element", &std::any::type_name::<T>())
+            .field("len", &self.len)
+            .finish()
+    }
+}
+
+impl<T: Pod> AsMem for &Buffer<T> {
+    fn as_raw(self) -> cl_mem {
+        self.mem.as_raw()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[allow(deprecated)]
+    use crate::command_queue::{create_command_queue, finish, release_command_queue};
+    use crate::context::{create_context, release_context};
+    use crate::device::{get_device_ids, CL_DEVICE_TYPE_GPU};
+    use crate::memory::{CL_MEM_READ_ONLY, CL_MEM_READ_WRITE};
+    use crate::platform::get_platform_ids;
+    use crate::types::cl_float4;
+    use opencl_sys::{cl_float, cl_uchar, cl_uint};
+
+    #[test]
+    fn test_buffer() {
+        let platform_ids = get_platform_ids().unwrap();
+        let device_ids = get_device_ids(platform_ids[0], CL_DEVICE_TYPE_GPU).unwrap();
+        let context = create_context(&device_ids, ptr::null(), None, ptr::null_mut()).unwrap();
+        #[allow(deprecated)]
+        let queue = unsafe { create_command_queue(context, device_ids[0], 0).unwrap() };
+
+        let data: Vec<cl_uint> = (0..16).collect();
+        let buffer = Buffer::create_with_data(context, CL_MEM_READ_WRITE, &data).unwrap();
+        println!("{:?}", buffer);
+        assert_eq!(16, buffer.len());
+        assert_eq!(64, buffer.size());
+        assert_eq!(data, buffer.read_to_vec(queue).unwrap());
+
+        buffer.write(queue, 12, &[100, 101, 102, 103]).unwrap();
+        let mut tail = [0; 6];
+        buffer.read(queue, 10, &mut tail).unwrap();
+        assert_eq!([10, 11, 100, 101, 102, 103], tail);
+
+        // Ranges outside of the buffer
+        assert_eq!(Err(CL_INVALID_VALUE), buffer.read(queue, 11, &mut tail));
+        assert_eq!(Err(CL_INVALID_VALUE), buffer.write(queue, usize::MAX, &[1]));
+        assert_eq!(
+            CL_INVALID_VALUE,
+            buffer.copy_to(queue, &buffer, 0, 8, 9).unwrap_err()
+        );
+
+        #[cfg(feature = "CL_VERSION_1_2")]
+        {
+            buffer.fill(queue, 7, 4, 4).unwrap();
+            finish(queue).unwrap();
+            assert_eq!(
+                &[3, 7, 7, 7, 7, 8],
+                &buffer.read_to_vec(queue).unwrap()[3..9]
+            );
+        }
+
+        let copy = Buffer::<cl_uint>::create(context, CL_MEM_READ_WRITE, 4).unwrap();
+        buffer.copy_to(queue, &copy, 12, 0, 4).unwrap();
+        finish(queue).unwrap();
+        assert_eq!(vec![100, 101, 102, 103], copy.read_to_vec(queue).unwrap());
+
+        // A memory object as a buffer of another element type
+        let bytes = Buffer::<cl_uchar>::from_mem(copy.clone().into_mem()).unwrap();
+        assert_eq!(16, bytes.len());
+        assert_eq!(
+            CL_INVALID_BUFFER_SIZE,
+            Buffer::<[cl_uchar; 3]>::from_mem(bytes.into_mem()).unwrap_err()
+        );
+
+        let vectors = Buffer::create_with_data(
+            context,
+            CL_MEM_READ_ONLY,
+            &[cl_float4::from([1.0, 2.0, 3.0, 4.0]); 2],
+        )
+        .unwrap();
+        assert_eq!(32, vectors.size());
+        let floats = Buffer::<cl_float>::from_mem(vectors.into_mem()).unwrap();
+        assert_eq!(
+            vec![1.0, 2.0, 3.0, 4.0, 1.0, 2.0, 3.0, 4.0],
+            floats.read_to_vec(queue).unwrap()
+        );
+
+        drop(floats);
+        drop(copy);
+        drop(buffer);
+        unsafe {
+            release_command_queue(queue).unwrap();
+            release_context(context).unwrap();
+        }
+    }
+}
@@ -0,0 +1,442 @@
+// Copyright (c) 2024 Via Technology Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Structured diagnostics from program build logs.
+//!
+//! The format of `CL_PROGRAM_BUILD_LOG` depends upon the `OpenCL` implementation.
+//! [`parse_build_log`] converts the common formats into [`Diagnostic`]s:
+//!
+//! * clang style, used by `PoCL`, Intel, AMD and recent NVIDIA drivers, e.g.:
+//!   `<source>:5:1: error: extraneous closing brace ('}')`
+//! * EDG style, used by older NVIDIA drivers, e.g.:
+//!   `"kernel.cl", line 5: error: identifier "x" is undefined`
+//!
+//! A diagnostic may be followed by the source line and a caret (`^`) line
+//! marking the column, which are stored as the snippet of the diagnostic.
+//! Other lines, e.g. "1 error generated.", are ignored.
+//!
+//! A `Diagnostic` is displayed like a rustc diagnostic, e.g.:
+//!
+//! ```text
+//! error: extraneous closing brace ('}')
+//!  --> <source>:5:1
+//!   |
+//! 5 | }
+//!   | ^
+//! ```
+
+use super::source_bundle::SourceFile;
+use std::fmt;
+
+/// The severity of a diagnostic.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    Note,
+    Remark,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    /// The name of the severity as it appears in a diagnostic.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Note => "note",
+            Self::Remark => "remark",
+            Self::Warning => "warning",
+            Self::Error => "error",
+        }
+    }
+
+    /// Parse a severity name, with an optional EDG error number, e.g. "error #20".
+    fn parse(text: &str) -> Option<Self> {
+        let name = text.split_once(" #").map_or(text, |(name, _)| name);
+        match name {
+            "fatal error" | "error" | "catastrophic error" => Some(Self::Error),
+            "warning" => Some(Self::Warning),
+            "remark" => Some(Self::Remark),
+            "note" => Some(Self::Note),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A diagnostic from a program build log.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Diagnostic {
+    /// The severity of the diagnostic.
+    pub severity: Option<Severity>,
+    /// The source file name, e.g. "<source>" for a program created from source strings.
+    pub file: Option<String>,
+    /// The line number, starting from 1.
+    pub line: Option<usize>,
+    /// The column number, starting from 1.
+    pub column: Option<usize>,
+    /// The diagnostic message.
+    pub message: String,
+    /// The source line of the diagnostic, if it is in the build log.
+    pub snippet: Option<String>,
+}
+
+impl Diagnostic {
+    /// Whether the diagnostic is an error.
+    #[must_use]
+    pub fn is_error(&self) -> bool {
+        Some(Severity::Error) == self.severity
+    }
+
+    /// Render the diagnostic like a rustc diagnostic, taking the source line
+    /// from `source` if the diagnostic is in that file, otherwise from the snippet.
+    ///
+    /// * `source` - the program source file and its name in the build log,
+    ///   e.g. "<source>", to show the source line if the build log does not contain it.
+    #[must_use]
+    pub fn render(&self, source: Option<&SourceFile>) -> String {
+        let mut text = String::new();
+        let _ = self.write_rendered(&mut text, source);
+        text
+    }
+
+    fn write_rendered(&self, f: &mut impl fmt::Write, source: Option<&SourceFile>) -> fmt::Result {
+        let severity = self.severity.unwrap_or(Severity::Error);
+        write!(f, "{severity}: {}", self.message)?;
+
+        let Some(line) = self.line else {
+            return Ok(());
+        };
+        let file = self.file.as_deref().unwrap_or("<source>");
+        let number = line.to_string();
+        let margin = " ".repeat(number.len());
+        write!(f, "\n{margin}--> {file}:{line}")?;
+        if let Some(column) = self.column {
+            write!(f, ":{column}")?;
+        }
+
+        // A diagnostic in an included file refers to the lines of that file
+        let source_line = source
+            .filter(|source| file == source.name)
+            .and_then(|source| source.source.lines().nth(line.saturating_sub(1)))
+            .or(self.snippet.as_deref());
+        if let Some(source_line) = source_line {
+            write!(f, "\n{margin} |\n{number} | {source_line}")?;
+            if let Some(column) = self.column {
+                // Keep tabs so that the caret lines up with the source line
+                let indent: String = source_line
+                    .chars()
+                    .take(column.saturating_sub(1))
+                    .map(|c| if '\t' == c { '\t' } else { ' ' })
+                    .collect();
+                write!(f, "\n{margin} | {indent}^")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.write_rendered(f, None)
+    }
+}
+
+/// Parse a clang style diagnostic line: `file:line[:column]: severity: message`
+/// or `severity: message`.
+fn parse_clang(text: &str) -> Option<Diagnostic> {
+    if let Some((severity, message)) = text.split_once(": ") {
+        if let Some(severity) = Severity::parse(severity) {
+            return Some(Diagnostic {
+                severity: Some(severity),
+                message: message.trim().to_owned(),
+                ..Diagnostic::default()
+            });
+        }
+    }
+
+    // The file name may contain ": ", so try each one as the end of the location
+    for (index, _) in text.match_indices(": ") {
+        let (severity, message) = text[index + 2..].split_once(": ")?;
+        if let (Some(severity), Some((file, line, column))) =
+            (Severity::parse(severity), parse_location(&text[..index]))
+        {
+            return Some(Diagnostic {
+                severity: Some(severity),
+                file: Some(file.to_owned()),
+                line: Some(line),
+                column,
+                message: message.trim().to_owned(),
+                snippet: None,
+            });
+        }
+    }
+    None
+}
+
+/// Parse a clang style location: `file:line[:column]`.
+fn parse_location(location: &str) -> Option<(&str, usize, Option<usize>)> {
+    let (rest, last) = location.rsplit_once(':')?;
+    let last = last.parse().ok()?;
+    match rest.rsplit_once(':') {
+        Some((file, line)) if line.parse::<usize>().is_ok() => {
+            line.parse().ok().map(|line| (file, line, Some(last)))
+        }
+        _ => Some((rest, last, None)),
+    }
+}
+
+/// Parse an EDG style diagnostic line: `"file", line number: severity: message`.
+fn parse_edg(text: &str) -> Option<Diagnostic> {
+    let text = text.strip_prefix('"')?;
+    let (file, rest) = text.split_once("\", line ")?;
+    let (line, rest) = rest.split_once(':')?;
+    let (severity, message) = rest.trim_start().split_once(": ")?;
+    Some(Diagnostic {
+        severity: Some(Severity::parse(severity)?),
+        file: Some(file.to_owned()),
+        line: Some(line.trim().parse().ok()?),
+        column: None,
+        message: message.trim().to_owned(),
+        snippet: None,
+    })
+}
+
+/// The 1-based column of the caret in a caret line, e.g. `    ^~~~`.
+fn caret_column(text: &str) -> Option<usize> {
+    let trimmed = text.trim();
+    if trimmed.starts_with('^') && trimmed.chars().all(|c| matches!(c, '^' | '~' | ' ')) {
+        text.find('^')
+            .map(|index| text[..index].chars().count() + 1)
+    } else {
+        None
+    }
+}
+
+/// Parse a program build log into diagnostics.
+///
+/// * `log` - the build log, see `CL_PROGRAM_BUILD_LOG`.
+///
+/// returns the diagnostics in the order that they appear in the log.
+#[must_use]
+pub fn parse_build_log(log: &str) -> Vec<Diagnostic> {
+    let lines: Vec<&str> = log
+        .lines()
+        .map(|line| line.trim_end_matches('\r'))
+        .collect();
+    let mut diagnostics: Vec<Diagnostic> = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        i += 1;
+        let Some(mut diagnostic) = parse_clang(line).or_else(|| parse_edg(line)) else {
+            continue;
+        };
+
+        // The source line and the caret line, if present
+        if diagnostic.line.is_some() {
+            if let Some(column) = lines.get(i + 1).and_then(|line| caret_column(line)) {
+                diagnostic.snippet = Some(lines[i].to_owned());
+                diagnostic.column = diagnostic.column.or(Some(column));
+                i += 2;
+            }
+        }
+        diagnostics.push(diagnostic);
+    }
+    diagnostics
+}
+
+/// Render the diagnostics of a program build log like rustc diagnostics,
+/// see [`Diagnostic::render`].
+///
+/// * `log` - the build log, see `CL_PROGRAM_BUILD_LOG`.
+/// * `source` - the program source file, if available.
+///
+/// returns the rendered diagnostics, separated by blank lines.
+#[must_use]
+pub fn render_build_log(log: &str, source: Option<&SourceFile>) -> String {
+    parse_build_log(log)
+        .iter()
+        .map(|diagnostic| diagnostic.render(source))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::{create_context, release_context};
+    use crate::device::{get_device_ids, CL_DEVICE_TYPE_GPU};
+    use crate::platform::get_platform_ids;
+    use crate::program::{
+        build_program, create_program_with_source, get_program_build_info, release_program,
+        CL_PROGRAM_BUILD_LOG,
+    };
+    use std::ffi::CString;
+    use std::ptr;
+
+    #[test]
+    fn test_parse_clang_log() {
+        let log = "\
+<source>:3:5: error: use of undeclared identifier 'b'
+    b[0] += 1;
+    ^
+<source>:1:13: warning: unused parameter 'n'
+kernel void add(global int* a, int n)
+            ^~~
+C:\\kernels\\add.cl:7: note: previous definition is here
+error: cannot compile the program
+2 errors generated.
+";
+        let diagnostics = parse_build_log(log);
+        assert_eq!(4, diagnostics.len());
+
+        assert_eq!(
+            Diagnostic {
+                severity: Some(Severity::Error),
+                file: Some(String::from("<source>")),
+                line: Some(3),
+                column: Some(5),
+                message: String::from("use of undeclared identifier 'b'"),
+                snippet: Some(String::from("    b[0] += 1;")),
+            },
+            diagnostics[0]
+        );
+        assert!(diagnostics[0].is_error());
+
+        assert_eq!(Some(Severity::Warning), diagnostics[1].severity);
+        assert_eq!(Some(13), diagnostics[1].column);
+        assert!(!diagnostics[1].is_error());
+
+        assert_eq!(Some(Severity::Note), diagnostics[2].severity);
+        assert_eq!(Some("C:\\kernels\\add.cl"), diagnostics[2].file.as_deref());
+        assert_eq!(Some(7), diagnostics[2].line);
+        assert_eq!(None, diagnostics[2].column);
+
+        assert_eq!(None, diagnostics[3].line);
+        assert_eq!("cannot compile the program", diagnostics[3].message);
+
+        assert_eq!(
+            "\
+error: use of undeclared identifier 'b'
+ --> <source>:3:5
+  |
+3 |     b[0] += 1;
+  |     ^",
+            diagnostics[0].to_string()
+        );
+        assert_eq!(
+            "error: cannot compile the program",
+            diagnostics[3].to_string()
+        );
+    }
+
+    #[test]
+    fn test_parse_edg_log() {
+        let log = "\
+\"kernel.cl\", line 12: error: identifier \"x\" is undefined
+      x = 1;
+      ^
+
+\"kernel.cl\", line 20: warning #550-D: variable \"y\" was set but never used
+
+1 error detected in the compilation of \"kernel.cl\".
+";
+        let diagnostics = parse_build_log(log);
+        assert_eq!(2, diagnostics.len());
+        assert_eq!(Some(Severity::Error), diagnostics[0].severity);
+        assert_eq!(Some("kernel.cl"), diagnostics[0].file.as_deref());
+        assert_eq!(Some(12), diagnostics[0].line);
+        assert_eq!(Some(7), diagnostics[0].column);
+        assert_eq!("identifier \"x\" is undefined", diagnostics[0].message);
+
+        assert_eq!(Some(Severity::Warning), diagnostics[1].severity);
+        assert_eq!(Some(20), diagnostics[1].line);
+        assert_eq!(None, diagnostics[1].column);
+
+        // The source line is taken from the source when it is given
+        let text = (1..=20)
+            .map(|i| format!("line {i};"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let source = SourceFile {
+            name: String::from("kernel.cl"),
+            source: text.clone(),
+        };
+        assert_eq!(
+            "\
+warning: variable \"y\" was set but never used
+  --> kernel.cl:20
+   |
+20 | line 20;",
+            diagnostics[1].render(Some(&source))
+        );
+
+        // but not for a diagnostic in another file
+        let header = SourceFile {
+            name: String::from("header.h"),
+            source: text,
+        };
+        assert_eq!(
+            "\
+warning: variable \"y\" was set but never used
+  --> kernel.cl:20",
+            diagnostics[1].render(Some(&header))
+        );
+    }
+
+    #[test]
+    fn test_render_build_log() {
+        let platform_ids = get_platform_ids().unwrap();
+        let device_ids = get_device_ids(platform_ids[0], CL_DEVICE_TYPE_GPU).unwrap();
+        let device_id = device_ids[0];
+        let context = create_context(&device_ids, ptr::null(), None, ptr::null_mut()).unwrap();
+
+        // The source has an extra closing brace
+        let source = "kernel void add(global int* a)\n{\n    a[0] += 1;\n}\n}\n";
+        let program = create_program_with_source(context, &[source]).unwrap();
+        let options = CString::new("").unwrap();
+        assert!(build_program(program, &[device_id], &options, None, ptr::null_mut()).is_err());
+        let log =
+            String::from(get_program_build_info(program, device_id, CL_PROGRAM_BUILD_LOG).unwrap());
+
+        let diagnostics = parse_build_log(&log);
+        let error = diagnostics
+            .iter()
+            .find(|diagnostic| diagnostic.is_error() && Some(5) == diagnostic.line)
+            .unwrap();
+        assert_eq!(Some(1), error.column);
+
+        // The name of the program source file depends upon the implementation
+        let source = SourceFile {
+            name: error
+                .file
+                .clone()
+                .unwrap_or_else(|| String::from("<source>")),
+            source: String::from(source),
+        };
+        let text = render_build_log(&log, Some(&source));
+        println!("{text}");
+        assert!(text.contains("5 | }"));
+
+        unsafe {
+            release_program(program).unwrap();
+            release_context(context).unwrap();
+        }
+    }
+}
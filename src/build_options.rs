@@ -0,0 +1,476 @@
+// Copyright (c) 2024 Via Technology Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Typed `OpenCL` program build options.
+//!
+//! [`BuildOptions`] builds the options string for `build_program`,
+//! `compile_program` and `link_program` from the options in the `OpenCL`
+//! specification, so that a misspelt option is a compile error, e.g.:
+//!
+//! ```no_run
+//! use cl3::build_options::{BuildOptions, ClStd};
+//! use cl3::program::build_program;
+//! use cl3::types::{cl_device_id, cl_int, cl_program};
+//! use std::ptr;
+//!
+//! fn example(program: cl_program, device: cl_device_id) -> Result<(), cl_int> {
+//!     let options = BuildOptions::new()
+//!         .cl_std(ClStd::Cl20)
+//!         .define_value("BLOCK_SIZE", 64)
+//!         .fast_relaxed_math()
+//!         .kernel_arg_info();
+//!     let options = options.for_devices(&[device])?;
+//!     build_program(program, &[device], &options, None, ptr::null_mut())
+//! }
+//! ```
+//!
+//! `for_devices` checks that the devices support the requested `-cl-std`
+//! version, see [`BuildOptions::validate`].
+//! Vendor specific options can be added with [`BuildOptions::option`].
+
+use super::api_error::{discard_error, record_error};
+use super::device_info::{get_device_param, OpenClCAllVersions, OpenClCVersion};
+use super::owned::AsDevice;
+use opencl_sys::{
+    cl_device_id, cl_int, cl_version, CL_INVALID_BUILD_OPTIONS, CL_VERSION_MINOR_BITS,
+    CL_VERSION_MINOR_MASK, CL_VERSION_PATCH_BITS,
+};
+use std::ffi::CString;
+use std::fmt;
+use std::path::Path;
+
+/// An `OpenCL` C language version for the `-cl-std` build option.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ClStd {
+    /// `-cl-std=CL1.1`
+    Cl11,
+    /// `-cl-std=CL1.2`
+    Cl12,
+    /// `-cl-std=CL2.0`
+    Cl20,
+    /// `-cl-std=CL3.0`
+    Cl30,
+}
+
+impl ClStd {
+    /// The value of the `-cl-std` option, e.g. "CL1.2".
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Cl11 => "CL1.1",
+            Self::Cl12 => "CL1.2",
+            Self::Cl20 => "CL2.0",
+            Self::Cl30 => "CL3.0",
+        }
+    }
+
+    /// The major and minor version numbers.
+    #[must_use]
+    pub const fn major_minor(self) -> (cl_version, cl_version) {
+        match self {
+            Self::Cl11 => (1, 1),
+            Self::Cl12 => (1, 2),
+            Self::Cl20 => (2, 0),
+            Self::Cl30 => (3, 0),
+        }
+    }
+
+    /// Whether the language version is supported by a device.
+    /// Uses `CL_DEVICE_OPENCL_C_ALL_VERSIONS` if the device supports it,
+    /// otherwise `CL_DEVICE_OPENCL_C_VERSION`, which is the highest version
+    /// before `OpenCL` 3.0.
+    ///
+    /// * `device` - the `cl_device_id` of the `OpenCL` device.
+    ///
+    /// returns a Result containing whether the version is supported
+    /// or the error code from the `OpenCL` C API function.
+    pub fn is_supported_by(self, device: impl AsDevice) -> Result<bool, cl_int> {
+        let device = device.as_raw();
+        let version = self.major_minor();
+        if let Some(versions) = discard_error(get_device_param(device, OpenClCAllVersions)) {
+            if !versions.is_empty() {
+                return Ok(versions
+                    .iter()
+                    .any(|name_version| major_minor(name_version.version) == version));
+            }
+        }
+        let text = get_device_param(device, OpenClCVersion)?;
+        Ok(parse_opencl_c_version(&text).is_some_and(|highest| version <= highest))
+    }
+}
+
+impl fmt::Display for ClStd {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The major and minor version numbers of a `cl_version`.
+pub(crate) const fn major_minor(version: cl_version) -> (cl_version, cl_version) {
+    (
+        version >> (CL_VERSION_MINOR_BITS + CL_VERSION_PATCH_BITS),
+        (version >> CL_VERSION_PATCH_BITS) & CL_VERSION_MINOR_MASK,
+    )
+}
+
+/// Parse a `CL_DEVICE_OPENCL_C_VERSION`, i.e.
+/// "`OpenCL C <major>.<minor> <vendor-specific information>`".
+fn parse_opencl_c_version(text: &str) -> Option<(cl_version, cl_version)> {
+    let version = text.strip_prefix("OpenCL C ")?.split_whitespace().next()?;
+    let (major, minor) = version.split_once('.')?;
+    Some((major.parse().ok()?, minor.parse().ok()?))
+}
+
+/// A builder for `OpenCL` program build, compile and link options.
+///
+/// The options are kept in the order that they are added.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BuildOptions {
+    options: Vec<String>,
+    cl_std: Option<ClStd>,
+}
+
+impl BuildOptions {
+    /// Create empty build options.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an option, e.g. a vendor specific option.
+    #[must_use]
+    pub fn option(mut self, option: impl Into<String>) -> Self {
+        self.options.push(option.into());
+        self
+    }
+
+    /// Predefine `name` as a macro with the value 1: `-D name`.
+    #[must_use]
+    pub fn define(self, name: &str) -> Self {
+        self.option(format!("-D {name}"))
+    }
+
+    /// Predefine `name` as a macro with a value: `-D name=value`.
+    #[must_use]
+    pub fn define_value(self, name: &str, value: impl fmt::Display) -> Self {
+        self.option(format!("-D {name}={value}"))
+    }
+
+    /// Add a directory to the header search path: `-I dir`.
+    /// A directory containing whitespace is quoted, which not all
+    /// `OpenCL` implementations support.
+    #[must_use]
+    pub fn include_dir(self, dir: impl AsRef<Path>) -> Self {
+        let dir = dir.as_ref().display().to_string();
+        if dir.contains(char::is_whitespace) {
+            self.option(format!("-I \"{dir}\""))
+        } else {
+            self.option(format!("-I {dir}"))
+        }
+    }
+
+    /// Set the `OpenCL` C language version: `-cl-std=`.
+    #[must_use]
+    pub const fn cl_std(mut self, version: ClStd) -> Self {
+        self.cl_std = Some(version);
+        self
+    }
+
+    /// The requested `OpenCL` C language version, if any.
+    #[must_use]
+    pub const fn get_cl_std(&self) -> Option<ClStd> {
+        self.cl_std
+    }
+
+    /// `-cl-single-precision-constant`
+    #[must_use]
+    pub fn single_precision_constant(self) -> Self {
+        self.option("-cl-single-precision-constant")
+    }
+
+    /// `-cl-denorms-are-zero`
+    #[must_use]
+    pub fn denorms_are_zero(self) -> Self {
+        self.option("-cl-denorms-are-zero")
+    }
+
+    /// `-cl-fp32-correctly-rounded-divide-sqrt`
+    #[must_use]
+    pub fn fp32_correctly_rounded_divide_sqrt(self) -> Self {
+        self.option("-cl-fp32-correctly-rounded-divide-sqrt")
+    }
+
+    /// `-cl-opt-disable`
+    #[must_use]
+    pub fn opt_disable(self) -> Self {
+        self.option("-cl-opt-disable")
+    }
+
+    /// `-cl-mad-enable`
+    #[must_use]
+    pub fn mad_enable(self) -> Self {
+        self.option("-cl-mad-enable")
+    }
+
+    /// `-cl-no-signed-zeros`
+    #[must_use]
+    pub fn no_signed_zeros(self) -> Self {
+        self.option("-cl-no-signed-zeros")
+    }
+
+    /// `-cl-unsafe-math-optimizations`
+    #[must_use]
+    pub fn unsafe_math_optimizations(self) -> Self {
+        self.option("-cl-unsafe-math-optimizations")
+    }
+
+    /// `-cl-finite-math-only`
+    #[must_use]
+    pub fn finite_math_only(self) -> Self {
+        self.option("-cl-finite-math-only")
+    }
+
+    /// `-cl-fast-relaxed-math`
+    #[must_use]
+    pub fn fast_relaxed_math(self) -> Self {
+        self.option("-cl-fast-relaxed-math")
+    }
+
+    /// `-cl-uniform-work-group-size`, `OpenCL` 2.0 or later.
+    #[must_use]
+    pub fn uniform_work_group_size(self) -> Self {
+        self.option("-cl-uniform-work-group-size")
+    }
+
+    /// `-cl-no-subgroup-ifp`, `OpenCL` 2.1 or later.
+    #[must_use]
+    pub fn no_subgroup_ifp(self) -> Self {
+        self.option("-cl-no-subgroup-ifp")
+    }
+
+    /// `-cl-kernel-arg-info`, store the kernel argument information for
+    /// `get_kernel_arg_info`.
+    #[must_use]
+    pub fn kernel_arg_info(self) -> Self {
+        self.option("-cl-kernel-arg-info")
+    }
+
+    /// `-g`, generate debugging information.
+    #[must_use]
+    pub fn debug(self) -> Self {
+        self.option("-g")
+    }
+
+    /// `-w`, inhibit all warning messages.
+    #[must_use]
+    pub fn no_warnings(self) -> Self {
+        self.option("-w")
+    }
+
+    /// `-Werror`, make all warnings into errors.
+    #[must_use]
+    pub fn warnings_as_errors(self) -> Self {
+        self.option("-Werror")
+    }
+
+    /// `-create-library`, a `link_program` option.
+    #[must_use]
+    pub fn create_library(self) -> Self {
+        self.option("-create-library")
+    }
+
+    /// `-enable-link-options`, a `link_program` option.
+    #[must_use]
+    pub fn enable_link_options(self) -> Self {
+        self.option("-enable-link-options")
+    }
+
+    /// Check that the devices support the requested `-cl-std` version,
+    /// see [`ClStd::is_supported_by`].
+    ///
+    /// * `devices` - the devices that the program will be built for.
+    ///
+    /// returns an empty Result or `CL_INVALID_BUILD_OPTIONS` if a device does
+    /// not support the version, or the error code from the `OpenCL` C API function.
+    pub fn validate(&self, devices: &[cl_device_id]) -> Result<(), cl_int> {
+        if let Some(version) = self.cl_std {
+            for device in devices {
+                if !version.is_supported_by(*device)? {
+                    return Err(record_error!(
+                        CL_INVALID_BUILD_OPTIONS,
+                        BuildOptions::validate,
+                        version,
+                        device
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The options as a null-terminated string.
+    ///
+    /// returns a Result containing the options or `CL_INVALID_BUILD_OPTIONS`
+    /// if an option contains a nul character.
+    pub fn to_cstring(&self) -> Result<CString, cl_int> {
+        CString::new(self.to_string()).map_err(|error| {
+            // The options and the position of the nul character
+            let nul_position = error.nul_position();
+            let options = String::from_utf8_lossy(&error.into_vec()).into_owned();
+            record_error!(
+                CL_INVALID_BUILD_OPTIONS,
+                BuildOptions::to_cstring,
+                options,
+                nul_position
+            )
+        })
+    }
+
+    /// Validate the options for the devices and return them as a
+    /// null-terminated string, see [`Self::validate`] and [`Self::to_cstring`].
+    ///
+    /// * `devices` - the devices that the program will be built for.
+    ///
+    /// returns a Result containing the options or `CL_INVALID_BUILD_OPTIONS`,
+    /// or the error code from the `OpenCL` C API function.
+    pub fn for_devices(&self, devices: &[cl_device_id]) -> Result<CString, cl_int> {
+        self.validate(devices)?;
+        self.to_cstring()
+    }
+}
+
+impl fmt::Display for BuildOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cl_std = self.cl_std.map(|version| format!("-cl-std={version}"));
+        for (index, option) in cl_std.iter().chain(&self.options).enumerate() {
+            if 0 < index {
+                f.write_str(" ")?;
+            }
+            f.write_str(option)?;
+        }
+        Ok(())
+    }
+}
+
+impl TryFrom<&BuildOptions> for CString {
+    type Error = cl_int;
+
+    fn try_from(options: &BuildOptions) -> Result<Self, cl_int> {
+        options.to_cstring()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_error::ApiError;
+    use crate::context::{create_context, release_context};
+    use crate::device::{get_device_ids, CL_DEVICE_TYPE_GPU};
+    use crate::platform::get_platform_ids;
+    use crate::program::{
+        build_program, create_program_with_source, get_program_build_info, release_program,
+        CL_PROGRAM_BUILD_OPTIONS,
+    };
+    use std::ptr;
+
+    #[test]
+    fn test_build_options() {
+        let options = BuildOptions::new()
+            .define("DEBUG")
+            .define_value("SCALE", 2.5)
+            .include_dir("include")
+            .include_dir("my headers")
+            .mad_enable()
+            .kernel_arg_info()
+            .warnings_as_errors()
+            .option("-cl-nv-verbose");
+        assert_eq!(
+            "-D DEBUG -D SCALE=2.5 -I include -I \"my headers\" -cl-mad-enable -cl-kernel-arg-info -Werror -cl-nv-verbose",
+            options.to_string()
+        );
+
+        let options = options.cl_std(ClStd::Cl12);
+        assert_eq!(Some(ClStd::Cl12), options.get_cl_std());
+        let text = options.to_cstring().unwrap();
+        assert!(text.to_str().unwrap().starts_with("-cl-std=CL1.2 -D DEBUG"));
+
+        assert_eq!("", BuildOptions::new().to_string());
+        let code = BuildOptions::new()
+            .option("-D A\0")
+            .to_cstring()
+            .unwrap_err();
+        assert_eq!(CL_INVALID_BUILD_OPTIONS, code);
+        assert_eq!(
+            "BuildOptions::to_cstring(options: \"-D A\\0\", nul_position: 4) failed: \
+             CL_INVALID_BUILD_OPTIONS",
+            crate::api_error::ApiError::from(code).to_string()
+        );
+
+        assert_eq!(Some((1, 2)), parse_opencl_c_version("OpenCL C 1.2 vendor"));
+        assert_eq!(Some((2, 0)), parse_opencl_c_version("OpenCL C 2.0"));
+        assert_eq!(None, parse_opencl_c_version("OpenCL 1.2"));
+    }
+
+    #[test]
+    fn test_build_options_program() {
+        let platform_ids = get_platform_ids().unwrap();
+        let device_ids = get_device_ids(platform_ids[0], CL_DEVICE_TYPE_GPU).unwrap();
+        let device_id = device_ids[0];
+
+        let versions = [ClStd::Cl11, ClStd::Cl12, ClStd::Cl20, ClStd::Cl30];
+        for version in versions {
+            println!(
+                "{}: {}",
+                version,
+                version.is_supported_by(device_id).unwrap()
+            );
+        }
+        assert!(ClStd::Cl12.is_supported_by(device_id).unwrap());
+
+        // A version that a device does not support is rejected
+        if let Some(version) = versions
+            .into_iter()
+            .find(|version| !version.is_supported_by(device_id).unwrap())
+        {
+            let code = BuildOptions::new()
+                .cl_std(version)
+                .validate(&[device_id])
+                .unwrap_err();
+            let error = ApiError::from(code);
+            assert_eq!(CL_INVALID_BUILD_OPTIONS, error.code());
+            assert_eq!(Some("BuildOptions::validate"), error.function());
+        }
+
+        let context = create_context(&device_ids, ptr::null(), None, ptr::null_mut()).unwrap();
+        let source = "kernel void scale(global float* a) { a[get_global_id(0)] *= SCALE; }";
+        let program = create_program_with_source(context, &[source]).unwrap();
+
+        let options = BuildOptions::new()
+            .cl_std(ClStd::Cl12)
+            .define_value("SCALE", "2.0f")
+            .kernel_arg_info();
+        let text = options.for_devices(&device_ids).unwrap();
+        build_program(program, &device_ids, &text, None, ptr::null_mut()).unwrap();
+
+        let value = get_program_build_info(program, device_id, CL_PROGRAM_BUILD_OPTIONS).unwrap();
+        let value = String::from(value);
+        println!("CL_PROGRAM_BUILD_OPTIONS: {}", value);
+        assert!(value.contains("-cl-std=CL1.2"));
+
+        unsafe {
+            release_program(program).unwrap();
+            release_context(context).unwrap();
+        }
+    }
+}
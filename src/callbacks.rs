@@ -0,0 +1,466 @@
+// Copyright (c) 2024 Via Technology Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! `OpenCL` callbacks as Rust closures.
+//!
+//! The functions in this module are safe variants of the adapter functions
+//! that register an `extern "C"` callback function with `user_data`.
+//! They take a Rust closure instead, box it and pass it to `OpenCL` through
+//! a generic `extern "C"` trampoline function, which frees the box after
+//! the last call of the closure, e.g.:
+//!
+//! ```no_run
+//! use cl3::callbacks::set_event_callback;
+//! use cl3::event::CL_COMPLETE;
+//! use cl3::types::{cl_event, cl_int};
+//!
+//! fn example(event: cl_event) -> Result<(), cl_int> {
+//!     set_event_callback(event, CL_COMPLETE, |_event, status| {
+//!         println!("event status: {}", status);
+//!     })
+//! }
+//! ```
+//!
+//! A panic in a closure cannot unwind into the `OpenCL` implementation:
+//! it is caught by the trampoline function and the callback returns normally
+//! after the panic hook has reported it.
+
+#![allow(non_camel_case_types)]
+
+use super::context::{self, cl_context_properties};
+use super::event;
+use super::memory;
+#[cfg(feature = "CL_VERSION_3_0")]
+use super::owned::AsContext;
+use super::owned::{AsEvent, AsMem, AsProgram};
+use super::program;
+use libc::{c_char, c_void, size_t};
+use opencl_sys::{cl_context, cl_device_id, cl_device_type, cl_event, cl_int, cl_mem, cl_program};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::ffi::CStr;
+use std::panic::{self, AssertUnwindSafe};
+use std::slice;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, PoisonError};
+
+/// Call a closure, catching any panic so that it cannot unwind across the
+/// FFI boundary. The default panic hook has already reported the panic.
+fn call_guarded(f: impl FnOnce()) {
+    let _ = panic::catch_unwind(AssertUnwindSafe(f));
+}
+
+/// Box a closure and return it as `user_data` for a trampoline function.
+fn into_user_data<F>(callback: F) -> *mut c_void {
+    Box::into_raw(Box::new(callback)).cast::<c_void>()
+}
+
+/// Take back a closure boxed by `into_user_data` and free the box.
+///
+/// # Safety
+///
+/// `user_data` must have been returned by `into_user_data::<F>` and not taken before.
+unsafe fn from_user_data<F>(user_data: *mut c_void) -> F {
+    *Box::from_raw(user_data.cast::<F>())
+}
+
+extern "C" fn event_trampoline<F: FnOnce(cl_event, cl_int) + Send + 'static>(
+    event: cl_event,
+    event_command_status: cl_int,
+    user_data: *mut c_void,
+) {
+    let callback = unsafe { from_user_data::<F>(user_data) };
+    call_guarded(move || callback(event, event_command_status));
+}
+
+/// Register a closure for a specific command execution status of an event.
+/// Calls `clSetEventCallback` to register a callback function, see
+/// [`event::set_event_callback`].
+///
+/// * `event` - the `OpenCL` event.
+/// * `command_exec_callback_type` - the command execution status:
+///   `CL_SUBMITTED`, `CL_RUNNING` or `CL_COMPLETE`.
+/// * `callback` - the closure, called once with the event and its command
+///   execution status, which is negative if the command was abnormally terminated.
+///
+/// returns an empty Result or the error code from the `OpenCL` C API function.
+pub fn set_event_callback<F>(
+    event: impl AsEvent,
+    command_exec_callback_type: cl_int,
+    callback: F,
+) -> Result<(), cl_int>
+where
+    F: FnOnce(cl_event, cl_int) + Send + 'static,
+{
+    let user_data = into_user_data(callback);
+    event::set_event_callback(
+        event,
+        command_exec_callback_type,
+        event_trampoline::<F>,
+        user_data,
+    )
+    .inspect_err(|_| drop(unsafe { from_user_data::<F>(user_data) }))
+}
+
+/// The state of a context notification closure.
+type ContextNotify<F> = Mutex<F>;
+
+unsafe extern "C" fn context_trampoline<F: FnMut(&str, &[u8]) + Send + 'static>(
+    errinfo: *const c_char,
+    private_info: *const c_void,
+    cb: size_t,
+    user_data: *mut c_void,
+) {
+    // The closure is owned by the context, see context_notify_destructor
+    let callback = unsafe { &*user_data.cast_const().cast::<ContextNotify<F>>() };
+    let errinfo = if errinfo.is_null() {
+        Cow::Borrowed("")
+    } else {
+        unsafe { CStr::from_ptr(errinfo) }.to_string_lossy()
+    };
+    let private_info: &[u8] = if private_info.is_null() || 0 == cb {
+        &[]
+    } else {
+        unsafe { slice::from_raw_parts(private_info.cast::<u8>(), cb) }
+    };
+    // The closure may be called from different threads at the same time
+    let mut callback = callback.lock().unwrap_or_else(PoisonError::into_inner);
+    call_guarded(|| (*callback)(&errinfo, private_info));
+}
+
+#[cfg(feature = "CL_VERSION_3_0")]
+extern "C" fn context_notify_destructor<F: FnMut(&str, &[u8]) + Send + 'static>(
+    _context: cl_context,
+    user_data: *mut c_void,
+) {
+    drop(unsafe { from_user_data::<ContextNotify<F>>(user_data) });
+}
+
+/// Register the notification closure of a new context so that it is freed
+/// when the context is destroyed, if possible.
+fn set_context_notify<F: FnMut(&str, &[u8]) + Send + 'static>(
+    result: Result<cl_context, cl_int>,
+    user_data: *mut c_void,
+) -> Result<cl_context, cl_int> {
+    match result {
+        Ok(context) => {
+            // Otherwise the closure is leaked, since it may be called while the context exists
+            #[cfg(feature = "CL_VERSION_3_0")]
+            let _ = context::set_context_destructor_callback(
+                context,
+                Some(context_notify_destructor::<F>),
+                user_data,
+            );
+            Ok(context)
+        }
+        Err(error) => {
+            drop(unsafe { from_user_data::<ContextNotify<F>>(user_data) });
+            Err(error)
+        }
+    }
+}
+
+/// Create an `OpenCL` context with a notification closure.
+/// Calls clCreateContext to create an `OpenCL` context, see
+/// [`context::create_context`].
+///
+/// The closure is freed when the context is destroyed if the
+/// "`CL_VERSION_3_0`" feature is enabled and the platform supports
+/// `clSetContextDestructorCallback`, otherwise it is leaked.
+///
+/// * `devices` - a slice of unique devices for an `OpenCL` platform.
+/// * `properties` - a null terminated list of `cl_context_properties`, see
+///   [Context Properties](https://www.khronos.org/registry/OpenCL/specs/3.0-unified/html/OpenCL_API.html#context-properties-table).
+/// * `callback` - the closure, called with the error information and any
+///   binary data about the error to report errors in the context.
+///
+/// returns a Result containing the new `OpenCL` context
+/// or the error code from the `OpenCL` C API function.
+pub fn create_context<F>(
+    devices: &[cl_device_id],
+    properties: *const cl_context_properties,
+    callback: F,
+) -> Result<cl_context, cl_int>
+where
+    F: FnMut(&str, &[u8]) + Send + 'static,
+{
+    let user_data = into_user_data::<ContextNotify<F>>(Mutex::new(callback));
+    let result = context::create_context(
+        devices,
+        properties,
+        Some(context_trampoline::<F>),
+        user_data,
+    );
+    set_context_notify::<F>(result, user_data)
+}
+
+/// Create an `OpenCL` context from a specific device type with a notification closure.
+/// Calls `clCreateContextFromType` to create an `OpenCL` context, see
+/// [`context::create_context_from_type`]
+/// and [`create_context`].
+///
+/// * `device_type` - the type of `OpenCL` device, see:
+///   [Device Types](https://www.khronos.org/registry/OpenCL/specs/3.0-unified/html/OpenCL_API.html#device-types-table).
+/// * `properties` - a null terminated list of `cl_context_properties`, see:
+///   [Context Properties](https://www.khronos.org/registry/OpenCL/specs/3.0-unified/html/OpenCL_API.html#context-properties-table).
+/// * `callback` - the closure, called with the error information and any
+///   binary data about the error to report errors in the context.
+///
+/// returns a Result containing the new `OpenCL` context
+/// or the error code from the `OpenCL` C API function.
+pub fn create_context_from_type<F>(
+    device_type: cl_device_type,
+    properties: *const cl_context_properties,
+    callback: F,
+) -> Result<cl_context, cl_int>
+where
+    F: FnMut(&str, &[u8]) + Send + 'static,
+{
+    let user_data = into_user_data::<ContextNotify<F>>(Mutex::new(callback));
+    let result = context::create_context_from_type(
+        device_type,
+        properties,
+        Some(context_trampoline::<F>),
+        user_data,
+    );
+    set_context_notify::<F>(result, user_data)
+}
+
+#[cfg(feature = "CL_VERSION_3_0")]
+extern "C" fn context_destructor_trampoline<F: FnOnce(cl_context) + Send + 'static>(
+    context: cl_context,
+    user_data: *mut c_void,
+) {
+    let callback = unsafe { from_user_data::<F>(user_data) };
+    call_guarded(move || callback(context));
+}
+
+/// Register a closure with a context that is called when the `context` is destroyed.
+/// Calls `clSetContextDestructorCallback`, see
+/// [`context::set_context_destructor_callback`](crate::context::set_context_destructor_callback).
+/// `CL_VERSION_3_0`
+///
+/// * `context` - the `OpenCL` context.
+/// * `callback` - the closure, called once with the destroyed context.
+///
+/// returns an empty Result or the error code from the `OpenCL` C API function.
+#[cfg(feature = "CL_VERSION_3_0")]
+pub fn set_context_destructor_callback<F>(
+    context: impl AsContext,
+    callback: F,
+) -> Result<(), cl_int>
+where
+    F: FnOnce(cl_context) + Send + 'static,
+{
+    let user_data = into_user_data(callback);
+    context::set_context_destructor_callback(
+        context,
+        Some(context_destructor_trampoline::<F>),
+        user_data,
+    )
+    .inspect_err(|_| drop(unsafe { from_user_data::<F>(user_data) }))
+}
+
+extern "C" fn mem_destructor_trampoline<F: FnOnce(cl_mem) + Send + 'static>(
+    memobj: cl_mem,
+    user_data: *mut c_void,
+) {
+    let callback = unsafe { from_user_data::<F>(user_data) };
+    call_guarded(move || callback(memobj));
+}
+
+/// Register a closure with an `OpenCL` memory object that is called when the
+/// memory object is destroyed.
+/// Calls `clSetMemObjectDestructorCallback`, see
+/// [`memory::set_mem_object_destructor_callback`].
+///
+/// * `memobj` - the `OpenCL` memory object.
+/// * `callback` - the closure, called once with the destroyed memory object.
+///
+/// returns an empty Result or the error code from the `OpenCL` C API function.
+pub fn set_mem_object_destructor_callback<F>(memobj: impl AsMem, callback: F) -> Result<(), cl_int>
+where
+    F: FnOnce(cl_mem) + Send + 'static,
+{
+    let user_data = into_user_data(callback);
+    unsafe {
+        memory::set_mem_object_destructor_callback(
+            memobj,
+            mem_destructor_trampoline::<F>,
+            user_data,
+        )
+    }
+    .inspect_err(|_| drop(unsafe { from_user_data::<F>(user_data) }))
+}
+
+type ProgramNotify = Box<dyn FnOnce(cl_program) + Send>;
+
+/// The build notification closures that have not been called, by id.
+///
+/// `clBuildProgram` may, or may not, call the notification routine when it
+/// returns an error, so the closures are passed to it by id rather than by
+/// pointer, to remove a closure that was not called without the risk of
+/// freeing it while `OpenCL` may still call it.
+static BUILD_NOTIFY: Mutex<BTreeMap<usize, ProgramNotify>> = Mutex::new(BTreeMap::new());
+static NEXT_BUILD_NOTIFY: AtomicUsize = AtomicUsize::new(1);
+
+fn take_build_notify(id: usize) -> Option<ProgramNotify> {
+    BUILD_NOTIFY
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .remove(&id)
+}
+
+unsafe extern "C" fn build_trampoline(program: cl_program, user_data: *mut c_void) {
+    if let Some(callback) = take_build_notify(user_data as usize) {
+        call_guarded(move || callback(program));
+    }
+}
+
+/// Build (compile & link) a program executable with a notification closure.
+/// Calls `clBuildProgram` to build an `OpenCL` program object, see
+/// [`program::build_program`].
+///
+/// `OpenCL` may build the program asynchronously and return before the build
+/// has completed. If it returns an error, the closure has either been called
+/// already or it is dropped without being called.
+///
+/// * `program` - a valid `OpenCL` program.
+/// * `devices` - a slice of devices that are in context.
+/// * `options` - the build options in a null-terminated string.
+/// * `callback` - the closure, called once with the program when the build
+///   has completed, successfully or unsuccessfully.
+///
+/// returns an empty Result or the error code from the `OpenCL` C API function.
+pub fn build_program<F>(
+    program: impl AsProgram,
+    devices: &[cl_device_id],
+    options: &CStr,
+    callback: F,
+) -> Result<(), cl_int>
+where
+    F: FnOnce(cl_program) + Send + 'static,
+{
+    let id = NEXT_BUILD_NOTIFY.fetch_add(1, Ordering::Relaxed);
+    BUILD_NOTIFY
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(id, Box::new(callback));
+    program::build_program(
+        program,
+        devices,
+        options,
+        Some(build_trampoline),
+        id as *mut c_void,
+    )
+    .inspect_err(|_| drop(take_build_notify(id)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::release_context;
+    use crate::device::{get_device_ids, CL_DEVICE_TYPE_GPU};
+    use crate::event::{create_user_event, release_event, set_user_event_status, CL_COMPLETE};
+    use crate::memory::{create_buffer, release_mem_object, CL_MEM_READ_WRITE};
+    use crate::platform::get_platform_ids;
+    use crate::program::{create_program_with_source, release_program};
+    use opencl_sys::{CL_BUILD_PROGRAM_FAILURE, CL_INVALID_VALUE};
+    use std::ptr;
+    use std::sync::mpsc;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_closure_callbacks() {
+        let platform_ids = get_platform_ids().unwrap();
+        assert!(0 < platform_ids.len());
+
+        let device_ids = get_device_ids(platform_ids[0], CL_DEVICE_TYPE_GPU).unwrap();
+        assert!(0 < device_ids.len());
+
+        // The notification closure is freed when the context is destroyed
+        let notify_state = Arc::new(AtomicUsize::new(0));
+        let state = Arc::clone(&notify_state);
+        let context = create_context(&device_ids, ptr::null(), move |errinfo, _| {
+            println!("context error: {}", errinfo);
+            state.fetch_add(1, Ordering::Relaxed);
+        })
+        .unwrap();
+        assert_eq!(2, Arc::strong_count(&notify_state));
+
+        // The closure is dropped if it cannot be registered
+        let error = create_context(&[], ptr::null(), |_, _| {}).unwrap_err();
+        assert_eq!(CL_INVALID_VALUE, error);
+
+        let (sender, receiver) = mpsc::channel();
+
+        let user_event = create_user_event(context).unwrap();
+        let event_sender = sender.clone();
+        set_event_callback(user_event, CL_COMPLETE, move |event, status| {
+            event_sender
+                .send(("event", event as usize, status))
+                .unwrap();
+        })
+        .unwrap();
+        // A panic in a closure does not unwind across the FFI boundary
+        set_event_callback(user_event, CL_COMPLETE, |_, _| panic!("callback panic")).unwrap();
+        set_user_event_status(user_event, CL_COMPLETE).unwrap();
+        assert_eq!(
+            ("event", user_event as usize, CL_COMPLETE),
+            receiver.recv().unwrap()
+        );
+        unsafe { release_event(user_event).unwrap() };
+
+        let buffer =
+            unsafe { create_buffer(context, CL_MEM_READ_WRITE, 4, ptr::null_mut()).unwrap() };
+        let mem_sender = sender.clone();
+        set_mem_object_destructor_callback(buffer, move |memobj| {
+            mem_sender.send(("mem", memobj as usize, 0)).unwrap();
+        })
+        .unwrap();
+        unsafe { release_mem_object(buffer).unwrap() };
+        assert_eq!(("mem", buffer as usize, 0), receiver.recv().unwrap());
+
+        // The closure is called when the build fails
+        let source = "kernel void add(global int* a)\n{\n    a[0] += 1;\n}\n}\n";
+        let program = create_program_with_source(context, &[source]).unwrap();
+        let build_sender = sender.clone();
+        let options = CStr::from_bytes_with_nul(b"\0").unwrap();
+        let error = build_program(program, &device_ids, options, move |program| {
+            build_sender.send(("build", program as usize, 0)).unwrap();
+        })
+        .unwrap_err();
+        assert_eq!(CL_BUILD_PROGRAM_FAILURE, error);
+        assert_eq!(("build", program as usize, 0), receiver.recv().unwrap());
+        assert!(BUILD_NOTIFY.lock().unwrap().is_empty());
+        unsafe { release_program(program).unwrap() };
+
+        #[cfg(feature = "CL_VERSION_3_0")]
+        {
+            let context_sender = sender.clone();
+            set_context_destructor_callback(context, move |context| {
+                context_sender
+                    .send(("context", context as usize, 0))
+                    .unwrap();
+            })
+            .unwrap();
+        }
+        unsafe { release_context(context).unwrap() };
+        #[cfg(feature = "CL_VERSION_3_0")]
+        {
+            assert_eq!(("context", context as usize, 0), receiver.recv().unwrap());
+            assert_eq!(1, Arc::strong_count(&notify_state));
+        }
+    }
+}
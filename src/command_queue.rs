@@ -31,7 +31,8 @@ pub use opencl_sys::{
     CL_SUCCESS,
 };
 
-use opencl_sys::{
+use super::api_error::{clear_error, record_error};
+use super::ffi::{
     clCreateCommandQueue, clEnqueueBarrierWithWaitList, clEnqueueCopyBuffer,
     clEnqueueCopyBufferRect, clEnqueueCopyBufferToImage, clEnqueueCopyImage,
     clEnqueueCopyImageToBuffer, clEnqueueFillBuffer, clEnqueueFillImage, clEnqueueMapBuffer,
@@ -43,15 +44,16 @@ use opencl_sys::{
 };
 
 #[cfg(feature = "CL_VERSION_2_0")]
-use opencl_sys::{
+use super::ffi::{
     clCreateCommandQueueWithProperties, clEnqueueSVMFree, clEnqueueSVMMap, clEnqueueSVMMemFill,
     clEnqueueSVMMemcpy, clEnqueueSVMUnmap,
 };
 
 #[cfg(feature = "CL_VERSION_2_1")]
-use opencl_sys::clEnqueueSVMMigrateMem;
+use super::ffi::clEnqueueSVMMigrateMem;
 
 use super::info_type::InfoType;
+use super::owned::{AsCommandQueue, AsContext, AsDevice, AsKernel, AsMem};
 use super::{api_info_size, api_info_value, api_info_vector};
 use libc::{c_void, intptr_t, size_t};
 use std::mem;
@@ -86,16 +88,19 @@ use std::ptr;
 )]
 #[inline]
 pub unsafe fn create_command_queue(
-    context: cl_context,
-    device: cl_device_id,
+    context: impl AsContext,
+    device: impl AsDevice,
     properties: cl_command_queue_properties,
 ) -> Result<cl_command_queue, cl_int> {
+    let context = context.as_raw();
+    let device = device.as_raw();
+    clear_error();
     let mut status: cl_int = CL_INVALID_VALUE;
     let queue: cl_command_queue = clCreateCommandQueue(context, device, properties, &mut status);
     if CL_SUCCESS == status {
         Ok(queue)
     } else {
-        Err(status)
+        Err(record_error!(status, clCreateCommandQueue))
     }
 }
 
@@ -117,17 +122,20 @@ pub unsafe fn create_command_queue(
 #[cfg(feature = "CL_VERSION_2_0")]
 #[inline]
 pub unsafe fn create_command_queue_with_properties(
-    context: cl_context,
-    device: cl_device_id,
+    context: impl AsContext,
+    device: impl AsDevice,
     properties: *const cl_queue_properties,
 ) -> Result<cl_command_queue, cl_int> {
+    let context = context.as_raw();
+    let device = device.as_raw();
+    clear_error();
     let mut status: cl_int = CL_INVALID_VALUE;
     let queue: cl_command_queue =
         clCreateCommandQueueWithProperties(context, device, properties, &mut status);
     if CL_SUCCESS == status {
         Ok(queue)
     } else {
-        Err(status)
+        Err(record_error!(status, clCreateCommandQueueWithProperties))
     }
 }
 
@@ -143,11 +151,12 @@ pub unsafe fn create_command_queue_with_properties(
 /// This function is unsafe because it changes the `OpenCL` object reference count.
 #[inline]
 pub unsafe fn retain_command_queue(command_queue: cl_command_queue) -> Result<(), cl_int> {
+    clear_error();
     let status: cl_int = clRetainCommandQueue(command_queue);
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(status, clRetainCommandQueue))
     }
 }
 
@@ -163,20 +172,23 @@ pub unsafe fn retain_command_queue(command_queue: cl_command_queue) -> Result<()
 /// This function is unsafe because it changes the `OpenCL` object reference count.
 #[inline]
 pub unsafe fn release_command_queue(command_queue: cl_command_queue) -> Result<(), cl_int> {
+    clear_error();
     let status: cl_int = clReleaseCommandQueue(command_queue);
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(status, clReleaseCommandQueue))
     }
 }
 
 /// Get data about an `OpenCL` command-queue.
 /// Calls clGetCommandQueueInfo to get the desired data about the command-queue.
 pub fn get_command_queue_data(
-    command_queue: cl_command_queue,
+    command_queue: impl AsCommandQueue,
     param_name: cl_command_queue_info,
 ) -> Result<Vec<u8>, cl_int> {
+    let command_queue = command_queue.as_raw();
+    clear_error();
     api_info_size!(get_size, clGetCommandQueueInfo);
     let size = get_size(command_queue, param_name)?;
     api_info_vector!(get_vector, u8, clGetCommandQueueInfo);
@@ -193,9 +205,11 @@ pub fn get_command_queue_data(
 /// returns a Result containing the desired information in an `InfoType` enum
 /// or the error code from the `OpenCL` C API function.
 pub fn get_command_queue_info(
-    command_queue: cl_command_queue,
+    command_queue: impl AsCommandQueue,
     param_name: cl_command_queue_info,
 ) -> Result<InfoType, cl_int> {
+    let command_queue = command_queue.as_raw();
+    clear_error();
     match param_name {
         CL_QUEUE_REFERENCE_COUNT
         | CL_QUEUE_SIZE // CL_VERSION_2_0
@@ -242,12 +256,14 @@ pub fn get_command_queue_info(
 ///
 /// returns an empty Result or the error code from the `OpenCL` C API function.
 #[inline]
-pub fn flush(command_queue: cl_command_queue) -> Result<(), cl_int> {
+pub fn flush(command_queue: impl AsCommandQueue) -> Result<(), cl_int> {
+    let command_queue = command_queue.as_raw();
+    clear_error();
     let status: cl_int = unsafe { clFlush(command_queue) };
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(status, clFlush))
     }
 }
 
@@ -258,12 +274,14 @@ pub fn flush(command_queue: cl_command_queue) -> Result<(), cl_int> {
 ///
 /// returns an empty Result or the error code from the `OpenCL` C API function.
 #[inline]
-pub fn finish(command_queue: cl_command_queue) -> Result<(), cl_int> {
+pub fn finish(command_queue: impl AsCommandQueue) -> Result<(), cl_int> {
+    let command_queue = command_queue.as_raw();
+    clear_error();
     let status: cl_int = unsafe { clFinish(command_queue) };
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(status, clFinish))
     }
 }
 
@@ -271,8 +289,8 @@ pub fn finish(command_queue: cl_command_queue) -> Result<(), cl_int> {
 
 #[inline]
 pub unsafe fn enqueue_read_buffer(
-    command_queue: cl_command_queue,
-    buffer: cl_mem,
+    command_queue: impl AsCommandQueue,
+    buffer: impl AsMem,
     blocking_read: cl_bool,
     offset: size_t,
     size: size_t,
@@ -280,6 +298,9 @@ pub unsafe fn enqueue_read_buffer(
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
+    let command_queue = command_queue.as_raw();
+    let buffer = buffer.as_raw();
+    clear_error();
     let mut event: cl_event = ptr::null_mut();
     let status: cl_int = clEnqueueReadBuffer(
         command_queue,
@@ -295,14 +316,14 @@ pub unsafe fn enqueue_read_buffer(
     if CL_SUCCESS == status {
         Ok(event)
     } else {
-        Err(status)
+        Err(record_error!(status, clEnqueueReadBuffer, offset, size))
     }
 }
 
 #[inline]
 pub unsafe fn enqueue_read_buffer_rect(
-    command_queue: cl_command_queue,
-    buffer: cl_mem,
+    command_queue: impl AsCommandQueue,
+    buffer: impl AsMem,
     blocking_read: cl_bool,
     buffer_origin: *const size_t,
     host_origin: *const size_t,
@@ -315,6 +336,9 @@ pub unsafe fn enqueue_read_buffer_rect(
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
+    let command_queue = command_queue.as_raw();
+    let buffer = buffer.as_raw();
+    clear_error();
     let mut event: cl_event = ptr::null_mut();
     let status: cl_int = clEnqueueReadBufferRect(
         command_queue,
@@ -335,14 +359,14 @@ pub unsafe fn enqueue_read_buffer_rect(
     if CL_SUCCESS == status {
         Ok(event)
     } else {
-        Err(status)
+        Err(record_error!(status, clEnqueueReadBufferRect))
     }
 }
 
 #[inline]
 pub unsafe fn enqueue_write_buffer(
-    command_queue: cl_command_queue,
-    buffer: cl_mem,
+    command_queue: impl AsCommandQueue,
+    buffer: impl AsMem,
     blocking_write: cl_bool,
     offset: size_t,
     size: size_t,
@@ -350,6 +374,9 @@ pub unsafe fn enqueue_write_buffer(
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
+    let command_queue = command_queue.as_raw();
+    let buffer = buffer.as_raw();
+    clear_error();
     let mut event: cl_event = ptr::null_mut();
     let status: cl_int = clEnqueueWriteBuffer(
         command_queue,
@@ -365,14 +392,14 @@ pub unsafe fn enqueue_write_buffer(
     if CL_SUCCESS == status {
         Ok(event)
     } else {
-        Err(status)
+        Err(record_error!(status, clEnqueueWriteBuffer, offset, size))
     }
 }
 
 #[inline]
 pub unsafe fn enqueue_write_buffer_rect(
-    command_queue: cl_command_queue,
-    buffer: cl_mem,
+    command_queue: impl AsCommandQueue,
+    buffer: impl AsMem,
     blocking_write: cl_bool,
     buffer_origin: *const size_t,
     host_origin: *const size_t,
@@ -385,6 +412,9 @@ pub unsafe fn enqueue_write_buffer_rect(
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
+    let command_queue = command_queue.as_raw();
+    let buffer = buffer.as_raw();
+    clear_error();
     let mut event: cl_event = ptr::null_mut();
     let status: cl_int = clEnqueueWriteBufferRect(
         command_queue,
@@ -405,15 +435,15 @@ pub unsafe fn enqueue_write_buffer_rect(
     if CL_SUCCESS == status {
         Ok(event)
     } else {
-        Err(status)
+        Err(record_error!(status, clEnqueueWriteBufferRect))
     }
 }
 
 #[cfg(feature = "CL_VERSION_1_2")]
 #[inline]
 pub unsafe fn enqueue_fill_buffer(
-    command_queue: cl_command_queue,
-    buffer: cl_mem,
+    command_queue: impl AsCommandQueue,
+    buffer: impl AsMem,
     pattern: *const c_void,
     pattern_size: size_t,
     offset: size_t,
@@ -421,6 +451,9 @@ pub unsafe fn enqueue_fill_buffer(
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
+    let command_queue = command_queue.as_raw();
+    let buffer = buffer.as_raw();
+    clear_error();
     let mut event: cl_event = ptr::null_mut();
     let status: cl_int = clEnqueueFillBuffer(
         command_queue,
@@ -436,21 +469,31 @@ pub unsafe fn enqueue_fill_buffer(
     if CL_SUCCESS == status {
         Ok(event)
     } else {
-        Err(status)
+        Err(record_error!(
+            status,
+            clEnqueueFillBuffer,
+            offset,
+            size,
+            pattern_size
+        ))
     }
 }
 
 #[inline]
 pub unsafe fn enqueue_copy_buffer(
-    command_queue: cl_command_queue,
-    src_buffer: cl_mem,
-    dst_buffer: cl_mem,
+    command_queue: impl AsCommandQueue,
+    src_buffer: impl AsMem,
+    dst_buffer: impl AsMem,
     src_offset: size_t,
     dst_offset: size_t,
     size: size_t,
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
+    let command_queue = command_queue.as_raw();
+    let src_buffer = src_buffer.as_raw();
+    let dst_buffer = dst_buffer.as_raw();
+    clear_error();
     let mut event: cl_event = ptr::null_mut();
     let status: cl_int = clEnqueueCopyBuffer(
         command_queue,
@@ -466,15 +509,21 @@ pub unsafe fn enqueue_copy_buffer(
     if CL_SUCCESS == status {
         Ok(event)
     } else {
-        Err(status)
+        Err(record_error!(
+            status,
+            clEnqueueCopyBuffer,
+            src_offset,
+            dst_offset,
+            size
+        ))
     }
 }
 
 #[inline]
 pub unsafe fn enqueue_copy_buffer_rect(
-    command_queue: cl_command_queue,
-    src_buffer: cl_mem,
-    dst_buffer: cl_mem,
+    command_queue: impl AsCommandQueue,
+    src_buffer: impl AsMem,
+    dst_buffer: impl AsMem,
     src_origin: *const size_t,
     dst_origin: *const size_t,
     region: *const size_t,
@@ -485,6 +534,10 @@ pub unsafe fn enqueue_copy_buffer_rect(
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
+    let command_queue = command_queue.as_raw();
+    let src_buffer = src_buffer.as_raw();
+    let dst_buffer = dst_buffer.as_raw();
+    clear_error();
     let mut event: cl_event = ptr::null_mut();
     let status: cl_int = clEnqueueCopyBufferRect(
         command_queue,
@@ -504,14 +557,14 @@ pub unsafe fn enqueue_copy_buffer_rect(
     if CL_SUCCESS == status {
         Ok(event)
     } else {
-        Err(status)
+        Err(record_error!(status, clEnqueueCopyBufferRect))
     }
 }
 
 #[inline]
 pub unsafe fn enqueue_read_image(
-    command_queue: cl_command_queue,
-    image: cl_mem,
+    command_queue: impl AsCommandQueue,
+    image: impl AsMem,
     blocking_read: cl_bool,
     origin: *const size_t,
     region: *const size_t,
@@ -521,6 +574,9 @@ pub unsafe fn enqueue_read_image(
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
+    let command_queue = command_queue.as_raw();
+    let image = image.as_raw();
+    clear_error();
     let mut event: cl_event = ptr::null_mut();
     let status: cl_int = clEnqueueReadImage(
         command_queue,
@@ -538,14 +594,14 @@ pub unsafe fn enqueue_read_image(
     if CL_SUCCESS == status {
         Ok(event)
     } else {
-        Err(status)
+        Err(record_error!(status, clEnqueueReadImage))
     }
 }
 
 #[inline]
 pub unsafe fn enqueue_write_image(
-    command_queue: cl_command_queue,
-    image: cl_mem,
+    command_queue: impl AsCommandQueue,
+    image: impl AsMem,
     blocking_write: cl_bool,
     origin: *const size_t,
     region: *const size_t,
@@ -555,6 +611,9 @@ pub unsafe fn enqueue_write_image(
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
+    let command_queue = command_queue.as_raw();
+    let image = image.as_raw();
+    clear_error();
     let mut event: cl_event = ptr::null_mut();
     let status: cl_int = clEnqueueWriteImage(
         command_queue,
@@ -572,21 +631,24 @@ pub unsafe fn enqueue_write_image(
     if CL_SUCCESS == status {
         Ok(event)
     } else {
-        Err(status)
+        Err(record_error!(status, clEnqueueWriteImage))
     }
 }
 
 #[cfg(feature = "CL_VERSION_1_2")]
 #[inline]
 pub unsafe fn enqueue_fill_image(
-    command_queue: cl_command_queue,
-    image: cl_mem,
+    command_queue: impl AsCommandQueue,
+    image: impl AsMem,
     fill_color: *const c_void,
     origin: *const size_t,
     region: *const size_t,
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
+    let command_queue = command_queue.as_raw();
+    let image = image.as_raw();
+    clear_error();
     let mut event: cl_event = ptr::null_mut();
     let status: cl_int = clEnqueueFillImage(
         command_queue,
@@ -601,21 +663,25 @@ pub unsafe fn enqueue_fill_image(
     if CL_SUCCESS == status {
         Ok(event)
     } else {
-        Err(status)
+        Err(record_error!(status, clEnqueueFillImage))
     }
 }
 
 #[inline]
 pub unsafe fn enqueue_copy_image(
-    command_queue: cl_command_queue,
-    src_image: cl_mem,
-    dst_image: cl_mem,
+    command_queue: impl AsCommandQueue,
+    src_image: impl AsMem,
+    dst_image: impl AsMem,
     src_origin: *const size_t,
     dst_origin: *const size_t,
     region: *const size_t,
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
+    let command_queue = command_queue.as_raw();
+    let src_image = src_image.as_raw();
+    let dst_image = dst_image.as_raw();
+    clear_error();
     let mut event: cl_event = ptr::null_mut();
     let status: cl_int = clEnqueueCopyImage(
         command_queue,
@@ -631,21 +697,25 @@ pub unsafe fn enqueue_copy_image(
     if CL_SUCCESS == status {
         Ok(event)
     } else {
-        Err(status)
+        Err(record_error!(status, clEnqueueCopyImage))
     }
 }
 
 #[inline]
 pub unsafe fn enqueue_copy_image_to_buffer(
-    command_queue: cl_command_queue,
-    src_image: cl_mem,
-    dst_buffer: cl_mem,
+    command_queue: impl AsCommandQueue,
+    src_image: impl AsMem,
+    dst_buffer: impl AsMem,
     src_origin: *const size_t,
     region: *const size_t,
     dst_offset: size_t,
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
+    let command_queue = command_queue.as_raw();
+    let src_image = src_image.as_raw();
+    let dst_buffer = dst_buffer.as_raw();
+    clear_error();
     let mut event: cl_event = ptr::null_mut();
     let status: cl_int = clEnqueueCopyImageToBuffer(
         command_queue,
@@ -661,21 +731,29 @@ pub unsafe fn enqueue_copy_image_to_buffer(
     if CL_SUCCESS == status {
         Ok(event)
     } else {
-        Err(status)
+        Err(record_error!(
+            status,
+            clEnqueueCopyImageToBuffer,
+            dst_offset
+        ))
     }
 }
 
 #[inline]
 pub unsafe fn enqueue_copy_buffer_to_image(
-    command_queue: cl_command_queue,
-    src_buffer: cl_mem,
-    dst_image: cl_mem,
+    command_queue: impl AsCommandQueue,
+    src_buffer: impl AsMem,
+    dst_image: impl AsMem,
     src_offset: size_t,
     dst_origin: *const size_t,
     region: *const size_t,
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
+    let command_queue = command_queue.as_raw();
+    let src_buffer = src_buffer.as_raw();
+    let dst_image = dst_image.as_raw();
+    clear_error();
     let mut event: cl_event = ptr::null_mut();
     let status: cl_int = clEnqueueCopyBufferToImage(
         command_queue,
@@ -691,7 +769,11 @@ pub unsafe fn enqueue_copy_buffer_to_image(
     if CL_SUCCESS == status {
         Ok(event)
     } else {
-        Err(status)
+        Err(record_error!(
+            status,
+            clEnqueueCopyBufferToImage,
+            src_offset
+        ))
     }
 }
 
@@ -699,8 +781,8 @@ pub unsafe fn enqueue_copy_buffer_to_image(
 /// The buffer pointer is returned in the `buffer_ptr` mutable reference.
 #[inline]
 pub unsafe fn enqueue_map_buffer(
-    command_queue: cl_command_queue,
-    buffer: cl_mem,
+    command_queue: impl AsCommandQueue,
+    buffer: impl AsMem,
     blocking_map: cl_bool,
     map_flags: cl_map_flags,
     offset: size_t,
@@ -709,6 +791,9 @@ pub unsafe fn enqueue_map_buffer(
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
+    let command_queue = command_queue.as_raw();
+    let buffer = buffer.as_raw();
+    clear_error();
     let mut event: cl_event = ptr::null_mut();
     let mut status: cl_int = CL_INVALID_VALUE;
     *buffer_ptr = clEnqueueMapBuffer(
@@ -726,7 +811,13 @@ pub unsafe fn enqueue_map_buffer(
     if CL_SUCCESS == status {
         Ok(event)
     } else {
-        Err(status)
+        Err(record_error!(
+            status,
+            clEnqueueMapBuffer,
+            map_flags,
+            offset,
+            size
+        ))
     }
 }
 
@@ -734,8 +825,8 @@ pub unsafe fn enqueue_map_buffer(
 /// The image pointer is returned in the `image_ptr` mutable reference.
 #[inline]
 pub unsafe fn enqueue_map_image(
-    command_queue: cl_command_queue,
-    image: cl_mem,
+    command_queue: impl AsCommandQueue,
+    image: impl AsMem,
     blocking_map: cl_bool,
     map_flags: cl_map_flags,
     origin: *const size_t,
@@ -746,6 +837,9 @@ pub unsafe fn enqueue_map_image(
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<*mut c_void, cl_int> {
+    let command_queue = command_queue.as_raw();
+    let image = image.as_raw();
+    clear_error();
     let mut event: cl_event = ptr::null_mut();
     let mut status: cl_int = CL_INVALID_VALUE;
     *image_ptr = clEnqueueMapImage(
@@ -765,18 +859,21 @@ pub unsafe fn enqueue_map_image(
     if CL_SUCCESS == status {
         Ok(event)
     } else {
-        Err(status)
+        Err(record_error!(status, clEnqueueMapImage, map_flags))
     }
 }
 
 #[inline]
 pub unsafe fn enqueue_unmap_mem_object(
-    command_queue: cl_command_queue,
-    memobj: cl_mem,
+    command_queue: impl AsCommandQueue,
+    memobj: impl AsMem,
     mapped_ptr: *mut c_void,
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
+    let command_queue = command_queue.as_raw();
+    let memobj = memobj.as_raw();
+    clear_error();
     let mut event: cl_event = ptr::null_mut();
     let status: cl_int = clEnqueueUnmapMemObject(
         command_queue,
@@ -789,20 +886,22 @@ pub unsafe fn enqueue_unmap_mem_object(
     if CL_SUCCESS == status {
         Ok(event)
     } else {
-        Err(status)
+        Err(record_error!(status, clEnqueueUnmapMemObject))
     }
 }
 
 #[cfg(feature = "CL_VERSION_1_2")]
 #[inline]
 pub unsafe fn enqueue_migrate_mem_object(
-    command_queue: cl_command_queue,
+    command_queue: impl AsCommandQueue,
     num_mem_objects: cl_uint,
     mem_objects: *const cl_mem,
     flags: cl_mem_migration_flags,
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
+    let command_queue = command_queue.as_raw();
+    clear_error();
     let mut event: cl_event = ptr::null_mut();
     let status: cl_int = clEnqueueMigrateMemObjects(
         command_queue,
@@ -816,14 +915,14 @@ pub unsafe fn enqueue_migrate_mem_object(
     if CL_SUCCESS == status {
         Ok(event)
     } else {
-        Err(status)
+        Err(record_error!(status, clEnqueueMigrateMemObjects, flags))
     }
 }
 
 #[inline]
 pub unsafe fn enqueue_nd_range_kernel(
-    command_queue: cl_command_queue,
-    kernel: cl_kernel,
+    command_queue: impl AsCommandQueue,
+    kernel: impl AsKernel,
     work_dim: cl_uint,
     global_work_offset: *const size_t,
     global_work_dims: *const size_t,
@@ -831,6 +930,9 @@ pub unsafe fn enqueue_nd_range_kernel(
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
+    let command_queue = command_queue.as_raw();
+    let kernel = kernel.as_raw();
+    clear_error();
     let mut event: cl_event = ptr::null_mut();
     let status: cl_int = clEnqueueNDRangeKernel(
         command_queue,
@@ -846,7 +948,7 @@ pub unsafe fn enqueue_nd_range_kernel(
     if CL_SUCCESS == status {
         Ok(event)
     } else {
-        Err(status)
+        Err(record_error!(status, clEnqueueNDRangeKernel, work_dim))
     }
 }
 
@@ -865,11 +967,14 @@ pub unsafe fn enqueue_nd_range_kernel(
 )]
 #[inline]
 pub unsafe fn enqueue_task(
-    command_queue: cl_command_queue,
-    kernel: cl_kernel,
+    command_queue: impl AsCommandQueue,
+    kernel: impl AsKernel,
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
+    let command_queue = command_queue.as_raw();
+    let kernel = kernel.as_raw();
+    clear_error();
     let mut event: cl_event = ptr::null_mut();
     let status: cl_int = clEnqueueTask(
         command_queue,
@@ -881,13 +986,13 @@ pub unsafe fn enqueue_task(
     if CL_SUCCESS == status {
         Ok(event)
     } else {
-        Err(status)
+        Err(record_error!(status, clEnqueueTask))
     }
 }
 
 #[inline]
 pub unsafe fn enqueue_native_kernel(
-    command_queue: cl_command_queue,
+    command_queue: impl AsCommandQueue,
     user_func: Option<unsafe extern "C" fn(*mut c_void)>,
     args: *mut c_void,
     cb_args: size_t,
@@ -897,6 +1002,8 @@ pub unsafe fn enqueue_native_kernel(
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
+    let command_queue = command_queue.as_raw();
+    clear_error();
     let mut event: cl_event = ptr::null_mut();
     let status: cl_int = clEnqueueNativeKernel(
         command_queue,
@@ -913,17 +1020,19 @@ pub unsafe fn enqueue_native_kernel(
     if CL_SUCCESS == status {
         Ok(event)
     } else {
-        Err(status)
+        Err(record_error!(status, clEnqueueNativeKernel))
     }
 }
 
 #[cfg(feature = "CL_VERSION_1_2")]
 #[inline]
 pub unsafe fn enqueue_marker_with_wait_list(
-    command_queue: cl_command_queue,
+    command_queue: impl AsCommandQueue,
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
+    let command_queue = command_queue.as_raw();
+    clear_error();
     let mut event: cl_event = ptr::null_mut();
     let status: cl_int = clEnqueueMarkerWithWaitList(
         command_queue,
@@ -934,17 +1043,19 @@ pub unsafe fn enqueue_marker_with_wait_list(
     if CL_SUCCESS == status {
         Ok(event)
     } else {
-        Err(status)
+        Err(record_error!(status, clEnqueueMarkerWithWaitList))
     }
 }
 
 #[cfg(feature = "CL_VERSION_1_2")]
 #[inline]
 pub unsafe fn enqueue_barrier_with_wait_list(
-    command_queue: cl_command_queue,
+    command_queue: impl AsCommandQueue,
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
+    let command_queue = command_queue.as_raw();
+    clear_error();
     let mut event: cl_event = ptr::null_mut();
     let status: cl_int = clEnqueueBarrierWithWaitList(
         command_queue,
@@ -955,14 +1066,14 @@ pub unsafe fn enqueue_barrier_with_wait_list(
     if CL_SUCCESS == status {
         Ok(event)
     } else {
-        Err(status)
+        Err(record_error!(status, clEnqueueBarrierWithWaitList))
     }
 }
 
 #[cfg(feature = "CL_VERSION_2_0")]
 #[inline]
 pub unsafe fn enqueue_svm_free(
-    command_queue: cl_command_queue,
+    command_queue: impl AsCommandQueue,
     num_svm_pointers: cl_uint,
     svm_pointers: *const *const c_void,
     pfn_free_func: Option<
@@ -977,6 +1088,8 @@ pub unsafe fn enqueue_svm_free(
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
+    let command_queue = command_queue.as_raw();
+    clear_error();
     let mut event: cl_event = ptr::null_mut();
     let status: cl_int = clEnqueueSVMFree(
         command_queue,
@@ -991,14 +1104,14 @@ pub unsafe fn enqueue_svm_free(
     if CL_SUCCESS == status {
         Ok(event)
     } else {
-        Err(status)
+        Err(record_error!(status, clEnqueueSVMFree))
     }
 }
 
 #[cfg(feature = "CL_VERSION_2_0")]
 #[inline]
 pub unsafe fn enqueue_svm_mem_cpy(
-    command_queue: cl_command_queue,
+    command_queue: impl AsCommandQueue,
     blocking_copy: cl_bool,
     dst_ptr: *mut c_void,
     src_ptr: *const c_void,
@@ -1006,6 +1119,8 @@ pub unsafe fn enqueue_svm_mem_cpy(
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
+    let command_queue = command_queue.as_raw();
+    clear_error();
     let mut event: cl_event = ptr::null_mut();
     let status: cl_int = clEnqueueSVMMemcpy(
         command_queue,
@@ -1020,14 +1135,14 @@ pub unsafe fn enqueue_svm_mem_cpy(
     if CL_SUCCESS == status {
         Ok(event)
     } else {
-        Err(status)
+        Err(record_error!(status, clEnqueueSVMMemcpy, size))
     }
 }
 
 #[cfg(feature = "CL_VERSION_2_0")]
 #[inline]
 pub unsafe fn enqueue_svm_mem_fill(
-    command_queue: cl_command_queue,
+    command_queue: impl AsCommandQueue,
     svm_ptr: *mut c_void,
     pattern: *const c_void,
     pattern_size: size_t,
@@ -1035,6 +1150,8 @@ pub unsafe fn enqueue_svm_mem_fill(
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
+    let command_queue = command_queue.as_raw();
+    clear_error();
     let mut event: cl_event = ptr::null_mut();
     let status: cl_int = clEnqueueSVMMemFill(
         command_queue,
@@ -1049,14 +1166,19 @@ pub unsafe fn enqueue_svm_mem_fill(
     if CL_SUCCESS == status {
         Ok(event)
     } else {
-        Err(status)
+        Err(record_error!(
+            status,
+            clEnqueueSVMMemFill,
+            size,
+            pattern_size
+        ))
     }
 }
 
 #[cfg(feature = "CL_VERSION_2_0")]
 #[inline]
 pub unsafe fn enqueue_svm_map(
-    command_queue: cl_command_queue,
+    command_queue: impl AsCommandQueue,
     blocking_map: cl_bool,
     flags: cl_map_flags,
     svm_ptr: *mut c_void,
@@ -1064,6 +1186,8 @@ pub unsafe fn enqueue_svm_map(
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
+    let command_queue = command_queue.as_raw();
+    clear_error();
     let mut event: cl_event = ptr::null_mut();
     let status: cl_int = clEnqueueSVMMap(
         command_queue,
@@ -1078,18 +1202,20 @@ pub unsafe fn enqueue_svm_map(
     if CL_SUCCESS == status {
         Ok(event)
     } else {
-        Err(status)
+        Err(record_error!(status, clEnqueueSVMMap, flags, size))
     }
 }
 
 #[cfg(feature = "CL_VERSION_2_0")]
 #[inline]
 pub unsafe fn enqueue_svm_unmap(
-    command_queue: cl_command_queue,
+    command_queue: impl AsCommandQueue,
     svm_ptr: *mut c_void,
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
+    let command_queue = command_queue.as_raw();
+    clear_error();
     let mut event: cl_event = ptr::null_mut();
     let status: cl_int = clEnqueueSVMUnmap(
         command_queue,
@@ -1101,14 +1227,14 @@ pub unsafe fn enqueue_svm_unmap(
     if CL_SUCCESS == status {
         Ok(event)
     } else {
-        Err(status)
+        Err(record_error!(status, clEnqueueSVMUnmap))
     }
 }
 
 #[cfg(feature = "CL_VERSION_2_1")]
 #[inline]
 pub unsafe fn enqueue_svm_migrate_mem(
-    command_queue: cl_command_queue,
+    command_queue: impl AsCommandQueue,
     num_svm_pointers: cl_uint,
     svm_pointers: *const *const c_void,
     sizes: *const size_t,
@@ -1116,6 +1242,8 @@ pub unsafe fn enqueue_svm_migrate_mem(
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
+    let command_queue = command_queue.as_raw();
+    clear_error();
     let mut event: cl_event = ptr::null_mut();
     let status: cl_int = clEnqueueSVMMigrateMem(
         command_queue,
@@ -1130,7 +1258,7 @@ pub unsafe fn enqueue_svm_migrate_mem(
     if CL_SUCCESS == status {
         Ok(event)
     } else {
-        Err(status)
+        Err(record_error!(status, clEnqueueSVMMigrateMem, flags))
     }
 }
 
@@ -24,14 +24,16 @@ pub use opencl_sys::{
     CL_SUCCESS,
 };
 
-use opencl_sys::{
+use super::api_error::{clear_error, record_error};
+use super::ffi::{
     clCreateContext, clCreateContextFromType, clGetContextInfo, clReleaseContext, clRetainContext,
 };
 
 #[cfg(feature = "CL_VERSION_3_0")]
-use opencl_sys::clSetContextDestructorCallback;
+use super::ffi::clSetContextDestructorCallback;
 
 use super::info_type::InfoType;
+use super::owned::AsContext;
 use super::{api_info_size, api_info_value, api_info_vector};
 use libc::{c_char, c_void, intptr_t, size_t};
 use std::mem;
@@ -56,6 +58,7 @@ pub fn create_context(
     pfn_notify: Option<unsafe extern "C" fn(*const c_char, *const c_void, size_t, *mut c_void)>,
     user_data: *mut c_void,
 ) -> Result<cl_context, cl_int> {
+    clear_error();
     let mut status: cl_int = CL_INVALID_VALUE;
     let context = unsafe {
         clCreateContext(
@@ -70,7 +73,7 @@ pub fn create_context(
     if CL_SUCCESS == status {
         Ok(context)
     } else {
-        Err(status)
+        Err(record_error!(status, clCreateContext))
     }
 }
 
@@ -93,6 +96,7 @@ pub fn create_context_from_type(
     pfn_notify: Option<unsafe extern "C" fn(*const c_char, *const c_void, size_t, *mut c_void)>,
     user_data: *mut c_void,
 ) -> Result<cl_context, cl_int> {
+    clear_error();
     let mut status: cl_int = CL_INVALID_VALUE;
     let context = unsafe {
         clCreateContextFromType(properties, device_type, pfn_notify, user_data, &mut status)
@@ -100,7 +104,7 @@ pub fn create_context_from_type(
     if CL_SUCCESS == status {
         Ok(context)
     } else {
-        Err(status)
+        Err(record_error!(status, clCreateContextFromType, device_type))
     }
 }
 
@@ -116,11 +120,12 @@ pub fn create_context_from_type(
 /// This function is unsafe because it changes the `OpenCL` object reference count.
 #[inline]
 pub unsafe fn retain_context(context: cl_context) -> Result<(), cl_int> {
+    clear_error();
     let status: cl_int = clRetainContext(context);
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(status, clRetainContext))
     }
 }
 
@@ -136,20 +141,23 @@ pub unsafe fn retain_context(context: cl_context) -> Result<(), cl_int> {
 /// This function is unsafe because it changes the `OpenCL` object reference count.
 #[inline]
 pub unsafe fn release_context(context: cl_context) -> Result<(), cl_int> {
+    clear_error();
     let status: cl_int = clReleaseContext(context);
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(status, clReleaseContext))
     }
 }
 
 /// Get data about an `OpenCL` context.
 /// Calls `clGetContextInfo` to get the desired data about the context.
 pub fn get_context_data(
-    context: cl_context,
+    context: impl AsContext,
     param_name: cl_context_info,
 ) -> Result<Vec<u8>, cl_int> {
+    let context = context.as_raw();
+    clear_error();
     api_info_size!(get_size, clGetContextInfo);
     let size = get_size(context, param_name)?;
     api_info_vector!(get_vector, u8, clGetContextInfo);
@@ -166,9 +174,11 @@ pub fn get_context_data(
 /// returns a Result containing the desired information in an `InfoType` enum
 /// or the error code from the `OpenCL` C API function.
 pub fn get_context_info(
-    context: cl_context,
+    context: impl AsContext,
     param_name: cl_context_info,
 ) -> Result<InfoType, cl_int> {
+    let context = context.as_raw();
+    clear_error();
     api_info_size!(get_size, clGetContextInfo);
 
     match param_name {
@@ -199,15 +209,17 @@ pub fn get_context_info(
 #[cfg(feature = "CL_VERSION_3_0")]
 #[inline]
 pub fn set_context_destructor_callback(
-    context: cl_context,
+    context: impl AsContext,
     pfn_notify: Option<unsafe extern "C" fn(cl_context, *mut c_void)>,
     user_data: *mut c_void,
 ) -> Result<(), cl_int> {
+    let context = context.as_raw();
+    clear_error();
     let status: cl_int = unsafe { clSetContextDestructorCallback(context, pfn_notify, user_data) };
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(status, clSetContextDestructorCallback))
     }
 }
 // #endif
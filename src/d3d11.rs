@@ -18,11 +18,20 @@
 
 #![allow(clippy::missing_safety_doc)]
 
+#[cfg(all(
+    feature = "cl_khr_d3d11_sharing",
+    any(feature = "dynamic", feature = "mock")
+))]
+pub use super::ffi::clGetSupportedD3D11TextureFormatsINTEL;
 pub use opencl_sys::cl_d3d11::*;
 pub use opencl_sys::{
     cl_context, cl_int, cl_mem_flags, cl_mem_object_type, cl_uint, CL_INVALID_VALUE, CL_SUCCESS,
 };
 
+#[allow(unused_imports)]
+use super::api_error::{clear_error, record_error};
+#[allow(unused_imports)]
+use super::owned::AsContext;
 #[allow(unused_imports)]
 use libc::c_void;
 #[allow(unused_imports)]
@@ -30,11 +39,13 @@ use std::ptr;
 
 #[cfg(feature = "cl_khr_d3d11_sharing")]
 pub unsafe fn get_supported_d3d11_texture_formats_intel(
-    context: cl_context,
+    context: impl AsContext,
     flags: cl_mem_flags,
     image_type: cl_mem_object_type,
     plane: cl_uint,
 ) -> Result<Vec<cl_uint>, cl_int> {
+    let context = context.as_raw();
+    clear_error();
     let mut count: cl_uint = 0;
     let status: cl_int = clGetSupportedD3D11TextureFormatsINTEL(
         context,
@@ -46,7 +57,13 @@ pub unsafe fn get_supported_d3d11_texture_formats_intel(
         &mut count,
     );
     if CL_SUCCESS != status {
-        Err(status)
+        Err(record_error!(
+            status,
+            clGetSupportedD3D11TextureFormatsINTEL,
+            flags,
+            image_type,
+            plane
+        ))
     } else if 0 < count {
         // Get the d3d11_formats.
         let len = count as usize;
@@ -63,7 +80,13 @@ pub unsafe fn get_supported_d3d11_texture_formats_intel(
         if CL_SUCCESS == status {
             Ok(ids)
         } else {
-            Err(status)
+            Err(record_error!(
+                status,
+                clGetSupportedD3D11TextureFormatsINTEL,
+                flags,
+                image_type,
+                plane
+            ))
         }
     } else {
         Ok(Vec::default())
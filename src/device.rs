@@ -110,14 +110,18 @@ pub use opencl_sys::{
     CL_VERSION_MINOR_MASK, CL_VERSION_PATCH_BITS, CL_VERSION_PATCH_MASK,
 };
 
-use opencl_sys::{
+use super::api_error::{clear_error, record_error};
+use super::ffi::{
     clCreateSubDevices, clGetDeviceIDs, clGetDeviceInfo, clReleaseDevice, clRetainDevice,
 };
 
 #[cfg(feature = "CL_VERSION_2_1")]
-use opencl_sys::{clGetDeviceAndHostTimer, clGetHostTimer, clSetDefaultDeviceCommandQueue};
+use super::ffi::{clGetDeviceAndHostTimer, clGetHostTimer, clSetDefaultDeviceCommandQueue};
 
 use super::info_type::InfoType;
+use super::owned::AsDevice;
+#[cfg(feature = "CL_VERSION_2_1")]
+use super::owned::{AsCommandQueue, AsContext};
 use super::{api_info_size, api_info_value, api_info_vector};
 use libc::{c_void, intptr_t, size_t};
 use std::mem;
@@ -150,13 +154,14 @@ pub fn get_device_ids(
     platform: cl_platform_id,
     device_type: cl_device_type,
 ) -> Result<Vec<cl_device_id>, cl_int> {
+    clear_error();
     // Get the number of devices of device_type
     let mut count: cl_uint = 0;
     let mut status =
         unsafe { clGetDeviceIDs(platform, device_type, 0, ptr::null_mut(), &mut count) };
 
     if (CL_SUCCESS != status) && (CL_DEVICE_NOT_FOUND != status) {
-        Err(status)
+        Err(record_error!(status, clGetDeviceIDs, device_type))
     } else if 0 < count {
         // Get the device ids.
         let len = count as size_t;
@@ -175,7 +180,7 @@ pub fn get_device_ids(
         if CL_SUCCESS == status {
             Ok(ids)
         } else {
-            Err(status)
+            Err(record_error!(status, clGetDeviceIDs, device_type))
         }
     } else {
         Ok(Vec::default())
@@ -185,9 +190,11 @@ pub fn get_device_ids(
 /// Get data about an `OpenCL` device.
 /// Calls clGetDeviceInfo to get the desired data about the device.
 pub fn get_device_data(
-    device: cl_device_id,
+    device: impl AsDevice,
     param_name: cl_device_info,
 ) -> Result<Vec<u8>, cl_int> {
+    let device = device.as_raw();
+    clear_error();
     api_info_size!(get_size, clGetDeviceInfo);
     let size = get_size(device, param_name)?;
     api_info_vector!(get_vector, u8, clGetDeviceInfo);
@@ -237,9 +244,11 @@ pub fn get_device_data(
 /// returns a Result containing the desired information in an `InfoType` enum
 /// or the error code from the `OpenCL` C API function.
 pub fn get_device_info(
-    device: cl_device_id,
+    device: impl AsDevice,
     param_name: cl_device_info,
 ) -> Result<InfoType, cl_int> {
+    let device = device.as_raw();
+    clear_error();
     api_info_size!(get_size, clGetDeviceInfo);
 
     match param_name {
@@ -451,7 +460,7 @@ pub fn get_device_info(
             if CL_SUCCESS == status {
                 Ok(InfoType::Uuid(value))
             } else {
-                Err(status)
+                Err(record_error!(status, clGetDeviceInfo, param_name))
             }
         }
 
@@ -469,7 +478,7 @@ pub fn get_device_info(
             if CL_SUCCESS == status {
                 Ok(InfoType::Luid(value))
             } else {
-                Err(status)
+                Err(record_error!(status, clGetDeviceInfo, param_name))
             }
         }
 
@@ -564,6 +573,7 @@ fn count_sub_devices(
     in_device: cl_device_id,
     properties: &[cl_device_partition_property],
 ) -> Result<cl_uint, cl_int> {
+    clear_error();
     let mut count: cl_uint = 0;
     let status: cl_int = unsafe {
         clCreateSubDevices(
@@ -577,7 +587,7 @@ fn count_sub_devices(
     if CL_SUCCESS == status {
         Ok(count)
     } else {
-        Err(status)
+        Err(record_error!(status, clCreateSubDevices))
     }
 }
 
@@ -594,9 +604,11 @@ fn count_sub_devices(
 #[inline]
 #[allow(clippy::cast_possible_truncation)]
 pub fn create_sub_devices(
-    in_device: cl_device_id,
+    in_device: impl AsDevice,
     properties: &[cl_device_partition_property],
 ) -> Result<Vec<cl_device_id>, cl_int> {
+    let in_device = in_device.as_raw();
+    clear_error();
     // get the number of partitions
     let num_devices: cl_uint = count_sub_devices(in_device, properties)?;
 
@@ -616,7 +628,7 @@ pub fn create_sub_devices(
     if CL_SUCCESS == status {
         Ok(ids)
     } else {
-        Err(status)
+        Err(record_error!(status, clCreateSubDevices))
     }
 }
 
@@ -634,11 +646,12 @@ pub fn create_sub_devices(
 #[cfg(feature = "CL_VERSION_1_2")]
 #[inline]
 pub unsafe fn retain_device(device: cl_device_id) -> Result<(), cl_int> {
+    clear_error();
     let status: cl_int = clRetainDevice(device);
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(status, clRetainDevice))
     }
 }
 
@@ -656,11 +669,12 @@ pub unsafe fn retain_device(device: cl_device_id) -> Result<(), cl_int> {
 #[cfg(feature = "CL_VERSION_1_2")]
 #[inline]
 pub unsafe fn release_device(device: cl_device_id) -> Result<(), cl_int> {
+    clear_error();
     let status: cl_int = clReleaseDevice(device);
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(status, clReleaseDevice))
     }
 }
 
@@ -677,15 +691,19 @@ pub unsafe fn release_device(device: cl_device_id) -> Result<(), cl_int> {
 #[cfg(feature = "CL_VERSION_2_1")]
 #[inline]
 pub fn set_default_device_command_queue(
-    context: cl_context,
-    device: cl_device_id,
-    command_queue: cl_command_queue,
+    context: impl AsContext,
+    device: impl AsDevice,
+    command_queue: impl AsCommandQueue,
 ) -> Result<(), cl_int> {
+    let context = context.as_raw();
+    let device = device.as_raw();
+    let command_queue = command_queue.as_raw();
+    clear_error();
     let status: cl_int = unsafe { clSetDefaultDeviceCommandQueue(context, device, command_queue) };
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(status, clSetDefaultDeviceCommandQueue))
     }
 }
 
@@ -699,7 +717,9 @@ pub fn set_default_device_command_queue(
 /// or the error code from the `OpenCL` C API function.
 #[cfg(feature = "CL_VERSION_2_1")]
 #[inline]
-pub fn get_device_and_host_timer(device: cl_device_id) -> Result<[cl_ulong; 2], cl_int> {
+pub fn get_device_and_host_timer(device: impl AsDevice) -> Result<[cl_ulong; 2], cl_int> {
+    let device = device.as_raw();
+    clear_error();
     let mut device_timestamp: cl_ulong = 0;
     let mut host_timestamp: cl_ulong = 0;
     let status: cl_int =
@@ -707,7 +727,7 @@ pub fn get_device_and_host_timer(device: cl_device_id) -> Result<[cl_ulong; 2],
     if CL_SUCCESS == status {
         Ok([device_timestamp, host_timestamp])
     } else {
-        Err(status)
+        Err(record_error!(status, clGetDeviceAndHostTimer))
     }
 }
 
@@ -721,13 +741,15 @@ pub fn get_device_and_host_timer(device: cl_device_id) -> Result<[cl_ulong; 2],
 /// or the error code from the `OpenCL` C API function.
 #[cfg(feature = "CL_VERSION_2_1")]
 #[inline]
-pub fn get_host_timer(device: cl_device_id) -> Result<cl_ulong, cl_int> {
+pub fn get_host_timer(device: impl AsDevice) -> Result<cl_ulong, cl_int> {
+    let device = device.as_raw();
+    clear_error();
     let mut host_timestamp: cl_ulong = 0;
     let status: cl_int = unsafe { clGetHostTimer(device, &mut host_timestamp) };
     if CL_SUCCESS == status {
         Ok(host_timestamp)
     } else {
-        Err(status)
+        Err(record_error!(status, clGetHostTimer))
     }
 }
 // #endif
@@ -793,6 +815,8 @@ mod tests {
     use super::*;
     use crate::error_codes::ClError;
     use crate::platform::get_platform_ids;
+    #[cfg(feature = "CL_VERSION_3_0")]
+    use crate::platform::{get_platform_info, CL_PLATFORM_VERSION};
 
     #[test]
     fn test_get_platform_devices() {
@@ -1843,8 +1867,14 @@ mod tests {
     fn test_get_device_info_3_0() {
         let platform_ids = get_platform_ids().unwrap();
 
-        // Choose the platform with the most compliant GPU
-        let platform_id = platform_ids[1];
+        // Choose the platform with the most compliant GPU, i.e. OpenCL 3.0
+        let platform_id = *platform_ids
+            .iter()
+            .find(|platform_id| {
+                get_platform_info(**platform_id, CL_PLATFORM_VERSION)
+                    .is_ok_and(|version| String::from(version).starts_with("OpenCL 3"))
+            })
+            .unwrap();
 
         let device_ids = get_device_ids(platform_id, CL_DEVICE_TYPE_GPU).unwrap();
         println!("CL_DEVICE_TYPE_GPU count: {}", device_ids.len());
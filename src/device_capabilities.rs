@@ -0,0 +1,813 @@
+// Copyright (c) 2024 Via Technology Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A snapshot of the capabilities of an `OpenCL` device.
+//!
+//! [`get_device_capabilities`] queries every device information parameter in
+//! [`device_info`](super::device_info) and returns them in a
+//! [`DeviceCapabilities`] struct, e.g.:
+//!
+//! ```no_run
+//! use cl3::device::{get_device_ids, CL_DEVICE_TYPE_GPU};
+//! use cl3::device_capabilities::get_device_capabilities;
+//! use cl3::platform::get_platform_ids;
+//!
+//! let platform_ids = get_platform_ids().unwrap();
+//! let device_ids = get_device_ids(platform_ids[0], CL_DEVICE_TYPE_GPU).unwrap();
+//! let capabilities = get_device_capabilities(device_ids[0]).unwrap();
+//! println!("{:#?}", capabilities);
+//! ```
+//!
+//! The parameters that all `OpenCL` 1.2 devices support are plain fields.
+//! The parameters of later `OpenCL` versions and extensions are `Option`s,
+//! which are `None` when the device returns `CL_INVALID_VALUE` for them.
+//!
+//! The structs implement `serde::Serialize` and `serde::Deserialize`
+//! when the "serde" feature is enabled.
+
+#![allow(non_upper_case_globals, clippy::too_many_lines)]
+
+use super::api_error::clear_error;
+use super::device_info::{
+    AddressBits, AtomicFenceCapabilities, AtomicMemoryCapabilities, Available,
+    AvailableAsyncQueuesAmd, BoardNameAmd, BuiltInKernels, BuiltInKernelsWithVersion,
+    CommandBufferCapabilitiesKhr, CommandBufferRequiredQueuePropertiesKhr, CompilerAvailable,
+    ComputeCapabilityMajorNv, ComputeCapabilityMinorNv, DeviceEnqueueCapabilities, DeviceParam,
+    DeviceType, DeviceVersion, DoubleFpConfig, DriverUuidKhr, DriverVersion, EndianLittle,
+    ErrorCorrectionSupport, ExecutionCapabilities, Extensions, ExtensionsWithVersion,
+    ExternalMemoryImportHandleTypesKhr, FeatureCapabilitiesIntel, GenericAddressSpaceSupport,
+    GfxipMajorAmd, GfxipMinorAmd, GlobalFreeMemoryAmd, GlobalMemCacheSize, GlobalMemCacheType,
+    GlobalMemCachelineSize, GlobalMemChannelBankWidthAmd, GlobalMemChannelBanksAmd,
+    GlobalMemChannelsAmd, GlobalMemSize, GlobalVariablePreferredTotalSize, GpuOverlapNv,
+    HalfFpConfig, HostUnifiedMemory, IdIntel, IlVersion, IlsWithVersion, Image2dMaxHeight,
+    Image2dMaxWidth, Image3dMaxDepth, Image3dMaxHeight, Image3dMaxWidth, ImageBaseAddressAlignment,
+    ImageMaxArraySize, ImageMaxBufferSize, ImagePitchAlignment, ImageSupport,
+    IntegerDotProductAccelerationProperties4x8BitPackedKhr,
+    IntegerDotProductAccelerationProperties8BitKhr, IntegerDotProductCapabilitiesKhr,
+    IntegratedMemoryNv, IpVersionIntel, KernelExecTimeoutNv, LatestConformanceVersionPassed,
+    LinkerAvailable, LocalMemBanksAmd, LocalMemSize, LocalMemSizePerComputeUnitAmd, LocalMemType,
+    LuidKhr, LuidValidKhr, MaxClockFrequency, MaxComputeUnits, MaxConstantArgs,
+    MaxConstantBufferSize, MaxGlobalVariableSize, MaxMemAllocSize, MaxNumSubGroups,
+    MaxOnDeviceEvents, MaxOnDeviceQueues, MaxParameterSize, MaxPipeArgs, MaxReadImageArgs,
+    MaxReadWriteImageArgs, MaxSamplers, MaxWorkGroupSize, MaxWorkGroupSizeAmd,
+    MaxWorkItemDimensions, MaxWorkItemSizes, MaxWriteImageArgs, MemBaseAddrAlign,
+    MinDataTypeAlignSize, Name, NativeVectorWidthChar, NativeVectorWidthDouble,
+    NativeVectorWidthFloat, NativeVectorWidthHalf, NativeVectorWidthInt, NativeVectorWidthLong,
+    NativeVectorWidthShort, NodeMaskKhr, NonUniformWorkGroupSupport, NumEusPerSubSliceIntel,
+    NumSlicesIntel, NumSubSlicesPerSliceIntel, NumThreadsPerEuIntel, NumericVersion,
+    OpenClCAllVersions, OpenClCFeatures, OpenClCVersion, ParentDevice, PartitionAffinityDomain,
+    PartitionMaxSubDevices, PartitionProperties, PartitionType, PciBusIdNv, PciBusInfoKhr,
+    PciSlotIdNv, PcieIdAmd, PipeMaxActiveReservations, PipeMaxPacketSize, PipeSupport, Platform,
+    PreferredConstantBufferSizeAmd, PreferredGlobalAtomicAlignment, PreferredInteropUserSync,
+    PreferredLocalAtomicAlignment, PreferredPlatformAtomicAlignment, PreferredVectorWidthChar,
+    PreferredVectorWidthDouble, PreferredVectorWidthFloat, PreferredVectorWidthHalf,
+    PreferredVectorWidthInt, PreferredVectorWidthLong, PreferredVectorWidthShort,
+    PreferredWorkGroupSizeAmd, PreferredWorkGroupSizeMultiple, PrintfBufferSize, Profile,
+    ProfilingTimerOffsetAmd, ProfilingTimerResolution, QueueOnDeviceMaxSize,
+    QueueOnDevicePreferredSize, QueueOnDeviceProperties, QueueOnHostProperties, ReferenceCount,
+    RegistersPerBlockNv, SemaphoreExportHandleTypesKhr, SemaphoreImportHandleTypesKhr,
+    SemaphoreTypesKhr, SimdInstructionWidthAmd, SimdPerComputeUnitAmd, SimdWidthAmd,
+    SingleFpConfig, SubGroupIndependentForwardProgress, SvmCapabilities, ThreadTraceSupportedAmd,
+    TopologyAmd, UuidKhr, Vendor, VendorId, WarpSizeNv, WavefrontWidthAmd,
+    WorkGroupCollectiveFunctionsSupport,
+};
+use super::owned::AsDevice;
+use libc::{intptr_t, size_t};
+use opencl_sys::{
+    cl_amd_device_topology, cl_bool, cl_command_queue_properties, cl_device_affinity_domain,
+    cl_device_atomic_capabilities, cl_device_command_buffer_capabilities_khr,
+    cl_device_device_enqueue_capabilities, cl_device_exec_capabilities,
+    cl_device_feature_capabilities_intel, cl_device_fp_config, cl_device_id,
+    cl_device_integer_dot_product_acceleration_properties_khr,
+    cl_device_integer_dot_product_capabilities_khr, cl_device_local_mem_type,
+    cl_device_mem_cache_type, cl_device_partition_property, cl_device_pci_bus_info_khr,
+    cl_device_svm_capabilities, cl_device_type, cl_external_memory_handle_type_khr,
+    cl_external_semaphore_handle_type_khr, cl_int, cl_name_version, cl_semaphore_type_khr,
+    cl_uchar, cl_uint, cl_ulong, cl_version, cl_version_khr, CL_FALSE, CL_INVALID_VALUE,
+    CL_LUID_SIZE_KHR, CL_UUID_SIZE_KHR,
+};
+
+/// The name and version of an IL, built-in kernel, extension or `OpenCL` C
+/// feature, see `cl_name_version`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NameVersion {
+    pub version: cl_version,
+    pub name: String,
+}
+
+impl From<cl_name_version> for NameVersion {
+    fn from(value: cl_name_version) -> Self {
+        let length = value
+            .name
+            .iter()
+            .position(|&c| 0 == c)
+            .unwrap_or(value.name.len());
+        Self {
+            version: value.version,
+            name: String::from_utf8_lossy(&value.name[..length]).into_owned(),
+        }
+    }
+}
+
+/// The PCI bus information of a device, see `cl_device_pci_bus_info_khr`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PciBusInfo {
+    pub domain: cl_uint,
+    pub bus: cl_uint,
+    pub device: cl_uint,
+    pub function: cl_uint,
+}
+
+impl From<cl_device_pci_bus_info_khr> for PciBusInfo {
+    fn from(value: cl_device_pci_bus_info_khr) -> Self {
+        Self {
+            domain: value.pci_domain,
+            bus: value.pci_bus,
+            device: value.pci_device,
+            function: value.pci_function,
+        }
+    }
+}
+
+/// The PCI topology of an AMD device, see `cl_amd_device_topology`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AmdDeviceTopology {
+    pub bus: u8,
+    pub device: u8,
+    pub function: u8,
+}
+
+impl From<cl_amd_device_topology> for AmdDeviceTopology {
+    fn from(value: cl_amd_device_topology) -> Self {
+        Self {
+            bus: value.bus,
+            device: value.device,
+            function: value.function,
+        }
+    }
+}
+
+/// The integer dot product instructions that a device accelerates,
+/// see `cl_device_integer_dot_product_acceleration_properties_khr`.
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IntegerDotProductAccelerationProperties {
+    pub signed_accelerated: bool,
+    pub unsigned_accelerated: bool,
+    pub mixed_signedness_accelerated: bool,
+    pub accumulating_saturating_signed_accelerated: bool,
+    pub accumulating_saturating_unsigned_accelerated: bool,
+    pub accumulating_saturating_mixed_signedness_accelerated: bool,
+}
+
+impl From<cl_device_integer_dot_product_acceleration_properties_khr>
+    for IntegerDotProductAccelerationProperties
+{
+    fn from(value: cl_device_integer_dot_product_acceleration_properties_khr) -> Self {
+        let is_true = |value: cl_bool| CL_FALSE != value;
+        Self {
+            signed_accelerated: is_true(value.signed_accelerated),
+            unsigned_accelerated: is_true(value.unsigned_accelerated),
+            mixed_signedness_accelerated: is_true(value.mixed_signedness_accelerated),
+            accumulating_saturating_signed_accelerated: is_true(
+                value.accumulating_saturating_signed_accelerated,
+            ),
+            accumulating_saturating_unsigned_accelerated: is_true(
+                value.accumulating_saturating_unsigned_accelerated,
+            ),
+            accumulating_saturating_mixed_signedness_accelerated: is_true(
+                value.accumulating_saturating_mixed_signedness_accelerated,
+            ),
+        }
+    }
+}
+
+/// The capabilities of an `OpenCL` device.  
+/// The `platform` and `parent_device` handles are stored as integer values,
+/// they are only valid in the process that queried them.
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DeviceCapabilities {
+    /// `CL_DEVICE_TYPE`
+    pub device_type: cl_device_type,
+    /// `CL_DEVICE_VENDOR_ID`
+    pub vendor_id: cl_uint,
+    /// `CL_DEVICE_MAX_COMPUTE_UNITS`
+    pub max_compute_units: cl_uint,
+    /// `CL_DEVICE_MAX_WORK_ITEM_DIMENSIONS`
+    pub max_work_item_dimensions: cl_uint,
+    /// `CL_DEVICE_MAX_WORK_GROUP_SIZE`
+    pub max_work_group_size: size_t,
+    /// `CL_DEVICE_MAX_WORK_ITEM_SIZES`
+    pub max_work_item_sizes: Vec<size_t>,
+    /// `CL_DEVICE_PREFERRED_VECTOR_WIDTH_CHAR`
+    pub preferred_vector_width_char: cl_uint,
+    /// `CL_DEVICE_PREFERRED_VECTOR_WIDTH_SHORT`
+    pub preferred_vector_width_short: cl_uint,
+    /// `CL_DEVICE_PREFERRED_VECTOR_WIDTH_INT`
+    pub preferred_vector_width_int: cl_uint,
+    /// `CL_DEVICE_PREFERRED_VECTOR_WIDTH_LONG`
+    pub preferred_vector_width_long: cl_uint,
+    /// `CL_DEVICE_PREFERRED_VECTOR_WIDTH_FLOAT`
+    pub preferred_vector_width_float: cl_uint,
+    /// `CL_DEVICE_PREFERRED_VECTOR_WIDTH_DOUBLE`
+    pub preferred_vector_width_double: cl_uint,
+    /// `CL_DEVICE_PREFERRED_VECTOR_WIDTH_HALF`
+    pub preferred_vector_width_half: cl_uint,
+    /// `CL_DEVICE_NATIVE_VECTOR_WIDTH_CHAR`
+    pub native_vector_width_char: cl_uint,
+    /// `CL_DEVICE_NATIVE_VECTOR_WIDTH_SHORT`
+    pub native_vector_width_short: cl_uint,
+    /// `CL_DEVICE_NATIVE_VECTOR_WIDTH_INT`
+    pub native_vector_width_int: cl_uint,
+    /// `CL_DEVICE_NATIVE_VECTOR_WIDTH_LONG`
+    pub native_vector_width_long: cl_uint,
+    /// `CL_DEVICE_NATIVE_VECTOR_WIDTH_FLOAT`
+    pub native_vector_width_float: cl_uint,
+    /// `CL_DEVICE_NATIVE_VECTOR_WIDTH_DOUBLE`
+    pub native_vector_width_double: cl_uint,
+    /// `CL_DEVICE_NATIVE_VECTOR_WIDTH_HALF`
+    pub native_vector_width_half: cl_uint,
+    /// `CL_DEVICE_MAX_CLOCK_FREQUENCY`
+    pub max_clock_frequency: cl_uint,
+    /// `CL_DEVICE_ADDRESS_BITS`
+    pub address_bits: cl_uint,
+    /// `CL_DEVICE_MAX_MEM_ALLOC_SIZE`
+    pub max_mem_alloc_size: cl_ulong,
+    /// `CL_DEVICE_IMAGE_SUPPORT`
+    pub image_support: bool,
+    /// `CL_DEVICE_MAX_READ_IMAGE_ARGS`
+    pub max_read_image_args: cl_uint,
+    /// `CL_DEVICE_MAX_WRITE_IMAGE_ARGS`
+    pub max_write_image_args: cl_uint,
+    /// `CL_DEVICE_MAX_READ_WRITE_IMAGE_ARGS`
+    pub max_read_write_image_args: Option<cl_uint>,
+    /// `CL_DEVICE_IL_VERSION`
+    pub il_version: Option<String>,
+    /// `CL_DEVICE_ILS_WITH_VERSION`
+    pub ils_with_version: Option<Vec<NameVersion>>,
+    /// `CL_DEVICE_IMAGE2D_MAX_WIDTH`
+    pub image2d_max_width: size_t,
+    /// `CL_DEVICE_IMAGE2D_MAX_HEIGHT`
+    pub image2d_max_height: size_t,
+    /// `CL_DEVICE_IMAGE3D_MAX_WIDTH`
+    pub image3d_max_width: size_t,
+    /// `CL_DEVICE_IMAGE3D_MAX_HEIGHT`
+    pub image3d_max_height: size_t,
+    /// `CL_DEVICE_IMAGE3D_MAX_DEPTH`
+    pub image3d_max_depth: size_t,
+    /// `CL_DEVICE_IMAGE_MAX_BUFFER_SIZE`
+    pub image_max_buffer_size: size_t,
+    /// `CL_DEVICE_IMAGE_MAX_ARRAY_SIZE`
+    pub image_max_array_size: size_t,
+    /// `CL_DEVICE_MAX_SAMPLERS`
+    pub max_samplers: cl_uint,
+    /// `CL_DEVICE_IMAGE_PITCH_ALIGNMENT`
+    pub image_pitch_alignment: Option<cl_uint>,
+    /// `CL_DEVICE_IMAGE_BASE_ADDRESS_ALIGNMENT`
+    pub image_base_address_alignment: Option<cl_uint>,
+    /// `CL_DEVICE_MAX_PIPE_ARGS`
+    pub max_pipe_args: Option<cl_uint>,
+    /// `CL_DEVICE_PIPE_MAX_ACTIVE_RESERVATIONS`
+    pub pipe_max_active_reservations: Option<cl_uint>,
+    /// `CL_DEVICE_PIPE_MAX_PACKET_SIZE`
+    pub pipe_max_packet_size: Option<cl_uint>,
+    /// `CL_DEVICE_MAX_PARAMETER_SIZE`
+    pub max_parameter_size: size_t,
+    /// `CL_DEVICE_MEM_BASE_ADDR_ALIGN`
+    pub mem_base_addr_align: cl_uint,
+    /// `CL_DEVICE_MIN_DATA_TYPE_ALIGN_SIZE`
+    pub min_data_type_align_size: Option<cl_uint>,
+    /// `CL_DEVICE_SINGLE_FP_CONFIG`
+    pub single_fp_config: cl_device_fp_config,
+    /// `CL_DEVICE_DOUBLE_FP_CONFIG`
+    pub double_fp_config: cl_device_fp_config,
+    /// `CL_DEVICE_HALF_FP_CONFIG`
+    pub half_fp_config: Option<cl_device_fp_config>,
+    /// `CL_DEVICE_GLOBAL_MEM_CACHE_TYPE`
+    pub global_mem_cache_type: cl_device_mem_cache_type,
+    /// `CL_DEVICE_GLOBAL_MEM_CACHELINE_SIZE`
+    pub global_mem_cacheline_size: cl_uint,
+    /// `CL_DEVICE_GLOBAL_MEM_CACHE_SIZE`
+    pub global_mem_cache_size: cl_ulong,
+    /// `CL_DEVICE_GLOBAL_MEM_SIZE`
+    pub global_mem_size: cl_ulong,
+    /// `CL_DEVICE_MAX_CONSTANT_BUFFER_SIZE`
+    pub max_constant_buffer_size: cl_ulong,
+    /// `CL_DEVICE_MAX_CONSTANT_ARGS`
+    pub max_constant_args: cl_uint,
+    /// `CL_DEVICE_MAX_GLOBAL_VARIABLE_SIZE`
+    pub max_global_variable_size: Option<size_t>,
+    /// `CL_DEVICE_GLOBAL_VARIABLE_PREFERRED_TOTAL_SIZE`
+    pub global_variable_preferred_total_size: Option<size_t>,
+    /// `CL_DEVICE_LOCAL_MEM_TYPE`
+    pub local_mem_type: cl_device_local_mem_type,
+    /// `CL_DEVICE_LOCAL_MEM_SIZE`
+    pub local_mem_size: cl_ulong,
+    /// `CL_DEVICE_ERROR_CORRECTION_SUPPORT`
+    pub error_correction_support: bool,
+    /// `CL_DEVICE_HOST_UNIFIED_MEMORY`
+    pub host_unified_memory: Option<bool>,
+    /// `CL_DEVICE_PROFILING_TIMER_RESOLUTION`
+    pub profiling_timer_resolution: size_t,
+    /// `CL_DEVICE_ENDIAN_LITTLE`
+    pub endian_little: bool,
+    /// `CL_DEVICE_AVAILABLE`
+    pub available: bool,
+    /// `CL_DEVICE_COMPILER_AVAILABLE`
+    pub compiler_available: bool,
+    /// `CL_DEVICE_LINKER_AVAILABLE`
+    pub linker_available: bool,
+    /// `CL_DEVICE_EXECUTION_CAPABILITIES`
+    pub execution_capabilities: cl_device_exec_capabilities,
+    /// `CL_DEVICE_QUEUE_ON_HOST_PROPERTIES`
+    pub queue_on_host_properties: cl_command_queue_properties,
+    /// `CL_DEVICE_QUEUE_ON_DEVICE_PROPERTIES`
+    pub queue_on_device_properties: Option<cl_command_queue_properties>,
+    /// `CL_DEVICE_QUEUE_ON_DEVICE_PREFERRED_SIZE`
+    pub queue_on_device_preferred_size: Option<cl_uint>,
+    /// `CL_DEVICE_QUEUE_ON_DEVICE_MAX_SIZE`
+    pub queue_on_device_max_size: Option<cl_uint>,
+    /// `CL_DEVICE_MAX_ON_DEVICE_QUEUES`
+    pub max_on_device_queues: Option<cl_uint>,
+    /// `CL_DEVICE_MAX_ON_DEVICE_EVENTS`
+    pub max_on_device_events: Option<cl_uint>,
+    /// `CL_DEVICE_BUILT_IN_KERNELS`
+    pub built_in_kernels: String,
+    /// `CL_DEVICE_BUILT_IN_KERNELS_WITH_VERSION`
+    pub built_in_kernels_with_version: Option<Vec<NameVersion>>,
+    /// `CL_DEVICE_PLATFORM`
+    pub platform: intptr_t,
+    /// `CL_DEVICE_NAME`
+    pub name: String,
+    /// `CL_DEVICE_VENDOR`
+    pub vendor: String,
+    /// `CL_DRIVER_VERSION`
+    pub driver_version: String,
+    /// `CL_DEVICE_PROFILE`
+    pub profile: String,
+    /// `CL_DEVICE_VERSION`
+    pub version: String,
+    /// `CL_DEVICE_NUMERIC_VERSION`
+    pub numeric_version: Option<cl_version>,
+    /// `CL_DEVICE_OPENCL_C_VERSION`
+    pub opencl_c_version: String,
+    /// `CL_DEVICE_OPENCL_C_ALL_VERSIONS`
+    pub opencl_c_all_versions: Option<Vec<NameVersion>>,
+    /// `CL_DEVICE_OPENCL_C_FEATURES`
+    pub opencl_c_features: Option<Vec<NameVersion>>,
+    /// `CL_DEVICE_EXTENSIONS`
+    pub extensions: String,
+    /// `CL_DEVICE_EXTENSIONS_WITH_VERSION`
+    pub extensions_with_version: Option<Vec<NameVersion>>,
+    /// `CL_DEVICE_PRINTF_BUFFER_SIZE`
+    pub printf_buffer_size: size_t,
+    /// `CL_DEVICE_PREFERRED_INTEROP_USER_SYNC`
+    pub preferred_interop_user_sync: bool,
+    /// `CL_DEVICE_PARENT_DEVICE`
+    pub parent_device: intptr_t,
+    /// `CL_DEVICE_PARTITION_MAX_SUB_DEVICES`
+    pub partition_max_sub_devices: cl_uint,
+    /// `CL_DEVICE_PARTITION_PROPERTIES`
+    pub partition_properties: Vec<cl_device_partition_property>,
+    /// `CL_DEVICE_PARTITION_AFFINITY_DOMAIN`
+    pub partition_affinity_domain: cl_device_affinity_domain,
+    /// `CL_DEVICE_PARTITION_TYPE`
+    pub partition_type: Vec<cl_device_partition_property>,
+    /// `CL_DEVICE_REFERENCE_COUNT`
+    pub reference_count: cl_uint,
+    /// `CL_DEVICE_SVM_CAPABILITIES`
+    pub svm_capabilities: Option<cl_device_svm_capabilities>,
+    /// `CL_DEVICE_PREFERRED_PLATFORM_ATOMIC_ALIGNMENT`
+    pub preferred_platform_atomic_alignment: Option<cl_uint>,
+    /// `CL_DEVICE_PREFERRED_GLOBAL_ATOMIC_ALIGNMENT`
+    pub preferred_global_atomic_alignment: Option<cl_uint>,
+    /// `CL_DEVICE_PREFERRED_LOCAL_ATOMIC_ALIGNMENT`
+    pub preferred_local_atomic_alignment: Option<cl_uint>,
+    /// `CL_DEVICE_MAX_NUM_SUB_GROUPS`
+    pub max_num_sub_groups: Option<cl_uint>,
+    /// `CL_DEVICE_SUB_GROUP_INDEPENDENT_FORWARD_PROGRESS`
+    pub sub_group_independent_forward_progress: Option<bool>,
+    /// `CL_DEVICE_ATOMIC_MEMORY_CAPABILITIES`
+    pub atomic_memory_capabilities: Option<cl_device_atomic_capabilities>,
+    /// `CL_DEVICE_ATOMIC_FENCE_CAPABILITIES`
+    pub atomic_fence_capabilities: Option<cl_device_atomic_capabilities>,
+    /// `CL_DEVICE_NON_UNIFORM_WORK_GROUP_SUPPORT`
+    pub non_uniform_work_group_support: Option<bool>,
+    /// `CL_DEVICE_WORK_GROUP_COLLECTIVE_FUNCTIONS_SUPPORT`
+    pub work_group_collective_functions_support: Option<bool>,
+    /// `CL_DEVICE_GENERIC_ADDRESS_SPACE_SUPPORT`
+    pub generic_address_space_support: Option<bool>,
+    /// `CL_DEVICE_DEVICE_ENQUEUE_CAPABILITIES`
+    pub device_enqueue_capabilities: Option<cl_device_device_enqueue_capabilities>,
+    /// `CL_DEVICE_PIPE_SUPPORT`
+    pub pipe_support: Option<bool>,
+    /// `CL_DEVICE_PREFERRED_WORK_GROUP_SIZE_MULTIPLE`
+    pub preferred_work_group_size_multiple: Option<size_t>,
+    /// `CL_DEVICE_LATEST_CONFORMANCE_VERSION_PASSED`
+    pub latest_conformance_version_passed: Option<String>,
+
+    // cl_khr_device_uuid
+    /// `CL_DEVICE_UUID_KHR`
+    pub uuid_khr: Option<[cl_uchar; CL_UUID_SIZE_KHR]>,
+    /// `CL_DRIVER_UUID_KHR`
+    pub driver_uuid_khr: Option<[cl_uchar; CL_UUID_SIZE_KHR]>,
+    /// `CL_DEVICE_LUID_VALID_KHR`
+    pub luid_valid_khr: Option<bool>,
+    /// `CL_DEVICE_LUID_KHR`
+    pub luid_khr: Option<[cl_uchar; CL_LUID_SIZE_KHR]>,
+    /// `CL_DEVICE_NODE_MASK_KHR`
+    pub node_mask_khr: Option<cl_uint>,
+
+    // cl_khr_integer_dot_product
+    /// `CL_DEVICE_INTEGER_DOT_PRODUCT_CAPABILITIES_KHR`
+    pub integer_dot_product_capabilities_khr:
+        Option<cl_device_integer_dot_product_capabilities_khr>,
+    /// `CL_DEVICE_INTEGER_DOT_PRODUCT_ACCELERATION_PROPERTIES_8BIT_KHR`
+    pub integer_dot_product_acceleration_properties_8bit_khr:
+        Option<IntegerDotProductAccelerationProperties>,
+    /// `CL_DEVICE_INTEGER_DOT_PRODUCT_ACCELERATION_PROPERTIES_4x8BIT_PACKED_KHR`
+    pub integer_dot_product_acceleration_properties_4x8bit_packed_khr:
+        Option<IntegerDotProductAccelerationProperties>,
+
+    // cl_khr_pci_bus_info
+    /// `CL_DEVICE_PCI_BUS_INFO_KHR`
+    pub pci_bus_info_khr: Option<PciBusInfo>,
+
+    // cl_khr_external_memory
+    /// `CL_DEVICE_EXTERNAL_MEMORY_IMPORT_HANDLE_TYPES_KHR`
+    pub external_memory_import_handle_types_khr: Option<Vec<cl_external_memory_handle_type_khr>>,
+
+    // cl_khr_external_semaphore
+    /// `CL_DEVICE_SEMAPHORE_IMPORT_HANDLE_TYPES_KHR`
+    pub semaphore_import_handle_types_khr: Option<Vec<cl_external_semaphore_handle_type_khr>>,
+    /// `CL_DEVICE_SEMAPHORE_EXPORT_HANDLE_TYPES_KHR`
+    pub semaphore_export_handle_types_khr: Option<Vec<cl_external_semaphore_handle_type_khr>>,
+
+    // cl_khr_semaphore
+    /// `CL_DEVICE_SEMAPHORE_TYPES_KHR`
+    pub semaphore_types_khr: Option<Vec<cl_semaphore_type_khr>>,
+
+    // cl_khr_command_buffer
+    /// `CL_DEVICE_COMMAND_BUFFER_CAPABILITIES_KHR`
+    pub command_buffer_capabilities_khr: Option<cl_device_command_buffer_capabilities_khr>,
+    /// `CL_DEVICE_COMMAND_BUFFER_REQUIRED_QUEUE_PROPERTIES_KHR`
+    pub command_buffer_required_queue_properties_khr: Option<cl_command_queue_properties>,
+
+    // cl_nv_device_attribute_query
+    /// `CL_DEVICE_COMPUTE_CAPABILITY_MAJOR_NV`
+    pub compute_capability_major_nv: Option<cl_uint>,
+    /// `CL_DEVICE_COMPUTE_CAPABILITY_MINOR_NV`
+    pub compute_capability_minor_nv: Option<cl_uint>,
+    /// `CL_DEVICE_REGISTERS_PER_BLOCK_NV`
+    pub registers_per_block_nv: Option<cl_uint>,
+    /// `CL_DEVICE_WARP_SIZE_NV`
+    pub warp_size_nv: Option<cl_uint>,
+    /// `CL_DEVICE_GPU_OVERLAP_NV`
+    pub gpu_overlap_nv: Option<bool>,
+    /// `CL_DEVICE_KERNEL_EXEC_TIMEOUT_NV`
+    pub kernel_exec_timeout_nv: Option<bool>,
+    /// `CL_DEVICE_INTEGRATED_MEMORY_NV`
+    pub integrated_memory_nv: Option<bool>,
+    /// `CL_DEVICE_PCI_BUS_ID_NV`
+    pub pci_bus_id_nv: Option<cl_uint>,
+    /// `CL_DEVICE_PCI_SLOT_ID_NV`
+    pub pci_slot_id_nv: Option<cl_uint>,
+
+    // cl_amd_device_attribute_query
+    /// `CL_DEVICE_PROFILING_TIMER_OFFSET_AMD`
+    pub profiling_timer_offset_amd: Option<cl_ulong>,
+    /// `CL_DEVICE_TOPOLOGY_AMD`
+    pub topology_amd: Option<AmdDeviceTopology>,
+    /// `CL_DEVICE_BOARD_NAME_AMD`
+    pub board_name_amd: Option<String>,
+    /// `CL_DEVICE_GLOBAL_FREE_MEMORY_AMD`
+    pub global_free_memory_amd: Option<Vec<size_t>>,
+    /// `CL_DEVICE_SIMD_PER_COMPUTE_UNIT_AMD`
+    pub simd_per_compute_unit_amd: Option<cl_uint>,
+    /// `CL_DEVICE_SIMD_WIDTH_AMD`
+    pub simd_width_amd: Option<cl_uint>,
+    /// `CL_DEVICE_SIMD_INSTRUCTION_WIDTH_AMD`
+    pub simd_instruction_width_amd: Option<cl_uint>,
+    /// `CL_DEVICE_WAVEFRONT_WIDTH_AMD`
+    pub wavefront_width_amd: Option<cl_uint>,
+    /// `CL_DEVICE_GLOBAL_MEM_CHANNELS_AMD`
+    pub global_mem_channels_amd: Option<cl_uint>,
+    /// `CL_DEVICE_GLOBAL_MEM_CHANNEL_BANKS_AMD`
+    pub global_mem_channel_banks_amd: Option<cl_uint>,
+    /// `CL_DEVICE_GLOBAL_MEM_CHANNEL_BANK_WIDTH_AMD`
+    pub global_mem_channel_bank_width_amd: Option<cl_uint>,
+    /// `CL_DEVICE_LOCAL_MEM_SIZE_PER_COMPUTE_UNIT_AMD`
+    pub local_mem_size_per_compute_unit_amd: Option<cl_uint>,
+    /// `CL_DEVICE_LOCAL_MEM_BANKS_AMD`
+    pub local_mem_banks_amd: Option<cl_uint>,
+    /// `CL_DEVICE_THREAD_TRACE_SUPPORTED_AMD`
+    pub thread_trace_supported_amd: Option<bool>,
+    /// `CL_DEVICE_GFXIP_MAJOR_AMD`
+    pub gfxip_major_amd: Option<cl_uint>,
+    /// `CL_DEVICE_GFXIP_MINOR_AMD`
+    pub gfxip_minor_amd: Option<cl_uint>,
+    /// `CL_DEVICE_AVAILABLE_ASYNC_QUEUES_AMD`
+    pub available_async_queues_amd: Option<cl_uint>,
+    /// `CL_DEVICE_PREFERRED_WORK_GROUP_SIZE_AMD`
+    pub preferred_work_group_size_amd: Option<size_t>,
+    /// `CL_DEVICE_MAX_WORK_GROUP_SIZE_AMD`
+    pub max_work_group_size_amd: Option<size_t>,
+    /// `CL_DEVICE_PREFERRED_CONSTANT_BUFFER_SIZE_AMD`
+    pub preferred_constant_buffer_size_amd: Option<size_t>,
+    /// `CL_DEVICE_PCIE_ID_AMD`
+    pub pcie_id_amd: Option<cl_uint>,
+
+    // cl_intel_device_attribute_query
+    /// `CL_DEVICE_IP_VERSION_INTEL`
+    pub ip_version_intel: Option<cl_version_khr>,
+    /// `CL_DEVICE_ID_INTEL`
+    pub id_intel: Option<cl_uint>,
+    /// `CL_DEVICE_NUM_SLICES_INTEL`
+    pub num_slices_intel: Option<cl_uint>,
+    /// `CL_DEVICE_NUM_SUB_SLICES_PER_SLICE_INTEL`
+    pub num_sub_slices_per_slice_intel: Option<cl_uint>,
+    /// `CL_DEVICE_NUM_EUS_PER_SUB_SLICE_INTEL`
+    pub num_eus_per_sub_slice_intel: Option<cl_uint>,
+    /// `CL_DEVICE_NUM_THREADS_PER_EU_INTEL`
+    pub num_threads_per_eu_intel: Option<cl_uint>,
+    /// `CL_DEVICE_FEATURE_CAPABILITIES_INTEL`
+    pub feature_capabilities_intel: Option<cl_device_feature_capabilities_intel>,
+}
+
+fn required<P: DeviceParam>(device: cl_device_id) -> Result<P::Value, cl_int> {
+    P::get(device)
+}
+
+fn optional<P: DeviceParam>(device: cl_device_id) -> Result<Option<P::Value>, cl_int> {
+    match P::get(device) {
+        Ok(value) => Ok(Some(value)),
+        Err(CL_INVALID_VALUE) => {
+            clear_error();
+            Ok(None)
+        }
+        Err(error) => Err(error),
+    }
+}
+
+fn name_versions(values: Vec<cl_name_version>) -> Vec<NameVersion> {
+    values.into_iter().map(NameVersion::from).collect()
+}
+
+fn handle_value<T>(handle: *mut T) -> intptr_t {
+    handle as intptr_t
+}
+
+/// Get the capabilities of an `OpenCL` device.  
+/// Calls clGetDeviceInfo for every device information parameter.
+///
+/// * `device` - the `cl_device_id` of the `OpenCL` device.
+///
+/// returns a Result containing the device capabilities
+/// or the error code from the `OpenCL` C API function.
+pub fn get_device_capabilities(device: impl AsDevice) -> Result<DeviceCapabilities, cl_int> {
+    let device = device.as_raw();
+    Ok(DeviceCapabilities {
+        device_type: required::<DeviceType>(device)?,
+        vendor_id: required::<VendorId>(device)?,
+        max_compute_units: required::<MaxComputeUnits>(device)?,
+        max_work_item_dimensions: required::<MaxWorkItemDimensions>(device)?,
+        max_work_group_size: required::<MaxWorkGroupSize>(device)?,
+        max_work_item_sizes: required::<MaxWorkItemSizes>(device)?,
+        preferred_vector_width_char: required::<PreferredVectorWidthChar>(device)?,
+        preferred_vector_width_short: required::<PreferredVectorWidthShort>(device)?,
+        preferred_vector_width_int: required::<PreferredVectorWidthInt>(device)?,
+        preferred_vector_width_long: required::<PreferredVectorWidthLong>(device)?,
+        preferred_vector_width_float: required::<PreferredVectorWidthFloat>(device)?,
+        preferred_vector_width_double: required::<PreferredVectorWidthDouble>(device)?,
+        preferred_vector_width_half: required::<PreferredVectorWidthHalf>(device)?,
+        native_vector_width_char: required::<NativeVectorWidthChar>(device)?,
+        native_vector_width_short: required::<NativeVectorWidthShort>(device)?,
+        native_vector_width_int: required::<NativeVectorWidthInt>(device)?,
+        native_vector_width_long: required::<NativeVectorWidthLong>(device)?,
+        native_vector_width_float: required::<NativeVectorWidthFloat>(device)?,
+        native_vector_width_double: required::<NativeVectorWidthDouble>(device)?,
+        native_vector_width_half: required::<NativeVectorWidthHalf>(device)?,
+        max_clock_frequency: required::<MaxClockFrequency>(device)?,
+        address_bits: required::<AddressBits>(device)?,
+        max_mem_alloc_size: required::<MaxMemAllocSize>(device)?,
+        image_support: required::<ImageSupport>(device)?,
+        max_read_image_args: required::<MaxReadImageArgs>(device)?,
+        max_write_image_args: required::<MaxWriteImageArgs>(device)?,
+        max_read_write_image_args: optional::<MaxReadWriteImageArgs>(device)?,
+        il_version: optional::<IlVersion>(device)?,
+        ils_with_version: optional::<IlsWithVersion>(device)?.map(name_versions),
+        image2d_max_width: required::<Image2dMaxWidth>(device)?,
+        image2d_max_height: required::<Image2dMaxHeight>(device)?,
+        image3d_max_width: required::<Image3dMaxWidth>(device)?,
+        image3d_max_height: required::<Image3dMaxHeight>(device)?,
+        image3d_max_depth: required::<Image3dMaxDepth>(device)?,
+        image_max_buffer_size: required::<ImageMaxBufferSize>(device)?,
+        image_max_array_size: required::<ImageMaxArraySize>(device)?,
+        max_samplers: required::<MaxSamplers>(device)?,
+        image_pitch_alignment: optional::<ImagePitchAlignment>(device)?,
+        image_base_address_alignment: optional::<ImageBaseAddressAlignment>(device)?,
+        max_pipe_args: optional::<MaxPipeArgs>(device)?,
+        pipe_max_active_reservations: optional::<PipeMaxActiveReservations>(device)?,
+        pipe_max_packet_size: optional::<PipeMaxPacketSize>(device)?,
+        max_parameter_size: required::<MaxParameterSize>(device)?,
+        mem_base_addr_align: required::<MemBaseAddrAlign>(device)?,
+        min_data_type_align_size: optional::<MinDataTypeAlignSize>(device)?,
+        single_fp_config: required::<SingleFpConfig>(device)?,
+        double_fp_config: required::<DoubleFpConfig>(device)?,
+        half_fp_config: optional::<HalfFpConfig>(device)?,
+        global_mem_cache_type: required::<GlobalMemCacheType>(device)?,
+        global_mem_cacheline_size: required::<GlobalMemCachelineSize>(device)?,
+        global_mem_cache_size: required::<GlobalMemCacheSize>(device)?,
+        global_mem_size: required::<GlobalMemSize>(device)?,
+        max_constant_buffer_size: required::<MaxConstantBufferSize>(device)?,
+        max_constant_args: required::<MaxConstantArgs>(device)?,
+        max_global_variable_size: optional::<MaxGlobalVariableSize>(device)?,
+        global_variable_preferred_total_size: optional::<GlobalVariablePreferredTotalSize>(device)?,
+        local_mem_type: required::<LocalMemType>(device)?,
+        local_mem_size: required::<LocalMemSize>(device)?,
+        error_correction_support: required::<ErrorCorrectionSupport>(device)?,
+        host_unified_memory: optional::<HostUnifiedMemory>(device)?,
+        profiling_timer_resolution: required::<ProfilingTimerResolution>(device)?,
+        endian_little: required::<EndianLittle>(device)?,
+        available: required::<Available>(device)?,
+        compiler_available: required::<CompilerAvailable>(device)?,
+        linker_available: required::<LinkerAvailable>(device)?,
+        execution_capabilities: required::<ExecutionCapabilities>(device)?,
+        queue_on_host_properties: required::<QueueOnHostProperties>(device)?,
+        queue_on_device_properties: optional::<QueueOnDeviceProperties>(device)?,
+        queue_on_device_preferred_size: optional::<QueueOnDevicePreferredSize>(device)?,
+        queue_on_device_max_size: optional::<QueueOnDeviceMaxSize>(device)?,
+        max_on_device_queues: optional::<MaxOnDeviceQueues>(device)?,
+        max_on_device_events: optional::<MaxOnDeviceEvents>(device)?,
+        built_in_kernels: required::<BuiltInKernels>(device)?,
+        built_in_kernels_with_version: optional::<BuiltInKernelsWithVersion>(device)?
+            .map(name_versions),
+        platform: handle_value(required::<Platform>(device)?),
+        name: required::<Name>(device)?,
+        vendor: required::<Vendor>(device)?,
+        driver_version: required::<DriverVersion>(device)?,
+        profile: required::<Profile>(device)?,
+        version: required::<DeviceVersion>(device)?,
+        numeric_version: optional::<NumericVersion>(device)?,
+        opencl_c_version: required::<OpenClCVersion>(device)?,
+        opencl_c_all_versions: optional::<OpenClCAllVersions>(device)?.map(name_versions),
+        opencl_c_features: optional::<OpenClCFeatures>(device)?.map(name_versions),
+        extensions: required::<Extensions>(device)?,
+        extensions_with_version: optional::<ExtensionsWithVersion>(device)?.map(name_versions),
+        printf_buffer_size: required::<PrintfBufferSize>(device)?,
+        preferred_interop_user_sync: required::<PreferredInteropUserSync>(device)?,
+        parent_device: handle_value(required::<ParentDevice>(device)?),
+        partition_max_sub_devices: required::<PartitionMaxSubDevices>(device)?,
+        partition_properties: required::<PartitionProperties>(device)?,
+        partition_affinity_domain: required::<PartitionAffinityDomain>(device)?,
+        partition_type: required::<PartitionType>(device)?,
+        reference_count: required::<ReferenceCount>(device)?,
+        svm_capabilities: optional::<SvmCapabilities>(device)?,
+        preferred_platform_atomic_alignment: optional::<PreferredPlatformAtomicAlignment>(device)?,
+        preferred_global_atomic_alignment: optional::<PreferredGlobalAtomicAlignment>(device)?,
+        preferred_local_atomic_alignment: optional::<PreferredLocalAtomicAlignment>(device)?,
+        max_num_sub_groups: optional::<MaxNumSubGroups>(device)?,
+        sub_group_independent_forward_progress: optional::<SubGroupIndependentForwardProgress>(
+            device,
+        )?,
+        atomic_memory_capabilities: optional::<AtomicMemoryCapabilities>(device)?,
+        atomic_fence_capabilities: optional::<AtomicFenceCapabilities>(device)?,
+        non_uniform_work_group_support: optional::<NonUniformWorkGroupSupport>(device)?,
+        work_group_collective_functions_support: optional::<WorkGroupCollectiveFunctionsSupport>(
+            device,
+        )?,
+        generic_address_space_support: optional::<GenericAddressSpaceSupport>(device)?,
+        device_enqueue_capabilities: optional::<DeviceEnqueueCapabilities>(device)?,
+        pipe_support: optional::<PipeSupport>(device)?,
+        preferred_work_group_size_multiple: optional::<PreferredWorkGroupSizeMultiple>(device)?,
+        latest_conformance_version_passed: optional::<LatestConformanceVersionPassed>(device)?,
+        // cl_khr_device_uuid
+        uuid_khr: optional::<UuidKhr>(device)?,
+        driver_uuid_khr: optional::<DriverUuidKhr>(device)?,
+        luid_valid_khr: optional::<LuidValidKhr>(device)?,
+        luid_khr: optional::<LuidKhr>(device)?,
+        node_mask_khr: optional::<NodeMaskKhr>(device)?,
+        // cl_khr_integer_dot_product
+        integer_dot_product_capabilities_khr: optional::<IntegerDotProductCapabilitiesKhr>(device)?,
+        integer_dot_product_acceleration_properties_8bit_khr: optional::<
+            IntegerDotProductAccelerationProperties8BitKhr,
+        >(device)?
+        .map(IntegerDotProductAccelerationProperties::from),
+        integer_dot_product_acceleration_properties_4x8bit_packed_khr: optional::<
+            IntegerDotProductAccelerationProperties4x8BitPackedKhr,
+        >(device)?
+        .map(IntegerDotProductAccelerationProperties::from),
+        // cl_khr_pci_bus_info
+        pci_bus_info_khr: optional::<PciBusInfoKhr>(device)?.map(PciBusInfo::from),
+        // cl_khr_external_memory
+        external_memory_import_handle_types_khr: optional::<ExternalMemoryImportHandleTypesKhr>(
+            device,
+        )?,
+        // cl_khr_external_semaphore
+        semaphore_import_handle_types_khr: optional::<SemaphoreImportHandleTypesKhr>(device)?,
+        semaphore_export_handle_types_khr: optional::<SemaphoreExportHandleTypesKhr>(device)?,
+        // cl_khr_semaphore
+        semaphore_types_khr: optional::<SemaphoreTypesKhr>(device)?,
+        // cl_khr_command_buffer
+        command_buffer_capabilities_khr: optional::<CommandBufferCapabilitiesKhr>(device)?,
+        command_buffer_required_queue_properties_khr: optional::<
+            CommandBufferRequiredQueuePropertiesKhr,
+        >(device)?,
+        // cl_nv_device_attribute_query
+        compute_capability_major_nv: optional::<ComputeCapabilityMajorNv>(device)?,
+        compute_capability_minor_nv: optional::<ComputeCapabilityMinorNv>(device)?,
+        registers_per_block_nv: optional::<RegistersPerBlockNv>(device)?,
+        warp_size_nv: optional::<WarpSizeNv>(device)?,
+        gpu_overlap_nv: optional::<GpuOverlapNv>(device)?,
+        kernel_exec_timeout_nv: optional::<KernelExecTimeoutNv>(device)?,
+        integrated_memory_nv: optional::<IntegratedMemoryNv>(device)?,
+        pci_bus_id_nv: optional::<PciBusIdNv>(device)?,
+        pci_slot_id_nv: optional::<PciSlotIdNv>(device)?,
+        // cl_amd_device_attribute_query
+        profiling_timer_offset_amd: optional::<ProfilingTimerOffsetAmd>(device)?,
+        topology_amd: optional::<TopologyAmd>(device)?.map(AmdDeviceTopology::from),
+        board_name_amd: optional::<BoardNameAmd>(device)?,
+        global_free_memory_amd: optional::<GlobalFreeMemoryAmd>(device)?,
+        simd_per_compute_unit_amd: optional::<SimdPerComputeUnitAmd>(device)?,
+        simd_width_amd: optional::<SimdWidthAmd>(device)?,
+        simd_instruction_width_amd: optional::<SimdInstructionWidthAmd>(device)?,
+        wavefront_width_amd: optional::<WavefrontWidthAmd>(device)?,
+        global_mem_channels_amd: optional::<GlobalMemChannelsAmd>(device)?,
+        global_mem_channel_banks_amd: optional::<GlobalMemChannelBanksAmd>(device)?,
+        global_mem_channel_bank_width_amd: optional::<GlobalMemChannelBankWidthAmd>(device)?,
+        local_mem_size_per_compute_unit_amd: optional::<LocalMemSizePerComputeUnitAmd>(device)?,
+        local_mem_banks_amd: optional::<LocalMemBanksAmd>(device)?,
+        thread_trace_supported_amd: optional::<ThreadTraceSupportedAmd>(device)?,
+        gfxip_major_amd: optional::<GfxipMajorAmd>(device)?,
+        gfxip_minor_amd: optional::<GfxipMinorAmd>(device)?,
+        available_async_queues_amd: optional::<AvailableAsyncQueuesAmd>(device)?,
+        preferred_work_group_size_amd: optional::<PreferredWorkGroupSizeAmd>(device)?,
+        max_work_group_size_amd: optional::<MaxWorkGroupSizeAmd>(device)?,
+        preferred_constant_buffer_size_amd: optional::<PreferredConstantBufferSizeAmd>(device)?,
+        pcie_id_amd: optional::<PcieIdAmd>(device)?,
+        // cl_intel_device_attribute_query
+        ip_version_intel: optional::<IpVersionIntel>(device)?,
+        id_intel: optional::<IdIntel>(device)?,
+        num_slices_intel: optional::<NumSlicesIntel>(device)?,
+        num_sub_slices_per_slice_intel: optional::<NumSubSlicesPerSliceIntel>(device)?,
+        num_eus_per_sub_slice_intel: optional::<NumEusPerSubSliceIntel>(device)?,
+        num_threads_per_eu_intel: optional::<NumThreadsPerEuIntel>(device)?,
+        feature_capabilities_intel: optional::<FeatureCapabilitiesIntel>(device)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device::{get_device_ids, CL_DEVICE_TYPE_GPU};
+    use crate::platform::get_platform_ids;
+
+    #[test]
+    fn test_get_device_capabilities() {
+        let platform_ids = get_platform_ids().unwrap();
+        assert!(0 < platform_ids.len());
+
+        // Choose the first platform
+        let platform_id = platform_ids[0];
+
+        let device_ids = get_device_ids(platform_id, CL_DEVICE_TYPE_GPU).unwrap();
+        assert!(0 < device_ids.len());
+
+        let capabilities = get_device_capabilities(device_ids[0]).unwrap();
+        println!("DeviceCapabilities: {:#?}", capabilities);
+
+        assert_eq!(CL_DEVICE_TYPE_GPU, capabilities.device_type);
+        assert_eq!(platform_id as intptr_t, capabilities.platform);
+        assert!(0 < capabilities.max_compute_units);
+        assert_eq!(
+            capabilities.max_work_item_dimensions as usize,
+            capabilities.max_work_item_sizes.len()
+        );
+        assert!(!capabilities.name.is_empty());
+        assert!(capabilities.version.starts_with("OpenCL "));
+
+        #[cfg(feature = "serde")]
+        {
+            let json = serde_json::to_string(&capabilities).unwrap();
+            let value: DeviceCapabilities = serde_json::from_str(&json).unwrap();
+            assert_eq!(capabilities, value);
+        }
+    }
+}
@@ -0,0 +1,1129 @@
+// Copyright (c) 2024 Via Technology Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Strongly typed `OpenCL` device information queries.
+//!
+//! Each `CL_DEVICE_*` parameter has a zero sized marker type that implements
+//! [`DeviceParam`], which specifies the Rust type of the parameter's value,
+//! so the value can be queried without decoding an [`InfoType`], e.g.:
+//!
+//! ```no_run
+//! use cl3::device::{get_device_ids, CL_DEVICE_TYPE_GPU};
+//! use cl3::device_info::{get_device_param, MaxComputeUnits, MaxWorkItemSizes, Name};
+//! use cl3::platform::get_platform_ids;
+//!
+//! let platform_ids = get_platform_ids().unwrap();
+//! let device_ids = get_device_ids(platform_ids[0], CL_DEVICE_TYPE_GPU).unwrap();
+//! let device_id = device_ids[0];
+//!
+//! let name: String = get_device_param(device_id, Name).unwrap();
+//! let compute_units: u32 = get_device_param(device_id, MaxComputeUnits).unwrap();
+//! let work_item_sizes: Vec<usize> = get_device_param(device_id, MaxWorkItemSizes).unwrap();
+//! ```
+//!
+//! The marker types are named after the `OpenCL` constants without their
+//! `CL_DEVICE_` (or `CL_`) prefix, except for `DeviceType` and `DeviceVersion`.
+//!
+//! [`InfoType`]: super::info_type::InfoType
+
+#![allow(non_upper_case_globals)]
+
+use super::api_error::{clear_error, record_error};
+use super::ffi::clGetDeviceInfo;
+use super::owned::AsDevice;
+use libc::{c_void, size_t};
+use opencl_sys::{
+    cl_amd_device_topology, cl_bool, cl_command_queue_properties, cl_device_affinity_domain,
+    cl_device_atomic_capabilities, cl_device_command_buffer_capabilities_khr,
+    cl_device_device_enqueue_capabilities, cl_device_exec_capabilities,
+    cl_device_feature_capabilities_intel, cl_device_fp_config, cl_device_id, cl_device_info,
+    cl_device_integer_dot_product_acceleration_properties_khr,
+    cl_device_integer_dot_product_capabilities_khr, cl_device_local_mem_type,
+    cl_device_mem_cache_type, cl_device_partition_property, cl_device_pci_bus_info_khr,
+    cl_device_svm_capabilities, cl_device_type, cl_external_memory_handle_type_khr,
+    cl_external_semaphore_handle_type_khr, cl_int, cl_name_version, cl_platform_id,
+    cl_semaphore_type_khr, cl_uchar, cl_uint, cl_ulong, cl_version, cl_version_khr,
+    CL_DEVICE_INTEGER_DOT_PRODUCT_ACCELERATION_PROPERTIES_4x8BIT_PACKED_KHR,
+    CL_DEVICE_ADDRESS_BITS, CL_DEVICE_ATOMIC_FENCE_CAPABILITIES,
+    CL_DEVICE_ATOMIC_MEMORY_CAPABILITIES, CL_DEVICE_AVAILABLE,
+    CL_DEVICE_AVAILABLE_ASYNC_QUEUES_AMD, CL_DEVICE_BOARD_NAME_AMD, CL_DEVICE_BUILT_IN_KERNELS,
+    CL_DEVICE_BUILT_IN_KERNELS_WITH_VERSION, CL_DEVICE_COMMAND_BUFFER_CAPABILITIES_KHR,
+    CL_DEVICE_COMMAND_BUFFER_REQUIRED_QUEUE_PROPERTIES_KHR, CL_DEVICE_COMPILER_AVAILABLE,
+    CL_DEVICE_COMPUTE_CAPABILITY_MAJOR_NV, CL_DEVICE_COMPUTE_CAPABILITY_MINOR_NV,
+    CL_DEVICE_DEVICE_ENQUEUE_CAPABILITIES, CL_DEVICE_DOUBLE_FP_CONFIG, CL_DEVICE_ENDIAN_LITTLE,
+    CL_DEVICE_ERROR_CORRECTION_SUPPORT, CL_DEVICE_EXECUTION_CAPABILITIES, CL_DEVICE_EXTENSIONS,
+    CL_DEVICE_EXTENSIONS_WITH_VERSION, CL_DEVICE_EXTERNAL_MEMORY_IMPORT_HANDLE_TYPES_KHR,
+    CL_DEVICE_FEATURE_CAPABILITIES_INTEL, CL_DEVICE_GENERIC_ADDRESS_SPACE_SUPPORT,
+    CL_DEVICE_GFXIP_MAJOR_AMD, CL_DEVICE_GFXIP_MINOR_AMD, CL_DEVICE_GLOBAL_FREE_MEMORY_AMD,
+    CL_DEVICE_GLOBAL_MEM_CACHELINE_SIZE, CL_DEVICE_GLOBAL_MEM_CACHE_SIZE,
+    CL_DEVICE_GLOBAL_MEM_CACHE_TYPE, CL_DEVICE_GLOBAL_MEM_CHANNELS_AMD,
+    CL_DEVICE_GLOBAL_MEM_CHANNEL_BANKS_AMD, CL_DEVICE_GLOBAL_MEM_CHANNEL_BANK_WIDTH_AMD,
+    CL_DEVICE_GLOBAL_MEM_SIZE, CL_DEVICE_GLOBAL_VARIABLE_PREFERRED_TOTAL_SIZE,
+    CL_DEVICE_GPU_OVERLAP_NV, CL_DEVICE_HALF_FP_CONFIG, CL_DEVICE_HOST_UNIFIED_MEMORY,
+    CL_DEVICE_ID_INTEL, CL_DEVICE_ILS_WITH_VERSION, CL_DEVICE_IL_VERSION,
+    CL_DEVICE_IMAGE2D_MAX_HEIGHT, CL_DEVICE_IMAGE2D_MAX_WIDTH, CL_DEVICE_IMAGE3D_MAX_DEPTH,
+    CL_DEVICE_IMAGE3D_MAX_HEIGHT, CL_DEVICE_IMAGE3D_MAX_WIDTH,
+    CL_DEVICE_IMAGE_BASE_ADDRESS_ALIGNMENT, CL_DEVICE_IMAGE_MAX_ARRAY_SIZE,
+    CL_DEVICE_IMAGE_MAX_BUFFER_SIZE, CL_DEVICE_IMAGE_PITCH_ALIGNMENT, CL_DEVICE_IMAGE_SUPPORT,
+    CL_DEVICE_INTEGER_DOT_PRODUCT_ACCELERATION_PROPERTIES_8BIT_KHR,
+    CL_DEVICE_INTEGER_DOT_PRODUCT_CAPABILITIES_KHR, CL_DEVICE_INTEGRATED_MEMORY_NV,
+    CL_DEVICE_IP_VERSION_INTEL, CL_DEVICE_KERNEL_EXEC_TIMEOUT_NV,
+    CL_DEVICE_LATEST_CONFORMANCE_VERSION_PASSED, CL_DEVICE_LINKER_AVAILABLE,
+    CL_DEVICE_LOCAL_MEM_BANKS_AMD, CL_DEVICE_LOCAL_MEM_SIZE,
+    CL_DEVICE_LOCAL_MEM_SIZE_PER_COMPUTE_UNIT_AMD, CL_DEVICE_LOCAL_MEM_TYPE, CL_DEVICE_LUID_KHR,
+    CL_DEVICE_LUID_VALID_KHR, CL_DEVICE_MAX_CLOCK_FREQUENCY, CL_DEVICE_MAX_COMPUTE_UNITS,
+    CL_DEVICE_MAX_CONSTANT_ARGS, CL_DEVICE_MAX_CONSTANT_BUFFER_SIZE,
+    CL_DEVICE_MAX_GLOBAL_VARIABLE_SIZE, CL_DEVICE_MAX_MEM_ALLOC_SIZE, CL_DEVICE_MAX_NUM_SUB_GROUPS,
+    CL_DEVICE_MAX_ON_DEVICE_EVENTS, CL_DEVICE_MAX_ON_DEVICE_QUEUES, CL_DEVICE_MAX_PARAMETER_SIZE,
+    CL_DEVICE_MAX_PIPE_ARGS, CL_DEVICE_MAX_READ_IMAGE_ARGS, CL_DEVICE_MAX_READ_WRITE_IMAGE_ARGS,
+    CL_DEVICE_MAX_SAMPLERS, CL_DEVICE_MAX_WORK_GROUP_SIZE, CL_DEVICE_MAX_WORK_GROUP_SIZE_AMD,
+    CL_DEVICE_MAX_WORK_ITEM_DIMENSIONS, CL_DEVICE_MAX_WORK_ITEM_SIZES,
+    CL_DEVICE_MAX_WRITE_IMAGE_ARGS, CL_DEVICE_MEM_BASE_ADDR_ALIGN,
+    CL_DEVICE_MIN_DATA_TYPE_ALIGN_SIZE, CL_DEVICE_NAME, CL_DEVICE_NATIVE_VECTOR_WIDTH_CHAR,
+    CL_DEVICE_NATIVE_VECTOR_WIDTH_DOUBLE, CL_DEVICE_NATIVE_VECTOR_WIDTH_FLOAT,
+    CL_DEVICE_NATIVE_VECTOR_WIDTH_HALF, CL_DEVICE_NATIVE_VECTOR_WIDTH_INT,
+    CL_DEVICE_NATIVE_VECTOR_WIDTH_LONG, CL_DEVICE_NATIVE_VECTOR_WIDTH_SHORT,
+    CL_DEVICE_NODE_MASK_KHR, CL_DEVICE_NON_UNIFORM_WORK_GROUP_SUPPORT, CL_DEVICE_NUMERIC_VERSION,
+    CL_DEVICE_NUM_EUS_PER_SUB_SLICE_INTEL, CL_DEVICE_NUM_SLICES_INTEL,
+    CL_DEVICE_NUM_SUB_SLICES_PER_SLICE_INTEL, CL_DEVICE_NUM_THREADS_PER_EU_INTEL,
+    CL_DEVICE_OPENCL_C_ALL_VERSIONS, CL_DEVICE_OPENCL_C_FEATURES, CL_DEVICE_OPENCL_C_VERSION,
+    CL_DEVICE_PARENT_DEVICE, CL_DEVICE_PARTITION_AFFINITY_DOMAIN,
+    CL_DEVICE_PARTITION_MAX_SUB_DEVICES, CL_DEVICE_PARTITION_PROPERTIES, CL_DEVICE_PARTITION_TYPE,
+    CL_DEVICE_PCIE_ID_AMD, CL_DEVICE_PCI_BUS_ID_NV, CL_DEVICE_PCI_BUS_INFO_KHR,
+    CL_DEVICE_PCI_SLOT_ID_NV, CL_DEVICE_PIPE_MAX_ACTIVE_RESERVATIONS,
+    CL_DEVICE_PIPE_MAX_PACKET_SIZE, CL_DEVICE_PIPE_SUPPORT, CL_DEVICE_PLATFORM,
+    CL_DEVICE_PREFERRED_CONSTANT_BUFFER_SIZE_AMD, CL_DEVICE_PREFERRED_GLOBAL_ATOMIC_ALIGNMENT,
+    CL_DEVICE_PREFERRED_INTEROP_USER_SYNC, CL_DEVICE_PREFERRED_LOCAL_ATOMIC_ALIGNMENT,
+    CL_DEVICE_PREFERRED_PLATFORM_ATOMIC_ALIGNMENT, CL_DEVICE_PREFERRED_VECTOR_WIDTH_CHAR,
+    CL_DEVICE_PREFERRED_VECTOR_WIDTH_DOUBLE, CL_DEVICE_PREFERRED_VECTOR_WIDTH_FLOAT,
+    CL_DEVICE_PREFERRED_VECTOR_WIDTH_HALF, CL_DEVICE_PREFERRED_VECTOR_WIDTH_INT,
+    CL_DEVICE_PREFERRED_VECTOR_WIDTH_LONG, CL_DEVICE_PREFERRED_VECTOR_WIDTH_SHORT,
+    CL_DEVICE_PREFERRED_WORK_GROUP_SIZE_AMD, CL_DEVICE_PREFERRED_WORK_GROUP_SIZE_MULTIPLE,
+    CL_DEVICE_PRINTF_BUFFER_SIZE, CL_DEVICE_PROFILE, CL_DEVICE_PROFILING_TIMER_OFFSET_AMD,
+    CL_DEVICE_PROFILING_TIMER_RESOLUTION, CL_DEVICE_QUEUE_ON_DEVICE_MAX_SIZE,
+    CL_DEVICE_QUEUE_ON_DEVICE_PREFERRED_SIZE, CL_DEVICE_QUEUE_ON_DEVICE_PROPERTIES,
+    CL_DEVICE_QUEUE_ON_HOST_PROPERTIES, CL_DEVICE_REFERENCE_COUNT,
+    CL_DEVICE_REGISTERS_PER_BLOCK_NV, CL_DEVICE_SEMAPHORE_EXPORT_HANDLE_TYPES_KHR,
+    CL_DEVICE_SEMAPHORE_IMPORT_HANDLE_TYPES_KHR, CL_DEVICE_SEMAPHORE_TYPES_KHR,
+    CL_DEVICE_SIMD_INSTRUCTION_WIDTH_AMD, CL_DEVICE_SIMD_PER_COMPUTE_UNIT_AMD,
+    CL_DEVICE_SIMD_WIDTH_AMD, CL_DEVICE_SINGLE_FP_CONFIG,
+    CL_DEVICE_SUB_GROUP_INDEPENDENT_FORWARD_PROGRESS, CL_DEVICE_SVM_CAPABILITIES,
+    CL_DEVICE_THREAD_TRACE_SUPPORTED_AMD, CL_DEVICE_TOPOLOGY_AMD, CL_DEVICE_TYPE,
+    CL_DEVICE_UUID_KHR, CL_DEVICE_VENDOR, CL_DEVICE_VENDOR_ID, CL_DEVICE_VERSION,
+    CL_DEVICE_WARP_SIZE_NV, CL_DEVICE_WAVEFRONT_WIDTH_AMD,
+    CL_DEVICE_WORK_GROUP_COLLECTIVE_FUNCTIONS_SUPPORT, CL_DRIVER_UUID_KHR, CL_DRIVER_VERSION,
+    CL_FALSE, CL_LUID_SIZE_KHR, CL_SUCCESS, CL_UUID_SIZE_KHR,
+};
+use std::mem;
+use std::ptr;
+
+/// An `OpenCL` device information parameter with a known value type.
+pub trait DeviceParam {
+    /// The type of the parameter value.
+    type Value;
+
+    /// The `cl_device_info` of the parameter.
+    const PARAM_NAME: cl_device_info;
+
+    /// Get the value of the parameter from a device.
+    ///
+    /// returns a Result containing the value
+    /// or the error code from the `OpenCL` C API function.
+    fn get(device: cl_device_id) -> Result<Self::Value, cl_int>;
+}
+
+/// Get a device information parameter as its Rust type.  
+/// Calls clGetDeviceInfo to get the desired information about the device.
+///
+/// * `device` - the `cl_device_id` of the `OpenCL` device.
+/// * `param` - the marker type of the parameter, e.g. `MaxComputeUnits`.
+///
+/// returns a Result containing the parameter value
+/// or the error code from the `OpenCL` C API function.
+pub fn get_device_param<P: DeviceParam>(
+    device: impl AsDevice,
+    _param: P,
+) -> Result<P::Value, cl_int> {
+    P::get(device.as_raw())
+}
+
+/// Get a fixed size value.
+/// T must be a plain old data type, i.e. an integer, handle, array or
+/// `#[repr(C)]` struct of them, which are valid when zeroed.
+fn get_value<T>(device: cl_device_id, param_name: cl_device_info) -> Result<T, cl_int> {
+    clear_error();
+    let mut data = mem::MaybeUninit::<T>::zeroed();
+    let status = unsafe {
+        clGetDeviceInfo(
+            device,
+            param_name,
+            mem::size_of::<T>(),
+            data.as_mut_ptr().cast::<c_void>(),
+            ptr::null_mut(),
+        )
+    };
+    if CL_SUCCESS == status {
+        Ok(unsafe { data.assume_init() })
+    } else {
+        Err(record_error!(status, clGetDeviceInfo, param_name))
+    }
+}
+
+fn get_bool(device: cl_device_id, param_name: cl_device_info) -> Result<bool, cl_int> {
+    let value: cl_bool = get_value(device, param_name)?;
+    Ok(CL_FALSE != value)
+}
+
+fn get_size(device: cl_device_id, param_name: cl_device_info) -> Result<size_t, cl_int> {
+    clear_error();
+    let mut size: size_t = 0;
+    let status = unsafe { clGetDeviceInfo(device, param_name, 0, ptr::null_mut(), &mut size) };
+    if CL_SUCCESS == status {
+        Ok(size)
+    } else {
+        Err(record_error!(status, clGetDeviceInfo, param_name))
+    }
+}
+
+/// Get a variable length array of values.
+/// T must be a plain old data type, see `get_value`.
+fn get_vector<T>(device: cl_device_id, param_name: cl_device_info) -> Result<Vec<T>, cl_int> {
+    clear_error();
+    let size = get_size(device, param_name)?;
+    let count = size / mem::size_of::<T>();
+    let mut data: Vec<T> = Vec::with_capacity(count);
+    if 0 < count {
+        let status = unsafe {
+            clGetDeviceInfo(
+                device,
+                param_name,
+                count * mem::size_of::<T>(),
+                data.as_mut_ptr().cast::<c_void>(),
+                ptr::null_mut(),
+            )
+        };
+        if CL_SUCCESS != status {
+            return Err(record_error!(status, clGetDeviceInfo, param_name));
+        }
+        unsafe { data.set_len(count) };
+    }
+    Ok(data)
+}
+
+fn get_string(device: cl_device_id, param_name: cl_device_info) -> Result<String, cl_int> {
+    let mut bytes: Vec<u8> = get_vector(device, param_name)?;
+    // Remove the trailing null characters
+    while bytes.last() == Some(&0) {
+        bytes.pop();
+    }
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+macro_rules! device_param {
+    ($name:ident, $param:ident, $ty:ty, $get:ident) => {
+        #[doc = concat!("The `", stringify!($param), "` device parameter.")]
+        #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+        pub struct $name;
+
+        impl DeviceParam for $name {
+            type Value = $ty;
+            const PARAM_NAME: cl_device_info = $param;
+
+            fn get(device: cl_device_id) -> Result<Self::Value, cl_int> {
+                $get(device, $param)
+            }
+        }
+    };
+}
+
+device_param!(DeviceType, CL_DEVICE_TYPE, cl_device_type, get_value);
+device_param!(VendorId, CL_DEVICE_VENDOR_ID, cl_uint, get_value);
+device_param!(
+    MaxComputeUnits,
+    CL_DEVICE_MAX_COMPUTE_UNITS,
+    cl_uint,
+    get_value
+);
+device_param!(
+    MaxWorkItemDimensions,
+    CL_DEVICE_MAX_WORK_ITEM_DIMENSIONS,
+    cl_uint,
+    get_value
+);
+device_param!(
+    MaxWorkGroupSize,
+    CL_DEVICE_MAX_WORK_GROUP_SIZE,
+    size_t,
+    get_value
+);
+device_param!(
+    MaxWorkItemSizes,
+    CL_DEVICE_MAX_WORK_ITEM_SIZES,
+    Vec<size_t>,
+    get_vector
+);
+device_param!(
+    PreferredVectorWidthChar,
+    CL_DEVICE_PREFERRED_VECTOR_WIDTH_CHAR,
+    cl_uint,
+    get_value
+);
+device_param!(
+    PreferredVectorWidthShort,
+    CL_DEVICE_PREFERRED_VECTOR_WIDTH_SHORT,
+    cl_uint,
+    get_value
+);
+device_param!(
+    PreferredVectorWidthInt,
+    CL_DEVICE_PREFERRED_VECTOR_WIDTH_INT,
+    cl_uint,
+    get_value
+);
+device_param!(
+    PreferredVectorWidthLong,
+    CL_DEVICE_PREFERRED_VECTOR_WIDTH_LONG,
+    cl_uint,
+    get_value
+);
+device_param!(
+    PreferredVectorWidthFloat,
+    CL_DEVICE_PREFERRED_VECTOR_WIDTH_FLOAT,
+    cl_uint,
+    get_value
+);
+device_param!(
+    PreferredVectorWidthDouble,
+    CL_DEVICE_PREFERRED_VECTOR_WIDTH_DOUBLE,
+    cl_uint,
+    get_value
+);
+device_param!(
+    PreferredVectorWidthHalf,
+    CL_DEVICE_PREFERRED_VECTOR_WIDTH_HALF,
+    cl_uint,
+    get_value
+);
+device_param!(
+    NativeVectorWidthChar,
+    CL_DEVICE_NATIVE_VECTOR_WIDTH_CHAR,
+    cl_uint,
+    get_value
+);
+device_param!(
+    NativeVectorWidthShort,
+    CL_DEVICE_NATIVE_VECTOR_WIDTH_SHORT,
+    cl_uint,
+    get_value
+);
+device_param!(
+    NativeVectorWidthInt,
+    CL_DEVICE_NATIVE_VECTOR_WIDTH_INT,
+    cl_uint,
+    get_value
+);
+device_param!(
+    NativeVectorWidthLong,
+    CL_DEVICE_NATIVE_VECTOR_WIDTH_LONG,
+    cl_uint,
+    get_value
+);
+device_param!(
+    NativeVectorWidthFloat,
+    CL_DEVICE_NATIVE_VECTOR_WIDTH_FLOAT,
+    cl_uint,
+    get_value
+);
+device_param!(
+    NativeVectorWidthDouble,
+    CL_DEVICE_NATIVE_VECTOR_WIDTH_DOUBLE,
+    cl_uint,
+    get_value
+);
+device_param!(
+    NativeVectorWidthHalf,
+    CL_DEVICE_NATIVE_VECTOR_WIDTH_HALF,
+    cl_uint,
+    get_value
+);
+device_param!(
+    MaxClockFrequency,
+    CL_DEVICE_MAX_CLOCK_FREQUENCY,
+    cl_uint,
+    get_value
+);
+device_param!(AddressBits, CL_DEVICE_ADDRESS_BITS, cl_uint, get_value);
+device_param!(
+    MaxMemAllocSize,
+    CL_DEVICE_MAX_MEM_ALLOC_SIZE,
+    cl_ulong,
+    get_value
+);
+device_param!(ImageSupport, CL_DEVICE_IMAGE_SUPPORT, bool, get_bool);
+device_param!(
+    MaxReadImageArgs,
+    CL_DEVICE_MAX_READ_IMAGE_ARGS,
+    cl_uint,
+    get_value
+);
+device_param!(
+    MaxWriteImageArgs,
+    CL_DEVICE_MAX_WRITE_IMAGE_ARGS,
+    cl_uint,
+    get_value
+);
+device_param!(
+    MaxReadWriteImageArgs,
+    CL_DEVICE_MAX_READ_WRITE_IMAGE_ARGS,
+    cl_uint,
+    get_value
+);
+device_param!(IlVersion, CL_DEVICE_IL_VERSION, String, get_string);
+device_param!(
+    IlsWithVersion,
+    CL_DEVICE_ILS_WITH_VERSION,
+    Vec<cl_name_version>,
+    get_vector
+);
+device_param!(
+    Image2dMaxWidth,
+    CL_DEVICE_IMAGE2D_MAX_WIDTH,
+    size_t,
+    get_value
+);
+device_param!(
+    Image2dMaxHeight,
+    CL_DEVICE_IMAGE2D_MAX_HEIGHT,
+    size_t,
+    get_value
+);
+device_param!(
+    Image3dMaxWidth,
+    CL_DEVICE_IMAGE3D_MAX_WIDTH,
+    size_t,
+    get_value
+);
+device_param!(
+    Image3dMaxHeight,
+    CL_DEVICE_IMAGE3D_MAX_HEIGHT,
+    size_t,
+    get_value
+);
+device_param!(
+    Image3dMaxDepth,
+    CL_DEVICE_IMAGE3D_MAX_DEPTH,
+    size_t,
+    get_value
+);
+device_param!(
+    ImageMaxBufferSize,
+    CL_DEVICE_IMAGE_MAX_BUFFER_SIZE,
+    size_t,
+    get_value
+);
+device_param!(
+    ImageMaxArraySize,
+    CL_DEVICE_IMAGE_MAX_ARRAY_SIZE,
+    size_t,
+    get_value
+);
+device_param!(MaxSamplers, CL_DEVICE_MAX_SAMPLERS, cl_uint, get_value);
+device_param!(
+    ImagePitchAlignment,
+    CL_DEVICE_IMAGE_PITCH_ALIGNMENT,
+    cl_uint,
+    get_value
+);
+device_param!(
+    ImageBaseAddressAlignment,
+    CL_DEVICE_IMAGE_BASE_ADDRESS_ALIGNMENT,
+    cl_uint,
+    get_value
+);
+device_param!(MaxPipeArgs, CL_DEVICE_MAX_PIPE_ARGS, cl_uint, get_value);
+device_param!(
+    PipeMaxActiveReservations,
+    CL_DEVICE_PIPE_MAX_ACTIVE_RESERVATIONS,
+    cl_uint,
+    get_value
+);
+device_param!(
+    PipeMaxPacketSize,
+    CL_DEVICE_PIPE_MAX_PACKET_SIZE,
+    cl_uint,
+    get_value
+);
+device_param!(
+    MaxParameterSize,
+    CL_DEVICE_MAX_PARAMETER_SIZE,
+    size_t,
+    get_value
+);
+device_param!(
+    MemBaseAddrAlign,
+    CL_DEVICE_MEM_BASE_ADDR_ALIGN,
+    cl_uint,
+    get_value
+);
+device_param!(
+    MinDataTypeAlignSize,
+    CL_DEVICE_MIN_DATA_TYPE_ALIGN_SIZE,
+    cl_uint,
+    get_value
+);
+device_param!(
+    SingleFpConfig,
+    CL_DEVICE_SINGLE_FP_CONFIG,
+    cl_device_fp_config,
+    get_value
+);
+device_param!(
+    DoubleFpConfig,
+    CL_DEVICE_DOUBLE_FP_CONFIG,
+    cl_device_fp_config,
+    get_value
+);
+device_param!(
+    HalfFpConfig,
+    CL_DEVICE_HALF_FP_CONFIG,
+    cl_device_fp_config,
+    get_value
+);
+device_param!(
+    GlobalMemCacheType,
+    CL_DEVICE_GLOBAL_MEM_CACHE_TYPE,
+    cl_device_mem_cache_type,
+    get_value
+);
+device_param!(
+    GlobalMemCachelineSize,
+    CL_DEVICE_GLOBAL_MEM_CACHELINE_SIZE,
+    cl_uint,
+    get_value
+);
+device_param!(
+    GlobalMemCacheSize,
+    CL_DEVICE_GLOBAL_MEM_CACHE_SIZE,
+    cl_ulong,
+    get_value
+);
+device_param!(
+    GlobalMemSize,
+    CL_DEVICE_GLOBAL_MEM_SIZE,
+    cl_ulong,
+    get_value
+);
+device_param!(
+    MaxConstantBufferSize,
+    CL_DEVICE_MAX_CONSTANT_BUFFER_SIZE,
+    cl_ulong,
+    get_value
+);
+device_param!(
+    MaxConstantArgs,
+    CL_DEVICE_MAX_CONSTANT_ARGS,
+    cl_uint,
+    get_value
+);
+device_param!(
+    MaxGlobalVariableSize,
+    CL_DEVICE_MAX_GLOBAL_VARIABLE_SIZE,
+    size_t,
+    get_value
+);
+device_param!(
+    GlobalVariablePreferredTotalSize,
+    CL_DEVICE_GLOBAL_VARIABLE_PREFERRED_TOTAL_SIZE,
+    size_t,
+    get_value
+);
+device_param!(
+    LocalMemType,
+    CL_DEVICE_LOCAL_MEM_TYPE,
+    cl_device_local_mem_type,
+    get_value
+);
+device_param!(LocalMemSize, CL_DEVICE_LOCAL_MEM_SIZE, cl_ulong, get_value);
+device_param!(
+    ErrorCorrectionSupport,
+    CL_DEVICE_ERROR_CORRECTION_SUPPORT,
+    bool,
+    get_bool
+);
+device_param!(
+    HostUnifiedMemory,
+    CL_DEVICE_HOST_UNIFIED_MEMORY,
+    bool,
+    get_bool
+);
+device_param!(
+    ProfilingTimerResolution,
+    CL_DEVICE_PROFILING_TIMER_RESOLUTION,
+    size_t,
+    get_value
+);
+device_param!(EndianLittle, CL_DEVICE_ENDIAN_LITTLE, bool, get_bool);
+device_param!(Available, CL_DEVICE_AVAILABLE, bool, get_bool);
+device_param!(
+    CompilerAvailable,
+    CL_DEVICE_COMPILER_AVAILABLE,
+    bool,
+    get_bool
+);
+device_param!(LinkerAvailable, CL_DEVICE_LINKER_AVAILABLE, bool, get_bool);
+device_param!(
+    ExecutionCapabilities,
+    CL_DEVICE_EXECUTION_CAPABILITIES,
+    cl_device_exec_capabilities,
+    get_value
+);
+device_param!(
+    QueueOnHostProperties,
+    CL_DEVICE_QUEUE_ON_HOST_PROPERTIES,
+    cl_command_queue_properties,
+    get_value
+);
+device_param!(
+    QueueOnDeviceProperties,
+    CL_DEVICE_QUEUE_ON_DEVICE_PROPERTIES,
+    cl_command_queue_properties,
+    get_value
+);
+device_param!(
+    QueueOnDevicePreferredSize,
+    CL_DEVICE_QUEUE_ON_DEVICE_PREFERRED_SIZE,
+    cl_uint,
+    get_value
+);
+device_param!(
+    QueueOnDeviceMaxSize,
+    CL_DEVICE_QUEUE_ON_DEVICE_MAX_SIZE,
+    cl_uint,
+    get_value
+);
+device_param!(
+    MaxOnDeviceQueues,
+    CL_DEVICE_MAX_ON_DEVICE_QUEUES,
+    cl_uint,
+    get_value
+);
+device_param!(
+    MaxOnDeviceEvents,
+    CL_DEVICE_MAX_ON_DEVICE_EVENTS,
+    cl_uint,
+    get_value
+);
+device_param!(
+    BuiltInKernels,
+    CL_DEVICE_BUILT_IN_KERNELS,
+    String,
+    get_string
+);
+device_param!(
+    BuiltInKernelsWithVersion,
+    CL_DEVICE_BUILT_IN_KERNELS_WITH_VERSION,
+    Vec<cl_name_version>,
+    get_vector
+);
+device_param!(Platform, CL_DEVICE_PLATFORM, cl_platform_id, get_value);
+device_param!(Name, CL_DEVICE_NAME, String, get_string);
+device_param!(Vendor, CL_DEVICE_VENDOR, String, get_string);
+device_param!(DriverVersion, CL_DRIVER_VERSION, String, get_string);
+device_param!(Profile, CL_DEVICE_PROFILE, String, get_string);
+device_param!(DeviceVersion, CL_DEVICE_VERSION, String, get_string);
+device_param!(
+    NumericVersion,
+    CL_DEVICE_NUMERIC_VERSION,
+    cl_version,
+    get_value
+);
+device_param!(
+    OpenClCVersion,
+    CL_DEVICE_OPENCL_C_VERSION,
+    String,
+    get_string
+);
+device_param!(
+    OpenClCAllVersions,
+    CL_DEVICE_OPENCL_C_ALL_VERSIONS,
+    Vec<cl_name_version>,
+    get_vector
+);
+device_param!(
+    OpenClCFeatures,
+    CL_DEVICE_OPENCL_C_FEATURES,
+    Vec<cl_name_version>,
+    get_vector
+);
+device_param!(Extensions, CL_DEVICE_EXTENSIONS, String, get_string);
+device_param!(
+    ExtensionsWithVersion,
+    CL_DEVICE_EXTENSIONS_WITH_VERSION,
+    Vec<cl_name_version>,
+    get_vector
+);
+device_param!(
+    PrintfBufferSize,
+    CL_DEVICE_PRINTF_BUFFER_SIZE,
+    size_t,
+    get_value
+);
+device_param!(
+    PreferredInteropUserSync,
+    CL_DEVICE_PREFERRED_INTEROP_USER_SYNC,
+    bool,
+    get_bool
+);
+device_param!(
+    ParentDevice,
+    CL_DEVICE_PARENT_DEVICE,
+    cl_device_id,
+    get_value
+);
+device_param!(
+    PartitionMaxSubDevices,
+    CL_DEVICE_PARTITION_MAX_SUB_DEVICES,
+    cl_uint,
+    get_value
+);
+device_param!(
+    PartitionProperties,
+    CL_DEVICE_PARTITION_PROPERTIES,
+    Vec<cl_device_partition_property>,
+    get_vector
+);
+device_param!(
+    PartitionAffinityDomain,
+    CL_DEVICE_PARTITION_AFFINITY_DOMAIN,
+    cl_device_affinity_domain,
+    get_value
+);
+device_param!(
+    PartitionType,
+    CL_DEVICE_PARTITION_TYPE,
+    Vec<cl_device_partition_property>,
+    get_vector
+);
+device_param!(
+    ReferenceCount,
+    CL_DEVICE_REFERENCE_COUNT,
+    cl_uint,
+    get_value
+);
+device_param!(
+    SvmCapabilities,
+    CL_DEVICE_SVM_CAPABILITIES,
+    cl_device_svm_capabilities,
+    get_value
+);
+device_param!(
+    PreferredPlatformAtomicAlignment,
+    CL_DEVICE_PREFERRED_PLATFORM_ATOMIC_ALIGNMENT,
+    cl_uint,
+    get_value
+);
+device_param!(
+    PreferredGlobalAtomicAlignment,
+    CL_DEVICE_PREFERRED_GLOBAL_ATOMIC_ALIGNMENT,
+    cl_uint,
+    get_value
+);
+device_param!(
+    PreferredLocalAtomicAlignment,
+    CL_DEVICE_PREFERRED_LOCAL_ATOMIC_ALIGNMENT,
+    cl_uint,
+    get_value
+);
+device_param!(
+    MaxNumSubGroups,
+    CL_DEVICE_MAX_NUM_SUB_GROUPS,
+    cl_uint,
+    get_value
+);
+device_param!(
+    SubGroupIndependentForwardProgress,
+    CL_DEVICE_SUB_GROUP_INDEPENDENT_FORWARD_PROGRESS,
+    bool,
+    get_bool
+);
+device_param!(
+    AtomicMemoryCapabilities,
+    CL_DEVICE_ATOMIC_MEMORY_CAPABILITIES,
+    cl_device_atomic_capabilities,
+    get_value
+);
+device_param!(
+    AtomicFenceCapabilities,
+    CL_DEVICE_ATOMIC_FENCE_CAPABILITIES,
+    cl_device_atomic_capabilities,
+    get_value
+);
+device_param!(
+    NonUniformWorkGroupSupport,
+    CL_DEVICE_NON_UNIFORM_WORK_GROUP_SUPPORT,
+    bool,
+    get_bool
+);
+device_param!(
+    WorkGroupCollectiveFunctionsSupport,
+    CL_DEVICE_WORK_GROUP_COLLECTIVE_FUNCTIONS_SUPPORT,
+    bool,
+    get_bool
+);
+device_param!(
+    GenericAddressSpaceSupport,
+    CL_DEVICE_GENERIC_ADDRESS_SPACE_SUPPORT,
+    bool,
+    get_bool
+);
+device_param!(
+    DeviceEnqueueCapabilities,
+    CL_DEVICE_DEVICE_ENQUEUE_CAPABILITIES,
+    cl_device_device_enqueue_capabilities,
+    get_value
+);
+device_param!(PipeSupport, CL_DEVICE_PIPE_SUPPORT, bool, get_bool);
+device_param!(
+    PreferredWorkGroupSizeMultiple,
+    CL_DEVICE_PREFERRED_WORK_GROUP_SIZE_MULTIPLE,
+    size_t,
+    get_value
+);
+device_param!(
+    LatestConformanceVersionPassed,
+    CL_DEVICE_LATEST_CONFORMANCE_VERSION_PASSED,
+    String,
+    get_string
+);
+
+// cl_khr_device_uuid
+device_param!(
+    UuidKhr,
+    CL_DEVICE_UUID_KHR,
+    [cl_uchar; CL_UUID_SIZE_KHR],
+    get_value
+);
+device_param!(
+    DriverUuidKhr,
+    CL_DRIVER_UUID_KHR,
+    [cl_uchar; CL_UUID_SIZE_KHR],
+    get_value
+);
+device_param!(LuidValidKhr, CL_DEVICE_LUID_VALID_KHR, bool, get_bool);
+device_param!(
+    LuidKhr,
+    CL_DEVICE_LUID_KHR,
+    [cl_uchar; CL_LUID_SIZE_KHR],
+    get_value
+);
+device_param!(NodeMaskKhr, CL_DEVICE_NODE_MASK_KHR, cl_uint, get_value);
+
+// cl_khr_integer_dot_product
+device_param!(
+    IntegerDotProductCapabilitiesKhr,
+    CL_DEVICE_INTEGER_DOT_PRODUCT_CAPABILITIES_KHR,
+    cl_device_integer_dot_product_capabilities_khr,
+    get_value
+);
+device_param!(
+    IntegerDotProductAccelerationProperties8BitKhr,
+    CL_DEVICE_INTEGER_DOT_PRODUCT_ACCELERATION_PROPERTIES_8BIT_KHR,
+    cl_device_integer_dot_product_acceleration_properties_khr,
+    get_value
+);
+device_param!(
+    IntegerDotProductAccelerationProperties4x8BitPackedKhr,
+    CL_DEVICE_INTEGER_DOT_PRODUCT_ACCELERATION_PROPERTIES_4x8BIT_PACKED_KHR,
+    cl_device_integer_dot_product_acceleration_properties_khr,
+    get_value
+);
+
+// cl_khr_pci_bus_info
+device_param!(
+    PciBusInfoKhr,
+    CL_DEVICE_PCI_BUS_INFO_KHR,
+    cl_device_pci_bus_info_khr,
+    get_value
+);
+
+// cl_khr_external_memory
+device_param!(
+    ExternalMemoryImportHandleTypesKhr,
+    CL_DEVICE_EXTERNAL_MEMORY_IMPORT_HANDLE_TYPES_KHR,
+    Vec<cl_external_memory_handle_type_khr>,
+    get_vector
+);
+
+// cl_khr_external_semaphore
+device_param!(
+    SemaphoreImportHandleTypesKhr,
+    CL_DEVICE_SEMAPHORE_IMPORT_HANDLE_TYPES_KHR,
+    Vec<cl_external_semaphore_handle_type_khr>,
+    get_vector
+);
+device_param!(
+    SemaphoreExportHandleTypesKhr,
+    CL_DEVICE_SEMAPHORE_EXPORT_HANDLE_TYPES_KHR,
+    Vec<cl_external_semaphore_handle_type_khr>,
+    get_vector
+);
+
+// cl_khr_semaphore
+device_param!(
+    SemaphoreTypesKhr,
+    CL_DEVICE_SEMAPHORE_TYPES_KHR,
+    Vec<cl_semaphore_type_khr>,
+    get_vector
+);
+
+// cl_khr_command_buffer
+device_param!(
+    CommandBufferCapabilitiesKhr,
+    CL_DEVICE_COMMAND_BUFFER_CAPABILITIES_KHR,
+    cl_device_command_buffer_capabilities_khr,
+    get_value
+);
+device_param!(
+    CommandBufferRequiredQueuePropertiesKhr,
+    CL_DEVICE_COMMAND_BUFFER_REQUIRED_QUEUE_PROPERTIES_KHR,
+    cl_command_queue_properties,
+    get_value
+);
+
+// cl_nv_device_attribute_query
+device_param!(
+    ComputeCapabilityMajorNv,
+    CL_DEVICE_COMPUTE_CAPABILITY_MAJOR_NV,
+    cl_uint,
+    get_value
+);
+device_param!(
+    ComputeCapabilityMinorNv,
+    CL_DEVICE_COMPUTE_CAPABILITY_MINOR_NV,
+    cl_uint,
+    get_value
+);
+device_param!(
+    RegistersPerBlockNv,
+    CL_DEVICE_REGISTERS_PER_BLOCK_NV,
+    cl_uint,
+    get_value
+);
+device_param!(WarpSizeNv, CL_DEVICE_WARP_SIZE_NV, cl_uint, get_value);
+device_param!(GpuOverlapNv, CL_DEVICE_GPU_OVERLAP_NV, bool, get_bool);
+device_param!(
+    KernelExecTimeoutNv,
+    CL_DEVICE_KERNEL_EXEC_TIMEOUT_NV,
+    bool,
+    get_bool
+);
+device_param!(
+    IntegratedMemoryNv,
+    CL_DEVICE_INTEGRATED_MEMORY_NV,
+    bool,
+    get_bool
+);
+device_param!(PciBusIdNv, CL_DEVICE_PCI_BUS_ID_NV, cl_uint, get_value);
+device_param!(PciSlotIdNv, CL_DEVICE_PCI_SLOT_ID_NV, cl_uint, get_value);
+
+// cl_amd_device_attribute_query
+device_param!(
+    ProfilingTimerOffsetAmd,
+    CL_DEVICE_PROFILING_TIMER_OFFSET_AMD,
+    cl_ulong,
+    get_value
+);
+device_param!(
+    TopologyAmd,
+    CL_DEVICE_TOPOLOGY_AMD,
+    cl_amd_device_topology,
+    get_value
+);
+device_param!(BoardNameAmd, CL_DEVICE_BOARD_NAME_AMD, String, get_string);
+device_param!(
+    GlobalFreeMemoryAmd,
+    CL_DEVICE_GLOBAL_FREE_MEMORY_AMD,
+    Vec<size_t>,
+    get_vector
+);
+device_param!(
+    SimdPerComputeUnitAmd,
+    CL_DEVICE_SIMD_PER_COMPUTE_UNIT_AMD,
+    cl_uint,
+    get_value
+);
+device_param!(SimdWidthAmd, CL_DEVICE_SIMD_WIDTH_AMD, cl_uint, get_value);
+device_param!(
+    SimdInstructionWidthAmd,
+    CL_DEVICE_SIMD_INSTRUCTION_WIDTH_AMD,
+    cl_uint,
+    get_value
+);
+device_param!(
+    WavefrontWidthAmd,
+    CL_DEVICE_WAVEFRONT_WIDTH_AMD,
+    cl_uint,
+    get_value
+);
+device_param!(
+    GlobalMemChannelsAmd,
+    CL_DEVICE_GLOBAL_MEM_CHANNELS_AMD,
+    cl_uint,
+    get_value
+);
+device_param!(
+    GlobalMemChannelBanksAmd,
+    CL_DEVICE_GLOBAL_MEM_CHANNEL_BANKS_AMD,
+    cl_uint,
+    get_value
+);
+device_param!(
+    GlobalMemChannelBankWidthAmd,
+    CL_DEVICE_GLOBAL_MEM_CHANNEL_BANK_WIDTH_AMD,
+    cl_uint,
+    get_value
+);
+device_param!(
+    LocalMemSizePerComputeUnitAmd,
+    CL_DEVICE_LOCAL_MEM_SIZE_PER_COMPUTE_UNIT_AMD,
+    cl_uint,
+    get_value
+);
+device_param!(
+    LocalMemBanksAmd,
+    CL_DEVICE_LOCAL_MEM_BANKS_AMD,
+    cl_uint,
+    get_value
+);
+device_param!(
+    ThreadTraceSupportedAmd,
+    CL_DEVICE_THREAD_TRACE_SUPPORTED_AMD,
+    bool,
+    get_bool
+);
+device_param!(GfxipMajorAmd, CL_DEVICE_GFXIP_MAJOR_AMD, cl_uint, get_value);
+device_param!(GfxipMinorAmd, CL_DEVICE_GFXIP_MINOR_AMD, cl_uint, get_value);
+device_param!(
+    AvailableAsyncQueuesAmd,
+    CL_DEVICE_AVAILABLE_ASYNC_QUEUES_AMD,
+    cl_uint,
+    get_value
+);
+device_param!(
+    PreferredWorkGroupSizeAmd,
+    CL_DEVICE_PREFERRED_WORK_GROUP_SIZE_AMD,
+    size_t,
+    get_value
+);
+device_param!(
+    MaxWorkGroupSizeAmd,
+    CL_DEVICE_MAX_WORK_GROUP_SIZE_AMD,
+    size_t,
+    get_value
+);
+device_param!(
+    PreferredConstantBufferSizeAmd,
+    CL_DEVICE_PREFERRED_CONSTANT_BUFFER_SIZE_AMD,
+    size_t,
+    get_value
+);
+device_param!(PcieIdAmd, CL_DEVICE_PCIE_ID_AMD, cl_uint, get_value);
+
+// cl_intel_device_attribute_query
+device_param!(
+    IpVersionIntel,
+    CL_DEVICE_IP_VERSION_INTEL,
+    cl_version_khr,
+    get_value
+);
+device_param!(IdIntel, CL_DEVICE_ID_INTEL, cl_uint, get_value);
+device_param!(
+    NumSlicesIntel,
+    CL_DEVICE_NUM_SLICES_INTEL,
+    cl_uint,
+    get_value
+);
+device_param!(
+    NumSubSlicesPerSliceIntel,
+    CL_DEVICE_NUM_SUB_SLICES_PER_SLICE_INTEL,
+    cl_uint,
+    get_value
+);
+device_param!(
+    NumEusPerSubSliceIntel,
+    CL_DEVICE_NUM_EUS_PER_SUB_SLICE_INTEL,
+    cl_uint,
+    get_value
+);
+device_param!(
+    NumThreadsPerEuIntel,
+    CL_DEVICE_NUM_THREADS_PER_EU_INTEL,
+    cl_uint,
+    get_value
+);
+device_param!(
+    FeatureCapabilitiesIntel,
+    CL_DEVICE_FEATURE_CAPABILITIES_INTEL,
+    cl_device_feature_capabilities_intel,
+    get_value
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device::{get_device_ids, get_device_info, CL_DEVICE_TYPE_GPU};
+    use crate::platform::get_platform_ids;
+
+    #[test]
+    fn test_get_device_param() {
+        let platform_ids = get_platform_ids().unwrap();
+        assert!(0 < platform_ids.len());
+
+        // Choose the first platform
+        let platform_id = platform_ids[0];
+
+        let device_ids = get_device_ids(platform_id, CL_DEVICE_TYPE_GPU).unwrap();
+        assert!(0 < device_ids.len());
+
+        let device_id = device_ids[0];
+
+        let value = get_device_param(device_id, DeviceType).unwrap();
+        assert_eq!(CL_DEVICE_TYPE_GPU, value);
+
+        let value = get_device_param(device_id, Name).unwrap();
+        println!("CL_DEVICE_NAME: {}", value);
+        assert_eq!(
+            String::from(get_device_info(device_id, CL_DEVICE_NAME).unwrap()),
+            value
+        );
+
+        let value = get_device_param(device_id, MaxComputeUnits).unwrap();
+        println!("CL_DEVICE_MAX_COMPUTE_UNITS: {}", value);
+        assert!(0 < value);
+
+        let dimensions = get_device_param(device_id, MaxWorkItemDimensions).unwrap();
+        let value = get_device_param(device_id, MaxWorkItemSizes).unwrap();
+        println!("CL_DEVICE_MAX_WORK_ITEM_SIZES: {:?}", value);
+        assert_eq!(dimensions as usize, value.len());
+
+        let value = get_device_param(device_id, Available).unwrap();
+        println!("CL_DEVICE_AVAILABLE: {}", value);
+        assert!(value);
+
+        let value = get_device_param(device_id, Platform).unwrap();
+        assert_eq!(platform_id, value);
+
+        assert_eq!(CL_DEVICE_NAME, <Name as DeviceParam>::PARAM_NAME);
+    }
+}
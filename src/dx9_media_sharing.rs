@@ -19,11 +19,31 @@
 #![allow(non_camel_case_types)]
 #![allow(clippy::missing_safety_doc)]
 
+#[cfg(all(
+    any(
+        feature = "cl_khr_dx9_media_sharing",
+        feature = "cl_intel_dx9_media_sharing"
+    ),
+    any(feature = "dynamic", feature = "mock")
+))]
+pub use super::ffi::clGetSupportedDX9MediaSurfaceFormatsINTEL;
+#[cfg(all(
+    feature = "cl_intel_dx9_media_sharing",
+    any(feature = "dynamic", feature = "mock")
+))]
+pub use super::ffi::{
+    clCreateFromDX9MediaSurfaceINTEL, clEnqueueAcquireDX9ObjectsINTEL,
+    clEnqueueReleaseDX9ObjectsINTEL, clGetDeviceIDsFromDX9INTEL,
+};
 pub use opencl_sys::cl_dx9_media_sharing::*;
 pub use opencl_sys::{
     cl_context, cl_int, cl_mem_flags, cl_mem_object_type, cl_uint, CL_INVALID_VALUE, CL_SUCCESS,
 };
 
+#[allow(unused_imports)]
+use super::api_error::{clear_error, record_error};
+#[allow(unused_imports)]
+use super::owned::{AsCommandQueue, AsContext};
 #[allow(unused_imports)]
 use libc::c_void;
 #[allow(unused_imports)]
@@ -36,6 +56,7 @@ pub unsafe fn get_device_ids_from_dx9_intel(
     dx9_object: *mut c_void,
     dx9_device_set: cl_dx9_device_set_intel,
 ) -> Result<Vec<cl_device_id>, cl_int> {
+    clear_error();
     let mut count: cl_uint = 0;
     let status: cl_int = clGetDeviceIDsFromDX9INTEL(
         platform,
@@ -47,7 +68,7 @@ pub unsafe fn get_device_ids_from_dx9_intel(
         &mut count,
     );
     if CL_SUCCESS != status {
-        Err(status)
+        Err(record_error!(status, clGetDeviceIDsFromDX9INTEL))
     } else if 0 < count {
         // Get the device ids.
         let len = count as usize;
@@ -64,7 +85,7 @@ pub unsafe fn get_device_ids_from_dx9_intel(
         if CL_SUCCESS == status {
             Ok(ids)
         } else {
-            Err(status)
+            Err(record_error!(status, clGetDeviceIDsFromDX9INTEL))
         }
     } else {
         Ok(Vec::default())
@@ -73,12 +94,14 @@ pub unsafe fn get_device_ids_from_dx9_intel(
 
 #[cfg(feature = "cl_intel_dx9_media_sharing")]
 pub unsafe fn create_from_dx9_media_surface_intel(
-    context: cl_context,
+    context: impl AsContext,
     flags: cl_mem_flags,
     resource: IDirect3DSurface9_ptr,
     shared_handle: HANDLE,
     plane: cl_uint,
 ) -> Result<cl_mem, cl_int> {
+    let context = context.as_raw();
+    clear_error();
     let mut status: cl_int = CL_INVALID_VALUE;
     let mem = clCreateFromDX9MediaSurfaceINTEL(
         context,
@@ -91,18 +114,25 @@ pub unsafe fn create_from_dx9_media_surface_intel(
     if CL_SUCCESS == status {
         Ok(mem)
     } else {
-        Err(status)
+        Err(record_error!(
+            status,
+            clCreateFromDX9MediaSurfaceINTEL,
+            flags,
+            plane
+        ))
     }
 }
 
 #[cfg(feature = "cl_intel_dx9_media_sharing")]
 pub unsafe fn enqueue_acquire_dx9_objects_intel(
-    command_queue: cl_command_queue,
+    command_queue: impl AsCommandQueue,
     num_objects: cl_uint,
     mem_objects: *const cl_mem,
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
+    let command_queue = command_queue.as_raw();
+    clear_error();
     let mut event: cl_event = ptr::null_mut();
     let status: cl_int = clEnqueueAcquireDX9ObjectsINTEL(
         command_queue,
@@ -115,18 +145,20 @@ pub unsafe fn enqueue_acquire_dx9_objects_intel(
     if CL_SUCCESS == status {
         Ok(event)
     } else {
-        Err(status)
+        Err(record_error!(status, clEnqueueAcquireDX9ObjectsINTEL))
     }
 }
 
 #[cfg(feature = "cl_intel_dx9_media_sharing")]
 pub unsafe fn enqueue_release_dx9_objects_intel(
-    command_queue: cl_command_queue,
+    command_queue: impl AsCommandQueue,
     num_objects: cl_uint,
     mem_objects: *const cl_mem,
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
+    let command_queue = command_queue.as_raw();
+    clear_error();
     let mut event: cl_event = ptr::null_mut();
     let status: cl_int = clEnqueueReleaseDX9ObjectsINTEL(
         command_queue,
@@ -139,7 +171,7 @@ pub unsafe fn enqueue_release_dx9_objects_intel(
     if CL_SUCCESS == status {
         Ok(event)
     } else {
-        Err(status)
+        Err(record_error!(status, clEnqueueReleaseDX9ObjectsINTEL))
     }
 }
 
@@ -148,11 +180,13 @@ pub unsafe fn enqueue_release_dx9_objects_intel(
     feature = "cl_intel_dx9_media_sharing"
 ))]
 pub unsafe fn get_supported_dx9_media_surface_formats_intel(
-    context: cl_context,
+    context: impl AsContext,
     flags: cl_mem_flags,
     image_type: cl_mem_object_type,
     plane: cl_uint,
 ) -> Result<Vec<cl_uint>, cl_int> {
+    let context = context.as_raw();
+    clear_error();
     let mut count: cl_uint = 0;
     let status: cl_int = clGetSupportedDX9MediaSurfaceFormatsINTEL(
         context,
@@ -164,7 +198,13 @@ pub unsafe fn get_supported_dx9_media_surface_formats_intel(
         &mut count,
     );
     if CL_SUCCESS != status {
-        Err(status)
+        Err(record_error!(
+            status,
+            clGetSupportedDX9MediaSurfaceFormatsINTEL,
+            flags,
+            image_type,
+            plane
+        ))
     } else if 0 < count {
         // Get the dx9 formats.
         let len = count as usize;
@@ -181,7 +221,13 @@ pub unsafe fn get_supported_dx9_media_surface_formats_intel(
         if CL_SUCCESS == status {
             Ok(ids)
         } else {
-            Err(status)
+            Err(record_error!(
+                status,
+                clGetSupportedDX9MediaSurfaceFormatsINTEL,
+                flags,
+                image_type,
+                plane
+            ))
         }
     } else {
         Ok(Vec::default())
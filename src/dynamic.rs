@@ -0,0 +1,1918 @@
+// Copyright (c) 2024 Via Technology Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Run time loading of the `OpenCL` ICD loader library.
+//!
+//! When the "dynamic" feature is enabled, the adapter modules call the
+//! `OpenCL` C API and extension functions defined in this module instead of
+//! the functions linked from `opencl-sys`.
+//! The functions are resolved from the `OpenCL` library the first time that
+//! one of them is called, or when [`load_library`] is called with the path of
+//! a specific `OpenCL` library.
+//! Extension functions that the library does not export are resolved with
+//! `clGetExtensionFunctionAddress`.
+//!
+//! If the `OpenCL` library cannot be loaded, the functions return
+//! [`CL_LIBRARY_NOT_LOADED`] and the library is loaded again by the next call.
+//! If the `OpenCL` library does not provide a function, e.g. an `OpenCL 3.0`
+//! function in an `OpenCL 1.2` ICD loader, the function returns
+//! [`CL_SYMBOL_NOT_AVAILABLE`].
+//! Functions that return an `OpenCL` object set `errcode_ret` to the error
+//! and return a null object.
+
+#![allow(non_snake_case, non_camel_case_types)]
+#![allow(clippy::missing_safety_doc, clippy::too_many_arguments)]
+
+pub use super::error_codes::{CL_LIBRARY_NOT_LOADED, CL_SYMBOL_NOT_AVAILABLE};
+
+#[allow(unused_imports)]
+use libc::c_int;
+use libc::{c_char, c_uchar, c_void, size_t};
+use libloading::Library;
+use opencl_sys::{
+    cl_GLenum, cl_GLint, cl_GLsync, cl_GLuint, cl_addressing_mode, cl_bool, cl_buffer_create_type,
+    cl_command_queue, cl_command_queue_info, cl_command_queue_properties, cl_context,
+    cl_context_info, cl_context_properties, cl_device_id, cl_device_info,
+    cl_device_partition_property, cl_device_type, cl_event, cl_event_info, cl_filter_mode,
+    cl_gl_context_info, cl_gl_object_type, cl_gl_texture_info, cl_image_desc, cl_image_format,
+    cl_image_info, cl_int, cl_kernel, cl_kernel_arg_info, cl_kernel_info,
+    cl_kernel_work_group_info, cl_map_flags, cl_mem, cl_mem_flags, cl_mem_info,
+    cl_mem_migration_flags, cl_mem_object_type, cl_platform_id, cl_platform_info,
+    cl_profiling_info, cl_program, cl_program_build_info, cl_program_info, cl_sampler,
+    cl_sampler_info, cl_uint,
+};
+#[allow(unused_imports)]
+use opencl_sys::{
+    cl_accelerator_info_intel, cl_accelerator_intel, cl_accelerator_type_intel,
+    cl_command_buffer_info_khr, cl_command_buffer_khr, cl_command_buffer_properties_khr,
+    cl_device_partition_property_ext, cl_external_semaphore_handle_type_khr, cl_icdl_info,
+    cl_image_pitch_info_qcom, cl_image_requirements_info_ext, cl_import_properties_arm,
+    cl_kernel_exec_info_arm, cl_mem_advice_intel, cl_mem_info_intel, cl_mem_migration_flags_ext,
+    cl_mem_properties_intel, cl_mipmap_filter_mode_img, cl_mutable_base_config_khr,
+    cl_mutable_command_info_khr, cl_mutable_command_khr, cl_ndrange_kernel_command_properties_khr,
+    cl_queue_properties_khr, cl_semaphore_info_khr, cl_semaphore_khr, cl_semaphore_payload_khr,
+    cl_semaphore_properties_khr, cl_semaphore_reimport_properties_khr, cl_svm_mem_flags_arm,
+    cl_sync_point_khr, DXGI_FORMAT,
+};
+#[allow(unused_imports)]
+use opencl_sys::{
+    cl_dx9_media_sharing::{
+        cl_dx9_device_set_intel, cl_dx9_device_source_intel, IDirect3DSurface9_ptr, D3DFORMAT,
+        HANDLE,
+    },
+    cl_egl::{cl_egl_image_properties_khr, CLeglDisplayKHR, CLeglImageKHR, CLeglSyncKHR},
+    cl_icd::cl_icd_dispatch,
+    cl_layer::cl_layer_info,
+};
+#[allow(unused_imports)]
+use opencl_sys::{
+    cl_kernel_exec_info, cl_kernel_sub_group_info, cl_mem_properties, cl_pipe_info,
+    cl_pipe_properties, cl_queue_properties, cl_sampler_properties, cl_svm_mem_flags, cl_ulong,
+};
+use std::ffi::OsStr;
+use std::mem;
+use std::ptr;
+use std::sync::OnceLock;
+
+/// The default names of the `OpenCL` ICD loader library, in search order.
+#[cfg(target_os = "windows")]
+const DEFAULT_LIBRARY_NAMES: &[&str] = &["OpenCL.dll"];
+#[cfg(target_os = "macos")]
+const DEFAULT_LIBRARY_NAMES: &[&str] = &["/System/Library/Frameworks/OpenCL.framework/OpenCL"];
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const DEFAULT_LIBRARY_NAMES: &[&str] = &["libOpenCL.so.1", "libOpenCL.so"];
+
+/// The loaded `OpenCL` library and its function table.
+struct OpenClLibrary {
+    functions: Functions,
+    // Must outlive the function pointers in `functions`.
+    _library: Library,
+}
+
+static OPENCL_LIBRARY: OnceLock<OpenClLibrary> = OnceLock::new();
+
+/// The `clGetExtensionFunctionAddress` function, which `opencl-sys` declares
+/// without its return type.
+type GetExtensionFunctionAddress =
+    unsafe extern "system" fn(func_name: *const c_char) -> *mut c_void;
+
+/// Get the address of a function that the `OpenCL` library does not export
+/// from `clGetExtensionFunctionAddress`, if the library provides it.
+unsafe fn extension_function<F: Copy>(
+    get_address: Option<GetExtensionFunctionAddress>,
+    name: &[u8],
+) -> Option<F> {
+    let address = get_address.map_or(ptr::null_mut(), |get_address| {
+        get_address(name.as_ptr().cast::<c_char>())
+    });
+    (!address.is_null()).then(|| mem::transmute_copy::<*mut c_void, F>(&address))
+}
+
+/// Load an `OpenCL` library and resolve its functions.
+fn open_library(path: &OsStr) -> Result<OpenClLibrary, cl_int> {
+    let library = unsafe { Library::new(path) }.map_err(|_| CL_LIBRARY_NOT_LOADED)?;
+    let functions = unsafe { Functions::load(&library) };
+    Ok(OpenClLibrary {
+        functions,
+        _library: library,
+    })
+}
+
+/// Load the first of the default `OpenCL` libraries that can be opened.
+fn open_default_library() -> Result<OpenClLibrary, cl_int> {
+    DEFAULT_LIBRARY_NAMES
+        .iter()
+        .find_map(|name| open_library(OsStr::new(name)).ok())
+        .ok_or(CL_LIBRARY_NOT_LOADED)
+}
+
+/// Get the function table, loading the default `OpenCL` library if required.
+/// A library that fails to load is not stored, so it is loaded again by the
+/// next call.
+fn functions() -> Result<&'static Functions, cl_int> {
+    if let Some(library) = OPENCL_LIBRARY.get() {
+        return Ok(&library.functions);
+    }
+    let library = open_default_library()?;
+    Ok(&OPENCL_LIBRARY.get_or_init(|| library).functions)
+}
+
+/// Load the `OpenCL` library from `path` instead of the default `OpenCL` ICD
+/// loader library.
+/// It must be called before any other `OpenCL` function, since the default
+/// library is loaded by the first `OpenCL` function call.
+///
+/// * `path` - the file name or path of the `OpenCL` library.
+///
+/// returns an empty Result or `CL_LIBRARY_NOT_LOADED` if the library could
+/// not be loaded, or `CL_INVALID_OPERATION` if a library has already been loaded.
+/// If the library could not be loaded, another library can be loaded or the
+/// default library is loaded by the next `OpenCL` function call.
+pub fn load_library<P: AsRef<OsStr>>(path: P) -> Result<(), cl_int> {
+    if OPENCL_LIBRARY.get().is_some() {
+        return Err(opencl_sys::CL_INVALID_OPERATION);
+    }
+    let library = open_library(path.as_ref())?;
+    OPENCL_LIBRARY
+        .set(library)
+        .map_err(|_| opencl_sys::CL_INVALID_OPERATION)
+}
+
+/// Determine whether an `OpenCL` library has been loaded, loading the default
+/// `OpenCL` ICD loader library if required.
+/// An application can call this function to decide whether to use `OpenCL`
+/// or to fall back to another implementation.
+///
+/// returns true if an `OpenCL` library is loaded, false otherwise.
+#[must_use]
+pub fn is_library_loaded() -> bool {
+    functions().is_ok()
+}
+
+/// Helper function for functions that return an `OpenCL` object:
+/// sets `errcode_ret`, if present, and returns a null pointer.
+unsafe fn object_error<T>(errcode_ret: *mut cl_int, error: cl_int) -> *mut T {
+    if !errcode_ret.is_null() {
+        *errcode_ret = error;
+    }
+    ptr::null_mut()
+}
+
+/// A macro to declare the `OpenCL` function table and the functions that call it.
+/// The optional identifier in brackets after the return type is either the
+/// name of the `errcode_ret` parameter of a function that returns a pointer,
+/// or empty for a function that returns a pointer or nothing without an error code.
+macro_rules! opencl_functions {
+    ($(
+        $(#[$meta:meta])*
+        fn $name:ident($($arg:ident: $ty:ty),* $(,)?) -> $ret:ty $([$($errcode_ret:ident)?])?;
+    )*) => {
+        /// The `OpenCL` API function pointers resolved from the `OpenCL` library.
+        struct Functions {
+            $(
+                $(#[$meta])*
+                $name: Option<unsafe extern "system" fn($($ty),*) -> $ret>,
+            )*
+        }
+
+        impl Functions {
+            unsafe fn load(library: &Library) -> Self {
+                let get_address = library
+                    .get::<GetExtensionFunctionAddress>(b"clGetExtensionFunctionAddress\0")
+                    .ok()
+                    .map(|symbol| *symbol);
+                Self {
+                    $(
+                        $(#[$meta])*
+                        $name: {
+                            let name = concat!(stringify!($name), "\0").as_bytes();
+                            library
+                                .get::<unsafe extern "system" fn($($ty),*) -> $ret>(name)
+                                .ok()
+                                .map(|symbol| *symbol)
+                                .or_else(|| extension_function(get_address, name))
+                        },
+                    )*
+                }
+            }
+        }
+
+        $(
+            $(#[$meta])*
+            #[inline]
+            pub unsafe fn $name($($arg: $ty),*) -> $ret {
+                let error = match functions() {
+                    Ok(functions) => match functions.$name {
+                        Some(function) => return function($($arg),*),
+                        None => CL_SYMBOL_NOT_AVAILABLE,
+                    },
+                    Err(error) => error,
+                };
+                opencl_functions!(@error error $([$($errcode_ret)?])?)
+            }
+        )*
+    };
+
+    (@error $error:ident) => {
+        $error
+    };
+    (@error $error:ident [$errcode_ret:ident]) => {
+        object_error($errcode_ret, $error)
+    };
+    (@error $error:ident []) => {{
+        let _ = $error;
+        Default::default()
+    }};
+}
+
+opencl_functions! {
+    // Platform API
+    fn clGetPlatformIDs(
+        num_entries: cl_uint,
+        platforms: *mut cl_platform_id,
+        num_platforms: *mut cl_uint,
+    ) -> cl_int;
+    fn clGetPlatformInfo(
+        platform: cl_platform_id,
+        param_name: cl_platform_info,
+        param_value_size: size_t,
+        param_value: *mut c_void,
+        param_value_size_ret: *mut size_t,
+    ) -> cl_int;
+    fn clUnloadPlatformCompiler(platform: cl_platform_id) -> cl_int;
+
+    // Device APIs
+    fn clGetDeviceIDs(
+        platform: cl_platform_id,
+        device_type: cl_device_type,
+        num_entries: cl_uint,
+        devices: *mut cl_device_id,
+        num_devices: *mut cl_uint,
+    ) -> cl_int;
+    fn clGetDeviceInfo(
+        device: cl_device_id,
+        param_name: cl_device_info,
+        param_value_size: size_t,
+        param_value: *mut c_void,
+        param_value_size_ret: *mut size_t,
+    ) -> cl_int;
+    fn clCreateSubDevices(
+        in_device: cl_device_id,
+        properties: *const cl_device_partition_property,
+        num_devices: cl_uint,
+        out_devices: *mut cl_device_id,
+        num_devices_ret: *mut cl_uint,
+    ) -> cl_int;
+    fn clRetainDevice(device: cl_device_id) -> cl_int;
+    fn clReleaseDevice(device: cl_device_id) -> cl_int;
+    #[cfg(feature = "CL_VERSION_2_1")]
+    fn clSetDefaultDeviceCommandQueue(
+        context: cl_context,
+        device: cl_device_id,
+        command_queue: cl_command_queue,
+    ) -> cl_int;
+    #[cfg(feature = "CL_VERSION_2_1")]
+    fn clGetDeviceAndHostTimer(
+        device: cl_device_id,
+        device_timestamp: *mut cl_ulong,
+        host_timestamp: *mut cl_ulong,
+    ) -> cl_int;
+    #[cfg(feature = "CL_VERSION_2_1")]
+    fn clGetHostTimer(device: cl_device_id, host_timestamp: *mut cl_ulong) -> cl_int;
+
+    // Context APIs
+    fn clCreateContext(
+        properties: *const cl_context_properties,
+        num_devices: cl_uint,
+        devices: *const cl_device_id,
+        pfn_notify: Option<
+            unsafe extern "C" fn(
+                errinfo: *const c_char,
+                private_info: *const c_void,
+                cb: size_t,
+                user_data: *mut c_void,
+            ),
+        >,
+        user_data: *mut c_void,
+        errcode_ret: *mut cl_int,
+    ) -> cl_context [errcode_ret];
+    fn clCreateContextFromType(
+        properties: *const cl_context_properties,
+        device_type: cl_device_type,
+        pfn_notify: Option<
+            unsafe extern "C" fn(
+                errinfo: *const c_char,
+                private_info: *const c_void,
+                cb: size_t,
+                user_data: *mut c_void,
+            ),
+        >,
+        user_data: *mut c_void,
+        errcode_ret: *mut cl_int,
+    ) -> cl_context [errcode_ret];
+    fn clRetainContext(context: cl_context) -> cl_int;
+    fn clReleaseContext(context: cl_context) -> cl_int;
+    fn clGetContextInfo(
+        context: cl_context,
+        param_name: cl_context_info,
+        param_value_size: size_t,
+        param_value: *mut c_void,
+        param_value_size_ret: *mut size_t,
+    ) -> cl_int;
+    #[cfg(feature = "CL_VERSION_3_0")]
+    fn clSetContextDestructorCallback(
+        context: cl_context,
+        pfn_notify: Option<unsafe extern "C" fn(context: cl_context, user_data: *mut c_void)>,
+        user_data: *mut c_void,
+    ) -> cl_int;
+
+    // Command Queue APIs
+    fn clCreateCommandQueue(
+        context: cl_context,
+        device: cl_device_id,
+        properties: cl_command_queue_properties,
+        errcode_ret: *mut cl_int,
+    ) -> cl_command_queue [errcode_ret];
+    #[cfg(feature = "CL_VERSION_2_0")]
+    fn clCreateCommandQueueWithProperties(
+        context: cl_context,
+        device: cl_device_id,
+        properties: *const cl_queue_properties,
+        errcode_ret: *mut cl_int,
+    ) -> cl_command_queue [errcode_ret];
+    fn clRetainCommandQueue(command_queue: cl_command_queue) -> cl_int;
+    fn clReleaseCommandQueue(command_queue: cl_command_queue) -> cl_int;
+    fn clGetCommandQueueInfo(
+        command_queue: cl_command_queue,
+        param_name: cl_command_queue_info,
+        param_value_size: size_t,
+        param_value: *mut c_void,
+        param_value_size_ret: *mut size_t,
+    ) -> cl_int;
+    fn clFlush(command_queue: cl_command_queue) -> cl_int;
+    fn clFinish(command_queue: cl_command_queue) -> cl_int;
+
+    // Enqueued Commands APIs
+    fn clEnqueueReadBuffer(
+        command_queue: cl_command_queue,
+        buffer: cl_mem,
+        blocking_read: cl_bool,
+        offset: size_t,
+        size: size_t,
+        ptr: *mut c_void,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    fn clEnqueueReadBufferRect(
+        command_queue: cl_command_queue,
+        buffer: cl_mem,
+        blocking_read: cl_bool,
+        buffer_origin: *const size_t,
+        host_origin: *const size_t,
+        region: *const size_t,
+        buffer_row_pitch: size_t,
+        buffer_slice_pitch: size_t,
+        host_row_pitch: size_t,
+        host_slice_pitch: size_t,
+        ptr: *mut c_void,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    fn clEnqueueWriteBuffer(
+        command_queue: cl_command_queue,
+        buffer: cl_mem,
+        blocking_write: cl_bool,
+        offset: size_t,
+        size: size_t,
+        ptr: *const c_void,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    fn clEnqueueWriteBufferRect(
+        command_queue: cl_command_queue,
+        buffer: cl_mem,
+        blocking_write: cl_bool,
+        buffer_origin: *const size_t,
+        host_origin: *const size_t,
+        region: *const size_t,
+        buffer_row_pitch: size_t,
+        buffer_slice_pitch: size_t,
+        host_row_pitch: size_t,
+        host_slice_pitch: size_t,
+        ptr: *const c_void,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    fn clEnqueueFillBuffer(
+        command_queue: cl_command_queue,
+        buffer: cl_mem,
+        pattern: *const c_void,
+        pattern_size: size_t,
+        offset: size_t,
+        size: size_t,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    fn clEnqueueCopyBuffer(
+        command_queue: cl_command_queue,
+        src_buffer: cl_mem,
+        dst_buffer: cl_mem,
+        src_offset: size_t,
+        dst_offset: size_t,
+        size: size_t,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    fn clEnqueueCopyBufferRect(
+        command_queue: cl_command_queue,
+        src_buffer: cl_mem,
+        dst_buffer: cl_mem,
+        src_origin: *const size_t,
+        dst_origin: *const size_t,
+        region: *const size_t,
+        src_row_pitch: size_t,
+        src_slice_pitch: size_t,
+        dst_row_pitch: size_t,
+        dst_slice_pitch: size_t,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    fn clEnqueueReadImage(
+        command_queue: cl_command_queue,
+        image: cl_mem,
+        blocking_read: cl_bool,
+        origin: *const size_t,
+        region: *const size_t,
+        row_pitch: size_t,
+        slice_pitch: size_t,
+        ptr: *mut c_void,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    fn clEnqueueWriteImage(
+        command_queue: cl_command_queue,
+        image: cl_mem,
+        blocking_write: cl_bool,
+        origin: *const size_t,
+        region: *const size_t,
+        input_row_pitch: size_t,
+        input_slice_pitch: size_t,
+        ptr: *const c_void,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    fn clEnqueueFillImage(
+        command_queue: cl_command_queue,
+        image: cl_mem,
+        fill_color: *const c_void,
+        origin: *const size_t,
+        region: *const size_t,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    fn clEnqueueCopyImage(
+        command_queue: cl_command_queue,
+        src_image: cl_mem,
+        dst_image: cl_mem,
+        src_origin: *const size_t,
+        dst_origin: *const size_t,
+        region: *const size_t,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    fn clEnqueueCopyImageToBuffer(
+        command_queue: cl_command_queue,
+        src_image: cl_mem,
+        dst_buffer: cl_mem,
+        src_origin: *const size_t,
+        region: *const size_t,
+        dst_offset: size_t,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    fn clEnqueueCopyBufferToImage(
+        command_queue: cl_command_queue,
+        src_buffer: cl_mem,
+        dst_image: cl_mem,
+        src_offset: size_t,
+        dst_origin: *const size_t,
+        region: *const size_t,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    fn clEnqueueMapBuffer(
+        command_queue: cl_command_queue,
+        buffer: cl_mem,
+        blocking_map: cl_bool,
+        map_flags: cl_map_flags,
+        offset: size_t,
+        size: size_t,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+        errcode_ret: *mut cl_int,
+    ) -> *mut c_void [errcode_ret];
+    fn clEnqueueMapImage(
+        command_queue: cl_command_queue,
+        image: cl_mem,
+        blocking_map: cl_bool,
+        map_flags: cl_map_flags,
+        origin: *const size_t,
+        region: *const size_t,
+        image_row_pitch: *mut size_t,
+        image_slice_pitch: *mut size_t,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+        errcode_ret: *mut cl_int,
+    ) -> *mut c_void [errcode_ret];
+    fn clEnqueueUnmapMemObject(
+        command_queue: cl_command_queue,
+        memobj: cl_mem,
+        mapped_ptr: *mut c_void,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    fn clEnqueueMigrateMemObjects(
+        command_queue: cl_command_queue,
+        num_mem_objects: cl_uint,
+        mem_objects: *const cl_mem,
+        flags: cl_mem_migration_flags,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    fn clEnqueueNDRangeKernel(
+        command_queue: cl_command_queue,
+        kernel: cl_kernel,
+        work_dim: cl_uint,
+        global_work_offset: *const size_t,
+        global_work_size: *const size_t,
+        local_work_size: *const size_t,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    fn clEnqueueTask(
+        command_queue: cl_command_queue,
+        kernel: cl_kernel,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    fn clEnqueueNativeKernel(
+        command_queue: cl_command_queue,
+        user_func: Option<unsafe extern "C" fn(*mut c_void)>,
+        args: *mut c_void,
+        cb_args: size_t,
+        num_mem_objects: cl_uint,
+        mem_list: *const cl_mem,
+        args_mem_loc: *const *const c_void,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    fn clEnqueueMarkerWithWaitList(
+        command_queue: cl_command_queue,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    fn clEnqueueBarrierWithWaitList(
+        command_queue: cl_command_queue,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    #[cfg(feature = "CL_VERSION_2_0")]
+    fn clEnqueueSVMFree(
+        command_queue: cl_command_queue,
+        num_svm_pointers: cl_uint,
+        svm_pointers: *const *const c_void,
+        pfn_free_func: Option<
+            unsafe extern "C" fn(
+                queue: cl_command_queue,
+                num_svm_pointers: cl_uint,
+                svm_pointers: *mut *mut c_void,
+                user_data: *mut c_void,
+            ),
+        >,
+        user_data: *mut c_void,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    #[cfg(feature = "CL_VERSION_2_0")]
+    fn clEnqueueSVMMemcpy(
+        command_queue: cl_command_queue,
+        blocking_copy: cl_bool,
+        dst_ptr: *mut c_void,
+        src_ptr: *const c_void,
+        size: size_t,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    #[cfg(feature = "CL_VERSION_2_0")]
+    fn clEnqueueSVMMemFill(
+        command_queue: cl_command_queue,
+        svm_ptr: *mut c_void,
+        pattern: *const c_void,
+        pattern_size: size_t,
+        size: size_t,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    #[cfg(feature = "CL_VERSION_2_0")]
+    fn clEnqueueSVMMap(
+        command_queue: cl_command_queue,
+        blocking_map: cl_bool,
+        flags: cl_map_flags,
+        svm_ptr: *mut c_void,
+        size: size_t,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    #[cfg(feature = "CL_VERSION_2_0")]
+    fn clEnqueueSVMUnmap(
+        command_queue: cl_command_queue,
+        svm_ptr: *mut c_void,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    #[cfg(feature = "CL_VERSION_2_1")]
+    fn clEnqueueSVMMigrateMem(
+        command_queue: cl_command_queue,
+        num_svm_pointers: cl_uint,
+        svm_pointers: *const *const c_void,
+        sizes: *const size_t,
+        flags: cl_mem_migration_flags,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+
+    // Memory Object APIs
+    fn clCreateBuffer(
+        context: cl_context,
+        flags: cl_mem_flags,
+        size: size_t,
+        host_ptr: *mut c_void,
+        errcode_ret: *mut cl_int,
+    ) -> cl_mem [errcode_ret];
+    fn clCreateSubBuffer(
+        buffer: cl_mem,
+        flags: cl_mem_flags,
+        buffer_create_type: cl_buffer_create_type,
+        buffer_create_info: *const c_void,
+        errcode_ret: *mut cl_int,
+    ) -> cl_mem [errcode_ret];
+    fn clCreateImage(
+        context: cl_context,
+        flags: cl_mem_flags,
+        image_format: *const cl_image_format,
+        image_desc: *const cl_image_desc,
+        host_ptr: *mut c_void,
+        errcode_ret: *mut cl_int,
+    ) -> cl_mem [errcode_ret];
+    #[cfg(feature = "CL_VERSION_2_0")]
+    fn clCreatePipe(
+        context: cl_context,
+        flags: cl_mem_flags,
+        pipe_packet_size: cl_uint,
+        pipe_max_packets: cl_uint,
+        properties: *const cl_pipe_properties,
+        errcode_ret: *mut cl_int,
+    ) -> cl_mem [errcode_ret];
+    #[cfg(feature = "CL_VERSION_3_0")]
+    fn clCreateBufferWithProperties(
+        context: cl_context,
+        properties: *const cl_mem_properties,
+        flags: cl_mem_flags,
+        size: size_t,
+        host_ptr: *mut c_void,
+        errcode_ret: *mut cl_int,
+    ) -> cl_mem [errcode_ret];
+    #[cfg(feature = "CL_VERSION_3_0")]
+    fn clCreateImageWithProperties(
+        context: cl_context,
+        properties: *const cl_mem_properties,
+        flags: cl_mem_flags,
+        image_format: *const cl_image_format,
+        image_desc: *const cl_image_desc,
+        host_ptr: *mut c_void,
+        errcode_ret: *mut cl_int,
+    ) -> cl_mem [errcode_ret];
+    fn clRetainMemObject(memobj: cl_mem) -> cl_int;
+    fn clReleaseMemObject(memobj: cl_mem) -> cl_int;
+    fn clGetSupportedImageFormats(
+        context: cl_context,
+        flags: cl_mem_flags,
+        image_type: cl_mem_object_type,
+        num_entries: cl_uint,
+        image_formats: *mut cl_image_format,
+        num_image_formats: *mut cl_uint,
+    ) -> cl_int;
+    fn clGetMemObjectInfo(
+        memobj: cl_mem,
+        param_name: cl_mem_info,
+        param_value_size: size_t,
+        param_value: *mut c_void,
+        param_value_size_ret: *mut size_t,
+    ) -> cl_int;
+    fn clGetImageInfo(
+        image: cl_mem,
+        param_name: cl_image_info,
+        param_value_size: size_t,
+        param_value: *mut c_void,
+        param_value_size_ret: *mut size_t,
+    ) -> cl_int;
+    #[cfg(feature = "CL_VERSION_2_0")]
+    fn clGetPipeInfo(
+        pipe: cl_mem,
+        param_name: cl_pipe_info,
+        param_value_size: size_t,
+        param_value: *mut c_void,
+        param_value_size_ret: *mut size_t,
+    ) -> cl_int;
+    fn clSetMemObjectDestructorCallback(
+        memobj: cl_mem,
+        pfn_notify: Option<unsafe extern "C" fn(memobj: cl_mem, user_data: *mut c_void)>,
+        user_data: *mut c_void,
+    ) -> cl_int;
+
+    // SVM Allocation APIs
+    #[cfg(feature = "CL_VERSION_2_0")]
+    fn clSVMAlloc(
+        context: cl_context,
+        flags: cl_svm_mem_flags,
+        size: size_t,
+        alignment: cl_uint,
+    ) -> *mut c_void [];
+    #[cfg(feature = "CL_VERSION_2_0")]
+    fn clSVMFree(context: cl_context, svm_pointer: *mut c_void) -> () [];
+
+    // Sampler APIs
+    fn clCreateSampler(
+        context: cl_context,
+        normalized_coords: cl_bool,
+        addressing_mode: cl_addressing_mode,
+        filter_mode: cl_filter_mode,
+        errcode_ret: *mut cl_int,
+    ) -> cl_sampler [errcode_ret];
+    #[cfg(feature = "CL_VERSION_2_0")]
+    fn clCreateSamplerWithProperties(
+        context: cl_context,
+        sampler_properties: *const cl_sampler_properties,
+        errcode_ret: *mut cl_int,
+    ) -> cl_sampler [errcode_ret];
+    fn clRetainSampler(sampler: cl_sampler) -> cl_int;
+    fn clReleaseSampler(sampler: cl_sampler) -> cl_int;
+    fn clGetSamplerInfo(
+        sampler: cl_sampler,
+        param_name: cl_sampler_info,
+        param_value_size: size_t,
+        param_value: *mut c_void,
+        param_value_size_ret: *mut size_t,
+    ) -> cl_int;
+
+    // Program Object APIs
+    fn clCreateProgramWithSource(
+        context: cl_context,
+        count: cl_uint,
+        strings: *const *const c_char,
+        lengths: *const size_t,
+        errcode_ret: *mut cl_int,
+    ) -> cl_program [errcode_ret];
+    fn clCreateProgramWithBinary(
+        context: cl_context,
+        num_devices: cl_uint,
+        device_list: *const cl_device_id,
+        lengths: *const size_t,
+        binaries: *const *const c_uchar,
+        binary_status: *mut cl_int,
+        errcode_ret: *mut cl_int,
+    ) -> cl_program [errcode_ret];
+    fn clCreateProgramWithBuiltInKernels(
+        context: cl_context,
+        num_devices: cl_uint,
+        device_list: *const cl_device_id,
+        kernel_names: *const c_char,
+        errcode_ret: *mut cl_int,
+    ) -> cl_program [errcode_ret];
+    #[cfg(feature = "CL_VERSION_2_1")]
+    fn clCreateProgramWithIL(
+        context: cl_context,
+        il: *const c_void,
+        length: size_t,
+        errcode_ret: *mut cl_int,
+    ) -> cl_program [errcode_ret];
+    fn clRetainProgram(program: cl_program) -> cl_int;
+    fn clReleaseProgram(program: cl_program) -> cl_int;
+    fn clBuildProgram(
+        program: cl_program,
+        num_devices: cl_uint,
+        device_list: *const cl_device_id,
+        options: *const c_char,
+        pfn_notify: Option<unsafe extern "C" fn(program: cl_program, user_data: *mut c_void)>,
+        user_data: *mut c_void,
+    ) -> cl_int;
+    fn clCompileProgram(
+        program: cl_program,
+        num_devices: cl_uint,
+        device_list: *const cl_device_id,
+        options: *const c_char,
+        num_input_headers: cl_uint,
+        input_headers: *const cl_program,
+        header_include_names: *const *const c_char,
+        pfn_notify: Option<unsafe extern "C" fn(program: cl_program, user_data: *mut c_void)>,
+        user_data: *mut c_void,
+    ) -> cl_int;
+    fn clLinkProgram(
+        context: cl_context,
+        num_devices: cl_uint,
+        device_list: *const cl_device_id,
+        options: *const c_char,
+        num_input_programs: cl_uint,
+        input_programs: *const cl_program,
+        pfn_notify: Option<unsafe extern "C" fn(program: cl_program, user_data: *mut c_void)>,
+        user_data: *mut c_void,
+        errcode_ret: *mut cl_int,
+    ) -> cl_program [errcode_ret];
+    #[cfg(feature = "CL_VERSION_2_2")]
+    fn clSetProgramSpecializationConstant(
+        program: cl_program,
+        spec_id: cl_uint,
+        spec_size: size_t,
+        spec_value: *const c_void,
+    ) -> cl_int;
+    fn clGetProgramInfo(
+        program: cl_program,
+        param_name: cl_program_info,
+        param_value_size: size_t,
+        param_value: *mut c_void,
+        param_value_size_ret: *mut size_t,
+    ) -> cl_int;
+    fn clGetProgramBuildInfo(
+        program: cl_program,
+        device: cl_device_id,
+        param_name: cl_program_build_info,
+        param_value_size: size_t,
+        param_value: *mut c_void,
+        param_value_size_ret: *mut size_t,
+    ) -> cl_int;
+
+    // Kernel Object APIs
+    fn clCreateKernel(
+        program: cl_program,
+        kernel_name: *const c_char,
+        errcode_ret: *mut cl_int,
+    ) -> cl_kernel [errcode_ret];
+    fn clCreateKernelsInProgram(
+        program: cl_program,
+        num_kernels: cl_uint,
+        kernels: *mut cl_kernel,
+        num_kernels_ret: *mut cl_uint,
+    ) -> cl_int;
+    #[cfg(feature = "CL_VERSION_2_1")]
+    fn clCloneKernel(source_kernel: cl_kernel, errcode_ret: *mut cl_int) -> cl_kernel [errcode_ret];
+    fn clRetainKernel(kernel: cl_kernel) -> cl_int;
+    fn clReleaseKernel(kernel: cl_kernel) -> cl_int;
+    fn clSetKernelArg(
+        kernel: cl_kernel,
+        arg_index: cl_uint,
+        arg_size: size_t,
+        arg_value: *const c_void,
+    ) -> cl_int;
+    #[cfg(feature = "CL_VERSION_2_0")]
+    fn clSetKernelArgSVMPointer(
+        kernel: cl_kernel,
+        arg_index: cl_uint,
+        arg_value: *const c_void,
+    ) -> cl_int;
+    #[cfg(feature = "CL_VERSION_2_0")]
+    fn clSetKernelExecInfo(
+        kernel: cl_kernel,
+        param_name: cl_kernel_exec_info,
+        param_value_size: size_t,
+        param_value: *const c_void,
+    ) -> cl_int;
+    fn clGetKernelInfo(
+        kernel: cl_kernel,
+        param_name: cl_kernel_info,
+        param_value_size: size_t,
+        param_value: *mut c_void,
+        param_value_size_ret: *mut size_t,
+    ) -> cl_int;
+    fn clGetKernelArgInfo(
+        kernel: cl_kernel,
+        arg_indx: cl_uint,
+        param_name: cl_kernel_arg_info,
+        param_value_size: size_t,
+        param_value: *mut c_void,
+        param_value_size_ret: *mut size_t,
+    ) -> cl_int;
+    fn clGetKernelWorkGroupInfo(
+        kernel: cl_kernel,
+        device: cl_device_id,
+        param_name: cl_kernel_work_group_info,
+        param_value_size: size_t,
+        param_value: *mut c_void,
+        param_value_size_ret: *mut size_t,
+    ) -> cl_int;
+    #[cfg(feature = "CL_VERSION_2_1")]
+    fn clGetKernelSubGroupInfo(
+        kernel: cl_kernel,
+        device: cl_device_id,
+        param_name: cl_kernel_sub_group_info,
+        input_value_size: size_t,
+        input_value: *const c_void,
+        param_value_size: size_t,
+        param_value: *mut c_void,
+        param_value_size_ret: *mut size_t,
+    ) -> cl_int;
+
+    // Event Object APIs
+    fn clWaitForEvents(num_events: cl_uint, event_list: *const cl_event) -> cl_int;
+    fn clGetEventInfo(
+        event: cl_event,
+        param_name: cl_event_info,
+        param_value_size: size_t,
+        param_value: *mut c_void,
+        param_value_size_ret: *mut size_t,
+    ) -> cl_int;
+    fn clCreateUserEvent(context: cl_context, errcode_ret: *mut cl_int) -> cl_event [errcode_ret];
+    fn clRetainEvent(event: cl_event) -> cl_int;
+    fn clReleaseEvent(event: cl_event) -> cl_int;
+    fn clSetUserEventStatus(event: cl_event, execution_status: cl_int) -> cl_int;
+    fn clSetEventCallback(
+        event: cl_event,
+        command_exec_callback_type: cl_int,
+        pfn_notify: Option<
+            unsafe extern "C" fn(event: cl_event, event_command_status: cl_int, user_data: *mut c_void),
+        >,
+        user_data: *mut c_void,
+    ) -> cl_int;
+
+    // Profiling APIs
+    fn clGetEventProfilingInfo(
+        event: cl_event,
+        param_name: cl_profiling_info,
+        param_value_size: size_t,
+        param_value: *mut c_void,
+        param_value_size_ret: *mut size_t,
+    ) -> cl_int;
+
+    // OpenGL sharing APIs
+    fn clCreateFromGLBuffer(
+        context: cl_context,
+        flags: cl_mem_flags,
+        bufobj: cl_GLuint,
+        errcode_ret: *mut cl_int,
+    ) -> cl_mem [errcode_ret];
+    fn clCreateFromGLTexture(
+        context: cl_context,
+        flags: cl_mem_flags,
+        target: cl_GLenum,
+        miplevel: cl_GLint,
+        texture: cl_GLuint,
+        errcode_ret: *mut cl_int,
+    ) -> cl_mem [errcode_ret];
+    fn clCreateFromGLRenderbuffer(
+        context: cl_context,
+        flags: cl_mem_flags,
+        renderbuffer: cl_GLuint,
+        errcode_ret: *mut cl_int,
+    ) -> cl_mem [errcode_ret];
+    fn clGetGLObjectInfo(
+        memobj: cl_mem,
+        gl_object_type: *mut cl_gl_object_type,
+        gl_object_name: *mut cl_GLuint,
+    ) -> cl_int;
+    fn clGetGLTextureInfo(
+        memobj: cl_mem,
+        param_name: cl_gl_texture_info,
+        param_value_size: size_t,
+        param_value: *mut c_void,
+        param_value_size_ret: *mut size_t,
+    ) -> cl_int;
+    fn clEnqueueAcquireGLObjects(
+        command_queue: cl_command_queue,
+        num_objects: cl_uint,
+        mem_objects: *const cl_mem,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    fn clEnqueueReleaseGLObjects(
+        command_queue: cl_command_queue,
+        num_objects: cl_uint,
+        mem_objects: *const cl_mem,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    fn clCreateFromGLTexture2D(
+        context: cl_context,
+        flags: cl_mem_flags,
+        target: cl_GLenum,
+        miplevel: cl_GLint,
+        texture: cl_GLuint,
+        errcode_ret: *mut cl_int,
+    ) -> cl_mem [errcode_ret];
+    fn clCreateFromGLTexture3D(
+        context: cl_context,
+        flags: cl_mem_flags,
+        target: cl_GLenum,
+        miplevel: cl_GLint,
+        texture: cl_GLuint,
+        errcode_ret: *mut cl_int,
+    ) -> cl_mem [errcode_ret];
+    fn clGetGLContextInfoKHR(
+        properties: *const cl_context_properties,
+        param_name: cl_gl_context_info,
+        param_value_size: size_t,
+        param_value: *mut c_void,
+        param_value_size_ret: *mut size_t,
+    ) -> cl_int;
+    fn clCreateEventFromGLsyncKHR(
+        context: cl_context,
+        sync: cl_GLsync,
+        errcode_ret: *mut cl_int,
+    ) -> cl_event [errcode_ret];
+
+    // Extension APIs
+    #[cfg(feature = "cl_khr_command_buffer")]
+    fn clCreateCommandBufferKHR(
+        num_queues: cl_uint,
+        queues: *const cl_command_queue,
+        properties: *const cl_command_buffer_properties_khr,
+        errcode_ret: *mut cl_int,
+    ) -> cl_command_buffer_khr [errcode_ret];
+    #[cfg(feature = "cl_khr_command_buffer")]
+    fn clFinalizeCommandBufferKHR(command_buffer: cl_command_buffer_khr) -> cl_int;
+    #[cfg(feature = "cl_khr_command_buffer")]
+    fn clRetainCommandBufferKHR(command_buffer: cl_command_buffer_khr) -> cl_int;
+    #[cfg(feature = "cl_khr_command_buffer")]
+    fn clReleaseCommandBufferKHR(command_buffer: cl_command_buffer_khr) -> cl_int;
+    #[cfg(feature = "cl_khr_command_buffer")]
+    fn clEnqueueCommandBufferKHR(
+        num_queues: cl_uint,
+        queues: *mut cl_command_queue,
+        command_buffer: cl_command_buffer_khr,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    #[cfg(feature = "cl_khr_command_buffer")]
+    fn clCommandBarrierWithWaitListKHR(
+        command_buffer: cl_command_buffer_khr,
+        command_queue: cl_command_queue,
+        num_sync_points_in_wait_list: cl_uint,
+        sync_point_wait_list: *const cl_sync_point_khr,
+        sync_point: *mut cl_sync_point_khr,
+        mutable_handle: *mut cl_mutable_command_khr,
+    ) -> cl_int;
+    #[cfg(feature = "cl_khr_command_buffer")]
+    fn clCommandCopyBufferKHR(
+        command_buffer: cl_command_buffer_khr,
+        command_queue: cl_command_queue,
+        src_buffer: cl_mem,
+        dst_buffer: cl_mem,
+        src_offset: size_t,
+        dst_offset: size_t,
+        size: size_t,
+        num_sync_points_in_wait_list: cl_uint,
+        sync_point_wait_list: *const cl_sync_point_khr,
+        sync_point: *mut cl_sync_point_khr,
+        mutable_handle: *mut cl_mutable_command_khr,
+    ) -> cl_int;
+    #[cfg(feature = "cl_khr_command_buffer")]
+    fn clCommandCopyBufferRectKHR(
+        command_buffer: cl_command_buffer_khr,
+        command_queue: cl_command_queue,
+        src_buffer: cl_mem,
+        dst_buffer: cl_mem,
+        src_origin: *const size_t,
+        dst_origin: *const size_t,
+        region: *const size_t,
+        src_row_pitch: size_t,
+        src_slice_pitch: size_t,
+        dst_row_pitch: size_t,
+        dst_slice_pitch: size_t,
+        num_sync_points_in_wait_list: cl_uint,
+        sync_point_wait_list: *const cl_sync_point_khr,
+        sync_point: *mut cl_sync_point_khr,
+        mutable_handle: *mut cl_mutable_command_khr,
+    ) -> cl_int;
+    #[cfg(feature = "cl_khr_command_buffer")]
+    fn clCommandCopyBufferToImageKHR(
+        command_buffer: cl_command_buffer_khr,
+        command_queue: cl_command_queue,
+        src_buffer: cl_mem,
+        dst_image: cl_mem,
+        src_offset: size_t,
+        dst_origin: *const size_t,
+        region: *const size_t,
+        num_sync_points_in_wait_list: cl_uint,
+        sync_point_wait_list: *const cl_sync_point_khr,
+        sync_point: *mut cl_sync_point_khr,
+        mutable_handle: *mut cl_mutable_command_khr,
+    ) -> cl_int;
+    #[cfg(feature = "cl_khr_command_buffer")]
+    fn clCommandCopyImageKHR(
+        command_buffer: cl_command_buffer_khr,
+        command_queue: cl_command_queue,
+        src_image: cl_mem,
+        dst_image: cl_mem,
+        src_origin: *const size_t,
+        dst_origin: *const size_t,
+        region: *const size_t,
+        num_sync_points_in_wait_list: cl_uint,
+        sync_point_wait_list: *const cl_sync_point_khr,
+        sync_point: *mut cl_sync_point_khr,
+        mutable_handle: *mut cl_mutable_command_khr,
+    ) -> cl_int;
+    #[cfg(feature = "cl_khr_command_buffer")]
+    fn clCommandCopyImageToBufferKHR(
+        command_buffer: cl_command_buffer_khr,
+        command_queue: cl_command_queue,
+        src_image: cl_mem,
+        dst_buffer: cl_mem,
+        src_origin: *const size_t,
+        region: *const size_t,
+        dst_offset: size_t,
+        num_sync_points_in_wait_list: cl_uint,
+        sync_point_wait_list: *const cl_sync_point_khr,
+        sync_point: *mut cl_sync_point_khr,
+        mutable_handle: *mut cl_mutable_command_khr,
+    ) -> cl_int;
+    #[cfg(feature = "cl_khr_command_buffer")]
+    fn clCommandFillBufferKHR(
+        command_buffer: cl_command_buffer_khr,
+        command_queue: cl_command_queue,
+        buffer: cl_mem,
+        pattern: *const c_void,
+        pattern_size: size_t,
+        offset: size_t,
+        size: size_t,
+        num_sync_points_in_wait_list: cl_uint,
+        sync_point_wait_list: *const cl_sync_point_khr,
+        sync_point: *mut cl_sync_point_khr,
+        mutable_handle: *mut cl_mutable_command_khr,
+    ) -> cl_int;
+    #[cfg(feature = "cl_khr_command_buffer")]
+    fn clCommandFillImageKHR(
+        command_buffer: cl_command_buffer_khr,
+        command_queue: cl_command_queue,
+        image: cl_mem,
+        fill_color: *const c_void,
+        origin: *const size_t,
+        region: *const size_t,
+        num_sync_points_in_wait_list: cl_uint,
+        sync_point_wait_list: *const cl_sync_point_khr,
+        sync_point: *mut cl_sync_point_khr,
+        mutable_handle: *mut cl_mutable_command_khr,
+    ) -> cl_int;
+    #[cfg(feature = "cl_khr_command_buffer")]
+    fn clCommandNDRangeKernelKHR(
+        command_buffer: cl_command_buffer_khr,
+        command_queue: cl_command_queue,
+        properties: *const cl_ndrange_kernel_command_properties_khr,
+        kernel: cl_kernel,
+        work_dim: cl_uint,
+        global_work_offset: *const size_t,
+        global_work_size: *const size_t,
+        local_work_size: *const size_t,
+        num_sync_points_in_wait_list: cl_uint,
+        sync_point_wait_list: *const cl_sync_point_khr,
+        sync_point: *mut cl_sync_point_khr,
+        mutable_handle: *mut cl_mutable_command_khr,
+    ) -> cl_int;
+    #[cfg(feature = "cl_khr_command_buffer")]
+    fn clCommandSVMMemcpyKHR(
+        command_buffer: cl_command_buffer_khr,
+        command_queue: cl_command_queue,
+        dst_ptr: *mut c_void,
+        src_ptr: *const c_void,
+        size: size_t,
+        num_sync_points_in_wait_list: cl_uint,
+        sync_point_wait_list: *const cl_sync_point_khr,
+        sync_point: *mut cl_sync_point_khr,
+        mutable_handle: *mut cl_mutable_command_khr,
+    ) -> cl_int;
+    #[cfg(feature = "cl_khr_command_buffer")]
+    fn clCommandSVMMemFillKHR(
+        command_buffer: cl_command_buffer_khr,
+        command_queue: cl_command_queue,
+        svm_ptr: *mut c_void,
+        pattern: *const c_void,
+        pattern_size: size_t,
+        size: size_t,
+        num_sync_points_in_wait_list: cl_uint,
+        sync_point_wait_list: *const cl_sync_point_khr,
+        sync_point: *mut cl_sync_point_khr,
+        mutable_handle: *mut cl_mutable_command_khr,
+    ) -> cl_int;
+    #[cfg(feature = "cl_khr_command_buffer")]
+    fn clGetCommandBufferInfoKHR(
+        command_buffer: cl_command_buffer_khr,
+        param_name: cl_command_buffer_info_khr,
+        param_value_size: size_t,
+        param_value: *mut c_void,
+        param_value_size_ret: *mut size_t,
+    ) -> cl_int;
+    #[cfg(feature = "cl_khr_command_buffer_multi_device")]
+    fn clRemapCommandBufferKHR(
+        command_buffer: cl_command_buffer_khr,
+        automatic: cl_bool,
+        num_queues: cl_uint,
+        queues: *const cl_command_queue,
+        num_handles: cl_uint,
+        handles: *const cl_mutable_command_khr,
+        handles_ret: *mut cl_mutable_command_khr,
+        errcode_ret: *mut cl_int,
+    ) -> cl_command_buffer_khr [errcode_ret];
+    #[cfg(feature = "cl_khr_command_buffer_mutable_dispatch")]
+    fn clUpdateMutableCommandsKHR(
+        command_buffer: cl_command_buffer_khr,
+        mutable_config: *const cl_mutable_base_config_khr,
+    ) -> cl_int;
+    #[cfg(feature = "cl_khr_command_buffer_mutable_dispatch")]
+    fn clGetMutableCommandInfoKHR(
+        command: cl_mutable_command_khr,
+        param_name: cl_mutable_command_info_khr,
+        param_value_size: size_t,
+        param_value: *mut c_void,
+        param_value_size_ret: *mut size_t,
+    ) -> cl_int;
+    #[cfg(feature = "cl_apple_setmemobjectdestructor")]
+    fn clSetMemObjectDestructorAPPLE(
+        memobj: cl_mem,
+        pfn_notify: Option<unsafe extern "C" fn(memobj: cl_mem, user_data: *mut c_void)>,
+        user_data: *mut c_void,
+    ) -> cl_int;
+    #[cfg(feature = "cl_khr_icd")]
+    fn clIcdGetPlatformIDsKHR(
+        num_entries: cl_uint,
+        platforms: *mut cl_platform_id,
+        num_platforms: *mut cl_uint,
+    ) -> cl_int;
+    #[cfg(feature = "cl_khr_il_program")]
+    fn clCreateProgramWithILKHR(
+        context: cl_context,
+        il: *const c_void,
+        length: size_t,
+        errcode_ret: *mut cl_int,
+    ) -> cl_program [errcode_ret];
+    #[cfg(feature = "cl_khr_terminate_context")]
+    fn clTerminateContextKHR(context: cl_context) -> cl_int;
+    #[cfg(feature = "cl_khr_create_command_queue")]
+    fn clCreateCommandQueueWithPropertiesKHR(
+        context: cl_context,
+        device: cl_device_id,
+        properties: *const cl_queue_properties_khr,
+        errcode_ret: *mut cl_int,
+    ) -> cl_command_queue [errcode_ret];
+    #[cfg(feature = "cl_ext_device_fission")]
+    fn clReleaseDeviceEXT(device: cl_device_id) -> cl_int;
+    #[cfg(feature = "cl_ext_device_fission")]
+    fn clRetainDeviceEXT(device: cl_device_id) -> cl_int;
+    #[cfg(feature = "cl_ext_device_fission")]
+    fn clCreateSubDevicesEXT(
+        in_device: cl_device_id,
+        properties: *const cl_device_partition_property_ext,
+        num_entries: cl_uint,
+        out_devices: *mut cl_device_id,
+        num_devices: *mut cl_uint,
+    ) -> cl_int;
+    #[cfg(feature = "cl_ext_migrate_memobject")]
+    fn clEnqueueMigrateMemObjectEXT(
+        command_queue: cl_command_queue,
+        num_mem_objects: cl_uint,
+        mem_objects: *const cl_mem,
+        flags: cl_mem_migration_flags_ext,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    #[cfg(feature = "cl_qcom_ext_host_ptr")]
+    fn clGetDeviceImageInfoQCOM(
+        device: cl_device_id,
+        image_width: size_t,
+        image_height: size_t,
+        image_format: *const cl_image_format,
+        param_name: cl_image_pitch_info_qcom,
+        param_value_size: size_t,
+        param_value: *mut c_void,
+        param_value_size_ret: *mut size_t,
+    ) -> cl_int;
+    #[cfg(feature = "cl_img_use_gralloc_ptr")]
+    fn clEnqueueAcquireGrallocObjectsIMG(
+        command_queue: cl_command_queue,
+        num_objects: cl_uint,
+        mem_objects: *const cl_mem,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    #[cfg(feature = "cl_img_use_gralloc_ptr")]
+    fn clEnqueueReleaseGrallocObjectsIMG(
+        command_queue: cl_command_queue,
+        num_objects: cl_uint,
+        mem_objects: *const cl_mem,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    #[cfg(feature = "cl_img_generate_mipmap")]
+    fn clEnqueueGenerateMipmapIMG(
+        command_queue: cl_command_queue,
+        src_image: cl_mem,
+        dst_image: cl_mem,
+        mipmap_filter_mode: cl_mipmap_filter_mode_img,
+        array_region: *const size_t,
+        mip_region: *const size_t,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    #[cfg(feature = "cl_khr_subgroups")]
+    fn clGetKernelSubGroupInfoKHR(
+        in_kernel: cl_kernel,
+        in_device: cl_device_id,
+        param_name: cl_kernel_sub_group_info,
+        input_value_size: size_t,
+        input_value: *const c_void,
+        param_value_size: size_t,
+        param_value: *mut c_void,
+        param_value_size_ret: *mut size_t,
+    ) -> cl_int;
+    #[cfg(feature = "cl_khr_suggested_local_work_size")]
+    fn clGetKernelSuggestedLocalWorkSizeKHR(
+        command_queue: cl_command_queue,
+        kernel: cl_kernel,
+        work_dim: cl_uint,
+        global_work_offset: *const size_t,
+        global_work_size: *const size_t,
+        suggested_local_work_size: *mut size_t,
+    ) -> cl_int;
+    #[cfg(feature = "cl_khr_external_memory")]
+    fn clEnqueueAcquireExternalMemObjectsKHR(
+        command_queue: cl_command_queue,
+        num_mem_objects: cl_uint,
+        mem_objects: *const cl_mem,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    #[cfg(feature = "cl_khr_external_memory")]
+    fn clEnqueueReleaseExternalMemObjectsKHR(
+        command_queue: cl_command_queue,
+        num_mem_objects: cl_uint,
+        mem_objects: *const cl_mem,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    #[cfg(feature = "cl_khr_external_semaphore")]
+    fn clGetSemaphoreHandleForTypeKHR(
+        sema_object: cl_semaphore_khr,
+        device: cl_device_id,
+        handle_type: cl_external_semaphore_handle_type_khr,
+        handle_size: size_t,
+        handle_ptr: *mut c_void,
+        handle_size_ret: *mut size_t,
+    ) -> cl_int;
+    #[cfg(feature = "cl_khr_external_semaphore_sync_fd")]
+    fn clReImportSemaphoreSyncFdKHR(
+        sema_object: cl_semaphore_khr,
+        reimport_props: *mut cl_semaphore_reimport_properties_khr,
+        fd: c_int,
+    ) -> cl_int;
+    #[cfg(feature = "cl_khr_semaphore")]
+    fn clCreateSemaphoreWithPropertiesKHR(
+        context: cl_context,
+        sema_props: *const cl_semaphore_properties_khr,
+        errcode_ret: *mut cl_int,
+    ) -> cl_semaphore_khr [errcode_ret];
+    #[cfg(feature = "cl_khr_semaphore")]
+    fn clEnqueueWaitSemaphoresKHR(
+        command_queue: cl_command_queue,
+        num_sema_objects: cl_uint,
+        sema_objects: *const cl_semaphore_khr,
+        sema_payload_list: *const cl_semaphore_payload_khr,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    #[cfg(feature = "cl_khr_semaphore")]
+    fn clEnqueueSignalSemaphoresKHR(
+        command_queue: cl_command_queue,
+        num_sema_objects: cl_uint,
+        sema_objects: *const cl_semaphore_khr,
+        sema_payload_list: *const cl_semaphore_payload_khr,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    #[cfg(feature = "cl_khr_semaphore")]
+    fn clGetSemaphoreInfoKHR(
+        sema_object: cl_semaphore_khr,
+        param_name: cl_semaphore_info_khr,
+        param_value_size: size_t,
+        param_value: *mut c_void,
+        param_value_size_ret: *mut size_t,
+    ) -> cl_int;
+    #[cfg(feature = "cl_khr_semaphore")]
+    fn clReleaseSemaphoreKHR(sema_object: cl_semaphore_khr) -> cl_int;
+    #[cfg(feature = "cl_khr_semaphore")]
+    fn clRetainSemaphoreKHR(sema_object: cl_semaphore_khr) -> cl_int;
+    #[cfg(feature = "cl_arm_import_memory")]
+    fn clImportMemoryARM(
+        context: cl_context,
+        flags: cl_mem_flags,
+        properties: *const cl_import_properties_arm,
+        memory: *mut c_void,
+        size: size_t,
+        errcode_ret: *mut cl_int,
+    ) -> cl_mem [errcode_ret];
+    #[cfg(feature = "cl_arm_shared_virtual_memory")]
+    fn clSVMAllocARM(
+        context: cl_context,
+        flags: cl_svm_mem_flags_arm,
+        size: size_t,
+        alignment: cl_uint,
+    ) -> *mut c_void [];
+    #[cfg(feature = "cl_arm_shared_virtual_memory")]
+    fn clSVMFreeARM(context: cl_context, svm_pointer: *mut c_void) -> () [];
+    #[cfg(feature = "cl_arm_shared_virtual_memory")]
+    fn clEnqueueSVMFreeARM(
+        command_queue: cl_command_queue,
+        num_svm_pointers: cl_uint,
+        svm_pointers: *mut *mut c_void,
+        pfn_free_func: Option< unsafe extern "C" fn( queue: cl_command_queue, num_svm_pointers: cl_uint, svm_pointers: *mut *mut c_void, user_data: *mut c_void, ), >,
+        user_data: *mut c_void,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    #[cfg(feature = "cl_arm_shared_virtual_memory")]
+    fn clEnqueueSVMMemcpyARM(
+        command_queue: cl_command_queue,
+        blocking_copy: cl_bool,
+        dst_ptr: *mut c_void,
+        src_ptr: *const c_void,
+        size: size_t,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    #[cfg(feature = "cl_arm_shared_virtual_memory")]
+    fn clEnqueueSVMMemFillARM(
+        command_queue: cl_command_queue,
+        svm_ptr: *mut c_void,
+        pattern: *const c_void,
+        pattern_size: size_t,
+        size: size_t,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    #[cfg(feature = "cl_arm_shared_virtual_memory")]
+    fn clEnqueueSVMMapARM(
+        command_queue: cl_command_queue,
+        blocking_map: cl_bool,
+        flags: cl_map_flags,
+        svm_ptr: *mut c_void,
+        size: size_t,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    #[cfg(feature = "cl_arm_shared_virtual_memory")]
+    fn clEnqueueSVMUnmapARM(
+        command_queue: cl_command_queue,
+        svm_ptr: *mut c_void,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    #[cfg(feature = "cl_arm_shared_virtual_memory")]
+    fn clSetKernelArgSVMPointerARM(
+        kernel: cl_kernel,
+        arg_index: cl_uint,
+        arg_value: *const c_void,
+    ) -> cl_int;
+    #[cfg(feature = "cl_arm_shared_virtual_memory")]
+    fn clSetKernelExecInfoARM(
+        kernel: cl_kernel,
+        param_name: cl_kernel_exec_info_arm,
+        param_value_size: size_t,
+        param_value: *const c_void,
+    ) -> cl_int;
+    #[cfg(feature = "cl_intel_accelerator")]
+    fn clCreateAcceleratorINTEL(
+        context: cl_context,
+        accelerator_type: cl_accelerator_type_intel,
+        descriptor_size: size_t,
+        descriptor: *const c_void,
+        errcode_ret: *mut cl_int,
+    ) -> cl_accelerator_intel [errcode_ret];
+    #[cfg(feature = "cl_intel_accelerator")]
+    fn clGetAcceleratorInfoINTEL(
+        accelerator: cl_accelerator_intel,
+        param_name: cl_accelerator_info_intel,
+        param_value_size: size_t,
+        param_value: *mut c_void,
+        param_value_size_ret: *mut size_t,
+    ) -> cl_int;
+    #[cfg(feature = "cl_intel_accelerator")]
+    fn clRetainAcceleratorINTEL(accelerator: cl_accelerator_intel) -> cl_int;
+    #[cfg(feature = "cl_intel_accelerator")]
+    fn clReleaseAcceleratorINTEL(accelerator: cl_accelerator_intel) -> cl_int;
+    #[cfg(feature = "cl_intel_unified_shared_memory")]
+    fn clHostMemAllocINTEL(
+        context: cl_context,
+        properties: *const cl_mem_properties_intel,
+        size: size_t,
+        alignment: cl_uint,
+        errcode_ret: *mut cl_int,
+    ) -> *mut c_void [errcode_ret];
+    #[cfg(feature = "cl_intel_unified_shared_memory")]
+    fn clDeviceMemAllocINTEL(
+        context: cl_context,
+        device: cl_device_id,
+        properties: *const cl_mem_properties_intel,
+        size: size_t,
+        alignment: cl_uint,
+        errcode_ret: *mut cl_int,
+    ) -> *mut c_void [errcode_ret];
+    #[cfg(feature = "cl_intel_unified_shared_memory")]
+    fn clSharedMemAllocINTEL(
+        context: cl_context,
+        device: cl_device_id,
+        properties: *const cl_mem_properties_intel,
+        size: size_t,
+        alignment: cl_uint,
+        errcode_ret: *mut cl_int,
+    ) -> *mut c_void [errcode_ret];
+    #[cfg(feature = "cl_intel_unified_shared_memory")]
+    fn clMemFreeINTEL(context: cl_context, ptr: *mut c_void) -> cl_int;
+    #[cfg(feature = "cl_intel_unified_shared_memory")]
+    fn clMemBlockingFreeINTEL(context: cl_context, ptr: *mut c_void) -> cl_int;
+    #[cfg(feature = "cl_intel_unified_shared_memory")]
+    fn clGetMemAllocInfoINTEL(
+        context: cl_context,
+        ptr: *const c_void,
+        param_name: cl_mem_info_intel,
+        param_value_size: size_t,
+        param_value: *mut c_void,
+        param_value_size_ret: *mut size_t,
+    ) -> cl_int;
+    #[cfg(feature = "cl_intel_unified_shared_memory")]
+    fn clSetKernelArgMemPointerINTEL(
+        kernel: cl_kernel,
+        arg_index: cl_uint,
+        arg_value: *const c_void,
+    ) -> cl_int;
+    #[cfg(feature = "cl_intel_unified_shared_memory")]
+    fn clEnqueueMemFillINTEL(
+        command_queue: cl_command_queue,
+        dst_ptr: *mut c_void,
+        pattern: *const c_void,
+        pattern_size: size_t,
+        size: size_t,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    #[cfg(feature = "cl_intel_unified_shared_memory")]
+    fn clEnqueueMemcpyINTEL(
+        command_queue: cl_command_queue,
+        blocking: cl_bool,
+        dst_ptr: *mut c_void,
+        src_ptr: *const c_void,
+        size: size_t,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    #[cfg(feature = "cl_intel_unified_shared_memory")]
+    fn clEnqueueMemAdviseINTEL(
+        command_queue: cl_command_queue,
+        ptr: *const c_void,
+        size: size_t,
+        advice: cl_mem_advice_intel,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    #[cfg(feature = "cl_intel_unified_shared_memory")]
+    fn clEnqueueMigrateMemINTEL(
+        command_queue: cl_command_queue,
+        ptr: *const c_void,
+        size: size_t,
+        flags: cl_mem_migration_flags,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    #[cfg(feature = "cl_intel_unified_shared_memory")]
+    fn clEnqueueMemsetINTEL(
+        command_queue: cl_command_queue,
+        dst_ptr: *mut c_void,
+        value: cl_int,
+        size: size_t,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    #[cfg(feature = "cl_intel_create_buffer_with_properties")]
+    fn clCreateBufferWithPropertiesINTEL(
+        context: cl_context,
+        properties: *const cl_mem_properties_intel,
+        flags: cl_mem_flags,
+        size: size_t,
+        host_ptr: *mut c_void,
+        errcode_ret: *mut cl_int,
+    ) -> cl_mem [errcode_ret];
+    #[cfg(feature = "cl_intel_program_scope_host_pipe")]
+    fn clEnqueueReadHostPipeINTEL(
+        queue: cl_command_queue,
+        program: cl_program,
+        pipe_symbol: *const c_char,
+        blocking_read: cl_bool,
+        ptr: *mut c_void,
+        size: size_t,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    #[cfg(feature = "cl_intel_program_scope_host_pipe")]
+    fn clEnqueueWriteHostPipeINTEL(
+        queue: cl_command_queue,
+        program: cl_program,
+        pipe_symbol: *const c_char,
+        blocking_write: cl_bool,
+        ptr: *const c_void,
+        size: size_t,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    #[cfg(feature = "cl_ext_image_requirements_info")]
+    fn clGetImageRequirementsInfoEXT(
+        context: cl_context,
+        properties: *const cl_mem_properties,
+        flags: cl_mem_flags,
+        image_format: *const cl_image_format,
+        image_desc: *const cl_image_desc,
+        param_name: cl_image_requirements_info_ext,
+        param_value_size: size_t,
+        param_value: *mut c_void,
+        param_value_size_ret: *mut size_t,
+    ) -> cl_int;
+    #[cfg(feature = "cl_loader_info")]
+    fn clGetICDLoaderInfoOCLICD(
+        param_name: cl_icdl_info,
+        param_value_size: size_t,
+        param_value: *mut c_void,
+        param_value_size_ret: *mut size_t,
+    ) -> cl_int;
+    #[cfg(feature = "cl_pocl_content_size")]
+    fn clSetContentSizeBufferPoCL(buffer: cl_mem, content_size_buffer: cl_mem) -> cl_int;
+
+    // EGL sharing APIs
+    fn clCreateFromEGLImageKHR(
+        context: cl_context,
+        display: CLeglDisplayKHR,
+        image: CLeglImageKHR,
+        flags: cl_mem_flags,
+        properties: *const cl_egl_image_properties_khr,
+        errcode_ret: *mut cl_int,
+    ) -> cl_mem [errcode_ret];
+    fn clEnqueueAcquireEGLObjectsKHR(
+        command_queue: cl_command_queue,
+        num_objects: cl_uint,
+        mem_objects: *const cl_mem,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    fn clEnqueueReleaseEGLObjectsKHR(
+        command_queue: cl_command_queue,
+        num_objects: cl_uint,
+        mem_objects: *const cl_mem,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    fn clCreateEventFromEGLSyncKHR(
+        context: cl_context,
+        sync: CLeglSyncKHR,
+        display: CLeglDisplayKHR,
+        errcode_ret: *mut cl_int,
+    ) -> cl_event [errcode_ret];
+
+    // Direct3D 10 sharing APIs
+    #[cfg(feature = "cl_khr_d3d10_sharing")]
+    fn clGetSupportedD3D10TextureFormatsINTEL(
+        context: cl_context,
+        flags: cl_mem_flags,
+        image_type: cl_mem_object_type,
+        num_entries: cl_uint,
+        d3d10_formats: *mut DXGI_FORMAT,
+        num_surface_formats: *mut cl_uint,
+    ) -> cl_int;
+
+    // Direct3D 11 sharing APIs
+    #[cfg(feature = "cl_khr_d3d11_sharing")]
+    fn clGetSupportedD3D11TextureFormatsINTEL(
+        context: cl_context,
+        flags: cl_mem_flags,
+        image_type: cl_mem_object_type,
+        plane: cl_uint,
+        num_entries: cl_uint,
+        d3d11_formats: *mut DXGI_FORMAT,
+        num_surface_formats: *mut cl_uint,
+    ) -> cl_int;
+
+    // DX9 media sharing APIs
+    #[cfg(feature = "cl_intel_dx9_media_sharing")]
+    fn clGetDeviceIDsFromDX9INTEL(
+        platform: cl_platform_id,
+        dx9_device_source: cl_dx9_device_source_intel,
+        dx9_object: *mut c_void,
+        dx9_device_set: cl_dx9_device_set_intel,
+        num_entries: cl_uint,
+        devices: *mut cl_device_id,
+        num_devices: *mut cl_uint,
+    ) -> cl_int;
+    #[cfg(feature = "cl_intel_dx9_media_sharing")]
+    fn clCreateFromDX9MediaSurfaceINTEL(
+        context: cl_context,
+        flags: cl_mem_flags,
+        resource: IDirect3DSurface9_ptr,
+        sharedHandle: HANDLE,
+        plane: cl_uint,
+        errcode_ret: *mut cl_int,
+    ) -> cl_mem [errcode_ret];
+    #[cfg(feature = "cl_intel_dx9_media_sharing")]
+    fn clEnqueueAcquireDX9ObjectsINTEL(
+        command_queue: cl_command_queue,
+        num_objects: cl_uint,
+        mem_objects: *const cl_mem,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    #[cfg(feature = "cl_intel_dx9_media_sharing")]
+    fn clEnqueueReleaseDX9ObjectsINTEL(
+        command_queue: cl_command_queue,
+        num_objects: cl_uint,
+        mem_objects: *const cl_mem,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    #[cfg(any(feature = "cl_khr_dx9_media_sharing", feature = "cl_intel_dx9_media_sharing"))]
+    fn clGetSupportedDX9MediaSurfaceFormatsINTEL(
+        context: cl_context,
+        flags: cl_mem_flags,
+        image_type: cl_mem_object_type,
+        plane: cl_uint,
+        num_entries: cl_uint,
+        dx9_formats: *mut D3DFORMAT,
+        num_surface_formats: *mut cl_uint,
+    ) -> cl_int;
+
+    // Layer APIs
+    #[cfg(feature = "cl_loader_layers")]
+    fn clGetLayerInfo(
+        param_name: cl_layer_info,
+        param_value_size: size_t,
+        param_value: *mut c_void,
+        param_value_size_ret: *mut size_t,
+    ) -> cl_int;
+    #[cfg(feature = "cl_loader_layers")]
+    fn clInitLayer(
+        num_entries: cl_uint,
+        target_dispatch: *const cl_icd_dispatch,
+        num_entries_ret: *mut cl_uint,
+        layer_dispatch_ret: *mut *const cl_icd_dispatch,
+    ) -> cl_int;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error_codes::error_text;
+
+    #[test]
+    fn test_load_missing_library() {
+        let result = open_library(OsStr::new("libOpenCL_does_not_exist.so"));
+        assert_eq!(CL_LIBRARY_NOT_LOADED, result.err().unwrap_or_default());
+        assert_eq!("CL_LIBRARY_NOT_LOADED", error_text(CL_LIBRARY_NOT_LOADED));
+    }
+
+    #[test]
+    fn test_object_error() {
+        let mut errcode_ret: cl_int = 0;
+        let context: cl_context =
+            unsafe { object_error(&mut errcode_ret, CL_SYMBOL_NOT_AVAILABLE) };
+        assert!(context.is_null());
+        assert_eq!(CL_SYMBOL_NOT_AVAILABLE, errcode_ret);
+
+        let context: cl_context = unsafe { object_error(ptr::null_mut(), CL_SYMBOL_NOT_AVAILABLE) };
+        assert!(context.is_null());
+    }
+
+    #[test]
+    fn test_extension_function() {
+        type TerminateContext = unsafe extern "system" fn(context: cl_context) -> cl_int;
+
+        unsafe extern "system" fn terminate_context(_context: cl_context) -> cl_int {
+            opencl_sys::CL_SUCCESS
+        }
+
+        unsafe extern "system" fn get_address(func_name: *const c_char) -> *mut c_void {
+            if b"clTerminateContextKHR" == std::ffi::CStr::from_ptr(func_name).to_bytes() {
+                terminate_context as *mut c_void
+            } else {
+                ptr::null_mut()
+            }
+        }
+
+        let function: Option<TerminateContext> =
+            unsafe { extension_function(Some(get_address), b"clTerminateContextKHR\0") };
+        let status = unsafe { function.unwrap()(ptr::null_mut()) };
+        assert_eq!(opencl_sys::CL_SUCCESS, status);
+
+        let function: Option<TerminateContext> =
+            unsafe { extension_function(Some(get_address), b"clUnknownFunctionKHR\0") };
+        assert!(function.is_none());
+        let function: Option<TerminateContext> =
+            unsafe { extension_function(None, b"clTerminateContextKHR\0") };
+        assert!(function.is_none());
+    }
+
+    #[test]
+    fn test_load_missing_library_is_not_stored() {
+        // A failed load must not prevent loading another library
+        let result = load_library("libOpenCL_does_not_exist.so");
+        assert!(result.is_err());
+        let result = load_library("libOpenCL_does_not_exist.so");
+        if OPENCL_LIBRARY.get().is_none() {
+            assert_eq!(Err(CL_LIBRARY_NOT_LOADED), result);
+        }
+    }
+}
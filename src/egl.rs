@@ -14,6 +14,11 @@
 
 //! `OpenCL` `OpenGL` ES Interoperability API.
 
+#[cfg(any(feature = "dynamic", feature = "mock"))]
+pub use super::ffi::{
+    clCreateEventFromEGLSyncKHR, clCreateFromEGLImageKHR, clEnqueueAcquireEGLObjectsKHR,
+    clEnqueueReleaseEGLObjectsKHR,
+};
 pub use opencl_sys::cl_egl::*;
 
 pub use opencl_sys::{
@@ -21,6 +26,10 @@ pub use opencl_sys::{
     CL_INVALID_VALUE, CL_SUCCESS,
 };
 
+#[allow(unused_imports)]
+use super::api_error::{clear_error, record_error};
+#[allow(unused_imports)]
+use super::owned::{AsCommandQueue, AsContext};
 #[allow(unused_imports)]
 use std::ptr;
 
@@ -44,18 +53,20 @@ use std::ptr;
 #[cfg(feature = "cl_khr_egl_image")]
 #[inline]
 pub unsafe fn create_from_egl_image(
-    context: cl_context,
+    context: impl AsContext,
     display: CLeglDisplayKHR,
     image: CLeglImageKHR,
     flags: cl_mem_flags,
     properties: *const cl_egl_image_properties_khr,
 ) -> Result<cl_mem, cl_int> {
+    let context = context.as_raw();
+    clear_error();
     let mut status: cl_int = CL_INVALID_VALUE;
     let mem = clCreateFromEGLImageKHR(context, display, image, flags, properties, &mut status);
     if CL_SUCCESS == status {
         Ok(mem)
     } else {
-        Err(status)
+        Err(record_error!(status, clCreateFromEGLImageKHR, flags))
     }
 }
 
@@ -78,12 +89,14 @@ pub unsafe fn create_from_egl_image(
 #[cfg(feature = "cl_khr_egl_image")]
 #[inline]
 pub unsafe fn enqueue_acquire_egl_objects(
-    command_queue: cl_command_queue,
+    command_queue: impl AsCommandQueue,
     num_objects: cl_uint,
     mem_objects: *const cl_mem,
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
+    let command_queue = command_queue.as_raw();
+    clear_error();
     let mut event: cl_event = ptr::null_mut();
     let status: cl_int = clEnqueueAcquireEGLObjectsKHR(
         command_queue,
@@ -96,7 +109,7 @@ pub unsafe fn enqueue_acquire_egl_objects(
     if CL_SUCCESS == status {
         Ok(event)
     } else {
-        Err(status)
+        Err(record_error!(status, clEnqueueAcquireEGLObjectsKHR))
     }
 }
 
@@ -119,12 +132,14 @@ pub unsafe fn enqueue_acquire_egl_objects(
 #[cfg(feature = "cl_khr_egl_image")]
 #[inline]
 pub unsafe fn enqueue_release_egl_objects(
-    command_queue: cl_command_queue,
+    command_queue: impl AsCommandQueue,
     num_objects: cl_uint,
     mem_objects: *const cl_mem,
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
+    let command_queue = command_queue.as_raw();
+    clear_error();
     let mut event: cl_event = ptr::null_mut();
     let status: cl_int = clEnqueueReleaseEGLObjectsKHR(
         command_queue,
@@ -137,7 +152,7 @@ pub unsafe fn enqueue_release_egl_objects(
     if CL_SUCCESS == status {
         Ok(event)
     } else {
-        Err(status)
+        Err(record_error!(status, clEnqueueReleaseEGLObjectsKHR))
     }
 }
 
@@ -158,15 +173,17 @@ pub unsafe fn enqueue_release_egl_objects(
 #[cfg(feature = "cl_khr_egl_event")]
 #[inline]
 pub unsafe fn create_event_from_egl_sync_khr(
-    context: cl_context,
+    context: impl AsContext,
     sync: CLeglSyncKHR,
     display: CLeglDisplayKHR,
 ) -> Result<cl_event, cl_int> {
+    let context = context.as_raw();
+    clear_error();
     let mut status: cl_int = CL_INVALID_VALUE;
     let event: cl_event = clCreateEventFromEGLSyncKHR(context, sync, display, &mut status);
     if CL_SUCCESS == status {
         Ok(event)
     } else {
-        Err(status)
+        Err(record_error!(status, clCreateEventFromEGLSyncKHR))
     }
 }
@@ -55,6 +55,13 @@ pub use opencl_sys::cl_egl::{CL_EGL_RESOURCE_NOT_ACQUIRED_KHR, CL_INVALID_EGL_OB
 use std::fmt;
 use thiserror::Error;
 
+// cl3 error codes, in a range that is not used by `OpenCL` or its extensions.
+
+/// The `OpenCL` library could not be loaded, see the "dynamic" feature.
+pub const CL_LIBRARY_NOT_LOADED: cl_int = -2000;
+/// The loaded `OpenCL` library does not provide the `OpenCL` API function.
+pub const CL_SYMBOL_NOT_AVAILABLE: cl_int = -2001;
+
 #[must_use]
 pub const fn error_text(error_code: cl_int) -> &'static str {
     match error_code {
@@ -161,6 +168,9 @@ pub const fn error_text(error_code: cl_int) -> &'static str {
         CL_INVALID_SYNC_POINT_WAIT_LIST_KHR => "CL_INVALID_SYNC_POINT_WAIT_LIST_KHR",
         CL_INCOMPATIBLE_COMMAND_QUEUE_KHR => "CL_INCOMPATIBLE_COMMAND_QUEUE_KHR",
 
+        CL_LIBRARY_NOT_LOADED => "CL_LIBRARY_NOT_LOADED",
+        CL_SYMBOL_NOT_AVAILABLE => "CL_SYMBOL_NOT_AVAILABLE",
+
         _ => "UNKNOWN_ERROR",
     }
 }
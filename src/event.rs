@@ -41,12 +41,14 @@ pub use opencl_sys::cl_egl::{
     CL_COMMAND_RELEASE_EGL_OBJECTS_KHR,
 };
 
-use opencl_sys::{
+use super::api_error::{clear_error, record_error};
+use super::ffi::{
     clCreateUserEvent, clGetEventInfo, clGetEventProfilingInfo, clReleaseEvent, clRetainEvent,
     clSetEventCallback, clSetUserEventStatus, clWaitForEvents,
 };
 
 use super::info_type::InfoType;
+use super::owned::{AsContext, AsEvent};
 use super::{api_info_size, api_info_value, api_info_vector};
 use libc::{c_void, intptr_t, size_t};
 use std::fmt;
@@ -62,17 +64,20 @@ use std::ptr;
 #[inline]
 #[allow(clippy::cast_possible_truncation)]
 pub fn wait_for_events(events: &[cl_event]) -> Result<(), cl_int> {
+    clear_error();
     let status: cl_int = unsafe { clWaitForEvents(events.len() as cl_uint, events.as_ptr()) };
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(status, clWaitForEvents))
     }
 }
 
 /// Get data about an `OpenCL` event.
 /// Calls `clGetEventInfo` to get the desired data about the event.
-pub fn get_event_data(event: cl_event, param_name: cl_event_info) -> Result<Vec<u8>, cl_int> {
+pub fn get_event_data(event: impl AsEvent, param_name: cl_event_info) -> Result<Vec<u8>, cl_int> {
+    let event = event.as_raw();
+    clear_error();
     api_info_size!(get_size, clGetEventInfo);
     let size = get_size(event, param_name)?;
     api_info_vector!(get_vector, u8, clGetEventInfo);
@@ -88,7 +93,9 @@ pub fn get_event_data(event: cl_event, param_name: cl_event_info) -> Result<Vec<
 ///
 /// returns a Result containing the desired information in an `InfoType` enum
 /// or the error code from the `OpenCL` C API function.
-pub fn get_event_info(event: cl_event, param_name: cl_event_info) -> Result<InfoType, cl_int> {
+pub fn get_event_info(event: impl AsEvent, param_name: cl_event_info) -> Result<InfoType, cl_int> {
+    let event = event.as_raw();
+    clear_error();
     match param_name {
         CL_EVENT_COMMAND_EXECUTION_STATUS => {
             api_info_value!(get_value, cl_int, clGetEventInfo);
@@ -117,13 +124,15 @@ pub fn get_event_info(event: cl_event, param_name: cl_event_info) -> Result<Info
 /// returns a Result containing the new `OpenCL` event object
 /// or the error code from the `OpenCL` C API function.
 #[inline]
-pub fn create_user_event(context: cl_context) -> Result<cl_event, cl_int> {
+pub fn create_user_event(context: impl AsContext) -> Result<cl_event, cl_int> {
+    let context = context.as_raw();
+    clear_error();
     let mut status: cl_int = CL_INVALID_VALUE;
     let event: cl_event = unsafe { clCreateUserEvent(context, &mut status) };
     if CL_SUCCESS == status {
         Ok(event)
     } else {
-        Err(status)
+        Err(record_error!(status, clCreateUserEvent))
     }
 }
 
@@ -139,11 +148,12 @@ pub fn create_user_event(context: cl_context) -> Result<cl_event, cl_int> {
 /// This function is unsafe because it changes the `OpenCL` object reference count.
 #[inline]
 pub unsafe fn retain_event(event: cl_event) -> Result<(), cl_int> {
+    clear_error();
     let status: cl_int = clRetainEvent(event);
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(status, clRetainEvent))
     }
 }
 
@@ -159,11 +169,12 @@ pub unsafe fn retain_event(event: cl_event) -> Result<(), cl_int> {
 /// This function is unsafe because it changes the `OpenCL` object reference count.
 #[inline]
 pub unsafe fn release_event(event: cl_event) -> Result<(), cl_int> {
+    clear_error();
     let status: cl_int = clReleaseEvent(event);
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(status, clReleaseEvent))
     }
 }
 
@@ -175,12 +186,18 @@ pub unsafe fn release_event(event: cl_event) -> Result<(), cl_int> {
 ///
 /// returns an empty Result or the error code from the `OpenCL` C API function.
 #[inline]
-pub fn set_user_event_status(event: cl_event, execution_status: cl_int) -> Result<(), cl_int> {
+pub fn set_user_event_status(event: impl AsEvent, execution_status: cl_int) -> Result<(), cl_int> {
+    let event = event.as_raw();
+    clear_error();
     let status: cl_int = unsafe { clSetUserEventStatus(event, execution_status) };
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(
+            status,
+            clSetUserEventStatus,
+            execution_status
+        ))
     }
 }
 
@@ -194,11 +211,13 @@ pub fn set_user_event_status(event: cl_event, execution_status: cl_int) -> Resul
 /// returns an empty Result or the error code from the `OpenCL` C API function.
 #[inline]
 pub fn set_event_callback(
-    event: cl_event,
+    event: impl AsEvent,
     command_exec_callback_type: cl_int,
     pfn_notify: extern "C" fn(cl_event, cl_int, *mut c_void),
     user_data: *mut c_void,
 ) -> Result<(), cl_int> {
+    let event = event.as_raw();
+    clear_error();
     let status: cl_int = unsafe {
         clSetEventCallback(
             event,
@@ -210,16 +229,22 @@ pub fn set_event_callback(
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(
+            status,
+            clSetEventCallback,
+            command_exec_callback_type
+        ))
     }
 }
 
 /// Get profiling data about an `OpenCL` event.
 /// Calls `clGetEventProfilingInfo` to get the desired profiling data about the event.
 pub fn get_event_profiling_data(
-    event: cl_event,
+    event: impl AsEvent,
     param_name: cl_profiling_info,
 ) -> Result<Vec<u8>, cl_int> {
+    let event = event.as_raw();
+    clear_error();
     api_info_size!(get_size, clGetEventProfilingInfo);
     let size = get_size(event, param_name)?;
     api_info_vector!(get_vector, u8, clGetEventProfilingInfo);
@@ -237,9 +262,11 @@ pub fn get_event_profiling_data(
 /// returns a Result containing the desired information in an `InfoType` enum
 /// or the error code from the `OpenCL` C API function.
 pub fn get_event_profiling_info(
-    event: cl_event,
+    event: impl AsEvent,
     param_name: cl_profiling_info,
 ) -> Result<InfoType, cl_int> {
+    let event = event.as_raw();
+    clear_error();
     match param_name {
         CL_PROFILING_COMMAND_QUEUED
         | CL_PROFILING_COMMAND_SUBMIT
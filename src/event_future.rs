@@ -0,0 +1,212 @@
+// Copyright (c) 2024 Via Technology Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! `OpenCL` events as Rust futures.
+//!
+//! An [`EventFuture`] registers a `CL_COMPLETE` callback on an event that
+//! wakes the task awaiting it, so that an async task can `.await` the
+//! completion of a command instead of blocking a thread in `clWaitForEvents`.
+//! It does not depend upon any async runtime, e.g.:
+//!
+//! ```no_run
+//! use cl3::event_future::EventFuture;
+//! use cl3::types::{cl_event, cl_int};
+//!
+//! async fn example(kernel_event: cl_event) -> Result<(), cl_int> {
+//!     EventFuture::new(kernel_event)?.await?;
+//!     Ok(())
+//! }
+//! ```
+
+#![allow(non_camel_case_types)]
+
+use super::event::{set_event_callback, CL_COMPLETE};
+use super::owned::{AsEvent, Event};
+use libc::c_void;
+use opencl_sys::{cl_event, cl_int};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::task::{Context, Poll, Waker};
+
+/// The state shared between an `EventFuture` and its event callback.
+#[derive(Debug, Default)]
+struct Shared {
+    status: Option<cl_int>,
+    waker: Option<Waker>,
+}
+
+fn lock(shared: &Mutex<Shared>) -> MutexGuard<'_, Shared> {
+    // The lock is never held while calling user code, so ignore poisoning
+    shared.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// The `CL_COMPLETE` event callback, `user_data` is an `Arc<Mutex<Shared>>`.
+extern "C" fn event_complete(_event: cl_event, status: cl_int, user_data: *mut c_void) {
+    // OpenCL calls the callback once, so take back the reference given to it
+    let shared = unsafe { Arc::from_raw(user_data.cast_const().cast::<Mutex<Shared>>()) };
+    let waker = {
+        let mut shared = lock(&shared);
+        shared.status = Some(status);
+        shared.waker.take()
+    };
+    if let Some(waker) = waker {
+        waker.wake();
+    }
+}
+
+/// A future that resolves when an `OpenCL` event completes.
+///
+/// The future resolves to the final command execution status of the event:
+/// `Ok(CL_COMPLETE)` or, if the command was abnormally terminated,
+/// `Err` holding the negative error code.
+///
+/// The future holds a reference to the event, so the event may be released
+/// by the caller after the future has been created.
+#[derive(Debug)]
+pub struct EventFuture {
+    event: Event,
+    shared: Arc<Mutex<Shared>>,
+}
+
+impl EventFuture {
+    /// Create a future for an `OpenCL` event.
+    /// Retains the event and calls `clSetEventCallback` to register a
+    /// `CL_COMPLETE` callback that wakes the task awaiting the future.
+    ///
+    /// * `event` - the `OpenCL` event.
+    ///
+    /// returns a Result containing the new `EventFuture`
+    /// or the error code from the `OpenCL` C API function.
+    pub fn new(event: impl AsEvent) -> Result<Self, cl_int> {
+        let event = unsafe { Event::retain_raw(event.as_raw())? };
+        let shared = Arc::new(Mutex::new(Shared::default()));
+
+        // The callback owns a reference to the shared state until it is called
+        let user_data = Arc::into_raw(Arc::clone(&shared))
+            .cast_mut()
+            .cast::<c_void>();
+        if let Err(error) = set_event_callback(&event, CL_COMPLETE, event_complete, user_data) {
+            drop(unsafe { Arc::from_raw(user_data.cast_const().cast::<Mutex<Shared>>()) });
+            return Err(error);
+        }
+
+        Ok(Self { event, shared })
+    }
+
+    /// The event of the future.
+    #[must_use]
+    pub const fn event(&self) -> &Event {
+        &self.event
+    }
+
+    /// The final command execution status of the event, if it has completed.
+    #[must_use]
+    pub fn status(&self) -> Option<cl_int> {
+        lock(&self.shared).status
+    }
+}
+
+impl Future for EventFuture {
+    type Output = Result<cl_int, cl_int>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut shared = lock(&self.shared);
+        match shared.status {
+            Some(status) if status < 0 => Poll::Ready(Err(status)),
+            Some(status) => Poll::Ready(Ok(status)),
+            None => {
+                match &mut shared.waker {
+                    Some(waker) => waker.clone_from(cx.waker()),
+                    waker @ None => *waker = Some(cx.waker().clone()),
+                }
+                Poll::Pending
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::{create_context, release_context};
+    use crate::device::{get_device_ids, CL_DEVICE_TYPE_GPU};
+    use crate::event::{create_user_event, release_event, set_user_event_status};
+    use crate::platform::get_platform_ids;
+    use opencl_sys::CL_INVALID_EVENT;
+    use std::ptr;
+    use std::sync::mpsc;
+    use std::task::Wake;
+    use std::thread;
+
+    /// A minimal executor that parks the current thread until it is woken.
+    struct ThreadWaker(thread::Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = Box::pin(future);
+        let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+        let mut cx = Context::from_waker(&waker);
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return output;
+            }
+            thread::park();
+        }
+    }
+
+    #[test]
+    fn test_event_future() {
+        let platform_ids = get_platform_ids().unwrap();
+        assert!(0 < platform_ids.len());
+
+        let device_ids = get_device_ids(platform_ids[0], CL_DEVICE_TYPE_GPU).unwrap();
+        assert!(0 < device_ids.len());
+
+        let context = create_context(&device_ids, ptr::null(), None, ptr::null_mut()).unwrap();
+
+        // Complete a user event from another thread while awaiting it
+        let user_event = create_user_event(context).unwrap();
+        let future = EventFuture::new(user_event).unwrap();
+        unsafe { release_event(user_event).unwrap() };
+        assert_eq!(None, future.status());
+
+        let (sender, receiver) = mpsc::channel();
+        let event = future.event().clone();
+        let handle = thread::spawn(move || {
+            receiver.recv().unwrap();
+            set_user_event_status(&event, CL_COMPLETE).unwrap();
+        });
+        let result = block_on(async {
+            sender.send(()).unwrap();
+            future.await
+        });
+        handle.join().unwrap();
+        assert_eq!(Ok(CL_COMPLETE), result);
+
+        // A negative status is an error
+        let user_event = create_user_event(context).unwrap();
+        set_user_event_status(user_event, CL_INVALID_EVENT).unwrap();
+        let future = EventFuture::new(user_event).unwrap();
+        unsafe { release_event(user_event).unwrap() };
+        assert_eq!(Err(CL_INVALID_EVENT), block_on(future));
+
+        unsafe { release_context(context).unwrap() };
+    }
+}
@@ -25,9 +25,93 @@
 
 pub use opencl_sys::*;
 
+#[cfg(feature = "cl_intel_create_buffer_with_properties")]
+pub use super::ffi::clCreateBufferWithPropertiesINTEL;
+#[cfg(feature = "cl_khr_create_command_queue")]
+pub use super::ffi::clCreateCommandQueueWithPropertiesKHR;
+#[cfg(feature = "cl_khr_il_program")]
+pub use super::ffi::clCreateProgramWithILKHR;
+#[cfg(feature = "cl_img_generate_mipmap")]
+pub use super::ffi::clEnqueueGenerateMipmapIMG;
+#[cfg(feature = "cl_ext_migrate_memobject")]
+pub use super::ffi::clEnqueueMigrateMemObjectEXT;
+#[cfg(feature = "cl_qcom_ext_host_ptr")]
+pub use super::ffi::clGetDeviceImageInfoQCOM;
+#[cfg(feature = "cl_loader_info")]
+pub use super::ffi::clGetICDLoaderInfoOCLICD;
+#[cfg(feature = "cl_ext_image_requirements_info")]
+pub use super::ffi::clGetImageRequirementsInfoEXT;
+#[cfg(feature = "cl_khr_subgroups")]
+pub use super::ffi::clGetKernelSubGroupInfoKHR;
+#[cfg(feature = "cl_khr_suggested_local_work_size")]
+pub use super::ffi::clGetKernelSuggestedLocalWorkSizeKHR;
+#[cfg(feature = "cl_khr_external_semaphore")]
+pub use super::ffi::clGetSemaphoreHandleForTypeKHR;
+#[cfg(feature = "cl_khr_icd")]
+pub use super::ffi::clIcdGetPlatformIDsKHR;
+#[cfg(feature = "cl_arm_import_memory")]
+pub use super::ffi::clImportMemoryARM;
+#[cfg(feature = "cl_khr_external_semaphore_sync_fd")]
+pub use super::ffi::clReImportSemaphoreSyncFdKHR;
+#[cfg(feature = "cl_khr_command_buffer_multi_device")]
+pub use super::ffi::clRemapCommandBufferKHR;
+#[cfg(feature = "cl_pocl_content_size")]
+pub use super::ffi::clSetContentSizeBufferPoCL;
+#[cfg(feature = "cl_apple_setmemobjectdestructor")]
+pub use super::ffi::clSetMemObjectDestructorAPPLE;
+#[cfg(feature = "cl_khr_terminate_context")]
+pub use super::ffi::clTerminateContextKHR;
+#[cfg(feature = "cl_khr_command_buffer")]
+pub use super::ffi::{
+    clCommandBarrierWithWaitListKHR, clCommandCopyBufferKHR, clCommandCopyBufferRectKHR,
+    clCommandCopyBufferToImageKHR, clCommandCopyImageKHR, clCommandCopyImageToBufferKHR,
+    clCommandFillBufferKHR, clCommandFillImageKHR, clCommandNDRangeKernelKHR,
+    clCommandSVMMemFillKHR, clCommandSVMMemcpyKHR, clCreateCommandBufferKHR,
+    clEnqueueCommandBufferKHR, clFinalizeCommandBufferKHR, clGetCommandBufferInfoKHR,
+    clReleaseCommandBufferKHR, clRetainCommandBufferKHR,
+};
+#[cfg(feature = "cl_intel_accelerator")]
+pub use super::ffi::{
+    clCreateAcceleratorINTEL, clGetAcceleratorInfoINTEL, clReleaseAcceleratorINTEL,
+    clRetainAcceleratorINTEL,
+};
+#[cfg(feature = "cl_khr_semaphore")]
+pub use super::ffi::{
+    clCreateSemaphoreWithPropertiesKHR, clEnqueueSignalSemaphoresKHR, clEnqueueWaitSemaphoresKHR,
+    clGetSemaphoreInfoKHR, clReleaseSemaphoreKHR, clRetainSemaphoreKHR,
+};
+#[cfg(feature = "cl_ext_device_fission")]
+pub use super::ffi::{clCreateSubDevicesEXT, clReleaseDeviceEXT, clRetainDeviceEXT};
+#[cfg(feature = "cl_intel_unified_shared_memory")]
+pub use super::ffi::{
+    clDeviceMemAllocINTEL, clEnqueueMemAdviseINTEL, clEnqueueMemFillINTEL, clEnqueueMemcpyINTEL,
+    clEnqueueMemsetINTEL, clEnqueueMigrateMemINTEL, clGetMemAllocInfoINTEL, clHostMemAllocINTEL,
+    clMemBlockingFreeINTEL, clMemFreeINTEL, clSetKernelArgMemPointerINTEL, clSharedMemAllocINTEL,
+};
+#[cfg(feature = "cl_khr_external_memory")]
+pub use super::ffi::{
+    clEnqueueAcquireExternalMemObjectsKHR, clEnqueueReleaseExternalMemObjectsKHR,
+};
+#[cfg(feature = "cl_img_use_gralloc_ptr")]
+pub use super::ffi::{clEnqueueAcquireGrallocObjectsIMG, clEnqueueReleaseGrallocObjectsIMG};
+#[cfg(feature = "cl_intel_program_scope_host_pipe")]
+pub use super::ffi::{clEnqueueReadHostPipeINTEL, clEnqueueWriteHostPipeINTEL};
+#[cfg(feature = "cl_arm_shared_virtual_memory")]
+pub use super::ffi::{
+    clEnqueueSVMFreeARM, clEnqueueSVMMapARM, clEnqueueSVMMemFillARM, clEnqueueSVMMemcpyARM,
+    clEnqueueSVMUnmapARM, clSVMAllocARM, clSVMFreeARM, clSetKernelArgSVMPointerARM,
+    clSetKernelExecInfoARM,
+};
+#[cfg(feature = "cl_khr_command_buffer_mutable_dispatch")]
+pub use super::ffi::{clGetMutableCommandInfoKHR, clUpdateMutableCommandsKHR};
+
+#[allow(unused_imports)]
+use super::api_error::{clear_error, record_error};
 #[allow(unused_imports)]
 use super::info_type::InfoType;
 #[allow(unused_imports)]
+use super::owned::{AsCommandQueue, AsContext, AsDevice, AsKernel, AsMem, AsProgram};
+#[allow(unused_imports)]
 use super::{api_info_size, api_info_value, api_info_vector};
 #[allow(unused_imports)]
 use libc::{c_char, c_int, c_void, intptr_t, size_t};
@@ -42,6 +126,7 @@ pub fn create_command_buffer_khr(
     queues: &[cl_command_queue],
     properties: *const cl_command_buffer_properties_khr,
 ) -> Result<cl_command_buffer_khr, cl_int> {
+    clear_error();
     let mut status: cl_int = CL_INVALID_VALUE;
     let buffer = unsafe {
         clCreateCommandBufferKHR(
@@ -54,17 +139,18 @@ pub fn create_command_buffer_khr(
     if CL_SUCCESS == status {
         Ok(buffer)
     } else {
-        Err(status)
+        Err(record_error!(status, clCreateCommandBufferKHR))
     }
 }
 
 #[cfg(feature = "cl_khr_command_buffer")]
 pub fn finalize_command_buffer_khr(command_buffer: cl_command_buffer_khr) -> Result<(), cl_int> {
+    clear_error();
     let status: cl_int = unsafe { clFinalizeCommandBufferKHR(command_buffer) };
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(status, clFinalizeCommandBufferKHR))
     }
 }
 
@@ -72,11 +158,12 @@ pub fn finalize_command_buffer_khr(command_buffer: cl_command_buffer_khr) -> Res
 pub unsafe fn retain_command_buffer_khr(
     command_buffer: cl_command_buffer_khr,
 ) -> Result<(), cl_int> {
+    clear_error();
     let status: cl_int = clRetainCommandBufferKHR(command_buffer);
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(status, clRetainCommandBufferKHR))
     }
 }
 
@@ -84,11 +171,12 @@ pub unsafe fn retain_command_buffer_khr(
 pub unsafe fn release_command_buffer_khr(
     command_buffer: cl_command_buffer_khr,
 ) -> Result<(), cl_int> {
+    clear_error();
     let status: cl_int = clReleaseCommandBufferKHR(command_buffer);
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(status, clReleaseCommandBufferKHR))
     }
 }
 
@@ -100,6 +188,7 @@ pub unsafe fn enqueue_command_buffer_khr(
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
+    clear_error();
     let mut event: cl_event = ptr::null_mut();
     let status: cl_int = clEnqueueCommandBufferKHR(
         num_queues,
@@ -112,7 +201,7 @@ pub unsafe fn enqueue_command_buffer_khr(
     if CL_SUCCESS == status {
         Ok(event)
     } else {
-        Err(status)
+        Err(record_error!(status, clEnqueueCommandBufferKHR))
     }
 }
 
@@ -120,11 +209,13 @@ pub unsafe fn enqueue_command_buffer_khr(
 #[allow(clippy::cast_possible_truncation)]
 pub unsafe fn command_barrier_with_wait_list_khr(
     command_buffer: cl_command_buffer_khr,
-    command_queue: cl_command_queue,
+    command_queue: impl AsCommandQueue,
     sync_point_wait_list: &[cl_sync_point_khr],
     sync_point: *mut cl_sync_point_khr,
     mutable_handle: *mut cl_mutable_command_khr,
 ) -> Result<(), cl_int> {
+    let command_queue = command_queue.as_raw();
+    clear_error();
     let status: cl_int = clCommandBarrierWithWaitListKHR(
         command_buffer,
         command_queue,
@@ -136,7 +227,7 @@ pub unsafe fn command_barrier_with_wait_list_khr(
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(status, clCommandBarrierWithWaitListKHR))
     }
 }
 
@@ -144,9 +235,9 @@ pub unsafe fn command_barrier_with_wait_list_khr(
 #[allow(clippy::cast_possible_truncation)]
 pub unsafe fn command_copy_buffer_khr(
     command_buffer: cl_command_buffer_khr,
-    command_queue: cl_command_queue,
-    src_buffer: cl_mem,
-    dst_buffer: cl_mem,
+    command_queue: impl AsCommandQueue,
+    src_buffer: impl AsMem,
+    dst_buffer: impl AsMem,
     src_offset: size_t,
     dst_offset: size_t,
     size: size_t,
@@ -154,6 +245,10 @@ pub unsafe fn command_copy_buffer_khr(
     sync_point: *mut cl_sync_point_khr,
     mutable_handle: *mut cl_mutable_command_khr,
 ) -> Result<(), cl_int> {
+    let command_queue = command_queue.as_raw();
+    let src_buffer = src_buffer.as_raw();
+    let dst_buffer = dst_buffer.as_raw();
+    clear_error();
     let status: cl_int = clCommandCopyBufferKHR(
         command_buffer,
         command_queue,
@@ -170,7 +265,13 @@ pub unsafe fn command_copy_buffer_khr(
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(
+            status,
+            clCommandCopyBufferKHR,
+            src_offset,
+            dst_offset,
+            size
+        ))
     }
 }
 
@@ -178,9 +279,9 @@ pub unsafe fn command_copy_buffer_khr(
 #[allow(clippy::cast_possible_truncation)]
 pub unsafe fn command_copy_buffer_rect_khr(
     command_buffer: cl_command_buffer_khr,
-    command_queue: cl_command_queue,
-    src_buffer: cl_mem,
-    dst_buffer: cl_mem,
+    command_queue: impl AsCommandQueue,
+    src_buffer: impl AsMem,
+    dst_buffer: impl AsMem,
     src_origin: *const size_t,
     dst_origin: *const size_t,
     region: *const size_t,
@@ -192,6 +293,10 @@ pub unsafe fn command_copy_buffer_rect_khr(
     sync_point: *mut cl_sync_point_khr,
     mutable_handle: *mut cl_mutable_command_khr,
 ) -> Result<(), cl_int> {
+    let command_queue = command_queue.as_raw();
+    let src_buffer = src_buffer.as_raw();
+    let dst_buffer = dst_buffer.as_raw();
+    clear_error();
     let status: cl_int = clCommandCopyBufferRectKHR(
         command_buffer,
         command_queue,
@@ -212,7 +317,7 @@ pub unsafe fn command_copy_buffer_rect_khr(
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(status, clCommandCopyBufferRectKHR))
     }
 }
 
@@ -220,9 +325,9 @@ pub unsafe fn command_copy_buffer_rect_khr(
 #[allow(clippy::cast_possible_truncation)]
 pub unsafe fn command_copy_buffer_to_image_khr(
     command_buffer: cl_command_buffer_khr,
-    command_queue: cl_command_queue,
-    src_buffer: cl_mem,
-    dst_image: cl_mem,
+    command_queue: impl AsCommandQueue,
+    src_buffer: impl AsMem,
+    dst_image: impl AsMem,
     src_offset: size_t,
     dst_origin: *const size_t,
     region: *const size_t,
@@ -230,6 +335,10 @@ pub unsafe fn command_copy_buffer_to_image_khr(
     sync_point: *mut cl_sync_point_khr,
     mutable_handle: *mut cl_mutable_command_khr,
 ) -> Result<(), cl_int> {
+    let command_queue = command_queue.as_raw();
+    let src_buffer = src_buffer.as_raw();
+    let dst_image = dst_image.as_raw();
+    clear_error();
     let status: cl_int = clCommandCopyBufferToImageKHR(
         command_buffer,
         command_queue,
@@ -246,7 +355,11 @@ pub unsafe fn command_copy_buffer_to_image_khr(
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(
+            status,
+            clCommandCopyBufferToImageKHR,
+            src_offset
+        ))
     }
 }
 
@@ -254,9 +367,9 @@ pub unsafe fn command_copy_buffer_to_image_khr(
 #[allow(clippy::cast_possible_truncation)]
 pub unsafe fn command_copy_image_khr(
     command_buffer: cl_command_buffer_khr,
-    command_queue: cl_command_queue,
-    src_image: cl_mem,
-    dst_image: cl_mem,
+    command_queue: impl AsCommandQueue,
+    src_image: impl AsMem,
+    dst_image: impl AsMem,
     src_origin: *const size_t,
     dst_origin: *const size_t,
     region: *const size_t,
@@ -264,6 +377,10 @@ pub unsafe fn command_copy_image_khr(
     sync_point: *mut cl_sync_point_khr,
     mutable_handle: *mut cl_mutable_command_khr,
 ) -> Result<(), cl_int> {
+    let command_queue = command_queue.as_raw();
+    let src_image = src_image.as_raw();
+    let dst_image = dst_image.as_raw();
+    clear_error();
     let status: cl_int = clCommandCopyImageKHR(
         command_buffer,
         command_queue,
@@ -280,7 +397,7 @@ pub unsafe fn command_copy_image_khr(
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(status, clCommandCopyImageKHR))
     }
 }
 
@@ -288,9 +405,9 @@ pub unsafe fn command_copy_image_khr(
 #[allow(clippy::cast_possible_truncation)]
 pub unsafe fn command_copy_image_to_buffer_khr(
     command_buffer: cl_command_buffer_khr,
-    command_queue: cl_command_queue,
-    src_image: cl_mem,
-    dst_buffer: cl_mem,
+    command_queue: impl AsCommandQueue,
+    src_image: impl AsMem,
+    dst_buffer: impl AsMem,
     src_origin: *const size_t,
     region: *const size_t,
     dst_offset: size_t,
@@ -298,6 +415,10 @@ pub unsafe fn command_copy_image_to_buffer_khr(
     sync_point: *mut cl_sync_point_khr,
     mutable_handle: *mut cl_mutable_command_khr,
 ) -> Result<(), cl_int> {
+    let command_queue = command_queue.as_raw();
+    let src_image = src_image.as_raw();
+    let dst_buffer = dst_buffer.as_raw();
+    clear_error();
     let status: cl_int = clCommandCopyImageToBufferKHR(
         command_buffer,
         command_queue,
@@ -314,7 +435,11 @@ pub unsafe fn command_copy_image_to_buffer_khr(
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(
+            status,
+            clCommandCopyImageToBufferKHR,
+            dst_offset
+        ))
     }
 }
 
@@ -322,8 +447,8 @@ pub unsafe fn command_copy_image_to_buffer_khr(
 #[allow(clippy::cast_possible_truncation)]
 pub unsafe fn command_fill_buffer_khr(
     command_buffer: cl_command_buffer_khr,
-    command_queue: cl_command_queue,
-    buffer: cl_mem,
+    command_queue: impl AsCommandQueue,
+    buffer: impl AsMem,
     pattern: *const c_void,
     pattern_size: size_t,
     offset: size_t,
@@ -332,6 +457,9 @@ pub unsafe fn command_fill_buffer_khr(
     sync_point: *mut cl_sync_point_khr,
     mutable_handle: *mut cl_mutable_command_khr,
 ) -> Result<(), cl_int> {
+    let command_queue = command_queue.as_raw();
+    let buffer = buffer.as_raw();
+    clear_error();
     let status: cl_int = clCommandFillBufferKHR(
         command_buffer,
         command_queue,
@@ -348,7 +476,13 @@ pub unsafe fn command_fill_buffer_khr(
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(
+            status,
+            clCommandFillBufferKHR,
+            offset,
+            size,
+            pattern_size
+        ))
     }
 }
 
@@ -356,8 +490,8 @@ pub unsafe fn command_fill_buffer_khr(
 #[allow(clippy::cast_possible_truncation)]
 pub unsafe fn command_fill_image_khr(
     command_buffer: cl_command_buffer_khr,
-    command_queue: cl_command_queue,
-    image: cl_mem,
+    command_queue: impl AsCommandQueue,
+    image: impl AsMem,
     fill_color: *const c_void,
     origin: *const size_t,
     region: *const size_t,
@@ -365,6 +499,9 @@ pub unsafe fn command_fill_image_khr(
     sync_point: *mut cl_sync_point_khr,
     mutable_handle: *mut cl_mutable_command_khr,
 ) -> Result<(), cl_int> {
+    let command_queue = command_queue.as_raw();
+    let image = image.as_raw();
+    clear_error();
     let status: cl_int = clCommandFillImageKHR(
         command_buffer,
         command_queue,
@@ -380,7 +517,7 @@ pub unsafe fn command_fill_image_khr(
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(status, clCommandFillImageKHR))
     }
 }
 
@@ -388,9 +525,9 @@ pub unsafe fn command_fill_image_khr(
 #[allow(clippy::cast_possible_truncation)]
 pub unsafe fn command_nd_range_kernel_khr(
     command_buffer: cl_command_buffer_khr,
-    command_queue: cl_command_queue,
+    command_queue: impl AsCommandQueue,
     properties: *const cl_ndrange_kernel_command_properties_khr,
-    kernel: cl_kernel,
+    kernel: impl AsKernel,
     work_dim: cl_uint,
     global_work_offset: *const size_t,
     global_work_size: *const size_t,
@@ -399,6 +536,9 @@ pub unsafe fn command_nd_range_kernel_khr(
     sync_point: *mut cl_sync_point_khr,
     mutable_handle: *mut cl_mutable_command_khr,
 ) -> Result<(), cl_int> {
+    let command_queue = command_queue.as_raw();
+    let kernel = kernel.as_raw();
+    clear_error();
     let status: cl_int = clCommandNDRangeKernelKHR(
         command_buffer,
         command_queue,
@@ -416,7 +556,7 @@ pub unsafe fn command_nd_range_kernel_khr(
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(status, clCommandNDRangeKernelKHR, work_dim))
     }
 }
 
@@ -424,7 +564,7 @@ pub unsafe fn command_nd_range_kernel_khr(
 #[allow(clippy::cast_possible_truncation)]
 pub unsafe fn command_svm_memcpy_khr(
     command_buffer: cl_command_buffer_khr,
-    command_queue: cl_command_queue,
+    command_queue: impl AsCommandQueue,
     dst_ptr: *mut c_void,
     src_ptr: *const c_void,
     size: size_t,
@@ -432,6 +572,8 @@ pub unsafe fn command_svm_memcpy_khr(
     sync_point: *mut cl_sync_point_khr,
     mutable_handle: *mut cl_mutable_command_khr,
 ) -> Result<(), cl_int> {
+    let command_queue = command_queue.as_raw();
+    clear_error();
     let status: cl_int = clCommandSVMMemcpyKHR(
         command_buffer,
         command_queue,
@@ -446,7 +588,7 @@ pub unsafe fn command_svm_memcpy_khr(
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(status, clCommandSVMMemcpyKHR, size))
     }
 }
 
@@ -454,7 +596,7 @@ pub unsafe fn command_svm_memcpy_khr(
 #[allow(clippy::cast_possible_truncation)]
 pub unsafe fn command_svm_mem_fill_khr(
     command_buffer: cl_command_buffer_khr,
-    command_queue: cl_command_queue,
+    command_queue: impl AsCommandQueue,
     svm_ptr: *mut c_void,
     pattern: *const c_void,
     pattern_size: size_t,
@@ -463,6 +605,8 @@ pub unsafe fn command_svm_mem_fill_khr(
     sync_point: *mut cl_sync_point_khr,
     mutable_handle: *mut cl_mutable_command_khr,
 ) -> Result<(), cl_int> {
+    let command_queue = command_queue.as_raw();
+    clear_error();
     let status: cl_int = clCommandSVMMemFillKHR(
         command_buffer,
         command_queue,
@@ -478,7 +622,12 @@ pub unsafe fn command_svm_mem_fill_khr(
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(
+            status,
+            clCommandSVMMemFillKHR,
+            size,
+            pattern_size
+        ))
     }
 }
 
@@ -487,6 +636,7 @@ pub fn get_command_buffer_data_khr(
     command_buffer: cl_command_buffer_khr,
     param_name: cl_command_buffer_info_khr,
 ) -> Result<Vec<u8>, cl_int> {
+    clear_error();
     api_info_size!(get_size, clGetCommandBufferInfoKHR);
     let size = get_size(command_buffer, param_name)?;
     api_info_vector!(get_vector, u8, clGetCommandBufferInfoKHR);
@@ -498,6 +648,7 @@ pub fn get_command_buffer_info_khr(
     command_queue: cl_command_buffer_khr,
     param_name: cl_command_buffer_info_khr,
 ) -> Result<InfoType, cl_int> {
+    clear_error();
     match param_name {
         CL_COMMAND_BUFFER_NUM_QUEUES_KHR
         | CL_COMMAND_BUFFER_REFERENCE_COUNT_KHR
@@ -549,6 +700,7 @@ pub unsafe fn remap_command_buffer_khr(
     handles: *const cl_mutable_command_khr,
     handles_ret: *mut cl_mutable_command_khr,
 ) -> Result<cl_command_buffer_khr, cl_int> {
+    clear_error();
     let mut errcode_ret: cl_int = CL_INVALID_VALUE;
     let cmd_buffer = clRemapCommandBufferKHR(
         command_buffer,
@@ -563,7 +715,7 @@ pub unsafe fn remap_command_buffer_khr(
     if CL_SUCCESS == errcode_ret {
         Ok(cmd_buffer)
     } else {
-        Err(errcode_ret)
+        Err(record_error!(errcode_ret, clRemapCommandBufferKHR))
     }
 }
 
@@ -572,11 +724,12 @@ pub unsafe fn update_mutable_commands_khr(
     command_buffer: cl_command_buffer_khr,
     mutable_config: *const cl_mutable_base_config_khr,
 ) -> Result<(), cl_int> {
+    clear_error();
     let status: cl_int = clUpdateMutableCommandsKHR(command_buffer, mutable_config);
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(status, clUpdateMutableCommandsKHR))
     }
 }
 
@@ -585,6 +738,7 @@ pub fn get_command_buffer_mutable_dispatch_data(
     command: cl_mutable_command_khr,
     param_name: cl_mutable_command_info_khr,
 ) -> Result<Vec<u8>, cl_int> {
+    clear_error();
     api_info_size!(get_size, clGetMutableCommandInfoKHR);
     let size = get_size(command, param_name)?;
     api_info_vector!(get_vector, u8, clGetMutableCommandInfoKHR);
@@ -593,27 +747,30 @@ pub fn get_command_buffer_mutable_dispatch_data(
 
 #[cfg(feature = "cl_apple_setmemobjectdestructor")]
 pub unsafe fn set_mem_object_destructor_apple(
-    memobj: cl_mem,
+    memobj: impl AsMem,
     pfn_notify: Option<unsafe extern "C" fn(cl_context, *mut c_void)>,
     user_data: *mut c_void,
 ) -> Result<(), cl_int> {
+    let memobj = memobj.as_raw();
+    clear_error();
     let status: cl_int = clSetMemObjectDestructorAPPLE(memobj, pfn_notify, user_data);
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(status, clSetMemObjectDestructorAPPLE))
     }
 }
 
 #[cfg(feature = "cl_khr_icd")]
 #[allow(clippy::uninit_vec)]
 pub fn icd_get_platform_ids_khr() -> Result<Vec<cl_platform_id>, cl_int> {
+    clear_error();
     // Get the number of platforms
     let mut count: cl_uint = 0;
     let mut status = unsafe { clIcdGetPlatformIDsKHR(0, ptr::null_mut(), &mut count) };
 
     if CL_SUCCESS != status {
-        Err(status)
+        Err(record_error!(status, clIcdGetPlatformIDsKHR))
     } else if 0 < count {
         // Get the platform ids.
         let len = count as usize;
@@ -626,7 +783,7 @@ pub fn icd_get_platform_ids_khr() -> Result<Vec<cl_platform_id>, cl_int> {
         if CL_SUCCESS == status {
             Ok(ids)
         } else {
-            Err(status)
+            Err(record_error!(status, clIcdGetPlatformIDsKHR))
         }
     } else {
         Ok(Vec::default())
@@ -634,7 +791,12 @@ pub fn icd_get_platform_ids_khr() -> Result<Vec<cl_platform_id>, cl_int> {
 }
 
 #[cfg(feature = "cl_khr_il_program")]
-pub fn create_program_with_il_khr(context: cl_context, il: &[u8]) -> Result<cl_program, cl_int> {
+pub fn create_program_with_il_khr(
+    context: impl AsContext,
+    il: &[u8],
+) -> Result<cl_program, cl_int> {
+    let context = context.as_raw();
+    clear_error();
     let mut status: cl_int = CL_INVALID_VALUE;
     let program = unsafe {
         clCreateProgramWithILKHR(
@@ -647,53 +809,60 @@ pub fn create_program_with_il_khr(context: cl_context, il: &[u8]) -> Result<cl_p
     if CL_SUCCESS == status {
         Ok(program)
     } else {
-        Err(status)
+        Err(record_error!(status, clCreateProgramWithILKHR))
     }
 }
 
 #[cfg(feature = "cl_khr_terminate_context")]
-pub unsafe fn terminate_context_khr(context: cl_context) -> Result<(), cl_int> {
+pub unsafe fn terminate_context_khr(context: impl AsContext) -> Result<(), cl_int> {
+    let context = context.as_raw();
+    clear_error();
     let status = clTerminateContextKHR(context);
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(status, clTerminateContextKHR))
     }
 }
 
 #[cfg(feature = "cl_khr_create_command_queue")]
 pub fn create_command_queue_with_properties_khr(
-    context: cl_context,
-    device: cl_device_id,
+    context: impl AsContext,
+    device: impl AsDevice,
     properties: *const cl_queue_properties_khr,
 ) -> Result<cl_command_queue, cl_int> {
+    let context = context.as_raw();
+    let device = device.as_raw();
+    clear_error();
     let mut status: cl_int = CL_INVALID_VALUE;
     let queue: cl_command_queue =
         unsafe { clCreateCommandQueueWithPropertiesKHR(context, device, properties, &mut status) };
     if CL_SUCCESS == status {
         Ok(queue)
     } else {
-        Err(status)
+        Err(record_error!(status, clCreateCommandQueueWithPropertiesKHR))
     }
 }
 
 #[cfg(feature = "cl_ext_device_fission")]
 pub unsafe fn release_device_ext(device: cl_device_id) -> Result<(), cl_int> {
+    clear_error();
     let status = clReleaseDeviceEXT(device);
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(status, clReleaseDeviceEXT))
     }
 }
 
 #[cfg(feature = "cl_ext_device_fission")]
 pub unsafe fn retain_device_ext(device: cl_device_id) -> Result<(), cl_int> {
+    clear_error();
     let status = clRetainDeviceEXT(device);
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(status, clRetainDeviceEXT))
     }
 }
 
@@ -703,6 +872,7 @@ fn count_sub_devices_ext(
     in_device: cl_device_id,
     properties: &[cl_device_partition_property_ext],
 ) -> Result<cl_uint, cl_int> {
+    clear_error();
     let mut count: cl_uint = 0;
     let status: cl_int = unsafe {
         clCreateSubDevicesEXT(
@@ -716,16 +886,18 @@ fn count_sub_devices_ext(
     if CL_SUCCESS == status {
         Ok(count)
     } else {
-        Err(status)
+        Err(record_error!(status, clCreateSubDevicesEXT))
     }
 }
 
 #[cfg(feature = "cl_ext_device_fission")]
 #[allow(clippy::cast_possible_truncation)]
 pub fn create_sub_devices_ext(
-    in_device: cl_device_id,
+    in_device: impl AsDevice,
     properties: &[cl_device_partition_property_ext],
 ) -> Result<Vec<cl_device_id>, cl_int> {
+    let in_device = in_device.as_raw();
+    clear_error();
     // get the number of partitions
     let num_devices: cl_uint = count_sub_devices_ext(in_device, properties)?;
 
@@ -745,19 +917,21 @@ pub fn create_sub_devices_ext(
     if CL_SUCCESS == status {
         Ok(ids)
     } else {
-        Err(status)
+        Err(record_error!(status, clCreateSubDevicesEXT))
     }
 }
 
 #[cfg(feature = "cl_ext_migrate_memobject")]
 pub unsafe fn enqueue_migrate_mem_object_ext(
-    command_queue: cl_command_queue,
+    command_queue: impl AsCommandQueue,
     num_mem_objects: cl_uint,
     mem_objects: *const cl_mem,
     flags: cl_mem_migration_flags_ext,
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
+    let command_queue = command_queue.as_raw();
+    clear_error();
     let mut event: cl_event = ptr::null_mut();
     let status: cl_int = clEnqueueMigrateMemObjectEXT(
         command_queue,
@@ -771,18 +945,20 @@ pub unsafe fn enqueue_migrate_mem_object_ext(
     if CL_SUCCESS == status {
         Ok(event)
     } else {
-        Err(status)
+        Err(record_error!(status, clEnqueueMigrateMemObjectEXT, flags))
     }
 }
 
 #[cfg(feature = "cl_qcom_ext_host_ptr")]
 pub fn get_device_image_info_qcom(
-    device: cl_device_id,
+    device: impl AsDevice,
     image_width: size_t,
     image_height: size_t,
     image_format: *const cl_image_format,
     param_name: cl_image_pitch_info_qcom,
 ) -> Result<cl_uint, cl_int> {
+    let device = device.as_raw();
+    clear_error();
     let mut data: cl_uint = 0;
     let data_ptr: *mut cl_uint = &mut data;
     let status = unsafe {
@@ -800,18 +976,20 @@ pub fn get_device_image_info_qcom(
     if CL_SUCCESS == status {
         Ok(data)
     } else {
-        Err(status)
+        Err(record_error!(status, clGetDeviceImageInfoQCOM, param_name))
     }
 }
 
 #[cfg(feature = "cl_img_use_gralloc_ptr")]
 pub unsafe fn enqueue_acquire_gralloc_objects_img(
-    command_queue: cl_command_queue,
+    command_queue: impl AsCommandQueue,
     num_objects: cl_uint,
     mem_objects: *const cl_mem,
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
+    let command_queue = command_queue.as_raw();
+    clear_error();
     let mut event: cl_event = ptr::null_mut();
     let status: cl_int = clEnqueueAcquireGrallocObjectsIMG(
         command_queue,
@@ -824,18 +1002,20 @@ pub unsafe fn enqueue_acquire_gralloc_objects_img(
     if CL_SUCCESS == status {
         Ok(event)
     } else {
-        Err(status)
+        Err(record_error!(status, clEnqueueAcquireGrallocObjectsIMG))
     }
 }
 
 #[cfg(feature = "cl_img_use_gralloc_ptr")]
 pub unsafe fn enqueue_release_gralloc_objects_img(
-    command_queue: cl_command_queue,
+    command_queue: impl AsCommandQueue,
     num_objects: cl_uint,
     mem_objects: *const cl_mem,
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
+    let command_queue = command_queue.as_raw();
+    clear_error();
     let mut event: cl_event = ptr::null_mut();
     let status: cl_int = clEnqueueReleaseGrallocObjectsIMG(
         command_queue,
@@ -848,21 +1028,25 @@ pub unsafe fn enqueue_release_gralloc_objects_img(
     if CL_SUCCESS == status {
         Ok(event)
     } else {
-        Err(status)
+        Err(record_error!(status, clEnqueueReleaseGrallocObjectsIMG))
     }
 }
 
 #[cfg(feature = "cl_img_generate_mipmap")]
 pub unsafe fn enqueue_generate_mipmap_img(
-    command_queue: cl_command_queue,
-    src_image: cl_mem,
-    dst_image: cl_mem,
+    command_queue: impl AsCommandQueue,
+    src_image: impl AsMem,
+    dst_image: impl AsMem,
     mipmap_filter_mode: cl_mipmap_filter_mode_img,
     array_region: *const size_t,
     mip_region: *const size_t,
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
+    let command_queue = command_queue.as_raw();
+    let src_image = src_image.as_raw();
+    let dst_image = dst_image.as_raw();
+    clear_error();
     let mut event: cl_event = ptr::null_mut();
     let status: cl_int = clEnqueueGenerateMipmapIMG(
         command_queue,
@@ -878,18 +1062,21 @@ pub unsafe fn enqueue_generate_mipmap_img(
     if CL_SUCCESS == status {
         Ok(event)
     } else {
-        Err(status)
+        Err(record_error!(status, clEnqueueGenerateMipmapIMG))
     }
 }
 
 #[cfg(feature = "cl_khr_subgroups")]
 pub fn get_kernel_sub_group_info_khr(
-    kernel: cl_kernel,
-    device: cl_device_id,
+    kernel: impl AsKernel,
+    device: impl AsDevice,
     param_name: cl_kernel_sub_group_info,
     input_value_size: size_t,
     input_value: *const c_void,
 ) -> Result<size_t, cl_int> {
+    let kernel = kernel.as_raw();
+    let device = device.as_raw();
+    clear_error();
     match param_name {
         CL_KERNEL_MAX_SUB_GROUP_SIZE_FOR_NDRANGE_KHR
         | CL_KERNEL_SUB_GROUP_COUNT_FOR_NDRANGE_KHR
@@ -913,21 +1100,35 @@ pub fn get_kernel_sub_group_info_khr(
             if CL_SUCCESS == status {
                 Ok(data)
             } else {
-                Err(status)
+                Err(record_error!(
+                    status,
+                    clGetKernelSubGroupInfoKHR,
+                    param_name,
+                    input_value_size
+                ))
             }
         }
     }
 }
 
+/// Get the local work size that the implementation suggests for a kernel.
+/// Calls clGetKernelSuggestedLocalWorkSizeKHR.
+///
+/// returns a Result containing the suggested local work size of each of the
+/// `work_dim` dimensions or the error code from the `OpenCL` C API function.
 #[cfg(feature = "cl_khr_suggested_local_work_size")]
 pub fn get_kernel_suggested_local_work_size_khr(
-    command_queue: cl_command_queue,
-    kernel: cl_kernel,
+    command_queue: impl AsCommandQueue,
+    kernel: impl AsKernel,
     work_dim: cl_uint,
     global_work_offset: *const size_t,
     global_work_size: *const size_t,
-) -> Result<size_t, cl_int> {
-    let mut suggested_local_work_size: size_t = 0;
+) -> Result<Vec<size_t>, cl_int> {
+    let command_queue = command_queue.as_raw();
+    let kernel = kernel.as_raw();
+    clear_error();
+    // The function writes a size for each dimension
+    let mut suggested_local_work_size: Vec<size_t> = vec![0; work_dim as usize];
     let status: cl_int = unsafe {
         clGetKernelSuggestedLocalWorkSizeKHR(
             command_queue,
@@ -935,24 +1136,30 @@ pub fn get_kernel_suggested_local_work_size_khr(
             work_dim,
             global_work_offset,
             global_work_size,
-            &mut suggested_local_work_size,
+            suggested_local_work_size.as_mut_ptr(),
         )
     };
     if CL_SUCCESS == status {
         Ok(suggested_local_work_size)
     } else {
-        Err(status)
+        Err(record_error!(
+            status,
+            clGetKernelSuggestedLocalWorkSizeKHR,
+            work_dim
+        ))
     }
 }
 
 #[cfg(feature = "cl_khr_external_memory")]
 pub unsafe fn enqueue_acquire_external_mem_objects_khr(
-    command_queue: cl_command_queue,
+    command_queue: impl AsCommandQueue,
     num_mem_objects: cl_uint,
     mem_objects: *const cl_mem,
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
+    let command_queue = command_queue.as_raw();
+    clear_error();
     let mut event: cl_event = ptr::null_mut();
     let status: cl_int = clEnqueueAcquireExternalMemObjectsKHR(
         command_queue,
@@ -965,18 +1172,20 @@ pub unsafe fn enqueue_acquire_external_mem_objects_khr(
     if CL_SUCCESS == status {
         Ok(event)
     } else {
-        Err(status)
+        Err(record_error!(status, clEnqueueAcquireExternalMemObjectsKHR))
     }
 }
 
 #[cfg(feature = "cl_khr_external_memory")]
 pub unsafe fn enqueue_release_external_mem_objects_khr(
-    command_queue: cl_command_queue,
+    command_queue: impl AsCommandQueue,
     num_mem_objects: cl_uint,
     mem_objects: *const cl_mem,
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
+    let command_queue = command_queue.as_raw();
+    clear_error();
     let mut event: cl_event = ptr::null_mut();
     let status: cl_int = clEnqueueReleaseExternalMemObjectsKHR(
         command_queue,
@@ -989,16 +1198,18 @@ pub unsafe fn enqueue_release_external_mem_objects_khr(
     if CL_SUCCESS == status {
         Ok(event)
     } else {
-        Err(status)
+        Err(record_error!(status, clEnqueueReleaseExternalMemObjectsKHR))
     }
 }
 
 #[cfg(feature = "cl_khr_external_semaphore")]
 pub fn get_semaphore_handle_for_type_khr(
     sema_object: cl_semaphore_khr,
-    device: cl_device_id,
+    device: impl AsDevice,
     handle_type: cl_external_semaphore_handle_type_khr,
 ) -> Result<cl_semaphore_khr, cl_int> {
+    let device = device.as_raw();
+    clear_error();
     // Get the size of the information.
     let mut size: size_t = 0;
     let status: cl_int = unsafe {
@@ -1027,10 +1238,18 @@ pub fn get_semaphore_handle_for_type_khr(
         if CL_SUCCESS == status {
             Ok(data)
         } else {
-            Err(status)
+            Err(record_error!(
+                status,
+                clGetSemaphoreHandleForTypeKHR,
+                handle_type
+            ))
         }
     } else {
-        Err(status)
+        Err(record_error!(
+            status,
+            clGetSemaphoreHandleForTypeKHR,
+            handle_type
+        ))
     }
 }
 
@@ -1040,38 +1259,43 @@ pub unsafe fn reimport_semaphore_sync_fd(
     reimport_props: *mut cl_semaphore_reimport_properties_khr,
     fd: c_int,
 ) -> Result<(), cl_int> {
+    clear_error();
     let status: cl_int = clReImportSemaphoreSyncFdKHR(sema_object, reimport_props, fd);
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(status, clReImportSemaphoreSyncFdKHR))
     }
 }
 
 #[cfg(feature = "cl_khr_semaphore")]
 pub fn create_semaphore_with_properties_khr(
-    context: cl_context,
+    context: impl AsContext,
     sema_props: *const cl_semaphore_properties_khr,
 ) -> Result<cl_semaphore_khr, cl_int> {
+    let context = context.as_raw();
+    clear_error();
     let mut status: cl_int = CL_INVALID_VALUE;
     let semaphore: cl_semaphore_khr =
         unsafe { clCreateSemaphoreWithPropertiesKHR(context, sema_props, &mut status) };
     if CL_SUCCESS == status {
         Ok(semaphore)
     } else {
-        Err(status)
+        Err(record_error!(status, clCreateSemaphoreWithPropertiesKHR))
     }
 }
 
 #[cfg(feature = "cl_khr_semaphore")]
 pub unsafe fn enqueue_wait_semaphores_khr(
-    command_queue: cl_command_queue,
+    command_queue: impl AsCommandQueue,
     num_sema_objects: cl_uint,
     sema_objects: *const cl_semaphore_khr,
     sema_payload_list: *const cl_semaphore_payload_khr,
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
+    let command_queue = command_queue.as_raw();
+    clear_error();
     let mut event: cl_event = ptr::null_mut();
     let status: cl_int = clEnqueueWaitSemaphoresKHR(
         command_queue,
@@ -1085,19 +1309,21 @@ pub unsafe fn enqueue_wait_semaphores_khr(
     if CL_SUCCESS == status {
         Ok(event)
     } else {
-        Err(status)
+        Err(record_error!(status, clEnqueueWaitSemaphoresKHR))
     }
 }
 
 #[cfg(feature = "cl_khr_semaphore")]
 pub unsafe fn enqueue_signal_semaphores_khr(
-    command_queue: cl_command_queue,
+    command_queue: impl AsCommandQueue,
     num_sema_objects: cl_uint,
     sema_objects: *const cl_semaphore_khr,
     sema_payload_list: *const cl_semaphore_payload_khr,
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
+    let command_queue = command_queue.as_raw();
+    clear_error();
     let mut event: cl_event = ptr::null_mut();
     let status: cl_int = clEnqueueSignalSemaphoresKHR(
         command_queue,
@@ -1111,7 +1337,7 @@ pub unsafe fn enqueue_signal_semaphores_khr(
     if CL_SUCCESS == status {
         Ok(event)
     } else {
-        Err(status)
+        Err(record_error!(status, clEnqueueSignalSemaphoresKHR))
     }
 }
 
@@ -1120,6 +1346,7 @@ pub fn get_semaphore_info_khr(
     sema_object: cl_semaphore_khr,
     param_name: cl_semaphore_info_khr,
 ) -> Result<Vec<u8>, cl_int> {
+    clear_error();
     api_info_size!(get_size, clGetSemaphoreInfoKHR);
     let size = get_size(sema_object, param_name)?;
     api_info_vector!(get_vector, u8, clGetSemaphoreInfoKHR);
@@ -1128,64 +1355,77 @@ pub fn get_semaphore_info_khr(
 
 #[cfg(feature = "cl_khr_semaphore")]
 pub unsafe fn release_semaphore_khr(sema_object: cl_semaphore_khr) -> Result<(), cl_int> {
+    clear_error();
     let status: cl_int = clReleaseSemaphoreKHR(sema_object);
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(status, clReleaseSemaphoreKHR))
     }
 }
 
 #[cfg(feature = "cl_khr_semaphore")]
 pub unsafe fn retain_semaphore_khr(sema_object: cl_semaphore_khr) -> Result<(), cl_int> {
+    clear_error();
     let status: cl_int = clRetainSemaphoreKHR(sema_object);
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(status, clRetainSemaphoreKHR))
     }
 }
 
 #[cfg(feature = "cl_arm_import_memory")]
 pub unsafe fn import_memory_arm(
-    context: cl_context,
+    context: impl AsContext,
     flags: cl_mem_flags,
     properties: *const cl_import_properties_arm,
     memory: *mut c_void,
     size: size_t,
 ) -> Result<cl_mem, cl_int> {
+    let context = context.as_raw();
+    clear_error();
     let mut status: cl_int = CL_INVALID_VALUE;
     let mem: cl_mem = clImportMemoryARM(context, flags, properties, memory, size, &mut status);
     if CL_SUCCESS == status {
         Ok(mem)
     } else {
-        Err(status)
+        Err(record_error!(status, clImportMemoryARM, flags, size))
     }
 }
 
 #[cfg(feature = "cl_arm_shared_virtual_memory")]
 pub unsafe fn svm_alloc_arm(
-    context: cl_context,
+    context: impl AsContext,
     flags: cl_svm_mem_flags_arm,
     size: size_t,
     alignment: cl_uint,
 ) -> Result<*mut c_void, cl_int> {
+    let context = context.as_raw();
+    clear_error();
     let ptr = clSVMAllocARM(context, flags, size, alignment);
     if ptr.is_null() {
-        Err(CL_INVALID_VALUE)
+        Err(record_error!(
+            CL_INVALID_VALUE,
+            clSVMAllocARM,
+            flags,
+            size,
+            alignment
+        ))
     } else {
         Ok(ptr)
     }
 }
 
 #[cfg(feature = "cl_arm_shared_virtual_memory")]
-pub unsafe fn svm_free_arm(context: cl_context, svm_pointer: *mut c_void) {
+pub unsafe fn svm_free_arm(context: impl AsContext, svm_pointer: *mut c_void) {
+    let context = context.as_raw();
     clSVMFreeARM(context, svm_pointer);
 }
 
 #[cfg(feature = "cl_arm_shared_virtual_memory")]
 pub unsafe fn enqueue_svm_free_arm(
-    command_queue: cl_command_queue,
+    command_queue: impl AsCommandQueue,
     num_svm_pointers: cl_uint,
     svm_pointers: *mut *mut c_void,
     pfn_free_func: Option<
@@ -1200,6 +1440,8 @@ pub unsafe fn enqueue_svm_free_arm(
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
+    let command_queue = command_queue.as_raw();
+    clear_error();
     let mut event: cl_event = ptr::null_mut();
     let status: cl_int = clEnqueueSVMFreeARM(
         command_queue,
@@ -1214,13 +1456,13 @@ pub unsafe fn enqueue_svm_free_arm(
     if CL_SUCCESS == status {
         Ok(event)
     } else {
-        Err(status)
+        Err(record_error!(status, clEnqueueSVMFreeARM))
     }
 }
 
 #[cfg(feature = "cl_arm_shared_virtual_memory")]
 pub unsafe fn enqueue_svm_mem_cpy_arm(
-    command_queue: cl_command_queue,
+    command_queue: impl AsCommandQueue,
     blocking_copy: cl_bool,
     dst_ptr: *mut c_void,
     src_ptr: *const c_void,
@@ -1228,6 +1470,8 @@ pub unsafe fn enqueue_svm_mem_cpy_arm(
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
+    let command_queue = command_queue.as_raw();
+    clear_error();
     let mut event: cl_event = ptr::null_mut();
     let status: cl_int = clEnqueueSVMMemcpyARM(
         command_queue,
@@ -1242,13 +1486,13 @@ pub unsafe fn enqueue_svm_mem_cpy_arm(
     if CL_SUCCESS == status {
         Ok(event)
     } else {
-        Err(status)
+        Err(record_error!(status, clEnqueueSVMMemcpyARM, size))
     }
 }
 
 #[cfg(feature = "cl_arm_shared_virtual_memory")]
 pub unsafe fn enqueue_svm_mem_fill_arm(
-    command_queue: cl_command_queue,
+    command_queue: impl AsCommandQueue,
     svm_ptr: *mut c_void,
     pattern: *const c_void,
     pattern_size: size_t,
@@ -1256,6 +1500,8 @@ pub unsafe fn enqueue_svm_mem_fill_arm(
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
+    let command_queue = command_queue.as_raw();
+    clear_error();
     let mut event: cl_event = ptr::null_mut();
     let status: cl_int = clEnqueueSVMMemFillARM(
         command_queue,
@@ -1270,13 +1516,18 @@ pub unsafe fn enqueue_svm_mem_fill_arm(
     if CL_SUCCESS == status {
         Ok(event)
     } else {
-        Err(status)
+        Err(record_error!(
+            status,
+            clEnqueueSVMMemFillARM,
+            size,
+            pattern_size
+        ))
     }
 }
 
 #[cfg(feature = "cl_arm_shared_virtual_memory")]
 pub unsafe fn enqueue_svm_map_arm(
-    command_queue: cl_command_queue,
+    command_queue: impl AsCommandQueue,
     blocking_map: cl_bool,
     flags: cl_map_flags,
     svm_ptr: *mut c_void,
@@ -1284,6 +1535,8 @@ pub unsafe fn enqueue_svm_map_arm(
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
+    let command_queue = command_queue.as_raw();
+    clear_error();
     let mut event: cl_event = ptr::null_mut();
     let status: cl_int = clEnqueueSVMMapARM(
         command_queue,
@@ -1298,17 +1551,19 @@ pub unsafe fn enqueue_svm_map_arm(
     if CL_SUCCESS == status {
         Ok(event)
     } else {
-        Err(status)
+        Err(record_error!(status, clEnqueueSVMMapARM, flags, size))
     }
 }
 
 #[cfg(feature = "cl_arm_shared_virtual_memory")]
 pub unsafe fn enqueue_svm_unmap_arm(
-    command_queue: cl_command_queue,
+    command_queue: impl AsCommandQueue,
     svm_ptr: *mut c_void,
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
+    let command_queue = command_queue.as_raw();
+    clear_error();
     let mut event: cl_event = ptr::null_mut();
     let status: cl_int = clEnqueueSVMUnmapARM(
         command_queue,
@@ -1320,47 +1575,62 @@ pub unsafe fn enqueue_svm_unmap_arm(
     if CL_SUCCESS == status {
         Ok(event)
     } else {
-        Err(status)
+        Err(record_error!(status, clEnqueueSVMUnmapARM))
     }
 }
 
 #[cfg(feature = "cl_arm_shared_virtual_memory")]
 pub fn set_kernel_arg_svm_pointer(
-    kernel: cl_kernel,
+    kernel: impl AsKernel,
     arg_index: cl_uint,
     arg_ptr: *const c_void,
 ) -> Result<(), cl_int> {
+    let kernel = kernel.as_raw();
+    clear_error();
     let status: cl_int = unsafe { clSetKernelArgSVMPointerARM(kernel, arg_index, arg_ptr) };
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(
+            status,
+            clSetKernelArgSVMPointerARM,
+            arg_index
+        ))
     }
 }
 
 #[cfg(feature = "cl_arm_shared_virtual_memory")]
 pub fn set_kernel_exec_info_arm(
-    kernel: cl_kernel,
+    kernel: impl AsKernel,
     param_name: cl_kernel_exec_info_arm,
     param_value_size: size_t,
     param_value: *const c_void,
 ) -> Result<(), cl_int> {
+    let kernel = kernel.as_raw();
+    clear_error();
     let status: cl_int =
         unsafe { clSetKernelExecInfoARM(kernel, param_name, param_value_size, param_value) };
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(
+            status,
+            clSetKernelExecInfoARM,
+            param_name,
+            param_value_size
+        ))
     }
 }
 
 #[cfg(feature = "cl_intel_accelerator")]
 pub fn create_accelerator_intel(
-    context: cl_context,
+    context: impl AsContext,
     accelerator_type: cl_accelerator_type_intel,
     descriptor_size: size_t,
     descriptor: *const c_void,
 ) -> Result<cl_accelerator_intel, cl_int> {
+    let context = context.as_raw();
+    clear_error();
     let mut status: cl_int = CL_INVALID_VALUE;
     let ptr = unsafe {
         clCreateAcceleratorINTEL(
@@ -1374,7 +1644,7 @@ pub fn create_accelerator_intel(
     if CL_SUCCESS == status {
         Ok(ptr)
     } else {
-        Err(status)
+        Err(record_error!(status, clCreateAcceleratorINTEL))
     }
 }
 
@@ -1383,6 +1653,7 @@ pub fn get_accelerator_data_intel(
     accelerator: cl_accelerator_intel,
     param_name: cl_accelerator_info_intel,
 ) -> Result<Vec<u8>, cl_int> {
+    clear_error();
     api_info_size!(get_size, clGetAcceleratorInfoINTEL);
     let size = get_size(accelerator, param_name)?;
     api_info_vector!(get_vector, u8, clGetAcceleratorInfoINTEL);
@@ -1394,6 +1665,7 @@ pub fn get_accelerator_info_intel(
     accelerator: cl_accelerator_intel,
     param_name: cl_accelerator_info_intel,
 ) -> Result<InfoType, cl_int> {
+    clear_error();
     match param_name {
         CL_ACCELERATOR_REFERENCE_COUNT_INTEL | CL_ACCELERATOR_TYPE_INTEL => {
             api_info_value!(get_value, cl_uint, clGetAcceleratorInfoINTEL);
@@ -1417,91 +1689,118 @@ pub fn get_accelerator_info_intel(
 
 #[cfg(feature = "cl_intel_accelerator")]
 pub unsafe fn retain_accelerator_intel(accelerator: cl_accelerator_intel) -> Result<(), cl_int> {
+    clear_error();
     let status = clRetainAcceleratorINTEL(accelerator);
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(status, clRetainAcceleratorINTEL))
     }
 }
 
 #[cfg(feature = "cl_intel_accelerator")]
 pub unsafe fn release_accelerator_intel(accelerator: cl_accelerator_intel) -> Result<(), cl_int> {
+    clear_error();
     let status = clReleaseAcceleratorINTEL(accelerator);
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(status, clReleaseAcceleratorINTEL))
     }
 }
 
 #[cfg(feature = "cl_intel_unified_shared_memory")]
 pub unsafe fn host_mem_alloc_intel(
-    context: cl_context,
+    context: impl AsContext,
     properties: *const cl_mem_properties_intel,
     size: size_t,
     alignment: cl_uint,
 ) -> Result<(), cl_int> {
+    let context = context.as_raw();
+    clear_error();
     let mut status: cl_int = CL_INVALID_VALUE;
     clHostMemAllocINTEL(context, properties, size, alignment, &mut status);
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(status, clHostMemAllocINTEL, size, alignment))
     }
 }
 
 #[cfg(feature = "cl_intel_unified_shared_memory")]
 pub unsafe fn device_mem_alloc_intel(
-    context: cl_context,
-    device: cl_device_id,
+    context: impl AsContext,
+    device: impl AsDevice,
     properties: *const cl_mem_properties_intel,
     size: size_t,
     alignment: cl_uint,
 ) -> Result<(), cl_int> {
+    let context = context.as_raw();
+    let device = device.as_raw();
+    clear_error();
     let mut status: cl_int = CL_INVALID_VALUE;
     clDeviceMemAllocINTEL(context, device, properties, size, alignment, &mut status);
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(
+            status,
+            clDeviceMemAllocINTEL,
+            size,
+            alignment
+        ))
     }
 }
 
 #[cfg(feature = "cl_intel_unified_shared_memory")]
 pub unsafe fn shared_mem_alloc_intel(
-    context: cl_context,
-    device: cl_device_id,
+    context: impl AsContext,
+    device: impl AsDevice,
     properties: *const cl_mem_properties_intel,
     size: size_t,
     alignment: cl_uint,
 ) -> Result<(), cl_int> {
+    let context = context.as_raw();
+    let device = device.as_raw();
+    clear_error();
     let mut status: cl_int = CL_INVALID_VALUE;
     clSharedMemAllocINTEL(context, device, properties, size, alignment, &mut status);
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(
+            status,
+            clSharedMemAllocINTEL,
+            size,
+            alignment
+        ))
     }
 }
 
 #[cfg(feature = "cl_intel_unified_shared_memory")]
-pub unsafe fn mem_free_intel(context: cl_context, ptr: *mut c_void) -> Result<(), cl_int> {
+pub unsafe fn mem_free_intel(context: impl AsContext, ptr: *mut c_void) -> Result<(), cl_int> {
+    let context = context.as_raw();
+    clear_error();
     let status = clMemFreeINTEL(context, ptr);
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(status, clMemFreeINTEL))
     }
 }
 
 #[cfg(feature = "cl_intel_unified_shared_memory")]
-pub unsafe fn mem_blocking_free_intel(context: cl_context, ptr: *mut c_void) -> Result<(), cl_int> {
+pub unsafe fn mem_blocking_free_intel(
+    context: impl AsContext,
+    ptr: *mut c_void,
+) -> Result<(), cl_int> {
+    let context = context.as_raw();
+    clear_error();
     let status = clMemBlockingFreeINTEL(context, ptr);
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(status, clMemBlockingFreeINTEL))
     }
 }
 
@@ -1511,6 +1810,7 @@ fn mem_alloc_info_intel<T: Default>(
     ptr: *const c_void,
     param_id: cl_mem_info_intel,
 ) -> Result<T, cl_int> {
+    clear_error();
     let mut data: T = T::default();
     let data_ptr: *mut T = &mut data;
     let status = unsafe {
@@ -1526,16 +1826,18 @@ fn mem_alloc_info_intel<T: Default>(
     if CL_SUCCESS == status {
         Ok(data)
     } else {
-        Err(status)
+        Err(record_error!(status, clGetMemAllocInfoINTEL, param_id))
     }
 }
 
 #[cfg(feature = "cl_intel_unified_shared_memory")]
 pub fn get_mem_alloc_info_intel(
-    context: cl_context,
+    context: impl AsContext,
     ptr: *const c_void,
     param_name: cl_mem_info_intel,
 ) -> Result<InfoType, cl_int> {
+    let context = context.as_raw();
+    clear_error();
     match param_name {
         CL_MEM_ALLOC_TYPE_INTEL => Ok(InfoType::Uint(mem_alloc_info_intel::<
             cl_unified_shared_memory_type_intel,
@@ -1557,7 +1859,7 @@ pub fn get_mem_alloc_info_intel(
                 clGetMemAllocInfoINTEL(context, ptr, param_name, 0, ptr::null_mut(), &mut size)
             };
             if CL_SUCCESS != status {
-                Err(status)
+                Err(record_error!(status, clGetMemAllocInfoINTEL, param_name))
             } else if 0 < size {
                 // Get the data.
                 let mut data: Vec<u8> = Vec::with_capacity(size);
@@ -1574,7 +1876,7 @@ pub fn get_mem_alloc_info_intel(
                 if CL_SUCCESS == status {
                     Ok(InfoType::VecUchar(data))
                 } else {
-                    Err(status)
+                    Err(record_error!(status, clGetMemAllocInfoINTEL, param_name))
                 }
             } else {
                 Ok(InfoType::VecUchar(Vec::default()))
@@ -1585,27 +1887,35 @@ pub fn get_mem_alloc_info_intel(
 
 #[cfg(feature = "cl_intel_unified_shared_memory")]
 pub unsafe fn set_kernel_arg_mem_pointer_intel(
-    kernel: cl_kernel,
+    kernel: impl AsKernel,
     arg_index: cl_uint,
     arg_value: *const c_void,
 ) -> Result<(), cl_int> {
+    let kernel = kernel.as_raw();
+    clear_error();
     let status = clSetKernelArgMemPointerINTEL(kernel, arg_index, arg_value);
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(
+            status,
+            clSetKernelArgMemPointerINTEL,
+            arg_index
+        ))
     }
 }
 
 #[cfg(feature = "cl_intel_unified_shared_memory")]
 pub unsafe fn enqueue_mem_set_intel(
-    command_queue: cl_command_queue,
+    command_queue: impl AsCommandQueue,
     dst_ptr: *mut c_void,
     value: cl_int,
     size: size_t,
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
+    let command_queue = command_queue.as_raw();
+    clear_error();
     let mut event: cl_event = ptr::null_mut();
     let status: cl_int = clEnqueueMemsetINTEL(
         command_queue,
@@ -1619,13 +1929,13 @@ pub unsafe fn enqueue_mem_set_intel(
     if CL_SUCCESS == status {
         Ok(event)
     } else {
-        Err(status)
+        Err(record_error!(status, clEnqueueMemsetINTEL, size))
     }
 }
 
 #[cfg(feature = "cl_intel_unified_shared_memory")]
 pub unsafe fn enqueue_mem_fill_intel(
-    command_queue: cl_command_queue,
+    command_queue: impl AsCommandQueue,
     dst_ptr: *mut c_void,
     pattern: *const c_void,
     pattern_size: size_t,
@@ -1633,6 +1943,8 @@ pub unsafe fn enqueue_mem_fill_intel(
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
+    let command_queue = command_queue.as_raw();
+    clear_error();
     let mut event: cl_event = ptr::null_mut();
     let status: cl_int = clEnqueueMemFillINTEL(
         command_queue,
@@ -1647,13 +1959,18 @@ pub unsafe fn enqueue_mem_fill_intel(
     if CL_SUCCESS == status {
         Ok(event)
     } else {
-        Err(status)
+        Err(record_error!(
+            status,
+            clEnqueueMemFillINTEL,
+            size,
+            pattern_size
+        ))
     }
 }
 
 #[cfg(feature = "cl_intel_unified_shared_memory")]
 pub unsafe fn enqueue_mem_copy_intel(
-    command_queue: cl_command_queue,
+    command_queue: impl AsCommandQueue,
     blocking: cl_bool,
     dst_ptr: *mut c_void,
     src_ptr: *const c_void,
@@ -1661,6 +1978,8 @@ pub unsafe fn enqueue_mem_copy_intel(
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
+    let command_queue = command_queue.as_raw();
+    clear_error();
     let mut event: cl_event = ptr::null_mut();
     let status: cl_int = clEnqueueMemcpyINTEL(
         command_queue,
@@ -1675,19 +1994,21 @@ pub unsafe fn enqueue_mem_copy_intel(
     if CL_SUCCESS == status {
         Ok(event)
     } else {
-        Err(status)
+        Err(record_error!(status, clEnqueueMemcpyINTEL, size))
     }
 }
 
 #[cfg(feature = "cl_intel_unified_shared_memory")]
 pub unsafe fn enqueue_migrate_mem_intel(
-    command_queue: cl_command_queue,
+    command_queue: impl AsCommandQueue,
     ptr: *const c_void,
     size: size_t,
     flags: cl_mem_migration_flags,
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
+    let command_queue = command_queue.as_raw();
+    clear_error();
     let mut event: cl_event = ptr::null_mut();
     let status: cl_int = clEnqueueMigrateMemINTEL(
         command_queue,
@@ -1701,19 +2022,21 @@ pub unsafe fn enqueue_migrate_mem_intel(
     if CL_SUCCESS == status {
         Ok(event)
     } else {
-        Err(status)
+        Err(record_error!(status, clEnqueueMigrateMemINTEL, flags, size))
     }
 }
 
 #[cfg(feature = "cl_intel_unified_shared_memory")]
 pub unsafe fn enqueue_mem_advise_intel(
-    command_queue: cl_command_queue,
+    command_queue: impl AsCommandQueue,
     ptr: *const c_void,
     size: size_t,
     advice: cl_mem_advice_intel,
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
+    let command_queue = command_queue.as_raw();
+    clear_error();
     let mut event: cl_event = ptr::null_mut();
     let status: cl_int = clEnqueueMemAdviseINTEL(
         command_queue,
@@ -1727,32 +2050,39 @@ pub unsafe fn enqueue_mem_advise_intel(
     if CL_SUCCESS == status {
         Ok(event)
     } else {
-        Err(status)
+        Err(record_error!(status, clEnqueueMemAdviseINTEL, size))
     }
 }
 
 #[cfg(feature = "cl_intel_create_buffer_with_properties")]
 pub unsafe fn create_buffer_with_properties_intel(
-    context: cl_context,
+    context: impl AsContext,
     properties: *const cl_mem_properties_intel,
     flags: cl_mem_flags,
     size: size_t,
     host_ptr: *mut c_void,
 ) -> Result<cl_mem, cl_int> {
+    let context = context.as_raw();
+    clear_error();
     let mut status: cl_int = CL_INVALID_VALUE;
     let mem: cl_mem =
         clCreateBufferWithPropertiesINTEL(context, properties, flags, size, host_ptr, &mut status);
     if CL_SUCCESS == status {
         Ok(mem)
     } else {
-        Err(status)
+        Err(record_error!(
+            status,
+            clCreateBufferWithPropertiesINTEL,
+            flags,
+            size
+        ))
     }
 }
 
 #[cfg(feature = "cl_intel_program_scope_host_pipe")]
 pub unsafe fn enqueue_read_host_pipe_intel(
-    command_queue: cl_command_queue,
-    program: cl_program,
+    command_queue: impl AsCommandQueue,
+    program: impl AsProgram,
     pipe_symbol: *const c_char,
     blocking_read: cl_bool,
     ptr: *mut c_void,
@@ -1760,6 +2090,9 @@ pub unsafe fn enqueue_read_host_pipe_intel(
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
+    let command_queue = command_queue.as_raw();
+    let program = program.as_raw();
+    clear_error();
     let mut event: cl_event = ptr::null_mut();
     let status: cl_int = clEnqueueReadHostPipeINTEL(
         command_queue,
@@ -1775,14 +2108,14 @@ pub unsafe fn enqueue_read_host_pipe_intel(
     if CL_SUCCESS == status {
         Ok(event)
     } else {
-        Err(status)
+        Err(record_error!(status, clEnqueueReadHostPipeINTEL, size))
     }
 }
 
 #[cfg(feature = "cl_intel_program_scope_host_pipe")]
 pub unsafe fn enqueue_write_host_pipe_intel(
-    command_queue: cl_command_queue,
-    program: cl_program,
+    command_queue: impl AsCommandQueue,
+    program: impl AsProgram,
     pipe_symbol: *const c_char,
     blocking_write: cl_bool,
     ptr: *const c_void,
@@ -1790,6 +2123,9 @@ pub unsafe fn enqueue_write_host_pipe_intel(
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
+    let command_queue = command_queue.as_raw();
+    let program = program.as_raw();
+    clear_error();
     let mut event: cl_event = ptr::null_mut();
     let status: cl_int = clEnqueueWriteHostPipeINTEL(
         command_queue,
@@ -1805,19 +2141,21 @@ pub unsafe fn enqueue_write_host_pipe_intel(
     if CL_SUCCESS == status {
         Ok(event)
     } else {
-        Err(status)
+        Err(record_error!(status, clEnqueueWriteHostPipeINTEL, size))
     }
 }
 
 #[cfg(feature = "cl_ext_image_requirements_info")]
 pub fn get_image_requirements_info_ext(
-    context: cl_context,
+    context: impl AsContext,
     properties: *const cl_mem_properties,
     flags: cl_mem_flags,
     image_format: *const cl_image_format,
     image_desc: *const cl_image_desc,
     param_name: cl_image_requirements_info_ext,
 ) -> Result<Vec<u8>, cl_int> {
+    let context = context.as_raw();
+    clear_error();
     // get the size
     let mut size: size_t = mem::size_of::<u8>();
     let status: cl_int = unsafe {
@@ -1853,15 +2191,26 @@ pub fn get_image_requirements_info_ext(
         if CL_SUCCESS == status {
             Ok(data)
         } else {
-            Err(status)
+            Err(record_error!(
+                status,
+                clGetImageRequirementsInfoEXT,
+                param_name,
+                flags
+            ))
         }
     } else {
-        Err(status)
+        Err(record_error!(
+            status,
+            clGetImageRequirementsInfoEXT,
+            param_name,
+            flags
+        ))
     }
 }
 
 #[cfg(feature = "cl_loader_info")]
 pub fn get_icd_loader_info_oclicd(param_name: cl_icdl_info) -> Result<Vec<u8>, cl_int> {
+    clear_error();
     // get the size
     let mut size: size_t = 0;
     let status = unsafe { clGetICDLoaderInfoOCLICD(param_name, 0, ptr::null_mut(), &mut size) };
@@ -1879,22 +2228,29 @@ pub fn get_icd_loader_info_oclicd(param_name: cl_icdl_info) -> Result<Vec<u8>, c
         if CL_SUCCESS == status {
             Ok(data)
         } else {
-            Err(status)
+            Err(record_error!(status, clGetICDLoaderInfoOCLICD, param_name))
         }
     } else {
-        Err(status)
+        Err(record_error!(status, clGetICDLoaderInfoOCLICD, param_name))
     }
 }
 
 #[cfg(feature = "cl_pocl_content_size")]
 pub fn set_content_size_buffer_pocl(
-    buffer: cl_mem,
-    content_size_buffer: cl_mem,
+    buffer: impl AsMem,
+    content_size_buffer: impl AsMem,
 ) -> Result<(), cl_int> {
+    let buffer = buffer.as_raw();
+    let content_size_buffer = content_size_buffer.as_raw();
+    clear_error();
     let status = unsafe { clSetContentSizeBufferPoCL(buffer, content_size_buffer) };
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(
+            status,
+            clSetContentSizeBufferPoCL,
+            content_size_buffer
+        ))
     }
 }
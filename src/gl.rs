@@ -30,7 +30,9 @@ pub use opencl_sys::{
 };
 
 #[allow(unused_imports)]
-use opencl_sys::{
+use super::api_error::{clear_error, record_error};
+#[allow(unused_imports)]
+use super::ffi::{
     clCreateEventFromGLsyncKHR, clCreateFromGLBuffer, clCreateFromGLRenderbuffer,
     clCreateFromGLTexture, clCreateFromGLTexture2D, clCreateFromGLTexture3D,
     clEnqueueAcquireGLObjects, clEnqueueReleaseGLObjects, clGetGLContextInfoKHR, clGetGLObjectInfo,
@@ -38,6 +40,7 @@ use opencl_sys::{
 };
 
 use super::info_type::InfoType;
+use super::owned::{AsCommandQueue, AsContext, AsMem};
 use super::{api_info_size, api_info_value, api_info_vector};
 #[allow(unused_imports)]
 use libc::{c_void, intptr_t, size_t};
@@ -57,16 +60,18 @@ use std::ptr;
 /// or the error code from the `OpenCL` C API function.
 #[inline]
 pub unsafe fn create_from_gl_buffer(
-    context: cl_context,
+    context: impl AsContext,
     flags: cl_mem_flags,
     bufobj: cl_GLuint,
 ) -> Result<cl_mem, cl_int> {
+    let context = context.as_raw();
+    clear_error();
     let mut status: cl_int = CL_INVALID_VALUE;
     let mem = clCreateFromGLBuffer(context, flags, bufobj, &mut status);
     if CL_SUCCESS == status {
         Ok(mem)
     } else {
-        Err(status)
+        Err(record_error!(status, clCreateFromGLBuffer, flags))
     }
 }
 
@@ -87,12 +92,14 @@ pub unsafe fn create_from_gl_buffer(
 /// or the error code from the `OpenCL` C API function.
 #[inline]
 pub unsafe fn create_from_gl_texture(
-    context: cl_context,
+    context: impl AsContext,
     flags: cl_mem_flags,
     texture_target: cl_GLenum,
     miplevel: cl_GLint,
     texture: cl_GLuint,
 ) -> Result<cl_mem, cl_int> {
+    let context = context.as_raw();
+    clear_error();
     let mut status: cl_int = CL_INVALID_VALUE;
     let mem = clCreateFromGLTexture(
         context,
@@ -105,7 +112,13 @@ pub unsafe fn create_from_gl_texture(
     if CL_SUCCESS == status {
         Ok(mem)
     } else {
-        Err(status)
+        Err(record_error!(
+            status,
+            clCreateFromGLTexture,
+            flags,
+            texture_target,
+            miplevel
+        ))
     }
 }
 
@@ -122,16 +135,18 @@ pub unsafe fn create_from_gl_texture(
 /// or the error code from the `OpenCL` C API function.
 #[inline]
 pub unsafe fn create_from_gl_render_buffer(
-    context: cl_context,
+    context: impl AsContext,
     flags: cl_mem_flags,
     renderbuffer: cl_GLuint,
 ) -> Result<cl_mem, cl_int> {
+    let context = context.as_raw();
+    clear_error();
     let mut status: cl_int = CL_INVALID_VALUE;
     let mem = clCreateFromGLRenderbuffer(context, flags, renderbuffer, &mut status);
     if CL_SUCCESS == status {
         Ok(mem)
     } else {
-        Err(status)
+        Err(record_error!(status, clCreateFromGLRenderbuffer, flags))
     }
 }
 
@@ -143,23 +158,27 @@ pub unsafe fn create_from_gl_render_buffer(
 /// returns a Result containing the OpenGL object type and name
 /// or the error code from the `OpenCL` C API function.
 #[inline]
-pub fn get_gl_object_info(memobj: cl_mem) -> Result<(cl_GLuint, cl_GLuint), cl_int> {
+pub fn get_gl_object_info(memobj: impl AsMem) -> Result<(cl_GLuint, cl_GLuint), cl_int> {
+    let memobj = memobj.as_raw();
+    clear_error();
     let mut object_type: cl_uint = CL_GL_OBJECT_BUFFER;
     let mut object_name: cl_uint = 0;
     let status = unsafe { clGetGLObjectInfo(memobj, &mut object_type, &mut object_name) };
     if CL_SUCCESS == status {
         Ok((object_type, object_name))
     } else {
-        Err(status)
+        Err(record_error!(status, clGetGLObjectInfo))
     }
 }
 
 /// Get data about an OpenGL texture object.
 /// Calls clGetGLTextureInfo to get the desired data about the texture object.
 pub fn get_gl_texture_data(
-    memobj: cl_mem,
+    memobj: impl AsMem,
     param_name: cl_gl_texture_info,
 ) -> Result<Vec<u8>, cl_int> {
+    let memobj = memobj.as_raw();
+    clear_error();
     api_info_size!(get_size, clGetGLTextureInfo);
     let size = get_size(memobj, param_name)?;
     api_info_vector!(get_vector, u8, clGetGLTextureInfo);
@@ -176,9 +195,11 @@ pub fn get_gl_texture_data(
 /// returns a Result containing the desired information in an `InfoType` enum
 /// or the error code from the `OpenCL` C API function.
 pub fn get_gl_texture_info(
-    memobj: cl_mem,
+    memobj: impl AsMem,
     param_name: cl_gl_texture_info,
 ) -> Result<InfoType, cl_int> {
+    let memobj = memobj.as_raw();
+    clear_error();
     match param_name {
         CL_GL_TEXTURE_TARGET => {
             api_info_value!(get_value, cl_GLenum, clGetGLTextureInfo);
@@ -207,12 +228,14 @@ pub fn get_gl_texture_info(
 /// or the error code from the `OpenCL` C API function.
 #[inline]
 pub unsafe fn enqueue_acquire_gl_objects(
-    command_queue: cl_command_queue,
+    command_queue: impl AsCommandQueue,
     num_objects: cl_uint,
     mem_objects: *const cl_mem,
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
+    let command_queue = command_queue.as_raw();
+    clear_error();
     let mut event: cl_event = ptr::null_mut();
     let status: cl_int = clEnqueueAcquireGLObjects(
         command_queue,
@@ -225,7 +248,7 @@ pub unsafe fn enqueue_acquire_gl_objects(
     if CL_SUCCESS == status {
         Ok(event)
     } else {
-        Err(status)
+        Err(record_error!(status, clEnqueueAcquireGLObjects))
     }
 }
 
@@ -242,12 +265,14 @@ pub unsafe fn enqueue_acquire_gl_objects(
 /// or the error code from the `OpenCL` C API function.
 #[inline]
 pub unsafe fn enqueue_release_gl_objects(
-    command_queue: cl_command_queue,
+    command_queue: impl AsCommandQueue,
     num_objects: cl_uint,
     mem_objects: *const cl_mem,
     num_events_in_wait_list: cl_uint,
     event_wait_list: *const cl_event,
 ) -> Result<cl_event, cl_int> {
+    let command_queue = command_queue.as_raw();
+    clear_error();
     let mut event: cl_event = ptr::null_mut();
     let status: cl_int = clEnqueueReleaseGLObjects(
         command_queue,
@@ -260,7 +285,7 @@ pub unsafe fn enqueue_release_gl_objects(
     if CL_SUCCESS == status {
         Ok(event)
     } else {
-        Err(status)
+        Err(record_error!(status, clEnqueueReleaseGLObjects))
     }
 }
 
@@ -294,12 +319,14 @@ pub unsafe fn enqueue_release_gl_objects(
 )]
 #[inline]
 pub unsafe fn create_from_gl_texture_2d(
-    context: cl_context,
+    context: impl AsContext,
     flags: cl_mem_flags,
     texture_target: cl_GLenum,
     miplevel: cl_GLint,
     texture: cl_GLuint,
 ) -> Result<cl_mem, cl_int> {
+    let context = context.as_raw();
+    clear_error();
     let mut status: cl_int = CL_INVALID_VALUE;
     let mem = clCreateFromGLTexture2D(
         context,
@@ -312,7 +339,13 @@ pub unsafe fn create_from_gl_texture_2d(
     if CL_SUCCESS == status {
         Ok(mem)
     } else {
-        Err(status)
+        Err(record_error!(
+            status,
+            clCreateFromGLTexture2D,
+            flags,
+            texture_target,
+            miplevel
+        ))
     }
 }
 
@@ -345,12 +378,14 @@ pub unsafe fn create_from_gl_texture_2d(
 )]
 #[inline]
 pub unsafe fn create_from_gl_texture_3d(
-    context: cl_context,
+    context: impl AsContext,
     flags: cl_mem_flags,
     texture_target: cl_GLenum,
     miplevel: cl_GLint,
     texture: cl_GLuint,
 ) -> Result<cl_mem, cl_int> {
+    let context = context.as_raw();
+    clear_error();
     let mut status: cl_int = CL_INVALID_VALUE;
     let mem = clCreateFromGLTexture3D(
         context,
@@ -363,7 +398,13 @@ pub unsafe fn create_from_gl_texture_3d(
     if CL_SUCCESS == status {
         Ok(mem)
     } else {
-        Err(status)
+        Err(record_error!(
+            status,
+            clCreateFromGLTexture3D,
+            flags,
+            texture_target,
+            miplevel
+        ))
     }
 }
 
@@ -381,6 +422,7 @@ pub fn get_gl_context_info_khr(
     properties: *mut cl_context_properties,
     param_name: cl_gl_context_info,
 ) -> Result<InfoType, cl_int> {
+    clear_error();
     match param_name {
         CL_CURRENT_DEVICE_FOR_GL_CONTEXT_KHR => {
             let mut data: intptr_t = 0;
@@ -397,7 +439,7 @@ pub fn get_gl_context_info_khr(
             if CL_SUCCESS == status {
                 Ok(InfoType::Ptr(data))
             } else {
-                Err(status)
+                Err(record_error!(status, clGetGLContextInfoKHR, param_name))
             }
         }
 
@@ -408,7 +450,7 @@ pub fn get_gl_context_info_khr(
                 clGetGLContextInfoKHR(properties, param_name, 0, ptr::null_mut(), &mut size)
             };
             if CL_SUCCESS != status {
-                Err(status)
+                Err(record_error!(status, clGetGLContextInfoKHR, param_name))
             } else if 0 < size {
                 // Get the data
                 let count = size / mem::size_of::<intptr_t>();
@@ -425,7 +467,7 @@ pub fn get_gl_context_info_khr(
                 if CL_SUCCESS == status {
                     Ok(InfoType::VecIntPtr(data))
                 } else {
-                    Err(status)
+                    Err(record_error!(status, clGetGLContextInfoKHR, param_name))
                 }
             } else {
                 Ok(InfoType::VecIntPtr(Vec::default()))
@@ -439,7 +481,7 @@ pub fn get_gl_context_info_khr(
                 clGetGLContextInfoKHR(properties, param_name, 0, ptr::null_mut(), &mut size)
             };
             if CL_SUCCESS != status {
-                Err(status)
+                Err(record_error!(status, clGetGLContextInfoKHR, param_name))
             } else if 0 < size {
                 // Get the data
                 let mut data: Vec<u8> = Vec::with_capacity(size);
@@ -455,7 +497,7 @@ pub fn get_gl_context_info_khr(
                 if CL_SUCCESS == status {
                     Ok(InfoType::VecUchar(data))
                 } else {
-                    Err(status)
+                    Err(record_error!(status, clGetGLContextInfoKHR, param_name))
                 }
             } else {
                 Ok(InfoType::VecUchar(Vec::default()))
@@ -476,14 +518,16 @@ pub fn get_gl_context_info_khr(
 #[cfg(feature = "cl_khr_gl_event")]
 #[inline]
 pub fn create_event_from_gl_sync_khr(
-    context: cl_context,
+    context: impl AsContext,
     sync: cl_GLsync,
 ) -> Result<cl_event, cl_int> {
+    let context = context.as_raw();
+    clear_error();
     let mut status: cl_int = CL_INVALID_VALUE;
     let event: cl_event = unsafe { clCreateEventFromGLsyncKHR(context, sync, &mut status) };
     if CL_SUCCESS == status {
         Ok(event)
     } else {
-        Err(status)
+        Err(record_error!(status, clCreateEventFromGLsyncKHR))
     }
 }
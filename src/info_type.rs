@@ -18,11 +18,17 @@ use opencl_sys::{
     CL_UUID_SIZE_KHR,
 };
 use std::fmt;
+use thiserror::Error;
 
 /// A Rust enum to handle `OpenCL` API "Info" function return types.  
 /// Each of the data types may be extracted from the enum using its associated
 /// From trait or `to_*` function.  
 ///
+/// The data types may also be extracted without panicking by the `try_*`
+/// functions, the `as_*` functions which borrow the data, or the `TryFrom<&InfoType>`
+/// traits. Note: `TryFrom<InfoType>` cannot be implemented, since it conflicts
+/// with the From traits.
+///
 /// # Panics
 ///
 /// The From traits and `to_*` functions will panic if they are called for the
@@ -46,6 +52,16 @@ pub enum InfoType {
     VecVecUchar(Vec<Vec<u8>>),
 }
 
+/// The error returned when an `InfoType` is not the requested data type.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Error)]
+#[error("{found} is not an {expected}")]
+pub struct InfoTypeError {
+    /// The requested `InfoType` variant.
+    pub expected: &'static str,
+    /// The actual `InfoType` variant.
+    pub found: &'static str,
+}
+
 /// A macro to help create the `InfoType` From traits.
 macro_rules! match_info_type {
     ($value:expr, $variant:path) => {
@@ -56,6 +72,26 @@ macro_rules! match_info_type {
     };
 }
 
+/// A macro to help create the `InfoType` `try_*` functions.
+macro_rules! try_match_info_type {
+    ($value:expr, $variant:path) => {
+        match $value {
+            $variant(x) => Ok(x),
+            other => Err(other.error(stringify!($variant))),
+        }
+    };
+}
+
+/// A macro to help create the `InfoType` `as_*` functions.
+macro_rules! as_info_type {
+    ($value:expr, $variant:path) => {
+        match $value {
+            $variant(x) => Some(x),
+            _ => None,
+        }
+    };
+}
+
 impl From<InfoType> for i32 {
     fn from(value: InfoType) -> Self {
         match_info_type!(value, InfoType::Int)
@@ -153,16 +189,73 @@ impl From<InfoType> for String {
     ///
     /// returns a utf8 String.
     fn from(info_type: InfoType) -> Self {
-        let mut a = Vec::<u8>::from(info_type);
+        string_from_bytes(&Vec::<u8>::from(info_type))
+    }
+}
 
-        // remove all trailing nulls, if any
-        while a.last() == Some(&0) {
-            a.pop();
+/// A macro to help create the `TryFrom<&InfoType>` traits.
+macro_rules! try_from_info_type {
+    ($type:ty, $as_fn:ident, $variant:path) => {
+        impl TryFrom<&InfoType> for $type {
+            type Error = InfoTypeError;
+
+            fn try_from(value: &InfoType) -> Result<Self, Self::Error> {
+                value
+                    .$as_fn()
+                    .map(|x| x.to_owned())
+                    .ok_or_else(|| value.error(stringify!($variant)))
+            }
         }
+    };
+}
+
+try_from_info_type!(i32, as_int, InfoType::Int);
+try_from_info_type!(u32, as_uint, InfoType::Uint);
+try_from_info_type!(u64, as_ulong, InfoType::Ulong);
+try_from_info_type!(usize, as_size, InfoType::Size);
+try_from_info_type!(isize, as_ptr, InfoType::Ptr);
+try_from_info_type!([u8; CL_LUID_SIZE_KHR], as_luid, InfoType::Luid);
+try_from_info_type!([u8; CL_UUID_SIZE_KHR], as_uuid, InfoType::Uuid);
+try_from_info_type!(Vec<u8>, as_vec_uchar, InfoType::VecUchar);
+try_from_info_type!(Vec<u32>, as_vec_uint, InfoType::VecUshort);
+try_from_info_type!(Vec<u64>, as_vec_ulong, InfoType::VecUlong);
+try_from_info_type!(Vec<usize>, as_vec_size, InfoType::VecSize);
+try_from_info_type!(Vec<isize>, as_vec_intptr, InfoType::VecIntPtr);
+try_from_info_type!(
+    Vec<cl_name_version>,
+    as_vec_name_version,
+    InfoType::VecNameVersion
+);
+try_from_info_type!(
+    Vec<cl_image_format>,
+    as_vec_image_format,
+    InfoType::VecImageFormat
+);
+try_from_info_type!(Vec<Vec<u8>>, as_vec_vec_uchar, InfoType::VecVecUchar);
+
+impl TryFrom<&InfoType> for String {
+    type Error = InfoTypeError;
+
+    /// Get a `Vec<cl_uchar>` aka `Vec<u8>` as a String, see `From<InfoType>`.
+    fn try_from(value: &InfoType) -> Result<Self, Self::Error> {
+        value
+            .as_vec_uchar()
+            .map(string_from_bytes)
+            .ok_or_else(|| value.error("InfoType::VecUchar"))
+    }
+}
+
+/// Convert bytes to a utf8 String, without any trailing nulls.
+fn string_from_bytes(bytes: &[u8]) -> String {
+    let mut a = bytes;
 
-        // convert invalid characters to std::char::REPLACEMENT_CHARACTER
-        Self::from_utf8_lossy(&a).into_owned()
+    // remove all trailing nulls, if any
+    while let [rest @ .., 0] = a {
+        a = rest;
     }
+
+    // convert invalid characters to std::char::REPLACEMENT_CHARACTER
+    String::from_utf8_lossy(a).into_owned()
 }
 
 impl fmt::Display for InfoType {
@@ -252,6 +345,35 @@ impl fmt::Display for InfoType {
 }
 
 impl InfoType {
+    /// The name of the `InfoType` variant, e.g. "`InfoType::Uint`".
+    #[must_use]
+    pub const fn variant_name(&self) -> &'static str {
+        match self {
+            Self::Int(_) => "InfoType::Int",
+            Self::Uint(_) => "InfoType::Uint",
+            Self::Ulong(_) => "InfoType::Ulong",
+            Self::Size(_) => "InfoType::Size",
+            Self::Ptr(_) => "InfoType::Ptr",
+            Self::Luid(_) => "InfoType::Luid",
+            Self::Uuid(_) => "InfoType::Uuid",
+            Self::VecUchar(_) => "InfoType::VecUchar",
+            Self::VecUshort(_) => "InfoType::VecUshort",
+            Self::VecUlong(_) => "InfoType::VecUlong",
+            Self::VecSize(_) => "InfoType::VecSize",
+            Self::VecIntPtr(_) => "InfoType::VecIntPtr",
+            Self::VecNameVersion(_) => "InfoType::VecNameVersion",
+            Self::VecImageFormat(_) => "InfoType::VecImageFormat",
+            Self::VecVecUchar(_) => "InfoType::VecVecUchar",
+        }
+    }
+
+    const fn error(&self, expected: &'static str) -> InfoTypeError {
+        InfoTypeError {
+            expected,
+            found: self.variant_name(),
+        }
+    }
+
     #[must_use]
     pub fn to_int(self) -> cl_int {
         i32::from(self)
@@ -321,9 +443,150 @@ impl InfoType {
     pub fn to_vec_vec_uchar(self) -> Vec<Vec<cl_uchar>> {
         Vec::<Vec<u8>>::from(self)
     }
+
+    pub fn try_int(self) -> Result<cl_int, InfoTypeError> {
+        try_match_info_type!(self, InfoType::Int)
+    }
+
+    pub fn try_uint(self) -> Result<cl_uint, InfoTypeError> {
+        try_match_info_type!(self, InfoType::Uint)
+    }
+
+    pub fn try_ulong(self) -> Result<cl_ulong, InfoTypeError> {
+        try_match_info_type!(self, InfoType::Ulong)
+    }
+
+    pub fn try_size(self) -> Result<size_t, InfoTypeError> {
+        try_match_info_type!(self, InfoType::Size)
+    }
+
+    pub fn try_ptr(self) -> Result<intptr_t, InfoTypeError> {
+        try_match_info_type!(self, InfoType::Ptr)
+    }
+
+    pub fn try_luid(self) -> Result<[u8; CL_LUID_SIZE_KHR], InfoTypeError> {
+        try_match_info_type!(self, InfoType::Luid)
+    }
+
+    pub fn try_uuid(self) -> Result<[u8; CL_UUID_SIZE_KHR], InfoTypeError> {
+        try_match_info_type!(self, InfoType::Uuid)
+    }
+
+    pub fn try_vec_uchar(self) -> Result<Vec<cl_uchar>, InfoTypeError> {
+        try_match_info_type!(self, InfoType::VecUchar)
+    }
+
+    pub fn try_vec_uint(self) -> Result<Vec<cl_uint>, InfoTypeError> {
+        try_match_info_type!(self, InfoType::VecUshort)
+    }
+
+    pub fn try_vec_ulong(self) -> Result<Vec<cl_ulong>, InfoTypeError> {
+        try_match_info_type!(self, InfoType::VecUlong)
+    }
+
+    pub fn try_vec_size(self) -> Result<Vec<size_t>, InfoTypeError> {
+        try_match_info_type!(self, InfoType::VecSize)
+    }
+
+    pub fn try_vec_intptr(self) -> Result<Vec<intptr_t>, InfoTypeError> {
+        try_match_info_type!(self, InfoType::VecIntPtr)
+    }
+
+    pub fn try_vec_name_version(self) -> Result<Vec<cl_name_version>, InfoTypeError> {
+        try_match_info_type!(self, InfoType::VecNameVersion)
+    }
+
+    pub fn try_vec_image_format(self) -> Result<Vec<cl_image_format>, InfoTypeError> {
+        try_match_info_type!(self, InfoType::VecImageFormat)
+    }
+
+    pub fn try_vec_vec_uchar(self) -> Result<Vec<Vec<cl_uchar>>, InfoTypeError> {
+        try_match_info_type!(self, InfoType::VecVecUchar)
+    }
+
+    /// Get a `Vec<cl_uchar>` as a String, see `From<InfoType>`.
+    pub fn try_string(self) -> Result<String, InfoTypeError> {
+        self.try_vec_uchar().map(|a| string_from_bytes(&a))
+    }
+
+    #[must_use]
+    pub const fn as_int(&self) -> Option<&cl_int> {
+        as_info_type!(self, Self::Int)
+    }
+
+    #[must_use]
+    pub const fn as_uint(&self) -> Option<&cl_uint> {
+        as_info_type!(self, Self::Uint)
+    }
+
+    #[must_use]
+    pub const fn as_ulong(&self) -> Option<&cl_ulong> {
+        as_info_type!(self, Self::Ulong)
+    }
+
+    #[must_use]
+    pub const fn as_size(&self) -> Option<&size_t> {
+        as_info_type!(self, Self::Size)
+    }
+
+    #[must_use]
+    pub const fn as_ptr(&self) -> Option<&intptr_t> {
+        as_info_type!(self, Self::Ptr)
+    }
+
+    #[must_use]
+    pub const fn as_luid(&self) -> Option<&[u8; CL_LUID_SIZE_KHR]> {
+        as_info_type!(self, Self::Luid)
+    }
+
+    #[must_use]
+    pub const fn as_uuid(&self) -> Option<&[u8; CL_UUID_SIZE_KHR]> {
+        as_info_type!(self, Self::Uuid)
+    }
+
+    #[must_use]
+    pub fn as_vec_uchar(&self) -> Option<&[cl_uchar]> {
+        as_info_type!(self, Self::VecUchar).map(Vec::as_slice)
+    }
+
+    #[must_use]
+    pub fn as_vec_uint(&self) -> Option<&[cl_uint]> {
+        as_info_type!(self, Self::VecUshort).map(Vec::as_slice)
+    }
+
+    #[must_use]
+    pub fn as_vec_ulong(&self) -> Option<&[cl_ulong]> {
+        as_info_type!(self, Self::VecUlong).map(Vec::as_slice)
+    }
+
+    #[must_use]
+    pub fn as_vec_size(&self) -> Option<&[size_t]> {
+        as_info_type!(self, Self::VecSize).map(Vec::as_slice)
+    }
+
+    #[must_use]
+    pub fn as_vec_intptr(&self) -> Option<&[intptr_t]> {
+        as_info_type!(self, Self::VecIntPtr).map(Vec::as_slice)
+    }
+
+    #[must_use]
+    pub fn as_vec_name_version(&self) -> Option<&[cl_name_version]> {
+        as_info_type!(self, Self::VecNameVersion).map(Vec::as_slice)
+    }
+
+    #[must_use]
+    pub fn as_vec_image_format(&self) -> Option<&[cl_image_format]> {
+        as_info_type!(self, Self::VecImageFormat).map(Vec::as_slice)
+    }
+
+    #[must_use]
+    pub fn as_vec_vec_uchar(&self) -> Option<&[Vec<cl_uchar>]> {
+        as_info_type!(self, Self::VecVecUchar).map(Vec::as_slice)
+    }
 }
 #[cfg(test)]
 mod tests {
+    use super::*;
     use crate::device::{
         get_device_ids, get_device_info, CL_DEVICE_MAX_WORK_ITEM_SIZES, CL_DEVICE_NAME,
         CL_DEVICE_PARTITION_PROPERTIES, CL_DEVICE_TYPE, CL_DEVICE_TYPE_ALL, CL_DEVICE_VENDOR_ID,
@@ -375,4 +638,29 @@ mod tests {
         let value = get_device_info(device_id, CL_DEVICE_PARTITION_PROPERTIES).unwrap();
         println!("CL_DEVICE_PARTITION_PROPERTIES: {:?}", value);
     }
+
+    #[test]
+    fn test_try_info_type() {
+        let value = InfoType::Uint(42);
+        assert_eq!(Some(&42), value.as_uint());
+        assert_eq!(None, value.as_size());
+        assert_eq!(Ok(42), u32::try_from(&value));
+
+        let error = usize::try_from(&value).unwrap_err();
+        assert_eq!("InfoType::Size", error.expected);
+        assert_eq!("InfoType::Uint", error.found);
+        println!("InfoTypeError: {}", error);
+
+        assert_eq!(Ok(42), value.try_uint());
+        let error = InfoType::Size(42).try_uint().unwrap_err();
+        assert_eq!("InfoType::Uint", error.expected);
+        assert_eq!("InfoType::Size", error.found);
+
+        let value = InfoType::VecUchar(b"cl3\0\0".to_vec());
+        assert_eq!(Some(&b"cl3\0\0"[..]), value.as_vec_uchar());
+        assert_eq!(Ok(String::from("cl3")), String::try_from(&value));
+        assert!(Vec::<u64>::try_from(&value).is_err());
+        assert_eq!(Ok(String::from("cl3")), value.try_string());
+        assert!(InfoType::Ulong(1).try_string().is_err());
+    }
 }
@@ -42,18 +42,20 @@ pub use opencl_sys::{
     CL_SUCCESS,
 };
 
-use opencl_sys::{
+use super::api_error::{clear_error, record_error};
+use super::ffi::{
     clCreateKernel, clCreateKernelsInProgram, clGetKernelArgInfo, clGetKernelInfo,
     clGetKernelWorkGroupInfo, clReleaseKernel, clRetainKernel, clSetKernelArg,
 };
 
 #[cfg(feature = "CL_VERSION_2_0")]
-use opencl_sys::{clSetKernelArgSVMPointer, clSetKernelExecInfo};
+use super::ffi::{clSetKernelArgSVMPointer, clSetKernelExecInfo};
 
 #[cfg(feature = "CL_VERSION_2_1")]
-use opencl_sys::{clCloneKernel, clGetKernelSubGroupInfo};
+use super::ffi::{clCloneKernel, clGetKernelSubGroupInfo};
 
 use super::info_type::InfoType;
+use super::owned::{AsDevice, AsKernel, AsProgram};
 use super::{
     api2_info_size, api2_info_value, api2_info_vector, api_info_size, api_info_value,
     api_info_vector,
@@ -72,24 +74,27 @@ use std::ptr;
 /// returns a Result containing the new `OpenCL` kernel object
 /// or the error code from the `OpenCL` C API function.
 #[inline]
-pub fn create_kernel(program: cl_program, kernel_name: &CStr) -> Result<cl_kernel, cl_int> {
+pub fn create_kernel(program: impl AsProgram, kernel_name: &CStr) -> Result<cl_kernel, cl_int> {
+    let program = program.as_raw();
+    clear_error();
     let mut status: cl_int = CL_INVALID_VALUE;
     let kernel: cl_kernel = unsafe { clCreateKernel(program, kernel_name.as_ptr(), &mut status) };
     if CL_SUCCESS == status {
         Ok(kernel)
     } else {
-        Err(status)
+        Err(record_error!(status, clCreateKernel, kernel_name))
     }
 }
 
 fn count_kernels_in_program(program: cl_program) -> Result<cl_uint, cl_int> {
+    clear_error();
     let mut count: cl_uint = 0;
     let status: cl_int =
         unsafe { clCreateKernelsInProgram(program, 0, ptr::null_mut(), &mut count) };
     if CL_SUCCESS == status {
         Ok(count)
     } else {
-        Err(status)
+        Err(record_error!(status, clCreateKernelsInProgram))
     }
 }
 
@@ -101,7 +106,9 @@ fn count_kernels_in_program(program: cl_program) -> Result<cl_uint, cl_int> {
 /// returns a Result containing the new `OpenCL` kernel objects
 /// or the error code from the `OpenCL` C API function.
 #[inline]
-pub fn create_kernels_in_program(program: cl_program) -> Result<Vec<cl_kernel>, cl_int> {
+pub fn create_kernels_in_program(program: impl AsProgram) -> Result<Vec<cl_kernel>, cl_int> {
+    let program = program.as_raw();
+    clear_error();
     let count: cl_uint = count_kernels_in_program(program)?;
     let mut kernels: Vec<cl_kernel> = Vec::with_capacity(count as size_t);
     let status: cl_int = unsafe {
@@ -116,7 +123,7 @@ pub fn create_kernels_in_program(program: cl_program) -> Result<Vec<cl_kernel>,
     if CL_SUCCESS == status {
         Ok(kernels)
     } else {
-        Err(status)
+        Err(record_error!(status, clCreateKernelsInProgram))
     }
 }
 
@@ -130,13 +137,15 @@ pub fn create_kernels_in_program(program: cl_program) -> Result<Vec<cl_kernel>,
 /// or the error code from the `OpenCL` C API function.
 #[cfg(feature = "CL_VERSION_2_1")]
 #[inline]
-pub fn clone_kernel(source_kernel: cl_kernel) -> Result<cl_kernel, cl_int> {
+pub fn clone_kernel(source_kernel: impl AsKernel) -> Result<cl_kernel, cl_int> {
+    let source_kernel = source_kernel.as_raw();
+    clear_error();
     let mut status: cl_int = CL_INVALID_VALUE;
     let kernel: cl_kernel = unsafe { clCloneKernel(source_kernel, &mut status) };
     if CL_SUCCESS == status {
         Ok(kernel)
     } else {
-        Err(status)
+        Err(record_error!(status, clCloneKernel))
     }
 }
 
@@ -152,11 +161,12 @@ pub fn clone_kernel(source_kernel: cl_kernel) -> Result<cl_kernel, cl_int> {
 /// This function is unsafe because it changes the `OpenCL` object reference count.
 #[inline]
 pub unsafe fn retain_kernel(kernel: cl_kernel) -> Result<(), cl_int> {
+    clear_error();
     let status: cl_int = clRetainKernel(kernel);
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(status, clRetainKernel))
     }
 }
 
@@ -172,11 +182,12 @@ pub unsafe fn retain_kernel(kernel: cl_kernel) -> Result<(), cl_int> {
 /// This function is unsafe because it changes the `OpenCL` object reference count.
 #[inline]
 pub unsafe fn release_kernel(kernel: cl_kernel) -> Result<(), cl_int> {
+    clear_error();
     let status: cl_int = clReleaseKernel(kernel);
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(status, clReleaseKernel))
     }
 }
 
@@ -194,16 +205,18 @@ pub unsafe fn release_kernel(kernel: cl_kernel) -> Result<(), cl_int> {
 /// This function is unsafe because arg must match the kernel argument.
 #[inline]
 pub unsafe fn set_kernel_arg(
-    kernel: cl_kernel,
+    kernel: impl AsKernel,
     arg_index: cl_uint,
     arg_size: size_t,
     arg_value: *const c_void,
 ) -> Result<(), cl_int> {
+    let kernel = kernel.as_raw();
+    clear_error();
     let status: cl_int = clSetKernelArg(kernel, arg_index, arg_size, arg_value);
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(status, clSetKernelArg, arg_index, arg_size))
     }
 }
 
@@ -222,15 +235,17 @@ pub unsafe fn set_kernel_arg(
 #[cfg(feature = "CL_VERSION_2_0")]
 #[inline]
 pub unsafe fn set_kernel_arg_svm_pointer(
-    kernel: cl_kernel,
+    kernel: impl AsKernel,
     arg_index: cl_uint,
     arg_ptr: *const c_void,
 ) -> Result<(), cl_int> {
+    let kernel = kernel.as_raw();
+    clear_error();
     let status: cl_int = clSetKernelArgSVMPointer(kernel, arg_index, arg_ptr);
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(status, clSetKernelArgSVMPointer, arg_index))
     }
 }
 
@@ -250,22 +265,34 @@ pub unsafe fn set_kernel_arg_svm_pointer(
 #[cfg(feature = "CL_VERSION_2_0")]
 #[inline]
 pub unsafe fn set_kernel_exec_info(
-    kernel: cl_kernel,
+    kernel: impl AsKernel,
     param_name: cl_kernel_exec_info,
     param_value_size: size_t,
     param_value: *const c_void,
 ) -> Result<(), cl_int> {
+    let kernel = kernel.as_raw();
+    clear_error();
     let status: cl_int = clSetKernelExecInfo(kernel, param_name, param_value_size, param_value);
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(
+            status,
+            clSetKernelExecInfo,
+            param_name,
+            param_value_size
+        ))
     }
 }
 
 /// Get data about an `OpenCL` kernel.
 /// Calls clGetKernelInfo to get the desired data about the kernel.
-pub fn get_kernel_data(kernel: cl_kernel, param_name: cl_kernel_info) -> Result<Vec<u8>, cl_int> {
+pub fn get_kernel_data(
+    kernel: impl AsKernel,
+    param_name: cl_kernel_info,
+) -> Result<Vec<u8>, cl_int> {
+    let kernel = kernel.as_raw();
+    clear_error();
     api_info_size!(get_size, clGetKernelInfo);
     let size = get_size(kernel, param_name)?;
     api_info_vector!(get_vector, u8, clGetKernelInfo);
@@ -281,7 +308,12 @@ pub fn get_kernel_data(kernel: cl_kernel, param_name: cl_kernel_info) -> Result<
 ///
 /// returns a Result containing the desired information in an `InfoType` enum
 /// or the error code from the `OpenCL` C API function.
-pub fn get_kernel_info(kernel: cl_kernel, param_name: cl_kernel_info) -> Result<InfoType, cl_int> {
+pub fn get_kernel_info(
+    kernel: impl AsKernel,
+    param_name: cl_kernel_info,
+) -> Result<InfoType, cl_int> {
+    let kernel = kernel.as_raw();
+    clear_error();
     match param_name {
         CL_KERNEL_NUM_ARGS | CL_KERNEL_REFERENCE_COUNT => {
             api_info_value!(get_value, cl_uint, clGetKernelInfo);
@@ -302,10 +334,11 @@ pub fn get_kernel_info(kernel: cl_kernel, param_name: cl_kernel_info) -> Result<
 /// Calls clGetKernelArgInfo to get the desired data about arguments of the kernel.
 #[cfg(feature = "CL_VERSION_1_2")]
 pub fn get_kernel_arg_data(
-    kernel: cl_kernel,
+    kernel: impl AsKernel,
     arg_indx: cl_uint,
     param_name: cl_kernel_arg_info,
 ) -> Result<Vec<u8>, cl_int> {
+    let kernel = kernel.as_raw();
     api2_info_size!(get_size, cl_uint, clGetKernelArgInfo);
     let size = get_size(kernel, arg_indx, param_name)?;
     api2_info_vector!(get_vector, cl_uint, u8, clGetKernelArgInfo);
@@ -324,10 +357,11 @@ pub fn get_kernel_arg_data(
 /// or the error code from the `OpenCL` C API function.
 #[cfg(feature = "CL_VERSION_1_2")]
 pub fn get_kernel_arg_info(
-    kernel: cl_kernel,
+    kernel: impl AsKernel,
     arg_indx: cl_uint,
     param_name: cl_kernel_arg_info,
 ) -> Result<InfoType, cl_int> {
+    let kernel = kernel.as_raw();
     match param_name {
         CL_KERNEL_ARG_ADDRESS_QUALIFIER | CL_KERNEL_ARG_ACCESS_QUALIFIER => {
             api2_info_value!(get_index_value, cl_uint, cl_uint, clGetKernelArgInfo);
@@ -352,10 +386,12 @@ pub fn get_kernel_arg_info(
 /// Get data about work groups of an `OpenCL` kernel.
 /// Calls clGetKernelArgInfo to get the desired data about work groups of the kernel.
 pub fn get_kernel_work_group_data(
-    kernel: cl_kernel,
-    device: cl_device_id,
+    kernel: impl AsKernel,
+    device: impl AsDevice,
     param_name: cl_kernel_work_group_info,
 ) -> Result<Vec<u8>, cl_int> {
+    let kernel = kernel.as_raw();
+    let device = device.as_raw();
     api2_info_size!(get_size, cl_device_id, clGetKernelWorkGroupInfo);
     let size = get_size(kernel, device, param_name)?;
     api2_info_vector!(get_vector, cl_device_id, u8, clGetKernelWorkGroupInfo);
@@ -373,10 +409,12 @@ pub fn get_kernel_work_group_data(
 /// returns a Result containing the desired information in an `InfoType` enum
 /// or the error code from the `OpenCL` C API function.
 pub fn get_kernel_work_group_info(
-    kernel: cl_kernel,
-    device: cl_device_id,
+    kernel: impl AsKernel,
+    device: impl AsDevice,
     param_name: cl_kernel_work_group_info,
 ) -> Result<InfoType, cl_int> {
+    let kernel = kernel.as_raw();
+    let device = device.as_raw();
     match param_name {
         CL_KERNEL_WORK_GROUP_SIZE | CL_KERNEL_PREFERRED_WORK_GROUP_SIZE_MULTIPLE => {
             api2_info_value!(
@@ -436,12 +474,15 @@ pub fn get_kernel_work_group_info(
 /// or the error code from the `OpenCL` C API function.
 #[cfg(feature = "CL_VERSION_2_1")]
 pub fn get_kernel_sub_group_info(
-    kernel: cl_kernel,
-    device: cl_device_id,
+    kernel: impl AsKernel,
+    device: impl AsDevice,
     param_name: cl_kernel_sub_group_info,
     input_value_size: size_t,
     input_value: *const c_void,
 ) -> Result<InfoType, cl_int> {
+    let kernel = kernel.as_raw();
+    let device = device.as_raw();
+    clear_error();
     let mut size: size_t = mem::size_of::<size_t>();
     match param_name {
         CL_KERNEL_MAX_SUB_GROUP_SIZE_FOR_NDRANGE
@@ -466,7 +507,12 @@ pub fn get_kernel_sub_group_info(
             if CL_SUCCESS == status {
                 Ok(InfoType::Size(data))
             } else {
-                Err(status)
+                Err(record_error!(
+                    status,
+                    clGetKernelSubGroupInfo,
+                    param_name,
+                    input_value_size
+                ))
             }
         }
 
@@ -504,10 +550,20 @@ pub fn get_kernel_sub_group_info(
                 if CL_SUCCESS == status {
                     Ok(InfoType::VecSize(data))
                 } else {
-                    Err(status)
+                    Err(record_error!(
+                        status,
+                        clGetKernelSubGroupInfo,
+                        param_name,
+                        input_value_size
+                    ))
                 }
             } else {
-                Err(status)
+                Err(record_error!(
+                    status,
+                    clGetKernelSubGroupInfo,
+                    param_name,
+                    input_value_size
+                ))
             }
         }
 
@@ -545,10 +601,20 @@ pub fn get_kernel_sub_group_info(
                 if CL_SUCCESS == status {
                     Ok(InfoType::VecUchar(data))
                 } else {
-                    Err(status)
+                    Err(record_error!(
+                        status,
+                        clGetKernelSubGroupInfo,
+                        param_name,
+                        input_value_size
+                    ))
                 }
             } else {
-                Err(status)
+                Err(record_error!(
+                    status,
+                    clGetKernelSubGroupInfo,
+                    param_name,
+                    input_value_size
+                ))
             }
         }
     }
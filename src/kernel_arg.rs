@@ -0,0 +1,521 @@
+// Copyright (c) 2024 Via Technology Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Type-safe kernel arguments.
+//!
+//! The [`KernelArg`] trait is implemented for the `cl_*` scalar types, the
+//! `OpenCL` vector types in [`types`](crate::types), memory objects, samplers,
+//! device queues, typed [`Buffer`]s, [`LocalMemory`] sizes and SVM and USM pointers.
+//! [`set_arg`] sets a kernel argument from a `KernelArg` value, so the size and
+//! pointer passed to `clSetKernelArg` always match the value.
+//!
+//! [`set_arg_checked`] also checks the value against the argument type reflected
+//! by [`KernelArgInfo`], if the kernel's program was built with the
+//! `-cl-kernel-arg-info` option, e.g. a `cl_ulong` value is rejected for a
+//! `uint` argument:
+//!
+//! ```no_run
+//! use cl3::kernel_arg::{set_arg, set_arg_checked, LocalMemory};
+//! use cl3::types::{cl_float4, cl_int, cl_kernel, cl_mem, cl_uint};
+//!
+//! // kernel void scale(global float4* a, local float4* tmp, uint n, float4 factor)
+//! fn example(kernel: cl_kernel, buffer: cl_mem, n: cl_uint) -> Result<(), cl_int> {
+//!     set_arg_checked(kernel, 0, &buffer)?;
+//!     set_arg_checked(kernel, 1, &LocalMemory::of::<cl_float4>(64))?;
+//!     set_arg_checked(kernel, 2, &n)?;
+//!     set_arg(kernel, 3, &cl_float4::from([2.0, 2.0, 2.0, 1.0]))
+//! }
+//! ```
+//!
+//! [`KernelSignature::set_arg`](crate::kernel_signature::KernelSignature::set_arg)
+//! checks values against a signature that has already been queried.
+
+#![allow(non_camel_case_types, clippy::not_unsafe_ptr_arg_deref)]
+
+use super::api_error::record_error;
+use super::buffer::Buffer;
+#[cfg(feature = "cl_intel_unified_shared_memory")]
+use super::ext::set_kernel_arg_mem_pointer_intel;
+use super::kernel::set_kernel_arg;
+#[cfg(feature = "CL_VERSION_2_0")]
+use super::kernel::set_kernel_arg_svm_pointer;
+use super::kernel_signature::{AddressSpace, ArgType, KernelArgInfo, ScalarType};
+use super::owned::{AsKernel, CommandQueue, Mem, Sampler};
+use super::types::{
+    cl_char16, cl_char2, cl_char4, cl_char8, cl_double16, cl_double2, cl_double4, cl_double8,
+    cl_float16, cl_float2, cl_float4, cl_float8, cl_half16, cl_half2, cl_half4, cl_half8, cl_int16,
+    cl_int2, cl_int4, cl_int8, cl_long16, cl_long2, cl_long4, cl_long8, cl_short16, cl_short2,
+    cl_short4, cl_short8, cl_uchar16, cl_uchar2, cl_uchar4, cl_uchar8, cl_uint16, cl_uint2,
+    cl_uint4, cl_uint8, cl_ulong16, cl_ulong2, cl_ulong4, cl_ulong8, cl_ushort16, cl_ushort2,
+    cl_ushort4, cl_ushort8, Pod,
+};
+#[cfg(any(feature = "CL_VERSION_2_0", feature = "cl_intel_unified_shared_memory"))]
+use libc::c_void;
+use libc::size_t;
+use opencl_sys::{
+    cl_char, cl_double, cl_float, cl_int, cl_kernel, cl_long, cl_mem, cl_short, cl_uchar, cl_uint,
+    cl_ulong, cl_ushort, CL_INVALID_ARG_VALUE, CL_KERNEL_ARG_INFO_NOT_AVAILABLE,
+};
+use std::mem;
+use std::ptr;
+
+/// A value that can be set as a kernel argument.
+pub trait KernelArg {
+    /// Set the value as the argument at `arg_index` of `kernel`.
+    ///
+    /// returns an empty Result or the error code from the `OpenCL` C API function.
+    fn set_arg(&self, kernel: cl_kernel, arg_index: cl_uint) -> Result<(), cl_int>;
+
+    /// Whether the value can be set as the reflected kernel argument.
+    fn matches(&self, arg: &KernelArgInfo) -> bool;
+}
+
+/// Set a plain value of `size_of::<T>()` bytes as a kernel argument.
+fn set_value<T>(kernel: cl_kernel, arg_index: cl_uint, value: &T) -> Result<(), cl_int> {
+    unsafe {
+        set_kernel_arg(
+            kernel,
+            arg_index,
+            mem::size_of::<T>(),
+            ptr::from_ref(value).cast(),
+        )
+    }
+}
+
+/// Whether the argument is a pointer to global or constant memory.
+fn is_buffer_pointer(arg: &KernelArgInfo) -> bool {
+    arg.arg_type.is_pointer() && AddressSpace::Local != arg.address_space
+}
+
+macro_rules! value_arg {
+    ($($type:ty => $pattern:pat),* $(,)?) => {
+        $(
+            impl KernelArg for $type {
+                fn set_arg(&self, kernel: cl_kernel, arg_index: cl_uint) -> Result<(), cl_int> {
+                    set_value(kernel, arg_index, self)
+                }
+
+                fn matches(&self, arg: &KernelArgInfo) -> bool {
+                    matches!(arg.arg_type, $pattern)
+                }
+            }
+        )*
+    };
+}
+
+// cl_half is cl_ushort, so it matches both ushort and half arguments.
+// The 3 component vectors are the 4 component vectors.
+value_arg! {
+    cl_char => ArgType::Scalar(ScalarType::Char),
+    cl_uchar => ArgType::Scalar(ScalarType::UChar),
+    cl_short => ArgType::Scalar(ScalarType::Short),
+    cl_ushort => ArgType::Scalar(ScalarType::UShort | ScalarType::Half),
+    cl_int => ArgType::Scalar(ScalarType::Int),
+    cl_uint => ArgType::Scalar(ScalarType::UInt),
+    cl_long => ArgType::Scalar(ScalarType::Long),
+    cl_ulong => ArgType::Scalar(ScalarType::ULong),
+    cl_float => ArgType::Scalar(ScalarType::Float),
+    cl_double => ArgType::Scalar(ScalarType::Double),
+    cl_char2 => ArgType::Vector(ScalarType::Char, 2),
+    cl_char4 => ArgType::Vector(ScalarType::Char, 3 | 4),
+    cl_char8 => ArgType::Vector(ScalarType::Char, 8),
+    cl_char16 => ArgType::Vector(ScalarType::Char, 16),
+    cl_uchar2 => ArgType::Vector(ScalarType::UChar, 2),
+    cl_uchar4 => ArgType::Vector(ScalarType::UChar, 3 | 4),
+    cl_uchar8 => ArgType::Vector(ScalarType::UChar, 8),
+    cl_uchar16 => ArgType::Vector(ScalarType::UChar, 16),
+    cl_short2 => ArgType::Vector(ScalarType::Short, 2),
+    cl_short4 => ArgType::Vector(ScalarType::Short, 3 | 4),
+    cl_short8 => ArgType::Vector(ScalarType::Short, 8),
+    cl_short16 => ArgType::Vector(ScalarType::Short, 16),
+    cl_ushort2 => ArgType::Vector(ScalarType::UShort, 2),
+    cl_ushort4 => ArgType::Vector(ScalarType::UShort, 3 | 4),
+    cl_ushort8 => ArgType::Vector(ScalarType::UShort, 8),
+    cl_ushort16 => ArgType::Vector(ScalarType::UShort, 16),
+    cl_half2 => ArgType::Vector(ScalarType::Half, 2),
+    cl_half4 => ArgType::Vector(ScalarType::Half, 3 | 4),
+    cl_half8 => ArgType::Vector(ScalarType::Half, 8),
+    cl_half16 => ArgType::Vector(ScalarType::Half, 16),
+    cl_int2 => ArgType::Vector(ScalarType::Int, 2),
+    cl_int4 => ArgType::Vector(ScalarType::Int, 3 | 4),
+    cl_int8 => ArgType::Vector(ScalarType::Int, 8),
+    cl_int16 => ArgType::Vector(ScalarType::Int, 16),
+    cl_uint2 => ArgType::Vector(ScalarType::UInt, 2),
+    cl_uint4 => ArgType::Vector(ScalarType::UInt, 3 | 4),
+    cl_uint8 => ArgType::Vector(ScalarType::UInt, 8),
+    cl_uint16 => ArgType::Vector(ScalarType::UInt, 16),
+    cl_long2 => ArgType::Vector(ScalarType::Long, 2),
+    cl_long4 => ArgType::Vector(ScalarType::Long, 3 | 4),
+    cl_long8 => ArgType::Vector(ScalarType::Long, 8),
+    cl_long16 => ArgType::Vector(ScalarType::Long, 16),
+    cl_ulong2 => ArgType::Vector(ScalarType::ULong, 2),
+    cl_ulong4 => ArgType::Vector(ScalarType::ULong, 3 | 4),
+    cl_ulong8 => ArgType::Vector(ScalarType::ULong, 8),
+    cl_ulong16 => ArgType::Vector(ScalarType::ULong, 16),
+    cl_float2 => ArgType::Vector(ScalarType::Float, 2),
+    cl_float4 => ArgType::Vector(ScalarType::Float, 3 | 4),
+    cl_float8 => ArgType::Vector(ScalarType::Float, 8),
+    cl_float16 => ArgType::Vector(ScalarType::Float, 16),
+    cl_double2 => ArgType::Vector(ScalarType::Double, 2),
+    cl_double4 => ArgType::Vector(ScalarType::Double, 3 | 4),
+    cl_double8 => ArgType::Vector(ScalarType::Double, 8),
+    cl_double16 => ArgType::Vector(ScalarType::Double, 16),
+}
+
+/// Whether the argument is a memory object: a buffer, image or pipe.
+fn is_memory_object(arg: &KernelArgInfo) -> bool {
+    is_buffer_pointer(arg) || matches!(arg.arg_type, ArgType::Image(_) | ArgType::Pipe(_))
+}
+
+/// A raw memory object, sampler or command-queue handle.
+/// `cl_mem`, `cl_sampler` and `cl_command_queue` are the same type, so it
+/// matches any of their arguments; use the owned handles for a stricter check.
+impl KernelArg for cl_mem {
+    fn set_arg(&self, kernel: cl_kernel, arg_index: cl_uint) -> Result<(), cl_int> {
+        set_value(kernel, arg_index, self)
+    }
+
+    fn matches(&self, arg: &KernelArgInfo) -> bool {
+        is_memory_object(arg) || matches!(arg.arg_type, ArgType::Sampler | ArgType::Queue)
+    }
+}
+
+macro_rules! handle_arg {
+    ($($type:ty => $matches:expr),* $(,)?) => {
+        $(
+            impl KernelArg for $type {
+                fn set_arg(&self, kernel: cl_kernel, arg_index: cl_uint) -> Result<(), cl_int> {
+                    set_value(kernel, arg_index, &self.as_raw())
+                }
+
+                fn matches(&self, arg: &KernelArgInfo) -> bool {
+                    $matches(arg)
+                }
+            }
+        )*
+    };
+}
+
+handle_arg! {
+    Mem => is_memory_object,
+    Sampler => |arg: &KernelArgInfo| ArgType::Sampler == arg.arg_type,
+    CommandQueue => |arg: &KernelArgInfo| ArgType::Queue == arg.arg_type,
+}
+
+/// A typed buffer matches a pointer to global or constant memory of elements
+/// of the same size, or of an unknown size, e.g. `void*`.
+impl<T: Pod> KernelArg for Buffer<T> {
+    fn set_arg(&self, kernel: cl_kernel, arg_index: cl_uint) -> Result<(), cl_int> {
+        set_value(kernel, arg_index, &self.as_mem().as_raw())
+    }
+
+    fn matches(&self, arg: &KernelArgInfo) -> bool {
+        match &arg.arg_type {
+            ArgType::Pointer(pointee) => {
+                is_buffer_pointer(arg)
+                    && pointee
+                        .size()
+                        .is_none_or(|size| mem::size_of::<T>() == size)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// The size of a `local` memory kernel argument, which the device allocates
+/// for each work-group.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LocalMemory(size_t);
+
+impl LocalMemory {
+    /// A local memory argument of `size` bytes.
+    #[must_use]
+    pub const fn new(size: size_t) -> Self {
+        Self(size)
+    }
+
+    /// A local memory argument for `count` values of type `T`.
+    #[must_use]
+    pub const fn of<T>(count: usize) -> Self {
+        Self(count * mem::size_of::<T>())
+    }
+
+    /// The size of the local memory in bytes.
+    #[must_use]
+    pub const fn size(self) -> size_t {
+        self.0
+    }
+}
+
+impl KernelArg for LocalMemory {
+    fn set_arg(&self, kernel: cl_kernel, arg_index: cl_uint) -> Result<(), cl_int> {
+        unsafe { set_kernel_arg(kernel, arg_index, self.0, ptr::null()) }
+    }
+
+    fn matches(&self, arg: &KernelArgInfo) -> bool {
+        arg.arg_type.is_pointer() && AddressSpace::Local == arg.address_space
+    }
+}
+
+/// A shared virtual memory pointer kernel argument, see `svm_alloc`.
+#[cfg(feature = "CL_VERSION_2_0")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SvmPointer(*const c_void);
+
+#[cfg(feature = "CL_VERSION_2_0")]
+impl SvmPointer {
+    /// # Safety
+    ///
+    /// `ptr` must point into a SVM allocation that is valid while the kernel
+    /// argument is used, or be null.
+    #[must_use]
+    pub const unsafe fn new(ptr: *const c_void) -> Self {
+        Self(ptr)
+    }
+}
+
+#[cfg(feature = "CL_VERSION_2_0")]
+impl KernelArg for SvmPointer {
+    fn set_arg(&self, kernel: cl_kernel, arg_index: cl_uint) -> Result<(), cl_int> {
+        unsafe { set_kernel_arg_svm_pointer(kernel, arg_index, self.0) }
+    }
+
+    fn matches(&self, arg: &KernelArgInfo) -> bool {
+        is_buffer_pointer(arg)
+    }
+}
+
+/// A unified shared memory pointer kernel argument, see `host_mem_alloc_intel`.
+#[cfg(feature = "cl_intel_unified_shared_memory")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UsmPointer(*const c_void);
+
+#[cfg(feature = "cl_intel_unified_shared_memory")]
+impl UsmPointer {
+    /// # Safety
+    ///
+    /// `ptr` must point into a USM allocation that is valid while the kernel
+    /// argument is used, or be null.
+    #[must_use]
+    pub const unsafe fn new(ptr: *const c_void) -> Self {
+        Self(ptr)
+    }
+}
+
+#[cfg(feature = "cl_intel_unified_shared_memory")]
+impl KernelArg for UsmPointer {
+    fn set_arg(&self, kernel: cl_kernel, arg_index: cl_uint) -> Result<(), cl_int> {
+        unsafe { set_kernel_arg_mem_pointer_intel(kernel, arg_index, self.0) }
+    }
+
+    fn matches(&self, arg: &KernelArgInfo) -> bool {
+        is_buffer_pointer(arg)
+    }
+}
+
+/// Check a value against a reflected kernel argument.
+///
+/// returns an empty Result or `CL_INVALID_ARG_VALUE` if the value does not match.
+pub fn check_arg<T: KernelArg>(arg: &KernelArgInfo, value: &T) -> Result<(), cl_int> {
+    if value.matches(arg) {
+        Ok(())
+    } else {
+        let name = &arg.name;
+        let arg_type = arg.arg_type.to_string();
+        Err(record_error!(
+            CL_INVALID_ARG_VALUE,
+            check_arg,
+            name,
+            arg_type
+        ))
+    }
+}
+
+/// Set the argument value for a specific argument of a kernel.
+/// Calls clSetKernelArg, or the SVM or USM variant for pointers.
+///
+/// * `kernel` - the `OpenCL` kernel.
+/// * `arg_index` - the kernel argument index.
+/// * `value` - the argument value.
+///
+/// returns an empty Result or the error code from the `OpenCL` C API function.
+pub fn set_arg<T: KernelArg>(
+    kernel: impl AsKernel,
+    arg_index: cl_uint,
+    value: &T,
+) -> Result<(), cl_int> {
+    value.set_arg(kernel.as_raw(), arg_index)
+}
+
+/// Set the argument value for a specific argument of a kernel, checking the
+/// value against the reflected argument type if the kernel's program was built
+/// with the `-cl-kernel-arg-info` option.
+///
+/// * `kernel` - the `OpenCL` kernel.
+/// * `arg_index` - the kernel argument index.
+/// * `value` - the argument value.
+///
+/// returns an empty Result, `CL_INVALID_ARG_VALUE` if the value does not match
+/// the argument or the error code from the `OpenCL` C API function.
+pub fn set_arg_checked<T: KernelArg>(
+    kernel: impl AsKernel,
+    arg_index: cl_uint,
+    value: &T,
+) -> Result<(), cl_int> {
+    let kernel = kernel.as_raw();
+    match KernelArgInfo::from_kernel(kernel, arg_index) {
+        Ok(arg) => check_arg(&arg, value)?,
+        Err(CL_KERNEL_ARG_INFO_NOT_AVAILABLE) => {}
+        Err(e) => return Err(e),
+    }
+    value.set_arg(kernel, arg_index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::{create_context, release_context};
+    use crate::device::{get_device_ids, CL_DEVICE_TYPE_GPU};
+    use crate::kernel::{create_kernel, release_kernel};
+    use crate::kernel_signature::KernelSignature;
+    use crate::memory::{create_buffer, CL_ADDRESS_NONE, CL_FILTER_NEAREST, CL_MEM_READ_WRITE};
+    use crate::platform::get_platform_ids;
+    use crate::program::{build_program, create_program_with_source, release_program};
+    #[allow(deprecated)]
+    use crate::sampler::create_sampler;
+    use opencl_sys::{CL_FALSE, CL_INVALID_ARG_INDEX, CL_INVALID_ARG_SIZE};
+    use std::ffi::CString;
+
+    #[test]
+    fn test_kernel_arg() {
+        let platform_ids = get_platform_ids().unwrap();
+        let device_ids = get_device_ids(platform_ids[0], CL_DEVICE_TYPE_GPU).unwrap();
+        let context = create_context(&device_ids, ptr::null(), None, ptr::null_mut()).unwrap();
+
+        let source = r#"
+            kernel void scale(global float4* a,
+                local float4* tmp,
+                sampler_t smp,
+                uint n,
+                float3 factor,
+                short2 offset)
+            {
+                a[get_global_id(0)] *= factor.x;
+            }
+        "#;
+        let program = create_program_with_source(context, &[source]).unwrap();
+        let options = CString::new("-cl-kernel-arg-info").unwrap();
+        build_program(program, &device_ids, &options, None, ptr::null_mut()).unwrap();
+        let kernel = create_kernel(program, &CString::new("scale").unwrap()).unwrap();
+
+        let buffer = unsafe {
+            Mem::from_raw(create_buffer(context, CL_MEM_READ_WRITE, 64, ptr::null_mut()).unwrap())
+        };
+        #[allow(deprecated)]
+        let sampler = unsafe {
+            Sampler::from_raw(
+                create_sampler(context, CL_FALSE, CL_ADDRESS_NONE, CL_FILTER_NEAREST).unwrap(),
+            )
+        };
+        let factor = cl_float4::from([2.0, 2.0, 2.0, 0.0]);
+
+        set_arg_checked(kernel, 0, &buffer).unwrap();
+        set_arg_checked(kernel, 1, &LocalMemory::of::<cl_float4>(16)).unwrap();
+        set_arg_checked(kernel, 2, &sampler).unwrap();
+        set_arg_checked(kernel, 3, &(4 as cl_uint)).unwrap();
+        set_arg_checked(kernel, 4, &factor).unwrap();
+        set_arg_checked(kernel, 5, &cl_short2::from([1, -1])).unwrap();
+
+        // A ulong value for a uint argument
+        assert_eq!(
+            Err(CL_INVALID_ARG_VALUE),
+            set_arg_checked(kernel, 3, &(4 as cl_ulong))
+        );
+        assert_eq!(
+            Err(CL_INVALID_ARG_SIZE),
+            set_arg(kernel, 3, &(4 as cl_ulong))
+        );
+        // An int value for a uint argument has the right size
+        assert_eq!(
+            Err(CL_INVALID_ARG_VALUE),
+            set_arg_checked(kernel, 3, &(4 as cl_int))
+        );
+        assert_eq!(
+            Err(CL_INVALID_ARG_VALUE),
+            set_arg_checked(kernel, 1, &buffer)
+        );
+        assert_eq!(
+            Err(CL_INVALID_ARG_VALUE),
+            set_arg_checked(kernel, 0, &LocalMemory::new(64))
+        );
+
+        // Typed buffers of float4 sized elements match a float4 pointer
+        let vectors = Buffer::<cl_float4>::create(context, CL_MEM_READ_WRITE, 4).unwrap();
+        set_arg_checked(kernel, 0, &vectors).unwrap();
+        let floats = Buffer::<cl_float>::from_mem(vectors.into_mem()).unwrap();
+        assert_eq!(
+            Err(CL_INVALID_ARG_VALUE),
+            set_arg_checked(kernel, 0, &floats)
+        );
+        set_arg_checked(
+            kernel,
+            0,
+            &Buffer::<[cl_float; 4]>::from_mem(floats.into_mem()).unwrap(),
+        )
+        .unwrap();
+
+        let signature = KernelSignature::from_kernel(kernel).unwrap();
+        signature.set_arg(kernel, 4, &factor).unwrap();
+        assert_eq!(
+            Err(CL_INVALID_ARG_VALUE),
+            signature.set_arg(kernel, 5, &factor)
+        );
+        assert_eq!(
+            Err(CL_INVALID_ARG_INDEX),
+            signature.set_arg(kernel, 6, &factor)
+        );
+
+        unsafe { release_kernel(kernel).unwrap() };
+        unsafe { release_program(program).unwrap() };
+
+        // Without -cl-kernel-arg-info only the size is checked, but a real
+        // implementation may still report the argument types.
+        #[cfg(feature = "mock")]
+        {
+            let program = create_program_with_source(context, &[source]).unwrap();
+            build_program(
+                program,
+                &device_ids,
+                &CString::default(),
+                None,
+                ptr::null_mut(),
+            )
+            .unwrap();
+            let kernel = create_kernel(program, &CString::new("scale").unwrap()).unwrap();
+            set_arg_checked(kernel, 3, &(4 as cl_int)).unwrap();
+            assert_eq!(
+                Err(CL_INVALID_ARG_SIZE),
+                set_arg_checked(kernel, 3, &(4 as cl_ulong))
+            );
+            unsafe {
+                release_kernel(kernel).unwrap();
+                release_program(program).unwrap();
+            }
+        }
+
+        drop(buffer);
+        drop(sampler);
+        unsafe { release_context(context).unwrap() };
+    }
+}
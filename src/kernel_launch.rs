@@ -0,0 +1,867 @@
+// Copyright (c) 2024 Via Technology Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A kernel launch builder.
+//!
+//! [`KernelLaunch`] checks the work sizes of an ND-range against the device's
+//! `CL_DEVICE_MAX_WORK_ITEM_SIZES`, the kernel's `CL_KERNEL_WORK_GROUP_SIZE`
+//! and `CL_KERNEL_COMPILE_WORK_GROUP_SIZE`, and whether the device and program
+//! support non-uniform work-groups, before calling `enqueue_nd_range_kernel`.
+//! Each failed check is reported as a [`LaunchError`] that names the limit,
+//! instead of a plain `CL_INVALID_WORK_GROUP_SIZE`, e.g.:
+//!
+//! ```no_run
+//! use cl3::kernel_launch::KernelLaunch;
+//! use cl3::types::{cl_command_queue, cl_event, cl_int, cl_kernel};
+//!
+//! fn example(queue: cl_command_queue, kernel: cl_kernel, n: usize) -> Result<cl_event, cl_int> {
+//!     // The kernel arguments have been set and the kernel checks get_global_id(0) < n
+//!     let launch = KernelLaunch::new(kernel, &[n]).pad_global_size();
+//!     let sizes = launch.work_sizes(queue)?;
+//!     println!("global: {:?}, local: {:?}", sizes.global_size, sizes.local_size);
+//!     Ok(unsafe { launch.enqueue(queue)? })
+//! }
+//! ```
+//!
+//! Unless a local size is given, the builder chooses one: the kernel's
+//! compile work-group size, the size suggested by
+//! `get_kernel_suggested_local_work_size_khr` (with the
+//! `cl_khr_suggested_local_work_size` feature, if the device supports the
+//! extension), or a heuristic based on `CL_KERNEL_PREFERRED_WORK_GROUP_SIZE_MULTIPLE`.
+//! A local size tuned by the [`autotune`](super::autotune) module is used
+//! instead of the suggested size if the launch is given a [`TuningTable`].
+//!
+//! If the work-groups must be uniform, i.e. each global size must be a multiple
+//! of the local size, [`KernelLaunch::pad_global_size`] rounds the global sizes
+//! up to multiples of the local sizes. The kernel must then ignore the
+//! work-items outside of the original global size.
+
+use super::api_error::discard_error;
+use super::autotune::TuningTable;
+use super::command_queue::{enqueue_nd_range_kernel, get_command_queue_info, CL_QUEUE_DEVICE};
+#[cfg(feature = "cl_khr_suggested_local_work_size")]
+use super::device_info::Extensions;
+use super::device_info::{
+    get_device_param, DeviceVersion, MaxWorkItemSizes, NonUniformWorkGroupSupport,
+};
+use super::error_codes::error_text;
+#[cfg(feature = "cl_khr_suggested_local_work_size")]
+use super::ext::get_kernel_suggested_local_work_size_khr;
+use super::kernel::{
+    get_kernel_info, get_kernel_work_group_info, CL_KERNEL_COMPILE_WORK_GROUP_SIZE,
+    CL_KERNEL_PREFERRED_WORK_GROUP_SIZE_MULTIPLE, CL_KERNEL_PROGRAM, CL_KERNEL_WORK_GROUP_SIZE,
+};
+use super::owned::{AsCommandQueue, AsKernel};
+use super::program::{get_program_build_info, CL_PROGRAM_BUILD_OPTIONS};
+use libc::size_t;
+use opencl_sys::{
+    cl_command_queue, cl_device_id, cl_event, cl_int, cl_kernel, cl_program, cl_uint,
+    CL_INVALID_GLOBAL_OFFSET, CL_INVALID_WORK_DIMENSION, CL_INVALID_WORK_GROUP_SIZE,
+    CL_INVALID_WORK_ITEM_SIZE,
+};
+use std::fmt;
+use std::ptr;
+use std::sync::Arc;
+
+/// An error from checking or enqueueing a kernel launch.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LaunchError {
+    /// The number of global size dimensions is not 1, 2 or 3.
+    WorkDimension(usize),
+    /// The number of global offset dimensions differs from the global size.
+    GlobalOffset { dimensions: usize, work_dim: usize },
+    /// The number of local size dimensions differs from the global size.
+    LocalDimension { dimensions: usize, work_dim: usize },
+    /// A local size exceeds `CL_DEVICE_MAX_WORK_ITEM_SIZES`.
+    WorkItemSize {
+        dimension: usize,
+        size: size_t,
+        max: size_t,
+    },
+    /// The work-group size is zero or exceeds `CL_KERNEL_WORK_GROUP_SIZE`.
+    WorkGroupSize { size: size_t, max: size_t },
+    /// The local size differs from the kernel's `reqd_work_group_size` attribute.
+    CompileWorkGroupSize {
+        local_size: Vec<size_t>,
+        required: Vec<size_t>,
+    },
+    /// A global size is not a multiple of the local size, and the device or
+    /// program does not support non-uniform work-groups.
+    NonUniform {
+        dimension: usize,
+        global_size: size_t,
+        local_size: size_t,
+    },
+    /// The error code from an `OpenCL` C API function.
+    Api(cl_int),
+}
+
+impl LaunchError {
+    /// The `OpenCL` error code of the error.
+    #[must_use]
+    pub const fn code(&self) -> cl_int {
+        match self {
+            Self::WorkDimension(_) => CL_INVALID_WORK_DIMENSION,
+            Self::GlobalOffset { .. } => CL_INVALID_GLOBAL_OFFSET,
+            Self::WorkItemSize { .. } => CL_INVALID_WORK_ITEM_SIZE,
+            Self::LocalDimension { .. }
+            | Self::WorkGroupSize { .. }
+            | Self::CompileWorkGroupSize { .. }
+            | Self::NonUniform { .. } => CL_INVALID_WORK_GROUP_SIZE,
+            Self::Api(code) => *code,
+        }
+    }
+}
+
+impl fmt::Display for LaunchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::WorkDimension(work_dim) => {
+                write!(f, "{work_dim} work dimensions, expected 1, 2 or 3")
+            }
+            Self::GlobalOffset {
+                dimensions,
+                work_dim,
+            } => write!(
+                f,
+                "global offset has {dimensions} dimensions, global size has {work_dim}"
+            ),
+            Self::LocalDimension {
+                dimensions,
+                work_dim,
+            } => write!(
+                f,
+                "local size has {dimensions} dimensions, global size has {work_dim}"
+            ),
+            Self::WorkItemSize {
+                dimension,
+                size,
+                max,
+            } => write!(
+                f,
+                "local size {size} in dimension {dimension} exceeds CL_DEVICE_MAX_WORK_ITEM_SIZES {max}"
+            ),
+            Self::WorkGroupSize { size, max } => write!(
+                f,
+                "work-group size {size} is not in 1..={max} (CL_KERNEL_WORK_GROUP_SIZE)"
+            ),
+            Self::CompileWorkGroupSize {
+                local_size,
+                required,
+            } => write!(
+                f,
+                "local size {local_size:?} differs from the kernel's reqd_work_group_size {required:?}"
+            ),
+            Self::NonUniform {
+                dimension,
+                global_size,
+                local_size,
+            } => write!(
+                f,
+                "global size {global_size} in dimension {dimension} is not a multiple of local size {local_size} and non-uniform work-groups are not supported, pad the global size"
+            ),
+            Self::Api(code) => write!(f, "{code}: {}", error_text(*code)),
+        }
+    }
+}
+
+impl std::error::Error for LaunchError {}
+
+impl From<cl_int> for LaunchError {
+    fn from(code: cl_int) -> Self {
+        Self::Api(code)
+    }
+}
+
+impl From<LaunchError> for cl_int {
+    fn from(error: LaunchError) -> Self {
+        error.code()
+    }
+}
+
+/// How the local work size of a launch is chosen.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LocalSize {
+    /// The compile work-group size, the tuned size, the suggested size or a heuristic.
+    Auto,
+    /// The `OpenCL` implementation chooses, i.e. a null `local_work_size`.
+    Implementation,
+    /// The given local size.
+    Fixed(Vec<size_t>),
+}
+
+/// The work sizes of a launch, after checking and choosing the local size.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WorkSizes {
+    /// The global offset, empty if there is no offset.
+    pub global_offset: Vec<size_t>,
+    /// The global size, padded if `pad_global_size` was set.
+    pub global_size: Vec<size_t>,
+    /// The local size, None if the implementation chooses it.
+    pub local_size: Option<Vec<size_t>>,
+}
+
+/// The limits of a kernel on a device.
+struct Limits {
+    max_work_item_sizes: Vec<size_t>,
+    work_group_size: size_t,
+    compile_work_group_size: Vec<size_t>,
+    preferred_multiple: size_t,
+    non_uniform: bool,
+}
+
+/// A builder for an ND-range kernel launch.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KernelLaunch {
+    kernel: cl_kernel,
+    global_offset: Vec<size_t>,
+    global_size: Vec<size_t>,
+    local_size: LocalSize,
+    pad_global_size: bool,
+    wait_list: Vec<cl_event>,
+    tuning_table: Option<Arc<TuningTable>>,
+}
+
+impl KernelLaunch {
+    /// Create a launch of `kernel` over `global_size` work-items, with an
+    /// automatic local size.
+    #[must_use]
+    pub fn new(kernel: impl AsKernel, global_size: &[size_t]) -> Self {
+        Self {
+            kernel: kernel.as_raw(),
+            global_offset: Vec::new(),
+            global_size: global_size.to_vec(),
+            local_size: LocalSize::Auto,
+            pad_global_size: false,
+            wait_list: Vec::new(),
+            tuning_table: None,
+        }
+    }
+
+    #[must_use]
+    pub fn global_offset(mut self, global_offset: &[size_t]) -> Self {
+        self.global_offset = global_offset.to_vec();
+        self
+    }
+
+    #[must_use]
+    pub fn local_size(mut self, local_size: &[size_t]) -> Self {
+        self.local_size = LocalSize::Fixed(local_size.to_vec());
+        self
+    }
+
+    /// Let the `OpenCL` implementation choose the local size.
+    #[must_use]
+    pub fn implementation_local_size(mut self) -> Self {
+        self.local_size = LocalSize::Implementation;
+        self
+    }
+
+    /// Round the global sizes up to multiples of the local sizes if the
+    /// work-groups must be uniform.
+    #[must_use]
+    pub const fn pad_global_size(mut self) -> Self {
+        self.pad_global_size = true;
+        self
+    }
+
+    /// The events to wait for before the kernel is executed.
+    #[must_use]
+    pub fn wait_for(mut self, events: &[cl_event]) -> Self {
+        self.wait_list.extend_from_slice(events);
+        self
+    }
+
+    /// Use the local size tuned for the kernel, device and global size in a
+    /// tuning table, if any, when the local size is automatic.
+    #[must_use]
+    pub fn tuning_table(mut self, tuning_table: Arc<TuningTable>) -> Self {
+        self.tuning_table = Some(tuning_table);
+        self
+    }
+
+    #[must_use]
+    pub const fn get_local_size(&self) -> &LocalSize {
+        &self.local_size
+    }
+
+    /// Check the work sizes against the kernel and the device of a command-queue
+    /// and choose the local size.
+    ///
+    /// * `command_queue` - the command-queue that the kernel will be enqueued on.
+    ///
+    /// returns a Result containing the work sizes or a `LaunchError`.
+    pub fn work_sizes(&self, command_queue: impl AsCommandQueue) -> Result<WorkSizes, LaunchError> {
+        let command_queue = command_queue.as_raw();
+        let work_dim = self.global_size.len();
+        if !(1..=3).contains(&work_dim) {
+            return Err(LaunchError::WorkDimension(work_dim));
+        }
+        if !self.global_offset.is_empty() && work_dim != self.global_offset.len() {
+            return Err(LaunchError::GlobalOffset {
+                dimensions: self.global_offset.len(),
+                work_dim,
+            });
+        }
+
+        let device =
+            get_command_queue_info(command_queue, CL_QUEUE_DEVICE)?.to_ptr() as cl_device_id;
+        let limits = self.limits(device)?;
+        let mut global_size = self.global_size.clone();
+        let local_size = match &self.local_size {
+            LocalSize::Implementation => None,
+            LocalSize::Fixed(local_size) => Some(local_size.clone()),
+            LocalSize::Auto => Some(self.auto_local_size(command_queue, device, &limits)),
+        };
+
+        if let Some(local_size) = &local_size {
+            check_local_size(local_size, work_dim, &limits)?;
+            for (dimension, (global, local)) in global_size.iter_mut().zip(local_size).enumerate() {
+                if 0 != *global % local && !limits.non_uniform {
+                    if !self.pad_global_size {
+                        return Err(LaunchError::NonUniform {
+                            dimension,
+                            global_size: *global,
+                            local_size: *local,
+                        });
+                    }
+                    *global = global.next_multiple_of(*local);
+                }
+            }
+        }
+
+        Ok(WorkSizes {
+            global_offset: self.global_offset.clone(),
+            global_size,
+            local_size,
+        })
+    }
+
+    /// Check the work sizes and enqueue the kernel.
+    /// Calls `enqueue_nd_range_kernel`.
+    ///
+    /// * `command_queue` - the command-queue to enqueue the kernel on.
+    ///
+    /// returns a Result containing the new `OpenCL` event or a `LaunchError`.
+    ///
+    /// # Safety
+    ///
+    /// This function is unsafe because the kernel arguments must be set and
+    /// valid while the kernel is executed.
+    #[allow(clippy::cast_possible_truncation)]
+    pub unsafe fn enqueue(
+        &self,
+        command_queue: impl AsCommandQueue,
+    ) -> Result<cl_event, LaunchError> {
+        let command_queue = command_queue.as_raw();
+        let sizes = self.work_sizes(command_queue)?;
+        let global_offset = if sizes.global_offset.is_empty() {
+            ptr::null()
+        } else {
+            sizes.global_offset.as_ptr()
+        };
+        let local_size = sizes.local_size.as_ref().map_or(ptr::null(), Vec::as_ptr);
+        let wait_list = if self.wait_list.is_empty() {
+            ptr::null()
+        } else {
+            self.wait_list.as_ptr()
+        };
+        Ok(enqueue_nd_range_kernel(
+            command_queue,
+            self.kernel,
+            sizes.global_size.len() as cl_uint,
+            global_offset,
+            sizes.global_size.as_ptr(),
+            local_size,
+            self.wait_list.len() as cl_uint,
+            wait_list,
+        )?)
+    }
+
+    fn limits(&self, device: cl_device_id) -> Result<Limits, cl_int> {
+        let max_work_item_sizes = get_device_param(device, MaxWorkItemSizes)?;
+        let work_group_size =
+            get_kernel_work_group_info(self.kernel, device, CL_KERNEL_WORK_GROUP_SIZE)?.to_size();
+        let compile_work_group_size =
+            get_kernel_work_group_info(self.kernel, device, CL_KERNEL_COMPILE_WORK_GROUP_SIZE)?
+                .to_vec_size();
+        let preferred_multiple = get_kernel_work_group_info(
+            self.kernel,
+            device,
+            CL_KERNEL_PREFERRED_WORK_GROUP_SIZE_MULTIPLE,
+        )?
+        .to_size();
+        Ok(Limits {
+            max_work_item_sizes,
+            work_group_size,
+            compile_work_group_size,
+            preferred_multiple,
+            non_uniform: non_uniform_work_groups(self.kernel, device)?,
+        })
+    }
+
+    fn auto_local_size(
+        &self,
+        command_queue: cl_command_queue,
+        device: cl_device_id,
+        limits: &Limits,
+    ) -> Vec<size_t> {
+        let work_dim = self.global_size.len();
+        if limits.compile_work_group_size.iter().any(|size| 0 != *size) {
+            return limits
+                .compile_work_group_size
+                .iter()
+                .copied()
+                .take(work_dim)
+                .collect();
+        }
+        if let Some(local_size) = self.tuned_local_size(device, limits) {
+            return local_size;
+        }
+        if let Some(local_size) = self.suggested_local_size(command_queue, device) {
+            return local_size;
+        }
+        let heuristic = |global_size: &[size_t], uniform| {
+            heuristic_local_size(
+                global_size,
+                &limits.max_work_item_sizes,
+                limits.work_group_size,
+                limits.preferred_multiple,
+                uniform,
+            )
+        };
+        let local_size = heuristic(&self.global_size, !limits.non_uniform);
+        let multiple = limits.preferred_multiple.max(1);
+        if limits.non_uniform || !self.pad_global_size || 0 == local_size[0] % multiple {
+            return local_size;
+        }
+
+        // Pad rather than use a divisor that is not a multiple of the preferred multiple
+        let mut global_size = self.global_size.clone();
+        global_size[0] = global_size[0].next_multiple_of(multiple);
+        heuristic(&global_size, false)
+    }
+
+    /// The local size in the tuning table, if it is valid for the limits and
+    /// divides the global size when the work-groups must be uniform and the
+    /// global size is not padded.
+    fn tuned_local_size(&self, device: cl_device_id, limits: &Limits) -> Option<Vec<size_t>> {
+        let tuning_table = self.tuning_table.as_ref()?;
+        let setting =
+            discard_error(tuning_table.get_for_kernel(device, self.kernel, &self.global_size))??;
+        check_local_size(&setting.local_size, self.global_size.len(), limits).ok()?;
+        (limits.non_uniform
+            || self.pad_global_size
+            || self
+                .global_size
+                .iter()
+                .zip(&setting.local_size)
+                .all(|(global, local)| 0 == global % local))
+        .then(|| setting.local_size.clone())
+    }
+
+    #[cfg(feature = "cl_khr_suggested_local_work_size")]
+    #[allow(clippy::cast_possible_truncation)]
+    fn suggested_local_size(
+        &self,
+        command_queue: cl_command_queue,
+        device: cl_device_id,
+    ) -> Option<Vec<size_t>> {
+        let extensions = discard_error(get_device_param(device, Extensions))?;
+        if !extensions
+            .split_whitespace()
+            .any(|extension| "cl_khr_suggested_local_work_size" == extension)
+        {
+            return None;
+        }
+        let global_offset = if self.global_offset.is_empty() {
+            ptr::null()
+        } else {
+            self.global_offset.as_ptr()
+        };
+        discard_error(get_kernel_suggested_local_work_size_khr(
+            command_queue,
+            self.kernel,
+            self.global_size.len() as cl_uint,
+            global_offset,
+            self.global_size.as_ptr(),
+        ))
+        .filter(|local_size| !local_size.contains(&0))
+    }
+
+    #[cfg(not(feature = "cl_khr_suggested_local_work_size"))]
+    #[allow(clippy::unused_self)]
+    const fn suggested_local_size(
+        &self,
+        _command_queue: cl_command_queue,
+        _device: cl_device_id,
+    ) -> Option<Vec<size_t>> {
+        None
+    }
+}
+
+/// Check a local size against the limits of the kernel and device.
+fn check_local_size(
+    local_size: &[size_t],
+    work_dim: usize,
+    limits: &Limits,
+) -> Result<(), LaunchError> {
+    if work_dim != local_size.len() {
+        return Err(LaunchError::LocalDimension {
+            dimensions: local_size.len(),
+            work_dim,
+        });
+    }
+    for (dimension, (size, max)) in local_size
+        .iter()
+        .zip(&limits.max_work_item_sizes)
+        .enumerate()
+    {
+        if max < size {
+            return Err(LaunchError::WorkItemSize {
+                dimension,
+                size: *size,
+                max: *max,
+            });
+        }
+    }
+    let size: size_t = local_size.iter().product();
+    if !(1..=limits.work_group_size).contains(&size) {
+        return Err(LaunchError::WorkGroupSize {
+            size,
+            max: limits.work_group_size,
+        });
+    }
+
+    // The unused dimensions of a reqd_work_group_size are 1
+    let required = &limits.compile_work_group_size;
+    if required.iter().any(|size| 0 != *size)
+        && required
+            .iter()
+            .enumerate()
+            .any(|(i, required)| *required != local_size.get(i).copied().unwrap_or(1))
+    {
+        return Err(LaunchError::CompileWorkGroupSize {
+            local_size: local_size.to_vec(),
+            required: required.clone(),
+        });
+    }
+    Ok(())
+}
+
+/// Parse the major version of a `CL_DEVICE_VERSION`, i.e.
+/// "`OpenCL <major>.<minor> <vendor-specific information>`".
+fn device_major_version(version: &str) -> Option<u32> {
+    let version = version.strip_prefix("OpenCL ")?;
+    version.split('.').next()?.parse().ok()
+}
+
+/// Whether program build options allow non-uniform work-groups: an `OpenCL` C
+/// 2.0 or later `-cl-std`, without `-cl-uniform-work-group-size`.
+fn options_allow_non_uniform(options: &str) -> bool {
+    let mut allow = false;
+    for option in options.split_whitespace() {
+        if "-cl-uniform-work-group-size" == option {
+            return false;
+        }
+        if let Some(version) = option.strip_prefix("-cl-std=CL") {
+            allow = version
+                .split('.')
+                .next()
+                .and_then(|major| major.parse::<u32>().ok())
+                .is_some_and(|major| 2 <= major);
+        }
+    }
+    allow
+}
+
+/// Whether a kernel can be enqueued with non-uniform work-groups on a device.
+/// Programs that were not built from `OpenCL` C 2.0 or later, e.g. from IL, are
+/// conservatively assumed to require uniform work-groups.
+fn non_uniform_work_groups(kernel: cl_kernel, device: cl_device_id) -> Result<bool, cl_int> {
+    let device_support = match device_major_version(&get_device_param(device, DeviceVersion)?) {
+        Some(0 | 1) | None => false,
+        Some(2) => true,
+        Some(_) => {
+            discard_error(get_device_param(device, NonUniformWorkGroupSupport)).unwrap_or(false)
+        }
+    };
+    if !device_support {
+        return Ok(false);
+    }
+    let program = get_kernel_info(kernel, CL_KERNEL_PROGRAM)?.to_ptr() as cl_program;
+    let options = String::from(get_program_build_info(
+        program,
+        device,
+        CL_PROGRAM_BUILD_OPTIONS,
+    )?);
+    Ok(options_allow_non_uniform(&options))
+}
+
+/// Choose a local size for a global size.
+///
+/// The first dimension is preferably a multiple of `preferred_multiple`, each
+/// local size is at most the global size and the work-group size is at most
+/// `max_work_group_size`. If `uniform`, each local size is a divisor of the
+/// global size.
+fn heuristic_local_size(
+    global_size: &[size_t],
+    max_work_item_sizes: &[size_t],
+    max_work_group_size: size_t,
+    preferred_multiple: size_t,
+    uniform: bool,
+) -> Vec<size_t> {
+    let mut budget = max_work_group_size.max(1);
+    global_size
+        .iter()
+        .enumerate()
+        .map(|(dimension, global)| {
+            let multiple = if 0 == dimension {
+                preferred_multiple.max(1)
+            } else {
+                1
+            };
+            let max = max_work_item_sizes.get(dimension).copied().unwrap_or(1);
+            let limit = budget.min(max).min(*global).max(1);
+            let size = if uniform {
+                // The largest divisor, preferably a multiple
+                let mut divisors = (1..=limit)
+                    .rev()
+                    .filter(|size| 0 == global % size)
+                    .peekable();
+                let largest = divisors.peek().copied().unwrap_or(1);
+                divisors
+                    .find(|size| 0 == size % multiple)
+                    .unwrap_or(largest)
+            } else if multiple <= limit {
+                limit - limit % multiple
+            } else {
+                limit
+            };
+            budget /= size;
+            size
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[allow(deprecated)]
+    use crate::command_queue::{create_command_queue, finish, release_command_queue};
+    use crate::context::{create_context, release_context};
+    use crate::device::{get_device_ids, CL_DEVICE_TYPE_GPU};
+    use crate::event::release_event;
+    use crate::kernel::{create_kernel, release_kernel};
+    use crate::kernel_arg::set_arg;
+    use crate::memory::{create_buffer, release_mem_object, CL_MEM_READ_WRITE};
+    use crate::platform::get_platform_ids;
+    use crate::program::{build_program, create_program_with_source, release_program};
+    use opencl_sys::cl_float;
+    use std::ffi::CString;
+
+    #[test]
+    fn test_heuristic_local_size() {
+        assert_eq!(
+            vec![100],
+            heuristic_local_size(&[100], &[256], 256, 8, true)
+        );
+        assert_eq!(
+            vec![200],
+            heuristic_local_size(&[1000], &[256], 256, 8, true)
+        );
+        assert_eq!(
+            vec![96],
+            heuristic_local_size(&[100], &[256], 256, 8, false)
+        );
+        assert_eq!(
+            vec![256],
+            heuristic_local_size(&[1000], &[256], 256, 8, false)
+        );
+        assert_eq!(vec![5], heuristic_local_size(&[5], &[256], 256, 8, false));
+        assert_eq!(
+            vec![64, 4],
+            heuristic_local_size(&[64, 64], &[256, 256], 256, 8, true)
+        );
+        assert_eq!(vec![1], heuristic_local_size(&[0], &[256], 256, 8, true));
+
+        assert!(!options_allow_non_uniform(""));
+        assert!(!options_allow_non_uniform("-cl-std=CL1.2"));
+        assert!(options_allow_non_uniform("-cl-std=CL3.0 -cl-mad-enable"));
+        assert!(!options_allow_non_uniform(
+            "-cl-std=CL2.0 -cl-uniform-work-group-size"
+        ));
+        assert_eq!(Some(3), device_major_version("OpenCL 3.0 CUDA"));
+    }
+
+    #[test]
+    fn test_kernel_launch() {
+        let platform_ids = get_platform_ids().unwrap();
+        let device_ids = get_device_ids(platform_ids[0], CL_DEVICE_TYPE_GPU).unwrap();
+        let device_id = device_ids[0];
+        let context = create_context(&device_ids, ptr::null(), None, ptr::null_mut()).unwrap();
+        #[allow(deprecated)]
+        let queue = unsafe { create_command_queue(context, device_id, 0).unwrap() };
+
+        let source = r#"
+            kernel void fill(global float* a, float v, uint n) {
+                size_t i = get_global_id(0);
+                if (i < n) a[i] = v;
+            }
+            kernel __attribute__((reqd_work_group_size(16, 1, 1)))
+            void fill16(global float* a, float v) { a[get_global_id(0)] = v; }
+        "#;
+        let program = create_program_with_source(context, &[source]).unwrap();
+        build_program(
+            program,
+            &device_ids,
+            &CString::default(),
+            None,
+            ptr::null_mut(),
+        )
+        .unwrap();
+        let kernel = create_kernel(program, &CString::new("fill").unwrap()).unwrap();
+        let kernel16 = create_kernel(program, &CString::new("fill16").unwrap()).unwrap();
+        let max_work_item_sizes = get_device_param(device_id, MaxWorkItemSizes).unwrap();
+        let work_group_size =
+            get_kernel_work_group_info(kernel, device_id, CL_KERNEL_WORK_GROUP_SIZE)
+                .unwrap()
+                .to_size();
+
+        // Without -cl-std=CL2.0 or later the work-groups must be uniform
+        let sizes = KernelLaunch::new(kernel, &[100]).work_sizes(queue).unwrap();
+        let local_size = sizes.local_size.unwrap();
+        assert_eq!(0, 100 % local_size[0]);
+        #[cfg(feature = "mock")]
+        assert_eq!(vec![100], local_size);
+        let launch = KernelLaunch::new(kernel, &[100]).local_size(&[64]);
+        let error = launch.work_sizes(queue).unwrap_err();
+        println!("{}", error);
+        assert_eq!(
+            LaunchError::NonUniform {
+                dimension: 0,
+                global_size: 100,
+                local_size: 64
+            },
+            error
+        );
+        assert_eq!(CL_INVALID_WORK_GROUP_SIZE, cl_int::from(error));
+        let sizes = launch.pad_global_size().work_sizes(queue).unwrap();
+        assert_eq!(vec![128], sizes.global_size);
+
+        let size = max_work_item_sizes[0] + 1;
+        assert_eq!(
+            Err(LaunchError::WorkItemSize {
+                dimension: 0,
+                size,
+                max: max_work_item_sizes[0]
+            }),
+            KernelLaunch::new(kernel, &[size])
+                .local_size(&[size])
+                .work_sizes(queue)
+        );
+        assert_eq!(
+            Err(LaunchError::WorkGroupSize {
+                size: max_work_item_sizes[0] * max_work_item_sizes[1],
+                max: work_group_size
+            }),
+            KernelLaunch::new(kernel, &max_work_item_sizes[..2])
+                .local_size(&max_work_item_sizes[..2])
+                .work_sizes(queue)
+        );
+        assert_eq!(
+            Err(LaunchError::WorkDimension(4)),
+            KernelLaunch::new(kernel, &[1, 1, 1, 1]).work_sizes(queue)
+        );
+        assert_eq!(
+            CL_INVALID_GLOBAL_OFFSET,
+            KernelLaunch::new(kernel, &[64])
+                .global_offset(&[0, 0])
+                .work_sizes(queue)
+                .unwrap_err()
+                .code()
+        );
+
+        // The compile work-group size
+        let sizes = KernelLaunch::new(kernel16, &[64])
+            .work_sizes(queue)
+            .unwrap();
+        assert_eq!(Some(vec![16]), sizes.local_size);
+        assert!(matches!(
+            KernelLaunch::new(kernel16, &[64])
+                .local_size(&[32])
+                .work_sizes(queue),
+            Err(LaunchError::CompileWorkGroupSize { .. })
+        ));
+
+        // Enqueue a padded launch
+        let n: cl_uint = 100;
+        let buffer = unsafe {
+            create_buffer(
+                context,
+                CL_MEM_READ_WRITE,
+                n as usize * std::mem::size_of::<cl_float>(),
+                ptr::null_mut(),
+            )
+            .unwrap()
+        };
+        set_arg(kernel, 0, &buffer).unwrap();
+        set_arg(kernel, 1, &(1.0 as cl_float)).unwrap();
+        set_arg(kernel, 2, &n).unwrap();
+        let launch = KernelLaunch::new(kernel, &[n as usize]).pad_global_size();
+        let sizes = launch.work_sizes(queue).unwrap();
+        println!("{:?}", sizes);
+        let local_size = sizes.local_size.clone().unwrap();
+        assert_eq!(0, sizes.global_size[0] % local_size[0]);
+        assert!(n as usize <= sizes.global_size[0]);
+        #[cfg(feature = "mock")]
+        {
+            assert_eq!(vec![104], sizes.global_size);
+            assert_eq!(vec![104], local_size);
+        }
+        let event = unsafe { launch.enqueue(queue).unwrap() };
+        finish(queue).unwrap();
+
+        unsafe {
+            release_event(event).unwrap();
+            release_mem_object(buffer).unwrap();
+            release_kernel(kernel16).unwrap();
+            release_kernel(kernel).unwrap();
+            release_program(program).unwrap();
+        }
+
+        // OpenCL C 3.0 programs may have non-uniform work-groups on the device
+        let program = create_program_with_source(context, &[source]).unwrap();
+        let options = CString::new("-cl-std=CL3.0").unwrap();
+        build_program(program, &device_ids, &options, None, ptr::null_mut()).unwrap();
+        let kernel = create_kernel(program, &CString::new("fill").unwrap()).unwrap();
+        let sizes = KernelLaunch::new(kernel, &[100])
+            .local_size(&[64])
+            .work_sizes(queue)
+            .unwrap();
+        assert_eq!(vec![100], sizes.global_size);
+        #[cfg(feature = "mock")]
+        {
+            let sizes = KernelLaunch::new(kernel, &[100]).work_sizes(queue).unwrap();
+            assert_eq!(Some(vec![96]), sizes.local_size);
+        }
+
+        unsafe {
+            release_kernel(kernel).unwrap();
+            release_program(program).unwrap();
+            release_command_queue(queue).unwrap();
+            release_context(context).unwrap();
+        }
+    }
+}
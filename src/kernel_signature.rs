@@ -0,0 +1,711 @@
+// Copyright (c) 2024 Via Technology Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Kernel argument reflection.
+//!
+//! [`KernelSignature::from_kernel`] queries the name, attributes and
+//! arguments of a kernel and parses the type name of each argument into an
+//! [`ArgType`]: a scalar, vector, pointer, image, sampler, pipe or other type.
+//! The signature renders back to an `OpenCL` C kernel prototype, e.g.:
+//!
+//! ```no_run
+//! use cl3::kernel_signature::KernelSignature;
+//! use cl3::types::{cl_int, cl_kernel};
+//!
+//! // The kernel's program was built with the -cl-kernel-arg-info option
+//! fn example(kernel: cl_kernel) -> Result<(), cl_int> {
+//!     let signature = KernelSignature::from_kernel(kernel)?;
+//!     println!("{}", signature);
+//!     for arg in &signature.args {
+//!         println!("{}: {:?} in {}", arg.name, arg.arg_type, arg.address_space);
+//!     }
+//!     Ok(())
+//! }
+//! ```
+//!
+//! The argument information is only available if the kernel's program was
+//! built with the `-cl-kernel-arg-info` option, otherwise `from_kernel` returns
+//! `CL_KERNEL_ARG_INFO_NOT_AVAILABLE`.
+
+use super::api_error::record_error;
+use super::kernel::{
+    get_kernel_arg_info, get_kernel_info, CL_KERNEL_ARG_ACCESS_NONE,
+    CL_KERNEL_ARG_ACCESS_QUALIFIER, CL_KERNEL_ARG_ACCESS_READ_ONLY,
+    CL_KERNEL_ARG_ACCESS_READ_WRITE, CL_KERNEL_ARG_ACCESS_WRITE_ONLY,
+    CL_KERNEL_ARG_ADDRESS_CONSTANT, CL_KERNEL_ARG_ADDRESS_GLOBAL, CL_KERNEL_ARG_ADDRESS_LOCAL,
+    CL_KERNEL_ARG_ADDRESS_PRIVATE, CL_KERNEL_ARG_ADDRESS_QUALIFIER, CL_KERNEL_ARG_NAME,
+    CL_KERNEL_ARG_TYPE_CONST, CL_KERNEL_ARG_TYPE_NAME, CL_KERNEL_ARG_TYPE_PIPE,
+    CL_KERNEL_ARG_TYPE_QUALIFIER, CL_KERNEL_ARG_TYPE_RESTRICT, CL_KERNEL_ARG_TYPE_VOLATILE,
+    CL_KERNEL_ATTRIBUTES, CL_KERNEL_FUNCTION_NAME, CL_KERNEL_NUM_ARGS,
+};
+use super::kernel_arg::{check_arg, KernelArg};
+use super::owned::AsKernel;
+use opencl_sys::{
+    cl_int, cl_kernel_arg_access_qualifier, cl_kernel_arg_address_qualifier,
+    cl_kernel_arg_type_qualifier, cl_uint, cl_ulong, CL_INVALID_ARG_INDEX, CL_INVALID_VALUE,
+};
+use std::fmt;
+
+/// The `OpenCL` C built-in scalar types.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ScalarType {
+    Bool,
+    Char,
+    UChar,
+    Short,
+    UShort,
+    Int,
+    UInt,
+    Long,
+    ULong,
+    Half,
+    Float,
+    Double,
+    SizeT,
+    PtrDiffT,
+    IntPtrT,
+    UIntPtrT,
+}
+
+impl ScalarType {
+    /// Parse an `OpenCL` C scalar type name, e.g. "uint" or "unsigned int".
+    #[must_use]
+    pub fn parse(type_name: &str) -> Option<Self> {
+        let scalar = match type_name {
+            "bool" => Self::Bool,
+            "char" | "signed char" => Self::Char,
+            "uchar" | "unsigned char" => Self::UChar,
+            "short" | "signed short" => Self::Short,
+            "ushort" | "unsigned short" => Self::UShort,
+            "int" | "signed int" => Self::Int,
+            "uint" | "unsigned int" | "unsigned" => Self::UInt,
+            "long" | "signed long" => Self::Long,
+            "ulong" | "unsigned long" => Self::ULong,
+            "half" => Self::Half,
+            "float" => Self::Float,
+            "double" => Self::Double,
+            "size_t" => Self::SizeT,
+            "ptrdiff_t" => Self::PtrDiffT,
+            "intptr_t" => Self::IntPtrT,
+            "uintptr_t" => Self::UIntPtrT,
+            _ => return None,
+        };
+        Some(scalar)
+    }
+
+    /// The `OpenCL` C name of the type.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Bool => "bool",
+            Self::Char => "char",
+            Self::UChar => "uchar",
+            Self::Short => "short",
+            Self::UShort => "ushort",
+            Self::Int => "int",
+            Self::UInt => "uint",
+            Self::Long => "long",
+            Self::ULong => "ulong",
+            Self::Half => "half",
+            Self::Float => "float",
+            Self::Double => "double",
+            Self::SizeT => "size_t",
+            Self::PtrDiffT => "ptrdiff_t",
+            Self::IntPtrT => "intptr_t",
+            Self::UIntPtrT => "uintptr_t",
+        }
+    }
+
+    /// The size of the type in bytes, if it does not depend on the device.
+    /// `bool`, `size_t`, `ptrdiff_t`, `intptr_t` and `uintptr_t` are not valid
+    /// kernel argument types since their sizes are implementation defined.
+    #[must_use]
+    pub const fn size(self) -> Option<usize> {
+        match self {
+            Self::Char | Self::UChar => Some(1),
+            Self::Short | Self::UShort | Self::Half => Some(2),
+            Self::Int | Self::UInt | Self::Float => Some(4),
+            Self::Long | Self::ULong | Self::Double => Some(8),
+            Self::Bool | Self::SizeT | Self::PtrDiffT | Self::IntPtrT | Self::UIntPtrT => None,
+        }
+    }
+}
+
+impl fmt::Display for ScalarType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The type of a kernel argument, parsed from its `OpenCL` C type name.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ArgType {
+    /// A scalar type, e.g. `float`.
+    Scalar(ScalarType),
+    /// A vector type and its number of components, e.g. `float4`.
+    Vector(ScalarType, u32),
+    /// A pointer to the type, e.g. `float*`.
+    Pointer(Box<Self>),
+    /// An image type, e.g. `image2d_t`.
+    Image(String),
+    /// The `sampler_t` type.
+    Sampler,
+    /// The `queue_t` type.
+    Queue,
+    /// A pipe of the element type, e.g. `pipe int4`.
+    Pipe(Box<Self>),
+    /// Any other type, e.g. `void` or a `struct`.
+    Other(String),
+}
+
+impl ArgType {
+    /// Parse an `OpenCL` C type name, as returned by `CL_KERNEL_ARG_TYPE_NAME`.
+    ///
+    /// * `type_name` - the type name, e.g. "float4*".
+    /// * `pipe` - whether the argument is a pipe of `type_name` elements, i.e.
+    ///   its type qualifier contains `CL_KERNEL_ARG_TYPE_PIPE`.
+    #[must_use]
+    pub fn parse(type_name: &str, pipe: bool) -> Self {
+        let type_name = type_name.trim();
+        if pipe {
+            return Self::Pipe(Box::new(Self::parse(type_name, false)));
+        }
+        if let Some(pointee) = type_name.strip_suffix('*') {
+            return Self::Pointer(Box::new(Self::parse(pointee, false)));
+        }
+        if let Some(scalar) = ScalarType::parse(type_name) {
+            return Self::Scalar(scalar);
+        }
+        if type_name.starts_with("image") && type_name.ends_with("_t") {
+            return Self::Image(type_name.to_owned());
+        }
+        match type_name {
+            "sampler_t" => return Self::Sampler,
+            "queue_t" => return Self::Queue,
+            _ => {}
+        }
+
+        let base = type_name.trim_end_matches(|c: char| c.is_ascii_digit());
+        let count = type_name[base.len()..].parse::<u32>().ok();
+        match (ScalarType::parse(base), count) {
+            (Some(scalar), Some(count @ (2 | 3 | 4 | 8 | 16))) => Self::Vector(scalar, count),
+            _ => Self::Other(type_name.to_owned()),
+        }
+    }
+
+    /// The size of the argument value in bytes, if it does not depend on the
+    /// device or the memory object. A 3 component vector has the size of a
+    /// 4 component vector.
+    #[must_use]
+    pub fn size(&self) -> Option<usize> {
+        match self {
+            Self::Scalar(scalar) => scalar.size(),
+            Self::Vector(scalar, count) => scalar
+                .size()
+                .map(|size| size * if 3 == *count { 4 } else { *count as usize }),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub const fn is_pointer(&self) -> bool {
+        matches!(self, Self::Pointer(_))
+    }
+
+    #[must_use]
+    pub const fn is_image(&self) -> bool {
+        matches!(self, Self::Image(_))
+    }
+}
+
+impl fmt::Display for ArgType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Scalar(scalar) => write!(f, "{scalar}"),
+            Self::Vector(scalar, count) => write!(f, "{scalar}{count}"),
+            Self::Pointer(pointee) => write!(f, "{pointee}*"),
+            Self::Image(name) | Self::Other(name) => f.write_str(name),
+            Self::Sampler => f.write_str("sampler_t"),
+            Self::Queue => f.write_str("queue_t"),
+            Self::Pipe(element) => write!(f, "pipe {element}"),
+        }
+    }
+}
+
+/// The address space of a kernel argument.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AddressSpace {
+    Global,
+    Local,
+    Constant,
+    Private,
+}
+
+impl AddressSpace {
+    /// Convert a `CL_KERNEL_ARG_ADDRESS_QUALIFIER` value.
+    #[must_use]
+    pub const fn from_qualifier(qualifier: cl_kernel_arg_address_qualifier) -> Option<Self> {
+        match qualifier {
+            CL_KERNEL_ARG_ADDRESS_GLOBAL => Some(Self::Global),
+            CL_KERNEL_ARG_ADDRESS_LOCAL => Some(Self::Local),
+            CL_KERNEL_ARG_ADDRESS_CONSTANT => Some(Self::Constant),
+            CL_KERNEL_ARG_ADDRESS_PRIVATE => Some(Self::Private),
+            _ => None,
+        }
+    }
+
+    /// The `OpenCL` C address space qualifier.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Global => "global",
+            Self::Local => "local",
+            Self::Constant => "constant",
+            Self::Private => "private",
+        }
+    }
+}
+
+impl fmt::Display for AddressSpace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The access qualifier of an image or pipe kernel argument.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Access {
+    ReadOnly,
+    WriteOnly,
+    ReadWrite,
+}
+
+impl Access {
+    /// Convert a `CL_KERNEL_ARG_ACCESS_QUALIFIER` value.
+    /// returns None for `CL_KERNEL_ARG_ACCESS_NONE` or an unknown value.
+    #[must_use]
+    pub const fn from_qualifier(qualifier: cl_kernel_arg_access_qualifier) -> Option<Self> {
+        match qualifier {
+            CL_KERNEL_ARG_ACCESS_READ_ONLY => Some(Self::ReadOnly),
+            CL_KERNEL_ARG_ACCESS_WRITE_ONLY => Some(Self::WriteOnly),
+            CL_KERNEL_ARG_ACCESS_READ_WRITE => Some(Self::ReadWrite),
+            _ => None,
+        }
+    }
+
+    /// The `OpenCL` C access qualifier.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::ReadOnly => "read_only",
+            Self::WriteOnly => "write_only",
+            Self::ReadWrite => "read_write",
+        }
+    }
+}
+
+impl fmt::Display for Access {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A kernel argument.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KernelArgInfo {
+    /// The index of the argument, for `set_kernel_arg`.
+    pub index: cl_uint,
+    pub name: String,
+    /// The type name returned by `CL_KERNEL_ARG_TYPE_NAME`.
+    pub type_name: String,
+    pub arg_type: ArgType,
+    pub address_space: AddressSpace,
+    pub access: Option<Access>,
+    /// The `CL_KERNEL_ARG_TYPE_QUALIFIER` bitfield.
+    pub type_qualifier: cl_ulong,
+}
+
+impl KernelArgInfo {
+    /// Query the information of a kernel argument.
+    ///
+    /// * `kernel` - the `OpenCL` kernel.
+    /// * `index` - the kernel argument index.
+    ///
+    /// returns a Result containing the argument
+    /// or the error code from the `OpenCL` C API function.
+    pub fn from_kernel(kernel: impl AsKernel, index: cl_uint) -> Result<Self, cl_int> {
+        let kernel = kernel.as_raw();
+        let address_qualifier = cl_uint::from(get_kernel_arg_info(
+            kernel,
+            index,
+            CL_KERNEL_ARG_ADDRESS_QUALIFIER,
+        )?);
+        let access_qualifier = cl_uint::from(get_kernel_arg_info(
+            kernel,
+            index,
+            CL_KERNEL_ARG_ACCESS_QUALIFIER,
+        )?);
+        let type_qualifier = cl_ulong::from(get_kernel_arg_info(
+            kernel,
+            index,
+            CL_KERNEL_ARG_TYPE_QUALIFIER,
+        )?);
+        let type_name = String::from(get_kernel_arg_info(kernel, index, CL_KERNEL_ARG_TYPE_NAME)?);
+        let name = String::from(get_kernel_arg_info(kernel, index, CL_KERNEL_ARG_NAME)?);
+
+        let address_space = AddressSpace::from_qualifier(address_qualifier).ok_or_else(|| {
+            record_error!(
+                CL_INVALID_VALUE,
+                KernelArgInfo::from_kernel,
+                index,
+                address_qualifier
+            )
+        })?;
+        let access = if CL_KERNEL_ARG_ACCESS_NONE == access_qualifier {
+            None
+        } else {
+            Some(Access::from_qualifier(access_qualifier).ok_or_else(|| {
+                record_error!(
+                    CL_INVALID_VALUE,
+                    KernelArgInfo::from_kernel,
+                    index,
+                    access_qualifier
+                )
+            })?)
+        };
+        let pipe = 0 != type_qualifier & cl_ulong::from(CL_KERNEL_ARG_TYPE_PIPE);
+        let arg_type = ArgType::parse(&type_name, pipe);
+        Ok(Self {
+            index,
+            name,
+            type_name,
+            arg_type,
+            address_space,
+            access,
+            type_qualifier,
+        })
+    }
+
+    fn has_qualifier(&self, qualifier: cl_kernel_arg_type_qualifier) -> bool {
+        0 != self.type_qualifier & cl_ulong::from(qualifier)
+    }
+
+    #[must_use]
+    pub fn is_const(&self) -> bool {
+        self.has_qualifier(CL_KERNEL_ARG_TYPE_CONST)
+    }
+
+    #[must_use]
+    pub fn is_restrict(&self) -> bool {
+        self.has_qualifier(CL_KERNEL_ARG_TYPE_RESTRICT)
+    }
+
+    #[must_use]
+    pub fn is_volatile(&self) -> bool {
+        self.has_qualifier(CL_KERNEL_ARG_TYPE_VOLATILE)
+    }
+
+    #[must_use]
+    pub fn is_pipe(&self) -> bool {
+        self.has_qualifier(CL_KERNEL_ARG_TYPE_PIPE)
+    }
+}
+
+/// Renders the `OpenCL` C parameter declaration, e.g. `global const float* restrict x`.
+/// The type qualifiers of a pointer argument apply to the pointee type, except
+/// for `restrict` which applies to the pointer.
+impl fmt::Display for KernelArgInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(access) = self.access {
+            write!(f, "{access} ")?;
+        }
+        if self.arg_type.is_pointer() {
+            write!(f, "{} ", self.address_space)?;
+            // constant memory is always const
+            if self.is_const() && AddressSpace::Constant != self.address_space {
+                f.write_str("const ")?;
+            }
+            if self.is_volatile() {
+                f.write_str("volatile ")?;
+            }
+        }
+        write!(f, "{}", self.arg_type)?;
+        if self.is_restrict() {
+            f.write_str(" restrict")?;
+        }
+        if !self.name.is_empty() {
+            write!(f, " {}", self.name)?;
+        }
+        Ok(())
+    }
+}
+
+/// The signature of a kernel: its name, attributes and arguments.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KernelSignature {
+    pub name: String,
+    /// The attributes returned by `CL_KERNEL_ATTRIBUTES`, separated by spaces,
+    /// e.g. "`reqd_work_group_size(64, 1, 1) vec_type_hint(float4)`".
+    pub attributes: String,
+    pub args: Vec<KernelArgInfo>,
+}
+
+impl KernelSignature {
+    /// Query the signature of a kernel.
+    /// The kernel's program must have been built with the `-cl-kernel-arg-info`
+    /// option for the argument information to be available.
+    ///
+    /// * `kernel` - the `OpenCL` kernel.
+    ///
+    /// returns a Result containing the kernel signature
+    /// or the error code from the `OpenCL` C API function.
+    pub fn from_kernel(kernel: impl AsKernel) -> Result<Self, cl_int> {
+        let kernel = kernel.as_raw();
+        let name = String::from(get_kernel_info(kernel, CL_KERNEL_FUNCTION_NAME)?);
+        let attributes = String::from(get_kernel_info(kernel, CL_KERNEL_ATTRIBUTES)?);
+        let num_args = cl_uint::from(get_kernel_info(kernel, CL_KERNEL_NUM_ARGS)?);
+        let args = (0..num_args)
+            .map(|index| KernelArgInfo::from_kernel(kernel, index))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self {
+            name,
+            attributes,
+            args,
+        })
+    }
+
+    /// Find an argument by name.
+    #[must_use]
+    pub fn arg(&self, name: &str) -> Option<&KernelArgInfo> {
+        self.args.iter().find(|arg| name == arg.name)
+    }
+
+    /// Set the argument value for a specific argument of the kernel, checking
+    /// the value against the argument type in the signature.
+    ///
+    /// * `kernel` - the `OpenCL` kernel of the signature.
+    /// * `arg_index` - the kernel argument index.
+    /// * `value` - the argument value.
+    ///
+    /// returns an empty Result, `CL_INVALID_ARG_INDEX` or `CL_INVALID_ARG_VALUE`
+    /// if the value does not match the argument, or the error code from the
+    /// `OpenCL` C API function.
+    pub fn set_arg<T: KernelArg>(
+        &self,
+        kernel: impl AsKernel,
+        arg_index: cl_uint,
+        value: &T,
+    ) -> Result<(), cl_int> {
+        let arg = self.args.get(arg_index as usize).ok_or_else(|| {
+            record_error!(CL_INVALID_ARG_INDEX, KernelSignature::set_arg, arg_index)
+        })?;
+        check_arg(arg, value)?;
+        value.set_arg(kernel.as_raw(), arg_index)
+    }
+}
+
+/// Split `CL_KERNEL_ATTRIBUTES` at the whitespace outside of parentheses,
+/// since the attribute arguments may contain spaces.
+fn split_attributes(attributes: &str) -> Vec<&str> {
+    let mut split = Vec::new();
+    let mut depth = 0_usize;
+    let mut start = None;
+    for (index, c) in attributes.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            c if c.is_whitespace() && 0 == depth => {
+                if let Some(start) = start.take() {
+                    split.push(&attributes[start..index]);
+                }
+                continue;
+            }
+            _ => {}
+        }
+        start.get_or_insert(index);
+    }
+    if let Some(start) = start {
+        split.push(&attributes[start..]);
+    }
+    split
+}
+
+/// Renders the `OpenCL` C kernel prototype, e.g. `kernel void saxpy(global float* z, float a)`.
+impl fmt::Display for KernelSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("kernel ")?;
+        for attribute in split_attributes(&self.attributes) {
+            write!(f, "__attribute__(({attribute})) ")?;
+        }
+        write!(f, "void {}(", self.name)?;
+        for (i, arg) in self.args.iter().enumerate() {
+            if 0 < i {
+                f.write_str(", ")?;
+            }
+            write!(f, "{arg}")?;
+        }
+        f.write_str(")")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::{create_context, release_context};
+    use crate::device::{get_device_ids, CL_DEVICE_TYPE_GPU};
+    use crate::kernel::{create_kernel, release_kernel};
+    use crate::platform::get_platform_ids;
+    use crate::program::{build_program, create_program_with_source, release_program};
+    use opencl_sys::CL_KERNEL_ARG_INFO_NOT_AVAILABLE;
+    use std::ffi::CString;
+    use std::ptr;
+
+    #[test]
+    fn test_arg_type_parse() {
+        assert_eq!(
+            ArgType::Scalar(ScalarType::UInt),
+            ArgType::parse("unsigned int", false)
+        );
+        assert_eq!(
+            ArgType::Vector(ScalarType::Float, 4),
+            ArgType::parse("float4", false)
+        );
+        assert_eq!(Some(16), ArgType::parse("float4", false).size());
+        assert_eq!(Some(16), ArgType::parse("int3", false).size());
+        assert_eq!(
+            ArgType::Pointer(Box::new(ArgType::Pointer(Box::new(ArgType::Scalar(
+                ScalarType::Char
+            ))))),
+            ArgType::parse("char**", false)
+        );
+        assert_eq!(
+            ArgType::Image("image2d_array_t".to_owned()),
+            ArgType::parse("image2d_array_t", false)
+        );
+        assert_eq!(ArgType::Sampler, ArgType::parse("sampler_t", false));
+        assert_eq!(ArgType::Queue, ArgType::parse("queue_t", false));
+        assert_eq!(
+            ArgType::Pipe(Box::new(ArgType::Vector(ScalarType::Int, 2))),
+            ArgType::parse("int2", true)
+        );
+        assert_eq!(
+            ArgType::Other("float5".to_owned()),
+            ArgType::parse("float5", false)
+        );
+        assert_eq!(
+            ArgType::Pointer(Box::new(ArgType::Other("struct particle".to_owned()))),
+            ArgType::parse("struct particle*", false)
+        );
+
+        let arg = KernelArgInfo {
+            index: 0,
+            name: "p".to_owned(),
+            type_name: "int2".to_owned(),
+            arg_type: ArgType::parse("int2", true),
+            address_space: AddressSpace::Global,
+            access: Some(Access::ReadOnly),
+            type_qualifier: cl_ulong::from(CL_KERNEL_ARG_TYPE_PIPE),
+        };
+        assert_eq!("read_only pipe int2 p", arg.to_string());
+
+        // Attribute arguments may contain spaces
+        let signature = KernelSignature {
+            name: "f".to_owned(),
+            attributes: " reqd_work_group_size(64, 1, 1)  vec_type_hint(float4) ".to_owned(),
+            args: vec![arg],
+        };
+        assert_eq!(
+            "kernel __attribute__((reqd_work_group_size(64, 1, 1))) \
+             __attribute__((vec_type_hint(float4))) void f(read_only pipe int2 p)",
+            signature.to_string()
+        );
+    }
+
+    #[test]
+    fn test_kernel_signature() {
+        let platform_ids = get_platform_ids().unwrap();
+        let device_ids = get_device_ids(platform_ids[0], CL_DEVICE_TYPE_GPU).unwrap();
+        let context = create_context(&device_ids, ptr::null(), None, ptr::null_mut()).unwrap();
+
+        let source = r#"
+            kernel void blur(global float* restrict out,
+                global const float4* in,
+                local float* tmp,
+                constant int* weights,
+                read_only image2d_t img,
+                sampler_t smp,
+                uint n,
+                float4 scale)
+            {
+                out[get_global_id(0)] = in[get_global_id(0)].x;
+            }
+        "#;
+        let program = create_program_with_source(context, &[source]).unwrap();
+        let options = CString::new("-cl-kernel-arg-info").unwrap();
+        build_program(program, &device_ids, &options, None, ptr::null_mut()).unwrap();
+        let kernel = create_kernel(program, &CString::new("blur").unwrap()).unwrap();
+
+        let signature = KernelSignature::from_kernel(kernel).unwrap();
+        println!("{}", signature);
+        assert_eq!("blur", signature.name);
+        assert_eq!(8, signature.args.len());
+
+        let arg = signature.arg("in").unwrap();
+        assert_eq!(1, arg.index);
+        assert_eq!(AddressSpace::Global, arg.address_space);
+        assert_eq!(
+            ArgType::Pointer(Box::new(ArgType::Vector(ScalarType::Float, 4))),
+            arg.arg_type
+        );
+        assert!(arg.is_const());
+        assert!(signature.args[0].is_restrict());
+        assert_eq!(AddressSpace::Local, signature.args[2].address_space);
+        assert_eq!(Some(Access::ReadOnly), signature.args[4].access);
+        assert_eq!(ArgType::Sampler, signature.args[5].arg_type);
+        assert_eq!(
+            ArgType::Scalar(ScalarType::UInt),
+            signature.arg("n").unwrap().arg_type
+        );
+        assert_eq!(
+            "kernel void blur(global float* restrict out, global const float4* in, \
+             local float* tmp, constant int* weights, read_only image2d_t img, \
+             sampler_t smp, uint n, float4 scale)",
+            signature.to_string()
+        );
+        unsafe { release_kernel(kernel).unwrap() };
+        unsafe { release_program(program).unwrap() };
+
+        // Without -cl-kernel-arg-info
+        let program = create_program_with_source(context, &[source]).unwrap();
+        build_program(
+            program,
+            &device_ids,
+            &CString::default(),
+            None,
+            ptr::null_mut(),
+        )
+        .unwrap();
+        let kernel = create_kernel(program, &CString::new("blur").unwrap()).unwrap();
+        assert_eq!(
+            Err(CL_KERNEL_ARG_INFO_NOT_AVAILABLE),
+            KernelSignature::from_kernel(kernel)
+        );
+
+        unsafe {
+            release_kernel(kernel).unwrap();
+            release_program(program).unwrap();
+            release_context(context).unwrap();
+        }
+    }
+}
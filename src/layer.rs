@@ -14,10 +14,13 @@
 
 //! `OpenCL` layer extensions
 
+#[cfg(any(feature = "dynamic", feature = "mock"))]
+pub use super::ffi::{clGetLayerInfo, clInitLayer};
 pub use opencl_sys::cl_layer::*;
 pub use opencl_sys::*;
 
 #[allow(unused_imports)]
+use super::api_error::{clear_error, record_error};
 use libc::{c_void, size_t};
 #[allow(unused_imports)]
 use std::ptr;
@@ -25,6 +28,7 @@ use std::ptr;
 /// Query information about the `OpenCL` layer.
 /// Calls `clGetLayerInfo`.
 pub fn get_layer_data(param_name: cl_layer_info) -> Result<Vec<u8>, cl_int> {
+    clear_error();
     let mut size: size_t = 0;
     let status = unsafe { clGetLayerInfo(param_name, 0, ptr::null_mut(), &mut size) };
     if CL_SUCCESS == status {
@@ -40,10 +44,10 @@ pub fn get_layer_data(param_name: cl_layer_info) -> Result<Vec<u8>, cl_int> {
         if CL_SUCCESS == status {
             Ok(data)
         } else {
-            Err(status)
+            Err(record_error!(status, clGetLayerInfo, param_name))
         }
     } else {
-        Err(status)
+        Err(record_error!(status, clGetLayerInfo, param_name))
     }
 }
 
@@ -57,6 +61,7 @@ pub fn get_layer_data(param_name: cl_layer_info) -> Result<Vec<u8>, cl_int> {
 pub unsafe fn init_layer(
     target_dispatch: &[cl_icd_dispatch],
 ) -> Result<&[cl_icd_dispatch], cl_int> {
+    clear_error();
     let mut num_entries_ret: cl_uint = 0;
     let mut layer_dispatch_ret: *const cl_icd_dispatch = ptr::null();
     let status = clInitLayer(
@@ -69,6 +74,6 @@ pub unsafe fn init_layer(
         let slice = std::slice::from_raw_parts(layer_dispatch_ret, num_entries_ret as usize);
         Ok(slice)
     } else {
-        Err(status)
+        Err(record_error!(status, clInitLayer))
     }
 }
@@ -47,6 +47,17 @@
 //!
 //! The default features are "`CL_VERSION_1_1`" and "`CL_VERSION_1_2`".
 //!
+//! The "dynamic" feature loads the `OpenCL` API and extension functions
+//! from the `OpenCL` ICD loader library at run time, instead of calling the
+//! functions linked from `opencl-sys`. If the library or a function is not
+//! available, the adapter functions return `CL_LIBRARY_NOT_LOADED` or
+//! `CL_SYMBOL_NOT_AVAILABLE` respectively, so an application can detect that
+//! `OpenCL` is not available and fall back to another implementation.
+//!
+//! The "mock" feature calls an in-process mock `OpenCL` implementation instead,
+//! so that code using this crate can be tested without `OpenCL` hardware or
+//! drivers, see the `mock` module. It takes precedence over the "dynamic" feature.
+//!
 //! Rust deprecation warnings are given for `OpenCL` API functions that are
 //! deprecated by an enabled `OpenCL` version e.g., `clCreateCommandQueue` is
 //! deprecated whenever "`CL_VERSION_2_0`" is enabled.
@@ -56,17 +67,74 @@
 //! They contain Rust adapter functions for the `OpenCL` API C functions defined
 //! in those sections with their associated types and constants. The exceptions are:
 //!
+//! * `dynamic` - contains the `OpenCL` API functions that are loaded from the
+//!   `OpenCL` library at run time when the "dynamic" feature is enabled.
+//! * `mock` - contains a mock `OpenCL` implementation of the `OpenCL` API
+//!   functions for testing when the "mock" feature is enabled.
+//! * [`api_error`] - contains an error type (`ApiError`) with the `OpenCL` C API
+//!   function, arguments and build logs of the last error returned by an adapter function.
+//! * [`autotune`] - contains an autotuner (`Autotuner`) that times the local
+//!   sizes and `-D` tunables of a kernel, and a table (`TuningTable`) of the
+//!   fastest settings per device and driver, which kernel launches consult.
+//! * [`buffer`] - contains a typed buffer (`Buffer<T>`) of `Pod` elements with
+//!   bounds checked reads, writes, fills and copies in element units.
+//! * [`build_log`] - contains a parser (`parse_build_log`) for the diagnostics
+//!   in program build logs, which renders them like rustc diagnostics.
+//! * [`build_options`] - contains a builder (`BuildOptions`) for the program
+//!   build, compile and link options, which checks `-cl-std` against the devices.
+//! * [`callbacks`] - contains safe variants of the adapter functions that
+//!   register `OpenCL` callback functions, which take Rust closures instead.
+//! * [`device_capabilities`] - contains a struct (`DeviceCapabilities`) holding all
+//!   of the device information parameters and a function (`get_device_capabilities`)
+//!   to query them from a device.
+//! * [`device_info`] - contains marker types for the `OpenCL` device information
+//!   parameters and a function (`get_device_param`) to query a parameter as its Rust type.
 //! * [`error_codes`] - contains the `OpenCL` API error codes from cl.h and a function
-//! (`error_text`) to convert an error code to it's enum name from cl.h.
+//!   (`error_text`) to convert an error code to it's enum name from cl.h.
+//! * [`event_future`] - contains a future (`EventFuture`) that resolves when an
+//!   `OpenCL` event completes, for use with any async runtime.
 //! * [`info_type`] - contains a Rust enum (`InfoType`) to hold the `OpenCL` types
-//! that can be returned from `OpenCL` "Info" functions, e.g. clGetPlatformInfo,
-//! clGetDeviceInfo, clGetProgramInfo, etc.
+//!   that can be returned from `OpenCL` "Info" functions, e.g. clGetPlatformInfo,
+//!   clGetDeviceInfo, clGetProgramInfo, etc.
+//! * [`kernel_arg`] - contains a trait (`KernelArg`) for the values that can be
+//!   set as kernel arguments and a safe `set_arg`, which can check the values
+//!   against the reflected argument types.
+//! * [`kernel_launch`] - contains a builder (`KernelLaunch`) for ND-range kernel
+//!   launches that checks the work sizes against the device and kernel limits and
+//!   chooses the local size, padding the global size if requested.
+//! * [`kernel_signature`] - contains a reflection (`KernelSignature`) of a
+//!   kernel's arguments with parsed types, address spaces and names, which renders
+//!   back to an `OpenCL` C kernel prototype.
 //! * [`macros`] - contains Rust macros to call the `OpenCL` "Info" functions and
-//! return the appropriate `InfoType` in a Rust Result.
+//!   return the appropriate `InfoType` in a Rust Result.
+//! * [`mapped_memory`] - contains guards (`MappedBuffer` and `MappedImage`) for
+//!   mapped buffer and image regions, which wait for the map, give slice access
+//!   according to the map flags and unmap the region when they are dropped.
+//! * [`owned`] - contains owned `OpenCL` object handles that release the objects
+//!   when they are dropped. The adapter functions accept either raw handles or
+//!   references to the owned handles.
+//! * [`profiler`] - contains a profiler (`Profiler`) that collects a timeline of
+//!   labelled commands from profiling enabled command-queues and writes it as
+//!   Chrome trace JSON.
+//! * [`program_cache`] - contains an on-disk cache (`ProgramCache`) of program
+//!   binaries keyed by the program, build options, device, driver and platform.
+//! * [`program_linker`] - contains a linker (`ProgramLinker`) that compiles
+//!   source, IL and binary modules separately and links them into libraries and
+//!   executables, reporting the stage and module that failed.
+//! * [`rect_transfer`] - contains a description (`RectTransfer`) of a
+//!   rectangular transfer between sub-volumes (`Region3D`) of buffers and host
+//!   arrays, which defaults the pitches and checks the footprints.
+//! * [`source_bundle`] - contains a bundle (`SourceBundle`) of an `OpenCL` C source
+//!   file and its `#include` files, which can be loaded from include paths or
+//!   embedded with the `include_source_bundle` macro.
+//! * [`spirv`] - contains a SPIR-V module reflection (`SpirvModule`) of the
+//!   specialization constants, with typed setters that check the values, and a
+//!   check of the module's version, capabilities and extensions against a device.
 //!
 //! It is vital to call the correct `InfoType` method type when decoding the
 //! result of "Info" functions, since the methods will panic if called with the
-//! wrong type, see [`info_type`].
+//! wrong type, see [`info_type`]. The `try_*` methods and `TryFrom<&InfoType>`
+//! traits return an `InfoTypeError` instead of panicking.
 //!
 //! # Use
 //!
@@ -81,24 +149,60 @@
 
 extern crate opencl_sys;
 
+#[cfg(not(any(feature = "dynamic", feature = "mock")))]
+use opencl_sys as ffi;
+
+#[cfg(all(feature = "dynamic", not(feature = "mock")))]
+use dynamic as ffi;
+
+#[cfg(feature = "mock")]
+use mock as ffi;
+
+pub mod api_error;
+pub mod autotune;
+pub mod buffer;
+pub mod build_log;
+pub mod build_options;
+pub mod callbacks;
 pub mod command_queue;
 pub mod context;
 pub mod d3d10;
 pub mod d3d11;
 pub mod device;
+pub mod device_capabilities;
+pub mod device_info;
 pub mod dx9_media_sharing;
+#[cfg(feature = "dynamic")]
+pub mod dynamic;
 pub mod egl;
 pub mod error_codes;
 pub mod event;
+pub mod event_future;
 pub mod ext;
 pub mod gl;
 pub mod info_type;
 pub mod kernel;
+#[cfg(feature = "CL_VERSION_1_2")]
+pub mod kernel_arg;
+pub mod kernel_launch;
+#[cfg(feature = "CL_VERSION_1_2")]
+pub mod kernel_signature;
 #[cfg(feature = "cl_loader_layers")]
 pub mod layer;
 pub mod macros;
+pub mod mapped_memory;
 pub mod memory;
+#[cfg(feature = "mock")]
+pub mod mock;
+pub mod owned;
 pub mod platform;
+pub mod profiler;
 pub mod program;
+pub mod program_cache;
+#[cfg(feature = "CL_VERSION_1_2")]
+pub mod program_linker;
+pub mod rect_transfer;
 pub mod sampler;
+pub mod source_bundle;
+pub mod spirv;
 pub mod types;
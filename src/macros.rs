@@ -22,7 +22,11 @@ macro_rules! api_info_size {
             let mut size: size_t = 0;
             let status = unsafe { $api(object, param_name, 0, ptr::null_mut(), &mut size) };
             if CL_SUCCESS != status {
-                Err(status)
+                Err($crate::api_error::record(
+                    status,
+                    stringify!($api),
+                    &[("param_name", &param_name)],
+                ))
             } else {
                 Ok(size)
             }
@@ -48,7 +52,11 @@ macro_rules! api_info_value {
                 )
             };
             if CL_SUCCESS != status {
-                Err(status)
+                Err($crate::api_error::record(
+                    status,
+                    stringify!($api),
+                    &[("param_name", &param_name)],
+                ))
             } else {
                 Ok(data)
             }
@@ -79,7 +87,11 @@ macro_rules! api_info_vector {
                     )
                 };
                 if CL_SUCCESS != status {
-                    Err(status)
+                    Err($crate::api_error::record(
+                        status,
+                        stringify!($api),
+                        &[("param_name", &param_name)],
+                    ))
                 } else {
                     Ok(data)
                 }
@@ -98,7 +110,11 @@ macro_rules! api2_info_size {
             let mut size: size_t = 0;
             let status = unsafe { $api(object, idx, param_name, 0, ptr::null_mut(), &mut size) };
             if CL_SUCCESS != status {
-                Err(status)
+                Err($crate::api_error::record(
+                    status,
+                    stringify!($api),
+                    &[("param_name", &param_name)],
+                ))
             } else {
                 Ok(size)
             }
@@ -125,7 +141,11 @@ macro_rules! api2_info_value {
                 )
             };
             if CL_SUCCESS != status {
-                Err(status)
+                Err($crate::api_error::record(
+                    status,
+                    stringify!($api),
+                    &[("param_name", &param_name)],
+                ))
             } else {
                 Ok(data)
             }
@@ -158,7 +178,11 @@ macro_rules! api2_info_vector {
                     )
                 };
                 if CL_SUCCESS != status {
-                    Err(status)
+                    Err($crate::api_error::record(
+                        status,
+                        stringify!($api),
+                        &[("param_name", &param_name)],
+                    ))
                 } else {
                     Ok(data)
                 }
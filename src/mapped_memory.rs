@@ -0,0 +1,678 @@
+// Copyright (c) 2024 Via Technology Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Guards for mapped buffer and image memory.
+//!
+//! [`MappedBuffer`] and [`MappedImage`] map a region of a memory object with
+//! `enqueue_map_buffer` or `enqueue_map_image`, wait for the map command to
+//! complete, give access to the mapped host memory and enqueue
+//! `enqueue_unmap_mem_object` when they are dropped, so the mapped pointer
+//! cannot be used before the map completes or after the unmap.
+//!
+//! The map flags are a type parameter, which determines the access:
+//!
+//! * [`MapRead`] - `CL_MAP_READ`, derefs to `&[T]`.
+//! * [`MapWrite`] - `CL_MAP_WRITE`, derefs to `&[T]` and `&mut [T]`.
+//! * [`MapReadWrite`] - `CL_MAP_READ | CL_MAP_WRITE`, derefs to `&[T]` and `&mut [T]`.
+//! * [`MapWriteInvalidate`] - `CL_MAP_WRITE_INVALIDATE_REGION`, derefs to
+//!   `&mut [MaybeUninit<T>]`, since the contents of the region are undefined.
+//!
+//! The accesses that write, i.e. [`WriteAccess`], mutably borrow the buffer or
+//! image for the lifetime of the guard, so the mapped slices cannot alias and
+//! the memory object cannot be read, written or mapped again through it, e.g.:
+//!
+//! ```no_run
+//! use cl3::buffer::Buffer;
+//! use cl3::mapped_memory::MapWrite;
+//! use cl3::types::{cl_command_queue, cl_float, cl_int};
+//!
+//! fn example(queue: cl_command_queue, buffer: &mut Buffer<cl_float>) -> Result<cl_float, cl_int> {
+//!     {
+//!         let mut mapped = buffer.map_mut::<MapWrite>(queue, 0, buffer.len())?;
+//!         mapped.fill(1.0);
+//!     } // unmapped here
+//!     let mapped = buffer.map(queue, 0, buffer.len())?;
+//!     Ok(mapped.iter().sum())
+//! }
+//! ```
+//!
+//! The borrow only covers the handle that was mapped: clones of the buffer or
+//! image, and kernels with the memory object as an argument, share the same
+//! `cl_mem`, so the commands enqueued with them while a region is mapped must
+//! not access it.
+
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
+
+use super::api_error::{preserve_error, record_error};
+use super::buffer::Buffer;
+use super::command_queue::{enqueue_map_buffer, enqueue_map_image, enqueue_unmap_mem_object};
+use super::event::wait_for_events;
+use super::memory::{
+    get_image_info, get_mem_object_info, CL_IMAGE_ELEMENT_SIZE, CL_MAP_READ, CL_MAP_WRITE,
+    CL_MAP_WRITE_INVALIDATE_REGION, CL_MEM_OBJECT_IMAGE1D_ARRAY, CL_MEM_TYPE,
+};
+use super::owned::{AsCommandQueue, CommandQueue, Event, Mem};
+use super::types::Pod;
+use libc::size_t;
+use opencl_sys::{cl_int, cl_map_flags, cl_mem, CL_INVALID_VALUE, CL_NON_BLOCKING};
+use std::fmt;
+use std::marker::PhantomData;
+use std::mem::{self, ManuallyDrop, MaybeUninit};
+use std::ops::{Deref, DerefMut};
+use std::ptr;
+use std::slice;
+
+/// The access of a mapped region, i.e. its map flags.
+pub trait MapAccess {
+    /// The `cl_map_flags` of the access.
+    const FLAGS: cl_map_flags;
+}
+
+/// An access that writes to the mapped region, which mutably borrows the
+/// memory object.
+pub trait WriteAccess: MapAccess {}
+
+/// `CL_MAP_READ`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MapRead;
+
+/// `CL_MAP_WRITE`, the mapped region contains the contents of the memory object.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MapWrite;
+
+/// `CL_MAP_READ | CL_MAP_WRITE`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MapReadWrite;
+
+/// `CL_MAP_WRITE_INVALIDATE_REGION`, the contents of the mapped region are undefined.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MapWriteInvalidate;
+
+impl MapAccess for MapRead {
+    const FLAGS: cl_map_flags = CL_MAP_READ;
+}
+
+impl MapAccess for MapWrite {
+    const FLAGS: cl_map_flags = CL_MAP_WRITE;
+}
+
+impl MapAccess for MapReadWrite {
+    const FLAGS: cl_map_flags = CL_MAP_READ | CL_MAP_WRITE;
+}
+
+impl MapAccess for MapWriteInvalidate {
+    const FLAGS: cl_map_flags = CL_MAP_WRITE_INVALIDATE_REGION;
+}
+
+/// A mapped region of a memory object, which is unmapped when it is dropped.
+struct Mapping {
+    command_queue: CommandQueue,
+    memobj: cl_mem,
+    ptr: *mut u8,
+}
+
+impl Mapping {
+    /// Wait for the map command to complete, the region is unmapped if the
+    /// wait fails.
+    fn new(
+        command_queue: CommandQueue,
+        memobj: cl_mem,
+        ptr: *mut u8,
+        event: &Event,
+    ) -> Result<Self, cl_int> {
+        let mapping = Self {
+            command_queue,
+            memobj,
+            ptr,
+        };
+        wait_for_events(&[event.as_raw()])?;
+        Ok(mapping)
+    }
+
+    fn unmap(self) -> Result<Event, cl_int> {
+        let mapping = ManuallyDrop::new(self);
+        let command_queue = unsafe { ptr::read(&mapping.command_queue) };
+        let event = unsafe {
+            enqueue_unmap_mem_object(
+                &command_queue,
+                mapping.memobj,
+                mapping.ptr.cast(),
+                0,
+                ptr::null(),
+            )?
+        };
+        Ok(unsafe { Event::from_raw(event) })
+    }
+}
+
+impl Drop for Mapping {
+    fn drop(&mut self) {
+        // There is nothing useful to do with an error in drop, and an
+        // error that is being returned keeps its recorded context
+        preserve_error(|| {
+            if let Ok(event) = unsafe {
+                enqueue_unmap_mem_object(
+                    &self.command_queue,
+                    self.memobj,
+                    self.ptr.cast(),
+                    0,
+                    ptr::null(),
+                )
+            } {
+                drop(unsafe { Event::from_raw(event) });
+            }
+        });
+    }
+}
+
+/// A guard for a mapped region of a [`Buffer`].
+pub struct MappedBuffer<'a, T: Pod, A: MapAccess> {
+    mapping: Mapping,
+    len: usize,
+    buffer: PhantomData<&'a Buffer<T>>,
+    access: PhantomData<A>,
+}
+
+impl<'a, T: Pod> MappedBuffer<'a, T, MapRead> {
+    /// Map `len` elements of a buffer for reading, starting at element `offset`.
+    /// Calls `enqueue_map_buffer` and waits for the map to complete.
+    ///
+    /// * `command_queue` - the command-queue to map and unmap the buffer on.
+    /// * `buffer` - the buffer.
+    /// * `offset` - the first element of the region.
+    /// * `len` - the number of elements in the region, it must not be zero.
+    ///
+    /// returns a Result containing the guard, or `CL_INVALID_VALUE` if the
+    /// region is outside of the buffer or the error code from the `OpenCL` C
+    /// API function.
+    pub fn new(
+        command_queue: impl AsCommandQueue,
+        buffer: &'a Buffer<T>,
+        offset: usize,
+        len: usize,
+    ) -> Result<Self, cl_int> {
+        Self::map(command_queue, buffer, offset, len)
+    }
+}
+
+impl<'a, T: Pod, A: WriteAccess> MappedBuffer<'a, T, A> {
+    /// Map `len` elements of a buffer for writing, starting at element `offset`,
+    /// see [`MappedBuffer::new`].
+    /// The buffer is mutably borrowed until the guard is dropped.
+    pub fn new_mut(
+        command_queue: impl AsCommandQueue,
+        buffer: &'a mut Buffer<T>,
+        offset: usize,
+        len: usize,
+    ) -> Result<Self, cl_int> {
+        Self::map(command_queue, buffer, offset, len)
+    }
+}
+
+impl<T: Pod, A: MapAccess> MappedBuffer<'_, T, A> {
+    fn map(
+        command_queue: impl AsCommandQueue,
+        buffer: &Buffer<T>,
+        offset: usize,
+        len: usize,
+    ) -> Result<Self, cl_int> {
+        match offset.checked_add(len) {
+            Some(end) if end <= buffer.len() => {}
+            _ => {
+                return Err(record_error!(
+                    CL_INVALID_VALUE,
+                    MappedBuffer::map,
+                    offset,
+                    len
+                ))
+            }
+        }
+        let command_queue = unsafe { CommandQueue::retain_raw(command_queue.as_raw())? };
+        let element_size = mem::size_of::<T>();
+        let mut ptr: cl_mem = ptr::null_mut();
+        let event = unsafe {
+            Event::from_raw(enqueue_map_buffer(
+                &command_queue,
+                buffer.as_mem(),
+                CL_NON_BLOCKING,
+                A::FLAGS,
+                offset * element_size,
+                len * element_size,
+                &mut ptr,
+                0,
+                ptr::null(),
+            )?)
+        };
+        let mapping = Mapping::new(command_queue, buffer.as_mem().as_raw(), ptr.cast(), &event)?;
+        // The slices require an aligned pointer, dropping the mapping unmaps it
+        if 0 != mapping.ptr.align_offset(mem::align_of::<T>()) {
+            return Err(record_error!(CL_INVALID_VALUE, MappedBuffer::map, ptr));
+        }
+        Ok(Self {
+            mapping,
+            len,
+            buffer: PhantomData,
+            access: PhantomData,
+        })
+    }
+
+    /// The number of mapped elements.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        0 == self.len
+    }
+
+    /// Unmap the region.
+    /// Calls `enqueue_unmap_mem_object`.
+    ///
+    /// returns a Result containing the event of the unmap or the error code
+    /// from the `OpenCL` C API function.
+    pub fn unmap(self) -> Result<Event, cl_int> {
+        self.mapping.unmap()
+    }
+}
+
+impl<T: Pod, A: MapAccess> fmt::Debug for MappedBuffer<'_, T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MappedBuffer")
+            .field("memobj", &self.mapping.memobj)
+            .field("ptr", &self.mapping.ptr)
+            .field("len", &self.len)
+            .field("flags", &A::FLAGS)
+            .finish()
+    }
+}
+
+/// A guard for a mapped region of an image.
+pub struct MappedImage<'a, A: MapAccess> {
+    mapping: Mapping,
+    region: [size_t; 3],
+    element_size: size_t,
+    row_pitch: size_t,
+    slice_pitch: size_t,
+    /// The number of bytes between the starts of consecutive rows of the
+    /// region, the slice pitch for the images of a 1D image array.
+    row_stride: size_t,
+    image: PhantomData<&'a Mem>,
+    access: PhantomData<A>,
+}
+
+impl<'a> MappedImage<'a, MapRead> {
+    /// Map a region of an image for reading.
+    /// Calls `enqueue_map_image` and waits for the map to complete.
+    ///
+    /// * `command_queue` - the command-queue to map and unmap the image on.
+    /// * `image` - the image.
+    /// * `origin` - the (x, y, z) offset in pixels, or (x, array index) for a
+    ///   1D image array and (x, y, array index) for a 2D image array.
+    /// * `region` - the (width, height, depth) in pixels, or (width, arrays)
+    ///   for a 1D image array and (width, height, arrays) for a 2D image array.
+    ///   The unused dimensions must be 1.
+    ///
+    /// returns a Result containing the guard or the error code from the
+    /// `OpenCL` C API function.
+    pub fn new(
+        command_queue: impl AsCommandQueue,
+        image: &'a Mem,
+        origin: [size_t; 3],
+        region: [size_t; 3],
+    ) -> Result<Self, cl_int> {
+        Self::map(command_queue, image, origin, region)
+    }
+}
+
+impl<'a, A: WriteAccess> MappedImage<'a, A> {
+    /// Map a region of an image for writing, see [`MappedImage::new`].
+    /// The image is mutably borrowed until the guard is dropped.
+    pub fn new_mut(
+        command_queue: impl AsCommandQueue,
+        image: &'a mut Mem,
+        origin: [size_t; 3],
+        region: [size_t; 3],
+    ) -> Result<Self, cl_int> {
+        Self::map(command_queue, image, origin, region)
+    }
+}
+
+impl<A: MapAccess> MappedImage<'_, A> {
+    fn map(
+        command_queue: impl AsCommandQueue,
+        image: &Mem,
+        origin: [size_t; 3],
+        region: [size_t; 3],
+    ) -> Result<Self, cl_int> {
+        if region.contains(&0) {
+            return Err(record_error!(CL_INVALID_VALUE, MappedImage::map, region));
+        }
+        let element_size = get_image_info(image, CL_IMAGE_ELEMENT_SIZE)?.to_size();
+        let image_type = get_mem_object_info(image, CL_MEM_TYPE)?.to_uint();
+        let command_queue = unsafe { CommandQueue::retain_raw(command_queue.as_raw())? };
+        let mut row_pitch: size_t = 0;
+        let mut slice_pitch: size_t = 0;
+        let mut ptr: cl_mem = ptr::null_mut();
+        let event = unsafe {
+            Event::from_raw(enqueue_map_image(
+                &command_queue,
+                image,
+                CL_NON_BLOCKING,
+                A::FLAGS,
+                origin.as_ptr(),
+                region.as_ptr(),
+                &mut row_pitch,
+                &mut slice_pitch,
+                &mut ptr,
+                0,
+                ptr::null(),
+            )?)
+        };
+        // The images of a 1D image array are separated by the slice pitch
+        let row_stride = if CL_MEM_OBJECT_IMAGE1D_ARRAY == image_type {
+            slice_pitch
+        } else {
+            row_pitch
+        };
+        Ok(Self {
+            mapping: Mapping::new(command_queue, image.as_raw(), ptr.cast(), &event)?,
+            region,
+            element_size,
+            row_pitch,
+            slice_pitch,
+            row_stride,
+            image: PhantomData,
+            access: PhantomData,
+        })
+    }
+
+    /// The (width, height, depth) of the mapped region in pixels.
+    #[must_use]
+    pub const fn region(&self) -> [size_t; 3] {
+        self.region
+    }
+
+    /// The size of a pixel in bytes.
+    #[must_use]
+    pub const fn element_size(&self) -> size_t {
+        self.element_size
+    }
+
+    /// The number of bytes between the starts of consecutive rows.
+    #[must_use]
+    pub const fn row_pitch(&self) -> size_t {
+        self.row_pitch
+    }
+
+    /// The number of bytes between the starts of consecutive 2D slices or the
+    /// images of a 1D image array, zero for 1D and 2D images.
+    #[must_use]
+    pub const fn slice_pitch(&self) -> size_t {
+        self.slice_pitch
+    }
+
+    /// The number of mapped bytes, from the first pixel of the region to the
+    /// last pixel of its last row.
+    #[must_use]
+    pub const fn size(&self) -> size_t {
+        (self.region[2] - 1) * self.slice_pitch
+            + (self.region[1] - 1) * self.row_stride
+            + self.region[0] * self.element_size
+    }
+
+    /// The byte offset of row `y` of slice `z` in the mapped region.
+    const fn row_offset(&self, y: size_t, z: size_t) -> Option<size_t> {
+        if y < self.region[1] && z < self.region[2] {
+            Some(z * self.slice_pitch + y * self.row_stride)
+        } else {
+            None
+        }
+    }
+
+    /// The byte length of a row of the mapped region.
+    const fn row_size(&self) -> size_t {
+        self.region[0] * self.element_size
+    }
+
+    /// Unmap the region.
+    /// Calls `enqueue_unmap_mem_object`.
+    ///
+    /// returns a Result containing the event of the unmap or the error code
+    /// from the `OpenCL` C API function.
+    pub fn unmap(self) -> Result<Event, cl_int> {
+        self.mapping.unmap()
+    }
+}
+
+impl<A: MapAccess> fmt::Debug for MappedImage<'_, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MappedImage")
+            .field("memobj", &self.mapping.memobj)
+            .field("ptr", &self.mapping.ptr)
+            .field("region", &self.region)
+            .field("element_size", &self.element_size)
+            .field("row_pitch", &self.row_pitch)
+            .field("slice_pitch", &self.slice_pitch)
+            .field("flags", &A::FLAGS)
+            .finish()
+    }
+}
+
+macro_rules! map_deref {
+    ($($access:ty => $buffer_element:ty, $image_element:ty;)*) => {
+        $(
+            impl<T: Pod> Deref for MappedBuffer<'_, T, $access> {
+                type Target = [$buffer_element];
+
+                fn deref(&self) -> &Self::Target {
+                    // The map has completed and the aligned region holds len elements
+                    unsafe { slice::from_raw_parts(self.mapping.ptr.cast(), self.len) }
+                }
+            }
+
+            impl Deref for MappedImage<'_, $access> {
+                type Target = [$image_element];
+
+                fn deref(&self) -> &Self::Target {
+                    // The map has completed and the region holds size() bytes
+                    unsafe { slice::from_raw_parts(self.mapping.ptr.cast(), self.size()) }
+                }
+            }
+
+            impl MappedImage<'_, $access> {
+                /// The bytes of the pixels of row `y` of slice `z` of the
+                /// mapped region, or of image `y` of a 1D image array, None if
+                /// the row is outside of the region.
+                #[must_use]
+                pub fn row(&self, y: size_t, z: size_t) -> Option<&[$image_element]> {
+                    let offset = self.row_offset(y, z)?;
+                    Some(&self[offset..offset + self.row_size()])
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! map_deref_mut {
+    ($($access:ty),* $(,)?) => {
+        $(
+            impl WriteAccess for $access {}
+
+            impl<T: Pod> DerefMut for MappedBuffer<'_, T, $access> {
+                fn deref_mut(&mut self) -> &mut Self::Target {
+                    unsafe { slice::from_raw_parts_mut(self.mapping.ptr.cast(), self.len) }
+                }
+            }
+
+            impl DerefMut for MappedImage<'_, $access> {
+                fn deref_mut(&mut self) -> &mut Self::Target {
+                    unsafe { slice::from_raw_parts_mut(self.mapping.ptr.cast(), self.size()) }
+                }
+            }
+
+            impl MappedImage<'_, $access> {
+                /// The mutable bytes of the pixels of row `y` of slice `z` of
+                /// the mapped region, or of image `y` of a 1D image array, None
+                /// if the row is outside of the region.
+                pub fn row_mut(&mut self, y: size_t, z: size_t) -> Option<&mut <Self as Deref>::Target> {
+                    let offset = self.row_offset(y, z)?;
+                    let end = offset + self.row_size();
+                    Some(&mut self[offset..end])
+                }
+            }
+        )*
+    };
+}
+
+map_deref! {
+    MapRead => T, u8;
+    MapWrite => T, u8;
+    MapReadWrite => T, u8;
+    MapWriteInvalidate => MaybeUninit<T>, MaybeUninit<u8>;
+}
+
+map_deref_mut!(MapWrite, MapReadWrite, MapWriteInvalidate);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[allow(deprecated)]
+    use crate::command_queue::{create_command_queue, finish, release_command_queue};
+    use crate::context::{create_context, release_context};
+    use crate::device::{get_device_ids, CL_DEVICE_TYPE_GPU};
+    use crate::memory::{
+        create_image, CL_MEM_OBJECT_IMAGE2D, CL_MEM_READ_WRITE, CL_RGBA, CL_UNSIGNED_INT8,
+    };
+    use crate::platform::get_platform_ids;
+    use opencl_sys::{cl_image_desc, cl_image_format, cl_uint};
+
+    #[test]
+    fn test_mapped_memory() {
+        let platform_ids = get_platform_ids().unwrap();
+        let device_ids = get_device_ids(platform_ids[0], CL_DEVICE_TYPE_GPU).unwrap();
+        let context = create_context(&device_ids, ptr::null(), None, ptr::null_mut()).unwrap();
+        #[allow(deprecated)]
+        let queue = unsafe { create_command_queue(context, device_ids[0], 0).unwrap() };
+
+        let data: Vec<cl_uint> = (0..16).collect();
+        let mut buffer = Buffer::create_with_data(context, CL_MEM_READ_WRITE, &data).unwrap();
+        {
+            let mapped = buffer.map(queue, 4, 4).unwrap();
+            let other = MappedBuffer::new(queue, &buffer, 0, 4).unwrap();
+            println!("{:?}", mapped);
+            assert_eq!(&[4, 5, 6, 7], &mapped[..]);
+            assert_eq!(&[0, 1, 2, 3], &other[..]);
+        }
+        {
+            let mut mapped = buffer.map_mut::<MapReadWrite>(queue, 0, 16).unwrap();
+            mapped[0] = 100;
+            mapped[15] += 100;
+        }
+        let mut mapped = buffer.map_mut::<MapWriteInvalidate>(queue, 8, 2).unwrap();
+        assert_eq!(2, mapped.len());
+        for (i, element) in mapped.iter_mut().enumerate() {
+            element.write(200 + i as cl_uint);
+        }
+        mapped.unmap().unwrap();
+        finish(queue).unwrap();
+
+        let data = buffer.read_to_vec(queue).unwrap();
+        assert_eq!(100, data[0]);
+        assert_eq!([200, 201], data[8..10]);
+        assert_eq!(115, data[15]);
+        assert_eq!(
+            CL_INVALID_VALUE,
+            buffer.map_mut::<MapWrite>(queue, 15, 2).unwrap_err()
+        );
+
+        // A 2D image of 4 by 3 RGBA pixels
+        let format = cl_image_format {
+            image_channel_order: CL_RGBA,
+            image_channel_data_type: CL_UNSIGNED_INT8,
+        };
+        let desc = cl_image_desc {
+            image_type: CL_MEM_OBJECT_IMAGE2D,
+            image_width: 4,
+            image_height: 3,
+            image_depth: 1,
+            image_array_size: 1,
+            image_row_pitch: 0,
+            image_slice_pitch: 0,
+            num_mip_levels: 0,
+            num_samples: 0,
+            buffer: ptr::null_mut(),
+        };
+        let mut image = unsafe {
+            Mem::from_raw(
+                create_image(context, CL_MEM_READ_WRITE, &format, &desc, ptr::null_mut()).unwrap(),
+            )
+        };
+        {
+            let mut mapped =
+                MappedImage::<MapWriteInvalidate>::new_mut(queue, &mut image, [0, 0, 0], [4, 3, 1])
+                    .unwrap();
+            println!("{:?}", mapped);
+            assert_eq!(4, mapped.element_size());
+            assert!(16 <= mapped.row_pitch());
+            assert_eq!(0, mapped.slice_pitch());
+            for y in 0..3 {
+                for byte in mapped.row_mut(y, 0).unwrap() {
+                    byte.write(y as u8);
+                }
+            }
+            assert!(mapped.row_mut(3, 0).is_none());
+        }
+        let mapped = MappedImage::new(queue, &image, [1, 1, 0], [2, 2, 1]).unwrap();
+        assert_eq!(mapped.row_pitch() + 8, mapped.size());
+        assert_eq!(Some(&[1; 8][..]), mapped.row(0, 0));
+        assert_eq!(Some(&[2; 8][..]), mapped.row(1, 0));
+        assert_eq!(None, mapped.row(0, 1));
+        drop(mapped);
+
+        // The images of a 1D image array are rows separated by the slice pitch
+        let desc = cl_image_desc {
+            image_type: CL_MEM_OBJECT_IMAGE1D_ARRAY,
+            image_height: 1,
+            image_array_size: 3,
+            ..desc
+        };
+        let mut array = unsafe {
+            Mem::from_raw(
+                create_image(context, CL_MEM_READ_WRITE, &format, &desc, ptr::null_mut()).unwrap(),
+            )
+        };
+        {
+            let mut mapped =
+                MappedImage::<MapWrite>::new_mut(queue, &mut array, [0, 0, 0], [4, 3, 1]).unwrap();
+            assert_eq!(2 * mapped.slice_pitch() + 16, mapped.size());
+            for y in 0..3 {
+                mapped.row_mut(y, 0).unwrap().fill(y as u8);
+            }
+        }
+        let mapped = MappedImage::new(queue, &array, [0, 1, 0], [4, 2, 1]).unwrap();
+        assert_eq!(Some(&[1; 16][..]), mapped.row(0, 0));
+        assert_eq!(Some(&[2; 16][..]), mapped.row(1, 0));
+        drop(mapped);
+
+        drop(array);
+        drop(image);
+        drop(buffer);
+        unsafe {
+            release_command_queue(queue).unwrap();
+            release_context(context).unwrap();
+        }
+    }
+}
@@ -43,19 +43,21 @@ pub use opencl_sys::{
     CL_UNORM_SHORT_565, CL_UNSIGNED_INT16, CL_UNSIGNED_INT32, CL_UNSIGNED_INT8,
 };
 
-use opencl_sys::{
+use super::api_error::{clear_error, record_error};
+use super::ffi::{
     clCreateBuffer, clCreateImage, clCreateSubBuffer, clGetImageInfo, clGetMemObjectInfo,
     clGetSupportedImageFormats, clReleaseMemObject, clRetainMemObject,
     clSetMemObjectDestructorCallback,
 };
 
 #[cfg(feature = "CL_VERSION_2_0")]
-use opencl_sys::{clCreatePipe, clGetPipeInfo, clSVMAlloc, clSVMFree};
+use super::ffi::{clCreatePipe, clGetPipeInfo, clSVMAlloc, clSVMFree};
 
 #[cfg(feature = "CL_VERSION_3_0")]
-use opencl_sys::{clCreateBufferWithProperties, clCreateImageWithProperties};
+use super::ffi::{clCreateBufferWithProperties, clCreateImageWithProperties};
 
 use super::info_type::InfoType;
+use super::owned::{AsContext, AsMem};
 use super::{api_info_size, api_info_value, api_info_vector};
 use libc::{c_void, intptr_t, size_t};
 use std::mem;
@@ -80,17 +82,19 @@ use std::ptr;
 /// This function is unsafe because incorrect `flags` can cause undefined behaviour.
 #[inline]
 pub unsafe fn create_buffer(
-    context: cl_context,
+    context: impl AsContext,
     flags: cl_mem_flags,
     size: size_t,
     host_ptr: *mut c_void,
 ) -> Result<cl_mem, cl_int> {
+    let context = context.as_raw();
+    clear_error();
     let mut status: cl_int = CL_INVALID_VALUE;
     let mem: cl_mem = clCreateBuffer(context, flags, size, host_ptr, &mut status);
     if CL_SUCCESS == status {
         Ok(mem)
     } else {
-        Err(status)
+        Err(record_error!(status, clCreateBuffer, flags, size))
     }
 }
 
@@ -113,11 +117,13 @@ pub unsafe fn create_buffer(
 /// This function is unsafe because incorrect `flags` can cause undefined behaviour.
 #[inline]
 pub unsafe fn create_sub_buffer(
-    buffer: cl_mem,
+    buffer: impl AsMem,
     flags: cl_mem_flags,
     buffer_create_type: cl_buffer_create_type,
     buffer_create_info: *const c_void,
 ) -> Result<cl_mem, cl_int> {
+    let buffer = buffer.as_raw();
+    clear_error();
     let mut status: cl_int = CL_INVALID_VALUE;
     let mem: cl_mem = clCreateSubBuffer(
         buffer,
@@ -129,7 +135,12 @@ pub unsafe fn create_sub_buffer(
     if CL_SUCCESS == status {
         Ok(mem)
     } else {
-        Err(status)
+        Err(record_error!(
+            status,
+            clCreateSubBuffer,
+            flags,
+            buffer_create_type
+        ))
     }
 }
 
@@ -156,12 +167,14 @@ pub unsafe fn create_sub_buffer(
 #[cfg(feature = "CL_VERSION_1_2")]
 #[inline]
 pub unsafe fn create_image(
-    context: cl_context,
+    context: impl AsContext,
     flags: cl_mem_flags,
     image_format: *const cl_image_format,
     image_desc: *const cl_image_desc,
     host_ptr: *mut c_void,
 ) -> Result<cl_mem, cl_int> {
+    let context = context.as_raw();
+    clear_error();
     let mut status: cl_int = CL_INVALID_VALUE;
     let mem: cl_mem = clCreateImage(
         context,
@@ -174,7 +187,7 @@ pub unsafe fn create_image(
     if CL_SUCCESS == status {
         Ok(mem)
     } else {
-        Err(status)
+        Err(record_error!(status, clCreateImage, flags))
     }
 }
 
@@ -199,12 +212,14 @@ pub unsafe fn create_image(
 #[cfg(feature = "CL_VERSION_2_0")]
 #[inline]
 pub unsafe fn create_pipe(
-    context: cl_context,
+    context: impl AsContext,
     flags: cl_mem_flags,
     pipe_packet_size: cl_uint,
     pipe_max_packets: cl_uint,
     // properties: *const cl_pipe_properties,
 ) -> Result<cl_mem, cl_int> {
+    let context = context.as_raw();
+    clear_error();
     let mut status: cl_int = CL_INVALID_VALUE;
     let mem: cl_mem = clCreatePipe(
         context,
@@ -217,7 +232,13 @@ pub unsafe fn create_pipe(
     if CL_SUCCESS == status {
         Ok(mem)
     } else {
-        Err(status)
+        Err(record_error!(
+            status,
+            clCreatePipe,
+            flags,
+            pipe_packet_size,
+            pipe_max_packets
+        ))
     }
 }
 
@@ -243,19 +264,26 @@ pub unsafe fn create_pipe(
 #[cfg(feature = "CL_VERSION_3_0")]
 #[inline]
 pub unsafe fn create_buffer_with_properties(
-    context: cl_context,
+    context: impl AsContext,
     properties: *const cl_mem_properties,
     flags: cl_mem_flags,
     size: size_t,
     host_ptr: *mut c_void,
 ) -> Result<cl_mem, cl_int> {
+    let context = context.as_raw();
+    clear_error();
     let mut status: cl_int = CL_INVALID_VALUE;
     let mem: cl_mem =
         clCreateBufferWithProperties(context, properties, flags, size, host_ptr, &mut status);
     if CL_SUCCESS == status {
         Ok(mem)
     } else {
-        Err(status)
+        Err(record_error!(
+            status,
+            clCreateBufferWithProperties,
+            flags,
+            size
+        ))
     }
 }
 
@@ -284,13 +312,15 @@ pub unsafe fn create_buffer_with_properties(
 #[inline]
 #[cfg(feature = "CL_VERSION_3_0")]
 pub unsafe fn create_image_with_properties(
-    context: cl_context,
+    context: impl AsContext,
     properties: *const cl_mem_properties,
     flags: cl_mem_flags,
     image_format: *const cl_image_format,
     image_desc: *const cl_image_desc,
     host_ptr: *mut c_void,
 ) -> Result<cl_mem, cl_int> {
+    let context = context.as_raw();
+    clear_error();
     let mut status: cl_int = CL_INVALID_VALUE;
     let mem: cl_mem = clCreateImageWithProperties(
         context,
@@ -304,7 +334,7 @@ pub unsafe fn create_image_with_properties(
     if CL_SUCCESS == status {
         Ok(mem)
     } else {
-        Err(status)
+        Err(record_error!(status, clCreateImageWithProperties, flags))
     }
 }
 
@@ -320,11 +350,12 @@ pub unsafe fn create_image_with_properties(
 /// This function is unsafe because it changes the `OpenCL` object reference count.
 #[inline]
 pub unsafe fn retain_mem_object(memobj: cl_mem) -> Result<(), cl_int> {
+    clear_error();
     let status: cl_int = clRetainMemObject(memobj);
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(status, clRetainMemObject))
     }
 }
 
@@ -340,11 +371,12 @@ pub unsafe fn retain_mem_object(memobj: cl_mem) -> Result<(), cl_int> {
 /// This function is unsafe because it changes the `OpenCL` object reference count.
 #[inline]
 pub unsafe fn release_mem_object(memobj: cl_mem) -> Result<(), cl_int> {
+    clear_error();
     let status: cl_int = clReleaseMemObject(memobj);
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(status, clReleaseMemObject))
     }
 }
 
@@ -353,6 +385,7 @@ fn count_supported_image_formats(
     flags: cl_mem_flags,
     image_type: cl_mem_object_type,
 ) -> Result<cl_uint, cl_int> {
+    clear_error();
     let mut count: cl_uint = 0;
     let status: cl_int = unsafe {
         clGetSupportedImageFormats(context, flags, image_type, 0, ptr::null_mut(), &mut count)
@@ -360,7 +393,12 @@ fn count_supported_image_formats(
     if CL_SUCCESS == status {
         Ok(count)
     } else {
-        Err(status)
+        Err(record_error!(
+            status,
+            clGetSupportedImageFormats,
+            flags,
+            image_type
+        ))
     }
 }
 
@@ -378,10 +416,12 @@ fn count_supported_image_formats(
 /// or the error code from the `OpenCL` C API function.
 #[inline]
 pub fn get_supported_image_formats(
-    context: cl_context,
+    context: impl AsContext,
     flags: cl_mem_flags,
     image_type: cl_mem_object_type,
 ) -> Result<Vec<cl_image_format>, cl_int> {
+    let context = context.as_raw();
+    clear_error();
     let count: cl_uint = count_supported_image_formats(context, flags, image_type)?;
     let mut image_formats: Vec<cl_image_format> = Vec::with_capacity(count as usize);
     let status: cl_int = unsafe {
@@ -398,13 +438,20 @@ pub fn get_supported_image_formats(
     if CL_SUCCESS == status {
         Ok(image_formats)
     } else {
-        Err(status)
+        Err(record_error!(
+            status,
+            clGetSupportedImageFormats,
+            flags,
+            image_type
+        ))
     }
 }
 
 /// Get data about an `OpenCL` memory object.
 /// Calls `clGetMemObjectInfo` to get the desired data about the memory object.
-pub fn get_mem_object_data(memobj: cl_mem, param_name: cl_mem_info) -> Result<Vec<u8>, cl_int> {
+pub fn get_mem_object_data(memobj: impl AsMem, param_name: cl_mem_info) -> Result<Vec<u8>, cl_int> {
+    let memobj = memobj.as_raw();
+    clear_error();
     api_info_size!(get_size, clGetMemObjectInfo);
     let size = get_size(memobj, param_name)?;
     api_info_vector!(get_vector, u8, clGetMemObjectInfo);
@@ -420,7 +467,12 @@ pub fn get_mem_object_data(memobj: cl_mem, param_name: cl_mem_info) -> Result<Ve
 ///
 /// returns a Result containing the desired information in an `InfoType` enum
 /// or the error code from the `OpenCL` C API function.
-pub fn get_mem_object_info(memobj: cl_mem, param_name: cl_mem_info) -> Result<InfoType, cl_int> {
+pub fn get_mem_object_info(
+    memobj: impl AsMem,
+    param_name: cl_mem_info,
+) -> Result<InfoType, cl_int> {
+    let memobj = memobj.as_raw();
+    clear_error();
     match param_name {
         CL_MEM_TYPE
         | CL_MEM_MAP_COUNT
@@ -460,7 +512,9 @@ pub fn get_mem_object_info(memobj: cl_mem, param_name: cl_mem_info) -> Result<In
 
 /// Get data about an `OpenCL` image object.
 /// Calls `clGetImageInfo` to get the desired data about the image object.
-pub fn get_image_data(image: cl_mem, param_name: cl_image_info) -> Result<Vec<u8>, cl_int> {
+pub fn get_image_data(image: impl AsMem, param_name: cl_image_info) -> Result<Vec<u8>, cl_int> {
+    let image = image.as_raw();
+    clear_error();
     api_info_size!(get_size, clGetImageInfo);
     let size = get_size(image, param_name)?;
     api_info_vector!(get_vector, u8, clGetImageInfo);
@@ -476,7 +530,9 @@ pub fn get_image_data(image: cl_mem, param_name: cl_image_info) -> Result<Vec<u8
 ///
 /// returns a Result containing the desired information in an `InfoType` enum
 /// or the error code from the `OpenCL` C API function.
-pub fn get_image_info(image: cl_mem, param_name: cl_image_info) -> Result<InfoType, cl_int> {
+pub fn get_image_info(image: impl AsMem, param_name: cl_image_info) -> Result<InfoType, cl_int> {
+    let image = image.as_raw();
+    clear_error();
     match param_name {
         CL_IMAGE_FORMAT => {
             api_info_size!(get_size, clGetImageInfo);
@@ -513,7 +569,9 @@ pub fn get_image_info(image: cl_mem, param_name: cl_image_info) -> Result<InfoTy
 /// Get data about an `OpenCL` pipe object.
 /// Calls `clGetPipeInfo` to get the desired data about the pipe object.
 #[cfg(feature = "CL_VERSION_2_0")]
-pub fn get_pipe_data(pipe: cl_mem, param_name: cl_pipe_info) -> Result<Vec<u8>, cl_int> {
+pub fn get_pipe_data(pipe: impl AsMem, param_name: cl_pipe_info) -> Result<Vec<u8>, cl_int> {
+    let pipe = pipe.as_raw();
+    clear_error();
     api_info_size!(get_size, clGetPipeInfo);
     let size = get_size(pipe, param_name)?;
     api_info_vector!(get_vector, u8, clGetPipeInfo);
@@ -531,7 +589,9 @@ pub fn get_pipe_data(pipe: cl_mem, param_name: cl_pipe_info) -> Result<Vec<u8>,
 /// returns a Result containing the desired information in an `InfoType` enum
 /// or the error code from the `OpenCL` C API function.
 #[cfg(feature = "CL_VERSION_2_0")]
-pub fn get_pipe_info(pipe: cl_mem, param_name: cl_pipe_info) -> Result<InfoType, cl_int> {
+pub fn get_pipe_info(pipe: impl AsMem, param_name: cl_pipe_info) -> Result<InfoType, cl_int> {
+    let pipe = pipe.as_raw();
+    clear_error();
     match param_name {
         CL_PIPE_PACKET_SIZE | CL_PIPE_MAX_PACKETS => {
             api_info_value!(get_value, cl_uint, clGetPipeInfo);
@@ -564,15 +624,17 @@ pub fn get_pipe_info(pipe: cl_mem, param_name: cl_pipe_info) -> Result<InfoType,
 /// This function is unsafe because `user_data` must be valid.
 #[inline]
 pub unsafe fn set_mem_object_destructor_callback(
-    memobj: cl_mem,
+    memobj: impl AsMem,
     pfn_notify: extern "C" fn(cl_mem, *mut c_void),
     user_data: *mut c_void,
 ) -> Result<(), cl_int> {
+    let memobj = memobj.as_raw();
+    clear_error();
     let status: cl_int = clSetMemObjectDestructorCallback(memobj, Some(pfn_notify), user_data);
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(status, clSetMemObjectDestructorCallback))
     }
 }
 
@@ -597,14 +659,22 @@ pub unsafe fn set_mem_object_destructor_callback(
 #[cfg(feature = "CL_VERSION_2_0")]
 #[inline]
 pub unsafe fn svm_alloc(
-    context: cl_context,
+    context: impl AsContext,
     flags: cl_svm_mem_flags,
     size: size_t,
     alignment: cl_uint,
 ) -> Result<*mut c_void, cl_int> {
+    let context = context.as_raw();
+    clear_error();
     let ptr = clSVMAlloc(context, flags, size, alignment);
     if ptr.is_null() {
-        Err(CL_INVALID_VALUE)
+        Err(record_error!(
+            CL_INVALID_VALUE,
+            clSVMAlloc,
+            flags,
+            size,
+            alignment
+        ))
     } else {
         Ok(ptr)
     }
@@ -622,6 +692,7 @@ pub unsafe fn svm_alloc(
 /// This function is unsafe because `svm_pointer` is no longer valid after it is called.
 #[cfg(feature = "CL_VERSION_2_0")]
 #[inline]
-pub unsafe fn svm_free(context: cl_context, svm_pointer: *mut c_void) {
+pub unsafe fn svm_free(context: impl AsContext, svm_pointer: *mut c_void) {
+    let context = context.as_raw();
     clSVMFree(context, svm_pointer);
 }
@@ -0,0 +1,7931 @@
+// Copyright (c) 2024 Via Technology Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! An in-process mock `OpenCL` implementation for testing without `OpenCL` hardware.
+//!
+//! When the "mock" feature is enabled, the adapter modules call the `OpenCL`
+//! C API functions defined in this module instead of the functions linked
+//! from `opencl-sys`, so that code using `cl3` can be tested on machines
+//! without an `OpenCL` device or driver.
+//!
+//! The mock provides one platform with one GPU device that supports
+//! `OpenCL 3.0` with `OpenCL C 1.2`, SVM and images.
+//! Memory objects are held in host memory and commands are executed as soon as
+//! the events in their wait lists are complete, so events normally complete
+//! before the enqueue function returns. Commands that wait on a user event are
+//! executed when [`clSetUserEventStatus`] completes the event.
+//!
+//! Programs are "built" by preprocessing their `OpenCL C` source and parsing
+//! the kernel declarations, so the program, kernel and kernel argument
+//! information is available, and build errors are reported in the build log.
+//! Kernel bodies are not executed: a Rust function registered by kernel name
+//! with [`register_kernel`] is called instead, see [`KernelCall`].
+//! Kernels without a registered function do nothing.
+//!
+//! The built-in kernel `copy_buffer(global const uchar* src, global uchar* dst)`
+//! copies one byte per work item.
+//!
+//! SPIR-V modules passed to `clCreateProgramWithIL` are only parsed for their
+//! entry points, kernel parameters and specialization constant IDs.
+//! Pipes and `OpenGL` interoperability are not supported.
+//! Extension functions that have core equivalents, e.g. the
+//! `cl_khr_il_program` and `cl_arm_shared_virtual_memory` functions, call the
+//! core functions. The other extension functions return `CL_INVALID_OPERATION`.
+//!
+//! All of the mock state is protected by a single mutex, so the mock can be
+//! used from multiple threads.
+//! Kernel and native kernel functions are called while the mutex is locked,
+//! so they must not call `OpenCL` API functions.
+
+#![allow(non_snake_case)]
+#![allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_possible_wrap,
+    clippy::cast_sign_loss,
+    clippy::missing_const_for_fn,
+    clippy::missing_safety_doc,
+    clippy::too_many_arguments,
+    clippy::too_many_lines,
+    clippy::wildcard_imports
+)]
+
+#[allow(unused_imports)]
+use libc::c_int;
+use libc::{c_char, c_uchar, c_void, size_t};
+#[allow(unused_imports)]
+use opencl_sys::*;
+#[allow(unused_imports)]
+use opencl_sys::{
+    cl_dx9_media_sharing::{
+        cl_dx9_device_set_intel, cl_dx9_device_source_intel, IDirect3DSurface9_ptr, D3DFORMAT,
+        HANDLE,
+    },
+    cl_egl::{cl_egl_image_properties_khr, CLeglDisplayKHR, CLeglImageKHR, CLeglSyncKHR},
+    cl_icd::cl_icd_dispatch,
+    cl_layer::cl_layer_info,
+};
+use std::alloc::{self, Layout};
+use std::collections::{HashMap, VecDeque};
+use std::ffi::CStr;
+use std::fmt::Write as _;
+use std::mem;
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+use std::slice;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, OnceLock, PoisonError};
+use std::time::Instant;
+
+/// A type that can be copied to and from `OpenCL` memory as raw bytes.
+///
+/// # Safety
+///
+/// The type must be valid for any bit pattern, e.g. an integer or floating
+/// point number, or an array of them.
+pub unsafe trait Scalar: Copy + 'static {}
+
+unsafe impl Scalar for i8 {}
+unsafe impl Scalar for u8 {}
+unsafe impl Scalar for i16 {}
+unsafe impl Scalar for u16 {}
+unsafe impl Scalar for i32 {}
+unsafe impl Scalar for u32 {}
+unsafe impl Scalar for i64 {}
+unsafe impl Scalar for u64 {}
+unsafe impl Scalar for isize {}
+unsafe impl Scalar for usize {}
+unsafe impl Scalar for f32 {}
+unsafe impl Scalar for f64 {}
+unsafe impl<T: Scalar, const N: usize> Scalar for [T; N] {}
+
+/// The value of a kernel argument when a kernel is enqueued.
+#[derive(Clone)]
+enum CallArg {
+    Null,
+    Memory(Storage, usize),
+    Local(usize),
+    Sampler(cl_sampler),
+    Value(Vec<u8>),
+    Svm(*mut c_void),
+}
+
+/// A kernel function call: the work sizes and kernel arguments of an
+/// `NDRange` kernel command, passed to the function registered for the kernel
+/// with [`register_kernel`].
+pub struct KernelCall {
+    name: String,
+    work_dim: cl_uint,
+    global_work_offset: [size_t; 3],
+    global_work_size: [size_t; 3],
+    local_work_size: Option<[size_t; 3]>,
+    args: Vec<CallArg>,
+}
+
+impl KernelCall {
+    /// The name of the kernel.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The number of work dimensions.
+    #[must_use]
+    pub const fn work_dim(&self) -> cl_uint {
+        self.work_dim
+    }
+
+    /// The global work offset in each work dimension.
+    #[must_use]
+    pub fn global_work_offset(&self) -> &[size_t] {
+        &self.global_work_offset[..self.work_dim as usize]
+    }
+
+    /// The global work size in each work dimension.
+    #[must_use]
+    pub fn global_work_size(&self) -> &[size_t] {
+        &self.global_work_size[..self.work_dim as usize]
+    }
+
+    /// The local work size in each work dimension, if it was specified.
+    #[must_use]
+    pub fn local_work_size(&self) -> Option<&[size_t]> {
+        self.local_work_size
+            .as_ref()
+            .map(|sizes| &sizes[..self.work_dim as usize])
+    }
+
+    /// The number of kernel arguments.
+    #[must_use]
+    pub fn num_args(&self) -> usize {
+        self.args.len()
+    }
+
+    fn arg(&self, index: usize) -> Result<&CallArg, cl_int> {
+        self.args.get(index).ok_or(CL_INVALID_ARG_INDEX)
+    }
+
+    /// Get the value of a kernel argument passed by value.
+    ///
+    /// returns the value, `CL_INVALID_ARG_SIZE` if the argument is not the
+    /// size of `T` or `CL_INVALID_ARG_VALUE` if it is not passed by value.
+    pub fn value<T: Scalar>(&self, index: usize) -> Result<T, cl_int> {
+        match self.arg(index)? {
+            CallArg::Value(bytes) if bytes.len() == mem::size_of::<T>() => {
+                Ok(unsafe { ptr::read_unaligned(bytes.as_ptr().cast::<T>()) })
+            }
+            CallArg::Value(_) => Err(CL_INVALID_ARG_SIZE),
+            _ => Err(CL_INVALID_ARG_VALUE),
+        }
+    }
+
+    /// Get the size in bytes of a memory object kernel argument.
+    pub fn buffer_size(&self, index: usize) -> Result<size_t, cl_int> {
+        match self.arg(index)? {
+            CallArg::Memory(_, size) => Ok(*size),
+            _ => Err(CL_INVALID_ARG_VALUE),
+        }
+    }
+
+    /// Read the contents of a memory object kernel argument.
+    ///
+    /// returns the contents as a vector of `T`, or `CL_INVALID_ARG_VALUE`
+    /// if the argument is not a memory object.
+    pub fn read<T: Scalar>(&self, index: usize) -> Result<Vec<T>, cl_int> {
+        match self.arg(index)? {
+            CallArg::Memory(storage, size) => {
+                let count = size / mem::size_of::<T>().max(1);
+                let mut values = Vec::with_capacity(count);
+                let src = storage.ptr.cast::<T>();
+                for i in 0..count {
+                    values.push(unsafe { ptr::read_unaligned(src.add(i)) });
+                }
+                Ok(values)
+            }
+            _ => Err(CL_INVALID_ARG_VALUE),
+        }
+    }
+
+    /// Write to the start of a memory object kernel argument.
+    ///
+    /// returns an empty Result, `CL_INVALID_ARG_SIZE` if `values` is larger
+    /// than the memory object, or `CL_INVALID_ARG_VALUE` if the argument is
+    /// not a memory object.
+    pub fn write<T: Scalar>(&mut self, index: usize, values: &[T]) -> Result<(), cl_int> {
+        match self.arg(index)? {
+            CallArg::Memory(storage, size) => {
+                let length = mem::size_of_val(values);
+                if *size < length {
+                    return Err(CL_INVALID_ARG_SIZE);
+                }
+                unsafe {
+                    ptr::copy(values.as_ptr().cast::<u8>(), storage.ptr, length);
+                }
+                Ok(())
+            }
+            _ => Err(CL_INVALID_ARG_VALUE),
+        }
+    }
+
+    /// Get the size in bytes of a local memory kernel argument.
+    pub fn local_size(&self, index: usize) -> Result<size_t, cl_int> {
+        match self.arg(index)? {
+            CallArg::Local(size) => Ok(*size),
+            _ => Err(CL_INVALID_ARG_VALUE),
+        }
+    }
+
+    /// Get the sampler of a sampler kernel argument.
+    pub fn sampler(&self, index: usize) -> Result<cl_sampler, cl_int> {
+        match self.arg(index)? {
+            CallArg::Sampler(sampler) => Ok(*sampler),
+            _ => Err(CL_INVALID_ARG_VALUE),
+        }
+    }
+
+    /// Get the pointer of a memory object or SVM pointer kernel argument.
+    /// The pointer is null if the argument was set to a null memory object.
+    pub fn svm_pointer(&self, index: usize) -> Result<*mut c_void, cl_int> {
+        match self.arg(index)? {
+            CallArg::Svm(pointer) => Ok(*pointer),
+            CallArg::Memory(storage, _) => Ok(storage.ptr.cast::<c_void>()),
+            CallArg::Null => Ok(ptr::null_mut()),
+            _ => Err(CL_INVALID_ARG_VALUE),
+        }
+    }
+}
+
+/// A kernel function registered with [`register_kernel`].
+type KernelFn = Arc<dyn Fn(&mut KernelCall) -> Result<(), cl_int> + Send + Sync>;
+
+fn kernel_registry() -> MutexGuard<'static, HashMap<String, KernelFn>> {
+    static KERNELS: OnceLock<Mutex<HashMap<String, KernelFn>>> = OnceLock::new();
+    KERNELS
+        .get_or_init(Mutex::default)
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Register the Rust function to call when a kernel called `name` is enqueued,
+/// replacing any function previously registered for the kernel name.
+///
+/// The function is called once per kernel command, not per work item.
+/// If it returns an error or panics, the command's event status is set to
+/// the error or `CL_OUT_OF_RESOURCES` respectively.
+/// The function is called while the mock state is locked, so it must not
+/// call `OpenCL` API functions.
+///
+/// * `name` - the kernel function name.
+/// * `function` - the function to call.
+pub fn register_kernel<F>(name: &str, function: F)
+where
+    F: Fn(&mut KernelCall) -> Result<(), cl_int> + Send + Sync + 'static,
+{
+    kernel_registry().insert(name.to_owned(), Arc::new(function));
+}
+
+/// Unregister the function registered for the kernel called `name`.
+///
+/// returns true if a function was registered, false otherwise.
+#[must_use]
+pub fn unregister_kernel(name: &str) -> bool {
+    kernel_registry().remove(name).is_some()
+}
+
+/// The built-in kernel: `copy_buffer(global const uchar* src, global uchar* dst)`.
+fn copy_buffer_kernel(call: &mut KernelCall) -> Result<(), cl_int> {
+    let offset = call.global_work_offset[0];
+    let end = offset + call.global_work_size[0];
+    let src = call.read::<u8>(0)?;
+    let mut dst = call.read::<u8>(1)?;
+    if src.len() < end || dst.len() < end {
+        return Err(CL_INVALID_ARG_SIZE);
+    }
+    dst[offset..end].copy_from_slice(&src[offset..end]);
+    call.write(1, &dst)
+}
+
+const BUILT_IN_KERNELS: &str = "copy_buffer";
+
+/// The alignment of memory allocations, see `CL_DEVICE_MEM_BASE_ADDR_ALIGN`.
+const ALIGNMENT: usize = 128;
+
+/// A zeroed host memory allocation for a memory object or SVM buffer.
+struct Allocation {
+    ptr: *mut u8,
+    layout: Layout,
+}
+
+impl Allocation {
+    fn new(size: usize, alignment: usize) -> Result<Arc<Self>, cl_int> {
+        let layout = Layout::from_size_align(size.max(1), alignment.max(ALIGNMENT))
+            .map_err(|_| CL_INVALID_BUFFER_SIZE)?;
+        let ptr = unsafe { alloc::alloc_zeroed(layout) };
+        if ptr.is_null() {
+            Err(CL_MEM_OBJECT_ALLOCATION_FAILURE)
+        } else {
+            Ok(Arc::new(Self { ptr, layout }))
+        }
+    }
+}
+
+// The allocation is only accessed through `Storage` pointers, see `State`.
+unsafe impl Send for Allocation {}
+unsafe impl Sync for Allocation {}
+
+impl Drop for Allocation {
+    fn drop(&mut self) {
+        unsafe { alloc::dealloc(self.ptr, self.layout) };
+    }
+}
+
+/// The memory of a memory object.
+/// Commands hold a clone so that the memory outlives the memory object
+/// if it is released before the commands are executed.
+#[derive(Clone)]
+struct Storage {
+    allocation: Option<Arc<Allocation>>,
+    ptr: *mut u8,
+}
+
+impl Storage {
+    fn at(&self, offset: usize) -> Self {
+        Self {
+            allocation: self.allocation.clone(),
+            ptr: unsafe { self.ptr.add(offset) },
+        }
+    }
+}
+
+type ContextNotify = unsafe extern "C" fn(*const c_char, *const c_void, size_t, *mut c_void);
+type ContextDestructor = unsafe extern "C" fn(cl_context, *mut c_void);
+type MemDestructor = unsafe extern "C" fn(cl_mem, *mut c_void);
+type ProgramNotify = unsafe extern "C" fn(cl_program, *mut c_void);
+type EventNotify = unsafe extern "C" fn(cl_event, cl_int, *mut c_void);
+type SvmFreeNotify = unsafe extern "C" fn(cl_command_queue, cl_uint, *mut *mut c_void, *mut c_void);
+type NativeKernel = unsafe extern "C" fn(*mut c_void);
+
+/// A callback to the application, called after the mock state is unlocked.
+enum Notification {
+    Context(ContextDestructor, usize, *mut c_void),
+    Mem(MemDestructor, usize, *mut c_void),
+    Program(ProgramNotify, usize, *mut c_void),
+    Event(EventNotify, usize, cl_int, *mut c_void),
+    SvmFree(SvmFreeNotify, usize, Vec<*mut c_void>, *mut c_void),
+}
+
+impl Notification {
+    unsafe fn call(self) {
+        match self {
+            Self::Context(notify, context, user_data) => notify(handle(context), user_data),
+            Self::Mem(notify, mem, user_data) => notify(handle(mem), user_data),
+            Self::Program(notify, program, user_data) => notify(handle(program), user_data),
+            Self::Event(notify, event, status, user_data) => {
+                notify(handle(event), status, user_data);
+            }
+            Self::SvmFree(notify, queue, mut pointers, user_data) => notify(
+                handle(queue),
+                pointers.len() as cl_uint,
+                pointers.as_mut_ptr(),
+                user_data,
+            ),
+        }
+    }
+}
+
+/// The handle of the mock platform.
+const PLATFORM: usize = 1;
+/// The handle of the mock device.
+const DEVICE: usize = 2;
+
+/// Convert an object id into an `OpenCL` handle.
+const fn handle(id: usize) -> *mut c_void {
+    id as *mut c_void
+}
+
+/// Convert an `OpenCL` handle into an object id.
+fn id<T>(handle: *const T) -> usize {
+    handle as usize
+}
+
+struct Context {
+    ref_count: cl_uint,
+    properties: Vec<cl_context_properties>,
+    destructors: Vec<(ContextDestructor, *mut c_void)>,
+}
+
+/// The action of a command, returns `CL_SUCCESS` or an error code.
+type Action = Box<dyn FnOnce(&mut State) -> cl_int>;
+
+struct Command {
+    event: usize,
+    wait_list: Vec<usize>,
+    action: Action,
+}
+
+struct Queue {
+    ref_count: cl_uint,
+    context: usize,
+    properties: cl_command_queue_properties,
+    properties_array: Vec<cl_queue_properties>,
+    size: cl_uint,
+    commands: VecDeque<Command>,
+    barrier: Option<usize>,
+    released: bool,
+}
+
+impl Queue {
+    const fn is_out_of_order(&self) -> bool {
+        0 != self.properties & CL_QUEUE_OUT_OF_ORDER_EXEC_MODE_ENABLE
+    }
+
+    const fn is_on_device(&self) -> bool {
+        0 != self.properties & CL_QUEUE_ON_DEVICE
+    }
+}
+
+struct Event {
+    ref_count: cl_uint,
+    context: usize,
+    queue: usize,
+    command_type: cl_command_type,
+    status: cl_int,
+    callbacks: Vec<(cl_int, EventNotify, *mut c_void)>,
+    profiling: bool,
+    /// The queued, submit, start and end times.
+    times: [cl_ulong; 4],
+}
+
+struct Image {
+    format: cl_image_format,
+    desc: cl_image_desc,
+    element_size: usize,
+    /// The width, height and depth of the image, where the array size
+    /// of 1D and 2D image arrays is the height and depth respectively.
+    dims: [usize; 3],
+    row_pitch: usize,
+    slice_pitch: usize,
+}
+
+struct Mem {
+    ref_count: cl_uint,
+    context: usize,
+    object_type: cl_mem_object_type,
+    flags: cl_mem_flags,
+    size: usize,
+    host_ptr: *mut c_void,
+    storage: Storage,
+    parent: usize,
+    offset: usize,
+    uses_svm_pointer: bool,
+    properties: Vec<cl_mem_properties>,
+    image: Option<Image>,
+    maps: Vec<usize>,
+    destructors: Vec<(MemDestructor, *mut c_void)>,
+}
+
+struct Svm {
+    context: usize,
+    allocation: Arc<Allocation>,
+    size: usize,
+}
+
+struct Sampler {
+    ref_count: cl_uint,
+    context: usize,
+    normalized_coords: cl_bool,
+    addressing_mode: cl_addressing_mode,
+    filter_mode: cl_filter_mode,
+    properties: Vec<cl_sampler_properties>,
+}
+
+/// How a program was created.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Origin {
+    Source,
+    Il,
+    Binary,
+    BuiltIn,
+    Linked,
+}
+
+struct Program {
+    ref_count: cl_uint,
+    context: usize,
+    devices: Vec<cl_device_id>,
+    origin: Origin,
+    source: String,
+    il: Vec<u8>,
+    binary_type: cl_program_binary_type,
+    build_status: cl_build_status,
+    build_options: String,
+    build_log: String,
+    arg_info: bool,
+    uniform_work_groups: bool,
+    kernels: Vec<KernelDecl>,
+    /// The sizes of the specialization constants of an IL program.
+    spec_ids: HashMap<cl_uint, usize>,
+    spec_constants: HashMap<cl_uint, Vec<u8>>,
+}
+
+struct Kernel {
+    ref_count: cl_uint,
+    context: usize,
+    program: usize,
+    decl: KernelDecl,
+    args: Vec<Option<CallArg>>,
+    arg_info: bool,
+    uniform_work_groups: bool,
+    built_in: bool,
+}
+
+/// The state of the mock `OpenCL` implementation.
+struct State {
+    next_id: usize,
+    start: Instant,
+    contexts: HashMap<usize, Context>,
+    queues: HashMap<usize, Queue>,
+    default_device_queue: usize,
+    events: HashMap<usize, Event>,
+    mems: HashMap<usize, Mem>,
+    svm: HashMap<usize, Svm>,
+    samplers: HashMap<usize, Sampler>,
+    programs: HashMap<usize, Program>,
+    kernels: HashMap<usize, Kernel>,
+    notifications: Vec<Notification>,
+}
+
+// The raw pointers in the state are only dereferenced while it is locked.
+#[allow(clippy::non_send_fields_in_send_ty)]
+unsafe impl Send for State {}
+
+static STATE: OnceLock<Mutex<State>> = OnceLock::new();
+static COMPLETED: Condvar = Condvar::new();
+
+fn lock() -> MutexGuard<'static, State> {
+    STATE
+        .get_or_init(|| {
+            Mutex::new(State {
+                next_id: 16,
+                start: Instant::now(),
+                contexts: HashMap::new(),
+                queues: HashMap::new(),
+                default_device_queue: 0,
+                events: HashMap::new(),
+                mems: HashMap::new(),
+                svm: HashMap::new(),
+                samplers: HashMap::new(),
+                programs: HashMap::new(),
+                kernels: HashMap::new(),
+                notifications: Vec::new(),
+            })
+        })
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Call `f` with the locked state, execute any commands that are ready,
+/// then call any application callbacks after unlocking the state.
+fn with_state<R>(f: impl FnOnce(&mut State) -> R) -> R {
+    let mut state = lock();
+    let result = f(&mut state);
+    state.progress();
+    let notifications = mem::take(&mut state.notifications);
+    drop(state);
+    for notification in notifications {
+        unsafe { notification.call() };
+    }
+    COMPLETED.notify_all();
+    result
+}
+
+/// Wait until all of the `events` are complete.
+///
+/// returns `CL_SUCCESS` or `CL_EXEC_STATUS_ERROR_FOR_EVENTS_IN_WAIT_LIST` if
+/// any of the events failed.
+fn wait_for(events: &[usize]) -> cl_int {
+    let mut state = lock();
+    loop {
+        let statuses = events
+            .iter()
+            .filter_map(|event| state.events.get(event).map(|event| event.status));
+        let mut status = CL_SUCCESS;
+        for value in statuses {
+            if CL_COMPLETE < value {
+                status = CL_QUEUED;
+                break;
+            } else if value < CL_COMPLETE {
+                status = CL_EXEC_STATUS_ERROR_FOR_EVENTS_IN_WAIT_LIST;
+            }
+        }
+        if CL_QUEUED != status {
+            return status;
+        }
+        state = COMPLETED
+            .wait(state)
+            .unwrap_or_else(PoisonError::into_inner);
+    }
+}
+
+/// The result of an info query: the bytes of the information or an error code.
+type Info = Result<Vec<u8>, cl_int>;
+
+fn bytes_of<T: Scalar>(values: &[T]) -> Vec<u8> {
+    unsafe { slice::from_raw_parts(values.as_ptr().cast::<u8>(), mem::size_of_val(values)) }
+        .to_vec()
+}
+
+#[allow(clippy::unnecessary_wraps)]
+fn value_info<T: Scalar>(value: T) -> Info {
+    Ok(bytes_of(&[value]))
+}
+
+#[allow(clippy::unnecessary_wraps)]
+fn slice_info<T: Scalar>(values: &[T]) -> Info {
+    Ok(bytes_of(values))
+}
+
+#[allow(clippy::unnecessary_wraps)]
+fn str_info(value: &str) -> Info {
+    let mut bytes = value.as_bytes().to_vec();
+    bytes.push(0);
+    Ok(bytes)
+}
+
+#[allow(clippy::unnecessary_wraps)]
+fn name_version_info(values: &[(cl_version, &str)]) -> Info {
+    let mut bytes = Vec::with_capacity(values.len() * mem::size_of::<cl_name_version>());
+    for (version, name) in values {
+        let mut value = [0_u8; CL_NAME_VERSION_MAX_NAME_SIZE];
+        let length = name.len().min(CL_NAME_VERSION_MAX_NAME_SIZE - 1);
+        value[..length].copy_from_slice(&name.as_bytes()[..length]);
+        bytes.extend_from_slice(&version.to_ne_bytes());
+        bytes.extend_from_slice(&value);
+    }
+    Ok(bytes)
+}
+
+/// Write the result of an info query to the `OpenCL` info function parameters.
+unsafe fn write_info(
+    info: Info,
+    param_value_size: size_t,
+    param_value: *mut c_void,
+    param_value_size_ret: *mut size_t,
+) -> cl_int {
+    match info {
+        Ok(bytes) => {
+            if !param_value.is_null() {
+                if param_value_size < bytes.len() {
+                    return CL_INVALID_VALUE;
+                }
+                ptr::copy_nonoverlapping(bytes.as_ptr(), param_value.cast::<u8>(), bytes.len());
+            }
+            if !param_value_size_ret.is_null() {
+                *param_value_size_ret = bytes.len();
+            }
+            CL_SUCCESS
+        }
+        Err(error) => error,
+    }
+}
+
+/// Return an `OpenCL` object handle and set `errcode_ret`, if present.
+unsafe fn object_result(result: Result<usize, cl_int>, errcode_ret: *mut cl_int) -> *mut c_void {
+    let (object, status) = match result {
+        Ok(object) => (handle(object), CL_SUCCESS),
+        Err(error) => (ptr::null_mut(), error),
+    };
+    if !errcode_ret.is_null() {
+        *errcode_ret = status;
+    }
+    object
+}
+
+/// Return a status as a Result.
+const fn check(status: cl_int) -> Result<(), cl_int> {
+    if CL_SUCCESS == status {
+        Ok(())
+    } else {
+        Err(status)
+    }
+}
+
+/// Get a slice from an `OpenCL` array parameter, which may be null if empty.
+unsafe fn array<'a, T>(values: *const T, count: usize) -> &'a [T] {
+    if values.is_null() || 0 == count {
+        &[]
+    } else {
+        slice::from_raw_parts(values, count)
+    }
+}
+
+/// Get a zero terminated properties list, including the terminating zero.
+unsafe fn properties_list<T: Copy + Default + PartialEq>(properties: *const T) -> Vec<T> {
+    let mut list = Vec::new();
+    if !properties.is_null() {
+        let mut property = properties;
+        loop {
+            let name = *property;
+            list.push(name);
+            if T::default() == name {
+                break;
+            }
+            list.push(*property.add(1));
+            property = property.add(2);
+        }
+    }
+    list
+}
+
+/// Read a 3 dimensional origin or region.
+unsafe fn triple(values: *const size_t) -> Option<[usize; 3]> {
+    if values.is_null() {
+        None
+    } else {
+        Some([*values, *values.add(1), *values.add(2)])
+    }
+}
+
+/// Generate methods to get an object from the state by handle.
+macro_rules! object_lookup {
+    ($get:ident, $get_mut:ident, $map:ident, $type:ty, $error:ident) => {
+        fn $get(&self, object: *mut c_void) -> Result<&$type, cl_int> {
+            self.$map.get(&id(object)).ok_or($error)
+        }
+
+        fn $get_mut(&mut self, object: *mut c_void) -> Result<&mut $type, cl_int> {
+            self.$map.get_mut(&id(object)).ok_or($error)
+        }
+    };
+}
+
+impl State {
+    object_lookup!(context, context_mut, contexts, Context, CL_INVALID_CONTEXT);
+    object_lookup!(event, event_mut, events, Event, CL_INVALID_EVENT);
+    object_lookup!(mem, mem_mut, mems, Mem, CL_INVALID_MEM_OBJECT);
+    object_lookup!(sampler, sampler_mut, samplers, Sampler, CL_INVALID_SAMPLER);
+    object_lookup!(program, program_mut, programs, Program, CL_INVALID_PROGRAM);
+    object_lookup!(kernel, kernel_mut, kernels, Kernel, CL_INVALID_KERNEL);
+
+    fn queue(&self, queue: cl_command_queue) -> Result<&Queue, cl_int> {
+        self.queues
+            .get(&id(queue))
+            .filter(|queue| !queue.released)
+            .ok_or(CL_INVALID_COMMAND_QUEUE)
+    }
+
+    fn queue_mut(&mut self, queue: cl_command_queue) -> Result<&mut Queue, cl_int> {
+        self.queues
+            .get_mut(&id(queue))
+            .filter(|queue| !queue.released)
+            .ok_or(CL_INVALID_COMMAND_QUEUE)
+    }
+
+    fn new_id(&mut self) -> usize {
+        let object = self.next_id;
+        self.next_id += 1;
+        object
+    }
+
+    fn now(&self) -> cl_ulong {
+        self.start.elapsed().as_nanos() as cl_ulong
+    }
+
+    fn retain_context(&mut self, context: usize) {
+        if let Some(context) = self.contexts.get_mut(&context) {
+            context.ref_count += 1;
+        }
+    }
+
+    fn release_context(&mut self, context: usize) {
+        if let Some(object) = self.contexts.get_mut(&context) {
+            object.ref_count -= 1;
+            if 0 == object.ref_count {
+                if let Some(object) = self.contexts.remove(&context) {
+                    for (notify, user_data) in object.destructors.into_iter().rev() {
+                        self.notifications
+                            .push(Notification::Context(notify, context, user_data));
+                    }
+                }
+            }
+        }
+    }
+
+    fn release_queue(&mut self, queue: usize) {
+        if let Some(object) = self.queues.get_mut(&queue) {
+            object.ref_count -= 1;
+            if 0 == object.ref_count {
+                object.released = true;
+                let context = object.context;
+                if self.default_device_queue == queue {
+                    self.default_device_queue = 0;
+                }
+                self.release_context(context);
+            }
+        }
+    }
+
+    fn retain_mem(&mut self, mem: usize) {
+        if let Some(mem) = self.mems.get_mut(&mem) {
+            mem.ref_count += 1;
+        }
+    }
+
+    fn release_mem(&mut self, mem: usize) {
+        if let Some(object) = self.mems.get_mut(&mem) {
+            object.ref_count -= 1;
+            if 0 == object.ref_count {
+                if let Some(object) = self.mems.remove(&mem) {
+                    for (notify, user_data) in object.destructors.into_iter().rev() {
+                        self.notifications
+                            .push(Notification::Mem(notify, mem, user_data));
+                    }
+                    if 0 != object.parent {
+                        self.release_mem(object.parent);
+                    }
+                    self.release_context(object.context);
+                }
+            }
+        }
+    }
+
+    fn release_program(&mut self, program: usize) {
+        if let Some(object) = self.programs.get_mut(&program) {
+            object.ref_count -= 1;
+            if 0 == object.ref_count {
+                let context = object.context;
+                self.programs.remove(&program);
+                self.release_context(context);
+            }
+        }
+    }
+
+    fn release_kernel(&mut self, kernel: usize) {
+        if let Some(object) = self.kernels.get_mut(&kernel) {
+            object.ref_count -= 1;
+            if 0 == object.ref_count {
+                let program = object.program;
+                self.kernels.remove(&kernel);
+                self.release_program(program);
+            }
+        }
+    }
+
+    fn retain_event(&mut self, event: usize) {
+        if let Some(event) = self.events.get_mut(&event) {
+            event.ref_count += 1;
+        }
+    }
+
+    fn release_event(&mut self, event: usize) {
+        if let Some(object) = self.events.get_mut(&event) {
+            object.ref_count -= 1;
+            if 0 == object.ref_count {
+                let context = object.context;
+                self.events.remove(&event);
+                self.release_context(context);
+            }
+        }
+    }
+
+    fn new_event(
+        &mut self,
+        context: usize,
+        queue: usize,
+        command_type: cl_command_type,
+        status: cl_int,
+        profiling: bool,
+    ) -> usize {
+        let event = self.new_id();
+        let now = self.now();
+        self.retain_context(context);
+        self.events.insert(
+            event,
+            Event {
+                ref_count: 1,
+                context,
+                queue,
+                command_type,
+                status,
+                callbacks: Vec::new(),
+                profiling,
+                times: [now; 4],
+            },
+        );
+        event
+    }
+
+    /// Set the execution status of an event and queue its callbacks.
+    fn set_event_status(&mut self, event: usize, status: cl_int) {
+        let now = self.now();
+        if let Some(object) = self.events.get_mut(&event) {
+            object.status = status;
+            match status {
+                CL_SUBMITTED => object.times[1] = now,
+                CL_RUNNING => object.times[2] = now,
+                CL_QUEUED => object.times[0] = now,
+                _ => object.times[3] = now,
+            }
+            let (ready, waiting) = mem::take(&mut object.callbacks)
+                .into_iter()
+                .partition(|(callback_type, _, _)| status <= *callback_type);
+            object.callbacks = waiting;
+            for (_, notify, user_data) in ready {
+                self.notifications
+                    .push(Notification::Event(notify, event, status, user_data));
+            }
+        }
+    }
+
+    /// Get the events of an event wait list.
+    unsafe fn wait_list(
+        &self,
+        context: usize,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+    ) -> Result<Vec<usize>, cl_int> {
+        if (0 == num_events_in_wait_list) != event_wait_list.is_null() {
+            return Err(CL_INVALID_EVENT_WAIT_LIST);
+        }
+        let events = array(event_wait_list, num_events_in_wait_list as usize);
+        let mut wait_list = Vec::with_capacity(events.len());
+        for event in events {
+            let object = self.event(*event).map_err(|_| CL_INVALID_EVENT_WAIT_LIST)?;
+            if context != object.context {
+                return Err(CL_INVALID_CONTEXT);
+            }
+            wait_list.push(id(*event));
+        }
+        Ok(wait_list)
+    }
+
+    fn is_ready(&self, command: &Command) -> bool {
+        command.wait_list.iter().all(|event| {
+            self.events
+                .get(event)
+                .is_none_or(|event| CL_COMPLETE >= event.status)
+        })
+    }
+
+    /// Execute the next ready command of a queue.
+    ///
+    /// returns true if a command was executed, false otherwise.
+    fn execute_next(&mut self, queue: usize) -> bool {
+        let Some(object) = self.queues.get(&queue) else {
+            return false;
+        };
+        let count = if object.is_out_of_order() {
+            object.commands.len()
+        } else {
+            object.commands.len().min(1)
+        };
+        let Some(index) = (0..count).find(|&index| self.is_ready(&object.commands[index])) else {
+            return false;
+        };
+        let Some(command) = self
+            .queues
+            .get_mut(&queue)
+            .and_then(|queue| queue.commands.remove(index))
+        else {
+            return false;
+        };
+
+        let failed = command.wait_list.iter().any(|event| {
+            self.events
+                .get(event)
+                .is_some_and(|event| CL_COMPLETE > event.status)
+        });
+        self.set_event_status(command.event, CL_SUBMITTED);
+        self.set_event_status(command.event, CL_RUNNING);
+        let status = if failed {
+            CL_EXEC_STATUS_ERROR_FOR_EVENTS_IN_WAIT_LIST
+        } else {
+            (command.action)(self)
+        };
+        self.set_event_status(
+            command.event,
+            if CL_SUCCESS == status {
+                CL_COMPLETE
+            } else {
+                status
+            },
+        );
+
+        if let Some(object) = self.queues.get_mut(&queue) {
+            if Some(command.event) == object.barrier {
+                object.barrier = None;
+            }
+        }
+        for event in command.wait_list {
+            self.release_event(event);
+        }
+        self.release_event(command.event);
+        true
+    }
+
+    /// Execute all of the commands that are ready.
+    fn progress(&mut self) {
+        let queues: Vec<usize> = self.queues.keys().copied().collect();
+        let mut progressed = true;
+        while progressed {
+            progressed = false;
+            for queue in &queues {
+                while self.execute_next(*queue) {
+                    progressed = true;
+                }
+            }
+        }
+        self.queues
+            .retain(|_, queue| !(queue.released && queue.commands.is_empty()));
+    }
+
+    /// Add a command to a queue.
+    fn push_command(&mut self, queue: usize, mut command: Command, command_type: cl_command_type) {
+        let Some(object) = self.queues.get_mut(&queue) else {
+            return;
+        };
+        let is_barrier = CL_COMMAND_BARRIER == command_type;
+        let mut events = Vec::new();
+        if (is_barrier || CL_COMMAND_MARKER == command_type) && command.wait_list.is_empty() {
+            events.extend(object.commands.iter().map(|command| command.event));
+        } else if let Some(barrier) = object.barrier {
+            events.push(barrier);
+        }
+        if is_barrier {
+            object.barrier = Some(command.event);
+        }
+        for event in events {
+            self.retain_event(event);
+            command.wait_list.push(event);
+        }
+        if let Some(object) = self.queues.get_mut(&queue) {
+            object.commands.push_back(command);
+        }
+    }
+}
+
+/// Enqueue a command on a command queue.
+///
+/// * `prepare` - validates the command parameters and returns the action
+///   of the command.
+unsafe fn enqueue_command(
+    command_queue: cl_command_queue,
+    command_type: cl_command_type,
+    blocking: bool,
+    num_events_in_wait_list: cl_uint,
+    event_wait_list: *const cl_event,
+    event: *mut cl_event,
+    prepare: impl FnOnce(&mut State, usize) -> Result<Action, cl_int>,
+) -> cl_int {
+    let result = with_state(|state| {
+        let queue = state.queue(command_queue)?;
+        if queue.is_on_device() {
+            return Err(CL_INVALID_COMMAND_QUEUE);
+        }
+        let context = queue.context;
+        let profiling = 0 != queue.properties & CL_QUEUE_PROFILING_ENABLE;
+        let wait_list = state.wait_list(context, num_events_in_wait_list, event_wait_list)?;
+        let action = prepare(state, context)?;
+
+        let queue = id(command_queue);
+        let new_event = state.new_event(context, queue, command_type, CL_QUEUED, profiling);
+        for event in &wait_list {
+            state.retain_event(*event);
+        }
+        if blocking {
+            state.retain_event(new_event);
+        }
+        if !event.is_null() {
+            state.retain_event(new_event);
+            *event = handle(new_event);
+        }
+        let command = Command {
+            event: new_event,
+            wait_list,
+            action,
+        };
+        state.push_command(queue, command, command_type);
+        Ok(new_event)
+    });
+    match result {
+        Ok(new_event) if blocking => {
+            let status = wait_for(&[new_event]);
+            with_state(|state| state.release_event(new_event));
+            status
+        }
+        Ok(_) => CL_SUCCESS,
+        Err(error) => error,
+    }
+}
+
+const PLATFORM_NAME: &str = "cl3 mock platform";
+const VENDOR: &str = "cl3";
+const VERSION: &str = "OpenCL 3.0 cl3 mock";
+const OPENCL_C_VERSION: &str = "OpenCL C 1.2 cl3 mock";
+const EXTENSIONS: &[&str] = &[
+    "cl_khr_byte_addressable_store",
+    "cl_khr_fp64",
+    "cl_khr_il_program",
+];
+const OPENCL_C_FEATURES: &[&str] = &[
+    "__opencl_c_images",
+    "__opencl_c_read_write_images",
+    "__opencl_c_fp64",
+    "__opencl_c_int64",
+];
+const IL_VERSION: &str = "SPIR-V_1.0";
+const MAX_WORK_GROUP_SIZE: usize = 256;
+const MAX_WORK_ITEM_SIZES: [usize; 3] = [256, 256, 256];
+const MAX_MEM_ALLOC_SIZE: usize = 256 * 1024 * 1024;
+const LOCAL_MEM_SIZE: usize = 32 * 1024;
+const PREFERRED_WORK_GROUP_SIZE_MULTIPLE: usize = 8;
+const IMAGE2D_MAX_SIZE: usize = 16384;
+const IMAGE3D_MAX_SIZE: usize = 2048;
+const IMAGE_MAX_ARRAY_SIZE: usize = 2048;
+const IMAGE_MAX_BUFFER_SIZE: usize = 65536;
+
+const fn make_version(major: cl_version, minor: cl_version, patch: cl_version) -> cl_version {
+    (major << 22) | (minor << 12) | patch
+}
+
+const VERSION_1_0: cl_version = make_version(1, 0, 0);
+const VERSION_3_0: cl_version = make_version(3, 0, 0);
+
+fn extensions_with_version() -> Info {
+    let extensions: Vec<(cl_version, &str)> = EXTENSIONS
+        .iter()
+        .map(|extension| (VERSION_1_0, *extension))
+        .collect();
+    name_version_info(&extensions)
+}
+
+fn platform_info(param_name: cl_platform_info) -> Info {
+    match param_name {
+        CL_PLATFORM_PROFILE => str_info("FULL_PROFILE"),
+        CL_PLATFORM_VERSION => str_info(VERSION),
+        CL_PLATFORM_NAME => str_info(PLATFORM_NAME),
+        CL_PLATFORM_VENDOR => str_info(VENDOR),
+        CL_PLATFORM_EXTENSIONS => str_info(&EXTENSIONS.join(" ")),
+        CL_PLATFORM_HOST_TIMER_RESOLUTION => value_info::<cl_ulong>(1),
+        CL_PLATFORM_NUMERIC_VERSION => value_info(VERSION_3_0),
+        CL_PLATFORM_EXTENSIONS_WITH_VERSION => extensions_with_version(),
+        _ => Err(CL_INVALID_VALUE),
+    }
+}
+
+#[allow(clippy::match_same_arms)]
+fn device_info(param_name: cl_device_info) -> Info {
+    const CL_TRUE_VALUE: cl_bool = CL_TRUE;
+    const CL_FALSE_VALUE: cl_bool = CL_FALSE;
+    match param_name {
+        CL_DEVICE_TYPE => value_info::<cl_device_type>(CL_DEVICE_TYPE_GPU),
+        CL_DEVICE_VENDOR_ID => value_info::<cl_uint>(0x1_0000),
+        CL_DEVICE_MAX_COMPUTE_UNITS => value_info::<cl_uint>(1),
+        CL_DEVICE_MAX_WORK_ITEM_DIMENSIONS => value_info::<cl_uint>(3),
+        CL_DEVICE_MAX_WORK_GROUP_SIZE => value_info(MAX_WORK_GROUP_SIZE),
+        CL_DEVICE_MAX_WORK_ITEM_SIZES => slice_info(&MAX_WORK_ITEM_SIZES),
+        CL_DEVICE_PREFERRED_VECTOR_WIDTH_CHAR | CL_DEVICE_NATIVE_VECTOR_WIDTH_CHAR => {
+            value_info::<cl_uint>(16)
+        }
+        CL_DEVICE_PREFERRED_VECTOR_WIDTH_SHORT | CL_DEVICE_NATIVE_VECTOR_WIDTH_SHORT => {
+            value_info::<cl_uint>(8)
+        }
+        CL_DEVICE_PREFERRED_VECTOR_WIDTH_INT
+        | CL_DEVICE_NATIVE_VECTOR_WIDTH_INT
+        | CL_DEVICE_PREFERRED_VECTOR_WIDTH_FLOAT
+        | CL_DEVICE_NATIVE_VECTOR_WIDTH_FLOAT => value_info::<cl_uint>(4),
+        CL_DEVICE_PREFERRED_VECTOR_WIDTH_LONG
+        | CL_DEVICE_NATIVE_VECTOR_WIDTH_LONG
+        | CL_DEVICE_PREFERRED_VECTOR_WIDTH_DOUBLE
+        | CL_DEVICE_NATIVE_VECTOR_WIDTH_DOUBLE => value_info::<cl_uint>(2),
+        CL_DEVICE_PREFERRED_VECTOR_WIDTH_HALF
+        | CL_DEVICE_NATIVE_VECTOR_WIDTH_HALF
+        | CL_DEVICE_PARTITION_MAX_SUB_DEVICES
+        | CL_DEVICE_PIPE_MAX_ACTIVE_RESERVATIONS
+        | CL_DEVICE_PIPE_MAX_PACKET_SIZE
+        | CL_DEVICE_MAX_PIPE_ARGS
+        | CL_DEVICE_PREFERRED_PLATFORM_ATOMIC_ALIGNMENT
+        | CL_DEVICE_PREFERRED_GLOBAL_ATOMIC_ALIGNMENT
+        | CL_DEVICE_PREFERRED_LOCAL_ATOMIC_ALIGNMENT
+        | CL_DEVICE_MAX_NUM_SUB_GROUPS => value_info::<cl_uint>(0),
+        CL_DEVICE_MAX_CLOCK_FREQUENCY => value_info::<cl_uint>(1000),
+        CL_DEVICE_ADDRESS_BITS => value_info::<cl_uint>(64),
+        CL_DEVICE_MAX_READ_IMAGE_ARGS => value_info::<cl_uint>(128),
+        CL_DEVICE_MAX_WRITE_IMAGE_ARGS | CL_DEVICE_MAX_READ_WRITE_IMAGE_ARGS => {
+            value_info::<cl_uint>(64)
+        }
+        CL_DEVICE_MAX_MEM_ALLOC_SIZE => value_info(MAX_MEM_ALLOC_SIZE as cl_ulong),
+        CL_DEVICE_IMAGE2D_MAX_WIDTH | CL_DEVICE_IMAGE2D_MAX_HEIGHT => value_info(IMAGE2D_MAX_SIZE),
+        CL_DEVICE_IMAGE3D_MAX_WIDTH
+        | CL_DEVICE_IMAGE3D_MAX_HEIGHT
+        | CL_DEVICE_IMAGE3D_MAX_DEPTH => value_info(IMAGE3D_MAX_SIZE),
+        CL_DEVICE_IMAGE_MAX_BUFFER_SIZE => value_info(IMAGE_MAX_BUFFER_SIZE),
+        CL_DEVICE_IMAGE_MAX_ARRAY_SIZE => value_info(IMAGE_MAX_ARRAY_SIZE),
+        CL_DEVICE_IMAGE_SUPPORT
+        | CL_DEVICE_ENDIAN_LITTLE
+        | CL_DEVICE_AVAILABLE
+        | CL_DEVICE_COMPILER_AVAILABLE
+        | CL_DEVICE_LINKER_AVAILABLE
+        | CL_DEVICE_HOST_UNIFIED_MEMORY
+        | CL_DEVICE_PREFERRED_INTEROP_USER_SYNC
+        | CL_DEVICE_NON_UNIFORM_WORK_GROUP_SUPPORT => value_info(CL_TRUE_VALUE),
+        CL_DEVICE_ERROR_CORRECTION_SUPPORT
+        | CL_DEVICE_SUB_GROUP_INDEPENDENT_FORWARD_PROGRESS
+        | CL_DEVICE_WORK_GROUP_COLLECTIVE_FUNCTIONS_SUPPORT
+        | CL_DEVICE_GENERIC_ADDRESS_SPACE_SUPPORT
+        | CL_DEVICE_PIPE_SUPPORT => value_info(CL_FALSE_VALUE),
+        CL_DEVICE_MAX_PARAMETER_SIZE => value_info::<size_t>(1024),
+        CL_DEVICE_MAX_SAMPLERS => value_info::<cl_uint>(16),
+        CL_DEVICE_MEM_BASE_ADDR_ALIGN => value_info((ALIGNMENT * 8) as cl_uint),
+        CL_DEVICE_MIN_DATA_TYPE_ALIGN_SIZE => value_info(ALIGNMENT as cl_uint),
+        CL_DEVICE_SINGLE_FP_CONFIG => value_info::<cl_device_fp_config>(
+            CL_FP_DENORM | CL_FP_INF_NAN | CL_FP_ROUND_TO_NEAREST | CL_FP_FMA,
+        ),
+        CL_DEVICE_DOUBLE_FP_CONFIG => value_info::<cl_device_fp_config>(
+            CL_FP_DENORM
+                | CL_FP_INF_NAN
+                | CL_FP_ROUND_TO_NEAREST
+                | CL_FP_ROUND_TO_ZERO
+                | CL_FP_ROUND_TO_INF
+                | CL_FP_FMA,
+        ),
+        CL_DEVICE_GLOBAL_MEM_CACHE_TYPE => {
+            value_info::<cl_device_mem_cache_type>(CL_READ_WRITE_CACHE)
+        }
+        CL_DEVICE_GLOBAL_MEM_CACHELINE_SIZE => value_info::<cl_uint>(64),
+        CL_DEVICE_GLOBAL_MEM_CACHE_SIZE => value_info::<cl_ulong>(1024 * 1024),
+        CL_DEVICE_GLOBAL_MEM_SIZE => value_info::<cl_ulong>(1024 * 1024 * 1024),
+        CL_DEVICE_MAX_CONSTANT_BUFFER_SIZE => value_info::<cl_ulong>(64 * 1024),
+        CL_DEVICE_MAX_CONSTANT_ARGS => value_info::<cl_uint>(8),
+        CL_DEVICE_LOCAL_MEM_TYPE => value_info::<cl_device_local_mem_type>(CL_LOCAL),
+        CL_DEVICE_LOCAL_MEM_SIZE => value_info(LOCAL_MEM_SIZE as cl_ulong),
+        CL_DEVICE_PROFILING_TIMER_RESOLUTION => value_info::<size_t>(1),
+        CL_DEVICE_EXECUTION_CAPABILITIES => {
+            value_info::<cl_device_exec_capabilities>(CL_EXEC_KERNEL | CL_EXEC_NATIVE_KERNEL)
+        }
+        CL_DEVICE_QUEUE_ON_HOST_PROPERTIES | CL_DEVICE_QUEUE_ON_DEVICE_PROPERTIES => {
+            value_info::<cl_command_queue_properties>(
+                CL_QUEUE_OUT_OF_ORDER_EXEC_MODE_ENABLE | CL_QUEUE_PROFILING_ENABLE,
+            )
+        }
+        CL_DEVICE_NAME => str_info("cl3 mock device"),
+        CL_DEVICE_VENDOR => str_info(VENDOR),
+        CL_DRIVER_VERSION => str_info("1.0"),
+        CL_DEVICE_PROFILE => str_info("FULL_PROFILE"),
+        CL_DEVICE_VERSION => str_info(VERSION),
+        CL_DEVICE_OPENCL_C_VERSION => str_info(OPENCL_C_VERSION),
+        CL_DEVICE_EXTENSIONS => str_info(&EXTENSIONS.join(" ")),
+        CL_DEVICE_PLATFORM => value_info(PLATFORM),
+        CL_DEVICE_PARENT_DEVICE => value_info::<usize>(0),
+        CL_DEVICE_BUILT_IN_KERNELS => str_info(BUILT_IN_KERNELS),
+        CL_DEVICE_PARTITION_PROPERTIES => slice_info::<cl_device_partition_property>(&[0]),
+        CL_DEVICE_PARTITION_AFFINITY_DOMAIN => value_info::<cl_device_affinity_domain>(0),
+        CL_DEVICE_PARTITION_TYPE => slice_info::<cl_device_partition_property>(&[]),
+        CL_DEVICE_REFERENCE_COUNT => value_info::<cl_uint>(1),
+        CL_DEVICE_PRINTF_BUFFER_SIZE => value_info::<size_t>(1024 * 1024),
+        CL_DEVICE_IMAGE_PITCH_ALIGNMENT | CL_DEVICE_IMAGE_BASE_ADDRESS_ALIGNMENT => {
+            value_info::<cl_uint>(1)
+        }
+        CL_DEVICE_MAX_GLOBAL_VARIABLE_SIZE => value_info::<size_t>(64 * 1024),
+        CL_DEVICE_QUEUE_ON_DEVICE_PREFERRED_SIZE => value_info::<cl_uint>(16 * 1024),
+        CL_DEVICE_QUEUE_ON_DEVICE_MAX_SIZE => value_info::<cl_uint>(256 * 1024),
+        CL_DEVICE_MAX_ON_DEVICE_QUEUES => value_info::<cl_uint>(1),
+        CL_DEVICE_MAX_ON_DEVICE_EVENTS => value_info::<cl_uint>(1024),
+        CL_DEVICE_SVM_CAPABILITIES => value_info::<cl_device_svm_capabilities>(
+            CL_DEVICE_SVM_COARSE_GRAIN_BUFFER | CL_DEVICE_SVM_FINE_GRAIN_BUFFER,
+        ),
+        CL_DEVICE_GLOBAL_VARIABLE_PREFERRED_TOTAL_SIZE => value_info::<size_t>(1024 * 1024),
+        CL_DEVICE_IL_VERSION => str_info(IL_VERSION),
+        CL_DEVICE_NUMERIC_VERSION => value_info(VERSION_3_0),
+        CL_DEVICE_EXTENSIONS_WITH_VERSION => extensions_with_version(),
+        CL_DEVICE_ILS_WITH_VERSION => name_version_info(&[(VERSION_1_0, "SPIR-V")]),
+        CL_DEVICE_BUILT_IN_KERNELS_WITH_VERSION => {
+            name_version_info(&[(VERSION_1_0, BUILT_IN_KERNELS)])
+        }
+        CL_DEVICE_ATOMIC_MEMORY_CAPABILITIES => value_info::<cl_device_atomic_capabilities>(
+            CL_DEVICE_ATOMIC_ORDER_RELAXED | CL_DEVICE_ATOMIC_SCOPE_WORK_GROUP,
+        ),
+        CL_DEVICE_ATOMIC_FENCE_CAPABILITIES => value_info::<cl_device_atomic_capabilities>(
+            CL_DEVICE_ATOMIC_ORDER_RELAXED
+                | CL_DEVICE_ATOMIC_ORDER_ACQ_REL
+                | CL_DEVICE_ATOMIC_SCOPE_WORK_GROUP,
+        ),
+        CL_DEVICE_OPENCL_C_ALL_VERSIONS => name_version_info(&[
+            (make_version(1, 0, 0), "OpenCL C"),
+            (make_version(1, 1, 0), "OpenCL C"),
+            (make_version(1, 2, 0), "OpenCL C"),
+            (make_version(3, 0, 0), "OpenCL C"),
+        ]),
+        CL_DEVICE_PREFERRED_WORK_GROUP_SIZE_MULTIPLE => {
+            value_info(PREFERRED_WORK_GROUP_SIZE_MULTIPLE)
+        }
+        CL_DEVICE_OPENCL_C_FEATURES => {
+            let features: Vec<(cl_version, &str)> = OPENCL_C_FEATURES
+                .iter()
+                .map(|feature| (VERSION_3_0, *feature))
+                .collect();
+            name_version_info(&features)
+        }
+        CL_DEVICE_DEVICE_ENQUEUE_CAPABILITIES => {
+            value_info::<cl_device_device_enqueue_capabilities>(CL_DEVICE_QUEUE_SUPPORTED)
+        }
+        CL_DEVICE_LATEST_CONFORMANCE_VERSION_PASSED => str_info("v2024-01-01-00"),
+        _ => Err(CL_INVALID_VALUE),
+    }
+}
+
+// Platform API
+
+pub unsafe fn clGetPlatformIDs(
+    num_entries: cl_uint,
+    platforms: *mut cl_platform_id,
+    num_platforms: *mut cl_uint,
+) -> cl_int {
+    if (0 == num_entries && !platforms.is_null())
+        || (platforms.is_null() && num_platforms.is_null())
+    {
+        return CL_INVALID_VALUE;
+    }
+    if !platforms.is_null() {
+        *platforms = handle(PLATFORM);
+    }
+    if !num_platforms.is_null() {
+        *num_platforms = 1;
+    }
+    CL_SUCCESS
+}
+
+pub unsafe fn clGetPlatformInfo(
+    platform: cl_platform_id,
+    param_name: cl_platform_info,
+    param_value_size: size_t,
+    param_value: *mut c_void,
+    param_value_size_ret: *mut size_t,
+) -> cl_int {
+    if !platform.is_null() && PLATFORM != id(platform) {
+        return CL_INVALID_PLATFORM;
+    }
+    write_info(
+        platform_info(param_name),
+        param_value_size,
+        param_value,
+        param_value_size_ret,
+    )
+}
+
+pub unsafe fn clUnloadPlatformCompiler(platform: cl_platform_id) -> cl_int {
+    if PLATFORM == id(platform) {
+        CL_SUCCESS
+    } else {
+        CL_INVALID_PLATFORM
+    }
+}
+
+// Device APIs
+
+/// Whether the mock device matches an `OpenCL` device type.
+fn match_device_type(device_type: cl_device_type) -> Result<bool, cl_int> {
+    const ALL_TYPES: cl_device_type = CL_DEVICE_TYPE_DEFAULT
+        | CL_DEVICE_TYPE_CPU
+        | CL_DEVICE_TYPE_GPU
+        | CL_DEVICE_TYPE_ACCELERATOR
+        | CL_DEVICE_TYPE_CUSTOM;
+    if CL_DEVICE_TYPE_ALL == device_type {
+        Ok(true)
+    } else if 0 == device_type || 0 != device_type & !ALL_TYPES {
+        Err(CL_INVALID_DEVICE_TYPE)
+    } else {
+        Ok(0 != device_type & (CL_DEVICE_TYPE_DEFAULT | CL_DEVICE_TYPE_GPU))
+    }
+}
+
+fn check_device(device: cl_device_id) -> Result<(), cl_int> {
+    if DEVICE == id(device) {
+        Ok(())
+    } else {
+        Err(CL_INVALID_DEVICE)
+    }
+}
+
+/// Check a device list parameter, returning the devices.
+unsafe fn device_list(
+    num_devices: cl_uint,
+    device_list: *const cl_device_id,
+) -> Result<Vec<cl_device_id>, cl_int> {
+    if (0 == num_devices) != device_list.is_null() {
+        return Err(CL_INVALID_VALUE);
+    }
+    let devices = array(device_list, num_devices as usize);
+    for device in devices {
+        check_device(*device)?;
+    }
+    Ok(devices.to_vec())
+}
+
+pub unsafe fn clGetDeviceIDs(
+    platform: cl_platform_id,
+    device_type: cl_device_type,
+    num_entries: cl_uint,
+    devices: *mut cl_device_id,
+    num_devices: *mut cl_uint,
+) -> cl_int {
+    if !platform.is_null() && PLATFORM != id(platform) {
+        return CL_INVALID_PLATFORM;
+    }
+    if (0 == num_entries && !devices.is_null()) || (devices.is_null() && num_devices.is_null()) {
+        return CL_INVALID_VALUE;
+    }
+    match match_device_type(device_type) {
+        Ok(true) => {
+            if !devices.is_null() {
+                *devices = handle(DEVICE);
+            }
+            if !num_devices.is_null() {
+                *num_devices = 1;
+            }
+            CL_SUCCESS
+        }
+        Ok(false) => {
+            if !num_devices.is_null() {
+                *num_devices = 0;
+            }
+            CL_DEVICE_NOT_FOUND
+        }
+        Err(error) => error,
+    }
+}
+
+pub unsafe fn clGetDeviceInfo(
+    device: cl_device_id,
+    param_name: cl_device_info,
+    param_value_size: size_t,
+    param_value: *mut c_void,
+    param_value_size_ret: *mut size_t,
+) -> cl_int {
+    let info = check_device(device).and_then(|()| device_info(param_name));
+    write_info(info, param_value_size, param_value, param_value_size_ret)
+}
+
+pub unsafe fn clCreateSubDevices(
+    in_device: cl_device_id,
+    properties: *const cl_device_partition_property,
+    _num_devices: cl_uint,
+    _out_devices: *mut cl_device_id,
+    _num_devices_ret: *mut cl_uint,
+) -> cl_int {
+    if let Err(error) = check_device(in_device) {
+        error
+    } else if properties.is_null() {
+        CL_INVALID_VALUE
+    } else {
+        // The mock device cannot be partitioned
+        CL_INVALID_VALUE
+    }
+}
+
+pub unsafe fn clRetainDevice(device: cl_device_id) -> cl_int {
+    check_device(device).err().unwrap_or(CL_SUCCESS)
+}
+
+pub unsafe fn clReleaseDevice(device: cl_device_id) -> cl_int {
+    check_device(device).err().unwrap_or(CL_SUCCESS)
+}
+
+pub unsafe fn clSetDefaultDeviceCommandQueue(
+    context: cl_context,
+    device: cl_device_id,
+    command_queue: cl_command_queue,
+) -> cl_int {
+    with_state(|state| {
+        state.context(context)?;
+        check_device(device)?;
+        let queue = state.queue(command_queue)?;
+        if id(context) != queue.context || !queue.is_on_device() {
+            return Err(CL_INVALID_COMMAND_QUEUE);
+        }
+        state.default_device_queue = id(command_queue);
+        Ok(())
+    })
+    .err()
+    .unwrap_or(CL_SUCCESS)
+}
+
+pub unsafe fn clGetDeviceAndHostTimer(
+    device: cl_device_id,
+    device_timestamp: *mut cl_ulong,
+    host_timestamp: *mut cl_ulong,
+) -> cl_int {
+    if let Err(error) = check_device(device) {
+        return error;
+    }
+    if device_timestamp.is_null() || host_timestamp.is_null() {
+        return CL_INVALID_VALUE;
+    }
+    let now = lock().now();
+    *device_timestamp = now;
+    *host_timestamp = now;
+    CL_SUCCESS
+}
+
+pub unsafe fn clGetHostTimer(device: cl_device_id, host_timestamp: *mut cl_ulong) -> cl_int {
+    if let Err(error) = check_device(device) {
+        return error;
+    }
+    if host_timestamp.is_null() {
+        return CL_INVALID_VALUE;
+    }
+    *host_timestamp = lock().now();
+    CL_SUCCESS
+}
+
+// Context APIs
+
+unsafe fn create_context(
+    properties: *const cl_context_properties,
+    pfn_notify: Option<ContextNotify>,
+    user_data: *mut c_void,
+) -> Result<usize, cl_int> {
+    if pfn_notify.is_none() && !user_data.is_null() {
+        return Err(CL_INVALID_VALUE);
+    }
+    let properties = properties_list(properties);
+    for pair in properties.chunks(2).filter(|pair| 2 == pair.len()) {
+        match pair[0] {
+            CL_CONTEXT_PLATFORM => {
+                if PLATFORM as cl_context_properties != pair[1] {
+                    return Err(CL_INVALID_PLATFORM);
+                }
+            }
+            CL_CONTEXT_INTEROP_USER_SYNC => {}
+            _ => return Err(CL_INVALID_PROPERTY),
+        }
+    }
+    Ok(with_state(|state| {
+        let context = state.new_id();
+        state.contexts.insert(
+            context,
+            Context {
+                ref_count: 1,
+                properties,
+                destructors: Vec::new(),
+            },
+        );
+        context
+    }))
+}
+
+pub unsafe fn clCreateContext(
+    properties: *const cl_context_properties,
+    num_devices: cl_uint,
+    devices: *const cl_device_id,
+    pfn_notify: Option<ContextNotify>,
+    user_data: *mut c_void,
+    errcode_ret: *mut cl_int,
+) -> cl_context {
+    let result = if 0 == num_devices || devices.is_null() {
+        Err(CL_INVALID_VALUE)
+    } else {
+        device_list(num_devices, devices)
+            .and_then(|_| create_context(properties, pfn_notify, user_data))
+    };
+    object_result(result, errcode_ret)
+}
+
+pub unsafe fn clCreateContextFromType(
+    properties: *const cl_context_properties,
+    device_type: cl_device_type,
+    pfn_notify: Option<ContextNotify>,
+    user_data: *mut c_void,
+    errcode_ret: *mut cl_int,
+) -> cl_context {
+    let result = match match_device_type(device_type) {
+        Ok(true) => create_context(properties, pfn_notify, user_data),
+        Ok(false) => Err(CL_DEVICE_NOT_FOUND),
+        Err(error) => Err(error),
+    };
+    object_result(result, errcode_ret)
+}
+
+pub unsafe fn clRetainContext(context: cl_context) -> cl_int {
+    with_state(|state| {
+        state.context_mut(context)?.ref_count += 1;
+        Ok(())
+    })
+    .err()
+    .unwrap_or(CL_SUCCESS)
+}
+
+pub unsafe fn clReleaseContext(context: cl_context) -> cl_int {
+    with_state(|state| {
+        state.context(context)?;
+        state.release_context(id(context));
+        Ok(())
+    })
+    .err()
+    .unwrap_or(CL_SUCCESS)
+}
+
+pub unsafe fn clGetContextInfo(
+    context: cl_context,
+    param_name: cl_context_info,
+    param_value_size: size_t,
+    param_value: *mut c_void,
+    param_value_size_ret: *mut size_t,
+) -> cl_int {
+    let info = with_state(|state| {
+        let context = state.context(context)?;
+        match param_name {
+            CL_CONTEXT_REFERENCE_COUNT => value_info(context.ref_count),
+            CL_CONTEXT_NUM_DEVICES => value_info::<cl_uint>(1),
+            CL_CONTEXT_DEVICES => value_info(DEVICE),
+            CL_CONTEXT_PROPERTIES => slice_info(&context.properties),
+            _ => Err(CL_INVALID_VALUE),
+        }
+    });
+    write_info(info, param_value_size, param_value, param_value_size_ret)
+}
+
+pub unsafe fn clSetContextDestructorCallback(
+    context: cl_context,
+    pfn_notify: Option<ContextDestructor>,
+    user_data: *mut c_void,
+) -> cl_int {
+    let Some(notify) = pfn_notify else {
+        return CL_INVALID_VALUE;
+    };
+    with_state(|state| {
+        state
+            .context_mut(context)?
+            .destructors
+            .push((notify, user_data));
+        Ok(())
+    })
+    .err()
+    .unwrap_or(CL_SUCCESS)
+}
+
+// Command Queue APIs
+
+fn create_command_queue(
+    context: cl_context,
+    device: cl_device_id,
+    properties: cl_command_queue_properties,
+    properties_array: Vec<cl_queue_properties>,
+    size: Option<cl_uint>,
+) -> Result<usize, cl_int> {
+    const VALID_PROPERTIES: cl_command_queue_properties = CL_QUEUE_OUT_OF_ORDER_EXEC_MODE_ENABLE
+        | CL_QUEUE_PROFILING_ENABLE
+        | CL_QUEUE_ON_DEVICE
+        | CL_QUEUE_ON_DEVICE_DEFAULT;
+    if 0 != properties & !VALID_PROPERTIES {
+        return Err(CL_INVALID_VALUE);
+    }
+    let on_device = 0 != properties & CL_QUEUE_ON_DEVICE;
+    if (on_device && 0 == properties & CL_QUEUE_OUT_OF_ORDER_EXEC_MODE_ENABLE)
+        || (!on_device && (0 != properties & CL_QUEUE_ON_DEVICE_DEFAULT || size.is_some()))
+    {
+        return Err(CL_INVALID_VALUE);
+    }
+    with_state(|state| {
+        state.context(context)?;
+        check_device(device)?;
+        let queue = state.new_id();
+        state.retain_context(id(context));
+        state.queues.insert(
+            queue,
+            Queue {
+                ref_count: 1,
+                context: id(context),
+                properties,
+                properties_array,
+                size: size.unwrap_or(if on_device { 16 * 1024 } else { 0 }),
+                commands: VecDeque::new(),
+                barrier: None,
+                released: false,
+            },
+        );
+        if 0 != properties & CL_QUEUE_ON_DEVICE_DEFAULT {
+            state.default_device_queue = queue;
+        }
+        Ok(queue)
+    })
+}
+
+pub unsafe fn clCreateCommandQueue(
+    context: cl_context,
+    device: cl_device_id,
+    properties: cl_command_queue_properties,
+    errcode_ret: *mut cl_int,
+) -> cl_command_queue {
+    let result = if 0 == properties & (CL_QUEUE_ON_DEVICE | CL_QUEUE_ON_DEVICE_DEFAULT) {
+        create_command_queue(context, device, properties, Vec::new(), None)
+    } else {
+        Err(CL_INVALID_VALUE)
+    };
+    object_result(result, errcode_ret)
+}
+
+pub unsafe fn clCreateCommandQueueWithProperties(
+    context: cl_context,
+    device: cl_device_id,
+    properties: *const cl_queue_properties,
+    errcode_ret: *mut cl_int,
+) -> cl_command_queue {
+    let properties_array = properties_list(properties);
+    let mut queue_properties = 0;
+    let mut size = None;
+    let mut result = Ok(());
+    for pair in properties_array.chunks(2).filter(|pair| 2 == pair.len()) {
+        match pair[0] {
+            name if u64::from(CL_QUEUE_PROPERTIES) == name => queue_properties = pair[1],
+            name if u64::from(CL_QUEUE_SIZE) == name => size = Some(pair[1] as cl_uint),
+            _ => result = Err(CL_INVALID_VALUE),
+        }
+    }
+    let result = result.and_then(|()| {
+        create_command_queue(context, device, queue_properties, properties_array, size)
+    });
+    object_result(result, errcode_ret)
+}
+
+pub unsafe fn clRetainCommandQueue(command_queue: cl_command_queue) -> cl_int {
+    with_state(|state| {
+        state.queue_mut(command_queue)?.ref_count += 1;
+        Ok(())
+    })
+    .err()
+    .unwrap_or(CL_SUCCESS)
+}
+
+pub unsafe fn clReleaseCommandQueue(command_queue: cl_command_queue) -> cl_int {
+    with_state(|state| {
+        state.queue(command_queue)?;
+        state.release_queue(id(command_queue));
+        Ok(())
+    })
+    .err()
+    .unwrap_or(CL_SUCCESS)
+}
+
+pub unsafe fn clGetCommandQueueInfo(
+    command_queue: cl_command_queue,
+    param_name: cl_command_queue_info,
+    param_value_size: size_t,
+    param_value: *mut c_void,
+    param_value_size_ret: *mut size_t,
+) -> cl_int {
+    let info = with_state(|state| {
+        let queue = state.queue(command_queue)?;
+        match param_name {
+            CL_QUEUE_CONTEXT => value_info(queue.context),
+            CL_QUEUE_DEVICE => value_info(DEVICE),
+            CL_QUEUE_REFERENCE_COUNT => value_info(queue.ref_count),
+            CL_QUEUE_PROPERTIES => value_info(queue.properties),
+            CL_QUEUE_SIZE if queue.is_on_device() => value_info(queue.size),
+            CL_QUEUE_SIZE => Err(CL_INVALID_COMMAND_QUEUE),
+            CL_QUEUE_DEVICE_DEFAULT => value_info(state.default_device_queue),
+            CL_QUEUE_PROPERTIES_ARRAY => slice_info(&queue.properties_array),
+            _ => Err(CL_INVALID_VALUE),
+        }
+    });
+    write_info(info, param_value_size, param_value, param_value_size_ret)
+}
+
+pub unsafe fn clFlush(command_queue: cl_command_queue) -> cl_int {
+    with_state(|state| state.queue(command_queue).map(|_| ()))
+        .err()
+        .unwrap_or(CL_SUCCESS)
+}
+
+pub unsafe fn clFinish(command_queue: cl_command_queue) -> cl_int {
+    if let Err(error) = with_state(|state| state.queue(command_queue).map(|_| ())) {
+        return error;
+    }
+    let mut state = lock();
+    while state
+        .queues
+        .get(&id(command_queue))
+        .is_some_and(|queue| !queue.commands.is_empty())
+    {
+        state = COMPLETED
+            .wait(state)
+            .unwrap_or_else(PoisonError::into_inner);
+    }
+    drop(state);
+    CL_SUCCESS
+}
+
+// Event Object APIs
+
+pub unsafe fn clWaitForEvents(num_events: cl_uint, event_list: *const cl_event) -> cl_int {
+    if 0 == num_events || event_list.is_null() {
+        return CL_INVALID_VALUE;
+    }
+    let events = array(event_list, num_events as usize);
+    let result = with_state(|state| {
+        let context = state.event(events[0])?.context;
+        for event in events {
+            if context != state.event(*event)?.context {
+                return Err(CL_INVALID_CONTEXT);
+            }
+        }
+        Ok(events.iter().map(|event| id(*event)).collect::<Vec<_>>())
+    });
+    match result {
+        Ok(events) => wait_for(&events),
+        Err(error) => error,
+    }
+}
+
+pub unsafe fn clGetEventInfo(
+    event: cl_event,
+    param_name: cl_event_info,
+    param_value_size: size_t,
+    param_value: *mut c_void,
+    param_value_size_ret: *mut size_t,
+) -> cl_int {
+    let info = with_state(|state| {
+        let event = state.event(event)?;
+        match param_name {
+            CL_EVENT_COMMAND_QUEUE => value_info(event.queue),
+            CL_EVENT_COMMAND_TYPE => value_info(event.command_type),
+            CL_EVENT_REFERENCE_COUNT => value_info(event.ref_count),
+            CL_EVENT_COMMAND_EXECUTION_STATUS => value_info(event.status),
+            CL_EVENT_CONTEXT => value_info(event.context),
+            _ => Err(CL_INVALID_VALUE),
+        }
+    });
+    write_info(info, param_value_size, param_value, param_value_size_ret)
+}
+
+pub unsafe fn clCreateUserEvent(context: cl_context, errcode_ret: *mut cl_int) -> cl_event {
+    let result = with_state(|state| {
+        state.context(context)?;
+        Ok(state.new_event(id(context), 0, CL_COMMAND_USER, CL_SUBMITTED, false))
+    });
+    object_result(result, errcode_ret)
+}
+
+pub unsafe fn clRetainEvent(event: cl_event) -> cl_int {
+    with_state(|state| {
+        state.event_mut(event)?.ref_count += 1;
+        Ok(())
+    })
+    .err()
+    .unwrap_or(CL_SUCCESS)
+}
+
+pub unsafe fn clReleaseEvent(event: cl_event) -> cl_int {
+    with_state(|state| {
+        state.event(event)?;
+        state.release_event(id(event));
+        Ok(())
+    })
+    .err()
+    .unwrap_or(CL_SUCCESS)
+}
+
+pub unsafe fn clSetUserEventStatus(event: cl_event, execution_status: cl_int) -> cl_int {
+    if CL_COMPLETE < execution_status {
+        return CL_INVALID_VALUE;
+    }
+    with_state(|state| {
+        let object = state.event(event)?;
+        if CL_COMMAND_USER != object.command_type {
+            return Err(CL_INVALID_EVENT);
+        }
+        if CL_SUBMITTED != object.status {
+            return Err(CL_INVALID_OPERATION);
+        }
+        state.set_event_status(id(event), execution_status);
+        Ok(())
+    })
+    .err()
+    .unwrap_or(CL_SUCCESS)
+}
+
+pub unsafe fn clSetEventCallback(
+    event: cl_event,
+    command_exec_callback_type: cl_int,
+    pfn_notify: Option<EventNotify>,
+    user_data: *mut c_void,
+) -> cl_int {
+    let Some(notify) = pfn_notify else {
+        return CL_INVALID_VALUE;
+    };
+    if !matches!(
+        command_exec_callback_type,
+        CL_SUBMITTED | CL_RUNNING | CL_COMPLETE
+    ) {
+        return CL_INVALID_VALUE;
+    }
+    with_state(|state| {
+        let object = state.event_mut(event)?;
+        if object.status <= command_exec_callback_type {
+            let status = object.status;
+            state
+                .notifications
+                .push(Notification::Event(notify, id(event), status, user_data));
+        } else {
+            object
+                .callbacks
+                .push((command_exec_callback_type, notify, user_data));
+        }
+        Ok(())
+    })
+    .err()
+    .unwrap_or(CL_SUCCESS)
+}
+
+// Profiling APIs
+
+pub unsafe fn clGetEventProfilingInfo(
+    event: cl_event,
+    param_name: cl_profiling_info,
+    param_value_size: size_t,
+    param_value: *mut c_void,
+    param_value_size_ret: *mut size_t,
+) -> cl_int {
+    let info = with_state(|state| {
+        let event = state.event(event)?;
+        if !event.profiling || CL_COMPLETE != event.status {
+            return Err(CL_PROFILING_INFO_NOT_AVAILABLE);
+        }
+        match param_name {
+            CL_PROFILING_COMMAND_QUEUED => value_info(event.times[0]),
+            CL_PROFILING_COMMAND_SUBMIT => value_info(event.times[1]),
+            CL_PROFILING_COMMAND_START => value_info(event.times[2]),
+            CL_PROFILING_COMMAND_END | CL_PROFILING_COMMAND_COMPLETE => value_info(event.times[3]),
+            _ => Err(CL_INVALID_VALUE),
+        }
+    });
+    write_info(info, param_value_size, param_value, param_value_size_ret)
+}
+
+// Memory Object APIs
+
+const ACCESS_FLAGS: cl_mem_flags = CL_MEM_READ_WRITE | CL_MEM_WRITE_ONLY | CL_MEM_READ_ONLY;
+const HOST_PTR_FLAGS: cl_mem_flags =
+    CL_MEM_USE_HOST_PTR | CL_MEM_ALLOC_HOST_PTR | CL_MEM_COPY_HOST_PTR;
+const HOST_ACCESS_FLAGS: cl_mem_flags =
+    CL_MEM_HOST_WRITE_ONLY | CL_MEM_HOST_READ_ONLY | CL_MEM_HOST_NO_ACCESS;
+
+/// Check memory object flags, returning the flags with the default access flag.
+fn check_mem_flags(flags: cl_mem_flags, host_ptr: *mut c_void) -> Result<cl_mem_flags, cl_int> {
+    const VALID_FLAGS: cl_mem_flags =
+        ACCESS_FLAGS | HOST_PTR_FLAGS | HOST_ACCESS_FLAGS | CL_MEM_KERNEL_READ_AND_WRITE;
+    if 0 != flags & !VALID_FLAGS
+        || 1 < (flags & ACCESS_FLAGS).count_ones()
+        || 1 < (flags & HOST_ACCESS_FLAGS).count_ones()
+        || (0 != flags & CL_MEM_USE_HOST_PTR
+            && 0 != flags & (CL_MEM_ALLOC_HOST_PTR | CL_MEM_COPY_HOST_PTR))
+    {
+        return Err(CL_INVALID_VALUE);
+    }
+    let needs_host_ptr = 0 != flags & (CL_MEM_USE_HOST_PTR | CL_MEM_COPY_HOST_PTR);
+    if needs_host_ptr == host_ptr.is_null() {
+        return Err(CL_INVALID_HOST_PTR);
+    }
+    Ok(if 0 == flags & ACCESS_FLAGS {
+        flags | CL_MEM_READ_WRITE
+    } else {
+        flags
+    })
+}
+
+impl State {
+    /// Find the SVM allocation containing a pointer.
+    fn find_svm(&self, pointer: *const c_void) -> Option<(&Svm, usize)> {
+        let address = pointer as usize;
+        self.svm.iter().find_map(|(start, svm)| {
+            (*start <= address && address < start + svm.size).then_some((svm, address - start))
+        })
+    }
+
+    /// Get the storage of a new memory object.
+    unsafe fn new_storage(
+        &self,
+        flags: cl_mem_flags,
+        size: usize,
+        host_ptr: *mut c_void,
+    ) -> Result<(Storage, bool), cl_int> {
+        if 0 == flags & CL_MEM_USE_HOST_PTR {
+            let allocation = Allocation::new(size, ALIGNMENT)?;
+            if 0 != flags & CL_MEM_COPY_HOST_PTR {
+                ptr::copy_nonoverlapping(host_ptr.cast::<u8>(), allocation.ptr, size);
+            }
+            let ptr = allocation.ptr;
+            Ok((
+                Storage {
+                    allocation: Some(allocation),
+                    ptr,
+                },
+                false,
+            ))
+        } else {
+            let allocation = self
+                .find_svm(host_ptr)
+                .map(|(svm, _)| svm.allocation.clone());
+            let uses_svm_pointer = allocation.is_some();
+            Ok((
+                Storage {
+                    allocation,
+                    ptr: host_ptr.cast::<u8>(),
+                },
+                uses_svm_pointer,
+            ))
+        }
+    }
+
+    fn insert_mem(&mut self, mem: Mem) -> usize {
+        let object = self.new_id();
+        self.retain_context(mem.context);
+        if 0 != mem.parent {
+            self.retain_mem(mem.parent);
+        }
+        self.mems.insert(object, mem);
+        object
+    }
+}
+
+unsafe fn create_buffer(
+    context: cl_context,
+    properties: Vec<cl_mem_properties>,
+    flags: cl_mem_flags,
+    size: size_t,
+    host_ptr: *mut c_void,
+) -> Result<usize, cl_int> {
+    with_state(|state| {
+        state.context(context)?;
+        let flags = check_mem_flags(flags, host_ptr)?;
+        if 0 == size || MAX_MEM_ALLOC_SIZE < size {
+            return Err(CL_INVALID_BUFFER_SIZE);
+        }
+        let (storage, uses_svm_pointer) = state.new_storage(flags, size, host_ptr)?;
+        let mem = Mem {
+            ref_count: 1,
+            context: id(context),
+            object_type: CL_MEM_OBJECT_BUFFER,
+            flags,
+            size,
+            host_ptr: if 0 == flags & CL_MEM_USE_HOST_PTR {
+                ptr::null_mut()
+            } else {
+                host_ptr
+            },
+            storage,
+            parent: 0,
+            offset: 0,
+            uses_svm_pointer,
+            properties,
+            image: None,
+            maps: Vec::new(),
+            destructors: Vec::new(),
+        };
+        Ok(state.insert_mem(mem))
+    })
+}
+
+pub unsafe fn clCreateBuffer(
+    context: cl_context,
+    flags: cl_mem_flags,
+    size: size_t,
+    host_ptr: *mut c_void,
+    errcode_ret: *mut cl_int,
+) -> cl_mem {
+    object_result(
+        create_buffer(context, Vec::new(), flags, size, host_ptr),
+        errcode_ret,
+    )
+}
+
+pub unsafe fn clCreateBufferWithProperties(
+    context: cl_context,
+    properties: *const cl_mem_properties,
+    flags: cl_mem_flags,
+    size: size_t,
+    host_ptr: *mut c_void,
+    errcode_ret: *mut cl_int,
+) -> cl_mem {
+    let properties = properties_list(properties);
+    let result = if 1 < properties.len() {
+        Err(CL_INVALID_PROPERTY)
+    } else {
+        create_buffer(context, properties, flags, size, host_ptr)
+    };
+    object_result(result, errcode_ret)
+}
+
+pub unsafe fn clCreateSubBuffer(
+    buffer: cl_mem,
+    flags: cl_mem_flags,
+    buffer_create_type: cl_buffer_create_type,
+    buffer_create_info: *const c_void,
+    errcode_ret: *mut cl_int,
+) -> cl_mem {
+    let result = with_state(|state| {
+        let parent = state.mem(buffer)?;
+        if CL_MEM_OBJECT_BUFFER != parent.object_type || 0 != parent.parent {
+            return Err(CL_INVALID_MEM_OBJECT);
+        }
+        if CL_BUFFER_CREATE_TYPE_REGION != buffer_create_type || buffer_create_info.is_null() {
+            return Err(CL_INVALID_VALUE);
+        }
+        if 0 != flags & (HOST_PTR_FLAGS | !(ACCESS_FLAGS | HOST_ACCESS_FLAGS)) {
+            return Err(CL_INVALID_VALUE);
+        }
+        let access = match flags & ACCESS_FLAGS {
+            0 => parent.flags & ACCESS_FLAGS,
+            access if CL_MEM_READ_WRITE == parent.flags & ACCESS_FLAGS => access,
+            access if access == parent.flags & ACCESS_FLAGS => access,
+            _ => return Err(CL_INVALID_VALUE),
+        };
+        let host_access = match flags & HOST_ACCESS_FLAGS {
+            0 => parent.flags & HOST_ACCESS_FLAGS,
+            host_access if 0 == parent.flags & HOST_ACCESS_FLAGS => host_access,
+            host_access if host_access == parent.flags & HOST_ACCESS_FLAGS => host_access,
+            _ => return Err(CL_INVALID_VALUE),
+        };
+        let region = ptr::read_unaligned(buffer_create_info.cast::<cl_buffer_region>());
+        if 0 == region.size {
+            return Err(CL_INVALID_BUFFER_SIZE);
+        }
+        if parent.size < region.origin + region.size {
+            return Err(CL_INVALID_VALUE);
+        }
+        if 0 != region.origin % ALIGNMENT {
+            return Err(CL_MISALIGNED_SUB_BUFFER_OFFSET);
+        }
+        let mem = Mem {
+            ref_count: 1,
+            context: parent.context,
+            object_type: CL_MEM_OBJECT_BUFFER,
+            flags: access | host_access | (parent.flags & HOST_PTR_FLAGS),
+            size: region.size,
+            host_ptr: if parent.host_ptr.is_null() {
+                ptr::null_mut()
+            } else {
+                parent
+                    .host_ptr
+                    .cast::<u8>()
+                    .add(region.origin)
+                    .cast::<c_void>()
+            },
+            storage: parent.storage.at(region.origin),
+            parent: id(buffer),
+            offset: region.origin,
+            uses_svm_pointer: parent.uses_svm_pointer,
+            properties: Vec::new(),
+            image: None,
+            maps: Vec::new(),
+            destructors: Vec::new(),
+        };
+        Ok(state.insert_mem(mem))
+    });
+    object_result(result, errcode_ret)
+}
+
+/// The supported image channel orders and data types.
+const IMAGE_FORMATS: &[(cl_channel_order, &[cl_channel_type])] = &[
+    (
+        CL_R,
+        &[
+            CL_SNORM_INT8,
+            CL_SNORM_INT16,
+            CL_UNORM_INT8,
+            CL_UNORM_INT16,
+            CL_SIGNED_INT8,
+            CL_SIGNED_INT16,
+            CL_SIGNED_INT32,
+            CL_UNSIGNED_INT8,
+            CL_UNSIGNED_INT16,
+            CL_UNSIGNED_INT32,
+            CL_HALF_FLOAT,
+            CL_FLOAT,
+        ],
+    ),
+    (
+        CL_RG,
+        &[
+            CL_SNORM_INT8,
+            CL_SNORM_INT16,
+            CL_UNORM_INT8,
+            CL_UNORM_INT16,
+            CL_SIGNED_INT8,
+            CL_SIGNED_INT16,
+            CL_SIGNED_INT32,
+            CL_UNSIGNED_INT8,
+            CL_UNSIGNED_INT16,
+            CL_UNSIGNED_INT32,
+            CL_HALF_FLOAT,
+            CL_FLOAT,
+        ],
+    ),
+    (
+        CL_RGBA,
+        &[
+            CL_SNORM_INT8,
+            CL_SNORM_INT16,
+            CL_UNORM_INT8,
+            CL_UNORM_INT16,
+            CL_SIGNED_INT8,
+            CL_SIGNED_INT16,
+            CL_SIGNED_INT32,
+            CL_UNSIGNED_INT8,
+            CL_UNSIGNED_INT16,
+            CL_UNSIGNED_INT32,
+            CL_HALF_FLOAT,
+            CL_FLOAT,
+        ],
+    ),
+    (CL_BGRA, &[CL_UNORM_INT8]),
+];
+
+const fn channel_count(order: cl_channel_order) -> usize {
+    match order {
+        CL_RG => 2,
+        CL_RGBA | CL_BGRA => 4,
+        _ => 1,
+    }
+}
+
+const fn channel_size(data_type: cl_channel_type) -> usize {
+    match data_type {
+        CL_SNORM_INT16 | CL_UNORM_INT16 | CL_SIGNED_INT16 | CL_UNSIGNED_INT16 | CL_HALF_FLOAT => 2,
+        CL_SIGNED_INT32 | CL_UNSIGNED_INT32 | CL_FLOAT => 4,
+        _ => 1,
+    }
+}
+
+fn is_supported_format(format: cl_image_format) -> bool {
+    IMAGE_FORMATS.iter().any(|(order, types)| {
+        *order == format.image_channel_order && types.contains(&format.image_channel_data_type)
+    })
+}
+
+unsafe fn create_image(
+    context: cl_context,
+    properties: Vec<cl_mem_properties>,
+    flags: cl_mem_flags,
+    image_format: *const cl_image_format,
+    image_desc: *const cl_image_desc,
+    host_ptr: *mut c_void,
+) -> Result<usize, cl_int> {
+    with_state(|state| {
+        state.context(context)?;
+        let mut flags = check_mem_flags(flags, host_ptr)?;
+        if image_format.is_null() {
+            return Err(CL_INVALID_IMAGE_FORMAT_DESCRIPTOR);
+        }
+        if image_desc.is_null() {
+            return Err(CL_INVALID_IMAGE_DESCRIPTOR);
+        }
+        let format = *image_format;
+        let desc = *image_desc;
+        if !is_supported_format(format) {
+            return Err(CL_IMAGE_FORMAT_NOT_SUPPORTED);
+        }
+        let element_size = channel_count(format.image_channel_order)
+            * channel_size(format.image_channel_data_type);
+
+        let (dims, max_dims) = match desc.image_type {
+            CL_MEM_OBJECT_IMAGE1D => ([desc.image_width, 1, 1], [IMAGE2D_MAX_SIZE, 1, 1]),
+            CL_MEM_OBJECT_IMAGE1D_BUFFER => {
+                ([desc.image_width, 1, 1], [IMAGE_MAX_BUFFER_SIZE, 1, 1])
+            }
+            CL_MEM_OBJECT_IMAGE1D_ARRAY => (
+                [desc.image_width, desc.image_array_size, 1],
+                [IMAGE2D_MAX_SIZE, IMAGE_MAX_ARRAY_SIZE, 1],
+            ),
+            CL_MEM_OBJECT_IMAGE2D => (
+                [desc.image_width, desc.image_height, 1],
+                [IMAGE2D_MAX_SIZE, IMAGE2D_MAX_SIZE, 1],
+            ),
+            CL_MEM_OBJECT_IMAGE2D_ARRAY => (
+                [desc.image_width, desc.image_height, desc.image_array_size],
+                [IMAGE2D_MAX_SIZE, IMAGE2D_MAX_SIZE, IMAGE_MAX_ARRAY_SIZE],
+            ),
+            CL_MEM_OBJECT_IMAGE3D => (
+                [desc.image_width, desc.image_height, desc.image_depth],
+                [IMAGE3D_MAX_SIZE, IMAGE3D_MAX_SIZE, IMAGE3D_MAX_SIZE],
+            ),
+            _ => return Err(CL_INVALID_IMAGE_DESCRIPTOR),
+        };
+        if dims.contains(&0) || 0 != desc.num_mip_levels || 0 != desc.num_samples {
+            return Err(CL_INVALID_IMAGE_DESCRIPTOR);
+        }
+        if dims.iter().zip(max_dims.iter()).any(|(dim, max)| max < dim) {
+            return Err(CL_INVALID_IMAGE_SIZE);
+        }
+
+        // The row pitch is the pitch between the rows of the image, which are
+        // the images of a 1D image array
+        let is_1d_array = CL_MEM_OBJECT_IMAGE1D_ARRAY == desc.image_type;
+        let min_row_pitch = dims[0] * element_size;
+        let given_row_pitch = if is_1d_array {
+            desc.image_slice_pitch
+        } else {
+            desc.image_row_pitch
+        };
+        let has_buffer = !desc.buffer.is_null();
+        if (host_ptr.is_null()
+            && !has_buffer
+            && (0 != desc.image_row_pitch || 0 != desc.image_slice_pitch))
+            || (0 != given_row_pitch
+                && (given_row_pitch < min_row_pitch || 0 != given_row_pitch % element_size))
+        {
+            return Err(CL_INVALID_IMAGE_DESCRIPTOR);
+        }
+        let host_row_pitch = if 0 == given_row_pitch {
+            min_row_pitch
+        } else {
+            given_row_pitch
+        };
+        let min_slice_pitch = host_row_pitch * dims[1];
+        if !is_1d_array && 0 != desc.image_slice_pitch && desc.image_slice_pitch < min_slice_pitch {
+            return Err(CL_INVALID_IMAGE_DESCRIPTOR);
+        }
+        let host_slice_pitch = if is_1d_array || 0 == desc.image_slice_pitch {
+            min_slice_pitch
+        } else {
+            desc.image_slice_pitch
+        };
+        let uses_host_layout = has_buffer || 0 != flags & CL_MEM_USE_HOST_PTR;
+        let (row_pitch, slice_pitch) = if uses_host_layout {
+            (host_row_pitch, host_slice_pitch)
+        } else {
+            (min_row_pitch, min_row_pitch * dims[1])
+        };
+        let size = slice_pitch * dims[2];
+
+        let (storage, uses_svm_pointer, parent) = if has_buffer {
+            if !matches!(
+                desc.image_type,
+                CL_MEM_OBJECT_IMAGE1D_BUFFER | CL_MEM_OBJECT_IMAGE2D
+            ) || !host_ptr.is_null()
+            {
+                return Err(CL_INVALID_IMAGE_DESCRIPTOR);
+            }
+            let buffer = state.mem(desc.buffer)?;
+            if CL_MEM_OBJECT_BUFFER != buffer.object_type
+                && CL_MEM_OBJECT_IMAGE2D != buffer.object_type
+            {
+                return Err(CL_INVALID_IMAGE_DESCRIPTOR);
+            }
+            if buffer.size < size {
+                return Err(CL_INVALID_IMAGE_SIZE);
+            }
+            if 0 == flags & (ACCESS_FLAGS & !CL_MEM_READ_WRITE) {
+                flags = (flags & !ACCESS_FLAGS) | (buffer.flags & ACCESS_FLAGS);
+            }
+            (
+                buffer.storage.clone(),
+                buffer.uses_svm_pointer,
+                id(desc.buffer),
+            )
+        } else if 0 == flags & CL_MEM_USE_HOST_PTR {
+            let allocation = Allocation::new(size, ALIGNMENT)?;
+            if 0 != flags & CL_MEM_COPY_HOST_PTR {
+                copy_rect(
+                    host_ptr.cast::<u8>(),
+                    [0; 3],
+                    Pitch::new(host_row_pitch, host_slice_pitch),
+                    allocation.ptr,
+                    [0; 3],
+                    Pitch::new(row_pitch, slice_pitch),
+                    [min_row_pitch, dims[1], dims[2]],
+                );
+            }
+            let ptr = allocation.ptr;
+            (
+                Storage {
+                    allocation: Some(allocation),
+                    ptr,
+                },
+                false,
+                0,
+            )
+        } else {
+            let (storage, uses_svm_pointer) = state.new_storage(flags, size, host_ptr)?;
+            (storage, uses_svm_pointer, 0)
+        };
+
+        let mem = Mem {
+            ref_count: 1,
+            context: id(context),
+            object_type: desc.image_type,
+            flags,
+            size,
+            host_ptr: if 0 == flags & CL_MEM_USE_HOST_PTR {
+                ptr::null_mut()
+            } else {
+                host_ptr
+            },
+            storage,
+            parent,
+            offset: 0,
+            uses_svm_pointer,
+            properties,
+            image: Some(Image {
+                format,
+                desc,
+                element_size,
+                dims,
+                row_pitch,
+                slice_pitch,
+            }),
+            maps: Vec::new(),
+            destructors: Vec::new(),
+        };
+        Ok(state.insert_mem(mem))
+    })
+}
+
+pub unsafe fn clCreateImage(
+    context: cl_context,
+    flags: cl_mem_flags,
+    image_format: *const cl_image_format,
+    image_desc: *const cl_image_desc,
+    host_ptr: *mut c_void,
+    errcode_ret: *mut cl_int,
+) -> cl_mem {
+    object_result(
+        create_image(
+            context,
+            Vec::new(),
+            flags,
+            image_format,
+            image_desc,
+            host_ptr,
+        ),
+        errcode_ret,
+    )
+}
+
+pub unsafe fn clCreateImageWithProperties(
+    context: cl_context,
+    properties: *const cl_mem_properties,
+    flags: cl_mem_flags,
+    image_format: *const cl_image_format,
+    image_desc: *const cl_image_desc,
+    host_ptr: *mut c_void,
+    errcode_ret: *mut cl_int,
+) -> cl_mem {
+    let properties = properties_list(properties);
+    let result = if 1 < properties.len() {
+        Err(CL_INVALID_PROPERTY)
+    } else {
+        create_image(
+            context,
+            properties,
+            flags,
+            image_format,
+            image_desc,
+            host_ptr,
+        )
+    };
+    object_result(result, errcode_ret)
+}
+
+pub unsafe fn clCreatePipe(
+    context: cl_context,
+    _flags: cl_mem_flags,
+    _pipe_packet_size: cl_uint,
+    _pipe_max_packets: cl_uint,
+    _properties: *const cl_pipe_properties,
+    errcode_ret: *mut cl_int,
+) -> cl_mem {
+    // The mock device does not support pipes
+    let result = with_state(|state| state.context(context).and(Err(CL_INVALID_OPERATION)));
+    object_result(result, errcode_ret)
+}
+
+pub unsafe fn clRetainMemObject(memobj: cl_mem) -> cl_int {
+    with_state(|state| {
+        state.mem_mut(memobj)?.ref_count += 1;
+        Ok(())
+    })
+    .err()
+    .unwrap_or(CL_SUCCESS)
+}
+
+pub unsafe fn clReleaseMemObject(memobj: cl_mem) -> cl_int {
+    with_state(|state| {
+        state.mem(memobj)?;
+        state.release_mem(id(memobj));
+        Ok(())
+    })
+    .err()
+    .unwrap_or(CL_SUCCESS)
+}
+
+pub unsafe fn clGetSupportedImageFormats(
+    context: cl_context,
+    flags: cl_mem_flags,
+    image_type: cl_mem_object_type,
+    num_entries: cl_uint,
+    image_formats: *mut cl_image_format,
+    num_image_formats: *mut cl_uint,
+) -> cl_int {
+    if let Err(error) = with_state(|state| state.context(context).map(|_| ())) {
+        return error;
+    }
+    if (0 == num_entries && !image_formats.is_null())
+        || check_mem_flags(flags & !HOST_PTR_FLAGS, ptr::null_mut()).is_err()
+        || !matches!(
+            image_type,
+            CL_MEM_OBJECT_IMAGE1D
+                | CL_MEM_OBJECT_IMAGE1D_BUFFER
+                | CL_MEM_OBJECT_IMAGE1D_ARRAY
+                | CL_MEM_OBJECT_IMAGE2D
+                | CL_MEM_OBJECT_IMAGE2D_ARRAY
+                | CL_MEM_OBJECT_IMAGE3D
+        )
+    {
+        return CL_INVALID_VALUE;
+    }
+    let formats: Vec<cl_image_format> = IMAGE_FORMATS
+        .iter()
+        .flat_map(|(order, types)| {
+            types.iter().map(|data_type| cl_image_format {
+                image_channel_order: *order,
+                image_channel_data_type: *data_type,
+            })
+        })
+        .collect();
+    if !image_formats.is_null() {
+        for (i, format) in formats.iter().take(num_entries as usize).enumerate() {
+            *image_formats.add(i) = *format;
+        }
+    }
+    if !num_image_formats.is_null() {
+        *num_image_formats = formats.len() as cl_uint;
+    }
+    CL_SUCCESS
+}
+
+pub unsafe fn clGetMemObjectInfo(
+    memobj: cl_mem,
+    param_name: cl_mem_info,
+    param_value_size: size_t,
+    param_value: *mut c_void,
+    param_value_size_ret: *mut size_t,
+) -> cl_int {
+    let info = with_state(|state| {
+        let mem = state.mem(memobj)?;
+        match param_name {
+            CL_MEM_TYPE => value_info(mem.object_type),
+            CL_MEM_FLAGS => value_info(mem.flags),
+            CL_MEM_SIZE => value_info(mem.size),
+            CL_MEM_HOST_PTR => value_info(mem.host_ptr as usize),
+            CL_MEM_MAP_COUNT => value_info(mem.maps.len() as cl_uint),
+            CL_MEM_REFERENCE_COUNT => value_info(mem.ref_count),
+            CL_MEM_CONTEXT => value_info(mem.context),
+            CL_MEM_ASSOCIATED_MEMOBJECT => value_info(mem.parent),
+            CL_MEM_OFFSET => value_info(mem.offset),
+            CL_MEM_USES_SVM_POINTER => value_info::<cl_bool>(cl_bool::from(mem.uses_svm_pointer)),
+            CL_MEM_PROPERTIES => slice_info(&mem.properties),
+            _ => Err(CL_INVALID_VALUE),
+        }
+    });
+    write_info(info, param_value_size, param_value, param_value_size_ret)
+}
+
+pub unsafe fn clGetImageInfo(
+    image: cl_mem,
+    param_name: cl_image_info,
+    param_value_size: size_t,
+    param_value: *mut c_void,
+    param_value_size_ret: *mut size_t,
+) -> cl_int {
+    let info = with_state(|state| {
+        let image = state
+            .mem(image)?
+            .image
+            .as_ref()
+            .ok_or(CL_INVALID_MEM_OBJECT)?;
+        let desc = &image.desc;
+        match param_name {
+            CL_IMAGE_FORMAT => slice_info(&[
+                image.format.image_channel_order,
+                image.format.image_channel_data_type,
+            ]),
+            CL_IMAGE_ELEMENT_SIZE => value_info(image.element_size),
+            CL_IMAGE_ROW_PITCH => value_info(if CL_MEM_OBJECT_IMAGE1D_ARRAY == desc.image_type {
+                image.dims[0] * image.element_size
+            } else {
+                image.row_pitch
+            }),
+            CL_IMAGE_SLICE_PITCH => value_info(match desc.image_type {
+                CL_MEM_OBJECT_IMAGE1D_ARRAY => image.row_pitch,
+                CL_MEM_OBJECT_IMAGE2D_ARRAY | CL_MEM_OBJECT_IMAGE3D => image.slice_pitch,
+                _ => 0,
+            }),
+            CL_IMAGE_WIDTH => value_info(desc.image_width),
+            CL_IMAGE_HEIGHT => value_info(match desc.image_type {
+                CL_MEM_OBJECT_IMAGE2D | CL_MEM_OBJECT_IMAGE2D_ARRAY | CL_MEM_OBJECT_IMAGE3D => {
+                    desc.image_height
+                }
+                _ => 0,
+            }),
+            CL_IMAGE_DEPTH => value_info(if CL_MEM_OBJECT_IMAGE3D == desc.image_type {
+                desc.image_depth
+            } else {
+                0
+            }),
+            CL_IMAGE_ARRAY_SIZE => value_info(match desc.image_type {
+                CL_MEM_OBJECT_IMAGE1D_ARRAY | CL_MEM_OBJECT_IMAGE2D_ARRAY => desc.image_array_size,
+                _ => 0,
+            }),
+            CL_IMAGE_BUFFER => value_info(desc.buffer as usize),
+            CL_IMAGE_NUM_MIP_LEVELS | CL_IMAGE_NUM_SAMPLES => value_info::<cl_uint>(0),
+            _ => Err(CL_INVALID_VALUE),
+        }
+    });
+    write_info(info, param_value_size, param_value, param_value_size_ret)
+}
+
+pub unsafe fn clGetPipeInfo(
+    pipe: cl_mem,
+    _param_name: cl_pipe_info,
+    _param_value_size: size_t,
+    _param_value: *mut c_void,
+    _param_value_size_ret: *mut size_t,
+) -> cl_int {
+    // The mock device does not support pipes, so no memory object is a pipe
+    with_state(|state| state.mem(pipe).map(|_| ()))
+        .err()
+        .unwrap_or(CL_INVALID_MEM_OBJECT)
+}
+
+pub unsafe fn clSetMemObjectDestructorCallback(
+    memobj: cl_mem,
+    pfn_notify: Option<MemDestructor>,
+    user_data: *mut c_void,
+) -> cl_int {
+    let Some(notify) = pfn_notify else {
+        return CL_INVALID_VALUE;
+    };
+    with_state(|state| {
+        state.mem_mut(memobj)?.destructors.push((notify, user_data));
+        Ok(())
+    })
+    .err()
+    .unwrap_or(CL_SUCCESS)
+}
+
+// SVM Allocation APIs
+
+pub unsafe fn clSVMAlloc(
+    context: cl_context,
+    flags: cl_svm_mem_flags,
+    size: size_t,
+    alignment: cl_uint,
+) -> *mut c_void {
+    const VALID_FLAGS: cl_svm_mem_flags =
+        ACCESS_FLAGS | CL_MEM_SVM_FINE_GRAIN_BUFFER | CL_MEM_SVM_ATOMICS;
+    with_state(|state| {
+        if state.context(context).is_err()
+            || 0 != flags & !VALID_FLAGS
+            || 1 < (flags & ACCESS_FLAGS).count_ones()
+            || (0 != flags & CL_MEM_SVM_ATOMICS && 0 == flags & CL_MEM_SVM_FINE_GRAIN_BUFFER)
+            || 0 == size
+            || MAX_MEM_ALLOC_SIZE < size
+            || !(0 == alignment || alignment.is_power_of_two())
+        {
+            return ptr::null_mut();
+        }
+        Allocation::new(size, alignment as usize).map_or(ptr::null_mut(), |allocation| {
+            let pointer = allocation.ptr;
+            state.svm.insert(
+                pointer as usize,
+                Svm {
+                    context: id(context),
+                    allocation,
+                    size,
+                },
+            );
+            pointer.cast::<c_void>()
+        })
+    })
+}
+
+pub unsafe fn clSVMFree(context: cl_context, svm_pointer: *mut c_void) {
+    with_state(|state| {
+        if state
+            .svm
+            .get(&(svm_pointer as usize))
+            .is_some_and(|svm| id(context) == svm.context)
+        {
+            state.svm.remove(&(svm_pointer as usize));
+        }
+    });
+}
+
+// Sampler APIs
+
+unsafe fn create_sampler(
+    context: cl_context,
+    normalized_coords: cl_bool,
+    addressing_mode: cl_addressing_mode,
+    filter_mode: cl_filter_mode,
+    properties: Vec<cl_sampler_properties>,
+) -> Result<usize, cl_int> {
+    if !matches!(normalized_coords, CL_TRUE | CL_FALSE)
+        || !matches!(
+            addressing_mode,
+            CL_ADDRESS_NONE
+                | CL_ADDRESS_CLAMP_TO_EDGE
+                | CL_ADDRESS_CLAMP
+                | CL_ADDRESS_REPEAT
+                | CL_ADDRESS_MIRRORED_REPEAT
+        )
+        || !matches!(filter_mode, CL_FILTER_NEAREST | CL_FILTER_LINEAR)
+        || (CL_FALSE == normalized_coords
+            && matches!(
+                addressing_mode,
+                CL_ADDRESS_REPEAT | CL_ADDRESS_MIRRORED_REPEAT
+            ))
+    {
+        return Err(CL_INVALID_VALUE);
+    }
+    with_state(|state| {
+        state.context(context)?;
+        let sampler = state.new_id();
+        state.retain_context(id(context));
+        state.samplers.insert(
+            sampler,
+            Sampler {
+                ref_count: 1,
+                context: id(context),
+                normalized_coords,
+                addressing_mode,
+                filter_mode,
+                properties,
+            },
+        );
+        Ok(sampler)
+    })
+}
+
+pub unsafe fn clCreateSampler(
+    context: cl_context,
+    normalized_coords: cl_bool,
+    addressing_mode: cl_addressing_mode,
+    filter_mode: cl_filter_mode,
+    errcode_ret: *mut cl_int,
+) -> cl_sampler {
+    object_result(
+        create_sampler(
+            context,
+            normalized_coords,
+            addressing_mode,
+            filter_mode,
+            Vec::new(),
+        ),
+        errcode_ret,
+    )
+}
+
+pub unsafe fn clCreateSamplerWithProperties(
+    context: cl_context,
+    sampler_properties: *const cl_sampler_properties,
+    errcode_ret: *mut cl_int,
+) -> cl_sampler {
+    let properties = properties_list(sampler_properties);
+    let mut normalized_coords = CL_TRUE;
+    let mut addressing_mode = CL_ADDRESS_CLAMP;
+    let mut filter_mode = CL_FILTER_NEAREST;
+    let mut result = Ok(());
+    for pair in properties.chunks(2).filter(|pair| 2 == pair.len()) {
+        match pair[0] {
+            name if u64::from(CL_SAMPLER_NORMALIZED_COORDS) == name => {
+                normalized_coords = pair[1] as cl_bool;
+            }
+            name if u64::from(CL_SAMPLER_ADDRESSING_MODE) == name => {
+                addressing_mode = pair[1] as cl_addressing_mode;
+            }
+            name if u64::from(CL_SAMPLER_FILTER_MODE) == name => {
+                filter_mode = pair[1] as cl_filter_mode;
+            }
+            _ => result = Err(CL_INVALID_VALUE),
+        }
+    }
+    let result = result.and_then(|()| {
+        create_sampler(
+            context,
+            normalized_coords,
+            addressing_mode,
+            filter_mode,
+            properties,
+        )
+    });
+    object_result(result, errcode_ret)
+}
+
+pub unsafe fn clRetainSampler(sampler: cl_sampler) -> cl_int {
+    with_state(|state| {
+        state.sampler_mut(sampler)?.ref_count += 1;
+        Ok(())
+    })
+    .err()
+    .unwrap_or(CL_SUCCESS)
+}
+
+pub unsafe fn clReleaseSampler(sampler: cl_sampler) -> cl_int {
+    with_state(|state| {
+        let object = state.sampler_mut(sampler)?;
+        object.ref_count -= 1;
+        if 0 == object.ref_count {
+            let context = object.context;
+            state.samplers.remove(&id(sampler));
+            state.release_context(context);
+        }
+        Ok(())
+    })
+    .err()
+    .unwrap_or(CL_SUCCESS)
+}
+
+pub unsafe fn clGetSamplerInfo(
+    sampler: cl_sampler,
+    param_name: cl_sampler_info,
+    param_value_size: size_t,
+    param_value: *mut c_void,
+    param_value_size_ret: *mut size_t,
+) -> cl_int {
+    let info = with_state(|state| {
+        let sampler = state.sampler(sampler)?;
+        match param_name {
+            CL_SAMPLER_REFERENCE_COUNT => value_info(sampler.ref_count),
+            CL_SAMPLER_CONTEXT => value_info(sampler.context),
+            CL_SAMPLER_NORMALIZED_COORDS => value_info(sampler.normalized_coords),
+            CL_SAMPLER_ADDRESSING_MODE => value_info(sampler.addressing_mode),
+            CL_SAMPLER_FILTER_MODE => value_info(sampler.filter_mode),
+            CL_SAMPLER_PROPERTIES => slice_info(&sampler.properties),
+            _ => Err(CL_INVALID_VALUE),
+        }
+    });
+    write_info(info, param_value_size, param_value, param_value_size_ret)
+}
+
+// Enqueued Commands APIs
+
+/// The row and slice pitches of a 3D region of memory.
+#[derive(Clone, Copy)]
+struct Pitch {
+    row: usize,
+    slice: usize,
+}
+
+impl Pitch {
+    const fn new(row: usize, slice: usize) -> Self {
+        Self { row, slice }
+    }
+
+    const fn offset(self, origin: [usize; 3]) -> usize {
+        origin[0] + origin[1] * self.row + origin[2] * self.slice
+    }
+
+    /// The number of bytes from the origin to the end of the region.
+    const fn extent(self, origin: [usize; 3], region: [usize; 3]) -> usize {
+        self.offset(origin) + (region[2] - 1) * self.slice + (region[1] - 1) * self.row + region[0]
+    }
+}
+
+/// Copy a 3D region of memory, where the region and origins are in bytes,
+/// rows and slices.
+unsafe fn copy_rect(
+    src: *const u8,
+    src_origin: [usize; 3],
+    src_pitch: Pitch,
+    dst: *mut u8,
+    dst_origin: [usize; 3],
+    dst_pitch: Pitch,
+    region: [usize; 3],
+) {
+    for z in 0..region[2] {
+        for y in 0..region[1] {
+            let src_offset =
+                src_pitch.offset([src_origin[0], src_origin[1] + y, src_origin[2] + z]);
+            let dst_offset =
+                dst_pitch.offset([dst_origin[0], dst_origin[1] + y, dst_origin[2] + z]);
+            ptr::copy(src.add(src_offset), dst.add(dst_offset), region[0]);
+        }
+    }
+}
+
+/// Get the pitches of a host rectangle, calculating them if they are zero.
+const fn rect_pitch(
+    region: [usize; 3],
+    row_pitch: usize,
+    slice_pitch: usize,
+) -> Result<Pitch, cl_int> {
+    let row = if 0 == row_pitch { region[0] } else { row_pitch };
+    let slice = if 0 == slice_pitch {
+        region[1] * row
+    } else {
+        slice_pitch
+    };
+    if row < region[0] || slice < region[1] * row || 0 != slice % row {
+        Err(CL_INVALID_VALUE)
+    } else {
+        Ok(Pitch::new(row, slice))
+    }
+}
+
+/// Get an origin and a non-empty region.
+unsafe fn origin_region(
+    origin: *const size_t,
+    region: *const size_t,
+) -> Result<([usize; 3], [usize; 3]), cl_int> {
+    match (triple(origin), triple(region)) {
+        (Some(origin), Some(region)) if !region.contains(&0) => Ok((origin, region)),
+        _ => Err(CL_INVALID_VALUE),
+    }
+}
+
+/// Whether two byte ranges overlap.
+const fn overlaps(a: usize, a_size: usize, b: usize, b_size: usize) -> bool {
+    a < b + b_size && b < a + a_size
+}
+
+impl State {
+    /// Get the storage and size of a buffer in a context.
+    fn buffer(&self, buffer: cl_mem, context: usize) -> Result<&Mem, cl_int> {
+        let mem = self.mem(buffer)?;
+        if CL_MEM_OBJECT_BUFFER != mem.object_type {
+            Err(CL_INVALID_MEM_OBJECT)
+        } else if context != mem.context {
+            Err(CL_INVALID_CONTEXT)
+        } else {
+            Ok(mem)
+        }
+    }
+
+    /// Get an image and its memory object in a context.
+    fn image(&self, image: cl_mem, context: usize) -> Result<(&Mem, &Image), cl_int> {
+        let mem = self.mem(image)?;
+        let Some(object) = mem.image.as_ref() else {
+            return Err(CL_INVALID_MEM_OBJECT);
+        };
+        if context == mem.context {
+            Ok((mem, object))
+        } else {
+            Err(CL_INVALID_CONTEXT)
+        }
+    }
+}
+
+/// Check that the host can read or write a memory object.
+const fn check_host_access(mem: &Mem, write: bool) -> Result<(), cl_int> {
+    let denied = if write {
+        CL_MEM_HOST_READ_ONLY | CL_MEM_HOST_NO_ACCESS
+    } else {
+        CL_MEM_HOST_WRITE_ONLY | CL_MEM_HOST_NO_ACCESS
+    };
+    if 0 == mem.flags & denied {
+        Ok(())
+    } else {
+        Err(CL_INVALID_OPERATION)
+    }
+}
+
+/// Check an image region and return the region and origin in bytes, rows and slices.
+fn image_region(
+    image: &Image,
+    origin: [usize; 3],
+    region: [usize; 3],
+) -> Result<([usize; 3], [usize; 3]), cl_int> {
+    if (0..3).any(|i| image.dims[i] < origin[i] + region[i]) {
+        return Err(CL_INVALID_VALUE);
+    }
+    Ok((
+        [origin[0] * image.element_size, origin[1], origin[2]],
+        [region[0] * image.element_size, region[1], region[2]],
+    ))
+}
+
+/// Get the pitches of host memory for an image region.
+const fn image_host_pitch(
+    image: &Image,
+    region: [usize; 3],
+    row_pitch: usize,
+    slice_pitch: usize,
+) -> Result<Pitch, cl_int> {
+    let bytes = region[0] * image.element_size;
+    let row = if 0 == row_pitch { bytes } else { row_pitch };
+    if row < bytes {
+        return Err(CL_INVALID_VALUE);
+    }
+    // The rows of a 1D image array are its images, separated by the slice pitch
+    if CL_MEM_OBJECT_IMAGE1D_ARRAY == image.desc.image_type {
+        let slice = if 0 == slice_pitch { row } else { slice_pitch };
+        return if slice < row {
+            Err(CL_INVALID_VALUE)
+        } else {
+            Ok(Pitch::new(slice, slice * region[1]))
+        };
+    }
+    let slice = if 0 == slice_pitch {
+        row * region[1]
+    } else {
+        slice_pitch
+    };
+    if slice < row * region[1] {
+        Err(CL_INVALID_VALUE)
+    } else {
+        Ok(Pitch::new(row, slice))
+    }
+}
+
+const fn image_pitch(image: &Image) -> Pitch {
+    Pitch::new(image.row_pitch, image.slice_pitch)
+}
+
+pub unsafe fn clEnqueueReadBuffer(
+    command_queue: cl_command_queue,
+    buffer: cl_mem,
+    blocking_read: cl_bool,
+    offset: size_t,
+    size: size_t,
+    ptr: *mut c_void,
+    num_events_in_wait_list: cl_uint,
+    event_wait_list: *const cl_event,
+    event: *mut cl_event,
+) -> cl_int {
+    enqueue_command(
+        command_queue,
+        CL_COMMAND_READ_BUFFER,
+        CL_FALSE != blocking_read,
+        num_events_in_wait_list,
+        event_wait_list,
+        event,
+        |state, context| {
+            let mem = state.buffer(buffer, context)?;
+            check_host_access(mem, false)?;
+            if ptr.is_null() || 0 == size || mem.size < offset + size {
+                return Err(CL_INVALID_VALUE);
+            }
+            let src = mem.storage.at(offset);
+            Ok(Box::new(move |_| {
+                ptr::copy(src.ptr, ptr.cast::<u8>(), size);
+                CL_SUCCESS
+            }))
+        },
+    )
+}
+
+pub unsafe fn clEnqueueReadBufferRect(
+    command_queue: cl_command_queue,
+    buffer: cl_mem,
+    blocking_read: cl_bool,
+    buffer_origin: *const size_t,
+    host_origin: *const size_t,
+    region: *const size_t,
+    buffer_row_pitch: size_t,
+    buffer_slice_pitch: size_t,
+    host_row_pitch: size_t,
+    host_slice_pitch: size_t,
+    ptr: *mut c_void,
+    num_events_in_wait_list: cl_uint,
+    event_wait_list: *const cl_event,
+    event: *mut cl_event,
+) -> cl_int {
+    enqueue_command(
+        command_queue,
+        CL_COMMAND_READ_BUFFER_RECT,
+        CL_FALSE != blocking_read,
+        num_events_in_wait_list,
+        event_wait_list,
+        event,
+        |state, context| {
+            let mem = state.buffer(buffer, context)?;
+            check_host_access(mem, false)?;
+            let (buffer_origin, region) = origin_region(buffer_origin, region)?;
+            let host_origin = triple(host_origin).ok_or(CL_INVALID_VALUE)?;
+            let buffer_pitch = rect_pitch(region, buffer_row_pitch, buffer_slice_pitch)?;
+            let host_pitch = rect_pitch(region, host_row_pitch, host_slice_pitch)?;
+            if ptr.is_null() || mem.size < buffer_pitch.extent(buffer_origin, region) {
+                return Err(CL_INVALID_VALUE);
+            }
+            let src = mem.storage.clone();
+            Ok(Box::new(move |_| {
+                copy_rect(
+                    src.ptr,
+                    buffer_origin,
+                    buffer_pitch,
+                    ptr.cast::<u8>(),
+                    host_origin,
+                    host_pitch,
+                    region,
+                );
+                CL_SUCCESS
+            }))
+        },
+    )
+}
+
+pub unsafe fn clEnqueueWriteBuffer(
+    command_queue: cl_command_queue,
+    buffer: cl_mem,
+    blocking_write: cl_bool,
+    offset: size_t,
+    size: size_t,
+    ptr: *const c_void,
+    num_events_in_wait_list: cl_uint,
+    event_wait_list: *const cl_event,
+    event: *mut cl_event,
+) -> cl_int {
+    enqueue_command(
+        command_queue,
+        CL_COMMAND_WRITE_BUFFER,
+        CL_FALSE != blocking_write,
+        num_events_in_wait_list,
+        event_wait_list,
+        event,
+        |state, context| {
+            let mem = state.buffer(buffer, context)?;
+            check_host_access(mem, true)?;
+            if ptr.is_null() || 0 == size || mem.size < offset + size {
+                return Err(CL_INVALID_VALUE);
+            }
+            let dst = mem.storage.at(offset);
+            Ok(Box::new(move |_| {
+                ptr::copy(ptr.cast::<u8>(), dst.ptr, size);
+                CL_SUCCESS
+            }))
+        },
+    )
+}
+
+pub unsafe fn clEnqueueWriteBufferRect(
+    command_queue: cl_command_queue,
+    buffer: cl_mem,
+    blocking_write: cl_bool,
+    buffer_origin: *const size_t,
+    host_origin: *const size_t,
+    region: *const size_t,
+    buffer_row_pitch: size_t,
+    buffer_slice_pitch: size_t,
+    host_row_pitch: size_t,
+    host_slice_pitch: size_t,
+    ptr: *const c_void,
+    num_events_in_wait_list: cl_uint,
+    event_wait_list: *const cl_event,
+    event: *mut cl_event,
+) -> cl_int {
+    enqueue_command(
+        command_queue,
+        CL_COMMAND_WRITE_BUFFER_RECT,
+        CL_FALSE != blocking_write,
+        num_events_in_wait_list,
+        event_wait_list,
+        event,
+        |state, context| {
+            let mem = state.buffer(buffer, context)?;
+            check_host_access(mem, true)?;
+            let (buffer_origin, region) = origin_region(buffer_origin, region)?;
+            let host_origin = triple(host_origin).ok_or(CL_INVALID_VALUE)?;
+            let buffer_pitch = rect_pitch(region, buffer_row_pitch, buffer_slice_pitch)?;
+            let host_pitch = rect_pitch(region, host_row_pitch, host_slice_pitch)?;
+            if ptr.is_null() || mem.size < buffer_pitch.extent(buffer_origin, region) {
+                return Err(CL_INVALID_VALUE);
+            }
+            let dst = mem.storage.clone();
+            Ok(Box::new(move |_| {
+                copy_rect(
+                    ptr.cast::<u8>(),
+                    host_origin,
+                    host_pitch,
+                    dst.ptr,
+                    buffer_origin,
+                    buffer_pitch,
+                    region,
+                );
+                CL_SUCCESS
+            }))
+        },
+    )
+}
+
+/// Fill memory with a repeating pattern.
+unsafe fn fill(dst: *mut u8, pattern: &[u8], size: usize) {
+    for offset in (0..size).step_by(pattern.len()) {
+        let length = pattern.len().min(size - offset);
+        ptr::copy_nonoverlapping(pattern.as_ptr(), dst.add(offset), length);
+    }
+}
+
+/// Check a fill pattern and return a copy of it.
+unsafe fn fill_pattern(
+    pattern: *const c_void,
+    pattern_size: size_t,
+    offset: size_t,
+    size: size_t,
+) -> Result<Vec<u8>, cl_int> {
+    if pattern.is_null()
+        || !pattern_size.is_power_of_two()
+        || 128 < pattern_size
+        || !offset.is_multiple_of(pattern_size)
+        || !size.is_multiple_of(pattern_size)
+    {
+        Err(CL_INVALID_VALUE)
+    } else {
+        Ok(array(pattern.cast::<u8>(), pattern_size).to_vec())
+    }
+}
+
+pub unsafe fn clEnqueueFillBuffer(
+    command_queue: cl_command_queue,
+    buffer: cl_mem,
+    pattern: *const c_void,
+    pattern_size: size_t,
+    offset: size_t,
+    size: size_t,
+    num_events_in_wait_list: cl_uint,
+    event_wait_list: *const cl_event,
+    event: *mut cl_event,
+) -> cl_int {
+    enqueue_command(
+        command_queue,
+        CL_COMMAND_FILL_BUFFER,
+        false,
+        num_events_in_wait_list,
+        event_wait_list,
+        event,
+        |state, context| {
+            let mem = state.buffer(buffer, context)?;
+            let pattern = fill_pattern(pattern, pattern_size, offset, size)?;
+            if mem.size < offset + size {
+                return Err(CL_INVALID_VALUE);
+            }
+            let dst = mem.storage.at(offset);
+            Ok(Box::new(move |_| {
+                fill(dst.ptr, &pattern, size);
+                CL_SUCCESS
+            }))
+        },
+    )
+}
+
+pub unsafe fn clEnqueueCopyBuffer(
+    command_queue: cl_command_queue,
+    src_buffer: cl_mem,
+    dst_buffer: cl_mem,
+    src_offset: size_t,
+    dst_offset: size_t,
+    size: size_t,
+    num_events_in_wait_list: cl_uint,
+    event_wait_list: *const cl_event,
+    event: *mut cl_event,
+) -> cl_int {
+    enqueue_command(
+        command_queue,
+        CL_COMMAND_COPY_BUFFER,
+        false,
+        num_events_in_wait_list,
+        event_wait_list,
+        event,
+        |state, context| {
+            let src = state.buffer(src_buffer, context)?;
+            let dst = state.buffer(dst_buffer, context)?;
+            if 0 == size || src.size < src_offset + size || dst.size < dst_offset + size {
+                return Err(CL_INVALID_VALUE);
+            }
+            let src_start = src.storage.ptr as usize + src_offset;
+            let dst_start = dst.storage.ptr as usize + dst_offset;
+            if overlaps(src_start, size, dst_start, size) {
+                return Err(CL_MEM_COPY_OVERLAP);
+            }
+            let src = src.storage.at(src_offset);
+            let dst = dst.storage.at(dst_offset);
+            Ok(Box::new(move |_| {
+                ptr::copy(src.ptr, dst.ptr, size);
+                CL_SUCCESS
+            }))
+        },
+    )
+}
+
+pub unsafe fn clEnqueueCopyBufferRect(
+    command_queue: cl_command_queue,
+    src_buffer: cl_mem,
+    dst_buffer: cl_mem,
+    src_origin: *const size_t,
+    dst_origin: *const size_t,
+    region: *const size_t,
+    src_row_pitch: size_t,
+    src_slice_pitch: size_t,
+    dst_row_pitch: size_t,
+    dst_slice_pitch: size_t,
+    num_events_in_wait_list: cl_uint,
+    event_wait_list: *const cl_event,
+    event: *mut cl_event,
+) -> cl_int {
+    enqueue_command(
+        command_queue,
+        CL_COMMAND_COPY_BUFFER_RECT,
+        false,
+        num_events_in_wait_list,
+        event_wait_list,
+        event,
+        |state, context| {
+            let src = state.buffer(src_buffer, context)?;
+            let dst = state.buffer(dst_buffer, context)?;
+            let (src_origin, region) = origin_region(src_origin, region)?;
+            let dst_origin = triple(dst_origin).ok_or(CL_INVALID_VALUE)?;
+            let src_pitch = rect_pitch(region, src_row_pitch, src_slice_pitch)?;
+            let dst_pitch = rect_pitch(region, dst_row_pitch, dst_slice_pitch)?;
+            let src_end = src_pitch.extent(src_origin, region);
+            let dst_end = dst_pitch.extent(dst_origin, region);
+            if src.size < src_end || dst.size < dst_end {
+                return Err(CL_INVALID_VALUE);
+            }
+            if id(src_buffer) == id(dst_buffer) {
+                if src_row_pitch != dst_row_pitch || src_slice_pitch != dst_slice_pitch {
+                    return Err(CL_INVALID_VALUE);
+                }
+                let src_start = src_pitch.offset(src_origin);
+                let dst_start = dst_pitch.offset(dst_origin);
+                if overlaps(
+                    src_start,
+                    src_end - src_start,
+                    dst_start,
+                    dst_end - dst_start,
+                ) {
+                    return Err(CL_MEM_COPY_OVERLAP);
+                }
+            }
+            let src = src.storage.clone();
+            let dst = dst.storage.clone();
+            Ok(Box::new(move |_| {
+                copy_rect(
+                    src.ptr, src_origin, src_pitch, dst.ptr, dst_origin, dst_pitch, region,
+                );
+                CL_SUCCESS
+            }))
+        },
+    )
+}
+
+pub unsafe fn clEnqueueReadImage(
+    command_queue: cl_command_queue,
+    image: cl_mem,
+    blocking_read: cl_bool,
+    origin: *const size_t,
+    region: *const size_t,
+    row_pitch: size_t,
+    slice_pitch: size_t,
+    ptr: *mut c_void,
+    num_events_in_wait_list: cl_uint,
+    event_wait_list: *const cl_event,
+    event: *mut cl_event,
+) -> cl_int {
+    enqueue_command(
+        command_queue,
+        CL_COMMAND_READ_IMAGE,
+        CL_FALSE != blocking_read,
+        num_events_in_wait_list,
+        event_wait_list,
+        event,
+        |state, context| {
+            let (mem, object) = state.image(image, context)?;
+            check_host_access(mem, false)?;
+            let (origin, region) = origin_region(origin, region)?;
+            let host_pitch = image_host_pitch(object, region, row_pitch, slice_pitch)?;
+            let (origin, region) = image_region(object, origin, region)?;
+            if ptr.is_null() {
+                return Err(CL_INVALID_VALUE);
+            }
+            let src = mem.storage.clone();
+            let src_pitch = image_pitch(object);
+            Ok(Box::new(move |_| {
+                copy_rect(
+                    src.ptr,
+                    origin,
+                    src_pitch,
+                    ptr.cast::<u8>(),
+                    [0; 3],
+                    host_pitch,
+                    region,
+                );
+                CL_SUCCESS
+            }))
+        },
+    )
+}
+
+pub unsafe fn clEnqueueWriteImage(
+    command_queue: cl_command_queue,
+    image: cl_mem,
+    blocking_write: cl_bool,
+    origin: *const size_t,
+    region: *const size_t,
+    input_row_pitch: size_t,
+    input_slice_pitch: size_t,
+    ptr: *const c_void,
+    num_events_in_wait_list: cl_uint,
+    event_wait_list: *const cl_event,
+    event: *mut cl_event,
+) -> cl_int {
+    enqueue_command(
+        command_queue,
+        CL_COMMAND_WRITE_IMAGE,
+        CL_FALSE != blocking_write,
+        num_events_in_wait_list,
+        event_wait_list,
+        event,
+        |state, context| {
+            let (mem, object) = state.image(image, context)?;
+            check_host_access(mem, true)?;
+            let (origin, region) = origin_region(origin, region)?;
+            let host_pitch = image_host_pitch(object, region, input_row_pitch, input_slice_pitch)?;
+            let (origin, region) = image_region(object, origin, region)?;
+            if ptr.is_null() {
+                return Err(CL_INVALID_VALUE);
+            }
+            let dst = mem.storage.clone();
+            let dst_pitch = image_pitch(object);
+            Ok(Box::new(move |_| {
+                copy_rect(
+                    ptr.cast::<u8>(),
+                    [0; 3],
+                    host_pitch,
+                    dst.ptr,
+                    origin,
+                    dst_pitch,
+                    region,
+                );
+                CL_SUCCESS
+            }))
+        },
+    )
+}
+
+/// Convert a 16 bit float to its IEEE 754 half precision bits.
+fn f32_to_f16(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xff) as i32;
+    let mantissa = bits & 0x007f_ffff;
+    if 0xff == exponent {
+        // Infinity or NaN
+        return sign | 0x7c00 | if 0 == mantissa { 0 } else { 0x0200 };
+    }
+    let exponent = exponent - 127 + 15;
+    if 0x1f <= exponent {
+        sign | 0x7c00
+    } else if exponent <= 0 {
+        if exponent < -10 {
+            sign
+        } else {
+            let mantissa = mantissa | 0x0080_0000;
+            let shift = (14 - exponent) as u32;
+            let half = 1 << (shift - 1);
+            sign | ((mantissa + half) >> shift) as u16
+        }
+    } else {
+        let value = ((exponent as u32) << 10) | (mantissa >> 13);
+        let rounded = value + ((mantissa >> 12) & 1);
+        sign | rounded as u16
+    }
+}
+
+/// Convert a fill color to the bytes of an image element.
+fn fill_element(format: cl_image_format, color: &[u8; 16]) -> Vec<u8> {
+    let channel = |i: usize| {
+        let mut bytes = [0_u8; 4];
+        bytes.copy_from_slice(&color[i * 4..i * 4 + 4]);
+        bytes
+    };
+    let channels: &[usize] = match format.image_channel_order {
+        CL_R => &[0],
+        CL_RG => &[0, 1],
+        CL_BGRA => &[2, 1, 0, 3],
+        _ => &[0, 1, 2, 3],
+    };
+    let mut element = Vec::with_capacity(16);
+    for &i in channels {
+        let float = f32::from_ne_bytes(channel(i));
+        let signed = i32::from_ne_bytes(channel(i));
+        let unsigned = u32::from_ne_bytes(channel(i));
+        match format.image_channel_data_type {
+            CL_SNORM_INT8 => {
+                element.extend_from_slice(
+                    &((float.clamp(-1.0, 1.0) * 127.0).round() as i8).to_ne_bytes(),
+                );
+            }
+            CL_SNORM_INT16 => element.extend_from_slice(
+                &((float.clamp(-1.0, 1.0) * 32767.0).round() as i16).to_ne_bytes(),
+            ),
+            CL_UNORM_INT8 => element.push((float.clamp(0.0, 1.0) * 255.0).round() as u8),
+            CL_UNORM_INT16 => element.extend_from_slice(
+                &((float.clamp(0.0, 1.0) * 65535.0).round() as u16).to_ne_bytes(),
+            ),
+            CL_SIGNED_INT8 => element.extend_from_slice(
+                &(signed.clamp(i32::from(i8::MIN), i32::from(i8::MAX)) as i8).to_ne_bytes(),
+            ),
+            CL_SIGNED_INT16 => element.extend_from_slice(
+                &(signed.clamp(i32::from(i16::MIN), i32::from(i16::MAX)) as i16).to_ne_bytes(),
+            ),
+            CL_SIGNED_INT32 => element.extend_from_slice(&signed.to_ne_bytes()),
+            CL_UNSIGNED_INT8 => element.push(unsigned.min(u32::from(u8::MAX)) as u8),
+            CL_UNSIGNED_INT16 => {
+                element
+                    .extend_from_slice(&(unsigned.min(u32::from(u16::MAX)) as u16).to_ne_bytes());
+            }
+            CL_UNSIGNED_INT32 => element.extend_from_slice(&unsigned.to_ne_bytes()),
+            CL_HALF_FLOAT => element.extend_from_slice(&f32_to_f16(float).to_ne_bytes()),
+            _ => element.extend_from_slice(&float.to_ne_bytes()),
+        }
+    }
+    element
+}
+
+pub unsafe fn clEnqueueFillImage(
+    command_queue: cl_command_queue,
+    image: cl_mem,
+    fill_color: *const c_void,
+    origin: *const size_t,
+    region: *const size_t,
+    num_events_in_wait_list: cl_uint,
+    event_wait_list: *const cl_event,
+    event: *mut cl_event,
+) -> cl_int {
+    enqueue_command(
+        command_queue,
+        CL_COMMAND_FILL_IMAGE,
+        false,
+        num_events_in_wait_list,
+        event_wait_list,
+        event,
+        |state, context| {
+            let (mem, object) = state.image(image, context)?;
+            let (origin, region) = origin_region(origin, region)?;
+            let (origin, region) = image_region(object, origin, region)?;
+            if fill_color.is_null() {
+                return Err(CL_INVALID_VALUE);
+            }
+            let color = ptr::read_unaligned(fill_color.cast::<[u8; 16]>());
+            let element = fill_element(object.format, &color);
+            let dst = mem.storage.clone();
+            let pitch = image_pitch(object);
+            Ok(Box::new(move |_| {
+                for z in 0..region[2] {
+                    for y in 0..region[1] {
+                        let offset = pitch.offset([origin[0], origin[1] + y, origin[2] + z]);
+                        fill(dst.ptr.add(offset), &element, region[0]);
+                    }
+                }
+                CL_SUCCESS
+            }))
+        },
+    )
+}
+
+pub unsafe fn clEnqueueCopyImage(
+    command_queue: cl_command_queue,
+    src_image: cl_mem,
+    dst_image: cl_mem,
+    src_origin: *const size_t,
+    dst_origin: *const size_t,
+    region: *const size_t,
+    num_events_in_wait_list: cl_uint,
+    event_wait_list: *const cl_event,
+    event: *mut cl_event,
+) -> cl_int {
+    enqueue_command(
+        command_queue,
+        CL_COMMAND_COPY_IMAGE,
+        false,
+        num_events_in_wait_list,
+        event_wait_list,
+        event,
+        |state, context| {
+            let (src, src_object) = state.image(src_image, context)?;
+            let (dst, dst_object) = state.image(dst_image, context)?;
+            if src_object.format.image_channel_order != dst_object.format.image_channel_order
+                || src_object.format.image_channel_data_type
+                    != dst_object.format.image_channel_data_type
+            {
+                return Err(CL_IMAGE_FORMAT_MISMATCH);
+            }
+            let (src_origin, pixels) = origin_region(src_origin, region)?;
+            let dst_origin = triple(dst_origin).ok_or(CL_INVALID_VALUE)?;
+            let (src_origin, region) = image_region(src_object, src_origin, pixels)?;
+            let (dst_origin, _) = image_region(dst_object, dst_origin, pixels)?;
+            if id(src_image) == id(dst_image)
+                && (0..3).all(|i| overlaps(src_origin[i], region[i], dst_origin[i], region[i]))
+            {
+                return Err(CL_MEM_COPY_OVERLAP);
+            }
+            let src_pitch = image_pitch(src_object);
+            let dst_pitch = image_pitch(dst_object);
+            let src = src.storage.clone();
+            let dst = dst.storage.clone();
+            Ok(Box::new(move |_| {
+                copy_rect(
+                    src.ptr, src_origin, src_pitch, dst.ptr, dst_origin, dst_pitch, region,
+                );
+                CL_SUCCESS
+            }))
+        },
+    )
+}
+
+pub unsafe fn clEnqueueCopyImageToBuffer(
+    command_queue: cl_command_queue,
+    src_image: cl_mem,
+    dst_buffer: cl_mem,
+    src_origin: *const size_t,
+    region: *const size_t,
+    dst_offset: size_t,
+    num_events_in_wait_list: cl_uint,
+    event_wait_list: *const cl_event,
+    event: *mut cl_event,
+) -> cl_int {
+    enqueue_command(
+        command_queue,
+        CL_COMMAND_COPY_IMAGE_TO_BUFFER,
+        false,
+        num_events_in_wait_list,
+        event_wait_list,
+        event,
+        |state, context| {
+            let (src, object) = state.image(src_image, context)?;
+            let dst = state.buffer(dst_buffer, context)?;
+            let (src_origin, region) = origin_region(src_origin, region)?;
+            let dst_pitch = image_host_pitch(object, region, 0, 0)?;
+            let (src_origin, region) = image_region(object, src_origin, region)?;
+            if dst.size < dst_offset + dst_pitch.extent([0; 3], region) {
+                return Err(CL_INVALID_VALUE);
+            }
+            let src_pitch = image_pitch(object);
+            let src = src.storage.clone();
+            let dst = dst.storage.at(dst_offset);
+            Ok(Box::new(move |_| {
+                copy_rect(
+                    src.ptr, src_origin, src_pitch, dst.ptr, [0; 3], dst_pitch, region,
+                );
+                CL_SUCCESS
+            }))
+        },
+    )
+}
+
+pub unsafe fn clEnqueueCopyBufferToImage(
+    command_queue: cl_command_queue,
+    src_buffer: cl_mem,
+    dst_image: cl_mem,
+    src_offset: size_t,
+    dst_origin: *const size_t,
+    region: *const size_t,
+    num_events_in_wait_list: cl_uint,
+    event_wait_list: *const cl_event,
+    event: *mut cl_event,
+) -> cl_int {
+    enqueue_command(
+        command_queue,
+        CL_COMMAND_COPY_BUFFER_TO_IMAGE,
+        false,
+        num_events_in_wait_list,
+        event_wait_list,
+        event,
+        |state, context| {
+            let src = state.buffer(src_buffer, context)?;
+            let (dst, object) = state.image(dst_image, context)?;
+            let (dst_origin, region) = origin_region(dst_origin, region)?;
+            let src_pitch = image_host_pitch(object, region, 0, 0)?;
+            let (dst_origin, region) = image_region(object, dst_origin, region)?;
+            if src.size < src_offset + src_pitch.extent([0; 3], region) {
+                return Err(CL_INVALID_VALUE);
+            }
+            let dst_pitch = image_pitch(object);
+            let src = src.storage.at(src_offset);
+            let dst = dst.storage.clone();
+            Ok(Box::new(move |_| {
+                copy_rect(
+                    src.ptr, [0; 3], src_pitch, dst.ptr, dst_origin, dst_pitch, region,
+                );
+                CL_SUCCESS
+            }))
+        },
+    )
+}
+
+/// Check map flags.
+const fn check_map_flags(mem: &Mem, map_flags: cl_map_flags) -> Result<(), cl_int> {
+    const VALID_FLAGS: cl_map_flags = CL_MAP_READ | CL_MAP_WRITE | CL_MAP_WRITE_INVALIDATE_REGION;
+    let writes = 0 != map_flags & (CL_MAP_WRITE | CL_MAP_WRITE_INVALIDATE_REGION);
+    if 0 != map_flags & !VALID_FLAGS
+        || (0 != map_flags & CL_MAP_WRITE_INVALIDATE_REGION
+            && 0 != map_flags & (CL_MAP_READ | CL_MAP_WRITE))
+    {
+        Err(CL_INVALID_VALUE)
+    } else if (0 != map_flags & CL_MAP_READ
+        && 0 != mem.flags & (CL_MEM_HOST_WRITE_ONLY | CL_MEM_HOST_NO_ACCESS))
+        || (writes && 0 != mem.flags & (CL_MEM_HOST_READ_ONLY | CL_MEM_HOST_NO_ACCESS))
+    {
+        Err(CL_INVALID_OPERATION)
+    } else {
+        Ok(())
+    }
+}
+
+/// A command action that does nothing.
+fn no_action() -> Action {
+    Box::new(|_| CL_SUCCESS)
+}
+
+pub unsafe fn clEnqueueMapBuffer(
+    command_queue: cl_command_queue,
+    buffer: cl_mem,
+    blocking_map: cl_bool,
+    map_flags: cl_map_flags,
+    offset: size_t,
+    size: size_t,
+    num_events_in_wait_list: cl_uint,
+    event_wait_list: *const cl_event,
+    event: *mut cl_event,
+    errcode_ret: *mut cl_int,
+) -> *mut c_void {
+    let mut pointer = ptr::null_mut();
+    let status = enqueue_command(
+        command_queue,
+        CL_COMMAND_MAP_BUFFER,
+        CL_FALSE != blocking_map,
+        num_events_in_wait_list,
+        event_wait_list,
+        event,
+        |state, context| {
+            let mem = state.buffer(buffer, context)?;
+            check_map_flags(mem, map_flags)?;
+            if 0 == size || mem.size < offset + size {
+                return Err(CL_INVALID_VALUE);
+            }
+            pointer = mem.storage.at(offset).ptr.cast::<c_void>();
+            state.mem_mut(buffer)?.maps.push(pointer as usize);
+            Ok(no_action())
+        },
+    );
+    if !errcode_ret.is_null() {
+        *errcode_ret = status;
+    }
+    if CL_SUCCESS == status {
+        pointer
+    } else {
+        ptr::null_mut()
+    }
+}
+
+pub unsafe fn clEnqueueMapImage(
+    command_queue: cl_command_queue,
+    image: cl_mem,
+    blocking_map: cl_bool,
+    map_flags: cl_map_flags,
+    origin: *const size_t,
+    region: *const size_t,
+    image_row_pitch: *mut size_t,
+    image_slice_pitch: *mut size_t,
+    num_events_in_wait_list: cl_uint,
+    event_wait_list: *const cl_event,
+    event: *mut cl_event,
+    errcode_ret: *mut cl_int,
+) -> *mut c_void {
+    let mut pointer = ptr::null_mut();
+    let status = enqueue_command(
+        command_queue,
+        CL_COMMAND_MAP_IMAGE,
+        CL_FALSE != blocking_map,
+        num_events_in_wait_list,
+        event_wait_list,
+        event,
+        |state, context| {
+            let (mem, object) = state.image(image, context)?;
+            check_map_flags(mem, map_flags)?;
+            let (origin, region) = origin_region(origin, region)?;
+            let (origin, _) = image_region(object, origin, region)?;
+            if image_row_pitch.is_null() {
+                return Err(CL_INVALID_VALUE);
+            }
+            let pitch = image_pitch(object);
+            *image_row_pitch = pitch.row;
+            if !image_slice_pitch.is_null() {
+                *image_slice_pitch = match object.desc.image_type {
+                    CL_MEM_OBJECT_IMAGE1D_ARRAY => pitch.row,
+                    CL_MEM_OBJECT_IMAGE2D_ARRAY | CL_MEM_OBJECT_IMAGE3D => pitch.slice,
+                    _ => 0,
+                };
+            }
+            pointer = mem.storage.at(pitch.offset(origin)).ptr.cast::<c_void>();
+            state.mem_mut(image)?.maps.push(pointer as usize);
+            Ok(no_action())
+        },
+    );
+    if !errcode_ret.is_null() {
+        *errcode_ret = status;
+    }
+    if CL_SUCCESS == status {
+        pointer
+    } else {
+        ptr::null_mut()
+    }
+}
+
+pub unsafe fn clEnqueueUnmapMemObject(
+    command_queue: cl_command_queue,
+    memobj: cl_mem,
+    mapped_ptr: *mut c_void,
+    num_events_in_wait_list: cl_uint,
+    event_wait_list: *const cl_event,
+    event: *mut cl_event,
+) -> cl_int {
+    enqueue_command(
+        command_queue,
+        CL_COMMAND_UNMAP_MEM_OBJECT,
+        false,
+        num_events_in_wait_list,
+        event_wait_list,
+        event,
+        |state, context| {
+            let mem = state.mem_mut(memobj)?;
+            if context != mem.context {
+                return Err(CL_INVALID_CONTEXT);
+            }
+            let index = mem
+                .maps
+                .iter()
+                .position(|pointer| mapped_ptr as usize == *pointer)
+                .ok_or(CL_INVALID_VALUE)?;
+            mem.maps.remove(index);
+            Ok(no_action())
+        },
+    )
+}
+
+pub unsafe fn clEnqueueMigrateMemObjects(
+    command_queue: cl_command_queue,
+    num_mem_objects: cl_uint,
+    mem_objects: *const cl_mem,
+    flags: cl_mem_migration_flags,
+    num_events_in_wait_list: cl_uint,
+    event_wait_list: *const cl_event,
+    event: *mut cl_event,
+) -> cl_int {
+    enqueue_command(
+        command_queue,
+        CL_COMMAND_MIGRATE_MEM_OBJECTS,
+        false,
+        num_events_in_wait_list,
+        event_wait_list,
+        event,
+        |state, context| {
+            let objects = array(mem_objects, num_mem_objects as usize);
+            if objects.is_empty()
+                || 0 != flags
+                    & !(CL_MIGRATE_MEM_OBJECT_HOST | CL_MIGRATE_MEM_OBJECT_CONTENT_UNDEFINED)
+            {
+                return Err(CL_INVALID_VALUE);
+            }
+            for object in objects {
+                if context != state.mem(*object)?.context {
+                    return Err(CL_INVALID_CONTEXT);
+                }
+            }
+            Ok(no_action())
+        },
+    )
+}
+
+// Program compilation: a preprocessor and a parser for kernel declarations.
+
+/// The kind of a kernel argument, which determines the valid argument values.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ArgKind {
+    Memory,
+    Local,
+    Sampler,
+    Value(usize),
+    /// A value of a type that the mock does not know the size of, e.g. a struct.
+    Unknown,
+}
+
+#[derive(Clone)]
+struct ArgDecl {
+    name: String,
+    type_name: String,
+    address_qualifier: cl_kernel_arg_address_qualifier,
+    access_qualifier: cl_kernel_arg_access_qualifier,
+    type_qualifier: cl_kernel_arg_type_qualifier,
+    kind: ArgKind,
+}
+
+#[derive(Clone)]
+struct KernelDecl {
+    name: String,
+    attributes: String,
+    reqd_work_group_size: [usize; 3],
+    args: Vec<ArgDecl>,
+}
+
+/// The location of a line of source code, for diagnostics.
+#[derive(Clone)]
+struct Line {
+    file: String,
+    number: usize,
+    text: String,
+}
+
+/// A build log, with clang style diagnostics.
+#[derive(Default)]
+struct BuildLog {
+    text: String,
+    errors: usize,
+}
+
+impl BuildLog {
+    fn diagnostic(&mut self, line: &Line, column: usize, level: &str, message: &str) {
+        if level.ends_with("error") {
+            self.errors += 1;
+        }
+        let _ = writeln!(
+            self.text,
+            "{}:{}:{column}: {level}: {message}",
+            line.file, line.number
+        );
+        let _ = writeln!(self.text, "{}", line.text);
+        let _ = writeln!(self.text, "{}^", " ".repeat(column.saturating_sub(1)));
+    }
+
+    fn error(&mut self, message: &str) {
+        self.errors += 1;
+        let _ = writeln!(self.text, "error: {message}");
+    }
+
+    /// Finish the log, returning true if there were no errors.
+    fn finish(&mut self) -> bool {
+        match self.errors {
+            0 => true,
+            1 => {
+                self.text.push_str("1 error generated.\n");
+                false
+            }
+            errors => {
+                let _ = writeln!(self.text, "{errors} errors generated.");
+                false
+            }
+        }
+    }
+}
+
+const fn is_identifier_start(c: u8) -> bool {
+    c.is_ascii_alphabetic() || b'_' == c
+}
+
+const fn is_identifier(c: u8) -> bool {
+    c.is_ascii_alphanumeric() || b'_' == c
+}
+
+/// Get the length of the token at the start of `text`: an identifier,
+/// number, string or character literal, or a single character.
+fn token_length(text: &str) -> usize {
+    let bytes = text.as_bytes();
+    let first = bytes[0];
+    if is_identifier_start(first) || first.is_ascii_digit() {
+        bytes
+            .iter()
+            .position(|&c| !(is_identifier(c) || (first.is_ascii_digit() && b'.' == c)))
+            .unwrap_or(bytes.len())
+    } else if b'"' == first || b'\'' == first {
+        let mut i = 1;
+        while i < bytes.len() && first != bytes[i] {
+            i += if b'\\' == bytes[i] { 2 } else { 1 };
+        }
+        (i + 1).min(bytes.len())
+    } else {
+        text.chars().next().map_or(1, char::len_utf8)
+    }
+}
+
+/// Remove the comments and line continuations from source code,
+/// keeping the same number of lines.
+fn strip_comments(source: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    let mut continued_lines = 0;
+    let mut in_comment = false;
+    let mut rest = source;
+    while !rest.is_empty() {
+        if in_comment {
+            if rest.starts_with("*/") {
+                in_comment = false;
+                line.push(' ');
+                rest = &rest[2..];
+            } else {
+                if rest.starts_with('\n') {
+                    lines.push(mem::take(&mut line));
+                }
+                rest = &rest[rest.chars().next().map_or(1, char::len_utf8)..];
+            }
+        } else if rest.starts_with("/*") {
+            in_comment = true;
+            rest = &rest[2..];
+        } else if rest.starts_with("//") {
+            let end = rest.find('\n').unwrap_or(rest.len());
+            rest = &rest[end..];
+        } else if rest.starts_with("\\\n") || rest.starts_with("\\\r\n") {
+            continued_lines += 1;
+            rest = &rest[if rest.starts_with("\\\n") { 2 } else { 3 }..];
+        } else if rest.starts_with('\n') {
+            lines.push(mem::take(&mut line));
+            for _ in 0..continued_lines {
+                lines.push(String::new());
+            }
+            continued_lines = 0;
+            rest = &rest[1..];
+        } else {
+            let length = token_length(rest);
+            line.push_str(rest[..length].trim_end_matches('\r'));
+            rest = &rest[length..];
+        }
+    }
+    lines.push(line);
+    lines
+}
+
+/// A token of a preprocessor expression.
+#[derive(Clone, PartialEq, Eq)]
+enum ExprToken {
+    Number(i64),
+    Identifier(String),
+    Punct(String),
+}
+
+fn parse_integer(text: &str) -> Option<i64> {
+    let digits = text.trim_end_matches(['u', 'U', 'l', 'L']);
+    match digits
+        .strip_prefix("0x")
+        .or_else(|| digits.strip_prefix("0X"))
+    {
+        Some(hex) => i64::from_str_radix(hex, 16).ok(),
+        None if 1 < digits.len() && digits.starts_with('0') => {
+            i64::from_str_radix(&digits[1..], 8).ok()
+        }
+        None => digits.parse().ok(),
+    }
+}
+
+fn expression_tokens(text: &str) -> Option<Vec<ExprToken>> {
+    const OPERATORS: [&str; 8] = ["||", "&&", "==", "!=", "<=", ">=", "<<", ">>"];
+    let mut tokens = Vec::new();
+    let mut rest = text.trim_start();
+    while !rest.is_empty() {
+        let length = token_length(rest);
+        let token = &rest[..length];
+        let first = token.as_bytes()[0];
+        if is_identifier_start(first) {
+            tokens.push(ExprToken::Identifier(token.to_owned()));
+        } else if first.is_ascii_digit() {
+            tokens.push(ExprToken::Number(parse_integer(token)?));
+        } else if b'\'' == first {
+            let value = token.trim_matches('\'');
+            let value = value.strip_prefix('\\').map_or(value, |escaped| escaped);
+            tokens.push(ExprToken::Number(i64::from(*value.as_bytes().first()?)));
+        } else if let Some(operator) = OPERATORS
+            .iter()
+            .find(|operator| rest.starts_with(*operator))
+        {
+            tokens.push(ExprToken::Punct((*operator).to_owned()));
+            rest = rest[operator.len()..].trim_start();
+            continue;
+        } else {
+            tokens.push(ExprToken::Punct(token.to_owned()));
+        }
+        rest = rest[length..].trim_start();
+    }
+    Some(tokens)
+}
+
+/// A precedence climbing parser for preprocessor expressions.
+struct ExprParser {
+    tokens: Vec<ExprToken>,
+    position: usize,
+}
+
+impl ExprParser {
+    fn punct(&self) -> Option<&str> {
+        match self.tokens.get(self.position) {
+            Some(ExprToken::Punct(punct)) => Some(punct),
+            _ => None,
+        }
+    }
+
+    fn expect(&mut self, punct: &str) -> Option<()> {
+        (Some(punct) == self.punct()).then(|| self.position += 1)
+    }
+
+    fn conditional(&mut self) -> Option<i64> {
+        let condition = self.binary(1)?;
+        if self.expect("?").is_some() {
+            let if_true = self.conditional()?;
+            self.expect(":")?;
+            let if_false = self.conditional()?;
+            Some(if 0 == condition { if_false } else { if_true })
+        } else {
+            Some(condition)
+        }
+    }
+
+    fn binary(&mut self, min_precedence: u8) -> Option<i64> {
+        let mut left = self.unary()?;
+        while let Some(operator) = self.punct().map(str::to_owned) {
+            let precedence = match operator.as_str() {
+                "||" => 1,
+                "&&" => 2,
+                "|" => 3,
+                "^" => 4,
+                "&" => 5,
+                "==" | "!=" => 6,
+                "<" | ">" | "<=" | ">=" => 7,
+                "<<" | ">>" => 8,
+                "+" | "-" => 9,
+                "*" | "/" | "%" => 10,
+                _ => break,
+            };
+            if precedence < min_precedence {
+                break;
+            }
+            self.position += 1;
+            let right = self.binary(precedence + 1)?;
+            left = match operator.as_str() {
+                "||" => i64::from(0 != left || 0 != right),
+                "&&" => i64::from(0 != left && 0 != right),
+                "|" => left | right,
+                "^" => left ^ right,
+                "&" => left & right,
+                "==" => i64::from(left == right),
+                "!=" => i64::from(left != right),
+                "<" => i64::from(left < right),
+                ">" => i64::from(left > right),
+                "<=" => i64::from(left <= right),
+                ">=" => i64::from(left >= right),
+                "<<" => left.checked_shl(u32::try_from(right).ok()?)?,
+                ">>" => left.checked_shr(u32::try_from(right).ok()?)?,
+                "+" => left.wrapping_add(right),
+                "-" => left.wrapping_sub(right),
+                "*" => left.wrapping_mul(right),
+                "/" => left.checked_div(right)?,
+                _ => left.checked_rem(right)?,
+            };
+        }
+        Some(left)
+    }
+
+    fn unary(&mut self) -> Option<i64> {
+        match self.tokens.get(self.position)?.clone() {
+            ExprToken::Number(value) => {
+                self.position += 1;
+                Some(value)
+            }
+            ExprToken::Identifier(_) => {
+                self.position += 1;
+                Some(0)
+            }
+            ExprToken::Punct(punct) => {
+                self.position += 1;
+                match punct.as_str() {
+                    "!" => Some(i64::from(0 == self.unary()?)),
+                    "-" => Some(self.unary()?.wrapping_neg()),
+                    "+" => self.unary(),
+                    "~" => Some(!self.unary()?),
+                    "(" => {
+                        let value = self.conditional()?;
+                        self.expect(")")?;
+                        Some(value)
+                    }
+                    _ => None,
+                }
+            }
+        }
+    }
+}
+
+/// The state of a conditional preprocessor directive.
+#[allow(clippy::struct_excessive_bools)]
+struct Condition {
+    parent_active: bool,
+    taken: bool,
+    active: bool,
+    has_else: bool,
+}
+
+/// The maximum depth of nested include files and macro expansions.
+const MAX_NESTING: usize = 32;
+
+/// A C preprocessor, which supports includes, object-like macros and
+/// conditional compilation.
+struct Preprocessor<'a> {
+    headers: &'a [(String, String)],
+    options: &'a BuildOptions,
+    macros: HashMap<String, Option<String>>,
+    log: BuildLog,
+    output: Vec<(Line, String)>,
+}
+
+impl<'a> Preprocessor<'a> {
+    fn new(headers: &'a [(String, String)], options: &'a BuildOptions) -> Self {
+        let mut macros: HashMap<String, Option<String>> = HashMap::new();
+        let mut define = |name: &str, value: &str| {
+            macros.insert(name.to_owned(), Some(value.to_owned()));
+        };
+        for extension in EXTENSIONS {
+            define(extension, "1");
+        }
+        for (name, value) in [
+            ("__OPENCL_VERSION__", "300"),
+            ("CL_VERSION_1_0", "100"),
+            ("CL_VERSION_1_1", "110"),
+            ("CL_VERSION_1_2", "120"),
+            ("CL_VERSION_2_0", "200"),
+            ("CL_VERSION_3_0", "300"),
+            ("__ENDIAN_LITTLE__", "1"),
+            ("__IMAGE_SUPPORT__", "1"),
+            ("__OPENCL_C_VERSION__", &options.c_version.to_string()),
+        ] {
+            define(name, value);
+        }
+        if 300 <= options.c_version {
+            for feature in OPENCL_C_FEATURES {
+                define(feature, "1");
+            }
+        }
+        for (name, value) in &options.defines {
+            define(name, value);
+        }
+        Self {
+            headers,
+            options,
+            macros,
+            log: BuildLog::default(),
+            output: Vec::new(),
+        }
+    }
+
+    /// Expand the object-like macros in a line of source code.
+    fn expand(&self, text: &str, depth: usize) -> String {
+        let mut result = String::with_capacity(text.len());
+        let mut rest = text;
+        while !rest.is_empty() {
+            let length = token_length(rest);
+            let token = &rest[..length];
+            match self.macros.get(token) {
+                Some(Some(value)) if depth < MAX_NESTING => {
+                    result.push_str(&self.expand(value, depth + 1));
+                }
+                _ => result.push_str(token),
+            }
+            rest = &rest[length..];
+        }
+        result
+    }
+
+    /// Evaluate the expression of an `#if` or `#elif` directive.
+    fn evaluate(&mut self, expression: &str, line: &Line, column: usize) -> bool {
+        let tokens = expression_tokens(expression).and_then(|tokens| {
+            let mut result = Vec::with_capacity(tokens.len());
+            let mut tokens = tokens.into_iter().peekable();
+            while let Some(token) = tokens.next() {
+                match token {
+                    ExprToken::Identifier(name) if "defined" == name => {
+                        let parenthesized = tokens.next_if_eq(&ExprToken::Punct("(".to_owned()));
+                        let Some(ExprToken::Identifier(name)) = tokens.next() else {
+                            return None;
+                        };
+                        if parenthesized.is_some() {
+                            tokens.next_if_eq(&ExprToken::Punct(")".to_owned()))?;
+                        }
+                        result.push(ExprToken::Number(i64::from(
+                            self.macros.contains_key(&name),
+                        )));
+                    }
+                    ExprToken::Identifier(name) => match self.macros.get(&name) {
+                        Some(Some(value)) => {
+                            result.extend(expression_tokens(&self.expand(value, 1))?);
+                        }
+                        _ => result.push(ExprToken::Number(0)),
+                    },
+                    token => result.push(token),
+                }
+            }
+            Some(result)
+        });
+        let value = tokens.and_then(|tokens| {
+            let length = tokens.len();
+            let mut parser = ExprParser {
+                tokens,
+                position: 0,
+            };
+            let value = parser.conditional()?;
+            (length == parser.position).then_some(value)
+        });
+        value.map_or_else(
+            || {
+                self.log.diagnostic(
+                    line,
+                    column,
+                    "error",
+                    "invalid expression in preprocessor directive",
+                );
+                false
+            },
+            |value| 0 != value,
+        )
+    }
+
+    fn define(&mut self, text: &str, line: &Line, column: usize) {
+        let length = text
+            .bytes()
+            .position(|c| !is_identifier(c))
+            .unwrap_or(text.len());
+        if 0 == length || !is_identifier_start(text.as_bytes()[0]) {
+            self.log
+                .diagnostic(line, column, "error", "macro name must be an identifier");
+            return;
+        }
+        let (name, value) = text.split_at(length);
+        let value = if value.starts_with('(') {
+            // A function-like macro, which is defined but not expanded
+            None
+        } else {
+            Some(value.trim().to_owned())
+        };
+        self.macros.insert(name.to_owned(), value);
+    }
+
+    fn include(&mut self, text: &str, line: &Line, column: usize, depth: usize) {
+        let name = text
+            .strip_prefix('"')
+            .and_then(|name| name.strip_suffix('"'))
+            .or_else(|| {
+                text.strip_prefix('<')
+                    .and_then(|name| name.strip_suffix('>'))
+            });
+        let Some(name) = name else {
+            self.log
+                .diagnostic(line, column, "error", "expected \"FILENAME\" or <FILENAME>");
+            return;
+        };
+        if MAX_NESTING <= depth {
+            self.log
+                .diagnostic(line, column, "error", "#include nested too deeply");
+            return;
+        }
+        let source = self
+            .headers
+            .iter()
+            .find(|(header, _)| header == name)
+            .map(|(_, source)| source.clone())
+            .or_else(|| {
+                self.options.include_dirs.iter().find_map(|directory| {
+                    std::fs::read_to_string(std::path::Path::new(directory).join(name)).ok()
+                })
+            });
+        match source {
+            Some(source) => self.preprocess(name, &source, depth + 1),
+            None => self.log.diagnostic(
+                line,
+                column,
+                "fatal error",
+                &format!("'{name}' file not found"),
+            ),
+        }
+    }
+
+    /// Preprocess a source file.
+    fn preprocess(&mut self, file: &str, source: &str, depth: usize) {
+        let original: Vec<&str> = source.lines().collect();
+        let mut conditions: Vec<Condition> = Vec::new();
+        let lines = strip_comments(source);
+        let count = lines.len();
+        for (index, text) in lines.into_iter().enumerate() {
+            let line = Line {
+                file: file.to_owned(),
+                number: index + 1,
+                text: original.get(index).copied().unwrap_or_default().to_owned(),
+            };
+            let active = conditions.last().is_none_or(|condition| condition.active);
+            let trimmed = text.trim_start();
+            let Some(directive) = trimmed.strip_prefix('#') else {
+                if active {
+                    let text = self.expand(&text, 0);
+                    self.output.push((line, text));
+                }
+                continue;
+            };
+            let directive = directive.trim_start();
+            let column = text.len() - directive.len() + 1;
+            let length = directive
+                .bytes()
+                .position(|c| !is_identifier(c))
+                .unwrap_or(directive.len());
+            let (name, rest) = directive.split_at(length);
+            let rest = rest.trim();
+            match name {
+                "if" | "ifdef" | "ifndef" => {
+                    let value = active
+                        && match name {
+                            "ifdef" => self.macros.contains_key(rest),
+                            "ifndef" => !self.macros.contains_key(rest),
+                            _ => self.evaluate(rest, &line, column),
+                        };
+                    conditions.push(Condition {
+                        parent_active: active,
+                        taken: value,
+                        active: value,
+                        has_else: false,
+                    });
+                }
+                "elif" | "else" => match conditions.pop() {
+                    Some(mut condition) if !condition.has_else => {
+                        let value = condition.parent_active
+                            && !condition.taken
+                            && ("else" == name || self.evaluate(rest, &line, column));
+                        condition.active = value;
+                        condition.taken |= value;
+                        condition.has_else = "else" == name;
+                        conditions.push(condition);
+                    }
+                    Some(condition) => {
+                        self.log.diagnostic(
+                            &line,
+                            column,
+                            "error",
+                            &format!("#{name} after #else"),
+                        );
+                        conditions.push(condition);
+                    }
+                    None => {
+                        self.log.diagnostic(
+                            &line,
+                            column,
+                            "error",
+                            &format!("#{name} without #if"),
+                        );
+                    }
+                },
+                "endif" => {
+                    if conditions.pop().is_none() {
+                        self.log
+                            .diagnostic(&line, column, "error", "#endif without #if");
+                    }
+                }
+                _ if !active => {}
+                "define" => self.define(rest, &line, column),
+                "undef" => {
+                    self.macros.remove(rest);
+                }
+                "include" => self.include(rest, &line, column, depth),
+                "error" => self.log.diagnostic(&line, column, "error", rest),
+                "warning" => {
+                    if self.options.warnings_as_errors {
+                        self.log.diagnostic(&line, column, "error", rest);
+                    } else if !self.options.no_warnings {
+                        self.log.diagnostic(&line, column, "warning", rest);
+                    }
+                }
+                "" | "pragma" | "line" => {}
+                _ => self
+                    .log
+                    .diagnostic(&line, column, "error", "invalid preprocessing directive"),
+            }
+        }
+        if !conditions.is_empty() {
+            let line = Line {
+                file: file.to_owned(),
+                number: count,
+                text: original.last().copied().unwrap_or_default().to_owned(),
+            };
+            self.log
+                .diagnostic(&line, 1, "error", "unterminated conditional directive");
+        }
+    }
+}
+
+/// A token of preprocessed source code and the index of its line.
+struct Token<'a> {
+    text: &'a str,
+    line: usize,
+    column: usize,
+}
+
+fn source_tokens(lines: &[(Line, String)]) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    for (index, (_, text)) in lines.iter().enumerate() {
+        let mut offset = 0;
+        while offset < text.len() {
+            let rest = &text[offset..];
+            let length = token_length(rest);
+            if !rest.starts_with(char::is_whitespace) {
+                tokens.push(Token {
+                    text: &rest[..length],
+                    line: index,
+                    column: offset + 1,
+                });
+            }
+            offset += length;
+        }
+    }
+    tokens
+}
+
+/// Get the size of an `OpenCL` C scalar or vector type.
+fn value_size(type_name: &str) -> Option<usize> {
+    let base = type_name.trim_end_matches(|c: char| c.is_ascii_digit());
+    let size = match base {
+        "bool" | "char" | "uchar" => 1,
+        "short" | "ushort" | "half" => 2,
+        "int" | "uint" | "float" => 4,
+        "long" | "ulong" | "double" | "size_t" | "ptrdiff_t" | "intptr_t" | "uintptr_t" => 8,
+        _ => return None,
+    };
+    match &type_name[base.len()..] {
+        "" => Some(size),
+        "3" => Some(size * 4),
+        "2" | "4" | "8" | "16" => type_name[base.len()..]
+            .parse::<usize>()
+            .ok()
+            .map(|count| size * count),
+        _ => None,
+    }
+}
+
+fn is_image_type(type_name: &str) -> bool {
+    type_name.starts_with("image")
+}
+
+/// Normalize the words of an `OpenCL` C type to its type name.
+fn type_name(words: &[&str]) -> String {
+    match words {
+        ["unsigned", "char"] => "uchar".to_owned(),
+        ["unsigned", "short"] => "ushort".to_owned(),
+        ["unsigned", "int"] | ["unsigned"] => "uint".to_owned(),
+        ["unsigned", "long"] => "ulong".to_owned(),
+        ["signed", rest @ ..] if !rest.is_empty() => rest.join(" "),
+        _ => words.join(" "),
+    }
+}
+
+/// The parser for kernel declarations in preprocessed `OpenCL` C source code.
+struct KernelParser<'a> {
+    lines: &'a [(Line, String)],
+    tokens: Vec<Token<'a>>,
+    position: usize,
+    log: &'a mut BuildLog,
+}
+
+impl<'a> KernelParser<'a> {
+    fn error_at(&mut self, index: usize, message: &str) {
+        let index = index.min(self.tokens.len().saturating_sub(1));
+        if let Some(token) = self.tokens.get(index) {
+            let line = &self.lines[token.line].0;
+            self.log.diagnostic(line, token.column, "error", message);
+        } else {
+            self.log.error(message);
+        }
+    }
+
+    fn peek(&self) -> Option<&'a str> {
+        self.tokens.get(self.position).map(|token| token.text)
+    }
+
+    /// Skip a balanced sequence of tokens, starting at an opening bracket.
+    fn skip_balanced(&mut self, open: &str, close: &str) -> Option<usize> {
+        let start = self.position;
+        let mut depth = 0;
+        while let Some(token) = self.peek() {
+            self.position += 1;
+            if open == token {
+                depth += 1;
+            } else if close == token {
+                depth -= 1;
+                if 0 == depth {
+                    return Some(start);
+                }
+            }
+        }
+        self.error_at(start, &format!("expected '{close}'"));
+        None
+    }
+
+    /// Parse any `__attribute__((...))` specifiers.
+    fn attributes(&mut self, kernel: &mut KernelDecl) -> Option<()> {
+        while Some("__attribute__") == self.peek() {
+            self.position += 1;
+            let start = self.skip_balanced("(", ")")?;
+            let tokens: Vec<&str> = self.tokens[start + 2..self.position - 2]
+                .iter()
+                .map(|token| token.text)
+                .collect();
+            if let ["reqd_work_group_size", "(", x, ",", y, ",", z, ")"] = tokens.as_slice() {
+                for (size, value) in kernel.reqd_work_group_size.iter_mut().zip([x, y, z]) {
+                    *size = parse_integer(value).unwrap_or_default() as usize;
+                }
+            }
+            if !kernel.attributes.is_empty() {
+                kernel.attributes.push(' ');
+            }
+            kernel.attributes.push_str(&tokens.concat());
+        }
+        Some(())
+    }
+
+    fn argument(&mut self, start: usize, end: usize) -> Option<ArgDecl> {
+        let mut address_qualifier = 0;
+        let mut access_qualifier = CL_KERNEL_ARG_ACCESS_NONE;
+        let mut type_qualifier = CL_KERNEL_ARG_TYPE_NONE;
+        let mut words = Vec::new();
+        let mut pointers = 0;
+        for token in &self.tokens[start..end] {
+            match token.text.trim_start_matches("__") {
+                "global" => address_qualifier = CL_KERNEL_ARG_ADDRESS_GLOBAL,
+                "local" => address_qualifier = CL_KERNEL_ARG_ADDRESS_LOCAL,
+                "constant" => address_qualifier = CL_KERNEL_ARG_ADDRESS_CONSTANT,
+                "private" => address_qualifier = CL_KERNEL_ARG_ADDRESS_PRIVATE,
+                "read_only" => access_qualifier = CL_KERNEL_ARG_ACCESS_READ_ONLY,
+                "write_only" => access_qualifier = CL_KERNEL_ARG_ACCESS_WRITE_ONLY,
+                "read_write" => access_qualifier = CL_KERNEL_ARG_ACCESS_READ_WRITE,
+                "const" => type_qualifier |= CL_KERNEL_ARG_TYPE_CONST,
+                "restrict" => type_qualifier |= CL_KERNEL_ARG_TYPE_RESTRICT,
+                "volatile" => type_qualifier |= CL_KERNEL_ARG_TYPE_VOLATILE,
+                "*" => pointers += 1,
+                "[" => break,
+                text if is_identifier_start(text.as_bytes()[0]) => words.push(token.text),
+                _ => {}
+            }
+        }
+        let name = if 2 <= words.len() {
+            words.pop().unwrap_or_default()
+        } else {
+            ""
+        };
+        let base_type = type_name(&words);
+        if base_type.is_empty() {
+            self.error_at(start, "expected parameter declarator");
+            return None;
+        }
+        let kind = if 0 < pointers {
+            if 0 == address_qualifier || CL_KERNEL_ARG_ADDRESS_PRIVATE == address_qualifier {
+                self.error_at(
+                    start,
+                    "pointer arguments to kernel functions must reside in '__global', '__constant' or '__local' address space",
+                );
+                return None;
+            }
+            if CL_KERNEL_ARG_ADDRESS_CONSTANT == address_qualifier {
+                type_qualifier |= CL_KERNEL_ARG_TYPE_CONST;
+            }
+            if CL_KERNEL_ARG_ADDRESS_LOCAL == address_qualifier {
+                ArgKind::Local
+            } else {
+                ArgKind::Memory
+            }
+        } else {
+            type_qualifier = CL_KERNEL_ARG_TYPE_NONE;
+            if is_image_type(&base_type) {
+                address_qualifier = CL_KERNEL_ARG_ADDRESS_GLOBAL;
+                if CL_KERNEL_ARG_ACCESS_NONE == access_qualifier {
+                    access_qualifier = CL_KERNEL_ARG_ACCESS_READ_ONLY;
+                }
+                ArgKind::Memory
+            } else {
+                address_qualifier = CL_KERNEL_ARG_ADDRESS_PRIVATE;
+                if "sampler_t" == base_type {
+                    ArgKind::Sampler
+                } else {
+                    value_size(&base_type).map_or(ArgKind::Unknown, ArgKind::Value)
+                }
+            }
+        };
+        Some(ArgDecl {
+            name: name.to_owned(),
+            type_name: base_type + &"*".repeat(pointers),
+            address_qualifier,
+            access_qualifier,
+            type_qualifier,
+            kind,
+        })
+    }
+
+    /// Parse a kernel declaration after the `kernel` keyword.
+    ///
+    /// returns the kernel if it is a definition, rather than a declaration.
+    #[allow(clippy::option_option)]
+    fn kernel(&mut self) -> Option<Option<KernelDecl>> {
+        let mut kernel = KernelDecl {
+            name: String::new(),
+            attributes: String::new(),
+            reqd_work_group_size: [0; 3],
+            args: Vec::new(),
+        };
+        self.attributes(&mut kernel)?;
+        if Some("void") != self.peek() {
+            self.error_at(self.position, "kernel must have void return type");
+            return None;
+        }
+        self.position += 1;
+        self.attributes(&mut kernel)?;
+        match self.peek() {
+            Some(name) if is_identifier_start(name.as_bytes()[0]) => {
+                name.clone_into(&mut kernel.name);
+                self.position += 1;
+            }
+            _ => {
+                self.error_at(self.position, "expected identifier");
+                return None;
+            }
+        }
+        if Some("(") != self.peek() {
+            self.error_at(self.position, "expected '('");
+            return None;
+        }
+        let start = self.skip_balanced("(", ")")? + 1;
+        let end = self.position - 1;
+        let is_void = end == start + 1 && "void" == self.tokens[start].text;
+        if start < end && !is_void {
+            let mut arg_start = start;
+            let mut depth = 0;
+            for index in start..=end {
+                match self.tokens[index].text {
+                    "(" | "[" => depth += 1,
+                    ")" | "]" if index < end => depth -= 1,
+                    "," if 0 == depth => {
+                        let arg = self.argument(arg_start, index)?;
+                        kernel.args.push(arg);
+                        arg_start = index + 1;
+                    }
+                    _ => {}
+                }
+            }
+            let arg = self.argument(arg_start, end)?;
+            kernel.args.push(arg);
+        }
+        self.attributes(&mut kernel)?;
+        Some((Some("{") == self.peek()).then_some(kernel))
+    }
+
+    /// Parse the kernel definitions in the source code.
+    fn kernels(&mut self) -> Option<Vec<KernelDecl>> {
+        let mut kernels: Vec<KernelDecl> = Vec::new();
+        let mut depth = 0_usize;
+        let mut ok = true;
+        while let Some(token) = self.peek() {
+            self.position += 1;
+            match token {
+                "{" => depth += 1,
+                "}" if 0 == depth => {
+                    self.error_at(self.position - 1, "extraneous closing brace ('}')");
+                    ok = false;
+                }
+                "}" => depth -= 1,
+                "kernel" | "__kernel" if 0 == depth => match self.kernel() {
+                    Some(Some(kernel)) => {
+                        if kernels.iter().any(|other| other.name == kernel.name) {
+                            self.error_at(
+                                self.position - 1,
+                                &format!("redefinition of '{}'", kernel.name),
+                            );
+                            ok = false;
+                        }
+                        kernels.push(kernel);
+                    }
+                    Some(None) => {}
+                    None => ok = false,
+                },
+                _ => {}
+            }
+        }
+        if 0 < depth {
+            self.error_at(self.tokens.len(), "expected '}'");
+            ok = false;
+        }
+        ok.then_some(kernels)
+    }
+}
+
+/// Compile `OpenCL` C source code, returning the kernel declarations and the build log.
+fn compile_source(
+    source: &str,
+    headers: &[(String, String)],
+    options: &BuildOptions,
+) -> (Option<Vec<KernelDecl>>, String) {
+    let mut preprocessor = Preprocessor::new(headers, options);
+    preprocessor.preprocess("<source>", source, 0);
+    let Preprocessor {
+        mut log, output, ..
+    } = preprocessor;
+    let kernels = if 0 == log.errors {
+        let mut parser = KernelParser {
+            lines: &output,
+            tokens: source_tokens(&output),
+            position: 0,
+            log: &mut log,
+        };
+        parser.kernels()
+    } else {
+        None
+    };
+    let ok = log.finish();
+    (kernels.filter(|_| ok), log.text)
+}
+
+/// The `OpenCL` C compiler and linker options of a program.
+#[allow(clippy::struct_excessive_bools)]
+struct BuildOptions {
+    defines: Vec<(String, String)>,
+    include_dirs: Vec<String>,
+    /// The `OpenCL` C version, e.g. 120 for `OpenCL` C 1.2.
+    c_version: u32,
+    arg_info: bool,
+    uniform_work_group_size: bool,
+    create_library: bool,
+    warnings_as_errors: bool,
+    no_warnings: bool,
+}
+
+impl BuildOptions {
+    /// Whether kernels must be enqueued with uniform work-groups.
+    const fn uniform_work_groups(&self) -> bool {
+        self.c_version < 200 || self.uniform_work_group_size
+    }
+}
+
+/// The options that the compiler ignores.
+const COMPILER_OPTIONS: &[&str] = &[
+    "-cl-single-precision-constant",
+    "-cl-fp32-correctly-rounded-divide-sqrt",
+    "-cl-opt-disable",
+    "-cl-strict-aliasing",
+    "-cl-mad-enable",
+    "-g",
+];
+
+/// The options that both the compiler and linker ignore.
+const LINKER_OPTIONS: &[&str] = &[
+    "-cl-denorms-are-zero",
+    "-cl-no-signed-zeros",
+    "-cl-unsafe-math-optimizations",
+    "-cl-finite-math-only",
+    "-cl-fast-relaxed-math",
+    "-cl-no-subgroup-ifp",
+];
+
+/// Which `OpenCL` API function the options are for.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum OptionsFor {
+    Build,
+    Compile,
+    Link,
+}
+
+/// Parse compiler or linker options.
+///
+/// returns the options, or the build log error and error code.
+fn parse_options(options: &str, options_for: OptionsFor) -> Result<BuildOptions, (String, cl_int)> {
+    let mut result = BuildOptions {
+        defines: Vec::new(),
+        include_dirs: Vec::new(),
+        c_version: 120,
+        arg_info: false,
+        uniform_work_group_size: false,
+        create_library: false,
+        warnings_as_errors: false,
+        no_warnings: false,
+    };
+    let error = |message: String| {
+        let code = if OptionsFor::Link == options_for {
+            CL_INVALID_LINKER_OPTIONS
+        } else {
+            CL_INVALID_BUILD_OPTIONS
+        };
+        Err((format!("error: {message}\n"), code))
+    };
+    let compile = OptionsFor::Link != options_for;
+    let mut words = options.split_whitespace();
+    while let Some(word) = words.next() {
+        if compile && (word.starts_with("-D") || word.starts_with("-I")) {
+            let value = match &word[2..] {
+                "" => match words.next() {
+                    Some(value) => value,
+                    None => return error(format!("argument to '{word}' is missing")),
+                },
+                value => value,
+            };
+            if word.starts_with("-I") {
+                result.include_dirs.push(value.to_owned());
+            } else {
+                let (name, value) = value.split_once('=').unwrap_or((value, "1"));
+                result.defines.push((name.to_owned(), value.to_owned()));
+            }
+        } else if let Some(version) = word.strip_prefix("-cl-std=").filter(|_| compile) {
+            result.c_version = match version {
+                "CL1.0" => 100,
+                "CL1.1" => 110,
+                "CL1.2" => 120,
+                "CL3.0" => 300,
+                _ => return error(format!("invalid value '{version}' in '{word}'")),
+            };
+        } else {
+            match word {
+                "-cl-kernel-arg-info" if compile => result.arg_info = true,
+                "-cl-uniform-work-group-size" if compile => result.uniform_work_group_size = true,
+                "-Werror" if compile => result.warnings_as_errors = true,
+                "-w" if compile => result.no_warnings = true,
+                "-create-library" | "-enable-link-options" if OptionsFor::Link == options_for => {
+                    result.create_library |= "-create-library" == word;
+                }
+                _ if compile && COMPILER_OPTIONS.contains(&word) => {}
+                _ if LINKER_OPTIONS.contains(&word) => {}
+                _ => return error(format!("unknown argument: '{word}'")),
+            }
+        }
+    }
+    Ok(result)
+}
+
+// SPIR-V parsing
+
+const SPIRV_MAGIC: u32 = 0x0723_0203;
+
+/// A SPIR-V type that may be the type of a kernel argument.
+#[derive(Clone)]
+enum SpirvType {
+    Int(u32),
+    Float(u32),
+    Vector(u32, u32),
+    Pointer(u32, u32),
+    Image(u32, bool, u32),
+    Sampler,
+    Bool,
+    Other,
+}
+
+/// The kernels and specialization constant sizes of a SPIR-V module.
+struct SpirvModule {
+    kernels: Vec<KernelDecl>,
+    spec_ids: HashMap<cl_uint, usize>,
+}
+
+fn spirv_string(words: &[u32]) -> String {
+    let bytes: Vec<u8> = words.iter().flat_map(|word| word.to_le_bytes()).collect();
+    let length = bytes.iter().position(|&c| 0 == c).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..length]).into_owned()
+}
+
+fn spirv_type_name(types: &HashMap<u32, SpirvType>, type_id: u32) -> String {
+    match types.get(&type_id) {
+        Some(SpirvType::Int(width)) => match width {
+            8 => "char",
+            16 => "short",
+            64 => "long",
+            _ => "int",
+        }
+        .to_owned(),
+        Some(SpirvType::Float(width)) => match width {
+            16 => "half",
+            64 => "double",
+            _ => "float",
+        }
+        .to_owned(),
+        Some(SpirvType::Vector(component, count)) => {
+            format!("{}{count}", spirv_type_name(types, *component))
+        }
+        Some(SpirvType::Pointer(_, pointee)) => format!("{}*", spirv_type_name(types, *pointee)),
+        Some(SpirvType::Image(dim, arrayed, _)) => {
+            let dim = match dim {
+                0 => "1d",
+                2 => "3d",
+                5 => "1d_buffer",
+                _ => "2d",
+            };
+            format!("image{dim}{}_t", if *arrayed { "_array" } else { "" })
+        }
+        Some(SpirvType::Sampler) => "sampler_t".to_owned(),
+        Some(SpirvType::Bool) => "bool".to_owned(),
+        _ => "void".to_owned(),
+    }
+}
+
+fn spirv_type_size(types: &HashMap<u32, SpirvType>, type_id: u32) -> Option<usize> {
+    match types.get(&type_id)? {
+        SpirvType::Int(width) | SpirvType::Float(width) => Some(*width as usize / 8),
+        SpirvType::Vector(component, count) => {
+            let count = if 3 == *count { 4 } else { *count as usize };
+            Some(spirv_type_size(types, *component)? * count)
+        }
+        SpirvType::Bool => Some(1),
+        _ => None,
+    }
+}
+
+fn spirv_arg(types: &HashMap<u32, SpirvType>, type_id: u32, name: String) -> ArgDecl {
+    let type_name = spirv_type_name(types, type_id);
+    let (address_qualifier, access_qualifier, kind) = match types.get(&type_id) {
+        Some(SpirvType::Pointer(storage, _)) => match storage {
+            4 => (
+                CL_KERNEL_ARG_ADDRESS_LOCAL,
+                CL_KERNEL_ARG_ACCESS_NONE,
+                ArgKind::Local,
+            ),
+            0 => (
+                CL_KERNEL_ARG_ADDRESS_CONSTANT,
+                CL_KERNEL_ARG_ACCESS_NONE,
+                ArgKind::Memory,
+            ),
+            _ => (
+                CL_KERNEL_ARG_ADDRESS_GLOBAL,
+                CL_KERNEL_ARG_ACCESS_NONE,
+                ArgKind::Memory,
+            ),
+        },
+        Some(SpirvType::Image(_, _, access)) => (
+            CL_KERNEL_ARG_ADDRESS_GLOBAL,
+            match access {
+                1 => CL_KERNEL_ARG_ACCESS_WRITE_ONLY,
+                2 => CL_KERNEL_ARG_ACCESS_READ_WRITE,
+                _ => CL_KERNEL_ARG_ACCESS_READ_ONLY,
+            },
+            ArgKind::Memory,
+        ),
+        Some(SpirvType::Sampler) => (
+            CL_KERNEL_ARG_ADDRESS_PRIVATE,
+            CL_KERNEL_ARG_ACCESS_NONE,
+            ArgKind::Sampler,
+        ),
+        _ => (
+            CL_KERNEL_ARG_ADDRESS_PRIVATE,
+            CL_KERNEL_ARG_ACCESS_NONE,
+            spirv_type_size(types, type_id).map_or(ArgKind::Unknown, ArgKind::Value),
+        ),
+    };
+    ArgDecl {
+        name,
+        type_name,
+        address_qualifier,
+        access_qualifier,
+        type_qualifier: CL_KERNEL_ARG_TYPE_NONE,
+        kind,
+    }
+}
+
+/// Parse the kernel entry points and specialization constants of a SPIR-V module.
+fn parse_spirv(il: &[u8]) -> Option<SpirvModule> {
+    if !il.len().is_multiple_of(4) || il.len() < 20 {
+        return None;
+    }
+    let words: Vec<u32> = il
+        .chunks_exact(4)
+        .map(|chunk| u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        .collect();
+    if SPIRV_MAGIC != words[0] {
+        return None;
+    }
+
+    let mut names: HashMap<u32, String> = HashMap::new();
+    let mut entry_points: Vec<(u32, String)> = Vec::new();
+    let mut local_sizes: HashMap<u32, [usize; 3]> = HashMap::new();
+    let mut types: HashMap<u32, SpirvType> = HashMap::new();
+    let mut spec_decorations: HashMap<u32, cl_uint> = HashMap::new();
+    let mut spec_types: HashMap<u32, u32> = HashMap::new();
+    let mut parameters: HashMap<u32, Vec<(u32, u32)>> = HashMap::new();
+    let mut function = None;
+
+    let mut index = 5;
+    while index < words.len() {
+        let count = (words[index] >> 16) as usize;
+        let opcode = words[index] & 0xffff;
+        if 0 == count || words.len() < index + count {
+            return None;
+        }
+        let operands = &words[index + 1..index + count];
+        match (opcode, operands) {
+            (5, [target, name @ ..]) => {
+                names.insert(*target, spirv_string(name));
+            }
+            // OpEntryPoint with the Kernel execution model
+            (15, [6, function, name @ ..]) => entry_points.push((*function, spirv_string(name))),
+            // OpExecutionMode LocalSize
+            (16, [entry_point, 17, x, y, z, ..]) => {
+                local_sizes.insert(*entry_point, [*x as usize, *y as usize, *z as usize]);
+            }
+            (20, [id]) => {
+                types.insert(*id, SpirvType::Bool);
+            }
+            (21, [id, width, ..]) => {
+                types.insert(*id, SpirvType::Int(*width));
+            }
+            (22, [id, width, ..]) => {
+                types.insert(*id, SpirvType::Float(*width));
+            }
+            (23, [id, component, count]) => {
+                types.insert(*id, SpirvType::Vector(*component, *count));
+            }
+            (25, [id, _, dim, _, arrayed, ..]) => {
+                let access = operands.get(8).copied().unwrap_or_default();
+                types.insert(*id, SpirvType::Image(*dim, 0 != *arrayed, access));
+            }
+            (26, [id]) => {
+                types.insert(*id, SpirvType::Sampler);
+            }
+            (32, [id, storage, pointee]) => {
+                types.insert(*id, SpirvType::Pointer(*storage, *pointee));
+            }
+            (19 | 24 | 27..=31 | 33..=39, [id, ..]) => {
+                types.insert(*id, SpirvType::Other);
+            }
+            // OpSpecConstantTrue, OpSpecConstantFalse and OpSpecConstant
+            (48..=50, [result_type, id, ..]) => {
+                spec_types.insert(*id, *result_type);
+            }
+            // OpDecorate SpecId
+            (71, [target, 1, spec_id]) => {
+                spec_decorations.insert(*target, *spec_id);
+            }
+            (54, [_, id, ..]) => {
+                function = Some(*id);
+                parameters.entry(*id).or_default();
+            }
+            (55, [result_type, id]) => {
+                if let Some(function) = function {
+                    parameters
+                        .entry(function)
+                        .or_default()
+                        .push((*result_type, *id));
+                }
+            }
+            (56, _) => function = None,
+            _ => {}
+        }
+        index += count;
+    }
+
+    let kernels = entry_points
+        .into_iter()
+        .map(|(function, name)| {
+            let reqd_work_group_size = local_sizes.get(&function).copied().unwrap_or_default();
+            let attributes = if [0; 3] == reqd_work_group_size {
+                String::new()
+            } else {
+                let [x, y, z] = reqd_work_group_size;
+                format!("reqd_work_group_size({x},{y},{z})")
+            };
+            let args = parameters
+                .get(&function)
+                .map(|parameters| {
+                    parameters
+                        .iter()
+                        .map(|(type_id, id)| {
+                            spirv_arg(&types, *type_id, names.get(id).cloned().unwrap_or_default())
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            KernelDecl {
+                name,
+                attributes,
+                reqd_work_group_size,
+                args,
+            }
+        })
+        .collect();
+    let spec_ids = spec_decorations
+        .into_iter()
+        .filter_map(|(id, spec_id)| {
+            let size = spirv_type_size(&types, *spec_types.get(&id)?)?;
+            Some((spec_id, size))
+        })
+        .collect();
+    Some(SpirvModule { kernels, spec_ids })
+}
+
+// Program binaries
+
+const BINARY_MAGIC: &[u8; 8] = b"cl3mock\0";
+
+/// Encode a program binary: the binary type, build options and kernel declarations.
+fn encode_binary(program: &Program) -> Vec<u8> {
+    let mut text = format!(
+        "{} {} {}\n",
+        program.binary_type,
+        u8::from(program.arg_info),
+        u8::from(program.uniform_work_groups)
+    );
+    for kernel in &program.kernels {
+        let [x, y, z] = kernel.reqd_work_group_size;
+        let _ = writeln!(
+            text,
+            "K\t{}\t{x},{y},{z}\t{}",
+            kernel.name, kernel.attributes
+        );
+        for arg in &kernel.args {
+            let kind = match arg.kind {
+                ArgKind::Memory => "m".to_owned(),
+                ArgKind::Local => "l".to_owned(),
+                ArgKind::Sampler => "s".to_owned(),
+                ArgKind::Value(size) => format!("v{size}"),
+                ArgKind::Unknown => "u".to_owned(),
+            };
+            let _ = writeln!(
+                text,
+                "A\t{kind}\t{}\t{}\t{}\t{}\t{}",
+                arg.address_qualifier,
+                arg.access_qualifier,
+                arg.type_qualifier,
+                arg.type_name,
+                arg.name
+            );
+        }
+    }
+    let mut binary = BINARY_MAGIC.to_vec();
+    binary.extend_from_slice(text.as_bytes());
+    binary
+}
+
+/// The contents of a program binary.
+#[allow(clippy::struct_field_names)]
+struct Binary {
+    binary_type: cl_program_binary_type,
+    arg_info: bool,
+    uniform_work_groups: bool,
+    kernels: Vec<KernelDecl>,
+}
+
+fn decode_binary(binary: &[u8]) -> Option<Binary> {
+    let text = std::str::from_utf8(binary.strip_prefix(BINARY_MAGIC)?).ok()?;
+    let mut lines = text.lines();
+    let mut header = lines.next()?.split(' ');
+    let binary_type = header.next()?.parse().ok()?;
+    let arg_info = "1" == header.next()?;
+    let uniform_work_groups = "1" == header.next()?;
+    if !matches!(
+        binary_type,
+        CL_PROGRAM_BINARY_TYPE_COMPILED_OBJECT
+            | CL_PROGRAM_BINARY_TYPE_LIBRARY
+            | CL_PROGRAM_BINARY_TYPE_EXECUTABLE
+    ) {
+        return None;
+    }
+    let mut kernels: Vec<KernelDecl> = Vec::new();
+    for line in lines {
+        let fields: Vec<&str> = line.split('\t').collect();
+        match fields.as_slice() {
+            ["K", name, sizes, attributes] => {
+                let mut reqd_work_group_size = [0; 3];
+                for (size, value) in reqd_work_group_size.iter_mut().zip(sizes.split(',')) {
+                    *size = value.parse().ok()?;
+                }
+                kernels.push(KernelDecl {
+                    name: (*name).to_owned(),
+                    attributes: (*attributes).to_owned(),
+                    reqd_work_group_size,
+                    args: Vec::new(),
+                });
+            }
+            ["A", kind, address, access, qualifier, type_name, name] => {
+                let kind = match *kind {
+                    "m" => ArgKind::Memory,
+                    "l" => ArgKind::Local,
+                    "s" => ArgKind::Sampler,
+                    "u" => ArgKind::Unknown,
+                    size => ArgKind::Value(size.strip_prefix('v')?.parse().ok()?),
+                };
+                kernels.last_mut()?.args.push(ArgDecl {
+                    name: (*name).to_owned(),
+                    type_name: (*type_name).to_owned(),
+                    address_qualifier: address.parse().ok()?,
+                    access_qualifier: access.parse().ok()?,
+                    type_qualifier: qualifier.parse().ok()?,
+                    kind,
+                });
+            }
+            _ => return None,
+        }
+    }
+    Some(Binary {
+        binary_type,
+        arg_info,
+        uniform_work_groups,
+        kernels,
+    })
+}
+
+/// The built-in kernel declarations.
+fn built_in_kernel(name: &str) -> Option<KernelDecl> {
+    let arg = |name: &str, type_qualifier| ArgDecl {
+        name: name.to_owned(),
+        type_name: "uchar*".to_owned(),
+        address_qualifier: CL_KERNEL_ARG_ADDRESS_GLOBAL,
+        access_qualifier: CL_KERNEL_ARG_ACCESS_NONE,
+        type_qualifier,
+        kind: ArgKind::Memory,
+    };
+    ("copy_buffer" == name).then(|| KernelDecl {
+        name: name.to_owned(),
+        attributes: String::new(),
+        reqd_work_group_size: [0; 3],
+        args: vec![
+            arg("src", CL_KERNEL_ARG_TYPE_CONST),
+            arg("dst", CL_KERNEL_ARG_TYPE_NONE),
+        ],
+    })
+}
+
+// Program Object APIs
+
+impl Program {
+    fn new(context: usize, origin: Origin) -> Self {
+        Self {
+            ref_count: 1,
+            context,
+            devices: vec![handle(DEVICE)],
+            origin,
+            source: String::new(),
+            il: Vec::new(),
+            binary_type: CL_PROGRAM_BINARY_TYPE_NONE,
+            build_status: CL_BUILD_NONE,
+            build_options: String::new(),
+            build_log: String::new(),
+            arg_info: false,
+            uniform_work_groups: true,
+            kernels: Vec::new(),
+            spec_ids: HashMap::new(),
+            spec_constants: HashMap::new(),
+        }
+    }
+
+    /// Set the result of building, compiling or linking the program.
+    fn set_build_result(
+        &mut self,
+        kernels: Option<Vec<KernelDecl>>,
+        binary_type: cl_program_binary_type,
+        log: String,
+    ) -> bool {
+        self.build_log = log;
+        if let Some(kernels) = kernels {
+            self.kernels = kernels;
+            self.binary_type = binary_type;
+            self.build_status = CL_BUILD_SUCCESS;
+            true
+        } else {
+            self.kernels.clear();
+            self.binary_type = CL_PROGRAM_BINARY_TYPE_NONE;
+            self.build_status = CL_BUILD_ERROR;
+            false
+        }
+    }
+}
+
+impl State {
+    fn insert_program(&mut self, program: Program) -> usize {
+        let object = self.new_id();
+        self.retain_context(program.context);
+        self.programs.insert(object, program);
+        object
+    }
+
+    fn has_kernels(&self, program: usize) -> bool {
+        self.kernels
+            .values()
+            .any(|kernel| program == kernel.program)
+    }
+}
+
+pub unsafe fn clCreateProgramWithSource(
+    context: cl_context,
+    count: cl_uint,
+    strings: *const *const c_char,
+    lengths: *const size_t,
+    errcode_ret: *mut cl_int,
+) -> cl_program {
+    let result = with_state(|state| {
+        state.context(context)?;
+        let strings = array(strings, count as usize);
+        if strings.is_empty() || strings.iter().any(|string| string.is_null()) {
+            return Err(CL_INVALID_VALUE);
+        }
+        let mut source = String::new();
+        for (i, string) in strings.iter().enumerate() {
+            let length = if lengths.is_null() {
+                0
+            } else {
+                *lengths.add(i)
+            };
+            let bytes = if 0 == length {
+                CStr::from_ptr(*string).to_bytes()
+            } else {
+                slice::from_raw_parts(string.cast::<u8>(), length)
+            };
+            source.push_str(&String::from_utf8_lossy(bytes));
+        }
+        let mut program = Program::new(id(context), Origin::Source);
+        program.source = source;
+        Ok(state.insert_program(program))
+    });
+    object_result(result, errcode_ret)
+}
+
+pub unsafe fn clCreateProgramWithIL(
+    context: cl_context,
+    il: *const c_void,
+    length: size_t,
+    errcode_ret: *mut cl_int,
+) -> cl_program {
+    let result = with_state(|state| {
+        state.context(context)?;
+        let il = array(il.cast::<u8>(), length);
+        let module = parse_spirv(il).ok_or(CL_INVALID_VALUE)?;
+        let mut program = Program::new(id(context), Origin::Il);
+        program.il = il.to_vec();
+        program.spec_ids = module.spec_ids;
+        Ok(state.insert_program(program))
+    });
+    object_result(result, errcode_ret)
+}
+
+pub unsafe fn clCreateProgramWithBinary(
+    context: cl_context,
+    num_devices: cl_uint,
+    device_list: *const cl_device_id,
+    lengths: *const size_t,
+    binaries: *const *const c_uchar,
+    binary_status: *mut cl_int,
+    errcode_ret: *mut cl_int,
+) -> cl_program {
+    let result = with_state(|state| {
+        state.context(context)?;
+        let devices = self::device_list(num_devices, device_list)?;
+        if devices.is_empty() || lengths.is_null() || binaries.is_null() {
+            return Err(CL_INVALID_VALUE);
+        }
+        let mut decoded = None;
+        let mut status = CL_SUCCESS;
+        for i in 0..devices.len() {
+            let length = *lengths.add(i);
+            let binary = *binaries.add(i);
+            if 0 == length || binary.is_null() {
+                return Err(CL_INVALID_VALUE);
+            }
+            let binary = decode_binary(slice::from_raw_parts(binary, length));
+            if !binary_status.is_null() {
+                *binary_status.add(i) = if binary.is_some() {
+                    CL_SUCCESS
+                } else {
+                    CL_INVALID_BINARY
+                };
+            }
+            match binary {
+                Some(binary) => decoded = Some(binary),
+                None => status = CL_INVALID_BINARY,
+            }
+        }
+        check(status)?;
+        let binary = decoded.ok_or(CL_INVALID_BINARY)?;
+        let mut program = Program::new(id(context), Origin::Binary);
+        program.devices = devices;
+        program.binary_type = binary.binary_type;
+        program.arg_info = binary.arg_info;
+        program.uniform_work_groups = binary.uniform_work_groups;
+        program.kernels = binary.kernels;
+        Ok(state.insert_program(program))
+    });
+    object_result(result, errcode_ret)
+}
+
+pub unsafe fn clCreateProgramWithBuiltInKernels(
+    context: cl_context,
+    num_devices: cl_uint,
+    device_list: *const cl_device_id,
+    kernel_names: *const c_char,
+    errcode_ret: *mut cl_int,
+) -> cl_program {
+    let result = with_state(|state| {
+        state.context(context)?;
+        let devices = self::device_list(num_devices, device_list)?;
+        if devices.is_empty() || kernel_names.is_null() {
+            return Err(CL_INVALID_VALUE);
+        }
+        let names = CStr::from_ptr(kernel_names).to_string_lossy();
+        let kernels = names
+            .split(';')
+            .map(|name| built_in_kernel(name.trim()))
+            .collect::<Option<Vec<KernelDecl>>>()
+            .ok_or(CL_INVALID_VALUE)?;
+        let mut program = Program::new(id(context), Origin::BuiltIn);
+        program.devices = devices;
+        program.build_status = CL_BUILD_SUCCESS;
+        program.binary_type = CL_PROGRAM_BINARY_TYPE_EXECUTABLE;
+        program.uniform_work_groups = false;
+        program.kernels = kernels;
+        Ok(state.insert_program(program))
+    });
+    object_result(result, errcode_ret)
+}
+
+pub unsafe fn clRetainProgram(program: cl_program) -> cl_int {
+    with_state(|state| {
+        state.program_mut(program)?.ref_count += 1;
+        Ok(())
+    })
+    .err()
+    .unwrap_or(CL_SUCCESS)
+}
+
+pub unsafe fn clReleaseProgram(program: cl_program) -> cl_int {
+    with_state(|state| {
+        state.program(program)?;
+        state.release_program(id(program));
+        Ok(())
+    })
+    .err()
+    .unwrap_or(CL_SUCCESS)
+}
+
+/// Check the parameters common to building and compiling programs.
+unsafe fn check_build(
+    state: &State,
+    program: cl_program,
+    num_devices: cl_uint,
+    device_list: *const cl_device_id,
+    pfn_notify: Option<ProgramNotify>,
+    user_data: *mut c_void,
+) -> Result<(), cl_int> {
+    let object = state.program(program)?;
+    let devices = self::device_list(num_devices, device_list)?;
+    if devices
+        .iter()
+        .any(|device| !object.devices.contains(device))
+    {
+        return Err(CL_INVALID_DEVICE);
+    }
+    if pfn_notify.is_none() && !user_data.is_null() {
+        return Err(CL_INVALID_VALUE);
+    }
+    if Origin::BuiltIn == object.origin || state.has_kernels(id(program)) {
+        return Err(CL_INVALID_OPERATION);
+    }
+    Ok(())
+}
+
+unsafe fn options_string(options: *const c_char) -> String {
+    if options.is_null() {
+        String::new()
+    } else {
+        CStr::from_ptr(options).to_string_lossy().into_owned()
+    }
+}
+
+pub unsafe fn clBuildProgram(
+    program: cl_program,
+    num_devices: cl_uint,
+    device_list: *const cl_device_id,
+    options: *const c_char,
+    pfn_notify: Option<ProgramNotify>,
+    user_data: *mut c_void,
+) -> cl_int {
+    with_state(|state| {
+        check_build(
+            state,
+            program,
+            num_devices,
+            device_list,
+            pfn_notify,
+            user_data,
+        )?;
+        let options = options_string(options);
+        let object = state.program_mut(program)?;
+        object.build_options.clone_from(&options);
+        let result = match parse_options(&options, OptionsFor::Build) {
+            Ok(parsed) => {
+                let (kernels, log) = match object.origin {
+                    Origin::Source => compile_source(&object.source, &[], &parsed),
+                    Origin::Il => (
+                        parse_spirv(&object.il).map(|module| module.kernels),
+                        String::new(),
+                    ),
+                    _ if CL_PROGRAM_BINARY_TYPE_LIBRARY == object.binary_type => {
+                        return Err(CL_INVALID_BINARY);
+                    }
+                    _ => (Some(mem::take(&mut object.kernels)), String::new()),
+                };
+                if matches!(object.origin, Origin::Source | Origin::Il) {
+                    object.arg_info = parsed.arg_info;
+                    object.uniform_work_groups = parsed.uniform_work_groups();
+                }
+                if object.set_build_result(kernels, CL_PROGRAM_BINARY_TYPE_EXECUTABLE, log) {
+                    Ok(())
+                } else {
+                    Err(CL_BUILD_PROGRAM_FAILURE)
+                }
+            }
+            Err((log, error)) => {
+                object.set_build_result(None, CL_PROGRAM_BINARY_TYPE_NONE, log);
+                Err(error)
+            }
+        };
+        if let Some(notify) = pfn_notify {
+            state
+                .notifications
+                .push(Notification::Program(notify, id(program), user_data));
+        }
+        result
+    })
+    .err()
+    .unwrap_or(CL_SUCCESS)
+}
+
+pub unsafe fn clCompileProgram(
+    program: cl_program,
+    num_devices: cl_uint,
+    device_list: *const cl_device_id,
+    options: *const c_char,
+    num_input_headers: cl_uint,
+    input_headers: *const cl_program,
+    header_include_names: *const *const c_char,
+    pfn_notify: Option<ProgramNotify>,
+    user_data: *mut c_void,
+) -> cl_int {
+    with_state(|state| {
+        check_build(
+            state,
+            program,
+            num_devices,
+            device_list,
+            pfn_notify,
+            user_data,
+        )?;
+        let input_headers = array(input_headers, num_input_headers as usize);
+        if (0 == num_input_headers) != (input_headers.is_empty() && header_include_names.is_null())
+            || (0 < num_input_headers && header_include_names.is_null())
+        {
+            return Err(CL_INVALID_VALUE);
+        }
+        let mut headers = Vec::with_capacity(input_headers.len());
+        for (i, header) in input_headers.iter().enumerate() {
+            let name = *header_include_names.add(i);
+            if name.is_null() {
+                return Err(CL_INVALID_VALUE);
+            }
+            let name = CStr::from_ptr(name).to_string_lossy().into_owned();
+            headers.push((name, state.program(*header)?.source.clone()));
+        }
+        let options = options_string(options);
+        let object = state.program_mut(program)?;
+        object.build_options.clone_from(&options);
+        let result = match parse_options(&options, OptionsFor::Compile) {
+            Ok(parsed) => {
+                let (kernels, log) = match object.origin {
+                    Origin::Source => compile_source(&object.source, &headers, &parsed),
+                    Origin::Il => (
+                        parse_spirv(&object.il).map(|module| module.kernels),
+                        String::new(),
+                    ),
+                    _ => return Err(CL_INVALID_OPERATION),
+                };
+                object.arg_info = parsed.arg_info;
+                object.uniform_work_groups = parsed.uniform_work_groups();
+                if object.set_build_result(kernels, CL_PROGRAM_BINARY_TYPE_COMPILED_OBJECT, log) {
+                    Ok(())
+                } else {
+                    Err(CL_COMPILE_PROGRAM_FAILURE)
+                }
+            }
+            Err((log, error)) => {
+                object.set_build_result(None, CL_PROGRAM_BINARY_TYPE_NONE, log);
+                Err(error)
+            }
+        };
+        if let Some(notify) = pfn_notify {
+            state
+                .notifications
+                .push(Notification::Program(notify, id(program), user_data));
+        }
+        result
+    })
+    .err()
+    .unwrap_or(CL_SUCCESS)
+}
+
+pub unsafe fn clLinkProgram(
+    context: cl_context,
+    num_devices: cl_uint,
+    device_list: *const cl_device_id,
+    options: *const c_char,
+    num_input_programs: cl_uint,
+    input_programs: *const cl_program,
+    pfn_notify: Option<ProgramNotify>,
+    user_data: *mut c_void,
+    errcode_ret: *mut cl_int,
+) -> cl_program {
+    let mut status = CL_SUCCESS;
+    let result = with_state(|state| {
+        state.context(context)?;
+        let devices = self::device_list(num_devices, device_list)?;
+        let inputs = array(input_programs, num_input_programs as usize);
+        if inputs.is_empty() || (pfn_notify.is_none() && !user_data.is_null()) {
+            return Err(CL_INVALID_VALUE);
+        }
+        let options = options_string(options);
+        let parsed = parse_options(&options, OptionsFor::Link).map_err(|(_, error)| error)?;
+
+        let mut program = Program::new(id(context), Origin::Linked);
+        if !devices.is_empty() {
+            program.devices = devices;
+        }
+        program.build_options = options;
+        program.uniform_work_groups = false;
+        let mut kernels: Vec<KernelDecl> = Vec::new();
+        let mut log = BuildLog::default();
+        for input in inputs {
+            let input = state.program(*input)?;
+            if id(context) != input.context {
+                return Err(CL_INVALID_CONTEXT);
+            }
+            if !matches!(
+                input.binary_type,
+                CL_PROGRAM_BINARY_TYPE_COMPILED_OBJECT | CL_PROGRAM_BINARY_TYPE_LIBRARY
+            ) {
+                return Err(CL_INVALID_OPERATION);
+            }
+            program.arg_info |= input.arg_info;
+            program.uniform_work_groups |= input.uniform_work_groups;
+            for kernel in &input.kernels {
+                if kernels.iter().any(|other| other.name == kernel.name) {
+                    log.error(&format!("duplicate symbol: '{}'", kernel.name));
+                } else {
+                    kernels.push(kernel.clone());
+                }
+            }
+        }
+        let binary_type = if parsed.create_library {
+            CL_PROGRAM_BINARY_TYPE_LIBRARY
+        } else {
+            CL_PROGRAM_BINARY_TYPE_EXECUTABLE
+        };
+        let ok = log.finish();
+        if !program.set_build_result(ok.then_some(kernels), binary_type, log.text) {
+            status = CL_LINK_PROGRAM_FAILURE;
+        }
+        let program = state.insert_program(program);
+        if let Some(notify) = pfn_notify {
+            state
+                .notifications
+                .push(Notification::Program(notify, program, user_data));
+        }
+        Ok(program)
+    });
+    let program = object_result(result, errcode_ret);
+    if !errcode_ret.is_null() && CL_SUCCESS != status {
+        *errcode_ret = status;
+    }
+    program
+}
+
+pub unsafe fn clSetProgramSpecializationConstant(
+    program: cl_program,
+    spec_id: cl_uint,
+    spec_size: size_t,
+    spec_value: *const c_void,
+) -> cl_int {
+    with_state(|state| {
+        let object = state.program_mut(program)?;
+        if Origin::Il != object.origin {
+            return Err(CL_INVALID_PROGRAM);
+        }
+        let size = *object.spec_ids.get(&spec_id).ok_or(CL_INVALID_SPEC_ID)?;
+        if size != spec_size || spec_value.is_null() {
+            return Err(CL_INVALID_VALUE);
+        }
+        let value = array(spec_value.cast::<u8>(), spec_size).to_vec();
+        object.spec_constants.insert(spec_id, value);
+        Ok(())
+    })
+    .err()
+    .unwrap_or(CL_SUCCESS)
+}
+
+pub unsafe fn clGetProgramInfo(
+    program: cl_program,
+    param_name: cl_program_info,
+    param_value_size: size_t,
+    param_value: *mut c_void,
+    param_value_size_ret: *mut size_t,
+) -> cl_int {
+    let info = with_state(|state| {
+        let object = state.program(program)?;
+        let is_executable = CL_PROGRAM_BINARY_TYPE_EXECUTABLE == object.binary_type;
+        let binary = || {
+            if CL_PROGRAM_BINARY_TYPE_NONE == object.binary_type {
+                Vec::new()
+            } else {
+                encode_binary(object)
+            }
+        };
+        match param_name {
+            CL_PROGRAM_REFERENCE_COUNT => value_info(object.ref_count),
+            CL_PROGRAM_CONTEXT => value_info(object.context),
+            CL_PROGRAM_NUM_DEVICES => value_info(object.devices.len() as cl_uint),
+            CL_PROGRAM_DEVICES => {
+                let devices: Vec<usize> = object.devices.iter().map(|device| id(*device)).collect();
+                slice_info(&devices)
+            }
+            CL_PROGRAM_SOURCE => str_info(&object.source),
+            CL_PROGRAM_IL => slice_info(&object.il),
+            CL_PROGRAM_BINARY_SIZES => {
+                let size = binary().len();
+                slice_info(&vec![size; object.devices.len()])
+            }
+            CL_PROGRAM_BINARIES => {
+                let binary = binary();
+                if !param_value.is_null() {
+                    let count = param_value_size / mem::size_of::<*mut u8>();
+                    let pointers = slice::from_raw_parts(param_value.cast::<*mut u8>(), count);
+                    for pointer in pointers.iter().filter(|pointer| !pointer.is_null()) {
+                        ptr::copy_nonoverlapping(binary.as_ptr(), *pointer, binary.len());
+                    }
+                }
+                // Return the size of the array of pointers
+                let pointers = vec![0_usize; object.devices.len()];
+                slice_info(&pointers)
+            }
+            CL_PROGRAM_NUM_KERNELS if is_executable => value_info(object.kernels.len()),
+            CL_PROGRAM_KERNEL_NAMES if is_executable => {
+                let names: Vec<&str> = object
+                    .kernels
+                    .iter()
+                    .map(|kernel| kernel.name.as_str())
+                    .collect();
+                str_info(&names.join(";"))
+            }
+            CL_PROGRAM_NUM_KERNELS | CL_PROGRAM_KERNEL_NAMES => Err(CL_INVALID_PROGRAM_EXECUTABLE),
+            CL_PROGRAM_SCOPE_GLOBAL_CTORS_PRESENT | CL_PROGRAM_SCOPE_GLOBAL_DTORS_PRESENT => {
+                value_info::<cl_bool>(CL_FALSE)
+            }
+            _ => Err(CL_INVALID_VALUE),
+        }
+    });
+    write_info(info, param_value_size, param_value, param_value_size_ret)
+}
+
+pub unsafe fn clGetProgramBuildInfo(
+    program: cl_program,
+    device: cl_device_id,
+    param_name: cl_program_build_info,
+    param_value_size: size_t,
+    param_value: *mut c_void,
+    param_value_size_ret: *mut size_t,
+) -> cl_int {
+    let info = with_state(|state| {
+        let object = state.program(program)?;
+        if !object.devices.contains(&device) {
+            return Err(CL_INVALID_DEVICE);
+        }
+        match param_name {
+            CL_PROGRAM_BUILD_STATUS => value_info(object.build_status),
+            CL_PROGRAM_BUILD_OPTIONS => str_info(&object.build_options),
+            CL_PROGRAM_BUILD_LOG => str_info(&object.build_log),
+            CL_PROGRAM_BINARY_TYPE => value_info(object.binary_type as cl_uint),
+            CL_PROGRAM_BUILD_GLOBAL_VARIABLE_TOTAL_SIZE => value_info::<size_t>(0),
+            _ => Err(CL_INVALID_VALUE),
+        }
+    });
+    write_info(info, param_value_size, param_value, param_value_size_ret)
+}
+
+// Kernel Object APIs
+
+impl State {
+    fn insert_kernel(&mut self, program: usize, decl: KernelDecl) -> usize {
+        let object = self.new_id();
+        let Some(owner) = self.programs.get_mut(&program) else {
+            return object;
+        };
+        owner.ref_count += 1;
+        let kernel = Kernel {
+            ref_count: 1,
+            context: owner.context,
+            program,
+            args: vec![None; decl.args.len()],
+            decl,
+            arg_info: owner.arg_info,
+            uniform_work_groups: owner.uniform_work_groups,
+            built_in: Origin::BuiltIn == owner.origin,
+        };
+        self.kernels.insert(object, kernel);
+        object
+    }
+
+    /// Get the kernel declarations of a program executable.
+    fn executable_kernels(&self, program: cl_program) -> Result<Vec<KernelDecl>, cl_int> {
+        let object = self.program(program)?;
+        if CL_PROGRAM_BINARY_TYPE_EXECUTABLE == object.binary_type {
+            Ok(object.kernels.clone())
+        } else {
+            Err(CL_INVALID_PROGRAM_EXECUTABLE)
+        }
+    }
+}
+
+pub unsafe fn clCreateKernel(
+    program: cl_program,
+    kernel_name: *const c_char,
+    errcode_ret: *mut cl_int,
+) -> cl_kernel {
+    let result = with_state(|state| {
+        let kernels = state.executable_kernels(program)?;
+        if kernel_name.is_null() {
+            return Err(CL_INVALID_VALUE);
+        }
+        let name = CStr::from_ptr(kernel_name).to_string_lossy();
+        let decl = kernels
+            .into_iter()
+            .find(|kernel| kernel.name == name)
+            .ok_or(CL_INVALID_KERNEL_NAME)?;
+        Ok(state.insert_kernel(id(program), decl))
+    });
+    object_result(result, errcode_ret)
+}
+
+pub unsafe fn clCreateKernelsInProgram(
+    program: cl_program,
+    num_kernels: cl_uint,
+    kernels: *mut cl_kernel,
+    num_kernels_ret: *mut cl_uint,
+) -> cl_int {
+    with_state(|state| {
+        let decls = state.executable_kernels(program)?;
+        if !kernels.is_null() {
+            if (num_kernels as usize) < decls.len() {
+                return Err(CL_INVALID_VALUE);
+            }
+            for (i, decl) in decls.iter().enumerate() {
+                *kernels.add(i) = handle(state.insert_kernel(id(program), decl.clone()));
+            }
+        }
+        if !num_kernels_ret.is_null() {
+            *num_kernels_ret = decls.len() as cl_uint;
+        }
+        Ok(())
+    })
+    .err()
+    .unwrap_or(CL_SUCCESS)
+}
+
+pub unsafe fn clCloneKernel(source_kernel: cl_kernel, errcode_ret: *mut cl_int) -> cl_kernel {
+    let result = with_state(|state| {
+        let source = state.kernel(source_kernel)?;
+        let (program, decl, args) = (source.program, source.decl.clone(), source.args.clone());
+        let kernel = state.insert_kernel(program, decl);
+        if let Some(object) = state.kernels.get_mut(&kernel) {
+            object.args = args;
+        }
+        Ok(kernel)
+    });
+    object_result(result, errcode_ret)
+}
+
+pub unsafe fn clRetainKernel(kernel: cl_kernel) -> cl_int {
+    with_state(|state| {
+        state.kernel_mut(kernel)?.ref_count += 1;
+        Ok(())
+    })
+    .err()
+    .unwrap_or(CL_SUCCESS)
+}
+
+pub unsafe fn clReleaseKernel(kernel: cl_kernel) -> cl_int {
+    with_state(|state| {
+        state.kernel(kernel)?;
+        state.release_kernel(id(kernel));
+        Ok(())
+    })
+    .err()
+    .unwrap_or(CL_SUCCESS)
+}
+
+pub unsafe fn clSetKernelArg(
+    kernel: cl_kernel,
+    arg_index: cl_uint,
+    arg_size: size_t,
+    arg_value: *const c_void,
+) -> cl_int {
+    with_state(|state| {
+        let object = state.kernel(kernel)?;
+        let context = object.context;
+        let decl = object
+            .decl
+            .args
+            .get(arg_index as usize)
+            .ok_or(CL_INVALID_ARG_INDEX)?;
+        let arg = match decl.kind {
+            ArgKind::Memory => {
+                if mem::size_of::<cl_mem>() != arg_size {
+                    return Err(CL_INVALID_ARG_SIZE);
+                }
+                let memobj = if arg_value.is_null() {
+                    ptr::null_mut()
+                } else {
+                    *arg_value.cast::<cl_mem>()
+                };
+                if memobj.is_null() {
+                    if is_image_type(&decl.type_name) {
+                        return Err(CL_INVALID_ARG_VALUE);
+                    }
+                    CallArg::Null
+                } else {
+                    let mem = state.mem(memobj)?;
+                    if context != mem.context
+                        || mem.image.is_some() != is_image_type(&decl.type_name)
+                    {
+                        return Err(CL_INVALID_MEM_OBJECT);
+                    }
+                    CallArg::Memory(mem.storage.clone(), mem.size)
+                }
+            }
+            ArgKind::Local => {
+                if !arg_value.is_null() {
+                    return Err(CL_INVALID_ARG_VALUE);
+                }
+                if 0 == arg_size {
+                    return Err(CL_INVALID_ARG_SIZE);
+                }
+                CallArg::Local(arg_size)
+            }
+            ArgKind::Sampler => {
+                if mem::size_of::<cl_sampler>() != arg_size {
+                    return Err(CL_INVALID_ARG_SIZE);
+                }
+                if arg_value.is_null() {
+                    return Err(CL_INVALID_ARG_VALUE);
+                }
+                let sampler = *arg_value.cast::<cl_sampler>();
+                state.sampler(sampler)?;
+                CallArg::Sampler(sampler)
+            }
+            ArgKind::Value(size) if size != arg_size => return Err(CL_INVALID_ARG_SIZE),
+            ArgKind::Value(_) | ArgKind::Unknown if 0 == arg_size => {
+                return Err(CL_INVALID_ARG_SIZE)
+            }
+            ArgKind::Value(_) | ArgKind::Unknown if arg_value.is_null() => {
+                return Err(CL_INVALID_ARG_VALUE)
+            }
+            ArgKind::Value(_) | ArgKind::Unknown => {
+                CallArg::Value(array(arg_value.cast::<u8>(), arg_size).to_vec())
+            }
+        };
+        state.kernel_mut(kernel)?.args[arg_index as usize] = Some(arg);
+        Ok(())
+    })
+    .err()
+    .unwrap_or(CL_SUCCESS)
+}
+
+pub unsafe fn clSetKernelArgSVMPointer(
+    kernel: cl_kernel,
+    arg_index: cl_uint,
+    arg_value: *const c_void,
+) -> cl_int {
+    with_state(|state| {
+        let object = state.kernel_mut(kernel)?;
+        let decl = object
+            .decl
+            .args
+            .get(arg_index as usize)
+            .ok_or(CL_INVALID_ARG_INDEX)?;
+        if ArgKind::Memory != decl.kind || is_image_type(&decl.type_name) {
+            return Err(CL_INVALID_ARG_VALUE);
+        }
+        object.args[arg_index as usize] = Some(CallArg::Svm(arg_value.cast_mut()));
+        Ok(())
+    })
+    .err()
+    .unwrap_or(CL_SUCCESS)
+}
+
+pub unsafe fn clSetKernelExecInfo(
+    kernel: cl_kernel,
+    param_name: cl_kernel_exec_info,
+    param_value_size: size_t,
+    param_value: *const c_void,
+) -> cl_int {
+    with_state(|state| {
+        state.kernel(kernel)?;
+        if param_value.is_null() {
+            return Err(CL_INVALID_VALUE);
+        }
+        match param_name {
+            CL_KERNEL_EXEC_INFO_SVM_PTRS
+                if param_value_size.is_multiple_of(mem::size_of::<*mut c_void>()) =>
+            {
+                Ok(())
+            }
+            CL_KERNEL_EXEC_INFO_SVM_FINE_GRAIN_SYSTEM
+                if mem::size_of::<cl_bool>() == param_value_size =>
+            {
+                // The mock device does not support fine-grained system SVM
+                if CL_FALSE == *param_value.cast::<cl_bool>() {
+                    Ok(())
+                } else {
+                    Err(CL_INVALID_OPERATION)
+                }
+            }
+            _ => Err(CL_INVALID_VALUE),
+        }
+    })
+    .err()
+    .unwrap_or(CL_SUCCESS)
+}
+
+pub unsafe fn clGetKernelInfo(
+    kernel: cl_kernel,
+    param_name: cl_kernel_info,
+    param_value_size: size_t,
+    param_value: *mut c_void,
+    param_value_size_ret: *mut size_t,
+) -> cl_int {
+    let info = with_state(|state| {
+        let kernel = state.kernel(kernel)?;
+        match param_name {
+            CL_KERNEL_FUNCTION_NAME => str_info(&kernel.decl.name),
+            CL_KERNEL_NUM_ARGS => value_info(kernel.decl.args.len() as cl_uint),
+            CL_KERNEL_REFERENCE_COUNT => value_info(kernel.ref_count),
+            CL_KERNEL_CONTEXT => value_info(kernel.context),
+            CL_KERNEL_PROGRAM => value_info(kernel.program),
+            CL_KERNEL_ATTRIBUTES => str_info(&kernel.decl.attributes),
+            _ => Err(CL_INVALID_VALUE),
+        }
+    });
+    write_info(info, param_value_size, param_value, param_value_size_ret)
+}
+
+pub unsafe fn clGetKernelArgInfo(
+    kernel: cl_kernel,
+    arg_indx: cl_uint,
+    param_name: cl_kernel_arg_info,
+    param_value_size: size_t,
+    param_value: *mut c_void,
+    param_value_size_ret: *mut size_t,
+) -> cl_int {
+    let info = with_state(|state| {
+        let kernel = state.kernel(kernel)?;
+        let arg = kernel
+            .decl
+            .args
+            .get(arg_indx as usize)
+            .ok_or(CL_INVALID_ARG_INDEX)?;
+        if !kernel.arg_info {
+            return Err(CL_KERNEL_ARG_INFO_NOT_AVAILABLE);
+        }
+        match param_name {
+            CL_KERNEL_ARG_ADDRESS_QUALIFIER => value_info(arg.address_qualifier),
+            CL_KERNEL_ARG_ACCESS_QUALIFIER => value_info(arg.access_qualifier),
+            CL_KERNEL_ARG_TYPE_NAME => str_info(&arg.type_name),
+            CL_KERNEL_ARG_TYPE_QUALIFIER => value_info(arg.type_qualifier),
+            CL_KERNEL_ARG_NAME => str_info(&arg.name),
+            _ => Err(CL_INVALID_VALUE),
+        }
+    });
+    write_info(info, param_value_size, param_value, param_value_size_ret)
+}
+
+pub unsafe fn clGetKernelWorkGroupInfo(
+    kernel: cl_kernel,
+    device: cl_device_id,
+    param_name: cl_kernel_work_group_info,
+    param_value_size: size_t,
+    param_value: *mut c_void,
+    param_value_size_ret: *mut size_t,
+) -> cl_int {
+    let info = with_state(|state| {
+        let kernel = state.kernel(kernel)?;
+        if !device.is_null() {
+            check_device(device)?;
+        }
+        match param_name {
+            CL_KERNEL_WORK_GROUP_SIZE => value_info(MAX_WORK_GROUP_SIZE),
+            CL_KERNEL_COMPILE_WORK_GROUP_SIZE => slice_info(&kernel.decl.reqd_work_group_size),
+            CL_KERNEL_LOCAL_MEM_SIZE => {
+                let size: usize = kernel
+                    .args
+                    .iter()
+                    .map(|arg| match arg {
+                        Some(CallArg::Local(size)) => *size,
+                        _ => 0,
+                    })
+                    .sum();
+                value_info(size as cl_ulong)
+            }
+            CL_KERNEL_PREFERRED_WORK_GROUP_SIZE_MULTIPLE => {
+                value_info(PREFERRED_WORK_GROUP_SIZE_MULTIPLE)
+            }
+            CL_KERNEL_PRIVATE_MEM_SIZE => value_info::<cl_ulong>(0),
+            // Only valid for custom devices and built-in kernels
+            CL_KERNEL_GLOBAL_WORK_SIZE if kernel.built_in => {
+                slice_info(&[MAX_MEM_ALLOC_SIZE, 1, 1])
+            }
+            _ => Err(CL_INVALID_VALUE),
+        }
+    });
+    write_info(info, param_value_size, param_value, param_value_size_ret)
+}
+
+pub unsafe fn clGetKernelSubGroupInfo(
+    kernel: cl_kernel,
+    device: cl_device_id,
+    _param_name: cl_kernel_sub_group_info,
+    _input_value_size: size_t,
+    _input_value: *const c_void,
+    _param_value_size: size_t,
+    _param_value: *mut c_void,
+    _param_value_size_ret: *mut size_t,
+) -> cl_int {
+    with_state(|state| {
+        state.kernel(kernel)?;
+        if !device.is_null() {
+            check_device(device)?;
+        }
+        // The mock device does not support sub-groups
+        Err::<(), _>(CL_INVALID_OPERATION)
+    })
+    .err()
+    .unwrap_or(CL_SUCCESS)
+}
+
+// Kernel execution
+
+/// Prepare the action of a kernel command.
+unsafe fn prepare_kernel(
+    state: &State,
+    context: usize,
+    kernel: cl_kernel,
+    work_dim: cl_uint,
+    global_work_offset: *const size_t,
+    global_work_size: *const size_t,
+    local_work_size: *const size_t,
+) -> Result<Action, cl_int> {
+    let object = state.kernel(kernel)?;
+    if context != object.context {
+        return Err(CL_INVALID_CONTEXT);
+    }
+    if !(1..=3).contains(&work_dim) {
+        return Err(CL_INVALID_WORK_DIMENSION);
+    }
+    if global_work_size.is_null() {
+        return Err(CL_INVALID_GLOBAL_WORK_SIZE);
+    }
+    let dims = work_dim as usize;
+    let mut global = [1; 3];
+    global[..dims].copy_from_slice(array(global_work_size, dims));
+    let mut offset = [0; 3];
+    offset[..dims].copy_from_slice(
+        array(global_work_offset, dims)
+            .get(..dims)
+            .unwrap_or(&[0; 3][..dims]),
+    );
+    let local = (!local_work_size.is_null()).then(|| {
+        let mut local = [1; 3];
+        local[..dims].copy_from_slice(array(local_work_size, dims));
+        local
+    });
+
+    let args = object
+        .args
+        .iter()
+        .cloned()
+        .collect::<Option<Vec<CallArg>>>()
+        .ok_or(CL_INVALID_KERNEL_ARGS)?;
+    let local_mem_size: usize = args
+        .iter()
+        .map(|arg| match arg {
+            CallArg::Local(size) => *size,
+            _ => 0,
+        })
+        .sum();
+    if LOCAL_MEM_SIZE < local_mem_size {
+        return Err(CL_OUT_OF_RESOURCES);
+    }
+
+    let reqd = object.decl.reqd_work_group_size;
+    if let Some(local) = local {
+        if local.contains(&0) || MAX_WORK_GROUP_SIZE < local.iter().product() {
+            return Err(CL_INVALID_WORK_GROUP_SIZE);
+        }
+        if local
+            .iter()
+            .zip(MAX_WORK_ITEM_SIZES.iter())
+            .any(|(size, max)| max < size)
+        {
+            return Err(CL_INVALID_WORK_ITEM_SIZE);
+        }
+        if ([0; 3] != reqd && reqd != local)
+            || (object.uniform_work_groups
+                && global
+                    .iter()
+                    .zip(local.iter())
+                    .any(|(global, local)| 0 != global % local))
+        {
+            return Err(CL_INVALID_WORK_GROUP_SIZE);
+        }
+    } else if [0; 3] != reqd {
+        return Err(CL_INVALID_WORK_GROUP_SIZE);
+    }
+
+    if global.contains(&0) {
+        return Ok(no_action());
+    }
+    let built_in = object.built_in;
+    let mut call = KernelCall {
+        name: object.decl.name.clone(),
+        work_dim,
+        global_work_offset: offset,
+        global_work_size: global,
+        local_work_size: local,
+        args,
+    };
+    Ok(Box::new(move |_| {
+        let function: Option<KernelFn> = if built_in {
+            Some(Arc::new(copy_buffer_kernel))
+        } else {
+            kernel_registry().get(&call.name).cloned()
+        };
+        function.map_or(CL_SUCCESS, |function| {
+            match panic::catch_unwind(AssertUnwindSafe(|| function(&mut call))) {
+                Ok(Ok(())) => CL_SUCCESS,
+                Ok(Err(error)) => error,
+                Err(_) => CL_OUT_OF_RESOURCES,
+            }
+        })
+    }))
+}
+
+pub unsafe fn clEnqueueNDRangeKernel(
+    command_queue: cl_command_queue,
+    kernel: cl_kernel,
+    work_dim: cl_uint,
+    global_work_offset: *const size_t,
+    global_work_size: *const size_t,
+    local_work_size: *const size_t,
+    num_events_in_wait_list: cl_uint,
+    event_wait_list: *const cl_event,
+    event: *mut cl_event,
+) -> cl_int {
+    enqueue_command(
+        command_queue,
+        CL_COMMAND_NDRANGE_KERNEL,
+        false,
+        num_events_in_wait_list,
+        event_wait_list,
+        event,
+        |state, context| {
+            prepare_kernel(
+                state,
+                context,
+                kernel,
+                work_dim,
+                global_work_offset,
+                global_work_size,
+                local_work_size,
+            )
+        },
+    )
+}
+
+pub unsafe fn clEnqueueTask(
+    command_queue: cl_command_queue,
+    kernel: cl_kernel,
+    num_events_in_wait_list: cl_uint,
+    event_wait_list: *const cl_event,
+    event: *mut cl_event,
+) -> cl_int {
+    let size: size_t = 1;
+    enqueue_command(
+        command_queue,
+        CL_COMMAND_TASK,
+        false,
+        num_events_in_wait_list,
+        event_wait_list,
+        event,
+        |state, context| prepare_kernel(state, context, kernel, 1, ptr::null(), &size, &size),
+    )
+}
+
+pub unsafe fn clEnqueueNativeKernel(
+    command_queue: cl_command_queue,
+    user_func: Option<NativeKernel>,
+    args: *mut c_void,
+    cb_args: size_t,
+    num_mem_objects: cl_uint,
+    mem_list: *const cl_mem,
+    args_mem_loc: *const *const c_void,
+    num_events_in_wait_list: cl_uint,
+    event_wait_list: *const cl_event,
+    event: *mut cl_event,
+) -> cl_int {
+    enqueue_command(
+        command_queue,
+        CL_COMMAND_NATIVE_KERNEL,
+        false,
+        num_events_in_wait_list,
+        event_wait_list,
+        event,
+        |state, context| {
+            let Some(user_func) = user_func else {
+                return Err(CL_INVALID_VALUE);
+            };
+            if args.is_null() != (0 == cb_args)
+                || (0 < num_mem_objects && (mem_list.is_null() || args_mem_loc.is_null()))
+            {
+                return Err(CL_INVALID_VALUE);
+            }
+            let mut copy = array(args.cast::<u8>(), cb_args).to_vec();
+            let mut storages = Vec::with_capacity(num_mem_objects as usize);
+            let objects = array(mem_list, num_mem_objects as usize);
+            let locations = array(args_mem_loc, num_mem_objects as usize);
+            for (memobj, location) in objects.iter().zip(locations) {
+                let storage = state.buffer(*memobj, context)?.storage.clone();
+                let offset = (*location as usize).wrapping_sub(args as usize);
+                if cb_args < offset + mem::size_of::<*mut c_void>() {
+                    return Err(CL_INVALID_VALUE);
+                }
+                ptr::write_unaligned(copy.as_mut_ptr().add(offset).cast::<*mut u8>(), storage.ptr);
+                storages.push(storage);
+            }
+            Ok(Box::new(move |_| {
+                let _storages = storages;
+                user_func(copy.as_mut_ptr().cast::<c_void>());
+                CL_SUCCESS
+            }))
+        },
+    )
+}
+
+pub unsafe fn clEnqueueMarkerWithWaitList(
+    command_queue: cl_command_queue,
+    num_events_in_wait_list: cl_uint,
+    event_wait_list: *const cl_event,
+    event: *mut cl_event,
+) -> cl_int {
+    enqueue_command(
+        command_queue,
+        CL_COMMAND_MARKER,
+        false,
+        num_events_in_wait_list,
+        event_wait_list,
+        event,
+        |_, _| Ok(no_action()),
+    )
+}
+
+pub unsafe fn clEnqueueBarrierWithWaitList(
+    command_queue: cl_command_queue,
+    num_events_in_wait_list: cl_uint,
+    event_wait_list: *const cl_event,
+    event: *mut cl_event,
+) -> cl_int {
+    enqueue_command(
+        command_queue,
+        CL_COMMAND_BARRIER,
+        false,
+        num_events_in_wait_list,
+        event_wait_list,
+        event,
+        |_, _| Ok(no_action()),
+    )
+}
+
+pub unsafe fn clEnqueueMarker(command_queue: cl_command_queue, event: *mut cl_event) -> cl_int {
+    if event.is_null() {
+        return CL_INVALID_VALUE;
+    }
+    clEnqueueMarkerWithWaitList(command_queue, 0, ptr::null(), event)
+}
+
+pub unsafe fn clEnqueueWaitForEvents(
+    command_queue: cl_command_queue,
+    num_events: cl_uint,
+    event_list: *const cl_event,
+) -> cl_int {
+    if 0 == num_events || event_list.is_null() {
+        return CL_INVALID_VALUE;
+    }
+    clEnqueueBarrierWithWaitList(command_queue, num_events, event_list, ptr::null_mut())
+}
+
+pub unsafe fn clEnqueueBarrier(command_queue: cl_command_queue) -> cl_int {
+    clEnqueueBarrierWithWaitList(command_queue, 0, ptr::null(), ptr::null_mut())
+}
+
+// SVM enqueued commands
+
+pub unsafe fn clEnqueueSVMFree(
+    command_queue: cl_command_queue,
+    num_svm_pointers: cl_uint,
+    svm_pointers: *const *const c_void,
+    pfn_free_func: Option<SvmFreeNotify>,
+    user_data: *mut c_void,
+    num_events_in_wait_list: cl_uint,
+    event_wait_list: *const cl_event,
+    event: *mut cl_event,
+) -> cl_int {
+    let queue = id(command_queue);
+    enqueue_command(
+        command_queue,
+        CL_COMMAND_SVM_FREE,
+        false,
+        num_events_in_wait_list,
+        event_wait_list,
+        event,
+        |_, _| {
+            let pointers: Vec<*mut c_void> = array(svm_pointers, num_svm_pointers as usize)
+                .iter()
+                .map(|pointer| pointer.cast_mut())
+                .collect();
+            if pointers.is_empty() || pointers.iter().any(|pointer| pointer.is_null()) {
+                return Err(CL_INVALID_VALUE);
+            }
+            Ok(Box::new(move |state: &mut State| {
+                if let Some(notify) = pfn_free_func {
+                    state
+                        .notifications
+                        .push(Notification::SvmFree(notify, queue, pointers, user_data));
+                } else {
+                    for pointer in pointers {
+                        state.svm.remove(&(pointer as usize));
+                    }
+                }
+                CL_SUCCESS
+            }))
+        },
+    )
+}
+
+pub unsafe fn clEnqueueSVMMemcpy(
+    command_queue: cl_command_queue,
+    blocking_copy: cl_bool,
+    dst_ptr: *mut c_void,
+    src_ptr: *const c_void,
+    size: size_t,
+    num_events_in_wait_list: cl_uint,
+    event_wait_list: *const cl_event,
+    event: *mut cl_event,
+) -> cl_int {
+    enqueue_command(
+        command_queue,
+        CL_COMMAND_SVM_MEMCPY,
+        CL_FALSE != blocking_copy,
+        num_events_in_wait_list,
+        event_wait_list,
+        event,
+        |_, _| {
+            if dst_ptr.is_null() || src_ptr.is_null() {
+                return Err(CL_INVALID_VALUE);
+            }
+            if overlaps(src_ptr as usize, size, dst_ptr as usize, size) {
+                return Err(CL_MEM_COPY_OVERLAP);
+            }
+            Ok(Box::new(move |_| {
+                ptr::copy(src_ptr.cast::<u8>(), dst_ptr.cast::<u8>(), size);
+                CL_SUCCESS
+            }))
+        },
+    )
+}
+
+pub unsafe fn clEnqueueSVMMemFill(
+    command_queue: cl_command_queue,
+    svm_ptr: *mut c_void,
+    pattern: *const c_void,
+    pattern_size: size_t,
+    size: size_t,
+    num_events_in_wait_list: cl_uint,
+    event_wait_list: *const cl_event,
+    event: *mut cl_event,
+) -> cl_int {
+    enqueue_command(
+        command_queue,
+        CL_COMMAND_SVM_MEMFILL,
+        false,
+        num_events_in_wait_list,
+        event_wait_list,
+        event,
+        |_, _| {
+            let pattern = fill_pattern(pattern, pattern_size, svm_ptr as usize, size)?;
+            if svm_ptr.is_null() {
+                return Err(CL_INVALID_VALUE);
+            }
+            Ok(Box::new(move |_| {
+                fill(svm_ptr.cast::<u8>(), &pattern, size);
+                CL_SUCCESS
+            }))
+        },
+    )
+}
+
+pub unsafe fn clEnqueueSVMMap(
+    command_queue: cl_command_queue,
+    blocking_map: cl_bool,
+    flags: cl_map_flags,
+    svm_ptr: *mut c_void,
+    size: size_t,
+    num_events_in_wait_list: cl_uint,
+    event_wait_list: *const cl_event,
+    event: *mut cl_event,
+) -> cl_int {
+    enqueue_command(
+        command_queue,
+        CL_COMMAND_SVM_MAP,
+        CL_FALSE != blocking_map,
+        num_events_in_wait_list,
+        event_wait_list,
+        event,
+        |_, _| {
+            const VALID_FLAGS: cl_map_flags =
+                CL_MAP_READ | CL_MAP_WRITE | CL_MAP_WRITE_INVALIDATE_REGION;
+            if svm_ptr.is_null() || 0 == size || 0 != flags & !VALID_FLAGS {
+                return Err(CL_INVALID_VALUE);
+            }
+            Ok(no_action())
+        },
+    )
+}
+
+pub unsafe fn clEnqueueSVMUnmap(
+    command_queue: cl_command_queue,
+    svm_ptr: *mut c_void,
+    num_events_in_wait_list: cl_uint,
+    event_wait_list: *const cl_event,
+    event: *mut cl_event,
+) -> cl_int {
+    enqueue_command(
+        command_queue,
+        CL_COMMAND_SVM_UNMAP,
+        false,
+        num_events_in_wait_list,
+        event_wait_list,
+        event,
+        |_, _| {
+            if svm_ptr.is_null() {
+                return Err(CL_INVALID_VALUE);
+            }
+            Ok(no_action())
+        },
+    )
+}
+
+pub unsafe fn clEnqueueSVMMigrateMem(
+    command_queue: cl_command_queue,
+    num_svm_pointers: cl_uint,
+    svm_pointers: *const *const c_void,
+    _sizes: *const size_t,
+    flags: cl_mem_migration_flags,
+    num_events_in_wait_list: cl_uint,
+    event_wait_list: *const cl_event,
+    event: *mut cl_event,
+) -> cl_int {
+    enqueue_command(
+        command_queue,
+        CL_COMMAND_SVM_MIGRATE_MEM,
+        false,
+        num_events_in_wait_list,
+        event_wait_list,
+        event,
+        |_, _| {
+            if 0 == num_svm_pointers
+                || svm_pointers.is_null()
+                || 0 != flags
+                    & !(CL_MIGRATE_MEM_OBJECT_HOST | CL_MIGRATE_MEM_OBJECT_CONTENT_UNDEFINED)
+            {
+                return Err(CL_INVALID_VALUE);
+            }
+            Ok(no_action())
+        },
+    )
+}
+
+// OpenGL interoperability APIs: the mock platform does not support OpenGL
+// sharing, so there are no OpenGL contexts or objects.
+
+pub unsafe fn clCreateFromGLBuffer(
+    context: cl_context,
+    _flags: cl_mem_flags,
+    _bufobj: cl_GLuint,
+    errcode_ret: *mut cl_int,
+) -> cl_mem {
+    let result = with_state(|state| state.context(context).and(Err(CL_INVALID_CONTEXT)));
+    object_result(result, errcode_ret)
+}
+
+pub unsafe fn clCreateFromGLTexture(
+    context: cl_context,
+    flags: cl_mem_flags,
+    _target: cl_GLenum,
+    _miplevel: cl_GLint,
+    texture: cl_GLuint,
+    errcode_ret: *mut cl_int,
+) -> cl_mem {
+    clCreateFromGLBuffer(context, flags, texture, errcode_ret)
+}
+
+pub unsafe fn clCreateFromGLRenderbuffer(
+    context: cl_context,
+    flags: cl_mem_flags,
+    renderbuffer: cl_GLuint,
+    errcode_ret: *mut cl_int,
+) -> cl_mem {
+    clCreateFromGLBuffer(context, flags, renderbuffer, errcode_ret)
+}
+
+pub unsafe fn clCreateFromGLTexture2D(
+    context: cl_context,
+    flags: cl_mem_flags,
+    _texture_target: cl_GLenum,
+    _miplevel: cl_GLint,
+    texture: cl_GLuint,
+    errcode_ret: *mut cl_int,
+) -> cl_mem {
+    clCreateFromGLBuffer(context, flags, texture, errcode_ret)
+}
+
+pub unsafe fn clCreateFromGLTexture3D(
+    context: cl_context,
+    flags: cl_mem_flags,
+    _texture_target: cl_GLenum,
+    _miplevel: cl_GLint,
+    texture: cl_GLuint,
+    errcode_ret: *mut cl_int,
+) -> cl_mem {
+    clCreateFromGLBuffer(context, flags, texture, errcode_ret)
+}
+
+pub unsafe fn clGetGLObjectInfo(
+    memobj: cl_mem,
+    _gl_object_type: *mut cl_gl_object_type,
+    _gl_object_name: *mut cl_GLuint,
+) -> cl_int {
+    with_state(|state| state.mem(memobj).map(|_| ()))
+        .err()
+        .unwrap_or(CL_INVALID_GL_OBJECT)
+}
+
+pub unsafe fn clGetGLTextureInfo(
+    memobj: cl_mem,
+    _param_name: cl_gl_texture_info,
+    _param_value_size: size_t,
+    _param_value: *mut c_void,
+    _param_value_size_ret: *mut size_t,
+) -> cl_int {
+    clGetGLObjectInfo(memobj, ptr::null_mut(), ptr::null_mut())
+}
+
+pub unsafe fn clEnqueueAcquireGLObjects(
+    command_queue: cl_command_queue,
+    num_objects: cl_uint,
+    mem_objects: *const cl_mem,
+    num_events_in_wait_list: cl_uint,
+    event_wait_list: *const cl_event,
+    event: *mut cl_event,
+) -> cl_int {
+    enqueue_command(
+        command_queue,
+        CL_COMMAND_ACQUIRE_GL_OBJECTS,
+        false,
+        num_events_in_wait_list,
+        event_wait_list,
+        event,
+        |state, _| {
+            let objects = array(mem_objects, num_objects as usize);
+            if objects.is_empty() {
+                return Err(CL_INVALID_VALUE);
+            }
+            for object in objects {
+                state.mem(*object)?;
+            }
+            Err(CL_INVALID_GL_OBJECT)
+        },
+    )
+}
+
+pub unsafe fn clEnqueueReleaseGLObjects(
+    command_queue: cl_command_queue,
+    num_objects: cl_uint,
+    mem_objects: *const cl_mem,
+    num_events_in_wait_list: cl_uint,
+    event_wait_list: *const cl_event,
+    event: *mut cl_event,
+) -> cl_int {
+    clEnqueueAcquireGLObjects(
+        command_queue,
+        num_objects,
+        mem_objects,
+        num_events_in_wait_list,
+        event_wait_list,
+        event,
+    )
+}
+
+pub unsafe fn clGetGLContextInfoKHR(
+    _properties: *const cl_context_properties,
+    _param_name: cl_gl_context_info,
+    _param_value_size: size_t,
+    _param_value: *mut c_void,
+    _param_value_size_ret: *mut size_t,
+) -> cl_int {
+    CL_INVALID_GL_SHAREGROUP_REFERENCE_KHR
+}
+
+pub unsafe fn clCreateEventFromGLsyncKHR(
+    context: cl_context,
+    _sync: cl_GLsync,
+    errcode_ret: *mut cl_int,
+) -> cl_event {
+    clCreateFromGLBuffer(context, 0, 0, errcode_ret)
+}
+
+// Extension APIs: the extension functions with core equivalents call the core
+// functions, the other extensions are not supported.
+
+#[cfg(feature = "cl_apple_setmemobjectdestructor")]
+pub unsafe fn clSetMemObjectDestructorAPPLE(
+    memobj: cl_mem,
+    pfn_notify: Option<unsafe extern "C" fn(memobj: cl_mem, user_data: *mut c_void)>,
+    user_data: *mut c_void,
+) -> cl_int {
+    clSetMemObjectDestructorCallback(memobj, pfn_notify, user_data)
+}
+
+#[cfg(feature = "cl_khr_icd")]
+pub unsafe fn clIcdGetPlatformIDsKHR(
+    num_entries: cl_uint,
+    platforms: *mut cl_platform_id,
+    num_platforms: *mut cl_uint,
+) -> cl_int {
+    clGetPlatformIDs(num_entries, platforms, num_platforms)
+}
+
+#[cfg(feature = "cl_khr_il_program")]
+pub unsafe fn clCreateProgramWithILKHR(
+    context: cl_context,
+    il: *const c_void,
+    length: size_t,
+    errcode_ret: *mut cl_int,
+) -> cl_program {
+    clCreateProgramWithIL(context, il, length, errcode_ret)
+}
+
+#[cfg(feature = "cl_khr_create_command_queue")]
+pub unsafe fn clCreateCommandQueueWithPropertiesKHR(
+    context: cl_context,
+    device: cl_device_id,
+    properties: *const cl_queue_properties_khr,
+    errcode_ret: *mut cl_int,
+) -> cl_command_queue {
+    clCreateCommandQueueWithProperties(context, device, properties, errcode_ret)
+}
+
+#[cfg(feature = "cl_ext_device_fission")]
+pub unsafe fn clReleaseDeviceEXT(device: cl_device_id) -> cl_int {
+    clReleaseDevice(device)
+}
+
+#[cfg(feature = "cl_ext_device_fission")]
+pub unsafe fn clRetainDeviceEXT(device: cl_device_id) -> cl_int {
+    clRetainDevice(device)
+}
+
+#[cfg(feature = "cl_ext_migrate_memobject")]
+pub unsafe fn clEnqueueMigrateMemObjectEXT(
+    command_queue: cl_command_queue,
+    num_mem_objects: cl_uint,
+    mem_objects: *const cl_mem,
+    flags: cl_mem_migration_flags_ext,
+    num_events_in_wait_list: cl_uint,
+    event_wait_list: *const cl_event,
+    event: *mut cl_event,
+) -> cl_int {
+    clEnqueueMigrateMemObjects(
+        command_queue,
+        num_mem_objects,
+        mem_objects,
+        flags,
+        num_events_in_wait_list,
+        event_wait_list,
+        event,
+    )
+}
+
+#[cfg(feature = "cl_khr_subgroups")]
+pub unsafe fn clGetKernelSubGroupInfoKHR(
+    in_kernel: cl_kernel,
+    in_device: cl_device_id,
+    param_name: cl_kernel_sub_group_info,
+    input_value_size: size_t,
+    input_value: *const c_void,
+    param_value_size: size_t,
+    param_value: *mut c_void,
+    param_value_size_ret: *mut size_t,
+) -> cl_int {
+    clGetKernelSubGroupInfo(
+        in_kernel,
+        in_device,
+        param_name,
+        input_value_size,
+        input_value,
+        param_value_size,
+        param_value,
+        param_value_size_ret,
+    )
+}
+
+#[cfg(feature = "cl_arm_shared_virtual_memory")]
+pub unsafe fn clSVMAllocARM(
+    context: cl_context,
+    flags: cl_svm_mem_flags_arm,
+    size: size_t,
+    alignment: cl_uint,
+) -> *mut c_void {
+    clSVMAlloc(context, flags, size, alignment)
+}
+
+#[cfg(feature = "cl_arm_shared_virtual_memory")]
+pub unsafe fn clSVMFreeARM(context: cl_context, svm_pointer: *mut c_void) {
+    clSVMFree(context, svm_pointer);
+}
+
+#[cfg(feature = "cl_arm_shared_virtual_memory")]
+pub unsafe fn clEnqueueSVMFreeARM(
+    command_queue: cl_command_queue,
+    num_svm_pointers: cl_uint,
+    svm_pointers: *mut *mut c_void,
+    pfn_free_func: Option<
+        unsafe extern "C" fn(
+            queue: cl_command_queue,
+            num_svm_pointers: cl_uint,
+            svm_pointers: *mut *mut c_void,
+            user_data: *mut c_void,
+        ),
+    >,
+    user_data: *mut c_void,
+    num_events_in_wait_list: cl_uint,
+    event_wait_list: *const cl_event,
+    event: *mut cl_event,
+) -> cl_int {
+    clEnqueueSVMFree(
+        command_queue,
+        num_svm_pointers,
+        svm_pointers.cast_const().cast(),
+        pfn_free_func,
+        user_data,
+        num_events_in_wait_list,
+        event_wait_list,
+        event,
+    )
+}
+
+#[cfg(feature = "cl_arm_shared_virtual_memory")]
+pub unsafe fn clEnqueueSVMMemcpyARM(
+    command_queue: cl_command_queue,
+    blocking_copy: cl_bool,
+    dst_ptr: *mut c_void,
+    src_ptr: *const c_void,
+    size: size_t,
+    num_events_in_wait_list: cl_uint,
+    event_wait_list: *const cl_event,
+    event: *mut cl_event,
+) -> cl_int {
+    clEnqueueSVMMemcpy(
+        command_queue,
+        blocking_copy,
+        dst_ptr,
+        src_ptr,
+        size,
+        num_events_in_wait_list,
+        event_wait_list,
+        event,
+    )
+}
+
+#[cfg(feature = "cl_arm_shared_virtual_memory")]
+pub unsafe fn clEnqueueSVMMemFillARM(
+    command_queue: cl_command_queue,
+    svm_ptr: *mut c_void,
+    pattern: *const c_void,
+    pattern_size: size_t,
+    size: size_t,
+    num_events_in_wait_list: cl_uint,
+    event_wait_list: *const cl_event,
+    event: *mut cl_event,
+) -> cl_int {
+    clEnqueueSVMMemFill(
+        command_queue,
+        svm_ptr,
+        pattern,
+        pattern_size,
+        size,
+        num_events_in_wait_list,
+        event_wait_list,
+        event,
+    )
+}
+
+#[cfg(feature = "cl_arm_shared_virtual_memory")]
+pub unsafe fn clEnqueueSVMMapARM(
+    command_queue: cl_command_queue,
+    blocking_map: cl_bool,
+    flags: cl_map_flags,
+    svm_ptr: *mut c_void,
+    size: size_t,
+    num_events_in_wait_list: cl_uint,
+    event_wait_list: *const cl_event,
+    event: *mut cl_event,
+) -> cl_int {
+    clEnqueueSVMMap(
+        command_queue,
+        blocking_map,
+        flags,
+        svm_ptr,
+        size,
+        num_events_in_wait_list,
+        event_wait_list,
+        event,
+    )
+}
+
+#[cfg(feature = "cl_arm_shared_virtual_memory")]
+pub unsafe fn clEnqueueSVMUnmapARM(
+    command_queue: cl_command_queue,
+    svm_ptr: *mut c_void,
+    num_events_in_wait_list: cl_uint,
+    event_wait_list: *const cl_event,
+    event: *mut cl_event,
+) -> cl_int {
+    clEnqueueSVMUnmap(
+        command_queue,
+        svm_ptr,
+        num_events_in_wait_list,
+        event_wait_list,
+        event,
+    )
+}
+
+#[cfg(feature = "cl_arm_shared_virtual_memory")]
+pub unsafe fn clSetKernelArgSVMPointerARM(
+    kernel: cl_kernel,
+    arg_index: cl_uint,
+    arg_value: *const c_void,
+) -> cl_int {
+    clSetKernelArgSVMPointer(kernel, arg_index, arg_value)
+}
+
+#[cfg(feature = "cl_arm_shared_virtual_memory")]
+pub unsafe fn clSetKernelExecInfoARM(
+    kernel: cl_kernel,
+    param_name: cl_kernel_exec_info_arm,
+    param_value_size: size_t,
+    param_value: *const c_void,
+) -> cl_int {
+    clSetKernelExecInfo(kernel, param_name, param_value_size, param_value)
+}
+
+#[cfg(feature = "cl_intel_create_buffer_with_properties")]
+pub unsafe fn clCreateBufferWithPropertiesINTEL(
+    context: cl_context,
+    properties: *const cl_mem_properties_intel,
+    flags: cl_mem_flags,
+    size: size_t,
+    host_ptr: *mut c_void,
+    errcode_ret: *mut cl_int,
+) -> cl_mem {
+    clCreateBufferWithProperties(context, properties, flags, size, host_ptr, errcode_ret)
+}
+
+/// A macro to define the extension functions that the mock does not support.
+/// They return `CL_INVALID_OPERATION`, or a null object and set the
+/// `errcode_ret` parameter named in brackets after the return type.
+macro_rules! unsupported_functions {
+    ($(
+        $(#[$meta:meta])*
+        fn $name:ident($($arg:ident: $ty:ty),* $(,)?) -> $ret:ty $([$errcode_ret:ident])?;
+    )*) => {
+        $(
+            $(#[$meta])*
+            #[allow(unused_variables)]
+            pub unsafe fn $name($($arg: $ty),*) -> $ret {
+                unsupported_functions!(@error $([$errcode_ret])?)
+            }
+        )*
+    };
+
+    (@error) => {
+        CL_INVALID_OPERATION
+    };
+    (@error [$errcode_ret:ident]) => {{
+        if !$errcode_ret.is_null() {
+            *$errcode_ret = CL_INVALID_OPERATION;
+        }
+        ptr::null_mut()
+    }};
+}
+
+unsupported_functions! {
+    // Extension APIs
+    #[cfg(feature = "cl_khr_command_buffer")]
+    fn clCreateCommandBufferKHR(
+        num_queues: cl_uint,
+        queues: *const cl_command_queue,
+        properties: *const cl_command_buffer_properties_khr,
+        errcode_ret: *mut cl_int,
+    ) -> cl_command_buffer_khr [errcode_ret];
+    #[cfg(feature = "cl_khr_command_buffer")]
+    fn clFinalizeCommandBufferKHR(command_buffer: cl_command_buffer_khr) -> cl_int;
+    #[cfg(feature = "cl_khr_command_buffer")]
+    fn clRetainCommandBufferKHR(command_buffer: cl_command_buffer_khr) -> cl_int;
+    #[cfg(feature = "cl_khr_command_buffer")]
+    fn clReleaseCommandBufferKHR(command_buffer: cl_command_buffer_khr) -> cl_int;
+    #[cfg(feature = "cl_khr_command_buffer")]
+    fn clEnqueueCommandBufferKHR(
+        num_queues: cl_uint,
+        queues: *mut cl_command_queue,
+        command_buffer: cl_command_buffer_khr,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    #[cfg(feature = "cl_khr_command_buffer")]
+    fn clCommandBarrierWithWaitListKHR(
+        command_buffer: cl_command_buffer_khr,
+        command_queue: cl_command_queue,
+        num_sync_points_in_wait_list: cl_uint,
+        sync_point_wait_list: *const cl_sync_point_khr,
+        sync_point: *mut cl_sync_point_khr,
+        mutable_handle: *mut cl_mutable_command_khr,
+    ) -> cl_int;
+    #[cfg(feature = "cl_khr_command_buffer")]
+    fn clCommandCopyBufferKHR(
+        command_buffer: cl_command_buffer_khr,
+        command_queue: cl_command_queue,
+        src_buffer: cl_mem,
+        dst_buffer: cl_mem,
+        src_offset: size_t,
+        dst_offset: size_t,
+        size: size_t,
+        num_sync_points_in_wait_list: cl_uint,
+        sync_point_wait_list: *const cl_sync_point_khr,
+        sync_point: *mut cl_sync_point_khr,
+        mutable_handle: *mut cl_mutable_command_khr,
+    ) -> cl_int;
+    #[cfg(feature = "cl_khr_command_buffer")]
+    fn clCommandCopyBufferRectKHR(
+        command_buffer: cl_command_buffer_khr,
+        command_queue: cl_command_queue,
+        src_buffer: cl_mem,
+        dst_buffer: cl_mem,
+        src_origin: *const size_t,
+        dst_origin: *const size_t,
+        region: *const size_t,
+        src_row_pitch: size_t,
+        src_slice_pitch: size_t,
+        dst_row_pitch: size_t,
+        dst_slice_pitch: size_t,
+        num_sync_points_in_wait_list: cl_uint,
+        sync_point_wait_list: *const cl_sync_point_khr,
+        sync_point: *mut cl_sync_point_khr,
+        mutable_handle: *mut cl_mutable_command_khr,
+    ) -> cl_int;
+    #[cfg(feature = "cl_khr_command_buffer")]
+    fn clCommandCopyBufferToImageKHR(
+        command_buffer: cl_command_buffer_khr,
+        command_queue: cl_command_queue,
+        src_buffer: cl_mem,
+        dst_image: cl_mem,
+        src_offset: size_t,
+        dst_origin: *const size_t,
+        region: *const size_t,
+        num_sync_points_in_wait_list: cl_uint,
+        sync_point_wait_list: *const cl_sync_point_khr,
+        sync_point: *mut cl_sync_point_khr,
+        mutable_handle: *mut cl_mutable_command_khr,
+    ) -> cl_int;
+    #[cfg(feature = "cl_khr_command_buffer")]
+    fn clCommandCopyImageKHR(
+        command_buffer: cl_command_buffer_khr,
+        command_queue: cl_command_queue,
+        src_image: cl_mem,
+        dst_image: cl_mem,
+        src_origin: *const size_t,
+        dst_origin: *const size_t,
+        region: *const size_t,
+        num_sync_points_in_wait_list: cl_uint,
+        sync_point_wait_list: *const cl_sync_point_khr,
+        sync_point: *mut cl_sync_point_khr,
+        mutable_handle: *mut cl_mutable_command_khr,
+    ) -> cl_int;
+    #[cfg(feature = "cl_khr_command_buffer")]
+    fn clCommandCopyImageToBufferKHR(
+        command_buffer: cl_command_buffer_khr,
+        command_queue: cl_command_queue,
+        src_image: cl_mem,
+        dst_buffer: cl_mem,
+        src_origin: *const size_t,
+        region: *const size_t,
+        dst_offset: size_t,
+        num_sync_points_in_wait_list: cl_uint,
+        sync_point_wait_list: *const cl_sync_point_khr,
+        sync_point: *mut cl_sync_point_khr,
+        mutable_handle: *mut cl_mutable_command_khr,
+    ) -> cl_int;
+    #[cfg(feature = "cl_khr_command_buffer")]
+    fn clCommandFillBufferKHR(
+        command_buffer: cl_command_buffer_khr,
+        command_queue: cl_command_queue,
+        buffer: cl_mem,
+        pattern: *const c_void,
+        pattern_size: size_t,
+        offset: size_t,
+        size: size_t,
+        num_sync_points_in_wait_list: cl_uint,
+        sync_point_wait_list: *const cl_sync_point_khr,
+        sync_point: *mut cl_sync_point_khr,
+        mutable_handle: *mut cl_mutable_command_khr,
+    ) -> cl_int;
+    #[cfg(feature = "cl_khr_command_buffer")]
+    fn clCommandFillImageKHR(
+        command_buffer: cl_command_buffer_khr,
+        command_queue: cl_command_queue,
+        image: cl_mem,
+        fill_color: *const c_void,
+        origin: *const size_t,
+        region: *const size_t,
+        num_sync_points_in_wait_list: cl_uint,
+        sync_point_wait_list: *const cl_sync_point_khr,
+        sync_point: *mut cl_sync_point_khr,
+        mutable_handle: *mut cl_mutable_command_khr,
+    ) -> cl_int;
+    #[cfg(feature = "cl_khr_command_buffer")]
+    fn clCommandNDRangeKernelKHR(
+        command_buffer: cl_command_buffer_khr,
+        command_queue: cl_command_queue,
+        properties: *const cl_ndrange_kernel_command_properties_khr,
+        kernel: cl_kernel,
+        work_dim: cl_uint,
+        global_work_offset: *const size_t,
+        global_work_size: *const size_t,
+        local_work_size: *const size_t,
+        num_sync_points_in_wait_list: cl_uint,
+        sync_point_wait_list: *const cl_sync_point_khr,
+        sync_point: *mut cl_sync_point_khr,
+        mutable_handle: *mut cl_mutable_command_khr,
+    ) -> cl_int;
+    #[cfg(feature = "cl_khr_command_buffer")]
+    fn clCommandSVMMemcpyKHR(
+        command_buffer: cl_command_buffer_khr,
+        command_queue: cl_command_queue,
+        dst_ptr: *mut c_void,
+        src_ptr: *const c_void,
+        size: size_t,
+        num_sync_points_in_wait_list: cl_uint,
+        sync_point_wait_list: *const cl_sync_point_khr,
+        sync_point: *mut cl_sync_point_khr,
+        mutable_handle: *mut cl_mutable_command_khr,
+    ) -> cl_int;
+    #[cfg(feature = "cl_khr_command_buffer")]
+    fn clCommandSVMMemFillKHR(
+        command_buffer: cl_command_buffer_khr,
+        command_queue: cl_command_queue,
+        svm_ptr: *mut c_void,
+        pattern: *const c_void,
+        pattern_size: size_t,
+        size: size_t,
+        num_sync_points_in_wait_list: cl_uint,
+        sync_point_wait_list: *const cl_sync_point_khr,
+        sync_point: *mut cl_sync_point_khr,
+        mutable_handle: *mut cl_mutable_command_khr,
+    ) -> cl_int;
+    #[cfg(feature = "cl_khr_command_buffer")]
+    fn clGetCommandBufferInfoKHR(
+        command_buffer: cl_command_buffer_khr,
+        param_name: cl_command_buffer_info_khr,
+        param_value_size: size_t,
+        param_value: *mut c_void,
+        param_value_size_ret: *mut size_t,
+    ) -> cl_int;
+    #[cfg(feature = "cl_khr_command_buffer_multi_device")]
+    fn clRemapCommandBufferKHR(
+        command_buffer: cl_command_buffer_khr,
+        automatic: cl_bool,
+        num_queues: cl_uint,
+        queues: *const cl_command_queue,
+        num_handles: cl_uint,
+        handles: *const cl_mutable_command_khr,
+        handles_ret: *mut cl_mutable_command_khr,
+        errcode_ret: *mut cl_int,
+    ) -> cl_command_buffer_khr [errcode_ret];
+    #[cfg(feature = "cl_khr_command_buffer_mutable_dispatch")]
+    fn clUpdateMutableCommandsKHR(
+        command_buffer: cl_command_buffer_khr,
+        mutable_config: *const cl_mutable_base_config_khr,
+    ) -> cl_int;
+    #[cfg(feature = "cl_khr_command_buffer_mutable_dispatch")]
+    fn clGetMutableCommandInfoKHR(
+        command: cl_mutable_command_khr,
+        param_name: cl_mutable_command_info_khr,
+        param_value_size: size_t,
+        param_value: *mut c_void,
+        param_value_size_ret: *mut size_t,
+    ) -> cl_int;
+    #[cfg(feature = "cl_khr_terminate_context")]
+    fn clTerminateContextKHR(context: cl_context) -> cl_int;
+    #[cfg(feature = "cl_ext_device_fission")]
+    fn clCreateSubDevicesEXT(
+        in_device: cl_device_id,
+        properties: *const cl_device_partition_property_ext,
+        num_entries: cl_uint,
+        out_devices: *mut cl_device_id,
+        num_devices: *mut cl_uint,
+    ) -> cl_int;
+    #[cfg(feature = "cl_qcom_ext_host_ptr")]
+    fn clGetDeviceImageInfoQCOM(
+        device: cl_device_id,
+        image_width: size_t,
+        image_height: size_t,
+        image_format: *const cl_image_format,
+        param_name: cl_image_pitch_info_qcom,
+        param_value_size: size_t,
+        param_value: *mut c_void,
+        param_value_size_ret: *mut size_t,
+    ) -> cl_int;
+    #[cfg(feature = "cl_img_use_gralloc_ptr")]
+    fn clEnqueueAcquireGrallocObjectsIMG(
+        command_queue: cl_command_queue,
+        num_objects: cl_uint,
+        mem_objects: *const cl_mem,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    #[cfg(feature = "cl_img_use_gralloc_ptr")]
+    fn clEnqueueReleaseGrallocObjectsIMG(
+        command_queue: cl_command_queue,
+        num_objects: cl_uint,
+        mem_objects: *const cl_mem,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    #[cfg(feature = "cl_img_generate_mipmap")]
+    fn clEnqueueGenerateMipmapIMG(
+        command_queue: cl_command_queue,
+        src_image: cl_mem,
+        dst_image: cl_mem,
+        mipmap_filter_mode: cl_mipmap_filter_mode_img,
+        array_region: *const size_t,
+        mip_region: *const size_t,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    #[cfg(feature = "cl_khr_suggested_local_work_size")]
+    fn clGetKernelSuggestedLocalWorkSizeKHR(
+        command_queue: cl_command_queue,
+        kernel: cl_kernel,
+        work_dim: cl_uint,
+        global_work_offset: *const size_t,
+        global_work_size: *const size_t,
+        suggested_local_work_size: *mut size_t,
+    ) -> cl_int;
+    #[cfg(feature = "cl_khr_external_memory")]
+    fn clEnqueueAcquireExternalMemObjectsKHR(
+        command_queue: cl_command_queue,
+        num_mem_objects: cl_uint,
+        mem_objects: *const cl_mem,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    #[cfg(feature = "cl_khr_external_memory")]
+    fn clEnqueueReleaseExternalMemObjectsKHR(
+        command_queue: cl_command_queue,
+        num_mem_objects: cl_uint,
+        mem_objects: *const cl_mem,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    #[cfg(feature = "cl_khr_external_semaphore")]
+    fn clGetSemaphoreHandleForTypeKHR(
+        sema_object: cl_semaphore_khr,
+        device: cl_device_id,
+        handle_type: cl_external_semaphore_handle_type_khr,
+        handle_size: size_t,
+        handle_ptr: *mut c_void,
+        handle_size_ret: *mut size_t,
+    ) -> cl_int;
+    #[cfg(feature = "cl_khr_external_semaphore_sync_fd")]
+    fn clReImportSemaphoreSyncFdKHR(
+        sema_object: cl_semaphore_khr,
+        reimport_props: *mut cl_semaphore_reimport_properties_khr,
+        fd: c_int,
+    ) -> cl_int;
+    #[cfg(feature = "cl_khr_semaphore")]
+    fn clCreateSemaphoreWithPropertiesKHR(
+        context: cl_context,
+        sema_props: *const cl_semaphore_properties_khr,
+        errcode_ret: *mut cl_int,
+    ) -> cl_semaphore_khr [errcode_ret];
+    #[cfg(feature = "cl_khr_semaphore")]
+    fn clEnqueueWaitSemaphoresKHR(
+        command_queue: cl_command_queue,
+        num_sema_objects: cl_uint,
+        sema_objects: *const cl_semaphore_khr,
+        sema_payload_list: *const cl_semaphore_payload_khr,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    #[cfg(feature = "cl_khr_semaphore")]
+    fn clEnqueueSignalSemaphoresKHR(
+        command_queue: cl_command_queue,
+        num_sema_objects: cl_uint,
+        sema_objects: *const cl_semaphore_khr,
+        sema_payload_list: *const cl_semaphore_payload_khr,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    #[cfg(feature = "cl_khr_semaphore")]
+    fn clGetSemaphoreInfoKHR(
+        sema_object: cl_semaphore_khr,
+        param_name: cl_semaphore_info_khr,
+        param_value_size: size_t,
+        param_value: *mut c_void,
+        param_value_size_ret: *mut size_t,
+    ) -> cl_int;
+    #[cfg(feature = "cl_khr_semaphore")]
+    fn clReleaseSemaphoreKHR(sema_object: cl_semaphore_khr) -> cl_int;
+    #[cfg(feature = "cl_khr_semaphore")]
+    fn clRetainSemaphoreKHR(sema_object: cl_semaphore_khr) -> cl_int;
+    #[cfg(feature = "cl_arm_import_memory")]
+    fn clImportMemoryARM(
+        context: cl_context,
+        flags: cl_mem_flags,
+        properties: *const cl_import_properties_arm,
+        memory: *mut c_void,
+        size: size_t,
+        errcode_ret: *mut cl_int,
+    ) -> cl_mem [errcode_ret];
+    #[cfg(feature = "cl_intel_accelerator")]
+    fn clCreateAcceleratorINTEL(
+        context: cl_context,
+        accelerator_type: cl_accelerator_type_intel,
+        descriptor_size: size_t,
+        descriptor: *const c_void,
+        errcode_ret: *mut cl_int,
+    ) -> cl_accelerator_intel [errcode_ret];
+    #[cfg(feature = "cl_intel_accelerator")]
+    fn clGetAcceleratorInfoINTEL(
+        accelerator: cl_accelerator_intel,
+        param_name: cl_accelerator_info_intel,
+        param_value_size: size_t,
+        param_value: *mut c_void,
+        param_value_size_ret: *mut size_t,
+    ) -> cl_int;
+    #[cfg(feature = "cl_intel_accelerator")]
+    fn clRetainAcceleratorINTEL(accelerator: cl_accelerator_intel) -> cl_int;
+    #[cfg(feature = "cl_intel_accelerator")]
+    fn clReleaseAcceleratorINTEL(accelerator: cl_accelerator_intel) -> cl_int;
+    #[cfg(feature = "cl_intel_unified_shared_memory")]
+    fn clHostMemAllocINTEL(
+        context: cl_context,
+        properties: *const cl_mem_properties_intel,
+        size: size_t,
+        alignment: cl_uint,
+        errcode_ret: *mut cl_int,
+    ) -> *mut c_void [errcode_ret];
+    #[cfg(feature = "cl_intel_unified_shared_memory")]
+    fn clDeviceMemAllocINTEL(
+        context: cl_context,
+        device: cl_device_id,
+        properties: *const cl_mem_properties_intel,
+        size: size_t,
+        alignment: cl_uint,
+        errcode_ret: *mut cl_int,
+    ) -> *mut c_void [errcode_ret];
+    #[cfg(feature = "cl_intel_unified_shared_memory")]
+    fn clSharedMemAllocINTEL(
+        context: cl_context,
+        device: cl_device_id,
+        properties: *const cl_mem_properties_intel,
+        size: size_t,
+        alignment: cl_uint,
+        errcode_ret: *mut cl_int,
+    ) -> *mut c_void [errcode_ret];
+    #[cfg(feature = "cl_intel_unified_shared_memory")]
+    fn clMemFreeINTEL(context: cl_context, ptr: *mut c_void) -> cl_int;
+    #[cfg(feature = "cl_intel_unified_shared_memory")]
+    fn clMemBlockingFreeINTEL(context: cl_context, ptr: *mut c_void) -> cl_int;
+    #[cfg(feature = "cl_intel_unified_shared_memory")]
+    fn clGetMemAllocInfoINTEL(
+        context: cl_context,
+        ptr: *const c_void,
+        param_name: cl_mem_info_intel,
+        param_value_size: size_t,
+        param_value: *mut c_void,
+        param_value_size_ret: *mut size_t,
+    ) -> cl_int;
+    #[cfg(feature = "cl_intel_unified_shared_memory")]
+    fn clSetKernelArgMemPointerINTEL(
+        kernel: cl_kernel,
+        arg_index: cl_uint,
+        arg_value: *const c_void,
+    ) -> cl_int;
+    #[cfg(feature = "cl_intel_unified_shared_memory")]
+    fn clEnqueueMemFillINTEL(
+        command_queue: cl_command_queue,
+        dst_ptr: *mut c_void,
+        pattern: *const c_void,
+        pattern_size: size_t,
+        size: size_t,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    #[cfg(feature = "cl_intel_unified_shared_memory")]
+    fn clEnqueueMemcpyINTEL(
+        command_queue: cl_command_queue,
+        blocking: cl_bool,
+        dst_ptr: *mut c_void,
+        src_ptr: *const c_void,
+        size: size_t,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    #[cfg(feature = "cl_intel_unified_shared_memory")]
+    fn clEnqueueMemAdviseINTEL(
+        command_queue: cl_command_queue,
+        ptr: *const c_void,
+        size: size_t,
+        advice: cl_mem_advice_intel,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    #[cfg(feature = "cl_intel_unified_shared_memory")]
+    fn clEnqueueMigrateMemINTEL(
+        command_queue: cl_command_queue,
+        ptr: *const c_void,
+        size: size_t,
+        flags: cl_mem_migration_flags,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    #[cfg(feature = "cl_intel_unified_shared_memory")]
+    fn clEnqueueMemsetINTEL(
+        command_queue: cl_command_queue,
+        dst_ptr: *mut c_void,
+        value: cl_int,
+        size: size_t,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    #[cfg(feature = "cl_intel_program_scope_host_pipe")]
+    fn clEnqueueReadHostPipeINTEL(
+        queue: cl_command_queue,
+        program: cl_program,
+        pipe_symbol: *const c_char,
+        blocking_read: cl_bool,
+        ptr: *mut c_void,
+        size: size_t,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    #[cfg(feature = "cl_intel_program_scope_host_pipe")]
+    fn clEnqueueWriteHostPipeINTEL(
+        queue: cl_command_queue,
+        program: cl_program,
+        pipe_symbol: *const c_char,
+        blocking_write: cl_bool,
+        ptr: *const c_void,
+        size: size_t,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    #[cfg(feature = "cl_ext_image_requirements_info")]
+    fn clGetImageRequirementsInfoEXT(
+        context: cl_context,
+        properties: *const cl_mem_properties,
+        flags: cl_mem_flags,
+        image_format: *const cl_image_format,
+        image_desc: *const cl_image_desc,
+        param_name: cl_image_requirements_info_ext,
+        param_value_size: size_t,
+        param_value: *mut c_void,
+        param_value_size_ret: *mut size_t,
+    ) -> cl_int;
+    #[cfg(feature = "cl_loader_info")]
+    fn clGetICDLoaderInfoOCLICD(
+        param_name: cl_icdl_info,
+        param_value_size: size_t,
+        param_value: *mut c_void,
+        param_value_size_ret: *mut size_t,
+    ) -> cl_int;
+    #[cfg(feature = "cl_pocl_content_size")]
+    fn clSetContentSizeBufferPoCL(buffer: cl_mem, content_size_buffer: cl_mem) -> cl_int;
+
+    // EGL sharing APIs
+    fn clCreateFromEGLImageKHR(
+        context: cl_context,
+        display: CLeglDisplayKHR,
+        image: CLeglImageKHR,
+        flags: cl_mem_flags,
+        properties: *const cl_egl_image_properties_khr,
+        errcode_ret: *mut cl_int,
+    ) -> cl_mem [errcode_ret];
+    fn clEnqueueAcquireEGLObjectsKHR(
+        command_queue: cl_command_queue,
+        num_objects: cl_uint,
+        mem_objects: *const cl_mem,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    fn clEnqueueReleaseEGLObjectsKHR(
+        command_queue: cl_command_queue,
+        num_objects: cl_uint,
+        mem_objects: *const cl_mem,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    fn clCreateEventFromEGLSyncKHR(
+        context: cl_context,
+        sync: CLeglSyncKHR,
+        display: CLeglDisplayKHR,
+        errcode_ret: *mut cl_int,
+    ) -> cl_event [errcode_ret];
+
+    // Direct3D 10 sharing APIs
+    #[cfg(feature = "cl_khr_d3d10_sharing")]
+    fn clGetSupportedD3D10TextureFormatsINTEL(
+        context: cl_context,
+        flags: cl_mem_flags,
+        image_type: cl_mem_object_type,
+        num_entries: cl_uint,
+        d3d10_formats: *mut DXGI_FORMAT,
+        num_surface_formats: *mut cl_uint,
+    ) -> cl_int;
+
+    // Direct3D 11 sharing APIs
+    #[cfg(feature = "cl_khr_d3d11_sharing")]
+    fn clGetSupportedD3D11TextureFormatsINTEL(
+        context: cl_context,
+        flags: cl_mem_flags,
+        image_type: cl_mem_object_type,
+        plane: cl_uint,
+        num_entries: cl_uint,
+        d3d11_formats: *mut DXGI_FORMAT,
+        num_surface_formats: *mut cl_uint,
+    ) -> cl_int;
+
+    // DX9 media sharing APIs
+    #[cfg(feature = "cl_intel_dx9_media_sharing")]
+    fn clGetDeviceIDsFromDX9INTEL(
+        platform: cl_platform_id,
+        dx9_device_source: cl_dx9_device_source_intel,
+        dx9_object: *mut c_void,
+        dx9_device_set: cl_dx9_device_set_intel,
+        num_entries: cl_uint,
+        devices: *mut cl_device_id,
+        num_devices: *mut cl_uint,
+    ) -> cl_int;
+    #[cfg(feature = "cl_intel_dx9_media_sharing")]
+    fn clCreateFromDX9MediaSurfaceINTEL(
+        context: cl_context,
+        flags: cl_mem_flags,
+        resource: IDirect3DSurface9_ptr,
+        sharedHandle: HANDLE,
+        plane: cl_uint,
+        errcode_ret: *mut cl_int,
+    ) -> cl_mem [errcode_ret];
+    #[cfg(feature = "cl_intel_dx9_media_sharing")]
+    fn clEnqueueAcquireDX9ObjectsINTEL(
+        command_queue: cl_command_queue,
+        num_objects: cl_uint,
+        mem_objects: *const cl_mem,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    #[cfg(feature = "cl_intel_dx9_media_sharing")]
+    fn clEnqueueReleaseDX9ObjectsINTEL(
+        command_queue: cl_command_queue,
+        num_objects: cl_uint,
+        mem_objects: *const cl_mem,
+        num_events_in_wait_list: cl_uint,
+        event_wait_list: *const cl_event,
+        event: *mut cl_event,
+    ) -> cl_int;
+    #[cfg(any(feature = "cl_khr_dx9_media_sharing", feature = "cl_intel_dx9_media_sharing"))]
+    fn clGetSupportedDX9MediaSurfaceFormatsINTEL(
+        context: cl_context,
+        flags: cl_mem_flags,
+        image_type: cl_mem_object_type,
+        plane: cl_uint,
+        num_entries: cl_uint,
+        dx9_formats: *mut D3DFORMAT,
+        num_surface_formats: *mut cl_uint,
+    ) -> cl_int;
+
+    // Layer APIs
+    #[cfg(feature = "cl_loader_layers")]
+    fn clGetLayerInfo(
+        param_name: cl_layer_info,
+        param_value_size: size_t,
+        param_value: *mut c_void,
+        param_value_size_ret: *mut size_t,
+    ) -> cl_int;
+    #[cfg(feature = "cl_loader_layers")]
+    fn clInitLayer(
+        num_entries: cl_uint,
+        target_dispatch: *const cl_icd_dispatch,
+        num_entries_ret: *mut cl_uint,
+        layer_dispatch_ret: *mut *const cl_icd_dispatch,
+    ) -> cl_int;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::{create_context, release_context};
+    use crate::event::{create_user_event, get_event_info, release_event, set_user_event_status};
+    use crate::memory::{create_buffer, release_mem_object};
+    use crate::program::{
+        build_program, create_program_with_source, get_program_build_info, release_program,
+    };
+    use std::ffi::CString;
+
+    fn create_queue() -> (cl_context, cl_command_queue) {
+        let device = handle(DEVICE);
+        let context = create_context(&[device], ptr::null(), None, ptr::null_mut()).unwrap();
+        let mut status = CL_INVALID_VALUE;
+        let queue = unsafe { clCreateCommandQueue(context, device, 0, &mut status) };
+        assert_eq!(CL_SUCCESS, status);
+        (context, queue)
+    }
+
+    fn event_status(event: cl_event) -> cl_int {
+        get_event_info(event, CL_EVENT_COMMAND_EXECUTION_STATUS)
+            .unwrap()
+            .into()
+    }
+
+    #[test]
+    fn test_user_event_wait_list() {
+        let (context, queue) = create_queue();
+        let user_event = create_user_event(context).unwrap();
+        let buffer =
+            unsafe { create_buffer(context, CL_MEM_READ_WRITE, 4, ptr::null_mut()).unwrap() };
+
+        let value: u32 = 42;
+        let mut write_event = ptr::null_mut();
+        let status = unsafe {
+            clEnqueueWriteBuffer(
+                queue,
+                buffer,
+                CL_FALSE,
+                0,
+                4,
+                ptr::from_ref(&value).cast(),
+                1,
+                &user_event,
+                &mut write_event,
+            )
+        };
+        assert_eq!(CL_SUCCESS, status);
+        assert_eq!(CL_QUEUED, event_status(write_event));
+
+        // A barrier waits for the write command
+        let mut barrier_event = ptr::null_mut();
+        let status =
+            unsafe { clEnqueueBarrierWithWaitList(queue, 0, ptr::null(), &mut barrier_event) };
+        assert_eq!(CL_SUCCESS, status);
+        assert_eq!(CL_QUEUED, event_status(barrier_event));
+
+        set_user_event_status(user_event, CL_COMPLETE).unwrap();
+        assert_eq!(CL_COMPLETE, event_status(write_event));
+        assert_eq!(CL_COMPLETE, event_status(barrier_event));
+
+        let mut result: u32 = 0;
+        let status = unsafe {
+            clEnqueueReadBuffer(
+                queue,
+                buffer,
+                CL_TRUE,
+                0,
+                4,
+                ptr::from_mut(&mut result).cast(),
+                0,
+                ptr::null(),
+                ptr::null_mut(),
+            )
+        };
+        assert_eq!(CL_SUCCESS, status);
+        assert_eq!(value, result);
+
+        unsafe {
+            release_event(barrier_event).unwrap();
+            release_event(write_event).unwrap();
+            release_event(user_event).unwrap();
+            release_mem_object(buffer).unwrap();
+            clReleaseCommandQueue(queue);
+            release_context(context).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_failed_user_event() {
+        let (context, queue) = create_queue();
+        let user_event = create_user_event(context).unwrap();
+
+        let mut marker_event = ptr::null_mut();
+        let status =
+            unsafe { clEnqueueMarkerWithWaitList(queue, 1, &user_event, &mut marker_event) };
+        assert_eq!(CL_SUCCESS, status);
+
+        set_user_event_status(user_event, CL_INVALID_VALUE).unwrap();
+        assert_eq!(
+            CL_EXEC_STATUS_ERROR_FOR_EVENTS_IN_WAIT_LIST,
+            event_status(marker_event)
+        );
+
+        unsafe {
+            release_event(marker_event).unwrap();
+            release_event(user_event).unwrap();
+            clReleaseCommandQueue(queue);
+            release_context(context).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_copy_rect() {
+        // Copy a 2x2 region from the middle of a 4x4 array to a 2x2 array
+        let src: Vec<u8> = (0..16).collect();
+        let mut dst = [0_u8; 4];
+        unsafe {
+            copy_rect(
+                src.as_ptr(),
+                [1, 1, 0],
+                Pitch::new(4, 16),
+                dst.as_mut_ptr(),
+                [0, 0, 0],
+                Pitch::new(2, 4),
+                [2, 2, 1],
+            );
+        }
+        assert_eq!([5, 6, 9, 10], dst);
+    }
+
+    #[test]
+    fn test_build_error_log() {
+        let (context, queue) = create_queue();
+        let source = "kernel void add(global int* a)\n{\n    a[0] += 1;\n}\n}\n";
+        let program = create_program_with_source(context, &[source]).unwrap();
+        let device = handle(DEVICE);
+        let options = CString::new("-DVALUE=1").unwrap();
+        let result = build_program(program, &[device], &options, None, ptr::null_mut());
+        assert_eq!(Err(CL_BUILD_PROGRAM_FAILURE), result);
+
+        let log =
+            String::from(get_program_build_info(program, device, CL_PROGRAM_BUILD_LOG).unwrap());
+        println!("{log}");
+        assert!(log.contains("<source>:5:1: error: extraneous closing brace ('}')"));
+        assert!(log.contains("1 error generated."));
+
+        unsafe {
+            release_program(program).unwrap();
+            clReleaseCommandQueue(queue);
+            release_context(context).unwrap();
+        }
+    }
+}
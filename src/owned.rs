@@ -0,0 +1,304 @@
+// Copyright (c) 2024 Via Technology Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Owned `OpenCL` object handles.
+//!
+//! Each type owns one reference to an `OpenCL` object: it releases the object
+//! when it is dropped and retains the object when it is cloned, so that the
+//! objects are not leaked when a function returns early with an error, e.g.:
+//!
+//! ```no_run
+//! use cl3::context::create_context;
+//! use cl3::device::{get_device_ids, CL_DEVICE_TYPE_GPU};
+//! use cl3::owned::Context;
+//! use cl3::platform::get_platform_ids;
+//! use cl3::program::create_program_with_source;
+//! use cl3::types::cl_int;
+//! use std::ptr;
+//!
+//! fn example() -> Result<(), cl_int> {
+//!     let platform_ids = get_platform_ids()?;
+//!     let device_ids = get_device_ids(platform_ids[0], CL_DEVICE_TYPE_GPU)?;
+//!     let context = unsafe {
+//!         Context::from_raw(create_context(&device_ids, ptr::null(), None, ptr::null_mut())?)
+//!     };
+//!     // The context is released if this fails
+//!     let _program = create_program_with_source(&context, &["kernel void f() {}"])?;
+//!     Ok(())
+//! }
+//! ```
+//!
+//! The adapter functions of the `OpenCL` API modules accept either a raw
+//! handle or a reference to the owned type, through the `As*` traits below.
+//! The owned types are `#[repr(transparent)]`, so a slice of them can be
+//! passed to functions that take a slice or array of raw handles with
+//! `as_raw_slice`.
+
+#![allow(non_camel_case_types)]
+
+use super::api_error::preserve_error;
+use super::command_queue::release_command_queue;
+use super::command_queue::retain_command_queue;
+use super::context::{release_context, retain_context};
+#[cfg(feature = "CL_VERSION_1_2")]
+use super::device::{release_device, retain_device};
+use super::event::{release_event, retain_event};
+use super::kernel::{release_kernel, retain_kernel};
+use super::memory::{release_mem_object, retain_mem_object};
+use super::program::{release_program, retain_program};
+use super::sampler::{release_sampler, retain_sampler};
+use opencl_sys::cl_int;
+pub use opencl_sys::{
+    cl_command_queue, cl_context, cl_device_id, cl_event, cl_kernel, cl_mem, cl_program, cl_sampler,
+};
+use std::fmt;
+use std::mem;
+use std::slice;
+
+macro_rules! owned_handle {
+    (
+        $(#[$attr:meta])*
+        $name:ident, $as_trait:ident, $raw:ident, $object:literal, $retain:ident, $release:ident
+    ) => {
+        $(#[$attr])*
+        #[repr(transparent)]
+        pub struct $name($raw);
+
+        impl $name {
+            #[doc = concat!("Take ownership of a raw `", stringify!($raw), "` handle.")]
+            ///
+            /// # Safety
+            ///
+            #[doc = concat!("`handle` must be a valid ", $object, " handle and the caller must")]
+            /// own a reference to it, which is released when the returned value is dropped.
+            #[must_use]
+            pub const unsafe fn from_raw(handle: $raw) -> Self {
+                Self(handle)
+            }
+
+            #[doc = concat!("Retain a raw `", stringify!($raw), "` handle that the caller does not own.")]
+            ///
+            /// # Safety
+            ///
+            #[doc = concat!("`handle` must be a valid ", $object, " handle.")]
+            pub unsafe fn retain_raw(handle: $raw) -> Result<Self, cl_int> {
+                $retain(handle)?;
+                Ok(Self(handle))
+            }
+
+            #[doc = concat!("The raw `", stringify!($raw), "` handle.")]
+            #[must_use]
+            pub const fn as_raw(&self) -> $raw {
+                self.0
+            }
+
+            #[doc = concat!("Release ownership of the raw `", stringify!($raw), "` handle")]
+            /// without releasing the object.
+            #[must_use]
+            pub const fn into_raw(self) -> $raw {
+                let handle = self.0;
+                mem::forget(self);
+                handle
+            }
+
+            /// The raw handles of a slice of owned handles.
+            #[must_use]
+            pub const fn as_raw_slice(handles: &[Self]) -> &[$raw] {
+                // Safe because Self is repr(transparent)
+                unsafe { slice::from_raw_parts(handles.as_ptr().cast::<$raw>(), handles.len()) }
+            }
+        }
+
+        impl Clone for $name {
+            fn clone(&self) -> Self {
+                unsafe { $retain(self.0) }.expect(concat!("invalid OpenCL ", $object));
+                Self(self.0)
+            }
+        }
+
+        impl Drop for $name {
+            fn drop(&mut self) {
+                // There is nothing useful to do with an error in drop, and an
+                // error that is being returned keeps its recorded context
+                let _ = preserve_error(|| unsafe { $release(self.0) });
+            }
+        }
+
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.debug_tuple(stringify!($name)).field(&self.0).finish()
+            }
+        }
+
+        impl PartialEq for $name {
+            fn eq(&self, other: &Self) -> bool {
+                self.0 == other.0
+            }
+        }
+
+        impl Eq for $name {}
+
+        impl From<$name> for $raw {
+            fn from(value: $name) -> Self {
+                value.into_raw()
+            }
+        }
+
+        #[doc = concat!("A raw or owned ", $object, " handle.")]
+        pub trait $as_trait: Copy {
+            #[doc = concat!("The raw `", stringify!($raw), "` handle.")]
+            fn as_raw(self) -> $raw;
+        }
+
+        impl $as_trait for $raw {
+            fn as_raw(self) -> $raw {
+                self
+            }
+        }
+
+        impl $as_trait for &$name {
+            fn as_raw(self) -> $raw {
+                self.0
+            }
+        }
+    };
+}
+
+owned_handle!(
+    /// An owned `OpenCL` context.
+    Context, AsContext, cl_context, "context", retain_context, release_context
+);
+
+owned_handle!(
+    /// An owned `OpenCL` command-queue.
+    CommandQueue, AsCommandQueue, cl_command_queue, "command-queue",
+    retain_command_queue, release_command_queue
+);
+
+owned_handle!(
+    /// An owned `OpenCL` memory object: a buffer, image or pipe.
+    Mem, AsMem, cl_mem, "memory object", retain_mem_object, release_mem_object
+);
+
+owned_handle!(
+    /// An owned `OpenCL` program.
+    Program, AsProgram, cl_program, "program", retain_program, release_program
+);
+
+owned_handle!(
+    /// An owned `OpenCL` kernel.
+    Kernel, AsKernel, cl_kernel, "kernel", retain_kernel, release_kernel
+);
+
+owned_handle!(
+    /// An owned `OpenCL` event.
+    Event, AsEvent, cl_event, "event", retain_event, release_event
+);
+
+owned_handle!(
+    /// An owned `OpenCL` sampler.
+    Sampler, AsSampler, cl_sampler, "sampler", retain_sampler, release_sampler
+);
+
+#[cfg(feature = "CL_VERSION_1_2")]
+owned_handle!(
+    /// An owned `OpenCL` device.
+    /// Retaining and releasing only changes the reference count of sub-devices,
+    /// root devices are not reference counted.
+    Device, AsDevice, cl_device_id, "device", retain_device, release_device
+);
+
+/// A raw or owned device handle.
+#[cfg(not(feature = "CL_VERSION_1_2"))]
+pub trait AsDevice: Copy {
+    /// The raw `cl_device_id` handle.
+    fn as_raw(self) -> cl_device_id;
+}
+
+#[cfg(not(feature = "CL_VERSION_1_2"))]
+impl AsDevice for cl_device_id {
+    fn as_raw(self) -> cl_device_id {
+        self
+    }
+}
+
+// OpenCL objects are thread safe, except for setting kernel arguments.
+unsafe impl Send for Context {}
+unsafe impl Sync for Context {}
+unsafe impl Send for CommandQueue {}
+unsafe impl Sync for CommandQueue {}
+unsafe impl Send for Mem {}
+unsafe impl Sync for Mem {}
+unsafe impl Send for Program {}
+unsafe impl Sync for Program {}
+unsafe impl Send for Kernel {}
+unsafe impl Send for Event {}
+unsafe impl Sync for Event {}
+unsafe impl Send for Sampler {}
+unsafe impl Sync for Sampler {}
+#[cfg(feature = "CL_VERSION_1_2")]
+unsafe impl Send for Device {}
+#[cfg(feature = "CL_VERSION_1_2")]
+unsafe impl Sync for Device {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::{create_context, get_context_info, CL_CONTEXT_REFERENCE_COUNT};
+    use crate::device::{get_device_ids, CL_DEVICE_TYPE_GPU};
+    use crate::memory::{create_buffer, get_mem_object_info, CL_MEM_CONTEXT, CL_MEM_READ_WRITE};
+    use crate::platform::get_platform_ids;
+    use opencl_sys::cl_uint;
+    use std::ptr;
+
+    #[test]
+    fn test_owned_context() {
+        let platform_ids = get_platform_ids().unwrap();
+
+        // Choose the first platform
+        let platform_id = platform_ids[0];
+
+        let device_ids = get_device_ids(platform_id, CL_DEVICE_TYPE_GPU).unwrap();
+        assert!(0 < device_ids.len());
+
+        let context = create_context(&device_ids, ptr::null(), None, ptr::null_mut()).unwrap();
+        let context = unsafe { Context::from_raw(context) };
+
+        let value = get_context_info(&context, CL_CONTEXT_REFERENCE_COUNT).unwrap();
+        let initial_count = cl_uint::from(value);
+        println!("CL_CONTEXT_REFERENCE_COUNT: {}", initial_count);
+
+        // Cloning retains the context
+        let clone = context.clone();
+        assert_eq!(context, clone);
+        let value = get_context_info(&context, CL_CONTEXT_REFERENCE_COUNT).unwrap();
+        assert_eq!(initial_count + 1, cl_uint::from(value));
+
+        // Dropping releases the context
+        drop(clone);
+        let value = get_context_info(context.as_raw(), CL_CONTEXT_REFERENCE_COUNT).unwrap();
+        assert_eq!(initial_count, cl_uint::from(value));
+
+        // A buffer holds a reference to its context
+        let buffer = unsafe {
+            Mem::from_raw(create_buffer(&context, CL_MEM_READ_WRITE, 16, ptr::null_mut()).unwrap())
+        };
+        let value = get_mem_object_info(&buffer, CL_MEM_CONTEXT).unwrap();
+        assert_eq!(context.as_raw() as isize, isize::from(value));
+
+        let raw = buffer.into_raw();
+        let buffer = unsafe { Mem::from_raw(raw) };
+        assert_eq!(raw, Mem::as_raw_slice(&[buffer])[0]);
+    }
+}
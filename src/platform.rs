@@ -26,7 +26,8 @@ pub use opencl_sys::{
     CL_PLATFORM_SEMAPHORE_TYPES_KHR, CL_PLATFORM_VENDOR, CL_PLATFORM_VERSION, CL_SUCCESS,
 };
 
-use opencl_sys::{clGetPlatformIDs, clGetPlatformInfo};
+use super::api_error::{clear_error, record_error};
+use super::ffi::{clGetPlatformIDs, clGetPlatformInfo};
 
 use super::info_type::InfoType;
 use super::{api_info_size, api_info_value, api_info_vector};
@@ -48,12 +49,13 @@ use std::ptr;
 /// returns a Result containing a vector of available platform ids
 /// or the error code from the `OpenCL` C API function.
 pub fn get_platform_ids() -> Result<Vec<cl_platform_id>, cl_int> {
+    clear_error();
     // Get the number of platforms
     let mut count: cl_uint = 0;
     let mut status = unsafe { clGetPlatformIDs(0, ptr::null_mut(), &mut count) };
 
     if CL_SUCCESS != status {
-        Err(status)
+        Err(record_error!(status, clGetPlatformIDs))
     } else if 0 < count {
         // Get the platform ids.
         let len = count as usize;
@@ -66,7 +68,7 @@ pub fn get_platform_ids() -> Result<Vec<cl_platform_id>, cl_int> {
         if CL_SUCCESS == status {
             Ok(ids)
         } else {
-            Err(status)
+            Err(record_error!(status, clGetPlatformIDs))
         }
     } else {
         Ok(Vec::default())
@@ -79,6 +81,7 @@ pub fn get_platform_data(
     platform: cl_platform_id,
     param_name: cl_platform_info,
 ) -> Result<Vec<u8>, cl_int> {
+    clear_error();
     api_info_size!(get_size, clGetPlatformInfo);
     let size = get_size(platform, param_name)?;
     api_info_vector!(get_vector, u8, clGetPlatformInfo);
@@ -118,6 +121,7 @@ pub fn get_platform_info(
     platform: cl_platform_id,
     param_name: cl_platform_info,
 ) -> Result<InfoType, cl_int> {
+    clear_error();
     match param_name {
         // CL_VERSION_3_0
         CL_PLATFORM_NUMERIC_VERSION => {
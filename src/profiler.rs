@@ -0,0 +1,439 @@
+// Copyright (c) 2024 Via Technology Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A timeline of the commands on profiling enabled command-queues.
+//!
+//! A [`Profiler`] collects the events of commands enqueued on command-queues
+//! created with `CL_QUEUE_PROFILING_ENABLE`, with a label for each command.
+//! When the commands have completed, it reads their `CL_PROFILING_COMMAND_*`
+//! timestamps and writes them as a [Chrome trace](https://docs.google.com/document/d/1CvAClvFfyA5R-PhYUmn5OOQtYMH4h6I0nSsKchNAySU)
+//! that can be viewed with `chrome://tracing` or [Perfetto](https://ui.perfetto.dev), e.g.:
+//!
+//! ```no_run
+//! use cl3::profiler::Profiler;
+//! use cl3::types::{cl_event, cl_int};
+//! use std::fs::File;
+//! use std::ptr;
+//!
+//! fn profile(write_event: cl_event, kernel_event: cl_event) -> Result<Profiler, cl_int> {
+//!     let mut profiler = Profiler::new();
+//!     profiler.add(write_event, "upload")?;
+//!     profiler.add(kernel_event, "saxpy")?;
+//!     profiler.collect()?;
+//!     Ok(profiler)
+//! }
+//!
+//! # let (write_event, kernel_event) = (ptr::null_mut(), ptr::null_mut());
+//! let profiler = profile(write_event, kernel_event).expect("profiling failed");
+//! let file = File::create("trace.json").expect("create trace file");
+//! profiler.write_chrome_trace(file).expect("write trace file");
+//! ```
+//!
+//! The device timestamps are converted to host time with the offset between the
+//! device and host timers from `get_device_and_host_timer`, when the
+//! "`CL_VERSION_2_1`" feature is enabled and the device supports it.
+//! Otherwise, the timestamps are device time.
+
+#![allow(non_camel_case_types)]
+
+#[cfg(feature = "CL_VERSION_2_1")]
+use super::api_error::discard_error;
+use super::api_error::{preserve_error, record_error};
+use super::command_queue::{get_command_queue_info, CL_QUEUE_DEVICE};
+#[cfg(feature = "CL_VERSION_2_1")]
+use super::device::get_device_and_host_timer;
+use super::event::{
+    command_type_text, get_event_info, get_event_profiling_info, wait_for_events,
+    CL_EVENT_COMMAND_QUEUE, CL_EVENT_COMMAND_TYPE, CL_PROFILING_COMMAND_END,
+    CL_PROFILING_COMMAND_QUEUED, CL_PROFILING_COMMAND_START, CL_PROFILING_COMMAND_SUBMIT,
+};
+use super::info_type::InfoTypeError;
+use super::owned::{AsEvent, Event};
+use opencl_sys::{
+    cl_command_queue, cl_command_type, cl_device_id, cl_int, cl_ulong, CL_INVALID_VALUE,
+};
+use std::io::{self, Write};
+use std::mem;
+
+/// The profiling information of a completed command.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProfiledCommand {
+    /// The label given to the command.
+    pub label: String,
+    /// The type of the command, see `CL_EVENT_COMMAND_TYPE`.
+    pub command_type: cl_command_type,
+    /// The command-queue that the command was enqueued on.
+    pub queue: cl_command_queue,
+    /// The device of the command-queue.
+    pub device: cl_device_id,
+    /// The time in nanoseconds when the command was enqueued.
+    pub queued: cl_ulong,
+    /// The time in nanoseconds when the command was submitted to the device.
+    pub submit: cl_ulong,
+    /// The time in nanoseconds when the command started executing.
+    pub start: cl_ulong,
+    /// The time in nanoseconds when the command finished executing.
+    pub end: cl_ulong,
+}
+
+// The handles are only used as identifiers, see `owned`.
+unsafe impl Send for ProfiledCommand {}
+unsafe impl Sync for ProfiledCommand {}
+
+impl ProfiledCommand {
+    /// The name of the command type, e.g. "`CL_COMMAND_NDRANGE_KERNEL`".
+    #[must_use]
+    pub const fn command_name(&self) -> &'static str {
+        command_type_text(self.command_type)
+    }
+
+    /// The execution time of the command in nanoseconds.
+    #[must_use]
+    pub const fn duration(&self) -> cl_ulong {
+        self.end.saturating_sub(self.start)
+    }
+}
+
+/// Collects the profiling information of labelled commands.
+#[derive(Debug, Default)]
+pub struct Profiler {
+    pending: Vec<(Event, String)>,
+    commands: Vec<ProfiledCommand>,
+    host_offsets: Vec<(cl_device_id, i64)>,
+}
+
+// The device ids are only used as identifiers, see `owned`.
+unsafe impl Send for Profiler {}
+unsafe impl Sync for Profiler {}
+
+impl Profiler {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the event of a command enqueued on a profiling enabled command-queue.
+    /// The event is retained until the profiling information is collected.
+    ///
+    /// * `event` - the `OpenCL` event of the command.
+    /// * `label` - the label of the command in the timeline.
+    ///
+    /// returns an empty Result or the error code from the `OpenCL` C API function.
+    pub fn add(&mut self, event: impl AsEvent, label: impl Into<String>) -> Result<(), cl_int> {
+        let event = unsafe { Event::retain_raw(event.as_raw())? };
+        self.pending.push((event, label.into()));
+        Ok(())
+    }
+
+    /// Wait for the commands of the added events to complete and collect
+    /// their profiling information.
+    /// The events are released. A command whose information cannot be
+    /// collected is left out of the timeline, e.g. a command enqueued on a
+    /// command-queue without profiling enabled.
+    ///
+    /// returns an empty Result or the first error code from the `OpenCL` C API
+    /// functions, e.g. `CL_PROFILING_INFO_NOT_AVAILABLE` if profiling is not
+    /// enabled on a command-queue.
+    pub fn collect(&mut self) -> Result<(), cl_int> {
+        let pending = mem::take(&mut self.pending);
+        if pending.is_empty() {
+            return Ok(());
+        }
+
+        let events: Vec<_> = pending.iter().map(|(event, _)| event.as_raw()).collect();
+        // The information of the other commands can be collected if a command failed
+        let mut result = wait_for_events(&events);
+        for (event, label) in pending {
+            // Keep the recorded context of the first error
+            let command = if result.is_ok() {
+                self.command(&event, label)
+            } else {
+                preserve_error(|| self.command(&event, label))
+            };
+            match command {
+                Ok(command) => self.commands.push(command),
+                Err(error) => result = result.and(Err(error)),
+            }
+        }
+        result
+    }
+
+    /// The profiling information of the completed command of an event.
+    fn command(&mut self, event: &Event, label: String) -> Result<ProfiledCommand, cl_int> {
+        let command_type = info_value(get_event_info(event, CL_EVENT_COMMAND_TYPE)?.try_uint())?;
+        let queue = info_value(get_event_info(event, CL_EVENT_COMMAND_QUEUE)?.try_ptr())?
+            as cl_command_queue;
+        let device =
+            info_value(get_command_queue_info(queue, CL_QUEUE_DEVICE)?.try_ptr())? as cl_device_id;
+        let offset = host_offset(&mut self.host_offsets, device);
+        let time = |param_name| -> Result<cl_ulong, cl_int> {
+            let time = info_value(get_event_profiling_info(event, param_name)?.try_ulong())?;
+            Ok(time.wrapping_add_signed(offset))
+        };
+        Ok(ProfiledCommand {
+            label,
+            command_type,
+            queue,
+            device,
+            queued: time(CL_PROFILING_COMMAND_QUEUED)?,
+            submit: time(CL_PROFILING_COMMAND_SUBMIT)?,
+            start: time(CL_PROFILING_COMMAND_START)?,
+            end: time(CL_PROFILING_COMMAND_END)?,
+        })
+    }
+
+    /// The collected commands, in the order that they were added.
+    #[must_use]
+    pub fn commands(&self) -> &[ProfiledCommand] {
+        &self.commands
+    }
+
+    /// Remove the collected commands.
+    pub fn clear(&mut self) {
+        self.commands.clear();
+    }
+
+    /// Write the collected commands as Chrome trace JSON.
+    ///
+    /// Each device is a process and each command-queue is a thread of the
+    /// trace. Each command is a complete event named by its label, with the
+    /// command type as its category and its profiling timestamps as arguments.
+    ///
+    /// * `writer` - the writer for the JSON.
+    ///
+    /// returns an empty Result or the error from the writer.
+    pub fn write_chrome_trace(&self, mut writer: impl Write) -> io::Result<()> {
+        let mut devices: Vec<cl_device_id> = Vec::new();
+        let mut queues: Vec<cl_command_queue> = Vec::new();
+
+        write!(writer, "{{\"displayTimeUnit\":\"ns\",\"traceEvents\":[")?;
+        let mut separator = "";
+        for command in &self.commands {
+            let (pid, new_device) = index_of(&mut devices, command.device);
+            let (tid, new_queue) = index_of(&mut queues, command.queue);
+            if new_device {
+                write!(
+                    writer,
+                    "{separator}{{\"name\":\"process_name\",\"ph\":\"M\",\"pid\":{pid},\"args\":{{\"name\":\"device {:?}\"}}}}",
+                    command.device
+                )?;
+                separator = ",";
+            }
+            if new_queue {
+                write!(
+                    writer,
+                    "{separator}{{\"name\":\"thread_name\",\"ph\":\"M\",\"pid\":{pid},\"tid\":{tid},\"args\":{{\"name\":\"queue {:?}\"}}}}",
+                    command.queue
+                )?;
+                separator = ",";
+            }
+            write!(writer, "{separator}{{\"name\":")?;
+            write_json_string(&mut writer, &command.label)?;
+            write!(
+                writer,
+                ",\"cat\":\"{}\",\"ph\":\"X\",\"ts\":{},\"dur\":{},\"pid\":{pid},\"tid\":{tid},\"args\":{{\"queued\":{},\"submit\":{},\"start\":{},\"end\":{}}}}}",
+                command.command_name(),
+                Micros(command.start),
+                Micros(command.duration()),
+                command.queued,
+                command.submit,
+                command.start,
+                command.end
+            )?;
+            separator = ",";
+        }
+        write!(writer, "]}}")
+    }
+}
+
+/// The value of an `InfoType`, or `CL_INVALID_VALUE` if it is not the expected type.
+fn info_value<T>(value: Result<T, InfoTypeError>) -> Result<T, cl_int> {
+    value.map_err(|error| record_error!(CL_INVALID_VALUE, Profiler::collect, error))
+}
+
+/// The offset from the device timer to the host timer of a device,
+/// cached in `host_offsets`.
+fn host_offset(host_offsets: &mut Vec<(cl_device_id, i64)>, device: cl_device_id) -> i64 {
+    if let Some((_, offset)) = host_offsets.iter().find(|(id, _)| *id == device) {
+        return *offset;
+    }
+
+    #[cfg(feature = "CL_VERSION_2_1")]
+    #[allow(clippy::cast_possible_wrap)]
+    let offset = discard_error(get_device_and_host_timer(device))
+        .map_or(0, |[device_timestamp, host_timestamp]| {
+            host_timestamp.wrapping_sub(device_timestamp) as i64
+        });
+    #[cfg(not(feature = "CL_VERSION_2_1"))]
+    let offset = 0;
+
+    host_offsets.push((device, offset));
+    offset
+}
+
+/// The index of a handle in `handles` and whether it was added because it
+/// was not present.
+fn index_of<T: PartialEq>(handles: &mut Vec<T>, handle: T) -> (usize, bool) {
+    handles.iter().position(|h| *h == handle).map_or_else(
+        || {
+            handles.push(handle);
+            (handles.len() - 1, true)
+        },
+        |index| (index, false),
+    )
+}
+
+/// Nanoseconds displayed as microseconds, the Chrome trace time unit.
+struct Micros(cl_ulong);
+
+impl std::fmt::Display for Micros {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}.{:03}", self.0 / 1000, self.0 % 1000)
+    }
+}
+
+fn write_json_string(writer: &mut impl Write, text: &str) -> io::Result<()> {
+    write!(writer, "\"")?;
+    for c in text.chars() {
+        match c {
+            '"' => write!(writer, "\\\"")?,
+            '\\' => write!(writer, "\\\\")?,
+            '\n' => write!(writer, "\\n")?,
+            '\r' => write!(writer, "\\r")?,
+            '\t' => write!(writer, "\\t")?,
+            c if c.is_control() => write!(writer, "\\u{:04x}", u32::from(c))?,
+            c => write!(writer, "{c}")?,
+        }
+    }
+    write!(writer, "\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[allow(deprecated)]
+    use crate::command_queue::{
+        create_command_queue, enqueue_write_buffer, finish, release_command_queue,
+        CL_QUEUE_PROFILING_ENABLE,
+    };
+    use crate::context::{create_context, release_context};
+    use crate::device::{get_device_ids, CL_DEVICE_TYPE_GPU};
+    use crate::error_codes::CL_PROFILING_INFO_NOT_AVAILABLE;
+    use crate::event::{release_event, CL_COMMAND_WRITE_BUFFER};
+    use crate::memory::{create_buffer, release_mem_object, CL_MEM_READ_WRITE};
+    use crate::platform::get_platform_ids;
+    use crate::types::CL_FALSE;
+    use std::ptr;
+
+    #[test]
+    fn test_profiler() {
+        let platform_ids = get_platform_ids().unwrap();
+        assert!(0 < platform_ids.len());
+
+        let device_ids = get_device_ids(platform_ids[0], CL_DEVICE_TYPE_GPU).unwrap();
+        assert!(0 < device_ids.len());
+        let device_id = device_ids[0];
+
+        let context = create_context(&device_ids, ptr::null(), None, ptr::null_mut()).unwrap();
+        #[allow(deprecated)]
+        let queue =
+            unsafe { create_command_queue(context, device_id, CL_QUEUE_PROFILING_ENABLE).unwrap() };
+        let buffer =
+            unsafe { create_buffer(context, CL_MEM_READ_WRITE, 4, ptr::null_mut()).unwrap() };
+
+        let mut profiler = Profiler::new();
+        let value: u32 = 42;
+        for label in ["first \"write\"", "second write"] {
+            let event = unsafe {
+                enqueue_write_buffer(
+                    queue,
+                    buffer,
+                    CL_FALSE,
+                    0,
+                    4,
+                    ptr::from_ref(&value).cast(),
+                    0,
+                    ptr::null(),
+                )
+                .unwrap()
+            };
+            profiler.add(event, label).unwrap();
+            unsafe { release_event(event).unwrap() };
+        }
+        finish(queue).unwrap();
+        profiler.collect().unwrap();
+
+        let commands = profiler.commands();
+        assert_eq!(2, commands.len());
+        assert_eq!("first \"write\"", commands[0].label);
+        assert_eq!(CL_COMMAND_WRITE_BUFFER, commands[0].command_type);
+        assert_eq!("CL_COMMAND_WRITE_BUFFER", commands[0].command_name());
+        assert_eq!(queue, commands[0].queue);
+        assert_eq!(device_id, commands[0].device);
+        assert!(commands[0].queued <= commands[0].submit);
+        assert!(commands[0].submit <= commands[0].start);
+        assert!(commands[0].start <= commands[0].end);
+
+        let mut json = Vec::new();
+        profiler.write_chrome_trace(&mut json).unwrap();
+        println!("Chrome trace: {}", String::from_utf8_lossy(&json));
+        let trace: serde_json::Value = serde_json::from_slice(&json).unwrap();
+        let trace_events = trace["traceEvents"].as_array().unwrap();
+        // process_name, thread_name and the two commands
+        assert_eq!(4, trace_events.len());
+        assert_eq!("first \"write\"", trace_events[2]["name"]);
+        assert_eq!("CL_COMMAND_WRITE_BUFFER", trace_events[2]["cat"]);
+        assert_eq!("X", trace_events[2]["ph"]);
+        assert_eq!("second write", trace_events[3]["name"]);
+
+        profiler.clear();
+        assert!(profiler.commands().is_empty());
+
+        // A command on a command-queue without profiling is left out
+        #[allow(deprecated)]
+        let unprofiled_queue = unsafe { create_command_queue(context, device_id, 0).unwrap() };
+        for (queue, label) in [(unprofiled_queue, "unprofiled"), (queue, "profiled")] {
+            let event = unsafe {
+                enqueue_write_buffer(
+                    queue,
+                    buffer,
+                    CL_FALSE,
+                    0,
+                    4,
+                    ptr::from_ref(&value).cast(),
+                    0,
+                    ptr::null(),
+                )
+                .unwrap()
+            };
+            profiler.add(event, label).unwrap();
+            unsafe { release_event(event).unwrap() };
+            finish(queue).unwrap();
+        }
+        assert_eq!(Err(CL_PROFILING_INFO_NOT_AVAILABLE), profiler.collect());
+        assert_eq!(1, profiler.commands().len());
+        assert_eq!("profiled", profiler.commands()[0].label);
+
+        // The events are not collected again
+        profiler.collect().unwrap();
+        assert_eq!(1, profiler.commands().len());
+
+        unsafe {
+            release_mem_object(buffer).unwrap();
+            release_command_queue(unprofiled_queue).unwrap();
+            release_command_queue(queue).unwrap();
+            release_context(context).unwrap();
+        }
+    }
+}
@@ -29,19 +29,21 @@ pub use opencl_sys::{
     CL_PROGRAM_REFERENCE_COUNT, CL_PROGRAM_SOURCE, CL_SUCCESS, CL_TRUE,
 };
 
-use opencl_sys::{
+use super::api_error::{clear_error, preserve_error, record_build, record_error};
+use super::ffi::{
     clBuildProgram, clCompileProgram, clCreateProgramWithBinary, clCreateProgramWithBuiltInKernels,
     clCreateProgramWithSource, clGetProgramBuildInfo, clGetProgramInfo, clLinkProgram,
     clReleaseProgram, clRetainProgram, clUnloadPlatformCompiler,
 };
 
 #[cfg(feature = "CL_VERSION_2_1")]
-use opencl_sys::clCreateProgramWithIL;
+use super::ffi::clCreateProgramWithIL;
 
 #[cfg(feature = "CL_VERSION_2_2")]
-use opencl_sys::clSetProgramSpecializationConstant;
+use super::ffi::clSetProgramSpecializationConstant;
 
 use super::info_type::InfoType;
+use super::owned::{AsContext, AsDevice, AsProgram};
 use super::{
     api2_info_size, api2_info_value, api2_info_vector, api_info_size, api_info_value,
     api_info_vector,
@@ -66,9 +68,11 @@ pub const CL_PROGRAM_SCOPE_GLOBAL_DTORS_PRESENT: cl_program_info = 0x116B;
 #[allow(clippy::cast_possible_truncation)]
 #[inline]
 pub fn create_program_with_source(
-    context: cl_context,
+    context: impl AsContext,
     sources: &[&str],
 ) -> Result<cl_program, cl_int> {
+    let context = context.as_raw();
+    clear_error();
     let lengths: Vec<size_t> = sources.iter().map(|src| src.len()).collect();
     let mut status: cl_int = CL_INVALID_VALUE;
     let program: cl_program = unsafe {
@@ -84,7 +88,7 @@ pub fn create_program_with_source(
     if CL_SUCCESS == status {
         Ok(program)
     } else {
-        Err(status)
+        Err(record_error!(status, clCreateProgramWithSource))
     }
 }
 
@@ -96,38 +100,98 @@ pub fn create_program_with_source(
 /// * `binaries` - a slice of program binaries slices.
 ///
 /// returns a Result containing the new `OpenCL` program object
-/// or the error code from the `OpenCL` C API function.
+/// or the error code from the `OpenCL` C API function.  
+/// See `create_program_with_binary_status` for the status of each binary.
 ///
 /// # Safety
 ///
 /// This is unsafe when a device is not a member of context.
-#[allow(clippy::cast_possible_truncation)]
 pub unsafe fn create_program_with_binary(
-    context: cl_context,
+    context: impl AsContext,
     devices: &[cl_device_id],
     binaries: &[&[u8]],
 ) -> Result<cl_program, cl_int> {
-    let binaries_length = binaries.len();
+    create_program_with_binary_status(context, devices, binaries)
+        .map(|(program, _)| program)
+        .map_err(|(status, _)| status)
+}
+
+/// Create an `OpenCL` program object for a context and load binary bits into that object.  
+/// Calls `clCreateProgramWithBinary` to create an `OpenCL` program object.  
+///
+/// * `context` - a valid `OpenCL` context.
+/// * `devices` - a slice of devices that are in context.
+/// * `binaries` - a slice of program binaries slices, one for each device.
+///
+/// returns a Result containing the new `OpenCL` program object and the
+/// status of the binary for each device, or the error code from the `OpenCL`
+/// C API function and the status of the binary for each device.  
+/// A status is `CL_SUCCESS` if the binary was loaded successfully,
+/// `CL_INVALID_BINARY` if it is not valid for its device, or `CL_INVALID_VALUE`
+/// if it is empty or was not loaded, see `binary_status_failures`.
+///
+/// # Safety
+///
+/// This is unsafe when a device is not a member of context.
+#[allow(clippy::cast_possible_truncation)]
+pub unsafe fn create_program_with_binary_status(
+    context: impl AsContext,
+    devices: &[cl_device_id],
+    binaries: &[&[u8]],
+) -> Result<(cl_program, Vec<cl_int>), (cl_int, Vec<cl_int>)> {
+    let context = context.as_raw();
+    clear_error();
+    let mut binary_status: Vec<cl_int> = vec![CL_INVALID_VALUE; devices.len()];
+    if binaries.len() != devices.len() {
+        let status = CL_INVALID_VALUE;
+        return Err((
+            record_error!(status, clCreateProgramWithBinary),
+            binary_status,
+        ));
+    }
     let lengths: Vec<size_t> = binaries.iter().map(|bin| bin.len()).collect();
-    let mut binary_status: Vec<cl_int> = Vec::with_capacity(binaries_length);
+    let binary_ptrs: Vec<*const c_uchar> = binaries.iter().map(|bin| bin.as_ptr()).collect();
     let mut status: cl_int = CL_INVALID_VALUE;
     let program: cl_program = clCreateProgramWithBinary(
         context,
         devices.len() as cl_uint,
         devices.as_ptr(),
         lengths.as_ptr(),
-        binaries.as_ptr().cast::<*const c_uchar>(),
+        binary_ptrs.as_ptr(),
         binary_status.as_mut_ptr(),
         &mut status,
     );
-    binary_status.set_len(binaries_length);
     if CL_SUCCESS == status {
-        Ok(program)
+        Ok((program, binary_status))
     } else {
-        Err(status)
+        Err((
+            record_error!(status, clCreateProgramWithBinary, binary_status),
+            binary_status,
+        ))
     }
 }
 
+/// The devices whose binaries failed to load, e.g. to rebuild their programs
+/// from source.
+///
+/// * `devices` - the devices passed to `create_program_with_binary_status`.
+/// * `binary_status` - the status of the binary for each device returned by
+///   `create_program_with_binary_status`.
+///
+/// returns the device id and status of each binary that is not `CL_SUCCESS`.
+#[must_use]
+pub fn binary_status_failures(
+    devices: &[cl_device_id],
+    binary_status: &[cl_int],
+) -> Vec<(cl_device_id, cl_int)> {
+    devices
+        .iter()
+        .zip(binary_status)
+        .filter(|(_, status)| CL_SUCCESS != **status)
+        .map(|(device, status)| (*device, *status))
+        .collect()
+}
+
 /// Create an `OpenCL` program object for a context and  loads the information
 /// related to the built-in kernels into that object.  
 /// Calls `clCreateProgramWithBuiltInKernels` to create an `OpenCL` program object.  
@@ -146,10 +210,12 @@ pub unsafe fn create_program_with_binary(
 #[allow(clippy::cast_possible_truncation)]
 #[inline]
 pub unsafe fn create_program_with_builtin_kernels(
-    context: cl_context,
+    context: impl AsContext,
     devices: &[cl_device_id],
     kernel_names: &CStr,
 ) -> Result<cl_program, cl_int> {
+    let context = context.as_raw();
+    clear_error();
     let mut status: cl_int = CL_INVALID_VALUE;
     let program: cl_program = clCreateProgramWithBuiltInKernels(
         context,
@@ -161,7 +227,7 @@ pub unsafe fn create_program_with_builtin_kernels(
     if CL_SUCCESS == status {
         Ok(program)
     } else {
-        Err(status)
+        Err(record_error!(status, clCreateProgramWithBuiltInKernels))
     }
 }
 
@@ -177,7 +243,9 @@ pub unsafe fn create_program_with_builtin_kernels(
 /// or the error code from the `OpenCL` C API function.
 #[cfg(feature = "CL_VERSION_2_1")]
 #[inline]
-pub fn create_program_with_il(context: cl_context, il: &[u8]) -> Result<cl_program, cl_int> {
+pub fn create_program_with_il(context: impl AsContext, il: &[u8]) -> Result<cl_program, cl_int> {
+    let context = context.as_raw();
+    clear_error();
     let mut status: cl_int = CL_INVALID_VALUE;
     let program: cl_program = unsafe {
         clCreateProgramWithIL(
@@ -190,7 +258,7 @@ pub fn create_program_with_il(context: cl_context, il: &[u8]) -> Result<cl_progr
     if CL_SUCCESS == status {
         Ok(program)
     } else {
-        Err(status)
+        Err(record_error!(status, clCreateProgramWithIL))
     }
 }
 
@@ -206,11 +274,12 @@ pub fn create_program_with_il(context: cl_context, il: &[u8]) -> Result<cl_progr
 /// This function is unsafe because it changes the `OpenCL` object reference count.
 #[inline]
 pub unsafe fn retain_program(program: cl_program) -> Result<(), cl_int> {
+    clear_error();
     let status: cl_int = clRetainProgram(program);
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(status, clRetainProgram))
     }
 }
 
@@ -226,11 +295,12 @@ pub unsafe fn retain_program(program: cl_program) -> Result<(), cl_int> {
 /// This function is unsafe because it changes the `OpenCL` object reference count.
 #[inline]
 pub unsafe fn release_program(program: cl_program) -> Result<(), cl_int> {
+    clear_error();
     let status: cl_int = clReleaseProgram(program);
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(status, clReleaseProgram))
     }
 }
 
@@ -248,12 +318,14 @@ pub unsafe fn release_program(program: cl_program) -> Result<(), cl_int> {
 #[allow(clippy::cast_possible_truncation)]
 #[inline]
 pub fn build_program(
-    program: cl_program,
+    program: impl AsProgram,
     devices: &[cl_device_id],
     options: &CStr,
     pfn_notify: Option<unsafe extern "C" fn(cl_program, *mut c_void)>,
     user_data: *mut c_void,
 ) -> Result<(), cl_int> {
+    let program = program.as_raw();
+    clear_error();
     let status: cl_int = unsafe {
         clBuildProgram(
             program,
@@ -267,7 +339,13 @@ pub fn build_program(
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_build(
+            status,
+            "clBuildProgram",
+            &[("options", &options)],
+            program,
+            devices,
+        ))
     }
 }
 
@@ -294,7 +372,7 @@ pub fn build_program(
 #[allow(clippy::cast_possible_truncation)]
 #[inline]
 pub fn compile_program(
-    program: cl_program,
+    program: impl AsProgram,
     devices: &[cl_device_id],
     options: &CStr,
     input_headers: &[cl_program],
@@ -302,7 +380,14 @@ pub fn compile_program(
     pfn_notify: Option<unsafe extern "C" fn(program: cl_program, user_data: *mut c_void)>,
     user_data: *mut c_void,
 ) -> Result<(), cl_int> {
+    let program = program.as_raw();
+    clear_error();
     assert!(input_headers.len() == header_include_names.len());
+    // A &CStr is not a C string pointer
+    let header_include_names: Vec<*const c_char> = header_include_names
+        .iter()
+        .map(|name| name.as_ptr())
+        .collect();
     let status: cl_int = unsafe {
         let input_headers_ptr = if input_headers.is_empty() {
             ptr::null()
@@ -321,7 +406,7 @@ pub fn compile_program(
             options.as_ptr(),
             input_headers.len() as cl_uint,
             input_headers_ptr,
-            header_include_names_ptr.cast::<*const c_char>(),
+            header_include_names_ptr,
             pfn_notify,
             user_data,
         )
@@ -329,7 +414,13 @@ pub fn compile_program(
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_build(
+            status,
+            "clCompileProgram",
+            &[("options", &options)],
+            program,
+            devices,
+        ))
     }
 }
 
@@ -358,13 +449,15 @@ pub fn compile_program(
 #[allow(clippy::cast_possible_truncation)]
 #[inline]
 pub unsafe fn link_program(
-    context: cl_context,
+    context: impl AsContext,
     devices: &[cl_device_id],
     options: &CStr,
     input_programs: &[cl_program],
     pfn_notify: Option<unsafe extern "C" fn(program: cl_program, user_data: *mut c_void)>,
     user_data: *mut c_void,
 ) -> Result<cl_program, cl_int> {
+    let context = context.as_raw();
+    clear_error();
     assert!(!input_programs.is_empty());
     let mut status: cl_int = CL_INVALID_VALUE;
     let programme: cl_program = clLinkProgram(
@@ -381,6 +474,20 @@ pub unsafe fn link_program(
     if CL_SUCCESS == status {
         Ok(programme)
     } else {
+        let status = record_build(
+            status,
+            "clLinkProgram",
+            &[("options", &options)],
+            programme,
+            devices,
+        );
+        // The failed program is only returned for its build log, which
+        // the recorded error keeps
+        if !programme.is_null() {
+            preserve_error(|| {
+                let _ = release_program(programme);
+            });
+        }
         Err(status)
     }
 }
@@ -403,17 +510,24 @@ pub unsafe fn link_program(
 #[cfg(feature = "CL_VERSION_2_2")]
 #[inline]
 pub unsafe fn set_program_specialization_constant(
-    program: cl_program,
+    program: impl AsProgram,
     spec_id: cl_uint,
     spec_size: size_t,
     spec_value: *const c_void,
 ) -> Result<(), cl_int> {
+    let program = program.as_raw();
+    clear_error();
     let status: cl_int =
         clSetProgramSpecializationConstant(program, spec_id, spec_size, spec_value);
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(
+            status,
+            clSetProgramSpecializationConstant,
+            spec_id,
+            spec_size
+        ))
     }
 }
 
@@ -430,20 +544,23 @@ pub unsafe fn set_program_specialization_constant(
 #[cfg(feature = "CL_VERSION_1_2")]
 #[inline]
 pub unsafe fn unload_platform_compiler(platform: cl_platform_id) -> Result<(), cl_int> {
+    clear_error();
     let status: cl_int = clUnloadPlatformCompiler(platform);
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(status, clUnloadPlatformCompiler))
     }
 }
 
 /// Get data about an `OpenCL` program.
 /// Calls clGetProgramInfo to get the desired data about the program.
 pub fn get_program_data(
-    program: cl_program,
+    program: impl AsProgram,
     param_name: cl_program_info,
 ) -> Result<Vec<u8>, cl_int> {
+    let program = program.as_raw();
+    clear_error();
     api_info_size!(get_size, clGetProgramInfo);
     let size = get_size(program, param_name)?;
     api_info_vector!(get_vector, u8, clGetProgramInfo);
@@ -460,9 +577,11 @@ pub fn get_program_data(
 /// returns a Result containing the desired information in an `InfoType` enum
 /// or the error code from the `OpenCL` C API function.
 pub fn get_program_info(
-    program: cl_program,
+    program: impl AsProgram,
     param_name: cl_program_info,
 ) -> Result<InfoType, cl_int> {
+    let program = program.as_raw();
+    clear_error();
     api_info_size!(get_size, clGetProgramInfo);
 
     match param_name {
@@ -522,7 +641,7 @@ pub fn get_program_info(
             if CL_SUCCESS == status {
                 Ok(InfoType::VecVecUchar(binaries))
             } else {
-                Err(status)
+                Err(record_error!(status, clGetProgramInfo, param_name))
             }
         }
 
@@ -543,10 +662,12 @@ pub fn get_program_info(
 /// Get data about an `OpenCL` program build.
 /// Calls clGetProgramBuildInfo to get the desired data about the program build.
 pub fn get_program_build_data(
-    program: cl_program,
-    device: cl_device_id,
+    program: impl AsProgram,
+    device: impl AsDevice,
     param_name: cl_program_info,
 ) -> Result<Vec<u8>, cl_int> {
+    let program = program.as_raw();
+    let device = device.as_raw();
     api2_info_size!(get_size, cl_device_id, clGetProgramBuildInfo);
     let size = get_size(program, device, param_name)?;
     api2_info_vector!(get_vector, cl_device_id, u8, clGetProgramBuildInfo);
@@ -564,10 +685,12 @@ pub fn get_program_build_data(
 /// returns a Result containing the desired information in an `InfoType` enum
 /// or the error code from the `OpenCL` C API function.
 pub fn get_program_build_info(
-    program: cl_program,
-    device: cl_device_id,
+    program: impl AsProgram,
+    device: impl AsDevice,
     param_name: cl_program_build_info,
 ) -> Result<InfoType, cl_int> {
+    let program = program.as_raw();
+    let device = device.as_raw();
     match param_name {
         CL_PROGRAM_BUILD_STATUS => {
             api2_info_value!(
@@ -617,7 +740,7 @@ mod tests {
     use super::*;
     use crate::context::{create_context, release_context};
     use crate::device::{get_device_ids, CL_DEVICE_TYPE_ALL};
-    use crate::error_codes::error_text;
+    use crate::error_codes::{error_text, CL_INVALID_BINARY};
     use crate::platform::get_platform_ids;
     use std::ffi::CString;
 
@@ -860,4 +983,56 @@ mod tests {
             release_context(context).unwrap();
         }
     }
+
+    #[test]
+    fn test_create_program_with_binary_status() {
+        let platform_ids = get_platform_ids().unwrap();
+        let device_ids = get_device_ids(platform_ids[0], CL_DEVICE_TYPE_ALL).unwrap();
+
+        // Up to two distinct devices
+        let devices = &device_ids[..device_ids.len().min(2)];
+        let last = devices.len() - 1;
+        let context = create_context(devices, ptr::null(), None, ptr::null_mut()).unwrap();
+
+        let source = "kernel void add(global int* a) { a[0] += 1; }";
+        let program = create_program_with_source(context, &[source]).unwrap();
+        let no_options = CString::new("").unwrap();
+        build_program(program, devices, &no_options, None, ptr::null_mut()).unwrap();
+        let binaries: Vec<Vec<u8>> = get_program_info(program, CL_PROGRAM_BINARIES)
+            .unwrap()
+            .into();
+        let binaries: Vec<&[u8]> = binaries.iter().map(Vec::as_slice).collect();
+        unsafe { release_program(program).unwrap() };
+
+        let (program, binary_status) =
+            unsafe { create_program_with_binary_status(context, devices, &binaries) }.unwrap();
+        assert_eq!(vec![CL_SUCCESS; devices.len()], binary_status);
+        assert!(binary_status_failures(devices, &binary_status).is_empty());
+        unsafe { release_program(program).unwrap() };
+
+        // The last device rejects its binary
+        let mut invalid_binaries = binaries.clone();
+        invalid_binaries[last] = b"not a binary".as_slice();
+        let (status, binary_status) =
+            unsafe { create_program_with_binary_status(context, devices, &invalid_binaries) }
+                .unwrap_err();
+        println!("binary_status: {:?}", binary_status);
+        assert_eq!(CL_INVALID_BINARY, status);
+        let mut expected = vec![CL_SUCCESS; devices.len()];
+        expected[last] = CL_INVALID_BINARY;
+        assert_eq!(expected, binary_status);
+        assert_eq!(
+            vec![(devices[last], CL_INVALID_BINARY)],
+            binary_status_failures(devices, &binary_status)
+        );
+
+        // There must be a binary for each device
+        let (status, binary_status) =
+            unsafe { create_program_with_binary_status(context, devices, &binaries[..last]) }
+                .unwrap_err();
+        assert_eq!(CL_INVALID_VALUE, status);
+        assert_eq!(vec![CL_INVALID_VALUE; devices.len()], binary_status);
+
+        unsafe { release_context(context).unwrap() };
+    }
 }
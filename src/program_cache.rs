@@ -0,0 +1,441 @@
+// Copyright (c) 2024 Via Technology Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! An on-disk cache of program binaries.
+//!
+//! A [`ProgramCache`] stores the binary of a built program for each device in
+//! a directory, so that later runs can create the program from the binaries
+//! instead of building it from source, e.g.:
+//!
+//! ```no_run
+//! use cl3::program_cache::ProgramCache;
+//! use cl3::types::{cl_context, cl_device_id, cl_int, cl_program};
+//! use std::ffi::CString;
+//!
+//! fn example(context: cl_context, device: cl_device_id) -> Result<cl_program, cl_int> {
+//!     let cache = ProgramCache::new("/var/cache/my_app/opencl");
+//!     let source = "kernel void add(global int* a) { a[0] += 1; }";
+//!     let options = CString::new("-cl-fast-relaxed-math").unwrap();
+//!     cache.build_program_with_source(context, &[device], &[source], &options)
+//! }
+//! ```
+//!
+//! The cache key of a binary is a hash of the program source or IL, the build
+//! options, the device name, `CL_DRIVER_VERSION` and `CL_PLATFORM_VERSION`,
+//! so a new driver or platform never loads a stale binary.
+//! On a cache miss, or if the binary is rejected, the program is built from
+//! source and the binaries are written to the cache, replacing any stale
+//! entries. The files are written atomically, by writing a temporary file in
+//! the cache directory and renaming it.
+//!
+//! The cache is best effort: errors reading or writing the cache files are
+//! ignored, the program is built from source instead.
+
+#![allow(non_camel_case_types)]
+
+use super::api_error::{discard_error, preserve_error};
+use super::device::{get_device_info, CL_DEVICE_NAME, CL_DEVICE_PLATFORM, CL_DRIVER_VERSION};
+use super::owned::{AsContext, AsDevice};
+use super::platform::{get_platform_info, CL_PLATFORM_VERSION};
+#[cfg(feature = "CL_VERSION_2_1")]
+use super::program::create_program_with_il;
+use super::program::{
+    build_program, create_program_with_binary_status, create_program_with_source, get_program_info,
+    release_program, CL_PROGRAM_BINARIES, CL_PROGRAM_DEVICES,
+};
+use opencl_sys::{cl_context, cl_device_id, cl_int, cl_platform_id, cl_program};
+use std::ffi::CStr;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// The file name extension of the cached binaries.
+const BINARY_EXTENSION: &str = "clbin";
+
+/// A 128 bit FNV-1a hash, which is stable across runs and Rust versions.
+struct Fnv128(u128);
+
+impl Fnv128 {
+    const OFFSET_BASIS: u128 = 0x6c62_272e_07bb_0142_62b8_2175_6295_c58d;
+    const PRIME: u128 = 0x0000_0000_0100_0000_0000_0000_0000_013b;
+
+    const fn new() -> Self {
+        Self(Self::OFFSET_BASIS)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u128::from(*byte);
+            self.0 = self.0.wrapping_mul(Self::PRIME);
+        }
+    }
+
+    /// Write a field prefixed by its length, so that fields cannot run together.
+    fn write_field(&mut self, bytes: &[u8]) {
+        self.write(&(bytes.len() as u64).to_le_bytes());
+        self.write(bytes);
+    }
+}
+
+/// The program to build: source code strings or an intermediate language.
+#[derive(Clone, Copy)]
+enum ProgramInput<'a> {
+    Source(&'a [&'a str]),
+    #[cfg(feature = "CL_VERSION_2_1")]
+    Il(&'a [u8]),
+}
+
+impl ProgramInput<'_> {
+    fn hash(&self, hasher: &mut Fnv128) {
+        match self {
+            ProgramInput::Source(sources) => {
+                hasher.write_field(b"source");
+                hasher.write(&(sources.len() as u64).to_le_bytes());
+                for source in *sources {
+                    hasher.write_field(source.as_bytes());
+                }
+            }
+            #[cfg(feature = "CL_VERSION_2_1")]
+            ProgramInput::Il(il) => {
+                hasher.write_field(b"il");
+                hasher.write_field(il);
+            }
+        }
+    }
+
+    fn create_program(&self, context: cl_context) -> Result<cl_program, cl_int> {
+        match self {
+            ProgramInput::Source(sources) => create_program_with_source(context, sources),
+            #[cfg(feature = "CL_VERSION_2_1")]
+            ProgramInput::Il(il) => create_program_with_il(context, il),
+        }
+    }
+}
+
+/// An on-disk cache of program binaries, see the [module](self) documentation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProgramCache {
+    directory: PathBuf,
+}
+
+impl ProgramCache {
+    /// Create a program cache that stores binaries in `directory`.
+    /// The directory is created when the first binary is stored.
+    #[must_use]
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+        }
+    }
+
+    /// The directory of the cached binaries.
+    #[must_use]
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// The path of the cached binary of a program for a device.
+    ///
+    /// * `device` - the device of the binary.
+    /// * `sources` - the source code strings of the program.
+    /// * `options` - the build options in a null-terminated string.
+    ///
+    /// returns a Result containing the path of the binary
+    /// or the error code from the `OpenCL` C API function.
+    pub fn binary_path(
+        &self,
+        device: impl AsDevice,
+        sources: &[&str],
+        options: &CStr,
+    ) -> Result<PathBuf, cl_int> {
+        self.path(device.as_raw(), ProgramInput::Source(sources), options)
+    }
+
+    fn path(
+        &self,
+        device: cl_device_id,
+        input: ProgramInput,
+        options: &CStr,
+    ) -> Result<PathBuf, cl_int> {
+        let key = cache_key(device, input, options)?;
+        Ok(self
+            .directory
+            .join(format!("{key:032x}.{BINARY_EXTENSION}")))
+    }
+
+    /// Create and build a program from source code, using the cached binaries
+    /// if they are present and accepted by the devices.
+    /// Calls `create_program_with_binary_status` and `build_program` or,
+    /// if that fails, `create_program_with_source` and `build_program`
+    /// then stores the program binaries.
+    ///
+    /// * `context` - a valid `OpenCL` context.
+    /// * `devices` - a slice of devices that are in context.
+    /// * `sources` - a slice of source code strings.
+    /// * `options` - the build options in a null-terminated string.
+    ///
+    /// returns a Result containing the built `OpenCL` program object
+    /// or the error code from the `OpenCL` C API function.
+    pub fn build_program_with_source(
+        &self,
+        context: impl AsContext,
+        devices: &[cl_device_id],
+        sources: &[&str],
+        options: &CStr,
+    ) -> Result<cl_program, cl_int> {
+        self.build(
+            context.as_raw(),
+            devices,
+            ProgramInput::Source(sources),
+            options,
+        )
+    }
+
+    /// Create and build a program from an intermediate language (e.g. SPIR-V),
+    /// using the cached binaries if they are present and accepted by the devices,
+    /// see `build_program_with_source`.
+    /// `CL_VERSION_2_1`
+    ///
+    /// * `context` - a valid `OpenCL` context.
+    /// * `devices` - a slice of devices that are in context.
+    /// * `il` - a slice of bytes containing the intermediate language.
+    /// * `options` - the build options in a null-terminated string.
+    ///
+    /// returns a Result containing the built `OpenCL` program object
+    /// or the error code from the `OpenCL` C API function.
+    #[cfg(feature = "CL_VERSION_2_1")]
+    pub fn build_program_with_il(
+        &self,
+        context: impl AsContext,
+        devices: &[cl_device_id],
+        il: &[u8],
+        options: &CStr,
+    ) -> Result<cl_program, cl_int> {
+        self.build(context.as_raw(), devices, ProgramInput::Il(il), options)
+    }
+
+    fn build(
+        &self,
+        context: cl_context,
+        devices: &[cl_device_id],
+        input: ProgramInput,
+        options: &CStr,
+    ) -> Result<cl_program, cl_int> {
+        let paths = devices
+            .iter()
+            .map(|device| self.path(*device, input, options))
+            .collect::<Result<Vec<_>, cl_int>>()?;
+
+        if let Some(program) = load_program(context, devices, &paths, options) {
+            return Ok(program);
+        }
+
+        let program = input.create_program(context)?;
+        if let Err(error) = build_program(program, devices, options, None, std::ptr::null_mut()) {
+            // Return the build error with its recorded build log
+            preserve_error(|| {
+                let _ = unsafe { release_program(program) };
+            });
+            return Err(error);
+        }
+        store_binaries(program, devices, &paths);
+        Ok(program)
+    }
+}
+
+/// The cache key of a program for a device.
+fn cache_key(device: cl_device_id, input: ProgramInput, options: &CStr) -> Result<u128, cl_int> {
+    let name = String::from(get_device_info(device, CL_DEVICE_NAME)?);
+    let driver_version = String::from(get_device_info(device, CL_DRIVER_VERSION)?);
+    let platform = get_device_info(device, CL_DEVICE_PLATFORM)?.to_ptr() as cl_platform_id;
+    let platform_version = String::from(get_platform_info(platform, CL_PLATFORM_VERSION)?);
+
+    let mut hasher = Fnv128::new();
+    input.hash(&mut hasher);
+    hasher.write_field(options.to_bytes());
+    hasher.write_field(name.as_bytes());
+    hasher.write_field(driver_version.as_bytes());
+    hasher.write_field(platform_version.as_bytes());
+    Ok(hasher.0)
+}
+
+/// Create and build a program from the cached binaries of the devices.
+///
+/// returns the program or None if a binary is missing or rejected.
+fn load_program(
+    context: cl_context,
+    devices: &[cl_device_id],
+    paths: &[PathBuf],
+    options: &CStr,
+) -> Option<cl_program> {
+    let binaries = paths
+        .iter()
+        .map(|path| fs::read(path).ok().filter(|binary| !binary.is_empty()))
+        .collect::<Option<Vec<_>>>()?;
+    let binaries: Vec<&[u8]> = binaries.iter().map(Vec::as_slice).collect();
+
+    let (program, _) =
+        discard_error(unsafe { create_program_with_binary_status(context, devices, &binaries) })?;
+    if discard_error(build_program(
+        program,
+        devices,
+        options,
+        None,
+        std::ptr::null_mut(),
+    ))
+    .is_some()
+    {
+        Some(program)
+    } else {
+        let _ = unsafe { release_program(program) };
+        None
+    }
+}
+
+/// Store the binaries of a built program for the devices.
+fn store_binaries(program: cl_program, devices: &[cl_device_id], paths: &[PathBuf]) {
+    let (Some(program_devices), Some(binaries)) = (
+        discard_error(get_program_info(program, CL_PROGRAM_DEVICES)),
+        discard_error(get_program_info(program, CL_PROGRAM_BINARIES)),
+    ) else {
+        return;
+    };
+    let program_devices = Vec::<isize>::from(program_devices);
+    let binaries = Vec::<Vec<u8>>::from(binaries);
+
+    for (device, path) in devices.iter().zip(paths) {
+        let binary = program_devices
+            .iter()
+            .position(|program_device| *program_device == *device as isize)
+            .and_then(|index| binaries.get(index))
+            .filter(|binary| !binary.is_empty());
+        if let Some(binary) = binary {
+            let _ = write_atomically(path, binary);
+        }
+    }
+}
+
+/// Write a file by writing a temporary file in the same directory and
+/// renaming it, so that readers never see a partly written file.
+pub(crate) fn write_atomically(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    static NEXT_TEMPORARY: AtomicUsize = AtomicUsize::new(0);
+
+    if let Some(directory) = path.parent() {
+        fs::create_dir_all(directory)?;
+    }
+    let extension = path.extension().unwrap_or_default().to_string_lossy();
+    let temporary = path.with_extension(format!(
+        "{extension}.{}.{}.tmp",
+        process::id(),
+        NEXT_TEMPORARY.fetch_add(1, Ordering::Relaxed)
+    ));
+    let result = fs::File::create(&temporary)
+        .and_then(|mut file| {
+            file.write_all(contents)?;
+            file.sync_all()
+        })
+        .and_then(|()| fs::rename(&temporary, path));
+    if result.is_err() {
+        let _ = fs::remove_file(&temporary);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_error::ApiError;
+    use crate::context::{create_context, release_context};
+    use crate::device::{get_device_ids, CL_DEVICE_TYPE_GPU};
+    use crate::platform::get_platform_ids;
+    use crate::program::{CL_PROGRAM_KERNEL_NAMES, CL_PROGRAM_SOURCE};
+    use opencl_sys::CL_BUILD_PROGRAM_FAILURE;
+    use std::ffi::CString;
+    use std::ptr;
+
+    #[test]
+    fn test_program_cache() {
+        let platform_ids = get_platform_ids().unwrap();
+        let device_ids = get_device_ids(platform_ids[0], CL_DEVICE_TYPE_GPU).unwrap();
+        let device_id = device_ids[0];
+
+        let context = create_context(&device_ids, ptr::null(), None, ptr::null_mut()).unwrap();
+
+        let directory = std::env::temp_dir().join(format!("cl3_program_cache_{}", process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        let cache = ProgramCache::new(&directory);
+        assert_eq!(directory, cache.directory());
+
+        let source = "kernel void add(global int* a) { a[0] += 1; }";
+        let options = CString::new("-DVALUE=1").unwrap();
+        let path = cache.binary_path(device_id, &[source], &options).unwrap();
+        println!("binary path: {:?}", path);
+        assert!(path.starts_with(&directory));
+
+        // The key depends on the source and the build options
+        let other_options = CString::new("-DVALUE=2").unwrap();
+        assert_ne!(
+            path,
+            cache
+                .binary_path(device_id, &[source], &other_options)
+                .unwrap()
+        );
+        assert_ne!(path, cache.binary_path(device_id, &[""], &options).unwrap());
+
+        // A cache miss builds the program from source and stores the binary
+        let program = cache
+            .build_program_with_source(context, &[device_id], &[source], &options)
+            .unwrap();
+        let value = String::from(get_program_info(program, CL_PROGRAM_SOURCE).unwrap());
+        assert_eq!(source, value.trim_end_matches('\0'));
+        unsafe { release_program(program).unwrap() };
+        let binary = fs::read(&path).unwrap();
+        assert!(!binary.is_empty());
+
+        // A cache hit creates the program from the binary
+        let program = cache
+            .build_program_with_source(context, &[device_id], &[source], &options)
+            .unwrap();
+        let value = String::from(get_program_info(program, CL_PROGRAM_SOURCE).unwrap());
+        assert!(value.trim_end_matches('\0').is_empty());
+        let value = String::from(get_program_info(program, CL_PROGRAM_KERNEL_NAMES).unwrap());
+        assert_eq!("add", value.trim_end_matches('\0'));
+        unsafe { release_program(program).unwrap() };
+
+        // A rejected binary is replaced
+        fs::write(&path, b"not a binary").unwrap();
+        let program = cache
+            .build_program_with_source(context, &[device_id], &[source], &options)
+            .unwrap();
+        unsafe { release_program(program).unwrap() };
+        assert_eq!(binary, fs::read(&path).unwrap());
+
+        // A failed build returns the build error with its build log
+        let error = cache
+            .build_program_with_source(context, &[device_id], &["kernel void"], &options)
+            .unwrap_err();
+        assert_eq!(CL_BUILD_PROGRAM_FAILURE, error);
+        let error = ApiError::from(error);
+        assert_eq!(Some("clBuildProgram"), error.function());
+        assert!(!error.build_logs().is_empty());
+
+        // No temporary files are left in the directory
+        assert_eq!(1, fs::read_dir(&directory).unwrap().count());
+
+        fs::remove_dir_all(&directory).unwrap();
+        unsafe { release_context(context).unwrap() };
+    }
+}
@@ -0,0 +1,583 @@
+// Copyright (c) 2024 Via Technology Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Separate compilation and linking of multi-module programs.
+//!
+//! A [`ProgramLinker`] holds a set of named modules: `OpenCL` C source, IL or
+//! the binaries of compiled objects or libraries. Each source or IL module is
+//! compiled with its own options, then the modules are linked into a library
+//! or an executable, which may also be linked against other libraries, e.g.:
+//!
+//! ```no_run
+//! use cl3::program::{get_program_info, CL_PROGRAM_BINARIES};
+//! use cl3::program_linker::ProgramLinker;
+//! use cl3::types::{cl_context, cl_device_id, cl_int, cl_program};
+//! use std::ffi::CString;
+//!
+//! fn example(context: cl_context, device: cl_device_id) -> Result<cl_program, cl_int> {
+//!     let no_options = CString::default();
+//!
+//!     // Build a library, e.g. to ship its binary
+//!     let mut math = ProgramLinker::new();
+//!     math.add_source("square", "float square(float x) { return x * x; }", &no_options)
+//!         .add_source("cube", "float cube(float x) { return x * x * x; }", &no_options);
+//!     let library = math.link_library(context, &[device], &no_options)?;
+//!     let binaries = Vec::<Vec<u8>>::from(get_program_info(library, CL_PROGRAM_BINARIES)?);
+//!
+//!     // Link a kernel against the library binary
+//!     let source = "float square(float x);
+//!         kernel void squares(global float* a) { a[get_global_id(0)] = square(a[get_global_id(0)]); }";
+//!     let options = CString::new("-cl-mad-enable").unwrap();
+//!     let mut linker = ProgramLinker::new();
+//!     linker
+//!         .add_binary("math", &[&binaries[0]])
+//!         .add_source("squares", source, &options);
+//!     match linker.link_executable(context, &[device], &no_options, &[]) {
+//!         Ok(program) => Ok(program),
+//!         Err(error) => {
+//!             // e.g. "compile stage of module 'squares' failed: CL_COMPILE_PROGRAM_FAILURE"
+//!             eprintln!("{error}");
+//!             Err(error.code)
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! A [`LinkError`] reports the stage and the module that failed, with the build
+//! logs of the devices.
+//! The per device `CL_PROGRAM_BINARY_TYPE` of each stage is checked, so that
+//! e.g. an executable binary is rejected as an input module.
+
+#![allow(non_camel_case_types)]
+
+use super::api_error::{build_logs, record_error, ApiError, BuildLog};
+use super::error_codes::error_text;
+use super::owned::{AsContext, Program};
+#[cfg(feature = "CL_VERSION_2_1")]
+use super::program::create_program_with_il;
+use super::program::{
+    compile_program, create_program_with_binary_status, create_program_with_source,
+    get_program_build_info, link_program, CL_PROGRAM_BINARY_TYPE,
+    CL_PROGRAM_BINARY_TYPE_COMPILED_OBJECT, CL_PROGRAM_BINARY_TYPE_EXECUTABLE,
+    CL_PROGRAM_BINARY_TYPE_LIBRARY,
+};
+use opencl_sys::{
+    cl_context, cl_device_id, cl_int, cl_program, cl_program_binary_type, cl_uint,
+    CL_INVALID_BINARY, CL_INVALID_VALUE,
+};
+use std::error::Error;
+use std::ffi::{CStr, CString};
+use std::fmt;
+use std::ptr;
+
+/// The input of a module.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ModuleInput {
+    /// `OpenCL` C source, compiled with `compile_program`.
+    Source(String),
+    /// Intermediate language, e.g. SPIR-V, compiled with `compile_program`.
+    #[cfg(feature = "CL_VERSION_2_1")]
+    Il(Vec<u8>),
+    /// The binaries of a compiled object or library, one for each device.
+    Binary(Vec<Vec<u8>>),
+}
+
+/// A named module of a [`ProgramLinker`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Module {
+    /// The name of the module, used in errors.
+    pub name: String,
+    /// The input of the module.
+    pub input: ModuleInput,
+    /// The compile options of a source or IL module.
+    pub options: CString,
+}
+
+/// The stage of a [`ProgramLinker`] that failed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LinkStage {
+    /// Creating the program object of a module.
+    Create,
+    /// Compiling a source or IL module.
+    Compile,
+    /// Linking the modules and libraries.
+    Link,
+}
+
+impl fmt::Display for LinkStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Create => "create",
+            Self::Compile => "compile",
+            Self::Link => "link",
+        })
+    }
+}
+
+/// A failed [`ProgramLinker`] stage.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LinkError {
+    /// The `OpenCL` error code.
+    pub code: cl_int,
+    /// The stage that failed.
+    pub stage: LinkStage,
+    /// The name of the module that failed, `None` for the link stage.
+    pub module: Option<String>,
+    /// The build logs of the devices, if any.
+    pub build_logs: Vec<BuildLog>,
+}
+
+impl LinkError {
+    fn new(code: cl_int, stage: LinkStage, module: Option<&str>) -> Self {
+        Self {
+            code,
+            stage,
+            module: module.map(str::to_owned),
+            build_logs: Vec::new(),
+        }
+    }
+
+    /// An error with the build logs of the program that failed.
+    fn with_build_logs(
+        code: cl_int,
+        stage: LinkStage,
+        module: Option<&str>,
+        program: cl_program,
+        devices: &[cl_device_id],
+    ) -> Self {
+        Self {
+            build_logs: build_logs(program, devices),
+            ..Self::new(code, stage, module)
+        }
+    }
+
+    /// A failed `link_program`, with the build logs that it recorded, since
+    /// it releases the failed program.
+    fn link_failed(code: cl_int) -> Self {
+        let error = ApiError::from(code);
+        Self {
+            build_logs: if Some("clLinkProgram") == error.function() {
+                error.build_logs().to_vec()
+            } else {
+                Vec::new()
+            },
+            ..Self::new(code, LinkStage::Link, None)
+        }
+    }
+}
+
+impl fmt::Display for LinkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} stage", self.stage)?;
+        if let Some(module) = &self.module {
+            write!(f, " of module '{module}'")?;
+        }
+        write!(f, " failed: {}", error_text(self.code))?;
+        for build_log in &self.build_logs {
+            write!(
+                f,
+                "\nbuild log for device {:?}:\n{}",
+                build_log.device, build_log.log
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for LinkError {}
+
+impl From<LinkError> for cl_int {
+    fn from(error: LinkError) -> Self {
+        error.code
+    }
+}
+
+/// Get the `CL_PROGRAM_BINARY_TYPE` of a program for each device.
+///
+/// * `program` - the `OpenCL` program.
+/// * `devices` - the devices of the program.
+///
+/// returns a Result containing the binary types
+/// or the error code from the `OpenCL` C API function.
+pub fn binary_types(
+    program: cl_program,
+    devices: &[cl_device_id],
+) -> Result<Vec<cl_program_binary_type>, cl_int> {
+    devices
+        .iter()
+        .map(|device| {
+            get_program_build_info(program, *device, CL_PROGRAM_BINARY_TYPE).map(cl_uint::from)
+        })
+        .collect()
+}
+
+/// Check that a program has one of the expected binary types on every device.
+fn check_binary_types(
+    program: cl_program,
+    devices: &[cl_device_id],
+    expected: &[cl_program_binary_type],
+) -> Result<(), cl_int> {
+    let binary_types = binary_types(program, devices)?;
+    if binary_types
+        .iter()
+        .all(|binary_type| expected.contains(binary_type))
+    {
+        Ok(())
+    } else {
+        Err(record_error!(
+            CL_INVALID_BINARY,
+            check_binary_types,
+            binary_types,
+            expected
+        ))
+    }
+}
+
+/// A set of modules that are compiled separately and linked into a library
+/// or an executable program.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ProgramLinker {
+    modules: Vec<Module>,
+}
+
+impl ProgramLinker {
+    /// Create a linker without any modules.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an `OpenCL` C source module.
+    ///
+    /// * `name` - the name of the module.
+    /// * `source` - the `OpenCL` C source.
+    /// * `options` - the compile options of the module.
+    pub fn add_source(&mut self, name: &str, source: &str, options: &CStr) -> &mut Self {
+        self.add_module(name, ModuleInput::Source(source.to_owned()), options)
+    }
+
+    /// Add an intermediate language module, e.g. SPIR-V.
+    ///
+    /// * `name` - the name of the module.
+    /// * `il` - the intermediate language.
+    /// * `options` - the compile options of the module.
+    #[cfg(feature = "CL_VERSION_2_1")]
+    pub fn add_il(&mut self, name: &str, il: &[u8], options: &CStr) -> &mut Self {
+        self.add_module(name, ModuleInput::Il(il.to_vec()), options)
+    }
+
+    /// Add a compiled object or library module from its binaries.
+    ///
+    /// * `name` - the name of the module.
+    /// * `binaries` - a binary for each of the devices that the modules are
+    ///   linked for, in the same order as the devices.
+    pub fn add_binary(&mut self, name: &str, binaries: &[&[u8]]) -> &mut Self {
+        let binaries = binaries.iter().map(|binary| binary.to_vec()).collect();
+        self.add_module(name, ModuleInput::Binary(binaries), &CString::default())
+    }
+
+    fn add_module(&mut self, name: &str, input: ModuleInput, options: &CStr) -> &mut Self {
+        self.modules.push(Module {
+            name: name.to_owned(),
+            input,
+            options: options.to_owned(),
+        });
+        self
+    }
+
+    /// The modules, in the order that they were added.
+    #[must_use]
+    pub fn modules(&self) -> &[Module] {
+        &self.modules
+    }
+
+    /// Create and compile the program object of each module.
+    ///
+    /// * `context` - a valid `OpenCL` context.
+    /// * `devices` - the devices to compile the modules for.
+    ///
+    /// returns a Result containing the compiled object or library programs
+    /// of the modules, or the error of the module that failed.
+    pub fn compile(
+        &self,
+        context: impl AsContext,
+        devices: &[cl_device_id],
+    ) -> Result<Vec<Program>, LinkError> {
+        let context = context.as_raw();
+        self.modules
+            .iter()
+            .map(|module| compile_module(context, devices, module))
+            .collect()
+    }
+
+    /// Compile the modules and link them into a library program,
+    /// i.e. with the `-create-library` link option.
+    ///
+    /// * `context` - a valid `OpenCL` context.
+    /// * `devices` - the devices to build the library for.
+    /// * `options` - the link options.
+    ///
+    /// returns a Result containing the library program
+    /// or the error of the stage that failed.
+    pub fn link_library(
+        &self,
+        context: impl AsContext,
+        devices: &[cl_device_id],
+        options: &CStr,
+    ) -> Result<cl_program, LinkError> {
+        let mut library_options = b"-create-library ".to_vec();
+        library_options.extend_from_slice(options.to_bytes());
+        let library_options = CString::new(library_options)
+            .map_err(|_| LinkError::new(CL_INVALID_VALUE, LinkStage::Link, None))?;
+        self.link(
+            context.as_raw(),
+            devices,
+            &library_options,
+            &[],
+            CL_PROGRAM_BINARY_TYPE_LIBRARY,
+        )
+    }
+
+    /// Compile the modules and link them and the libraries into an executable program.
+    ///
+    /// * `context` - a valid `OpenCL` context.
+    /// * `devices` - the devices to build the program for.
+    /// * `options` - the link options.
+    /// * `libraries` - library programs to link with the modules, e.g. from `link_library`.
+    ///
+    /// returns a Result containing the executable program
+    /// or the error of the stage that failed.
+    pub fn link_executable(
+        &self,
+        context: impl AsContext,
+        devices: &[cl_device_id],
+        options: &CStr,
+        libraries: &[cl_program],
+    ) -> Result<cl_program, LinkError> {
+        self.link(
+            context.as_raw(),
+            devices,
+            options,
+            libraries,
+            CL_PROGRAM_BINARY_TYPE_EXECUTABLE,
+        )
+    }
+
+    fn link(
+        &self,
+        context: cl_context,
+        devices: &[cl_device_id],
+        options: &CStr,
+        libraries: &[cl_program],
+        binary_type: cl_program_binary_type,
+    ) -> Result<cl_program, LinkError> {
+        let objects = self.compile(context, devices)?;
+        let mut inputs: Vec<cl_program> = Program::as_raw_slice(&objects).to_vec();
+        inputs.extend_from_slice(libraries);
+        if inputs.is_empty() {
+            return Err(LinkError::new(CL_INVALID_VALUE, LinkStage::Link, None));
+        }
+        for library in libraries {
+            check_binary_types(*library, devices, &[CL_PROGRAM_BINARY_TYPE_LIBRARY])
+                .map_err(|code| LinkError::new(code, LinkStage::Link, None))?;
+        }
+
+        let program =
+            unsafe { link_program(context, devices, options, &inputs, None, ptr::null_mut()) }
+                .map_err(LinkError::link_failed)?;
+        let program = unsafe { Program::from_raw(program) };
+        check_binary_types(program.as_raw(), devices, &[binary_type]).map_err(|code| {
+            LinkError::with_build_logs(code, LinkStage::Link, None, program.as_raw(), devices)
+        })?;
+        Ok(program.into_raw())
+    }
+}
+
+/// Create the program object of a module and compile it if required.
+fn compile_module(
+    context: cl_context,
+    devices: &[cl_device_id],
+    module: &Module,
+) -> Result<Program, LinkError> {
+    let name = Some(module.name.as_str());
+    let program = match &module.input {
+        ModuleInput::Source(source) => create_program_with_source(context, &[source]),
+        #[cfg(feature = "CL_VERSION_2_1")]
+        ModuleInput::Il(il) => create_program_with_il(context, il),
+        ModuleInput::Binary(binaries) => {
+            let binaries: Vec<&[u8]> = binaries.iter().map(Vec::as_slice).collect();
+            unsafe { create_program_with_binary_status(context, devices, &binaries) }
+                .map(|(program, _)| program)
+                .map_err(|(code, _)| code)
+        }
+    }
+    .map_err(|code| LinkError::new(code, LinkStage::Create, name))?;
+    let program = unsafe { Program::from_raw(program) };
+
+    let (stage, expected): (LinkStage, &[cl_program_binary_type]) = if let ModuleInput::Binary(_) =
+        module.input
+    {
+        (
+            LinkStage::Create,
+            &[
+                CL_PROGRAM_BINARY_TYPE_COMPILED_OBJECT,
+                CL_PROGRAM_BINARY_TYPE_LIBRARY,
+            ],
+        )
+    } else {
+        compile_program(
+            &program,
+            devices,
+            &module.options,
+            &[],
+            &[],
+            None,
+            ptr::null_mut(),
+        )
+        .map_err(|code| {
+            LinkError::with_build_logs(code, LinkStage::Compile, name, program.as_raw(), devices)
+        })?;
+        (
+            LinkStage::Compile,
+            &[CL_PROGRAM_BINARY_TYPE_COMPILED_OBJECT],
+        )
+    };
+    check_binary_types(program.as_raw(), devices, expected)
+        .map_err(|code| LinkError::with_build_logs(code, stage, name, program.as_raw(), devices))?;
+    Ok(program)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::{create_context, release_context};
+    use crate::device::{get_device_ids, CL_DEVICE_TYPE_GPU};
+    use crate::platform::get_platform_ids;
+    use crate::program::{
+        get_program_info, release_program, CL_PROGRAM_BINARIES, CL_PROGRAM_KERNEL_NAMES,
+    };
+    use opencl_sys::{CL_COMPILE_PROGRAM_FAILURE, CL_LINK_PROGRAM_FAILURE};
+
+    #[test]
+    fn test_program_linker() {
+        let platform_ids = get_platform_ids().unwrap();
+        let device_ids = get_device_ids(platform_ids[0], CL_DEVICE_TYPE_GPU).unwrap();
+        let device_id = device_ids[0];
+
+        let context = create_context(&device_ids, ptr::null(), None, ptr::null_mut()).unwrap();
+        let no_options = CString::default();
+
+        // Build a library and get its binary
+        let mut math = ProgramLinker::new();
+        math.add_source(
+            "square",
+            "float square(float x) { return x * x; }
+            kernel void squares(global float* a) { a[get_global_id(0)] = square(a[get_global_id(0)]); }",
+            &CString::new("-D SCALE=2").unwrap(),
+        )
+        .add_source(
+            "cube",
+            "kernel void cubes(global float* a) { float x = a[get_global_id(0)]; a[get_global_id(0)] = x * x * x; }",
+            &no_options,
+        );
+        assert_eq!(2, math.modules().len());
+        let library = math
+            .link_library(context, &device_ids, &no_options)
+            .unwrap();
+        assert_eq!(
+            vec![CL_PROGRAM_BINARY_TYPE_LIBRARY],
+            binary_types(library, &[device_id]).unwrap()
+        );
+        let binaries =
+            Vec::<Vec<u8>>::from(get_program_info(library, CL_PROGRAM_BINARIES).unwrap());
+
+        // Link an executable against the library binary
+        let mut linker = ProgramLinker::new();
+        linker.add_binary("math", &[&binaries[0]]).add_source(
+            "scale",
+            "kernel void scale(global float* a) { a[get_global_id(0)] *= 2.0f; }",
+            &CString::new("-cl-mad-enable").unwrap(),
+        );
+        let program = linker
+            .link_executable(context, &device_ids, &no_options, &[])
+            .unwrap();
+        let value = String::from(get_program_info(program, CL_PROGRAM_KERNEL_NAMES).unwrap());
+        println!("CL_PROGRAM_KERNEL_NAMES: {}", value);
+        assert!(value.contains("cubes"));
+        assert!(value.contains("scale"));
+        unsafe { release_program(program).unwrap() };
+
+        // Link an executable with the library program
+        let mut linker = ProgramLinker::new();
+        linker.add_source(
+            "scale",
+            "kernel void scale(global float* a) { a[get_global_id(0)] *= 2.0f; }",
+            &no_options,
+        );
+        let program = linker
+            .link_executable(context, &device_ids, &no_options, &[library])
+            .unwrap();
+        unsafe { release_program(program).unwrap() };
+
+        // An executable is not a library
+        let executable = ProgramLinker::new()
+            .add_source(
+                "scale",
+                "kernel void scale(global float* a) {}",
+                &no_options,
+            )
+            .link_executable(context, &device_ids, &no_options, &[])
+            .unwrap();
+        let error = linker
+            .link_executable(context, &device_ids, &no_options, &[executable])
+            .unwrap_err();
+        assert_eq!(CL_INVALID_BINARY, error.code);
+        assert_eq!(LinkStage::Link, error.stage);
+        assert!(error.build_logs.is_empty());
+        unsafe { release_program(executable).unwrap() };
+
+        // A compile error reports the module
+        let error = ProgramLinker::new()
+            .add_source("good", "kernel void good(global float* a) {}", &no_options)
+            .add_source(
+                "bad",
+                "kernel void bad(global float* a) { a[0] = 1.0f;",
+                &no_options,
+            )
+            .link_library(context, &device_ids, &no_options)
+            .unwrap_err();
+        println!("{}", error);
+        assert_eq!(CL_COMPILE_PROGRAM_FAILURE, error.code);
+        assert_eq!(LinkStage::Compile, error.stage);
+        assert_eq!(Some("bad"), error.module.as_deref());
+        assert!(!error.build_logs.is_empty());
+
+        // A link error, e.g. duplicate kernels
+        let error = ProgramLinker::new()
+            .add_source("first", "kernel void f(global float* a) {}", &no_options)
+            .add_source("second", "kernel void f(global float* a) {}", &no_options)
+            .link_executable(context, &device_ids, &no_options, &[])
+            .unwrap_err();
+        println!("{}", error);
+        assert_eq!(CL_LINK_PROGRAM_FAILURE, error.code);
+        assert_eq!(LinkStage::Link, error.stage);
+        assert_eq!(None, error.module);
+        assert!(!error.build_logs.is_empty());
+
+        unsafe {
+            release_program(library).unwrap();
+            release_context(context).unwrap();
+        }
+    }
+}
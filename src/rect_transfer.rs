@@ -0,0 +1,355 @@
+// Copyright (c) 2024 Via Technology Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Rectangular transfers between buffers and host arrays.
+//!
+//! `enqueue_read_buffer_rect`, `enqueue_write_buffer_rect` and
+//! `enqueue_copy_buffer_rect` each take an origin and row and slice pitches
+//! for the source and destination and the region to transfer, in bytes.
+//! A [`RectTransfer`] describes the same transfer in elements: its extent and
+//! the [`Region3D`] of the sub-volume in the source and destination arrays.
+//! Zero pitches are replaced by the pitches of a packed sub-volume, and the
+//! footprint of each sub-volume is checked against the length of its array
+//! before the [`Buffer`](crate::buffer::Buffer) methods call the adapter
+//! functions, e.g.:
+//!
+//! ```no_run
+//! use cl3::buffer::Buffer;
+//! use cl3::rect_transfer::{RectTransfer, Region3D};
+//! use cl3::types::{cl_command_queue, cl_int, cl_uchar4};
+//!
+//! // Read a 64 by 32 pixel tile at (128, 256) from a 1920 by 1080 pixel frame
+//! fn read_tile(queue: cl_command_queue, frame: &Buffer<cl_uchar4>) -> Result<Vec<cl_uchar4>, cl_int> {
+//!     let transfer = RectTransfer::new([64, 32, 1]).src(Region3D::in_2d(128, 256, 1920));
+//!     let mut tile = vec![cl_uchar4::default(); 64 * 32];
+//!     frame.read_rect(queue, &transfer, &mut tile)?;
+//!     Ok(tile)
+//! }
+//! ```
+
+use super::api_error::record_error;
+use libc::size_t;
+use opencl_sys::{cl_int, CL_INVALID_VALUE};
+use std::mem;
+
+/// The position of a sub-volume in a 3D array that is stored row by row and
+/// slice by slice.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Region3D {
+    /// The (x, y, z) of the first element of the sub-volume: x in elements,
+    /// y in rows and z in slices.
+    pub origin: [usize; 3],
+    /// The number of elements per row of the array, zero for the width of the
+    /// sub-volume.
+    pub row_pitch: usize,
+    /// The number of elements per slice of the array, zero for the height of
+    /// the sub-volume times the row pitch.
+    pub slice_pitch: usize,
+}
+
+impl Region3D {
+    #[must_use]
+    pub const fn new(origin: [usize; 3], row_pitch: usize, slice_pitch: usize) -> Self {
+        Self {
+            origin,
+            row_pitch,
+            slice_pitch,
+        }
+    }
+
+    /// An array that holds just the packed sub-volume.
+    #[must_use]
+    pub const fn packed() -> Self {
+        Self::new([0; 3], 0, 0)
+    }
+
+    /// The sub-volume at (x, y) of a 2D array with `width` elements per row.
+    #[must_use]
+    pub const fn in_2d(x: usize, y: usize, width: usize) -> Self {
+        Self::new([x, y, 0], width, 0)
+    }
+
+    /// The sub-volume at (x, y, z) of a 3D array with `width` elements per
+    /// row and `height` rows per slice.
+    #[must_use]
+    pub const fn in_3d(x: usize, y: usize, z: usize, width: usize, height: usize) -> Self {
+        Self::new([x, y, z], width, width.saturating_mul(height))
+    }
+
+    /// The row and slice pitches for a sub-volume of `extent`, replacing
+    /// zero pitches with the pitches of the packed sub-volume.
+    ///
+    /// returns a Result containing the pitches, or `CL_INVALID_VALUE` if the
+    /// row pitch is less than the width, or the slice pitch is less than the
+    /// height times the row pitch or not a multiple of the row pitch.
+    pub fn pitches(&self, extent: [usize; 3]) -> Result<(usize, usize), cl_int> {
+        let row_pitch = if 0 == self.row_pitch {
+            extent[0]
+        } else {
+            self.row_pitch
+        };
+        let min_slice_pitch = extent[1]
+            .checked_mul(row_pitch)
+            .ok_or_else(|| record_error!(CL_INVALID_VALUE, Region3D::pitches, extent, row_pitch))?;
+        let slice_pitch = if 0 == self.slice_pitch {
+            min_slice_pitch
+        } else {
+            self.slice_pitch
+        };
+        if row_pitch < extent[0]
+            || slice_pitch < min_slice_pitch
+            || 0 == row_pitch
+            || 0 != slice_pitch % row_pitch
+        {
+            return Err(record_error!(
+                CL_INVALID_VALUE,
+                Region3D::pitches,
+                extent,
+                row_pitch,
+                slice_pitch
+            ));
+        }
+        Ok((row_pitch, slice_pitch))
+    }
+
+    /// The number of elements from the start of the array to the end of the
+    /// sub-volume of `extent`, i.e. the minimum length of the array.
+    ///
+    /// returns a Result containing the number of elements, or `CL_INVALID_VALUE`
+    /// if the pitches are invalid, the extent is empty or the footprint overflows.
+    pub fn footprint(&self, extent: [usize; 3]) -> Result<usize, cl_int> {
+        if extent.contains(&0) {
+            return Err(record_error!(CL_INVALID_VALUE, Region3D::footprint, extent));
+        }
+        let (row_pitch, slice_pitch) = self.pitches(extent)?;
+        let [x, y, z] = self.origin;
+        let last_row = y.checked_add(extent[1] - 1);
+        let last_slice = z.checked_add(extent[2] - 1);
+        last_slice
+            .and_then(|last_slice| last_slice.checked_mul(slice_pitch))
+            .zip(last_row.and_then(|last_row| last_row.checked_mul(row_pitch)))
+            .and_then(|(slices, rows)| slices.checked_add(rows))
+            .and_then(|end| end.checked_add(x))
+            .and_then(|end| end.checked_add(extent[0]))
+            .ok_or_else(|| record_error!(CL_INVALID_VALUE, Region3D::footprint, extent))
+    }
+}
+
+/// A rectangular transfer of a sub-volume of elements between two arrays.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct RectTransfer {
+    /// The sub-volume in the source array: the buffer of a read or copy and
+    /// the host slice of a write.
+    pub src: Region3D,
+    /// The sub-volume in the destination array: the host slice of a read and
+    /// the buffer of a write or copy.
+    pub dst: Region3D,
+    /// The (width, height, depth) of the sub-volume: the width in elements,
+    /// the height in rows and the depth in slices.
+    pub extent: [usize; 3],
+}
+
+/// The byte origins, region and pitches of a checked [`RectTransfer`].
+pub(crate) struct RectBytes {
+    pub src_origin: [size_t; 3],
+    pub dst_origin: [size_t; 3],
+    pub region: [size_t; 3],
+    pub src_row_pitch: size_t,
+    pub src_slice_pitch: size_t,
+    pub dst_row_pitch: size_t,
+    pub dst_slice_pitch: size_t,
+}
+
+impl RectTransfer {
+    /// A transfer of `extent` elements between packed arrays.
+    #[must_use]
+    pub const fn new(extent: [usize; 3]) -> Self {
+        Self {
+            src: Region3D::packed(),
+            dst: Region3D::packed(),
+            extent,
+        }
+    }
+
+    #[must_use]
+    pub const fn src(mut self, src: Region3D) -> Self {
+        self.src = src;
+        self
+    }
+
+    #[must_use]
+    pub const fn dst(mut self, dst: Region3D) -> Self {
+        self.dst = dst;
+        self
+    }
+
+    /// Check the footprints of the transfer against the lengths of the source
+    /// and destination arrays.
+    ///
+    /// returns an empty Result, or `CL_INVALID_VALUE` if a sub-volume is
+    /// invalid or exceeds its array.
+    pub fn check(&self, src_len: usize, dst_len: usize) -> Result<(), cl_int> {
+        if src_len < self.src.footprint(self.extent)?
+            || dst_len < self.dst.footprint(self.extent)?
+        {
+            return Err(record_error!(
+                CL_INVALID_VALUE,
+                RectTransfer::check,
+                src_len,
+                dst_len
+            ));
+        }
+        Ok(())
+    }
+
+    /// Check the transfer and convert it to bytes for elements of type `T`.
+    pub(crate) fn byte_rect<T>(&self, src_len: usize, dst_len: usize) -> Result<RectBytes, cl_int> {
+        self.check(src_len, dst_len)?;
+        let (src_row_pitch, src_slice_pitch) = self.src.pitches(self.extent)?;
+        let (dst_row_pitch, dst_slice_pitch) = self.dst.pitches(self.extent)?;
+        let bytes = |elements: usize| {
+            elements
+                .checked_mul(mem::size_of::<T>())
+                .ok_or_else(|| record_error!(CL_INVALID_VALUE, RectTransfer::byte_rect, elements))
+        };
+        let origin = |region: &Region3D| -> Result<[size_t; 3], cl_int> {
+            Ok([bytes(region.origin[0])?, region.origin[1], region.origin[2]])
+        };
+        Ok(RectBytes {
+            src_origin: origin(&self.src)?,
+            dst_origin: origin(&self.dst)?,
+            region: [bytes(self.extent[0])?, self.extent[1], self.extent[2]],
+            src_row_pitch: bytes(src_row_pitch)?,
+            src_slice_pitch: bytes(src_slice_pitch)?,
+            dst_row_pitch: bytes(dst_row_pitch)?,
+            dst_slice_pitch: bytes(dst_slice_pitch)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::buffer::Buffer;
+    #[allow(deprecated)]
+    use crate::command_queue::{create_command_queue, finish, release_command_queue};
+    use crate::context::{create_context, release_context};
+    use crate::device::{get_device_ids, CL_DEVICE_TYPE_GPU};
+    use crate::memory::CL_MEM_READ_WRITE;
+    use crate::platform::get_platform_ids;
+    use opencl_sys::cl_uint;
+    use std::ptr;
+
+    #[test]
+    fn test_region_3d() {
+        assert_eq!(Ok((4, 12)), Region3D::packed().pitches([4, 3, 2]));
+        assert_eq!(Ok((8, 24)), Region3D::in_2d(1, 1, 8).pitches([4, 3, 1]));
+        assert_eq!(
+            Ok((8, 48)),
+            Region3D::in_3d(0, 0, 0, 8, 6).pitches([4, 3, 2])
+        );
+        assert_eq!(
+            Err(CL_INVALID_VALUE),
+            Region3D::in_2d(0, 0, 3).pitches([4, 3, 1])
+        );
+        assert_eq!(
+            Err(CL_INVALID_VALUE),
+            Region3D::new([0; 3], 8, 20).pitches([4, 2, 2])
+        );
+
+        // The last row ends at x + width
+        assert_eq!(Ok(12), Region3D::packed().footprint([4, 3, 1]));
+        assert_eq!(
+            Ok(8 * 3 + 1 + 4),
+            Region3D::in_2d(1, 1, 8).footprint([4, 3, 1])
+        );
+        assert_eq!(
+            Ok(48 + 8 * 2 + 4),
+            Region3D::in_3d(0, 0, 1, 8, 6).footprint([4, 3, 1])
+        );
+        assert_eq!(
+            Err(CL_INVALID_VALUE),
+            Region3D::packed().footprint([4, 0, 1])
+        );
+        assert_eq!(
+            Err(CL_INVALID_VALUE),
+            Region3D::in_2d(usize::MAX, 0, 1).footprint([1, 1, 1])
+        );
+
+        let transfer = RectTransfer::new([3, 2, 1]).src(Region3D::in_2d(2, 1, 8));
+        assert_eq!(Ok(()), transfer.check(48, 6));
+        assert_eq!(Err(CL_INVALID_VALUE), transfer.check(12, 6));
+        assert_eq!(Err(CL_INVALID_VALUE), transfer.check(48, 5));
+
+        let rect = transfer.byte_rect::<cl_uint>(48, 6).unwrap();
+        assert_eq!([8, 1, 0], rect.src_origin);
+        assert_eq!([12, 2, 1], rect.region);
+        assert_eq!((32, 64), (rect.src_row_pitch, rect.src_slice_pitch));
+        assert_eq!((12, 24), (rect.dst_row_pitch, rect.dst_slice_pitch));
+    }
+
+    #[test]
+    fn test_rect_transfer() {
+        let platform_ids = get_platform_ids().unwrap();
+        let device_ids = get_device_ids(platform_ids[0], CL_DEVICE_TYPE_GPU).unwrap();
+        let context = create_context(&device_ids, ptr::null(), None, ptr::null_mut()).unwrap();
+        #[allow(deprecated)]
+        let queue = unsafe { create_command_queue(context, device_ids[0], 0).unwrap() };
+
+        // An 8 by 6 frame of y * 8 + x
+        let data: Vec<cl_uint> = (0..48).collect();
+        let frame = Buffer::create_with_data(context, CL_MEM_READ_WRITE, &data).unwrap();
+
+        let transfer = RectTransfer::new([3, 2, 1]).src(Region3D::in_2d(2, 1, 8));
+        let mut tile = [0; 6];
+        frame.read_rect(queue, &transfer, &mut tile).unwrap();
+        assert_eq!([10, 11, 12, 18, 19, 20], tile);
+        let mut short = [0; 5];
+        assert_eq!(
+            Err(CL_INVALID_VALUE),
+            frame.read_rect(queue, &transfer, &mut short)
+        );
+
+        // Write the right half of a 4 by 2 host tile to (6, 4)
+        let host = [100, 101, 102, 103, 104, 105, 106, 107];
+        let transfer = RectTransfer::new([2, 2, 1])
+            .src(Region3D::in_2d(2, 0, 4))
+            .dst(Region3D::in_2d(6, 4, 8));
+        frame.write_rect(queue, &transfer, &host).unwrap();
+        let data = frame.read_to_vec(queue).unwrap();
+        assert_eq!([36, 37, 102, 103], data[36..40]);
+        assert_eq!([44, 45, 106, 107], data[44..48]);
+        let transfer = transfer.dst(Region3D::in_2d(7, 4, 8));
+        assert_eq!(
+            Err(CL_INVALID_VALUE),
+            frame.write_rect(queue, &transfer, &host)
+        );
+
+        // Copy a 2 by 2 tile into a 3D buffer of two 2 by 2 slices
+        let volume = Buffer::<cl_uint>::create(context, CL_MEM_READ_WRITE, 8).unwrap();
+        let transfer = RectTransfer::new([2, 2, 1])
+            .src(Region3D::in_2d(0, 0, 8))
+            .dst(Region3D::in_3d(0, 0, 1, 2, 2));
+        frame.copy_rect_to(queue, &volume, &transfer).unwrap();
+        finish(queue).unwrap();
+        assert_eq!([0, 1, 8, 9], volume.read_to_vec(queue).unwrap()[4..]);
+
+        drop(volume);
+        drop(frame);
+        unsafe {
+            release_command_queue(queue).unwrap();
+            release_context(context).unwrap();
+        }
+    }
+}
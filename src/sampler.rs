@@ -25,12 +25,14 @@ pub use opencl_sys::{
     CL_SAMPLER_REFERENCE_COUNT, CL_SUCCESS,
 };
 
-use opencl_sys::{clCreateSampler, clGetSamplerInfo, clReleaseSampler, clRetainSampler};
+use super::api_error::{clear_error, record_error};
+use super::ffi::{clCreateSampler, clGetSamplerInfo, clReleaseSampler, clRetainSampler};
 
 #[cfg(feature = "CL_VERSION_2_0")]
-use opencl_sys::clCreateSamplerWithProperties;
+use super::ffi::clCreateSamplerWithProperties;
 
 use super::info_type::InfoType;
+use super::owned::{AsContext, AsSampler};
 use super::{api_info_size, api_info_value, api_info_vector};
 use libc::{c_void, intptr_t, size_t};
 use std::mem;
@@ -63,11 +65,13 @@ use std::ptr;
 )]
 #[inline]
 pub fn create_sampler(
-    context: cl_context,
+    context: impl AsContext,
     normalize_coords: cl_bool,
     addressing_mode: cl_addressing_mode,
     filter_mode: cl_filter_mode,
 ) -> Result<cl_sampler, cl_int> {
+    let context = context.as_raw();
+    clear_error();
     let mut status: cl_int = CL_INVALID_VALUE;
     let sampler: cl_sampler = unsafe {
         clCreateSampler(
@@ -81,7 +85,7 @@ pub fn create_sampler(
     if CL_SUCCESS == status {
         Ok(sampler)
     } else {
-        Err(status)
+        Err(record_error!(status, clCreateSampler))
     }
 }
 
@@ -98,16 +102,18 @@ pub fn create_sampler(
 #[cfg(feature = "CL_VERSION_2_0")]
 #[inline]
 pub fn create_sampler_with_properties(
-    context: cl_context,
+    context: impl AsContext,
     properties: *const cl_sampler_properties,
 ) -> Result<cl_sampler, cl_int> {
+    let context = context.as_raw();
+    clear_error();
     let mut status: cl_int = CL_INVALID_VALUE;
     let sampler: cl_sampler =
         unsafe { clCreateSamplerWithProperties(context, properties, &mut status) };
     if CL_SUCCESS == status {
         Ok(sampler)
     } else {
-        Err(status)
+        Err(record_error!(status, clCreateSamplerWithProperties))
     }
 }
 
@@ -123,11 +129,12 @@ pub fn create_sampler_with_properties(
 /// This function is unsafe because it changes the `OpenCL` object reference count.
 #[inline]
 pub unsafe fn retain_sampler(sampler: cl_sampler) -> Result<(), cl_int> {
+    clear_error();
     let status: cl_int = clRetainSampler(sampler);
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(status, clRetainSampler))
     }
 }
 
@@ -143,20 +150,23 @@ pub unsafe fn retain_sampler(sampler: cl_sampler) -> Result<(), cl_int> {
 /// This function is unsafe because it changes the `OpenCL` object reference count.
 #[inline]
 pub unsafe fn release_sampler(sampler: cl_sampler) -> Result<(), cl_int> {
+    clear_error();
     let status: cl_int = clReleaseSampler(sampler);
     if CL_SUCCESS == status {
         Ok(())
     } else {
-        Err(status)
+        Err(record_error!(status, clReleaseSampler))
     }
 }
 
 /// Get data about an `OpenCL` sampler object.
 /// Calls `clGetDeviceInfo` to get the desired data about the sampler object.
 pub fn get_sampler_data(
-    sampler: cl_sampler,
+    sampler: impl AsSampler,
     param_name: cl_sampler_info,
 ) -> Result<Vec<u8>, cl_int> {
+    let sampler = sampler.as_raw();
+    clear_error();
     api_info_size!(get_size, clGetSamplerInfo);
     let size = get_size(sampler, param_name)?;
     api_info_vector!(get_vector, u8, clGetSamplerInfo);
@@ -173,9 +183,11 @@ pub fn get_sampler_data(
 /// returns a Result containing the desired information in an `InfoType` enum
 /// or the error code from the `OpenCL` C API function.
 pub fn get_sampler_info(
-    sampler: cl_sampler,
+    sampler: impl AsSampler,
     param_name: cl_sampler_info,
 ) -> Result<InfoType, cl_int> {
+    let sampler = sampler.as_raw();
+    clear_error();
     match param_name {
         CL_SAMPLER_REFERENCE_COUNT
         | CL_SAMPLER_NORMALIZED_COORDS
@@ -0,0 +1,530 @@
+// Copyright (c) 2024 Via Technology Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! `OpenCL` C source files with their `#include` files.
+//!
+//! A [`SourceBundle`] holds a root source file and the header files that it
+//! includes, directly or indirectly, by their include names. It is either
+//! loaded from the file system with include search paths, or embedded in the
+//! executable at compile time with the [`include_source_bundle`](crate::include_source_bundle)
+//! macro, so that the kernel sources do not need to be shipped with it, e.g.:
+//!
+//! ```no_run
+//! use cl3::source_bundle::SourceBundle;
+//! use cl3::types::{cl_context, cl_int, cl_program};
+//!
+//! fn example(context: cl_context) -> Result<cl_program, cl_int> {
+//!     let bundle = SourceBundle::load("kernels/main.cl", &["kernels/include"])
+//!         .expect("kernel source files");
+//!     bundle.create_program(context)
+//! }
+//! ```
+//!
+//! A bundle can either be inlined into a single source string, with `#line`
+//! directives so that build logs refer to the original files, or compiled
+//! with `clCompileProgram` using a header program for each header file.
+//!
+//! Only `#include` directives at the start of a line are recognised, they are
+//! resolved regardless of any conditional directives around them.
+//! A header containing `#pragma once` is only inlined once.
+
+#[cfg(feature = "CL_VERSION_1_2")]
+use super::api_error::preserve_error;
+#[cfg(feature = "CL_VERSION_1_2")]
+use super::context::cl_context;
+use super::owned::AsContext;
+use super::program::create_program_with_source;
+#[cfg(feature = "CL_VERSION_1_2")]
+use super::program::{compile_program, release_program};
+#[cfg(feature = "CL_VERSION_1_2")]
+use opencl_sys::{cl_device_id, CL_INVALID_VALUE};
+use opencl_sys::{cl_int, cl_program};
+#[cfg(feature = "CL_VERSION_1_2")]
+use std::ffi::{CStr, CString};
+use std::fmt::Write;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+#[cfg(feature = "CL_VERSION_1_2")]
+use std::ptr;
+
+/// A source file and the name that it is included by.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SourceFile {
+    /// The include name of a header, or the file name of the root source file.
+    pub name: String,
+    /// The contents of the file.
+    pub source: String,
+}
+
+/// An `#include` directive: the include name and whether it is quoted,
+/// i.e. `#include "name"` rather than `#include <name>`.
+fn parse_include(line: &str) -> Option<(&str, bool)> {
+    let directive = line.trim_start().strip_prefix('#')?.trim_start();
+    let rest = directive.strip_prefix("include")?.trim();
+    if let Some(rest) = rest.strip_prefix('"') {
+        rest.split_once('"').map(|(name, _)| (name, true))
+    } else {
+        let rest = rest.strip_prefix('<')?;
+        rest.split_once('>').map(|(name, _)| (name, false))
+    }
+}
+
+fn is_pragma_once(line: &str) -> bool {
+    line.trim_start()
+        .strip_prefix('#')
+        .and_then(|directive| directive.trim_start().strip_prefix("pragma"))
+        .is_some_and(|rest| "once" == rest.trim())
+}
+
+/// Escape a file name for a `#line` directive.
+fn escape(name: &str) -> String {
+    name.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// An `OpenCL` C source file and the header files that it includes.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SourceBundle {
+    root: SourceFile,
+    headers: Vec<SourceFile>,
+}
+
+impl SourceBundle {
+    /// Create a bundle from a root source and header sources, e.g. embedded
+    /// with `include_str!`, see [`include_source_bundle`](crate::include_source_bundle).
+    ///
+    /// * `root` - the name and source of the root file.
+    /// * `headers` - the include names and sources of the header files.
+    ///
+    /// returns a Result containing the bundle or an error if a file includes
+    /// a header that is not in `headers`, or if a header is not included.
+    pub fn from_sources(root: (&str, &str), headers: &[(&str, &str)]) -> io::Result<Self> {
+        let bundle = Self {
+            root: SourceFile {
+                name: root.0.to_owned(),
+                source: root.1.to_owned(),
+            },
+            headers: headers
+                .iter()
+                .map(|(name, source)| SourceFile {
+                    name: (*name).to_owned(),
+                    source: (*source).to_owned(),
+                })
+                .collect(),
+        };
+        bundle.check_includes()?;
+        Ok(bundle)
+    }
+
+    /// Check that the headers are the files that the root source file
+    /// includes, directly or indirectly.
+    fn check_includes(&self) -> io::Result<()> {
+        let mut included: Vec<&str> = Vec::new();
+        let mut pending = vec![&self.root];
+        while let Some(file) = pending.pop() {
+            for (name, _) in file.source.lines().filter_map(parse_include) {
+                if included.contains(&name) {
+                    continue;
+                }
+                let header = self.header(name).ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::NotFound,
+                        format!(
+                            "'{name}' included from '{}' is not in the bundle",
+                            file.name
+                        ),
+                    )
+                })?;
+                included.push(name);
+                pending.push(header);
+            }
+        }
+
+        for (index, header) in self.headers.iter().enumerate() {
+            let message = if !included.contains(&header.name.as_str()) {
+                format!("'{}' is not included by '{}'", header.name, self.root.name)
+            } else if self.headers[..index].iter().any(|h| h.name == header.name) {
+                format!("'{}' is in the bundle more than once", header.name)
+            } else {
+                continue;
+            };
+            return Err(io::Error::new(io::ErrorKind::InvalidData, message));
+        }
+        Ok(())
+    }
+
+    /// Load a root source file and the header files that it includes.
+    ///
+    /// An `#include "name"` is searched for relative to the directory of the
+    /// including file, then in the `include_paths`.
+    /// An `#include <name>` is only searched for in the `include_paths`.
+    ///
+    /// * `root` - the path of the root source file.
+    /// * `include_paths` - the directories to search for include files, in order.
+    ///
+    /// returns a Result containing the bundle or an error if a file cannot be
+    /// read or found, or if an include name refers to different files.
+    pub fn load(root: impl AsRef<Path>, include_paths: &[impl AsRef<Path>]) -> io::Result<Self> {
+        let root = root.as_ref();
+        let include_paths: Vec<&Path> = include_paths.iter().map(AsRef::as_ref).collect();
+        let name = root
+            .file_name()
+            .map_or_else(|| root.to_string_lossy(), |name| name.to_string_lossy())
+            .into_owned();
+        let mut bundle = Self {
+            root: SourceFile {
+                name,
+                source: fs::read_to_string(root)?,
+            },
+            headers: Vec::new(),
+        };
+
+        // The headers that have been loaded, with their paths
+        let mut paths: Vec<PathBuf> = Vec::new();
+        let mut pending = vec![(root.to_path_buf(), bundle.root.source.clone())];
+        while let Some((path, source)) = pending.pop() {
+            for (name, quoted) in source.lines().filter_map(parse_include) {
+                let local = path
+                    .parent()
+                    .filter(|_| quoted)
+                    .map(|directory| directory.join(name));
+                let found = local
+                    .into_iter()
+                    .chain(include_paths.iter().map(|directory| directory.join(name)))
+                    .find(|candidate| candidate.is_file())
+                    .ok_or_else(|| {
+                        io::Error::new(
+                            io::ErrorKind::NotFound,
+                            format!("'{name}' included from '{}' not found", path.display()),
+                        )
+                    })?;
+
+                if let Some(index) = bundle.headers.iter().position(|h| h.name == name) {
+                    if !same_file(&paths[index], &found) {
+                        return Err(io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            format!(
+                                "'{name}' refers to both '{}' and '{}'",
+                                paths[index].display(),
+                                found.display()
+                            ),
+                        ));
+                    }
+                    continue;
+                }
+
+                let source = fs::read_to_string(&found)?;
+                bundle.headers.push(SourceFile {
+                    name: name.to_owned(),
+                    source: source.clone(),
+                });
+                paths.push(found.clone());
+                pending.push((found, source));
+            }
+        }
+        Ok(bundle)
+    }
+
+    /// The root source file.
+    #[must_use]
+    pub const fn root(&self) -> &SourceFile {
+        &self.root
+    }
+
+    /// The header files, by include name.
+    #[must_use]
+    pub fn headers(&self) -> &[SourceFile] {
+        &self.headers
+    }
+
+    /// The header file with an include name.
+    #[must_use]
+    pub fn header(&self, name: &str) -> Option<&SourceFile> {
+        self.headers.iter().find(|header| header.name == name)
+    }
+
+    /// The root source with the `#include` directives of the headers in the
+    /// bundle replaced by the header sources.
+    /// `#line` directives before and after each header keep the file names and
+    /// line numbers of the original files, e.g. in build logs.
+    /// `#include` directives of files that are not in the bundle are kept.
+    #[must_use]
+    pub fn inline(&self) -> String {
+        let mut output = String::new();
+        let mut stack = Vec::new();
+        let mut once = Vec::new();
+        self.inline_file(&self.root, &mut stack, &mut once, &mut output);
+        output
+    }
+
+    fn inline_file<'a>(
+        &'a self,
+        file: &'a SourceFile,
+        stack: &mut Vec<&'a str>,
+        once: &mut Vec<&'a str>,
+        output: &mut String,
+    ) {
+        stack.push(&file.name);
+        for (index, line) in file.source.lines().enumerate() {
+            match parse_include(line).and_then(|(name, _)| self.header(name)) {
+                // An include cycle would never end, since guards are not evaluated
+                Some(header)
+                    if stack.contains(&header.name.as_str())
+                        || once.contains(&header.name.as_str()) =>
+                {
+                    output.push('\n');
+                }
+                Some(header) => {
+                    if header.source.lines().any(is_pragma_once) {
+                        once.push(&header.name);
+                    }
+                    let _ = writeln!(output, "#line 1 \"{}\"", escape(&header.name));
+                    self.inline_file(header, stack, once, output);
+                    let _ = writeln!(output, "#line {} \"{}\"", index + 2, escape(&file.name));
+                }
+                // Keep the line numbers
+                None if is_pragma_once(line) => output.push('\n'),
+                None => {
+                    output.push_str(line);
+                    output.push('\n');
+                }
+            }
+        }
+        stack.pop();
+    }
+
+    /// Create a program from the inlined source of the bundle.
+    /// Calls `create_program_with_source`, see [`inline`](Self::inline).
+    ///
+    /// * `context` - a valid `OpenCL` context.
+    ///
+    /// returns a Result containing the new `OpenCL` program object
+    /// or the error code from the `OpenCL` C API function.
+    pub fn create_program(&self, context: impl AsContext) -> Result<cl_program, cl_int> {
+        create_program_with_source(context, &[&self.inline()])
+    }
+
+    /// Create and compile a program from the root source file, with a header
+    /// program for each header file.
+    /// Calls `create_program_with_source` and `compile_program`.
+    /// `CL_VERSION_1_2`
+    ///
+    /// * `context` - a valid `OpenCL` context.
+    /// * `devices` - a slice of devices that are in context.
+    /// * `options` - the compile options in a null-terminated string.
+    ///
+    /// returns a Result containing the compiled `OpenCL` program object,
+    /// to link with `link_program`, or the error code from the `OpenCL` C API function.
+    #[cfg(feature = "CL_VERSION_1_2")]
+    pub fn compile_program(
+        &self,
+        context: impl AsContext,
+        devices: &[cl_device_id],
+        options: &CStr,
+    ) -> Result<cl_program, cl_int> {
+        let context: cl_context = context.as_raw();
+        let names = self
+            .headers
+            .iter()
+            .map(|header| CString::new(header.name.as_str()).map_err(|_| CL_INVALID_VALUE))
+            .collect::<Result<Vec<_>, cl_int>>()?;
+        let names: Vec<&CStr> = names.iter().map(CString::as_c_str).collect();
+
+        let mut headers: Vec<cl_program> = Vec::with_capacity(self.headers.len());
+        let mut compile = || {
+            for header in &self.headers {
+                headers.push(create_program_with_source(context, &[&header.source])?);
+            }
+            let program = create_program_with_source(context, &[&self.root.source])?;
+            if let Err(error) = compile_program(
+                program,
+                devices,
+                options,
+                &headers,
+                &names,
+                None,
+                ptr::null_mut(),
+            ) {
+                preserve_error(|| {
+                    let _ = unsafe { release_program(program) };
+                });
+                return Err(error);
+            }
+            Ok(program)
+        };
+        let result = compile();
+
+        // The header programs are not needed after compiling, an error keeps
+        // its recorded build log
+        preserve_error(|| {
+            for header in headers {
+                let _ = unsafe { release_program(header) };
+            }
+        });
+        result
+    }
+}
+
+fn same_file(a: &Path, b: &Path) -> bool {
+    a == b || matches!((a.canonicalize(), b.canonicalize()), (Ok(a), Ok(b)) if a == b)
+}
+
+/// Embed an `OpenCL` C source file and its header files in the executable,
+/// returning a Result containing a [`SourceBundle`](crate::source_bundle::SourceBundle).
+///
+/// The first argument is the path of the root source file and the others are
+/// the include names of the header files with their paths.
+/// The paths are relative to the file containing the macro, like `include_str!`, e.g.:
+///
+/// ```ignore
+/// let bundle = cl3::include_source_bundle!(
+///     "kernels/main.cl",
+///     "common.h" => "kernels/common.h",
+///     "util/math.h" => "kernels/include/util/math.h",
+/// )
+/// .expect("the headers of kernels/main.cl");
+/// let source = bundle.inline();
+/// ```
+///
+/// The `#include` directives cannot be resolved at compile time, so the
+/// headers are checked against them when the bundle is created, see
+/// [`SourceBundle::from_sources`](crate::source_bundle::SourceBundle::from_sources).
+#[macro_export]
+macro_rules! include_source_bundle {
+    ($root:literal $(, $name:literal => $path:literal)* $(,)?) => {
+        $crate::source_bundle::SourceBundle::from_sources(
+            ($root, include_str!($root)),
+            &[$(($name, include_str!($path))),*],
+        )
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::{create_context, release_context};
+    use crate::device::{get_device_ids, CL_DEVICE_TYPE_GPU};
+    use crate::platform::get_platform_ids;
+    use crate::program::{build_program, release_program};
+    use std::ffi::CString;
+    use std::ptr;
+
+    fn bundle_dir() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/kernels/bundle")
+    }
+
+    #[test]
+    fn test_source_bundle() {
+        let directory = bundle_dir();
+        let include_paths = [directory.join("include"), directory.clone()];
+        let bundle = SourceBundle::load(directory.join("main.cl"), &include_paths).unwrap();
+        assert_eq!("main.cl", bundle.root().name);
+        let names: Vec<&str> = bundle.headers().iter().map(|h| h.name.as_str()).collect();
+        assert_eq!(vec!["common.h", "util/math.h"], names);
+        assert!(bundle
+            .header("util/math.h")
+            .unwrap()
+            .source
+            .contains("SQUARE"));
+
+        let source = bundle.inline();
+        println!("{source}");
+        assert!(!source.contains("#include"));
+        assert!(!source.contains("#pragma once"));
+        // common.h is only inlined once
+        assert_eq!(1, source.matches("#define SCALE").count());
+        assert!(source
+            .starts_with("#line 1 \"common.h\"\n\n\n#define SCALE 2.0f\n#line 2 \"main.cl\"\n"));
+        assert!(source.contains("#line 1 \"util/math.h\"\n\n\n#define SQUARE"));
+        assert!(source.contains("#line 3 \"main.cl\"\n\nkernel void scale"));
+
+        // An include file that is not found
+        let root =
+            std::env::temp_dir().join(format!("cl3_source_bundle_{}.cl", std::process::id()));
+        fs::write(&root, "#include \"missing.h\"\n").unwrap();
+        let error = SourceBundle::load(&root, &include_paths).unwrap_err();
+        fs::remove_file(&root).unwrap();
+        assert_eq!(io::ErrorKind::NotFound, error.kind());
+
+        // The macro embeds the same files
+        let embedded = crate::include_source_bundle!(
+            "../tests/kernels/bundle/main.cl",
+            "common.h" => "../tests/kernels/bundle/common.h",
+            "util/math.h" => "../tests/kernels/bundle/include/util/math.h",
+        )
+        .unwrap();
+        assert_eq!(bundle.root().source, embedded.root().source);
+        assert_eq!(bundle.headers(), embedded.headers());
+
+        // The embedded headers must be the included files
+        let error = crate::include_source_bundle!(
+            "../tests/kernels/bundle/main.cl",
+            "common.h" => "../tests/kernels/bundle/common.h",
+        )
+        .unwrap_err();
+        assert_eq!(io::ErrorKind::NotFound, error.kind());
+        let error = SourceBundle::from_sources(
+            ("main.cl", "#include \"common.h\"\n"),
+            &[("common.h", ""), ("unused.h", "")],
+        )
+        .unwrap_err();
+        assert_eq!(io::ErrorKind::InvalidData, error.kind());
+        assert_eq!("'unused.h' is not included by 'main.cl'", error.to_string());
+        let error = SourceBundle::from_sources(
+            ("main.cl", "#include \"common.h\"\n"),
+            &[("common.h", ""), ("common.h", "")],
+        )
+        .unwrap_err();
+        assert_eq!(io::ErrorKind::InvalidData, error.kind());
+    }
+
+    #[test]
+    fn test_source_bundle_program() {
+        let platform_ids = get_platform_ids().unwrap();
+        let device_ids = get_device_ids(platform_ids[0], CL_DEVICE_TYPE_GPU).unwrap();
+        let context = create_context(&device_ids, ptr::null(), None, ptr::null_mut()).unwrap();
+
+        let directory = bundle_dir();
+        let include_paths = [directory.join("include"), directory.clone()];
+        let bundle = SourceBundle::load(directory.join("main.cl"), &include_paths).unwrap();
+        let options = CString::new("").unwrap();
+
+        let program = bundle.create_program(context).unwrap();
+        build_program(program, &device_ids, &options, None, ptr::null_mut()).unwrap();
+        unsafe { release_program(program).unwrap() };
+
+        #[cfg(feature = "CL_VERSION_1_2")]
+        {
+            let program = bundle
+                .compile_program(context, &device_ids, &options)
+                .unwrap();
+            unsafe { release_program(program).unwrap() };
+
+            // A compile error keeps its build log
+            let bundle = SourceBundle::from_sources(
+                ("main.cl", "#include \"a.h\"\nkernel void f("),
+                &[("a.h", "#define A 1")],
+            )
+            .unwrap();
+            let error = bundle
+                .compile_program(context, &device_ids, &options)
+                .unwrap_err();
+            let error = crate::api_error::ApiError::from(error);
+            assert_eq!(Some("clCompileProgram"), error.function());
+            assert!(!error.build_logs().is_empty());
+        }
+
+        unsafe { release_context(context).unwrap() };
+    }
+}
@@ -0,0 +1,1063 @@
+// Copyright (c) 2024 Via Technology Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! SPIR-V module reflection.
+//!
+//! [`SpirvModule`] parses the SPIR-V passed to `create_program_with_il` and
+//! reflects its version, capabilities, extensions and addressing model, and
+//! the `OpSpecConstant*` declarations that have a `SpecId` decoration, with
+//! their types, default values and names (if the module has debug names).
+//! The typed setters check the type and size of a value before calling
+//! `set_program_specialization_constant`, e.g.:
+//!
+//! ```no_run
+//! use cl3::spirv::SpirvModule;
+//! use cl3::types::{cl_int, cl_program};
+//!
+//! // The program was created from `il` with `create_program_with_il`
+//! # #[cfg(feature = "CL_VERSION_2_2")]
+//! fn example(il: &[u8], program: cl_program) -> Result<(), cl_int> {
+//!     let module = SpirvModule::parse(il)?;
+//!     for constant in module.spec_constants() {
+//!         println!("{:?}", constant);
+//!     }
+//!     module.set_named_spec_constant(program, "BLOCK_SIZE", 64_u32)?;
+//!     module.set_spec_constant(program, 3, true)?;
+//!     Ok(())
+//! }
+//! ```
+//!
+//! [`SpirvModule::check_device`] compares the SPIR-V version, capabilities,
+//! extensions and addressing model of a module with the IL versions,
+//! extensions and features of a device before creating a program, e.g. to
+//! choose the IL variant for each device:
+//!
+//! ```no_run
+//! use cl3::spirv::SpirvModule;
+//! use cl3::types::{cl_device_id, cl_int};
+//!
+//! fn choose<'a>(variants: &[&'a [u8]], device: cl_device_id) -> Result<Option<&'a [u8]>, cl_int> {
+//!     for il in variants {
+//!         let issues = SpirvModule::parse(il)?.check_device(device)?;
+//!         if issues.is_empty() {
+//!             return Ok(Some(*il));
+//!         }
+//!         for issue in issues {
+//!             println!("{}", issue);
+//!         }
+//!     }
+//!     Ok(None)
+//! }
+//! ```
+
+#![allow(non_camel_case_types)]
+
+use super::api_error::{discard_error, record_error};
+use super::build_options::major_minor;
+use super::device_info::{
+    get_device_param, AddressBits, DeviceEnqueueCapabilities, Extensions,
+    GenericAddressSpaceSupport, IlVersion, IlsWithVersion, ImageSupport, PipeSupport,
+};
+use super::owned::AsDevice;
+#[cfg(feature = "CL_VERSION_2_2")]
+use super::owned::AsProgram;
+#[cfg(feature = "CL_VERSION_2_2")]
+use super::program::set_program_specialization_constant;
+#[cfg(feature = "CL_VERSION_2_2")]
+use libc::c_void;
+use opencl_sys::{cl_device_id, cl_int, cl_uint, CL_INVALID_SPEC_ID, CL_INVALID_VALUE};
+use std::collections::HashMap;
+use std::fmt;
+
+/// The magic number of a SPIR-V module.
+pub const SPIRV_MAGIC: u32 = 0x0723_0203;
+
+// The SPIR-V opcodes and decorations used by the reflection.
+const OP_NAME: u32 = 5;
+const OP_EXTENSION: u32 = 10;
+const OP_MEMORY_MODEL: u32 = 14;
+const OP_CAPABILITY: u32 = 17;
+const OP_TYPE_BOOL: u32 = 20;
+const OP_TYPE_INT: u32 = 21;
+const OP_TYPE_FLOAT: u32 = 22;
+const OP_SPEC_CONSTANT_TRUE: u32 = 48;
+const OP_SPEC_CONSTANT_FALSE: u32 = 49;
+const OP_SPEC_CONSTANT: u32 = 50;
+const OP_DECORATE: u32 = 71;
+const DECORATION_SPEC_ID: u32 = 1;
+
+// The SPIR-V addressing models.
+const ADDRESSING_MODEL_PHYSICAL32: u32 = 1;
+const ADDRESSING_MODEL_PHYSICAL64: u32 = 2;
+
+/// The names of the SPIR-V capabilities of the `OpenCL` environment.
+const CAPABILITY_NAMES: &[(u32, &str)] = &[
+    (4, "Addresses"),
+    (5, "Linkage"),
+    (6, "Kernel"),
+    (7, "Vector16"),
+    (8, "Float16Buffer"),
+    (9, "Float16"),
+    (10, "Float64"),
+    (11, "Int64"),
+    (12, "Int64Atomics"),
+    (13, "ImageBasic"),
+    (14, "ImageReadWrite"),
+    (15, "ImageMipmap"),
+    (17, "Pipes"),
+    (18, "Groups"),
+    (19, "DeviceEnqueue"),
+    (20, "LiteralSampler"),
+    (22, "Int16"),
+    (38, "GenericPointer"),
+    (39, "Int8"),
+    (43, "Sampled1D"),
+    (44, "Image1D"),
+    (46, "SampledBuffer"),
+    (47, "ImageBuffer"),
+    (57, "SubgroupDispatch"),
+    (58, "NamedBarrier"),
+    (59, "PipeStorage"),
+];
+
+/// The name of a SPIR-V capability, e.g. "Float64".
+#[must_use]
+pub fn capability_name(capability: u32) -> Option<&'static str> {
+    CAPABILITY_NAMES
+        .iter()
+        .find(|(value, _)| capability == *value)
+        .map(|(_, name)| *name)
+}
+
+/// What a device must support for a SPIR-V capability.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Requirement {
+    Extension(&'static str),
+    Images,
+    Pipes,
+    DeviceEnqueue,
+    GenericAddressSpace,
+}
+
+impl Requirement {
+    const fn of(capability: u32) -> Option<Self> {
+        match capability {
+            9 => Some(Self::Extension("cl_khr_fp16")),
+            10 => Some(Self::Extension("cl_khr_fp64")),
+            12 => Some(Self::Extension("cl_khr_int64_base_atomics")),
+            15 => Some(Self::Extension("cl_khr_mipmap_image")),
+            57 => Some(Self::Extension("cl_khr_subgroups")),
+            13 | 14 | 20 | 43 | 44 | 46 | 47 => Some(Self::Images),
+            17 | 59 => Some(Self::Pipes),
+            19 => Some(Self::DeviceEnqueue),
+            38 => Some(Self::GenericAddressSpace),
+            _ => None,
+        }
+    }
+
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Extension(extension) => extension,
+            Self::Images => "CL_DEVICE_IMAGE_SUPPORT",
+            Self::Pipes => "CL_DEVICE_PIPE_SUPPORT",
+            Self::DeviceEnqueue => "CL_DEVICE_DEVICE_ENQUEUE_CAPABILITIES",
+            Self::GenericAddressSpace => "CL_DEVICE_GENERIC_ADDRESS_SPACE_SUPPORT",
+        }
+    }
+}
+
+/// The `OpenCL` extensions that may provide a SPIR-V extension, e.g.
+/// `cl_khr_spirv_no_integer_wrap_decoration` or `cl_khr_no_integer_wrap_decoration`
+/// for `SPV_KHR_no_integer_wrap_decoration`.
+fn opencl_extensions(spirv_extension: &str) -> Vec<String> {
+    spirv_extension
+        .strip_prefix("SPV_")
+        .and_then(|name| name.split_once('_'))
+        .map_or_else(Vec::new, |(vendor, name)| {
+            let vendor = vendor.to_ascii_lowercase();
+            vec![
+                format!("cl_{vendor}_spirv_{name}"),
+                format!("cl_{vendor}_{name}"),
+            ]
+        })
+}
+
+/// A reason why a SPIR-V module cannot be used on a device.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Incompatibility {
+    /// The device does not support the SPIR-V version of the module.
+    Version {
+        /// The SPIR-V version of the module.
+        module: (u32, u32),
+        /// The SPIR-V versions that the device supports.
+        device: Vec<(u32, u32)>,
+    },
+    /// The addressing model of the module does not match the device address bits.
+    AddressingModel {
+        /// The `OpMemoryModel` addressing model of the module.
+        addressing_model: Option<u32>,
+        /// `CL_DEVICE_ADDRESS_BITS`
+        address_bits: cl_uint,
+    },
+    /// The device does not support a capability that the module declares.
+    Capability {
+        /// The capability.
+        capability: u32,
+        /// The extension or device information that the capability requires.
+        requires: &'static str,
+    },
+    /// The device does not support an extension that the module declares.
+    Extension(String),
+}
+
+impl fmt::Display for Incompatibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Version { module, device } => {
+                write!(
+                    f,
+                    "SPIR-V {}.{} is not supported, the device supports",
+                    module.0, module.1
+                )?;
+                if device.is_empty() {
+                    f.write_str(" no SPIR-V")
+                } else {
+                    for (i, (major, minor)) in device.iter().enumerate() {
+                        write!(f, "{}{major}.{minor}", if 0 == i { " " } else { ", " })?;
+                    }
+                    Ok(())
+                }
+            }
+            Self::AddressingModel {
+                addressing_model,
+                address_bits,
+            } => match addressing_model {
+                Some(ADDRESSING_MODEL_PHYSICAL32) => {
+                    write!(f, "Physical32 addressing requires 32 address bits, the device has {address_bits}")
+                }
+                Some(ADDRESSING_MODEL_PHYSICAL64) => {
+                    write!(f, "Physical64 addressing requires 64 address bits, the device has {address_bits}")
+                }
+                Some(model) => write!(f, "addressing model {model} is not supported by OpenCL"),
+                None => f.write_str("the module does not have an OpMemoryModel"),
+            },
+            Self::Capability {
+                capability,
+                requires,
+            } => match capability_name(*capability) {
+                Some(name) => write!(f, "capability {name} requires {requires}"),
+                None => write!(f, "capability {capability} requires {requires}"),
+            },
+            Self::Extension(extension) => write!(f, "extension {extension} is not supported"),
+        }
+    }
+}
+
+/// The type of a specialization constant.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SpecConstantType {
+    /// A boolean, set as a `cl_uchar`.
+    Bool,
+    /// An integer of the width in bits. `OpenCL` SPIR-V integers are signless.
+    Int(u32),
+    /// A floating point number of the width in bits.
+    Float(u32),
+}
+
+impl SpecConstantType {
+    /// The size in bytes of a value of the type.
+    #[must_use]
+    pub const fn size(self) -> usize {
+        match self {
+            Self::Bool => 1,
+            Self::Int(width) | Self::Float(width) => width as usize / 8,
+        }
+    }
+}
+
+impl fmt::Display for SpecConstantType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bool => f.write_str("bool"),
+            Self::Int(width) => write!(f, "i{width}"),
+            Self::Float(width) => write!(f, "f{width}"),
+        }
+    }
+}
+
+/// A Rust type that can be the value of a specialization constant.
+pub trait SpecConstantValue: Copy {
+    /// The specialization constant type of the Rust type.
+    const TYPE: SpecConstantType;
+
+    /// The value in the little endian format of `clSetProgramSpecializationConstant`.
+    fn to_bytes(self) -> Vec<u8>;
+
+    /// Convert a value from its little endian bytes.
+    ///
+    /// returns None if `bytes` is not the size of the type.
+    fn from_bytes(bytes: &[u8]) -> Option<Self>;
+}
+
+impl SpecConstantValue for bool {
+    const TYPE: SpecConstantType = SpecConstantType::Bool;
+
+    fn to_bytes(self) -> Vec<u8> {
+        vec![u8::from(self)]
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        match bytes {
+            [value] => Some(0 != *value),
+            _ => None,
+        }
+    }
+}
+
+macro_rules! spec_constant_value {
+    ($type:ty, $spec_type:expr) => {
+        impl SpecConstantValue for $type {
+            const TYPE: SpecConstantType = $spec_type;
+
+            fn to_bytes(self) -> Vec<u8> {
+                self.to_le_bytes().to_vec()
+            }
+
+            fn from_bytes(bytes: &[u8]) -> Option<Self> {
+                Some(Self::from_le_bytes(bytes.try_into().ok()?))
+            }
+        }
+    };
+}
+
+spec_constant_value!(i8, SpecConstantType::Int(8));
+spec_constant_value!(u8, SpecConstantType::Int(8));
+spec_constant_value!(i16, SpecConstantType::Int(16));
+spec_constant_value!(u16, SpecConstantType::Int(16));
+spec_constant_value!(i32, SpecConstantType::Int(32));
+spec_constant_value!(u32, SpecConstantType::Int(32));
+spec_constant_value!(i64, SpecConstantType::Int(64));
+spec_constant_value!(u64, SpecConstantType::Int(64));
+spec_constant_value!(f32, SpecConstantType::Float(32));
+spec_constant_value!(f64, SpecConstantType::Float(64));
+
+/// A specialization constant of a SPIR-V module.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SpecConstant {
+    /// The `SpecId` of the constant, the `spec_id` of
+    /// `set_program_specialization_constant`.
+    pub spec_id: cl_uint,
+    /// The `OpName` of the constant, if the module has debug names.
+    pub name: Option<String>,
+    /// The type of the constant.
+    pub spec_type: SpecConstantType,
+    /// The default value of the constant in little endian bytes.
+    pub default: Vec<u8>,
+}
+
+impl SpecConstant {
+    /// The default value of the constant as a Rust type.
+    ///
+    /// returns None if `T` is not the type of the constant.
+    #[must_use]
+    pub fn default_value<T: SpecConstantValue>(&self) -> Option<T> {
+        if T::TYPE == self.spec_type {
+            T::from_bytes(&self.default)
+        } else {
+            None
+        }
+    }
+}
+
+/// The reflection of a SPIR-V module.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SpirvModule {
+    version: (u32, u32),
+    capabilities: Vec<u32>,
+    extensions: Vec<String>,
+    addressing_model: Option<u32>,
+    spec_constants: Vec<SpecConstant>,
+}
+
+/// Convert SPIR-V bytes to words in the byte order of the magic number.
+fn spirv_words(il: &[u8]) -> Option<Vec<u32>> {
+    if !il.len().is_multiple_of(4) || il.len() < 20 {
+        return None;
+    }
+    let words = il
+        .chunks_exact(4)
+        .map(|chunk| [chunk[0], chunk[1], chunk[2], chunk[3]]);
+    if SPIRV_MAGIC == u32::from_le_bytes(il[..4].try_into().ok()?) {
+        Some(words.map(u32::from_le_bytes).collect())
+    } else if SPIRV_MAGIC == u32::from_be_bytes(il[..4].try_into().ok()?) {
+        Some(words.map(u32::from_be_bytes).collect())
+    } else {
+        None
+    }
+}
+
+/// Decode a SPIR-V literal string.
+fn spirv_string(words: &[u32]) -> String {
+    let bytes: Vec<u8> = words.iter().flat_map(|word| word.to_le_bytes()).collect();
+    let length = bytes.iter().position(|&c| 0 == c).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..length]).into_owned()
+}
+
+/// The SPIR-V versions that a device supports.
+fn device_spirv_versions(device: cl_device_id) -> Vec<(u32, u32)> {
+    if let Some(ils) = discard_error(get_device_param(device, IlsWithVersion)) {
+        if !ils.is_empty() {
+            return ils
+                .iter()
+                .filter(|il| il.name.starts_with(b"SPIR-V\0"))
+                .map(|il| major_minor(il.version))
+                .collect();
+        }
+    }
+    // e.g. "SPIR-V_1.0 SPIR-V_1.1", empty if the device does not support IL
+    let text = discard_error(get_device_param(device, IlVersion)).unwrap_or_default();
+    text.split_whitespace()
+        .filter_map(|il| {
+            let (major, minor) = il.strip_prefix("SPIR-V_")?.split_once('.')?;
+            Some((major.parse().ok()?, minor.parse().ok()?))
+        })
+        .collect()
+}
+
+impl SpirvModule {
+    /// Parse a SPIR-V module, in either byte order.
+    ///
+    /// * `il` - the SPIR-V module.
+    ///
+    /// returns a Result containing the reflection of the module
+    /// or `CL_INVALID_VALUE` if `il` is not a valid SPIR-V module.
+    pub fn parse(il: &[u8]) -> Result<Self, cl_int> {
+        let words = spirv_words(il).ok_or_else(|| {
+            let len = il.len();
+            record_error!(CL_INVALID_VALUE, SpirvModule::parse, len)
+        })?;
+        let version = ((words[1] >> 16) & 0xff, (words[1] >> 8) & 0xff);
+        let mut capabilities: Vec<u32> = Vec::new();
+        let mut extensions: Vec<String> = Vec::new();
+        let mut addressing_model = None;
+
+        let mut names: HashMap<u32, String> = HashMap::new();
+        let mut types: HashMap<u32, SpecConstantType> = HashMap::new();
+        let mut spec_ids: HashMap<u32, cl_uint> = HashMap::new();
+        // The result ids, types and default values of the spec constants
+        let mut constants: Vec<(u32, SpecConstantType, Vec<u8>)> = Vec::new();
+
+        let mut index = 5;
+        while index < words.len() {
+            let count = (words[index] >> 16) as usize;
+            let opcode = words[index] & 0xffff;
+            if 0 == count || words.len() < index + count {
+                return Err(record_error!(
+                    CL_INVALID_VALUE,
+                    SpirvModule::parse,
+                    index,
+                    count
+                ));
+            }
+            let operands = &words[index + 1..index + count];
+            match (opcode, operands) {
+                (OP_NAME, [target, name @ ..]) => {
+                    names.insert(*target, spirv_string(name));
+                }
+                (OP_EXTENSION, name) => extensions.push(spirv_string(name)),
+                (OP_MEMORY_MODEL, [addressing, ..]) => addressing_model = Some(*addressing),
+                (OP_CAPABILITY, [capability]) => capabilities.push(*capability),
+                (OP_TYPE_BOOL, [id]) => {
+                    types.insert(*id, SpecConstantType::Bool);
+                }
+                (OP_TYPE_INT, [id, width, ..]) => {
+                    types.insert(*id, SpecConstantType::Int(*width));
+                }
+                (OP_TYPE_FLOAT, [id, width, ..]) => {
+                    types.insert(*id, SpecConstantType::Float(*width));
+                }
+                (OP_SPEC_CONSTANT_TRUE | OP_SPEC_CONSTANT_FALSE, [result_type, id]) => {
+                    let value = u8::from(OP_SPEC_CONSTANT_TRUE == opcode);
+                    let spec_type = *types.get(result_type).ok_or_else(|| {
+                        record_error!(CL_INVALID_VALUE, SpirvModule::parse, index, result_type)
+                    })?;
+                    constants.push((*id, spec_type, vec![value]));
+                }
+                (OP_SPEC_CONSTANT, [result_type, id, value @ ..]) => {
+                    let spec_type = *types.get(result_type).ok_or_else(|| {
+                        record_error!(CL_INVALID_VALUE, SpirvModule::parse, index, result_type)
+                    })?;
+                    // The literal is in the low order words first
+                    let mut bytes: Vec<u8> =
+                        value.iter().flat_map(|word| word.to_le_bytes()).collect();
+                    if bytes.len() < spec_type.size() {
+                        return Err(record_error!(
+                            CL_INVALID_VALUE,
+                            SpirvModule::parse,
+                            index,
+                            spec_type
+                        ));
+                    }
+                    bytes.truncate(spec_type.size());
+                    constants.push((*id, spec_type, bytes));
+                }
+                (OP_DECORATE, [target, DECORATION_SPEC_ID, spec_id]) => {
+                    spec_ids.insert(*target, *spec_id);
+                }
+                _ => {}
+            }
+            index += count;
+        }
+
+        let mut spec_constants: Vec<SpecConstant> = constants
+            .into_iter()
+            .filter_map(|(id, spec_type, default)| {
+                Some(SpecConstant {
+                    spec_id: *spec_ids.get(&id)?,
+                    name: names.remove(&id),
+                    spec_type,
+                    default,
+                })
+            })
+            .collect();
+        spec_constants.sort_by_key(|constant| constant.spec_id);
+        Ok(Self {
+            version,
+            capabilities,
+            extensions,
+            addressing_model,
+            spec_constants,
+        })
+    }
+
+    /// The SPIR-V version of the module, (major, minor).
+    #[must_use]
+    pub const fn version(&self) -> (u32, u32) {
+        self.version
+    }
+
+    /// The `OpCapability` declarations of the module, see `capability_name`.
+    #[must_use]
+    pub fn capabilities(&self) -> &[u32] {
+        &self.capabilities
+    }
+
+    /// The `OpExtension` declarations of the module.
+    #[must_use]
+    pub fn extensions(&self) -> &[String] {
+        &self.extensions
+    }
+
+    /// The `OpMemoryModel` addressing model of the module, e.g. 2 for Physical64.
+    #[must_use]
+    pub const fn addressing_model(&self) -> Option<u32> {
+        self.addressing_model
+    }
+
+    /// Check whether the module can be used on a device, before calling
+    /// `create_program_with_il`.
+    /// Compares the SPIR-V version with `CL_DEVICE_ILS_WITH_VERSION`
+    /// (or `CL_DEVICE_IL_VERSION` before `OpenCL` 3.0), the addressing model with
+    /// `CL_DEVICE_ADDRESS_BITS`, and the capabilities and extensions with
+    /// `CL_DEVICE_EXTENSIONS` and the optional `OpenCL` 3.0 features.
+    /// SPIR-V extensions are matched with the `OpenCL` extensions of the same
+    /// name, e.g. `SPV_KHR_foo` with `cl_khr_spirv_foo` or `cl_khr_foo`.
+    ///
+    /// * `device` - the `cl_device_id` of the `OpenCL` device.
+    ///
+    /// returns a Result containing the incompatibilities, empty if the module
+    /// can be used on the device, or the error code from the `OpenCL` C API function.
+    pub fn check_device(&self, device: impl AsDevice) -> Result<Vec<Incompatibility>, cl_int> {
+        let device = device.as_raw();
+        let mut issues = Vec::new();
+
+        let versions = device_spirv_versions(device);
+        if !versions.contains(&self.version) {
+            issues.push(Incompatibility::Version {
+                module: self.version,
+                device: versions,
+            });
+        }
+
+        let address_bits = get_device_param(device, AddressBits)?;
+        let required_bits = match self.addressing_model {
+            Some(ADDRESSING_MODEL_PHYSICAL32) => Some(32),
+            Some(ADDRESSING_MODEL_PHYSICAL64) => Some(64),
+            _ => None,
+        };
+        if Some(address_bits) != required_bits {
+            issues.push(Incompatibility::AddressingModel {
+                addressing_model: self.addressing_model,
+                address_bits,
+            });
+        }
+
+        let device_extensions = get_device_param(device, Extensions)?;
+        let has_extension =
+            |name: &str| device_extensions.split_whitespace().any(|ext| name == ext);
+        for capability in &self.capabilities {
+            let Some(requirement) = Requirement::of(*capability) else {
+                continue;
+            };
+            // The optional OpenCL 3.0 features are supported by earlier
+            // versions that do not have the queries
+            let supported = match requirement {
+                Requirement::Extension(extension) => has_extension(extension),
+                Requirement::Images => get_device_param(device, ImageSupport)?,
+                Requirement::Pipes => {
+                    discard_error(get_device_param(device, PipeSupport)).unwrap_or(true)
+                }
+                Requirement::DeviceEnqueue => {
+                    Some(0) != discard_error(get_device_param(device, DeviceEnqueueCapabilities))
+                }
+                Requirement::GenericAddressSpace => {
+                    discard_error(get_device_param(device, GenericAddressSpaceSupport))
+                        .unwrap_or(true)
+                }
+            };
+            if !supported {
+                issues.push(Incompatibility::Capability {
+                    capability: *capability,
+                    requires: requirement.as_str(),
+                });
+            }
+        }
+
+        for extension in &self.extensions {
+            if !opencl_extensions(extension)
+                .iter()
+                .any(|name| has_extension(name))
+            {
+                issues.push(Incompatibility::Extension(extension.clone()));
+            }
+        }
+        Ok(issues)
+    }
+
+    /// The specialization constants of the module, in `spec_id` order.
+    #[must_use]
+    pub fn spec_constants(&self) -> &[SpecConstant] {
+        &self.spec_constants
+    }
+
+    /// Find a specialization constant by its `spec_id`.
+    #[must_use]
+    pub fn spec_constant(&self, spec_id: cl_uint) -> Option<&SpecConstant> {
+        self.spec_constants
+            .iter()
+            .find(|constant| spec_id == constant.spec_id)
+    }
+
+    /// Find a specialization constant by its `OpName`.
+    #[must_use]
+    pub fn named_spec_constant(&self, name: &str) -> Option<&SpecConstant> {
+        self.spec_constants
+            .iter()
+            .find(|constant| Some(name) == constant.name.as_deref())
+    }
+
+    /// Check the type of a value for a specialization constant.
+    ///
+    /// returns an empty Result, `CL_INVALID_SPEC_ID` if the module does not
+    /// have the constant or `CL_INVALID_VALUE` if the type is wrong.
+    pub fn check_spec_constant<T: SpecConstantValue>(
+        &self,
+        spec_id: cl_uint,
+    ) -> Result<(), cl_int> {
+        let constant = self.spec_constant(spec_id).ok_or_else(|| {
+            record_error!(
+                CL_INVALID_SPEC_ID,
+                SpirvModule::check_spec_constant,
+                spec_id
+            )
+        })?;
+        let spec_type = constant.spec_type;
+        if T::TYPE == spec_type {
+            Ok(())
+        } else {
+            let value_type = T::TYPE;
+            Err(record_error!(
+                CL_INVALID_VALUE,
+                SpirvModule::check_spec_constant,
+                spec_id,
+                spec_type,
+                value_type
+            ))
+        }
+    }
+
+    /// Set the value of a specialization constant of a program created from
+    /// the module, after checking the type of the value.
+    /// Calls `clSetProgramSpecializationConstant`.
+    /// `CL_VERSION_2_2`
+    ///
+    /// * `program` - the program created from the module.
+    /// * `spec_id` - the specialization constant whose value will be set.
+    /// * `value` - the value of the specialization constant.
+    ///
+    /// returns an empty Result, `CL_INVALID_SPEC_ID` or `CL_INVALID_VALUE`,
+    /// see `check_spec_constant`, or the error code from the `OpenCL` C API function.
+    #[cfg(feature = "CL_VERSION_2_2")]
+    pub fn set_spec_constant<T: SpecConstantValue>(
+        &self,
+        program: impl AsProgram,
+        spec_id: cl_uint,
+        value: T,
+    ) -> Result<(), cl_int> {
+        self.check_spec_constant::<T>(spec_id)?;
+        let bytes = value.to_bytes();
+        unsafe {
+            set_program_specialization_constant(
+                program,
+                spec_id,
+                bytes.len(),
+                bytes.as_ptr().cast::<c_void>(),
+            )
+        }
+    }
+
+    /// Set the value of a specialization constant by its `OpName`,
+    /// see `set_spec_constant`.
+    /// `CL_VERSION_2_2`
+    ///
+    /// * `program` - the program created from the module.
+    /// * `name` - the name of the specialization constant.
+    /// * `value` - the value of the specialization constant.
+    ///
+    /// returns an empty Result, `CL_INVALID_SPEC_ID` if the module does not
+    /// have the named constant, `CL_INVALID_VALUE` if the type is wrong,
+    /// or the error code from the `OpenCL` C API function.
+    #[cfg(feature = "CL_VERSION_2_2")]
+    pub fn set_named_spec_constant<T: SpecConstantValue>(
+        &self,
+        program: impl AsProgram,
+        name: &str,
+        value: T,
+    ) -> Result<(), cl_int> {
+        let constant = self.named_spec_constant(name).ok_or_else(|| {
+            record_error!(
+                CL_INVALID_SPEC_ID,
+                SpirvModule::set_named_spec_constant,
+                name
+            )
+        })?;
+        self.set_spec_constant(program, constant.spec_id, value)
+    }
+
+    /// Set the value of a specialization constant from its little endian
+    /// bytes, e.g. for a `half` constant, after checking the size.
+    /// `CL_VERSION_2_2`
+    ///
+    /// * `program` - the program created from the module.
+    /// * `spec_id` - the specialization constant whose value will be set.
+    /// * `bytes` - the value of the specialization constant.
+    ///
+    /// returns an empty Result, `CL_INVALID_SPEC_ID` if the module does not
+    /// have the constant, `CL_INVALID_VALUE` if the size is wrong,
+    /// or the error code from the `OpenCL` C API function.
+    #[cfg(feature = "CL_VERSION_2_2")]
+    pub fn set_spec_constant_bytes(
+        &self,
+        program: impl AsProgram,
+        spec_id: cl_uint,
+        bytes: &[u8],
+    ) -> Result<(), cl_int> {
+        let constant = self.spec_constant(spec_id).ok_or_else(|| {
+            record_error!(
+                CL_INVALID_SPEC_ID,
+                SpirvModule::set_spec_constant_bytes,
+                spec_id
+            )
+        })?;
+        if bytes.len() != constant.spec_type.size() {
+            let size = bytes.len();
+            return Err(record_error!(
+                CL_INVALID_VALUE,
+                SpirvModule::set_spec_constant_bytes,
+                spec_id,
+                size
+            ));
+        }
+        unsafe {
+            set_program_specialization_constant(
+                program,
+                spec_id,
+                bytes.len(),
+                bytes.as_ptr().cast::<c_void>(),
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Encode a SPIR-V instruction.
+    fn instruction(words: &mut Vec<u32>, opcode: u32, operands: &[u32]) {
+        words.push(((operands.len() as u32 + 1) << 16) | opcode);
+        words.extend_from_slice(operands);
+    }
+
+    /// Encode a SPIR-V literal string.
+    fn string(text: &str) -> Vec<u32> {
+        let mut bytes = text.as_bytes().to_vec();
+        bytes.resize((bytes.len() / 4 + 1) * 4, 0);
+        bytes
+            .chunks_exact(4)
+            .map(|chunk| u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+            .collect()
+    }
+
+    /// A SPIR-V module with an empty kernel and specialization constants.
+    fn spec_constants_module() -> Vec<u8> {
+        // Addresses, Kernel, Int64, Float64
+        spirv_module((1, 0), &[4, 6, 11, 10], &[], ADDRESSING_MODEL_PHYSICAL64)
+    }
+
+    /// A SPIR-V module with the version, capabilities, extensions and
+    /// addressing model, an empty kernel and specialization constants.
+    fn spirv_module(
+        version: (u32, u32),
+        capabilities: &[u32],
+        extensions: &[&str],
+        addressing_model: u32,
+    ) -> Vec<u8> {
+        let mut words = vec![SPIRV_MAGIC, (version.0 << 16) | (version.1 << 8), 0, 100, 0];
+        for capability in capabilities {
+            instruction(&mut words, OP_CAPABILITY, &[*capability]);
+        }
+        for extension in extensions {
+            instruction(&mut words, OP_EXTENSION, &string(extension));
+        }
+        // OpMemoryModel OpenCL
+        instruction(&mut words, OP_MEMORY_MODEL, &[addressing_model, 2]);
+        // OpEntryPoint Kernel %kernel "tune"
+        let mut operands = vec![6, 1];
+        operands.extend(string("tune"));
+        instruction(&mut words, 15, &operands);
+        for (id, name) in [(10, "BLOCK_SIZE"), (11, "USE_LOCAL"), (12, "SCALE")] {
+            let mut operands = vec![id];
+            operands.extend(string(name));
+            instruction(&mut words, OP_NAME, &operands);
+        }
+        for (id, spec_id) in [(10, 0), (11, 1), (12, 2), (13, 3)] {
+            instruction(&mut words, OP_DECORATE, &[id, DECORATION_SPEC_ID, spec_id]);
+        }
+        instruction(&mut words, 19, &[2]); // OpTypeVoid
+        instruction(&mut words, 33, &[3, 2]); // OpTypeFunction
+        instruction(&mut words, OP_TYPE_BOOL, &[4]);
+        instruction(&mut words, OP_TYPE_INT, &[5, 32, 0]);
+        instruction(&mut words, OP_TYPE_FLOAT, &[6, 32]);
+        instruction(&mut words, OP_TYPE_INT, &[7, 64, 0]);
+        instruction(&mut words, OP_SPEC_CONSTANT, &[5, 10, 64]);
+        instruction(&mut words, OP_SPEC_CONSTANT_TRUE, &[4, 11]);
+        instruction(&mut words, OP_SPEC_CONSTANT, &[6, 12, 1.5_f32.to_bits()]);
+        instruction(&mut words, OP_SPEC_CONSTANT, &[7, 13, 2, 1]);
+        // A constant without a SpecId is not a specialization constant
+        instruction(&mut words, OP_SPEC_CONSTANT, &[5, 14, 7]);
+        // The kernel function
+        instruction(&mut words, 54, &[2, 1, 0, 3]);
+        instruction(&mut words, 248, &[15]);
+        instruction(&mut words, 253, &[]);
+        instruction(&mut words, 56, &[]);
+        words.iter().flat_map(|word| word.to_le_bytes()).collect()
+    }
+
+    #[test]
+    fn test_spirv_spec_constants() {
+        let il = spec_constants_module();
+        let module = SpirvModule::parse(&il).unwrap();
+        println!("{:?}", module);
+        assert_eq!((1, 0), module.version());
+        assert_eq!(&[4, 6, 11, 10], module.capabilities());
+        assert!(module.extensions().is_empty());
+        assert_eq!(Some(ADDRESSING_MODEL_PHYSICAL64), module.addressing_model());
+
+        let constants = module.spec_constants();
+        assert_eq!(4, constants.len());
+        assert_eq!(
+            vec![0, 1, 2, 3],
+            constants.iter().map(|c| c.spec_id).collect::<Vec<_>>()
+        );
+
+        let block_size = module.named_spec_constant("BLOCK_SIZE").unwrap();
+        assert_eq!(0, block_size.spec_id);
+        assert_eq!(SpecConstantType::Int(32), block_size.spec_type);
+        assert_eq!(Some(64_u32), block_size.default_value());
+        assert_eq!(None, block_size.default_value::<f32>());
+
+        let use_local = module.spec_constant(1).unwrap();
+        assert_eq!(Some("USE_LOCAL"), use_local.name.as_deref());
+        assert_eq!(Some(true), use_local.default_value());
+
+        let scale = module.spec_constant(2).unwrap();
+        assert_eq!("f32", scale.spec_type.to_string());
+        assert_eq!(Some(1.5_f32), scale.default_value());
+
+        let count = module.spec_constant(3).unwrap();
+        assert_eq!(None, count.name);
+        assert_eq!(8, count.spec_type.size());
+        assert_eq!(Some(0x1_0000_0002_u64), count.default_value());
+
+        module.check_spec_constant::<u32>(0).unwrap();
+        module.check_spec_constant::<i32>(0).unwrap();
+        assert_eq!(
+            CL_INVALID_VALUE,
+            module.check_spec_constant::<u64>(0).unwrap_err()
+        );
+        assert_eq!(
+            CL_INVALID_VALUE,
+            module.check_spec_constant::<u8>(1).unwrap_err()
+        );
+        assert_eq!(
+            CL_INVALID_SPEC_ID,
+            module.check_spec_constant::<u32>(4).unwrap_err()
+        );
+
+        // Big endian modules are valid
+        let big_endian: Vec<u8> = il
+            .chunks_exact(4)
+            .flat_map(|chunk| [chunk[3], chunk[2], chunk[1], chunk[0]])
+            .collect();
+        assert_eq!(module, SpirvModule::parse(&big_endian).unwrap());
+
+        assert_eq!(
+            CL_INVALID_VALUE,
+            SpirvModule::parse(b"not a SPIR-V module!").unwrap_err()
+        );
+
+        // A truncated instruction
+        let mut truncated = il.clone();
+        truncated.extend_from_slice(&((3 << 16) | OP_NAME).to_le_bytes());
+        assert_eq!(
+            CL_INVALID_VALUE,
+            SpirvModule::parse(&truncated).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn test_spirv_check_device() {
+        use crate::device::{get_device_ids, CL_DEVICE_TYPE_GPU};
+        use crate::platform::get_platform_ids;
+
+        let platform_ids = get_platform_ids().unwrap();
+        let device_ids = get_device_ids(platform_ids[0], CL_DEVICE_TYPE_GPU).unwrap();
+        let device_id = device_ids[0];
+        let versions = device_spirv_versions(device_id);
+        println!("SPIR-V versions: {:?}", versions);
+
+        let module = SpirvModule::parse(&spec_constants_module()).unwrap();
+        let issues = module.check_device(device_id).unwrap();
+        for issue in &issues {
+            println!("{}", issue);
+        }
+
+        // The incompatibilities of an unsupported module
+        let il = spirv_module(
+            (9, 9),
+            &[4, 6, 9, 38, 4000],
+            &["SPV_KHR_no_integer_wrap_decoration", "SPV_XYZ_unknown"],
+            ADDRESSING_MODEL_PHYSICAL32,
+        );
+        let issues = SpirvModule::parse(&il)
+            .unwrap()
+            .check_device(device_id)
+            .unwrap();
+        for issue in &issues {
+            println!("{}", issue);
+        }
+        assert!(issues.contains(&Incompatibility::Version {
+            module: (9, 9),
+            device: versions,
+        }));
+        assert!(issues.contains(&Incompatibility::Extension("SPV_XYZ_unknown".to_owned())));
+        let address_bits = get_device_param(device_id, AddressBits).unwrap();
+        assert_eq!(
+            32 != address_bits,
+            issues.contains(&Incompatibility::AddressingModel {
+                addressing_model: Some(ADDRESSING_MODEL_PHYSICAL32),
+                address_bits
+            })
+        );
+        // An unknown capability has no requirement
+        assert!(!issues.iter().any(|issue| matches!(
+            issue,
+            Incompatibility::Capability {
+                capability: 4000,
+                ..
+            }
+        )));
+
+        assert_eq!(
+            vec!["cl_khr_spirv_linkonce_odr", "cl_khr_linkonce_odr"],
+            opencl_extensions("SPV_KHR_linkonce_odr")
+        );
+        assert_eq!(Some("Float64"), capability_name(10));
+        assert_eq!(None, capability_name(4000));
+    }
+
+    #[cfg(all(feature = "CL_VERSION_2_1", feature = "CL_VERSION_2_2"))]
+    #[test]
+    fn test_set_spec_constant() {
+        use crate::context::{create_context, release_context};
+        use crate::device::{get_device_ids, CL_DEVICE_TYPE_GPU};
+        use crate::platform::get_platform_ids;
+        use crate::program::{create_program_with_il, release_program};
+        use std::ptr;
+
+        let platform_ids = get_platform_ids().unwrap();
+        let device_ids = get_device_ids(platform_ids[0], CL_DEVICE_TYPE_GPU).unwrap();
+        let context = create_context(&device_ids, ptr::null(), None, ptr::null_mut()).unwrap();
+
+        let il = spec_constants_module();
+        let module = SpirvModule::parse(&il).unwrap();
+        let program = create_program_with_il(context, &il).unwrap();
+
+        module.set_spec_constant(program, 0, 128_u32).unwrap();
+        module
+            .set_named_spec_constant(program, "USE_LOCAL", false)
+            .unwrap();
+        module
+            .set_named_spec_constant(program, "SCALE", 2.0_f32)
+            .unwrap();
+        module.set_spec_constant(program, 3, 5_u64).unwrap();
+        module
+            .set_spec_constant_bytes(program, 0, &256_u32.to_le_bytes())
+            .unwrap();
+
+        // The wrong type, size or id is refused before calling OpenCL
+        assert_eq!(
+            CL_INVALID_VALUE,
+            module.set_spec_constant(program, 0, 1.0_f64).unwrap_err()
+        );
+        assert_eq!(
+            CL_INVALID_VALUE,
+            module
+                .set_spec_constant_bytes(program, 0, &[1, 2])
+                .unwrap_err()
+        );
+        assert_eq!(
+            CL_INVALID_SPEC_ID,
+            module
+                .set_named_spec_constant(program, "UNKNOWN", 1_u32)
+                .unwrap_err()
+        );
+
+        unsafe {
+            release_program(program).unwrap();
+            release_context(context).unwrap();
+        }
+    }
+}
@@ -36,3 +36,111 @@ pub use opencl_sys::{
     cl_svm_mem_flags, cl_uchar, cl_uint, cl_ulong, cl_ushort, cl_version, CL_BLOCKING, CL_FALSE,
     CL_NON_BLOCKING, CL_TRUE,
 };
+
+/// Plain old data that can be copied to and from `OpenCL` memory as bytes.
+///
+/// # Safety
+///
+/// The type must be `repr(C)` or `repr(transparent)` with no padding bytes,
+/// and every bit pattern must be a valid value of the type.
+pub unsafe trait Pod: Copy + 'static {}
+
+unsafe impl Pod for cl_char {}
+unsafe impl Pod for cl_uchar {}
+unsafe impl Pod for cl_short {}
+unsafe impl Pod for cl_ushort {}
+unsafe impl Pod for cl_int {}
+unsafe impl Pod for cl_uint {}
+unsafe impl Pod for cl_long {}
+unsafe impl Pod for cl_ulong {}
+unsafe impl Pod for cl_float {}
+unsafe impl Pod for cl_double {}
+unsafe impl<T: Pod, const N: usize> Pod for [T; N] {}
+
+macro_rules! cl_vector {
+    ($($(#[$attr:meta])* $name:ident, $scalar:ty, $count:literal, $align:literal;)*) => {
+        $(
+            #[doc = concat!("The `OpenCL` `", stringify!($name), "` vector type.")]
+            $(#[$attr])*
+            #[repr(C, align($align))]
+            #[derive(Clone, Copy, Debug, Default, PartialEq)]
+            pub struct $name {
+                pub s: [$scalar; $count],
+            }
+
+            unsafe impl Pod for $name {}
+
+            impl From<[$scalar; $count]> for $name {
+                fn from(s: [$scalar; $count]) -> Self {
+                    Self { s }
+                }
+            }
+
+            impl From<$name> for [$scalar; $count] {
+                fn from(value: $name) -> Self {
+                    value.s
+                }
+            }
+        )*
+    };
+}
+
+cl_vector! {
+    #[derive(Eq, Hash)] cl_char2, cl_char, 2, 2;
+    #[derive(Eq, Hash)] cl_char4, cl_char, 4, 4;
+    #[derive(Eq, Hash)] cl_char8, cl_char, 8, 8;
+    #[derive(Eq, Hash)] cl_char16, cl_char, 16, 16;
+    #[derive(Eq, Hash)] cl_uchar2, cl_uchar, 2, 2;
+    #[derive(Eq, Hash)] cl_uchar4, cl_uchar, 4, 4;
+    #[derive(Eq, Hash)] cl_uchar8, cl_uchar, 8, 8;
+    #[derive(Eq, Hash)] cl_uchar16, cl_uchar, 16, 16;
+    #[derive(Eq, Hash)] cl_short2, cl_short, 2, 4;
+    #[derive(Eq, Hash)] cl_short4, cl_short, 4, 8;
+    #[derive(Eq, Hash)] cl_short8, cl_short, 8, 16;
+    #[derive(Eq, Hash)] cl_short16, cl_short, 16, 32;
+    #[derive(Eq, Hash)] cl_ushort2, cl_ushort, 2, 4;
+    #[derive(Eq, Hash)] cl_ushort4, cl_ushort, 4, 8;
+    #[derive(Eq, Hash)] cl_ushort8, cl_ushort, 8, 16;
+    #[derive(Eq, Hash)] cl_ushort16, cl_ushort, 16, 32;
+    #[derive(Eq, Hash)] cl_half2, cl_half, 2, 4;
+    #[derive(Eq, Hash)] cl_half4, cl_half, 4, 8;
+    #[derive(Eq, Hash)] cl_half8, cl_half, 8, 16;
+    #[derive(Eq, Hash)] cl_half16, cl_half, 16, 32;
+    #[derive(Eq, Hash)] cl_int2, cl_int, 2, 8;
+    #[derive(Eq, Hash)] cl_int4, cl_int, 4, 16;
+    #[derive(Eq, Hash)] cl_int8, cl_int, 8, 32;
+    #[derive(Eq, Hash)] cl_int16, cl_int, 16, 64;
+    #[derive(Eq, Hash)] cl_uint2, cl_uint, 2, 8;
+    #[derive(Eq, Hash)] cl_uint4, cl_uint, 4, 16;
+    #[derive(Eq, Hash)] cl_uint8, cl_uint, 8, 32;
+    #[derive(Eq, Hash)] cl_uint16, cl_uint, 16, 64;
+    #[derive(Eq, Hash)] cl_long2, cl_long, 2, 16;
+    #[derive(Eq, Hash)] cl_long4, cl_long, 4, 32;
+    #[derive(Eq, Hash)] cl_long8, cl_long, 8, 64;
+    #[derive(Eq, Hash)] cl_long16, cl_long, 16, 128;
+    #[derive(Eq, Hash)] cl_ulong2, cl_ulong, 2, 16;
+    #[derive(Eq, Hash)] cl_ulong4, cl_ulong, 4, 32;
+    #[derive(Eq, Hash)] cl_ulong8, cl_ulong, 8, 64;
+    #[derive(Eq, Hash)] cl_ulong16, cl_ulong, 16, 128;
+    cl_float2, cl_float, 2, 8;
+    cl_float4, cl_float, 4, 16;
+    cl_float8, cl_float, 8, 32;
+    cl_float16, cl_float, 16, 64;
+    cl_double2, cl_double, 2, 16;
+    cl_double4, cl_double, 4, 32;
+    cl_double8, cl_double, 8, 64;
+    cl_double16, cl_double, 16, 128;
+}
+
+// As in cl_platform.h, the 3 component vectors are the 4 component vectors.
+pub type cl_char3 = cl_char4;
+pub type cl_uchar3 = cl_uchar4;
+pub type cl_short3 = cl_short4;
+pub type cl_ushort3 = cl_ushort4;
+pub type cl_half3 = cl_half4;
+pub type cl_int3 = cl_int4;
+pub type cl_uint3 = cl_uint4;
+pub type cl_long3 = cl_long4;
+pub type cl_ulong3 = cl_ulong4;
+pub type cl_float3 = cl_float4;
+pub type cl_double3 = cl_double4;
@@ -0,0 +1,3 @@
+#pragma once
+
+#define SCALE 2.0f
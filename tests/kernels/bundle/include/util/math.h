@@ -0,0 +1,3 @@
+#include "common.h"
+
+#define SQUARE(x) ((x) * (x))
@@ -0,0 +1,8 @@
+#include "common.h"
+#include <util/math.h>
+
+kernel void scale(global float* a)
+{
+    size_t i = get_global_id(0);
+    a[i] = SQUARE(a[i]) * SCALE;
+}